@@ -87,12 +87,7 @@ struct ClosureOpConversion : public EIROpConversion<ClosureOp> {
 
     auto loc = ctx.getLoc();
 
-    assert(op.isAnonymous() && "expected anonymous closures only");
-
     unsigned arity = op.arity();
-    unsigned index = op.index();
-    unsigned oldUnique = op.oldUnique();
-    StringRef unique = op.unique();
     FlatSymbolRefAttr callee = op.calleeAttr();
 
     LLVMType termTy = ctx.getUsizeType();
@@ -151,30 +146,53 @@ struct ClosureOpConversion : public EIROpConversion<ClosureOp> {
     Value defTypeIdx = llvm_constant(i32Ty, ctx.getI32Attr(0));
     ArrayRef<Value> defTypeIndices({zero, defIdx, defTypeIdx});
     Value definitionTypePtrGep = llvm_gep(i8PtrTy, valRef, defTypeIndices);
-    Value anonTypeConst = llvm_constant(i8Ty, ctx.getI8Attr(1));
-    llvm_store(anonTypeConst, definitionTypePtrGep);
-
-    // Definition - index
-    Value defIndexIdx = llvm_constant(i32Ty, ctx.getI32Attr(1));
-    ArrayRef<Value> defIndexIndices({zero, defIdx, defIndexIdx});
-    Value definitionIndexGep = llvm_gep(termPtrTy, valRef, defIndexIndices);
-    Value indexConst = llvm_constant(termTy, ctx.getIntegerAttr(index));
-    llvm_store(indexConst, definitionIndexGep);
-
-    // Definition - unique
-    Value defUniqueIdx = llvm_constant(i32Ty, ctx.getI32Attr(2));
-    ArrayRef<Value> defUniqueIndices({zero, defIdx, defUniqueIdx});
-    Value definitionUniqueGep = llvm_gep(uniquePtrTy, valRef, defUniqueIndices);
-    Value uniqueConst = llvm_constant(uniqueTy, ctx.getStringAttr(unique));
-    llvm_store(uniqueConst, definitionUniqueGep);
-
-    // Definition - old_unique
-    Value defOldUniqueIdx = llvm_constant(i32Ty, ctx.getI32Attr(3));
-    ArrayRef<Value> defOldUniqueIndices({zero, defIdx, defOldUniqueIdx});
-    Value definitionOldUniqueGep =
-        llvm_gep(i32PtrTy, valRef, defOldUniqueIndices);
-    Value oldUniqueConst = llvm_constant(i32Ty, ctx.getI32Attr(oldUnique));
-    llvm_store(oldUniqueConst, definitionOldUniqueGep);
+
+    if (!op.isAnonymous()) {
+      // Export closures only carry the function atom, stored in place of the
+      // index
+      Value exportTypeConst = llvm_constant(i8Ty, ctx.getI8Attr(0));
+      llvm_store(exportTypeConst, definitionTypePtrGep);
+
+      // Definition - function
+      Value defFunctionIdx = llvm_constant(i32Ty, ctx.getI32Attr(1));
+      ArrayRef<Value> defFunctionIndices({zero, defIdx, defFunctionIdx});
+      Value definitionFunctionGep =
+          llvm_gep(termPtrTy, valRef, defFunctionIndices);
+      Value functionConst = llvm_constant(
+          termTy,
+          ctx.getIntegerAttr(op.function().getValue().getLimitedValue()));
+      llvm_store(functionConst, definitionFunctionGep);
+    } else {
+      unsigned index = op.index();
+      unsigned oldUnique = op.oldUnique();
+      StringRef unique = op.unique();
+
+      Value anonTypeConst = llvm_constant(i8Ty, ctx.getI8Attr(1));
+      llvm_store(anonTypeConst, definitionTypePtrGep);
+
+      // Definition - index
+      Value defIndexIdx = llvm_constant(i32Ty, ctx.getI32Attr(1));
+      ArrayRef<Value> defIndexIndices({zero, defIdx, defIndexIdx});
+      Value definitionIndexGep = llvm_gep(termPtrTy, valRef, defIndexIndices);
+      Value indexConst = llvm_constant(termTy, ctx.getIntegerAttr(index));
+      llvm_store(indexConst, definitionIndexGep);
+
+      // Definition - unique
+      Value defUniqueIdx = llvm_constant(i32Ty, ctx.getI32Attr(2));
+      ArrayRef<Value> defUniqueIndices({zero, defIdx, defUniqueIdx});
+      Value definitionUniqueGep =
+          llvm_gep(uniquePtrTy, valRef, defUniqueIndices);
+      Value uniqueConst = llvm_constant(uniqueTy, ctx.getStringAttr(unique));
+      llvm_store(uniqueConst, definitionUniqueGep);
+
+      // Definition - old_unique
+      Value defOldUniqueIdx = llvm_constant(i32Ty, ctx.getI32Attr(3));
+      ArrayRef<Value> defOldUniqueIndices({zero, defIdx, defOldUniqueIdx});
+      Value definitionOldUniqueGep =
+          llvm_gep(i32PtrTy, valRef, defOldUniqueIndices);
+      Value oldUniqueConst = llvm_constant(i32Ty, ctx.getI32Attr(oldUnique));
+      llvm_store(oldUniqueConst, definitionOldUniqueGep);
+    }
 
     // Arity
     // arity: u8,
@@ -305,6 +305,27 @@ Value ModuleBuilder::build_closure(Closure *closure) {
   return op.getResult(0);
 }
 
+extern "C" MLIRValueRef MLIRBuildFunctionRef(MLIRModuleBuilderRef b,
+                                             MLIRLocationRef locref,
+                                             MLIRAttributeRef moduleref,
+                                             MLIRAttributeRef functionref,
+                                             const char *name, uint8_t arity) {
+  ModuleBuilder *builder = unwrap(b);
+  Location loc = unwrap(locref);
+  auto module = unwrap(moduleref).cast<AtomAttr>();
+  auto function = unwrap(functionref).cast<AtomAttr>();
+  return wrap(builder->build_function_ref(loc, StringRef(name), module,
+                                          function, arity));
+}
+
+Value ModuleBuilder::build_function_ref(Location loc, StringRef target,
+                                        AtomAttr module, AtomAttr function,
+                                        unsigned arity) {
+  auto callee = builder.getSymbolRefAttr(target);
+  auto op = builder.create<ClosureOp>(loc, callee, module, function, arity);
+  return op.getResult(0);
+}
+
 extern "C" bool MLIRBuildUnpackEnv(MLIRModuleBuilderRef b,
                                    MLIRLocationRef locref, MLIRValueRef ev,
                                    MLIRValueRef *values, unsigned numValues) {
@@ -1494,20 +1515,31 @@ void ModuleBuilder::build_binary_push(Location loc, Value head, Value tail,
 //===----------------------------------------------------------------------===//
 
 extern "C" void MLIRBuildReceiveStart(MLIRModuleBuilderRef b,
-                                      MLIRLocationRef locref, MLIRBlockRef contBlock, MLIRValueRef timeoutRef) {
+                                      MLIRLocationRef locref, MLIRBlockRef contBlock, MLIRValueRef timeoutRef,
+                                      MLIRValueRef markerRef) {
   ModuleBuilder *builder = unwrap(b);
   Location loc = unwrap(locref);
   Block *cont = unwrap(contBlock);
   Value timeout = unwrap(timeoutRef);
-  builder->build_receive_start(loc, cont, timeout);
+  Value marker;
+  if (markerRef) {
+    marker = unwrap(markerRef);
+  }
+  builder->build_receive_start(loc, cont, timeout, marker);
 }
 
-void ModuleBuilder::build_receive_start(Location loc, Block *cont, Value timeout) {
+void ModuleBuilder::build_receive_start(Location loc, Block *cont, Value timeout, Value marker) {
   // Construct a new receive ref and branch to the continuation block with it
   auto callee = builder.getSymbolRefAttr("__lumen_receive_start");
   auto recvRefType = builder.getType<ReceiveRefType>();
   auto op = builder.create<CallOp>(loc, callee, ArrayRef<Type>{recvRefType}, ArrayRef<Value>{timeout});
   auto receive_ref = op.getResult(0);
+  // Every message the receive matches contains the marker, so the runtime can skip the
+  // messages that arrived before it was made, if it is a reference
+  if (marker) {
+    auto setCallee = builder.getSymbolRefAttr("__lumen_builtin_receive_set");
+    builder.create<CallOp>(loc, setCallee, ArrayRef<Type>{}, ArrayRef<Value>{marker});
+  }
   builder.create<BranchOp>(loc, cont, ArrayRef<Value>{receive_ref});
 }
 
@@ -47,6 +47,8 @@ class ModuleBuilder {
   void add_function(FuncOp f);
 
   Value build_closure(Closure *closure);
+  Value build_function_ref(Location loc, StringRef target, AtomAttr module,
+                           AtomAttr function, unsigned arity);
   Value build_unpack_op(Location loc, Value env, unsigned index);
 
   //===----------------------------------------------------------------------===//
@@ -107,7 +109,7 @@ class ModuleBuilder {
   void build_binary_push(Location loc, Value head, Value tail, Value size,
                          BinarySpecifier *spec, Block *ok, Block *err);
   void build_binary_finish(Location loc, Block *cont, Value bin);
-  void build_receive_start(Location loc, Block *cont, Value timeout);
+  void build_receive_start(Location loc, Block *cont, Value timeout, Value marker);
   void build_receive_wait(Location loc, Block *timeout, Block *check, Value receive_ref);
   void build_receive_done(Location loc, Block *cont, Value receive_ref, ArrayRef<Value> args);
 
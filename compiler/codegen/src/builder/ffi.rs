@@ -297,6 +297,15 @@ extern "C" {
 
     pub fn MLIRBuildClosure(builder: ModuleBuilderRef, closure: *const Closure) -> ValueRef;
 
+    pub fn MLIRBuildFunctionRef(
+        builder: ModuleBuilderRef,
+        loc: LocationRef,
+        module: AttributeRef,
+        function: AttributeRef,
+        name: *const libc::c_char,
+        arity: u8,
+    ) -> ValueRef;
+
     pub fn MLIRBuildUnpackEnv(
         builder: ModuleBuilderRef,
         loc: LocationRef,
@@ -507,6 +516,7 @@ extern "C" {
         loc: LocationRef,
         cont_block: BlockRef,
         timeout: ValueRef,
+        marker: ValueRef,
     );
     pub fn MLIRBuildReceiveWait(
         builder: ModuleBuilderRef,
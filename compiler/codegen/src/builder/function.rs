@@ -1,6 +1,7 @@
 mod function;
 pub use self::function::*;
 
+use std::collections::HashSet;
 use std::mem;
 use std::ptr;
 use std::sync::Arc;
@@ -1106,9 +1107,18 @@ impl<'f, 'o> ScopedFunctionBuilder<'f, 'o> {
                     debug_in!(self, "block contains receive start operation");
                     let cont = self.get_block_by_value(reads[0]);
                     let timeout = self.build_value(reads[1])?;
+                    let marker = match self.receive_marker(ir_block, reads[0]) {
+                        Some(ir_marker) => Some(self.build_value(ir_marker)?),
+                        None => None,
+                    };
                     return OpBuilder::build_void_result(
                         self,
-                        OpKind::ReceiveStart(ReceiveStart { loc, cont, timeout }),
+                        OpKind::ReceiveStart(ReceiveStart {
+                            loc,
+                            cont,
+                            timeout,
+                            marker,
+                        }),
                     );
                 }
                 // receive_wait(timeout: fn(), check_message: fn(msg), recv_ref)
@@ -1339,8 +1349,8 @@ impl<'f, 'o> ScopedFunctionBuilder<'f, 'o> {
             ir::PrimOpKind::CaptureFunction => {
                 debug_in!(self, "primop is function capture");
                 assert_eq!(
-                    num_reads, 4,
-                    "expected capture function primop to have four operands"
+                    num_reads, 3,
+                    "expected capture function primop to have three operands"
                 );
                 let callee = Callee::new(self, ir_value)?;
                 OpKind::FunctionRef(FunctionRef { loc, callee })
@@ -1440,6 +1450,96 @@ impl<'f, 'o> ScopedFunctionBuilder<'f, 'o> {
     }
 }
 
+// Receive analysis
+impl<'f, 'o> ScopedFunctionBuilder<'f, 'o> {
+    /// Finds the value every message matched by the receive started in `ir_block` contains, if
+    /// there is one, by checking that each path from the message check to `receive_done` goes
+    /// through a match of the message, or a part of it, on the same value.
+    ///
+    /// When the value is a reference made by `make_ref/0`, the runtime skips the messages that
+    /// arrived before it was made, as none of them can contain it.  Without this, replies to
+    /// `gen_server:call` in a busy process are found by scanning its whole mailbox.
+    fn receive_marker(&self, ir_block: ir::Block, ir_cont: ir::Value) -> Option<ir::Value> {
+        // receive_wait(timeout: fn(), check_message: fn(msg), recv_ref)
+        let ir_wait = self.eir.value_block(ir_cont)?;
+        match self.eir.block_kind(ir_wait)? {
+            ir::OpKind::Dyn(dyn_op) if dyn_op.downcast_ref::<receive::ReceiveWait>().is_some() => {}
+            _ => return None,
+        }
+        let ir_check = self.eir.value_block(self.eir.block_reads(ir_wait)[1])?;
+
+        let mut marker = None;
+        // The message and the parts of it destructured by matches
+        let mut parts: HashSet<ir::Value> = self.eir.block_args(ir_check).iter().copied().collect();
+        let mut visited = HashSet::new();
+        let mut stack = vec![ir_check];
+
+        while let Some(ir_current) = stack.pop() {
+            if !visited.insert(ir_current) {
+                continue;
+            }
+
+            let reads = self.eir.block_reads(ir_current);
+
+            match self.eir.block_kind(ir_current)? {
+                ir::OpKind::Match { branches, .. } => {
+                    let dests = reads[0];
+                    let is_part = parts.contains(&reads[1]);
+
+                    for (i, kind) in branches.iter().enumerate() {
+                        let ir_dest = self.eir.value_block(self.eir.value_list_get_n(dests, i)?)?;
+
+                        if !is_part {
+                            stack.push(ir_dest);
+
+                            continue;
+                        }
+
+                        if let ir::MatchKind::Value = kind {
+                            let args = reads[i + 2];
+                            let expected = self.eir.value_list_get_n(args, 0)?;
+
+                            // Only values bound before the receive can be markers
+                            if self.analysis.live.is_live_at(ir_block, expected) {
+                                match marker {
+                                    None => {
+                                        marker = Some(expected);
+
+                                        continue;
+                                    }
+                                    Some(existing) if existing == expected => continue,
+                                    Some(_) => (),
+                                }
+                            }
+                        }
+
+                        parts.extend(self.eir.block_args(ir_dest).iter().copied());
+                        stack.push(ir_dest);
+                    }
+                }
+                ir::OpKind::Dyn(dyn_op)
+                    if dyn_op.downcast_ref::<receive::ReceiveDone>().is_some() =>
+                {
+                    // A message can be received without matching the marker
+                    return None;
+                }
+                // The message did not match, so the next one is checked
+                ir::OpKind::Dyn(dyn_op)
+                    if dyn_op.downcast_ref::<receive::ReceiveWait>().is_some() => {}
+                _ => {
+                    for read in reads {
+                        if let Some(ir_successor) = self.eir.value_block(*read) {
+                            stack.push(ir_successor);
+                        }
+                    }
+                }
+            }
+        }
+
+        marker
+    }
+}
+
 /// Maintains metadata about the current position of the builder
 #[derive(Default, Clone, Copy)]
 struct Position {
@@ -344,6 +344,8 @@ pub struct ReceiveStart {
     pub loc: LocationRef,
     pub cont: Block,
     pub timeout: Value,
+    /// A value every message the receive matches contains, see `receive_marker`
+    pub marker: Option<Value>,
 }
 
 #[derive(Debug, Clone)]
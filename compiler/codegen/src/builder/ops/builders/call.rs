@@ -2,6 +2,8 @@ use std::ffi::CString;
 
 use super::*;
 
+use crate::builder::traits::*;
+
 pub struct CallBuilder;
 
 impl CallBuilder {
@@ -90,8 +92,101 @@ impl CallBuilder {
 
                 Ok(None)
             }
-            callee => todo!("unimplemented call type {:?}", callee),
+            Callee::LocalDynamic {
+                module,
+                function,
+                arity,
+            } => {
+                builder.debug(&format!("call target is {}:<dynamic>/{}", module, arity));
+
+                let module_ref =
+                    module
+                        .name
+                        .as_value_ref(op.loc, builder.as_ref(), builder.options())?;
+                let function_ref = builder.value_ref(function);
+                Self::build_apply(
+                    builder,
+                    &op,
+                    module_ref,
+                    function_ref,
+                    arity,
+                    args.as_slice(),
+                    ok_block,
+                    ok_args.as_slice(),
+                    err_block,
+                    err_args.as_slice(),
+                )
+            }
+            Callee::GlobalDynamic {
+                module,
+                function,
+                arity,
+            } => {
+                builder.debug(&format!("call target is <dynamic>:<dynamic>/{}", arity));
+
+                let module_ref = builder.value_ref(module);
+                let function_ref = builder.value_ref(function);
+                Self::build_apply(
+                    builder,
+                    &op,
+                    module_ref,
+                    function_ref,
+                    arity,
+                    args.as_slice(),
+                    ok_block,
+                    ok_args.as_slice(),
+                    err_block,
+                    err_args.as_slice(),
+                )
+            }
+        }
+    }
+
+    /// Calls with a module or function only known at runtime are lowered to
+    /// `erlang:apply/3`, which resolves the target when it is called
+    fn build_apply<'f, 'o>(
+        builder: &mut ScopedFunctionBuilder<'f, 'o>,
+        op: &Call,
+        module: ValueRef,
+        function: ValueRef,
+        arity: usize,
+        args: &[ValueRef],
+        ok_block: BlockRef,
+        ok_args: &[ValueRef],
+        err_block: BlockRef,
+        err_args: &[ValueRef],
+    ) -> Result<Option<Value>> {
+        debug_assert_eq!(arity, args.len(), "arity does not match argument count");
+
+        let builder_ref = builder.as_ref();
+        let nil_ref = unsafe { MLIRBuildConstantNil(builder_ref, op.loc) };
+        let args_list = args.iter().rev().fold(nil_ref, |tail, head| unsafe {
+            MLIRCons(builder_ref, op.loc, *head, tail)
+        });
+        if args_list.is_null() {
+            return Err(anyhow!("failed to construct argument list for apply"));
         }
+
+        let apply_args = [module, function, args_list];
+        let name = CString::new("erlang:apply/3").unwrap();
+        unsafe {
+            MLIRBuildStaticCall(
+                builder_ref,
+                op.loc,
+                name.as_ptr(),
+                apply_args.as_ptr(),
+                apply_args.len() as libc::c_uint,
+                op.is_tail,
+                ok_block,
+                ok_args.as_ptr(),
+                ok_args.len() as libc::c_uint,
+                err_block,
+                err_args.as_ptr(),
+                err_args.len() as libc::c_uint,
+            );
+        }
+
+        Ok(None)
     }
 }
 
@@ -99,14 +194,51 @@ pub struct CalleeBuilder;
 
 impl CalleeBuilder {
     pub fn build<'f, 'o>(
-        _builder: &mut ScopedFunctionBuilder<'f, 'o>,
+        builder: &mut ScopedFunctionBuilder<'f, 'o>,
         ir_value: Option<ir::Value>,
         op: FunctionRef,
     ) -> Result<Option<Value>> {
-        todo!(
-            "build function reference constant for {:?} = {:?}",
-            ir_value,
-            &op.callee
-        );
+        // Only `fun M:F/A` with a constant module and function has a code pointer we
+        // can refer to, dynamic captures would need the target resolved at runtime
+        let ident = match op.callee {
+            Callee::Static(ident) => ident,
+            callee => {
+                return Err(anyhow!(
+                    "unsupported function reference to {}, only static captures are supported",
+                    callee
+                ))
+            }
+        };
+        builder.debug(&format!("function reference to {}", &ident));
+
+        let builder_ref = builder.as_ref();
+        let module_ref =
+            ident
+                .module
+                .name
+                .as_attribute_ref(op.loc, builder_ref, builder.options())?;
+        let function_ref =
+            ident
+                .name
+                .name
+                .as_attribute_ref(op.loc, builder_ref, builder.options())?;
+        let name = CString::new(ident.to_string()).unwrap();
+
+        let result_ref = unsafe {
+            MLIRBuildFunctionRef(
+                builder_ref,
+                op.loc,
+                module_ref,
+                function_ref,
+                name.as_ptr(),
+                ident.arity as u8,
+            )
+        };
+        if result_ref.is_null() {
+            return Err(anyhow!("failed to build function reference to {}", ident));
+        }
+
+        let result = builder.new_value(ir_value, result_ref, ValueDef::Result(0));
+        Ok(Some(result))
     }
 }
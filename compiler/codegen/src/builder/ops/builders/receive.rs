@@ -9,9 +9,10 @@ impl ReceiveStartBuilder {
     ) -> Result<Option<Value>> {
         let cont = builder.block_ref(op.cont);
         let timeout = builder.value_ref(op.timeout);
+        let marker = op.marker.map(|m| builder.value_ref(m)).unwrap_or_default();
 
         unsafe {
-            MLIRBuildReceiveStart(builder.as_ref(), op.loc, cont, timeout);
+            MLIRBuildReceiveStart(builder.as_ref(), op.loc, cont, timeout, marker);
         }
 
         Ok(None)
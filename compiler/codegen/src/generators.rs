@@ -1,3 +1,4 @@
+mod app_env;
 mod atom_table;
 mod exceptions;
 mod symbol_table;
@@ -34,5 +35,8 @@ pub fn run(
     let exception_handler = exceptions::generate(options, context, target_machine, output_dir)?;
     result.modules.push(exception_handler);
 
+    let app_env = app_env::generate(options, context, target_machine, output_dir)?;
+    result.modules.push(app_env);
+
     Ok(())
 }
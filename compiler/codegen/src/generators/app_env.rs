@@ -0,0 +1,130 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+
+use liblumen_beam::serialization::etf;
+use liblumen_llvm as llvm;
+use liblumen_llvm::builder::ModuleBuilder;
+use liblumen_llvm::enums::Linkage;
+use liblumen_llvm::target::TargetMachine;
+use liblumen_session::{Options, Term};
+
+use crate::meta::CompiledModule;
+use crate::Result;
+
+/// Generates an LLVM module containing the `env` of every application in the project
+///
+/// The environment is encoded in the external term format as a list of
+/// `{Application, [{Key, Value}]}` tuples, which the runtime decodes on demand for
+/// `application:get_env/2`.
+///
+/// - Generate a private constant containing the encoded environment
+/// - Generate the __LUMEN_APP_ENV global as a pointer to the first byte of the constant
+/// - Generate the __LUMEN_APP_ENV_SIZE global with the number of bytes in the constant
+pub fn generate(
+    options: &Options,
+    context: &llvm::Context,
+    target_machine: &TargetMachine,
+    output_dir: &Path,
+) -> Result<Arc<CompiledModule>> {
+    const NAME: &'static str = "liblumen_crt_app_env";
+
+    let builder = ModuleBuilder::new(NAME, options, context, target_machine)?;
+
+    let apps = options
+        .project
+        .iter()
+        .flat_map(|project| project.apps.iter())
+        .map(|app| {
+            let env = app
+                .env
+                .iter()
+                .map(|(key, value)| tuple(vec![atom(key), to_etf(value)]))
+                .collect::<Vec<_>>();
+            tuple(vec![atom(&app.name), etf::List::from(env).into()])
+        })
+        .collect::<Vec<_>>();
+    let mut encoded = Vec::new();
+    etf::Term::from(etf::List::from(apps))
+        .encode(&mut encoded)
+        .map_err(|err| anyhow!("unable to encode application environment: {}", err))?;
+
+    let i8_type = builder.get_i8_type();
+    let i8ptr_type = builder.get_pointer_type(i8_type);
+    let i64_type = builder.get_i64_type();
+
+    let env_const_init = builder.build_constant_bytes(encoded.as_slice());
+    let env_const_ty = builder.type_of(env_const_init);
+    let env_const =
+        builder.build_constant(env_const_ty, "__LUMEN_APP_ENV_BYTES", Some(env_const_init));
+    builder.set_linkage(env_const, Linkage::Private);
+
+    let env_global_init = builder.build_const_inbounds_gep(env_const, &[0, 0]);
+    let env_global = builder.build_global(i8ptr_type, "__LUMEN_APP_ENV", Some(env_global_init));
+    builder.set_alignment(env_global, 8);
+
+    let env_size_global_init = builder.build_constant_uint(i64_type, encoded.len() as u64);
+    let env_size_global =
+        builder.build_global(i64_type, "__LUMEN_APP_ENV_SIZE", Some(env_size_global_init));
+    builder.set_alignment(env_size_global, 8);
+
+    // Finalize module
+    let module = builder.finish()?;
+    // Open ll file for writing
+    let ir_path = output_dir.join(&format!("{}.ll", NAME));
+    let mut file = File::create(ir_path.as_path())?;
+    // Emit IR file
+    module.emit_ir(&mut file)?;
+
+    // Open object file for writing
+    let obj_path = output_dir.join(&format!("{}.o", NAME));
+    let mut file = File::create(obj_path.as_path())?;
+    // Emit object file
+    module.emit_obj(&mut file)?;
+
+    Ok(Arc::new(CompiledModule::new(
+        NAME.to_string(),
+        Some(obj_path),
+        None,
+    )))
+}
+
+fn to_etf(term: &Term) -> etf::Term {
+    match term {
+        Term::Atom(ref name) => atom(name),
+        Term::Integer(i) if *i >= i32::min_value() as i64 && *i <= i32::max_value() as i64 => {
+            etf::FixInteger::from(*i as i32).into()
+        }
+        Term::Integer(i) => etf::BigInteger::from(*i).into(),
+        Term::Float(n) => etf::Float::from(*n).into(),
+        // Strings are lists of characters
+        Term::String(ref s) => etf::List::from(
+            s.chars()
+                .map(|c| etf::FixInteger::from(c as i32).into())
+                .collect::<Vec<_>>(),
+        )
+        .into(),
+        Term::Binary(ref bytes) => etf::Binary::from(bytes.clone()).into(),
+        Term::List(ref elements) => {
+            etf::List::from(elements.iter().map(to_etf).collect::<Vec<_>>()).into()
+        }
+        Term::Tuple(ref elements) => tuple(elements.iter().map(to_etf).collect()),
+        Term::Map(ref entries) => etf::Map::from(
+            entries
+                .iter()
+                .map(|(key, value)| (to_etf(key), to_etf(value)))
+                .collect::<Vec<_>>(),
+        )
+        .into(),
+    }
+}
+
+fn atom(name: &str) -> etf::Term {
+    etf::Atom::from(name).into()
+}
+
+fn tuple(elements: Vec<etf::Term>) -> etf::Term {
+    etf::Tuple::from(elements).into()
+}
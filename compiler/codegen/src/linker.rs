@@ -43,6 +43,17 @@ impl LibSource {
     }
 }
 
+/// The functions of the runtime which are exported from libraries for use by the program
+/// embedding them
+pub const EMBEDDING_API: &[&str] = &[
+    "lumen_start",
+    "lumen_stop",
+    "lumen_spawn",
+    "lumen_send",
+    "lumen_register_callback",
+    "lumen_unregister_callback",
+];
+
 /// For all the linkers we support, and information they might
 /// need out of the shared crate context before we get rid of it.
 #[derive(Debug)]
@@ -52,9 +63,20 @@ pub struct LinkerInfo {
 
 impl LinkerInfo {
     pub fn new() -> LinkerInfo {
-        Self {
-            exports: Default::default(),
-        }
+        // Libraries only export the C API for embedding the runtime, see
+        // `runtimes/minimal/include/lumen.h`
+        let embedding_api = EMBEDDING_API
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect::<Vec<_>>();
+
+        let mut exports = FxHashMap::default();
+        exports.insert(ProjectType::Executable, Vec::new());
+        exports.insert(ProjectType::Staticlib, embedding_api.clone());
+        exports.insert(ProjectType::Dylib, embedding_api.clone());
+        exports.insert(ProjectType::Cdylib, embedding_api);
+
+        Self { exports }
     }
 
     pub fn to_linker<'a>(
@@ -29,6 +29,9 @@ use crate::meta::{CodegenResults, LibSource};
 
 use super::archive::{ArchiveBuilder, LlvmArchiveBuilder};
 
+const EMBEDDING_HEADER_NAME: &'static str = "lumen.h";
+const EMBEDDING_HEADER: &'static str = include_str!("../../../../runtimes/minimal/include/lumen.h");
+
 enum RlibFlavor {
     #[allow(dead_code)]
     Normal,
@@ -66,16 +69,28 @@ pub fn link_binary(
         .as_ref()
         .map(|of| of.clone())
         .unwrap_or_else(|| {
-            let name = PathBuf::from(options.project_name.as_str());
-            let ext = match project_type {
-                ProjectType::Executable if options.target.options.is_like_windows => "exe",
-                ProjectType::Executable => "out",
-                ProjectType::Staticlib => "a",
-                _ => "o",
-            };
-            let mut p = output_dir.as_path().join(name);
-            p.set_extension(ext);
-            p
+            let name = options.project_name.as_str();
+            let target_options = &options.target.options;
+            match project_type {
+                ProjectType::Executable => {
+                    let ext = if target_options.is_like_windows {
+                        "exe"
+                    } else {
+                        "out"
+                    };
+                    let mut p = output_dir.as_path().join(name);
+                    p.set_extension(ext);
+                    p
+                }
+                ProjectType::Staticlib => output_dir.as_path().join(format!(
+                    "{}{}{}",
+                    target_options.staticlib_prefix, name, target_options.staticlib_suffix
+                )),
+                ProjectType::Dylib | ProjectType::Cdylib => output_dir.as_path().join(format!(
+                    "{}{}{}",
+                    target_options.dll_prefix, name, target_options.dll_suffix
+                )),
+            }
         });
 
     match project_type {
@@ -102,6 +117,14 @@ pub fn link_binary(
         }
     }
 
+    // Libraries are embedded through the C API of the runtime, which is declared in a header
+    // written next to the library
+    if project_type != ProjectType::Executable {
+        let header = output_file.with_file_name(EMBEDDING_HEADER_NAME);
+        fs::write(&header, EMBEDDING_HEADER)
+            .map_err(|err| anyhow!("failed to write {}: {}", header.display(), err))?;
+    }
+
     // Remove the temporary object file and metadata if we aren't saving temps
     for obj in codegen_results.modules.iter().filter_map(|m| m.object()) {
         if let Err(e) = remove(obj) {
@@ -114,7 +137,7 @@ pub fn link_binary(
 
 fn link_staticlib(
     options: &Options,
-    _diagnostics: &DiagnosticsHandler,
+    diagnostics: &DiagnosticsHandler,
     project_type: ProjectType,
     codegen_results: &CodegenResults,
     output_file: &Path,
@@ -147,9 +170,46 @@ fn link_staticlib(
         }
     }
 
+    // The runtime is bundled into the archive, so that it is all that is needed to embed it,
+    // other than the native libraries the runtime depends on
+    let filesearch = options.target_filesearch(PathKind::All);
+    let rlib_dir = filesearch.get_lib_path();
+    for lib in runtime_libraries(options) {
+        if lib.ends_with(".rlib") {
+            let path = rlib_dir.join(lib);
+            let lto = false;
+            let skip_objects = false;
+            ab.add_rlib(&path, lib, lto, skip_objects)
+                .map_err(|err| anyhow!("failed to add {}: {}", path.display(), err))?;
+        } else {
+            ab.add_native_library(lib);
+        }
+    }
+
     ab.update_symbols();
     ab.build();
 
+    let native_libraries = codegen_results
+        .project_info
+        .native_libraries
+        .iter()
+        .filter_map(|lib| match lib.kind {
+            NativeLibraryKind::NativeUnknown | NativeLibraryKind::NativeStaticNobundle => {
+                lib.name.as_ref().map(|name| format!("-l{}", name))
+            }
+            NativeLibraryKind::NativeFramework => {
+                lib.name.as_ref().map(|name| format!("-framework {}", name))
+            }
+            NativeLibraryKind::NativeStatic | NativeLibraryKind::NativeRawDylib => None,
+        })
+        .collect::<Vec<_>>();
+    if !native_libraries.is_empty() {
+        diagnostics.note(format!(
+            "link against the following native artifacts when linking against this static library: {}",
+            native_libraries.join(" ")
+        ));
+    }
+
     Ok(())
 }
 
@@ -890,7 +950,7 @@ fn link_args(
     // If we're building something like a dynamic library then some platforms
     // need to make sure that all symbols are exported correctly from the
     // dynamic library.
-    //cmd.export_symbols(tmpdir, project_type);
+    cmd.export_symbols(tmpdir, project_type);
 
     // When linking a dynamic library, we put the metadata into a section of the
     // executable. This metadata is in a separate object file from the main
@@ -1088,18 +1148,8 @@ pub fn add_local_native_libraries(
     let search_path = archive_search_paths(options);
 
     // Add runtime libs we depend on
-    let no_std = options.codegen_opts.no_std.unwrap_or(false);
-    let libstd_libs = match options.target.arch.as_str() {
-        "x86_64" if !no_std => vec![
-            "libpanic_unwind.rlib",
-            "lumen_rt_minimal",
-            "libliblumen_otp.rlib",
-        ],
-        "wasm32" if !no_std => vec!["libpanic_abort.rlib", "lumen_web"],
-        _ => vec!["libpanic_unwind.rlib"],
-    };
     let rlib_dir = filesearch.get_lib_path();
-    for lib in libstd_libs {
+    for lib in runtime_libraries(options) {
         if lib.ends_with(".rlib") {
             link_rlib(cmd, options, tmpdir, &rlib_dir.join(lib));
         } else {
@@ -1150,6 +1200,21 @@ pub fn add_local_native_libraries(
     Ok(())
 }
 
+/// The libraries making up the runtime, which are either rlibs in the target library directory,
+/// or the names of static libraries in the search path
+fn runtime_libraries(options: &Options) -> Vec<&'static str> {
+    let no_std = options.codegen_opts.no_std.unwrap_or(false);
+    match options.target.arch.as_str() {
+        "x86_64" if !no_std => vec![
+            "libpanic_unwind.rlib",
+            "lumen_rt_minimal",
+            "libliblumen_otp.rlib",
+        ],
+        "wasm32" if !no_std => vec!["libpanic_abort.rlib", "lumen_web"],
+        _ => vec!["libpanic_unwind.rlib"],
+    }
+}
+
 fn create_rlib<'a>(
     options: &'a Options,
     codegen_results: &CodegenResults,
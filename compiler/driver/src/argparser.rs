@@ -124,6 +124,21 @@ fn compile_command<'a, 'b>() -> App<'a, 'b> {
                 .long("output-dir")
                 .value_name("DIR"),
         )
+        .arg(
+            Arg::with_name("output-type")
+                .help(
+                    "The kind of artifact to link\n  \
+                       bin       = an executable (default)\n  \
+                       staticlib = a static library to embed with the C API in lumen.h\n  \
+                       dylib     = a shared library to embed with the C API in lumen.h\n  \
+                       _",
+                )
+                .next_line_help(true)
+                .long("output-type")
+                .takes_value(true)
+                .value_name("TYPE")
+                .possible_values(&["bin", "staticlib", "dylib"]),
+        )
         .arg(
             Arg::with_name("debug")
                 .help("Generate source level debug information (same as -C debuginfo=2)")
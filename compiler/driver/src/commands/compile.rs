@@ -22,7 +22,7 @@ use crate::compiler::prelude::{Compiler as CompilerQueryGroup, *};
 use crate::compiler::Compiler;
 use crate::task;
 
-const NUM_GENERATED_MODULES: usize = 3;
+const NUM_GENERATED_MODULES: usize = 4;
 
 pub fn handle_command<'a>(
     c_opts: CodegenOptions,
@@ -94,6 +94,10 @@ pub fn handle_command<'a>(
         }
     }
 
+    if options.debugging_opts.incremental_info {
+        db.incremental_cache().report(&diagnostics);
+    }
+
     // Do not proceed to linking if there were compilation errors
     diagnostics.abort_if_errors();
 
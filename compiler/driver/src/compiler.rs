@@ -1,3 +1,4 @@
+pub(crate) mod incremental;
 mod queries;
 mod query_groups;
 
@@ -22,6 +23,7 @@ use crate::interner::{InternedInput, Interner, InternerStorage};
 use crate::output::CompilerOutput;
 use crate::parser::{Parser, ParserStorage};
 
+use self::incremental::IncrementalCache;
 use self::query_groups::{CompilerExt, CompilerStorage};
 
 pub(crate) mod prelude {
@@ -40,6 +42,7 @@ pub struct Compiler {
     codemap: Arc<CodeMap>,
     atoms: Arc<Mutex<HashSet<Symbol>>>,
     symbols: Arc<Mutex<HashSet<FunctionSymbol>>>,
+    incremental: Arc<IncrementalCache>,
 }
 impl Compiler {
     pub fn new(codemap: Arc<CodeMap>, diagnostics: Arc<DiagnosticsHandler>) -> Self {
@@ -52,6 +55,7 @@ impl Compiler {
             codemap,
             atoms: Arc::new(Mutex::new(atoms)),
             symbols: Arc::new(Mutex::new(HashSet::default())),
+            incremental: Arc::new(IncrementalCache::default()),
         }
     }
 }
@@ -72,6 +76,7 @@ impl salsa::ParallelDatabase for Compiler {
             codemap: self.codemap.clone(),
             atoms: self.atoms.clone(),
             symbols: self.symbols.clone(),
+            incremental: self.incremental.clone(),
         })
     }
 }
@@ -184,4 +189,8 @@ impl CompilerExt for Compiler {
             locked.insert(*i);
         }
     }
+
+    fn incremental_cache(&self) -> &IncrementalCache {
+        &self.incremental
+    }
 }
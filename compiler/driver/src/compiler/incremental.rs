@@ -0,0 +1,453 @@
+//! A persistent cache of compiled object files, so that unchanged modules are not
+//! rebuilt by every invocation of the compiler.
+//!
+//! Each entry is stored in the `incremental` directory of the output directory, as
+//! the object file of the module, plus the atoms and function symbols it contributes
+//! to the atom and symbol tables generated at link time. Entries are keyed by a hash
+//! of the module source, the header files it includes and every option which changes
+//! the generated code, so a change to any of them is a cache miss.
+//!
+//! NOTE: Includes are found by scanning for `-include` and `-include_lib` attributes,
+//! so an include whose path is built from a macro is not detected.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+use siphasher::sip::SipHasher13;
+
+use libeir_intern::Symbol;
+
+use liblumen_core::symbols::FunctionSymbol;
+use liblumen_session::{Input, Options, OutputType};
+use liblumen_util::diagnostics::DiagnosticsHandler;
+
+use crate::interner::InternedInput;
+
+const CACHE_DIR: &'static str = "incremental";
+const METADATA_EXTENSION: &'static str = "meta";
+
+/// Identifies a cache entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey(u64);
+impl CacheKey {
+    /// Computes the key for `input`, or `None` if it cannot be cached.
+    ///
+    /// Only inputs for which the object file is the sole output are cached, since
+    /// a cache hit skips every other stage of compilation.
+    pub fn new(options: &Options, input: &Input) -> anyhow::Result<Option<Self>> {
+        let output_types = &options.output_types;
+        if output_types.maybe_emit(input, OutputType::Object).is_none() {
+            return Ok(None);
+        }
+        let has_other_outputs = output_types.keys().any(|output_type| match output_type {
+            OutputType::Object | OutputType::Exe => false,
+            other => output_types.maybe_emit(input, *other).is_some(),
+        });
+        if has_other_outputs {
+            return Ok(None);
+        }
+
+        // Keys are stored on disk, so they must not change between runs, unlike the randomly
+        // keyed `DefaultHasher`
+        let mut hasher = SipHasher13::new_with_keys(0, 0);
+        crate::LUMEN_RELEASE.hash(&mut hasher);
+        crate::LUMEN_COMMIT_HASH.hash(&mut hasher);
+        let (source, dir) = match input {
+            Input::File(ref path) => (fs::read(path)?, path.parent()),
+            Input::Str { ref input, .. } => (input.as_bytes().to_vec(), None),
+        };
+        source.hash(&mut hasher);
+        input.get_type().hash(&mut hasher);
+
+        let mut defines = options.defines.iter().collect::<Vec<_>>();
+        defines.sort();
+        defines.hash(&mut hasher);
+        // Sources of an application are also compiled with its own `erl_opts`
+        let app = match (&options.project, input) {
+            (Some(ref project), Input::File(ref path)) => project.app_for(path),
+            _ => None,
+        };
+        if let Some(app) = app {
+            let mut app_defines = app.defines.iter().collect::<Vec<_>>();
+            app_defines.sort();
+            app_defines.hash(&mut hasher);
+        }
+
+        // The same search paths as `parse_config`
+        let include_paths = options
+            .include_path
+            .iter()
+            .chain(app.iter().flat_map(|app| app.include_path.iter()))
+            .cloned()
+            .collect::<Vec<_>>();
+        let code_paths = options
+            .code_path
+            .iter()
+            .cloned()
+            .chain(options.project.iter().flat_map(|project| {
+                project
+                    .apps
+                    .iter()
+                    .filter_map(|app| app.root.parent().map(Path::to_path_buf))
+            }))
+            .collect::<Vec<_>>();
+        include_paths.hash(&mut hasher);
+        code_paths.hash(&mut hasher);
+        let mut includes = Includes {
+            include_paths: &include_paths,
+            code_paths: &code_paths,
+            seen: HashSet::new(),
+        };
+        includes.hash_headers(&String::from_utf8_lossy(&source), dir, &mut hasher)?;
+
+        options.target.triple().hash(&mut hasher);
+        hash_codegen_options(options, &mut hasher);
+
+        Ok(Some(Self(hasher.finish())))
+    }
+}
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// The atoms and function symbols contributed by a module
+#[derive(Debug, Default, Clone)]
+pub struct ModuleSymbols {
+    pub atoms: Vec<Symbol>,
+    pub symbols: Vec<FunctionSymbol>,
+}
+impl ModuleSymbols {
+    /// Atom ids are only meaningful in the process which interned them, so names
+    /// are written out, and interned again when read back
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&(self.atoms.len() as u32).to_le_bytes())?;
+        for atom in self.atoms.iter() {
+            write_str(writer, &atom.as_str().get())?;
+        }
+        writer.write_all(&(self.symbols.len() as u32).to_le_bytes())?;
+        for symbol in self.symbols.iter() {
+            write_str(writer, &symbol_from_usize(symbol.module).as_str().get())?;
+            write_str(writer, &symbol_from_usize(symbol.function).as_str().get())?;
+            writer.write_all(&[symbol.arity])?;
+        }
+        Ok(())
+    }
+
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let num_atoms = read_u32(reader)?;
+        let mut atoms = Vec::with_capacity(num_atoms as usize);
+        for _ in 0..num_atoms {
+            atoms.push(Symbol::intern(&read_string(reader)?));
+        }
+        let num_symbols = read_u32(reader)?;
+        let mut symbols = Vec::with_capacity(num_symbols as usize);
+        for _ in 0..num_symbols {
+            let module = Symbol::intern(&read_string(reader)?);
+            let function = Symbol::intern(&read_string(reader)?);
+            let mut arity = [0; 1];
+            reader.read_exact(&mut arity)?;
+            symbols.push(FunctionSymbol {
+                module: module.as_usize(),
+                function: function.as_usize(),
+                arity: arity[0],
+                ptr: std::ptr::null(),
+            });
+        }
+        Ok(Self { atoms, symbols })
+    }
+}
+
+/// Whether a module was reused from the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    Hit,
+    Miss,
+}
+
+/// The on-disk cache, along with the bookkeeping for the current compilation
+#[derive(Default)]
+pub struct IncrementalCache {
+    /// Symbols of modules built in this session, to be stored once their object is emitted
+    pending: Mutex<HashMap<InternedInput, ModuleSymbols>>,
+    outcomes: Mutex<Vec<(String, Outcome)>>,
+}
+impl IncrementalCache {
+    /// Copies the cached object of `name` to `object`, returning the symbols of the
+    /// module if there was an entry for `key`
+    pub fn load(
+        &self,
+        output_dir: &Path,
+        name: &str,
+        key: CacheKey,
+        object: &Path,
+    ) -> anyhow::Result<Option<ModuleSymbols>> {
+        let (cached_object, metadata) = entry_paths(output_dir, name, key);
+        if !cached_object.exists() || !metadata.exists() {
+            self.outcomes.lock().push((name.to_owned(), Outcome::Miss));
+            return Ok(None);
+        }
+
+        let symbols = ModuleSymbols::read(&mut io::BufReader::new(fs::File::open(metadata)?))?;
+        if let Some(parent) = object.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(cached_object, object)?;
+
+        self.outcomes.lock().push((name.to_owned(), Outcome::Hit));
+        Ok(Some(symbols))
+    }
+
+    /// Records the symbols generated for `input`, until its object is stored
+    pub fn record(&self, input: InternedInput, symbols: ModuleSymbols) {
+        self.pending.lock().insert(input, symbols);
+    }
+
+    /// Stores `object` as the entry for `key`, replacing any stale entries for `name`
+    pub fn store(
+        &self,
+        output_dir: &Path,
+        input: InternedInput,
+        name: &str,
+        key: CacheKey,
+        object: &Path,
+    ) -> anyhow::Result<()> {
+        // Modules loaded directly from MLIR contribute no symbols
+        let symbols = self.pending.lock().remove(&input).unwrap_or_default();
+
+        let dir = output_dir.join(CACHE_DIR);
+        fs::create_dir_all(&dir)?;
+        remove_entries(&dir, name)?;
+
+        let (cached_object, metadata) = entry_paths(output_dir, name, key);
+        let mut writer = io::BufWriter::new(fs::File::create(&metadata)?);
+        symbols.write(&mut writer)?;
+        writer.flush()?;
+        // The object is copied last, as an entry is only valid once both files exist
+        fs::copy(object, cached_object)?;
+
+        Ok(())
+    }
+
+    /// Prints which modules were reused, for `-Z incremental-info`
+    pub fn report(&self, diagnostics: &DiagnosticsHandler) {
+        let mut outcomes = self.outcomes.lock().clone();
+        outcomes.sort();
+
+        let mut hits = 0;
+        for (name, outcome) in outcomes.iter() {
+            let outcome = match outcome {
+                Outcome::Hit => {
+                    hits += 1;
+                    "hit"
+                }
+                Outcome::Miss => "miss",
+            };
+            diagnostics.note(format!("incremental: {} {}", outcome, name));
+        }
+        diagnostics.note(format!(
+            "incremental: {} hits, {} misses",
+            hits,
+            outcomes.len() - hits
+        ));
+    }
+}
+
+/// Finds the header files included by a source file, like the preprocessor does
+struct Includes<'a> {
+    include_paths: &'a [PathBuf],
+    code_paths: &'a [PathBuf],
+    /// Headers already hashed, so that include cycles and headers included more than
+    /// once are only hashed once
+    seen: HashSet<PathBuf>,
+}
+impl<'a> Includes<'a> {
+    /// Hashes the path and contents of every header included by `source`, and the ones
+    /// they include, where `dir` is the directory of the file containing `source`
+    fn hash_headers<H: Hasher>(
+        &mut self,
+        source: &str,
+        dir: Option<&Path>,
+        state: &mut H,
+    ) -> io::Result<()> {
+        for (is_lib, name) in include_attributes(source) {
+            match self.resolve(is_lib, &name, dir) {
+                Some(path) => {
+                    if !self.seen.insert(path.clone()) {
+                        continue;
+                    }
+                    path.hash(state);
+                    let header = fs::read(&path)?;
+                    header.hash(state);
+                    self.hash_headers(&String::from_utf8_lossy(&header), path.parent(), state)?;
+                }
+                // Compilation fails on a missing header, but it may be added later
+                None => name.hash(state),
+            }
+        }
+        Ok(())
+    }
+
+    /// `-include` is relative to the including file, then the include paths.
+    /// `-include_lib` falls back to `App/...` in the code paths.
+    fn resolve(&self, is_lib: bool, name: &str, dir: Option<&Path>) -> Option<PathBuf> {
+        let name = expand_env_var(name);
+        let path = Path::new(&name);
+        if path.is_absolute() {
+            return Some(path.to_path_buf()).filter(|path| path.is_file());
+        }
+        let found = dir
+            .into_iter()
+            .chain(self.include_paths.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(path))
+            .find(|path| path.is_file());
+        if found.is_some() || !is_lib {
+            return found;
+        }
+        self.code_paths
+            .iter()
+            .map(|dir| dir.join(path))
+            .find(|path| path.is_file())
+    }
+}
+
+/// The file names of the `-include` and `-include_lib` attributes in `source`, and
+/// whether each is an `-include_lib`
+fn include_attributes(source: &str) -> Vec<(bool, String)> {
+    let mut attributes = Vec::new();
+    for line in source.lines() {
+        // Comments can't contain attributes
+        let line = line.split('%').next().unwrap().trim_start();
+        if !line.starts_with('-') {
+            continue;
+        }
+        let attribute = line[1..].trim_start();
+        let (is_lib, rest) = if attribute.starts_with("include_lib") {
+            (true, &attribute["include_lib".len()..])
+        } else if attribute.starts_with("include") {
+            (false, &attribute["include".len()..])
+        } else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        if !rest.starts_with('(') {
+            continue;
+        }
+        let rest = rest[1..].trim_start();
+        if !rest.starts_with('"') {
+            continue;
+        }
+        let rest = &rest[1..];
+        if let Some(end) = rest.find('"') {
+            attributes.push((is_lib, rest[..end].to_owned()));
+        }
+    }
+    attributes
+}
+
+/// Like the preprocessor, a leading `$VAR` is replaced by the value of the environment
+/// variable `VAR`
+fn expand_env_var(name: &str) -> String {
+    if !name.starts_with('$') {
+        return name.to_owned();
+    }
+    let end = name.find('/').unwrap_or_else(|| name.len());
+    match std::env::var(&name[1..end]) {
+        Ok(value) => format!("{}{}", value, &name[end..]),
+        Err(_) => name.to_owned(),
+    }
+}
+
+/// Hashes the options which change the generated object file.
+///
+/// Options which only affect linking are left out, so that changing them reuses the
+/// cached objects.
+fn hash_codegen_options<H: Hasher>(options: &Options, state: &mut H) {
+    let c = &options.codegen_opts;
+    let z = &options.debugging_opts;
+
+    let affects_codegen: &[&dyn fmt::Debug] = &[
+        &options.opt_level,
+        &options.debug_info,
+        &options.debug_assertions,
+        &c.target_cpu,
+        &c.target_features,
+        &c.passes,
+        &c.llvm_args,
+        &c.relocation_mode,
+        &c.code_model,
+        &c.tls_mode,
+        &c.no_prepopulate_passes,
+        &c.inline_threshold,
+        &c.panic,
+        &c.linker_plugin_lto,
+        &z.asm_comments,
+        &z.sanitizer,
+        &z.embed_bitcode,
+        &z.merge_functions,
+        &z.emit_stack_sizes,
+        &z.thinlto,
+    ];
+    // Not every option type implements `Hash`, but all of them implement `Debug`
+    for option in affects_codegen {
+        format!("{:?}", option).hash(state);
+    }
+}
+
+fn entry_paths(output_dir: &Path, name: &str, key: CacheKey) -> (PathBuf, PathBuf) {
+    let dir = output_dir.join(CACHE_DIR);
+    let stem = format!("{}-{}", name, key);
+    let object = dir
+        .join(&stem)
+        .with_extension(OutputType::Object.extension());
+    let metadata = dir.join(&stem).with_extension(METADATA_EXTENSION);
+    (object, metadata)
+}
+
+fn remove_entries(dir: &Path, name: &str) -> io::Result<()> {
+    let prefix = format!("{}-", name);
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_entry = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map_or(false, |stem| {
+                stem.starts_with(&prefix) && {
+                    let key = &stem[prefix.len()..];
+                    key.len() == 16 && key.chars().all(|c| c.is_ascii_hexdigit())
+                }
+            });
+        if is_entry {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Function symbols hold the raw ids of their module and function atoms
+fn symbol_from_usize(id: usize) -> Symbol {
+    Symbol::new(id as u32)
+}
+
+fn write_str<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    writer.write_all(&(s.len() as u32).to_le_bytes())?;
+    writer.write_all(s.as_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = read_u32(reader)?;
+    let mut bytes = vec![0; len as usize];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -10,6 +10,7 @@ use liblumen_llvm::{self as llvm, target::TargetMachineConfig};
 use liblumen_mlir as mlir;
 use liblumen_session::{Input, InputType, OutputType};
 
+use super::incremental::{CacheKey, ModuleSymbols};
 use super::prelude::*;
 
 /// Create context for LLVM
@@ -106,6 +107,13 @@ where
     ))?;
     db.add_atoms(built.atoms.iter());
     db.add_symbols(built.symbols.iter());
+    db.incremental_cache().record(
+        input,
+        ModuleSymbols {
+            atoms: built.atoms.iter().copied().collect(),
+            symbols: built.symbols.iter().copied().collect(),
+        },
+    );
     db.maybe_emit_file_with_opts(&options, input, &built.module)?;
     Ok(Arc::new(built.module))
 }
@@ -120,7 +128,7 @@ where
     C: Compiler,
 {
     match db.input_type(input) {
-        InputType::Erlang | InputType::AbstractErlang | InputType::EIR => {
+        InputType::Erlang | InputType::AbstractErlang | InputType::EIR | InputType::BEAM => {
             debug!("input {:?} is erlang", input);
             db.generate_mlir(thread_id, input)
         }
@@ -130,7 +138,7 @@ where
         }
         InputType::Unknown(None) => {
             debug!("unknown input type for {:?} on {:?}", input, thread_id);
-            db.report_error("invalid input, expected .erl or .mlir");
+            db.report_error("invalid input, expected .erl, .beam or .mlir");
             Err(ErrorReported)
         }
         InputType::Unknown(Some(ref ext)) => {
@@ -139,7 +147,7 @@ where
                 ext, input, thread_id
             );
             db.report_error(format!(
-                "invalid input extension ({}), expected .erl or .mlir",
+                "invalid input extension ({}), expected .erl, .beam or .mlir",
                 ext
             ));
             Err(ErrorReported)
@@ -264,6 +272,36 @@ where
         input, &input_info, thread_id
     );
 
+    // Reuse the object file from a previous compilation if nothing affecting it has changed
+    let name = input_info.file_stem().to_string_lossy().into_owned();
+    let output_dir = db.output_dir();
+    let cache = db.incremental_cache();
+    let cache_key = db.to_query_result(CacheKey::new(&options, &input_info))?;
+    if let Some(key) = cache_key {
+        let obj_path = output_dir.join(
+            options
+                .output_types
+                .maybe_emit(&input_info, OutputType::Object)
+                .unwrap(),
+        );
+        let cached = cache
+            .load(&output_dir, &name, key, &obj_path)
+            .unwrap_or_else(|err| {
+                diagnostics.warn(format!(
+                    "unable to read incremental cache for {}: {}",
+                    &source_name, err
+                ));
+                None
+            });
+        if let Some(symbols) = cached {
+            debug!("reusing cached object file for {:?}", input);
+            db.add_atoms(symbols.atoms.iter());
+            db.add_symbols(symbols.symbols.iter());
+            diagnostics.success("Fresh", format!("{}", &source_name));
+            return Ok(Arc::new(CompiledModule::new(name, Some(obj_path), None)));
+        }
+    }
+
     // Get LLVM IR module
     // We provide the current thread ID as part of the query, since the context
     // object of an LLVM module is not thread-safe, we only want to fulfill a
@@ -287,17 +325,23 @@ where
         },
     )?;
 
+    if let (Some(key), Some(obj_path)) = (cache_key, obj_path.as_ref()) {
+        // A stale cache only costs a rebuild, so failing to update it is not an error
+        if let Err(err) = cache.store(&output_dir, input, &name, key, obj_path) {
+            diagnostics.warn(format!(
+                "unable to update incremental cache for {}: {}",
+                &source_name, err
+            ));
+        }
+    }
+
     // Gather compiled module metadata
     let bc_path = options
         .output_types
         .maybe_emit(&input_info, OutputType::LLVMBitcode)
-        .map(|filename| db.output_dir().join(filename));
+        .map(|filename| output_dir.join(filename));
 
-    let compiled = Arc::new(CompiledModule::new(
-        input_info.file_stem().to_string_lossy().into_owned(),
-        obj_path,
-        bc_path,
-    ));
+    let compiled = Arc::new(CompiledModule::new(name, obj_path, bc_path));
 
     debug!("compilation finished for {:?}", input);
     diagnostics.success("Compiled", format!("{}", &source_name));
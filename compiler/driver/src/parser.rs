@@ -32,7 +32,7 @@ pub trait Parser: CompilerOutput {
     fn input_type(&self, input: InternedInput) -> InputType;
 
     #[salsa::invoke(queries::parse_config)]
-    fn parse_config(&self) -> ParseConfig;
+    fn parse_config(&self, input: InternedInput) -> ParseConfig;
 
     #[salsa::invoke(queries::input_parsed)]
     fn input_parsed(&self, input: InternedInput) -> QueryResult<IRModule>;
@@ -1,10 +1,14 @@
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use anyhow::anyhow;
+
 use libeir_frontend::{AnyFrontend, DynFrontend};
-use libeir_syntax_erl::ParseConfig;
+use libeir_intern::Symbol;
+use libeir_syntax_erl::{MacroDef, MacroIdent, ParseConfig};
 
-use liblumen_session::{IRModule, Input, InputType};
+use liblumen_session::{IRModule, Input, InputType, Project, Term};
 use liblumen_util::diagnostics::FileName;
 use liblumen_util::{seq, seq::Seq};
 
@@ -25,6 +29,11 @@ where
 
     let options = db.options();
 
+    // Projects are compiled one application at a time, in dependency order
+    if let Some(ref project) = options.project {
+        return db.to_query_result(find_project_sources(db, project));
+    }
+
     // Handle case where input is empty, indicating to compile the current working directory
     if options.input_file.is_none() {
         return db.to_query_result(find_sources(db, &options.current_dir));
@@ -79,7 +88,7 @@ where
     input_info.get_type()
 }
 
-pub(crate) fn parse_config<P>(db: &P) -> ParseConfig
+pub(crate) fn parse_config<P>(db: &P, input: InternedInput) -> ParseConfig
 where
     P: Parser,
 {
@@ -89,9 +98,58 @@ where
     parse_config.no_warn = options.no_warn;
     parse_config.include_paths = options.include_path.clone();
     parse_config.code_paths = options.code_path.clone();
+
+    let mut defines = options.defines.clone();
+    if let Some(ref project) = options.project {
+        // `include_lib` finds applications in the directories containing them
+        for app in project.apps.iter() {
+            if let Some(dir) = app.root.parent() {
+                if !parse_config.code_paths.iter().any(|path| path == dir) {
+                    parse_config.code_paths.push_back(dir.to_owned());
+                }
+            }
+        }
+        // Sources of an application get its include directories and `erl_opts` defines,
+        // though defines given on the command line take precedence
+        if let Input::File(ref path) = db.lookup_intern_input(input) {
+            if let Some(app) = project.app_for(path) {
+                parse_config
+                    .include_paths
+                    .extend(app.include_path.iter().cloned());
+                for (name, value) in app.defines.iter() {
+                    defines.entry(name.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+    }
+    parse_config.macros = Some(macro_definitions(&defines));
+
     parse_config
 }
 
+/// Converts `-D NAME[=VALUE]` style defines to preprocessor macros
+///
+/// NOTE: Values which are not atoms or strings are defined as the string of their source
+fn macro_definitions(defines: &HashMap<String, Option<String>>) -> HashMap<MacroIdent, MacroDef> {
+    defines
+        .iter()
+        .map(|(name, value)| {
+            let definition = match value {
+                None => MacroDef::Boolean(true),
+                Some(ref value) => match liblumen_session::consult(&format!("{}.", value)) {
+                    Ok(ref terms) => match terms.as_slice() {
+                        [Term::Atom(ref atom)] => MacroDef::Atom(Symbol::intern(atom)),
+                        [Term::String(ref string)] => MacroDef::String(Symbol::intern(string)),
+                        _ => MacroDef::String(Symbol::intern(value)),
+                    },
+                    Err(_) => MacroDef::String(Symbol::intern(value)),
+                },
+            };
+            (MacroIdent::Const(Symbol::intern(name)), definition)
+        })
+        .collect()
+}
+
 pub(crate) fn input_parsed<P>(db: &P, input: InternedInput) -> QueryResult<IRModule>
 where
     P: Parser,
@@ -102,8 +160,9 @@ where
 
     let codemap = db.codemap().clone();
     let frontend: AnyFrontend = match db.input_type(input) {
-        InputType::Erlang => ErlangFrontend::new(db.parse_config(), codemap).into(),
-        InputType::AbstractErlang => AbstrErlangFrontend::new(codemap).into(),
+        InputType::Erlang => ErlangFrontend::new(db.parse_config(input), codemap).into(),
+        // BEAM files are lowered from the abstract code recovered from their debug info
+        InputType::AbstractErlang | InputType::BEAM => AbstrErlangFrontend::new(codemap).into(),
         InputType::EIR => EirFrontend::new(codemap).into(),
         ty => {
             db.report_error(format!("invalid input type: {}", ty));
@@ -112,6 +171,10 @@ where
     };
 
     let (result, diags) = match db.lookup_intern_input(input) {
+        Input::File(ref path) if db.input_type(input) == InputType::BEAM => {
+            let listing = db.to_query_result(abstract_code_listing(path))?;
+            frontend.parse_string_dyn(&listing)
+        }
         Input::File(ref path) => frontend.parse_file_dyn(path),
         Input::Str { ref input, .. } => frontend.parse_string_dyn(input),
     };
@@ -150,6 +213,37 @@ where
     Ok(new_module)
 }
 
+/// Recovers the abstract code of a BEAM file compiled with `debug_info`, rendered in the format
+/// read by the abstract Erlang frontend
+fn abstract_code_listing(path: &Path) -> anyhow::Result<String> {
+    use liblumen_beam::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    AbstractCode::from_beam_file(path)
+        .and_then(|code| code.to_listing())
+        .map_err(|err| {
+            anyhow!(
+                "unable to read abstract code from {}: {}",
+                path.display(),
+                err
+            )
+        })
+}
+
+/// Finds the sources of every application in `project`, in the order of the applications
+fn find_project_sources<D>(db: &D, project: &Project) -> anyhow::Result<Arc<Seq<InternedInput>>>
+where
+    D: Parser,
+{
+    let mut inputs = Vec::new();
+    for app in project.apps.iter() {
+        for dir in app.src_dirs.iter().filter(|dir| dir.is_dir()) {
+            inputs.extend(find_sources(db, dir)?.iter().cloned());
+        }
+    }
+
+    Ok(Arc::new(inputs.into()))
+}
+
 pub fn find_sources<D, P>(db: &D, dir: P) -> anyhow::Result<Arc<Seq<InternedInput>>>
 where
     D: Parser,
@@ -166,6 +260,10 @@ where
             .unwrap_or(false)
     }
 
+    fn is_beam(path: &Path) -> bool {
+        path.extension().map_or(false, |ext| ext == "beam")
+    }
+
     fn is_valid_entry(entry: &DirEntry) -> bool {
         // Recursively enter subdirectories
         if entry.path().is_dir() {
@@ -179,16 +277,28 @@ where
 
     let walker = WalkDir::new(dir.as_ref()).follow_links(false).into_iter();
 
-    let mut inputs = Vec::new();
+    let mut paths = Vec::new();
 
     for maybe_entry in walker.filter_entry(is_valid_entry) {
         let entry = maybe_entry?;
         if entry.path().is_file() {
-            let input = Input::from(entry.path());
-            let interned = db.intern_input(input);
-            inputs.push(interned);
+            paths.push(entry.path().to_path_buf());
         }
     }
 
+    // Project directories usually contain both the sources and the `.beam` files built from
+    // them, in which case the sources take precedence
+    let sources = paths
+        .iter()
+        .filter(|path| !is_beam(path))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_os_string()))
+        .collect::<HashSet<_>>();
+
+    let inputs = paths
+        .into_iter()
+        .filter(|path| !is_beam(path) || !path.file_stem().map_or(false, |s| sources.contains(s)))
+        .map(|path| db.intern_input(Input::from(path)))
+        .collect::<Vec<_>>();
+
     Ok(Arc::new(inputs.into()))
 }
@@ -16,5 +16,5 @@ pub use self::options::{
     ShowOptionGroupHelp,
 };
 pub use self::output::{calculate_outputs, Emit, OutputType, OutputTypeError, OutputTypes};
-pub use self::project::ProjectType;
+pub use self::project::{consult, App, ConsultError, Project, ProjectType, RebarConfig, Term};
 pub use self::sanitizer::Sanitizer;
@@ -45,6 +45,8 @@ impl Define {
 pub struct Options {
     pub project_name: String,
     pub project_type: ProjectType,
+    /// Set when the input is a directory of OTP applications
+    pub project: Option<Project>,
     pub output_types: OutputTypes,
     pub color: ColorChoice,
     pub warnings_as_errors: bool,
@@ -108,9 +110,17 @@ impl Options {
             }
         }
 
-        let project_name = detect_project_name(args, cwd.as_path(), input_file.as_ref());
+        // Directories of OTP applications are built as a project, in dependency order
+        let project = match input_file {
+            None => Project::load(cwd.as_path())?,
+            Some(FileName::Real(ref path)) if path.is_dir() => Project::load(path)?,
+            Some(_) => None,
+        };
+
+        let project_name =
+            detect_project_name(args, cwd.as_path(), input_file.as_ref(), project.as_ref());
         let project_type_opt: Option<ProjectType> =
-            ParseOption::parse_option(&option!("project-type"), &args)?;
+            ParseOption::parse_option(&option!("output-type"), &args)?;
         let project_type = project_type_opt.unwrap_or(ProjectType::Executable);
         let output_types = OutputTypes::parse_option(&option!("emit"), &args)?;
 
@@ -222,6 +232,7 @@ impl Options {
         Ok(Self {
             project_name,
             project_type,
+            project,
             output_types,
             color: color_arg.into(),
             warnings_as_errors,
@@ -300,6 +311,7 @@ impl Options {
         Ok(Self {
             project_name,
             project_type: ProjectType::Executable,
+            project: None,
             output_types: OutputTypes::default(),
             color: ColorChoice::Auto,
             warnings_as_errors: false,
@@ -423,11 +435,20 @@ impl Options {
     }
 }
 
-fn detect_project_name<'a>(args: &ArgMatches<'a>, cwd: &Path, input: Option<&FileName>) -> String {
+fn detect_project_name<'a>(
+    args: &ArgMatches<'a>,
+    cwd: &Path,
+    input: Option<&FileName>,
+    project: Option<&Project>,
+) -> String {
     // If explicitly set, use the provided name
     if let Some(name) = args.value_of("name") {
         return name.to_owned();
     }
+    // If building an application, name the project after it
+    if let Some(app) = project.and_then(|project| project.main_app()) {
+        return app.name.clone();
+    }
     match input {
         // If we have a single input file, name the project after it
         Some(FileName::Real(ref path)) if path.exists() && path.is_file() => path
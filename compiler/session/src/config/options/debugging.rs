@@ -94,4 +94,7 @@ pub struct DebuggingOptions {
     #[option(hidden(true))]
     /// Emit a section containing stack size metadata
     pub emit_stack_sizes: bool,
+    #[option]
+    /// Print which modules were reused from the incremental compilation cache
+    pub incremental_info: bool,
 }
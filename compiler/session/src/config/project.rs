@@ -1,6 +1,19 @@
+mod app;
+mod rebar;
+mod terms;
+
+pub use self::app::App;
+pub use self::rebar::{Dependency, RebarConfig};
+pub use self::terms::{consult, ConsultError, Term};
+
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use anyhow::anyhow;
+
 use clap::ArgMatches;
 
 use crate::config::options::{invalid_value, required_option_missing};
@@ -59,3 +72,185 @@ impl ParseOption for ProjectType {
         }
     }
 }
+
+/// A project made up of OTP applications, as described by `rebar.config` and `.app.src` files
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: PathBuf,
+    /// The applications of the project and their dependencies, ordered so that each
+    /// application comes after the applications it depends on
+    pub apps: Vec<App>,
+}
+impl Project {
+    /// Loads the project rooted at `root`, or returns `None` if it contains no applications
+    pub fn load(root: &Path) -> anyhow::Result<Option<Self>> {
+        let config = RebarConfig::load(root)?;
+
+        let mut apps: Vec<App> = Vec::new();
+        let mut deps = config.deps.iter().cloned().collect::<VecDeque<_>>();
+        for pattern in config.project_app_dirs.iter() {
+            for dir in expand_app_dirs(root, pattern)? {
+                // Applications in an umbrella project inherit the options of the project
+                let app_config = if dir == root {
+                    config.clone()
+                } else {
+                    let mut app_config = RebarConfig::load(&dir)?;
+                    let mut erl_opts = config.erl_opts.clone();
+                    erl_opts.append(&mut app_config.erl_opts);
+                    app_config.erl_opts = erl_opts;
+                    app_config
+                };
+                if let Some(app) = App::load(&dir, &app_config)? {
+                    if apps.iter().all(|existing| existing.name != app.name) {
+                        deps.extend(app_config.deps.iter().cloned());
+                        apps.push(app);
+                    }
+                }
+            }
+        }
+        if apps.is_empty() {
+            return Ok(None);
+        }
+
+        // Dependencies use their own options, and may have dependencies of their own
+        let mut seen = apps
+            .iter()
+            .map(|app| app.name.clone())
+            .collect::<HashSet<_>>();
+        while let Some(dep) = deps.pop_front() {
+            if !seen.insert(dep.name.clone()) {
+                continue;
+            }
+            let dir = find_dependency(root, &dep)?;
+            let dep_config = RebarConfig::load(&dir)?;
+            let app = App::load(&dir, &dep_config)?.ok_or_else(|| {
+                anyhow!(
+                    "dependency `{}` in {} is not an OTP application",
+                    dep.name,
+                    dir.display()
+                )
+            })?;
+            deps.extend(dep_config.deps.iter().cloned());
+            apps.push(app);
+        }
+
+        Ok(Some(Self {
+            root: root.to_owned(),
+            apps: sort_by_dependencies(apps)?,
+        }))
+    }
+
+    /// The application at the root of the project, if it is not an umbrella project
+    pub fn main_app(&self) -> Option<&App> {
+        self.apps.iter().find(|app| app.root == self.root)
+    }
+
+    /// Returns the application whose sources contain `path`
+    pub fn app_for(&self, path: &Path) -> Option<&App> {
+        // Applications nested in the project root are more specific than the root itself
+        self.apps
+            .iter()
+            .filter(|app| app.contains(path))
+            .max_by_key(|app| app.root.components().count())
+    }
+}
+
+/// Expands a `project_app_dirs` pattern, where a trailing `*` matches any directory
+fn expand_app_dirs(root: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    if pattern == "." {
+        return Ok(vec![root.to_owned()]);
+    }
+    if !pattern.ends_with("/*") {
+        return Ok(vec![root.join(pattern)]);
+    }
+
+    let parent = root.join(&pattern[..pattern.len() - 2]);
+    if !parent.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(&parent)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    // Directory order is unspecified, so sort for reproducible builds
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Finds the source of a dependency, which rebar will have fetched into the project
+fn find_dependency(root: &Path, dep: &Dependency) -> anyhow::Result<PathBuf> {
+    if let Some(ref path) = dep.path {
+        return Ok(path.clone());
+    }
+    let candidates = [
+        root.join("_checkouts").join(&dep.name),
+        root.join("_build")
+            .join("default")
+            .join("lib")
+            .join(&dep.name),
+        root.join("deps").join(&dep.name),
+    ];
+    candidates
+        .iter()
+        .find(|dir| dir.is_dir())
+        .cloned()
+        .ok_or_else(|| {
+            anyhow!(
+                "unable to find dependency `{}`, fetch it with `rebar3 get-deps`",
+                dep.name
+            )
+        })
+}
+
+fn sort_by_dependencies(apps: Vec<App>) -> anyhow::Result<Vec<App>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Visiting,
+        Visited,
+    }
+
+    fn visit(
+        i: usize,
+        apps: &[App],
+        indices: &HashMap<&str, usize>,
+        states: &mut [State],
+        order: &mut Vec<usize>,
+    ) -> anyhow::Result<()> {
+        match states[i] {
+            State::Visited => return Ok(()),
+            State::Visiting => {
+                return Err(anyhow!("application `{}` depends on itself", apps[i].name))
+            }
+            State::Unvisited => (),
+        }
+        states[i] = State::Visiting;
+        // Applications outside of the project, like `kernel`, are provided by the runtime
+        let deps = apps[i].applications.iter().chain(apps[i].deps.iter());
+        for dep in deps {
+            if let Some(&j) = indices.get(dep.as_str()) {
+                visit(j, apps, indices, states, order)?;
+            }
+        }
+        states[i] = State::Visited;
+        order.push(i);
+        Ok(())
+    }
+
+    let indices = apps
+        .iter()
+        .enumerate()
+        .map(|(i, app)| (app.name.as_str(), i))
+        .collect::<HashMap<_, _>>();
+    let mut states = vec![State::Unvisited; apps.len()];
+    let mut order = Vec::with_capacity(apps.len());
+    for i in 0..apps.len() {
+        visit(i, &apps, &indices, &mut states, &mut order)?;
+    }
+
+    let mut apps = apps.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(order.into_iter().map(|i| apps[i].take().unwrap()).collect())
+}
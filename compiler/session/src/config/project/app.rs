@@ -0,0 +1,138 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use super::rebar::RebarConfig;
+use super::terms::{self, Term};
+
+/// An OTP application, as described by its `.app.src` file
+#[derive(Debug, Clone)]
+pub struct App {
+    pub name: String,
+    pub vsn: Option<String>,
+    /// The application callback module and its start arguments, from `{mod, {Module, Args}}`
+    pub module: Option<(String, Term)>,
+    /// The default configuration returned by `application:get_env/2`
+    pub env: Vec<(String, Term)>,
+    /// The applications which must be started before this one
+    pub applications: Vec<String>,
+    /// The root directory of the application
+    pub root: PathBuf,
+    pub src_dirs: Vec<PathBuf>,
+    pub include_path: VecDeque<PathBuf>,
+    pub defines: HashMap<String, Option<String>>,
+    /// The names of the dependencies listed in the `rebar.config` of the application
+    pub(super) deps: Vec<String>,
+}
+impl App {
+    /// Reads the application rooted at `root`, or returns `None` if it has no `.app.src` file.
+    ///
+    /// The `erl_opts` of `config` apply to the sources of the application.
+    pub fn load(root: &Path, config: &RebarConfig) -> anyhow::Result<Option<Self>> {
+        let src_dirs = config
+            .src_dirs()
+            .into_iter()
+            .map(|dir| root.join(dir))
+            .collect::<Vec<_>>();
+        let app_src = match find_app_src(&src_dirs)? {
+            None => return Ok(None),
+            Some(path) => path,
+        };
+
+        let source = fs::read_to_string(&app_src)
+            .with_context(|| format!("unable to read {}", app_src.display()))?;
+        let terms =
+            terms::consult(&source).with_context(|| format!("invalid {}", app_src.display()))?;
+        let invalid = |what: &str| anyhow!("invalid {}: {}", app_src.display(), what);
+
+        let (name, properties) = match terms.as_slice() {
+            [Term::Tuple(ref spec)] => match spec.as_slice() {
+                [Term::Atom(ref tag), Term::Atom(ref name), properties] if tag == "application" => {
+                    (name.clone(), properties)
+                }
+                _ => return Err(invalid("expected {application, Name, Properties}")),
+            },
+            _ => return Err(invalid("expected a single application specification")),
+        };
+        if properties.as_list().is_none() {
+            return Err(invalid("application properties must be a list"));
+        }
+
+        // A version of `git` or `semver` is resolved by rebar from version control
+        let vsn = properties.get("vsn").map(|vsn| match vsn.as_text() {
+            Some(text) => text.to_owned(),
+            None => vsn.to_string(),
+        });
+        let module = match properties.get("mod") {
+            None => None,
+            Some(Term::Tuple(ref callback)) => match callback.as_slice() {
+                [Term::Atom(ref module), args] => Some((module.clone(), args.clone())),
+                _ => return Err(invalid("mod must be {Module, StartArgs}")),
+            },
+            Some(_) => return Err(invalid("mod must be {Module, StartArgs}")),
+        };
+        let mut env = Vec::new();
+        if let Some(entries) = properties.get("env") {
+            for entry in entries
+                .as_list()
+                .ok_or_else(|| invalid("env must be a list"))?
+            {
+                match entry.as_tuple() {
+                    Some([Term::Atom(ref key), value]) => env.push((key.clone(), value.clone())),
+                    _ => return Err(invalid("env must be a list of {Key, Value} tuples")),
+                }
+            }
+        }
+        let applications = match properties.get("applications") {
+            None => Vec::new(),
+            Some(apps) => apps
+                .as_list()
+                .and_then(|apps| {
+                    apps.iter()
+                        .map(|app| app.as_atom().map(|app| app.to_owned()))
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| invalid("applications must be a list of atoms"))?,
+        };
+
+        let mut include_path = VecDeque::new();
+        include_path.push_back(root.join("include"));
+        include_path.extend(config.include_dirs(root));
+
+        Ok(Some(Self {
+            name,
+            vsn,
+            module,
+            env,
+            applications,
+            root: root.to_owned(),
+            src_dirs,
+            include_path,
+            defines: config.defines(),
+            deps: config.deps.iter().map(|dep| dep.name.clone()).collect(),
+        }))
+    }
+
+    /// Returns `true` if `path` is in one of the source directories of this application
+    pub fn contains(&self, path: &Path) -> bool {
+        self.src_dirs.iter().any(|dir| path.starts_with(dir))
+    }
+}
+
+fn find_app_src(src_dirs: &[PathBuf]) -> anyhow::Result<Option<PathBuf>> {
+    for dir in src_dirs.iter().filter(|dir| dir.is_dir()) {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_app_src = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.ends_with(".app.src"));
+            if is_app_src {
+                return Ok(Some(path));
+            }
+        }
+    }
+    Ok(None)
+}
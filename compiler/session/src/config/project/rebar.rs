@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use super::terms::{self, Term};
+
+/// The settings read from a `rebar.config` file
+#[derive(Debug, Clone, Default)]
+pub struct RebarConfig {
+    /// The directory containing the configuration file
+    pub dir: PathBuf,
+    pub erl_opts: Vec<Term>,
+    pub deps: Vec<Dependency>,
+    /// Patterns for the directories of the applications in the project, i.e. `apps/*`
+    pub project_app_dirs: Vec<String>,
+}
+impl RebarConfig {
+    /// Reads `rebar.config` from `dir`, or returns the defaults if there is none
+    pub fn load(dir: &Path) -> anyhow::Result<Self> {
+        let path = dir.join("rebar.config");
+        let mut config = Self {
+            dir: dir.to_owned(),
+            ..Self::default()
+        };
+        if !path.exists() {
+            config.project_app_dirs = default_project_app_dirs();
+            return Ok(config);
+        }
+
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("unable to read {}", path.display()))?;
+        let terms =
+            terms::consult(&source).with_context(|| format!("invalid {}", path.display()))?;
+        let settings = Term::List(terms);
+
+        if let Some(erl_opts) = settings.get("erl_opts") {
+            config.erl_opts = erl_opts
+                .as_list()
+                .ok_or_else(|| anyhow!("invalid {}: erl_opts must be a list", path.display()))?
+                .to_vec();
+        }
+        // Older configurations list source directories at the top level
+        if let Some(src_dirs) = settings.get("src_dirs") {
+            config.erl_opts.push(Term::Tuple(vec![
+                Term::Atom("src_dirs".to_owned()),
+                src_dirs.clone(),
+            ]));
+        }
+        if let Some(deps) = settings.get("deps") {
+            let deps = deps
+                .as_list()
+                .ok_or_else(|| anyhow!("invalid {}: deps must be a list", path.display()))?;
+            for dep in deps {
+                config.deps.push(
+                    Dependency::from_term(dep, dir).ok_or_else(|| {
+                        anyhow!("invalid dependency in {}: {}", path.display(), dep)
+                    })?,
+                );
+            }
+        }
+        config.project_app_dirs = match settings.get("project_app_dirs") {
+            None => default_project_app_dirs(),
+            Some(dirs) => text_list(dirs).ok_or_else(|| {
+                anyhow!(
+                    "invalid {}: project_app_dirs must be a list of strings",
+                    path.display()
+                )
+            })?,
+        };
+
+        Ok(config)
+    }
+
+    /// The source directories of an application, relative to its root
+    pub fn src_dirs(&self) -> Vec<PathBuf> {
+        let dirs = self
+            .erl_opts
+            .iter()
+            .filter_map(|opt| option_value(opt, "src_dirs"))
+            .filter_map(text_list)
+            .last();
+        dirs.unwrap_or_else(|| vec!["src".to_owned()])
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    /// The include directories given by `{i, Dir}` options, relative to `root`
+    pub fn include_dirs(&self, root: &Path) -> Vec<PathBuf> {
+        self.erl_opts
+            .iter()
+            .filter_map(|opt| option_value(opt, "i"))
+            .filter_map(|dir| dir.as_text())
+            .map(|dir| root.join(dir))
+            .collect()
+    }
+
+    /// The macros defined by `{d, Name}` and `{d, Name, Value}` options
+    pub fn defines(&self) -> HashMap<String, Option<String>> {
+        let mut defines = HashMap::new();
+        for opt in self.erl_opts.iter() {
+            match opt.as_tuple() {
+                Some([Term::Atom(ref d), name]) if d == "d" => {
+                    if let Some(name) = name.as_atom() {
+                        defines.insert(name.to_owned(), None);
+                    }
+                }
+                Some([Term::Atom(ref d), name, value]) if d == "d" => {
+                    if let Some(name) = name.as_atom() {
+                        defines.insert(name.to_owned(), Some(value.to_string()));
+                    }
+                }
+                _ => (),
+            }
+        }
+        defines
+    }
+}
+
+/// A dependency listed in the `deps` of a `rebar.config`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub name: String,
+    /// Set for dependencies given as `{Name, {path, Dir}}`
+    pub path: Option<PathBuf>,
+}
+impl Dependency {
+    fn from_term(term: &Term, dir: &Path) -> Option<Self> {
+        if let Some(name) = term.as_atom() {
+            return Some(Self {
+                name: name.to_owned(),
+                path: None,
+            });
+        }
+        let elements = term.as_tuple()?;
+        let name = elements.first()?.as_atom()?.to_owned();
+        let path = elements
+            .iter()
+            .skip(1)
+            .filter_map(|source| option_value(source, "path"))
+            .filter_map(|path| path.as_text())
+            .map(|path| dir.join(path))
+            .next();
+        Some(Self { name, path })
+    }
+}
+
+fn default_project_app_dirs() -> Vec<String> {
+    vec!["apps/*".to_owned(), "lib/*".to_owned(), ".".to_owned()]
+}
+
+/// Returns `Value` if `term` is `{Key, Value}`
+fn option_value<'a>(term: &'a Term, key: &str) -> Option<&'a Term> {
+    match term.as_tuple()? {
+        [k, value] if k.as_atom() == Some(key) => Some(value),
+        _ => None,
+    }
+}
+
+fn text_list(term: &Term) -> Option<Vec<String>> {
+    term.as_list()?
+        .iter()
+        .map(|element| element.as_text().map(|s| s.to_owned()))
+        .collect()
+}
@@ -0,0 +1,521 @@
+use std::fmt::{self, Write};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use thiserror::Error;
+
+/// An Erlang term, as written in configuration files such as `rebar.config`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Atom(String),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Binary(Vec<u8>),
+    List(Vec<Term>),
+    Tuple(Vec<Term>),
+    Map(Vec<(Term, Term)>),
+}
+impl Term {
+    pub fn as_atom(&self) -> Option<&str> {
+        match self {
+            Self::Atom(ref name) => Some(name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the text of a string, binary or atom
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Atom(ref s) | Self::String(ref s) => Some(s.as_str()),
+            Self::Binary(ref bytes) => std::str::from_utf8(bytes).ok(),
+            // The empty string and the empty list are the same term
+            Self::List(ref elements) if elements.is_empty() => Some(""),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Term]> {
+        match self {
+            Self::List(ref elements) => Some(elements.as_slice()),
+            Self::String(ref s) if s.is_empty() => Some(&[]),
+            _ => None,
+        }
+    }
+
+    pub fn as_tuple(&self) -> Option<&[Term]> {
+        match self {
+            Self::Tuple(ref elements) => Some(elements.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Looks up `key` in a list of `{Key, Value}` tuples
+    pub fn get(&self, key: &str) -> Option<&Term> {
+        self.as_list()?
+            .iter()
+            .filter_map(|element| element.as_tuple())
+            .find(|tuple| tuple.len() == 2 && tuple[0].as_atom() == Some(key))
+            .map(|tuple| &tuple[1])
+    }
+}
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Atom(ref name) => write_atom(f, name),
+            Self::Integer(i) => write!(f, "{}", i),
+            Self::Float(n) if n.fract() == 0.0 && n.is_finite() => write!(f, "{:.1}", n),
+            Self::Float(n) => write!(f, "{}", n),
+            Self::String(ref s) => write_quoted(f, s, '"'),
+            Self::Binary(ref bytes) => match std::str::from_utf8(bytes) {
+                Ok(s) => {
+                    f.write_str("<<")?;
+                    write_quoted(f, s, '"')?;
+                    f.write_str("/utf8>>")
+                }
+                Err(_) => {
+                    f.write_str("<<")?;
+                    write_separated(f, bytes.iter())?;
+                    f.write_str(">>")
+                }
+            },
+            Self::List(ref elements) => {
+                f.write_char('[')?;
+                write_separated(f, elements.iter())?;
+                f.write_char(']')
+            }
+            Self::Tuple(ref elements) => {
+                f.write_char('{')?;
+                write_separated(f, elements.iter())?;
+                f.write_char('}')
+            }
+            Self::Map(ref entries) => {
+                f.write_str("#{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{} => {}", key, value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_separated<T: fmt::Display, I: Iterator<Item = T>>(
+    f: &mut fmt::Formatter,
+    items: I,
+) -> fmt::Result {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn write_atom(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    let mut chars = name.chars();
+    let is_bare = chars.next().map_or(false, |c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@');
+    if is_bare {
+        f.write_str(name)
+    } else {
+        write_quoted(f, name, '\'')
+    }
+}
+
+fn write_quoted(f: &mut fmt::Formatter, s: &str, quote: char) -> fmt::Result {
+    f.write_char(quote)?;
+    for c in s.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            c if c == quote => {
+                f.write_char('\\')?;
+                f.write_char(c)?;
+            }
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char(quote)
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ConsultError {
+    #[error("unexpected end of input on line {0}")]
+    UnexpectedEof(usize),
+    #[error("unexpected character `{1}` on line {0}")]
+    UnexpectedChar(usize, char),
+    #[error("invalid {1} on line {0}")]
+    Invalid(usize, &'static str),
+    #[error("variables are not allowed in terms, found `{1}` on line {0}")]
+    Variable(usize, String),
+}
+
+/// Reads the terms of a file in the format accepted by `file:consult/1`, where each term
+/// is terminated by a full stop
+pub fn consult(source: &str) -> Result<Vec<Term>, ConsultError> {
+    let mut reader = Reader {
+        source,
+        chars: source.char_indices().peekable(),
+        line: 1,
+    };
+    let mut terms = Vec::new();
+    loop {
+        reader.skip_whitespace();
+        if reader.peek().is_none() {
+            return Ok(terms);
+        }
+        terms.push(reader.term()?);
+        reader.expect('.')?;
+    }
+}
+
+struct Reader<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    line: usize,
+}
+impl<'a> Reader<'a> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next().map(|(_, c)| c)
+    }
+
+    fn next(&mut self) -> Result<char, ConsultError> {
+        match self.chars.next() {
+            Some((_, c)) => {
+                if c == '\n' {
+                    self.line += 1;
+                }
+                Ok(c)
+            }
+            None => Err(ConsultError::UnexpectedEof(self.line)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '%' {
+                while self.peek().map_or(false, |c| c != '\n') {
+                    self.chars.next();
+                }
+            } else if c.is_whitespace() {
+                self.next().unwrap();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ConsultError> {
+        self.skip_whitespace();
+        match self.next()? {
+            c if c == expected => Ok(()),
+            c => Err(ConsultError::UnexpectedChar(self.line, c)),
+        }
+    }
+
+    /// Consumes `expected` if it is the next character, ignoring whitespace
+    fn accept(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.next().unwrap();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, ConsultError> {
+        self.skip_whitespace();
+        let c = self.peek().ok_or(ConsultError::UnexpectedEof(self.line))?;
+        match c {
+            '[' => {
+                self.next()?;
+                self.list()
+            }
+            '{' => {
+                self.next()?;
+                let elements = self.sequence('}')?;
+                Ok(Term::Tuple(elements))
+            }
+            '#' => {
+                self.next()?;
+                self.expect('{')?;
+                self.map()
+            }
+            '<' if self.peek_second() == Some('<') => {
+                self.next()?;
+                self.next()?;
+                self.binary()
+            }
+            '"' => {
+                let mut s = self.quoted('"')?;
+                // Adjacent string literals are concatenated
+                while self.accept_string_start() {
+                    s.push_str(&self.quoted('"')?);
+                }
+                Ok(Term::String(s))
+            }
+            '\'' => Ok(Term::Atom(self.quoted('\'')?)),
+            '$' => {
+                self.next()?;
+                let c = match self.next()? {
+                    '\\' => self.escape()?,
+                    c => c,
+                };
+                Ok(Term::Integer(c as i64))
+            }
+            '-' | '+' => {
+                self.next()?;
+                match self.number()? {
+                    Term::Integer(i) if c == '-' => Ok(Term::Integer(-i)),
+                    Term::Float(n) if c == '-' => Ok(Term::Float(-n)),
+                    number => Ok(number),
+                }
+            }
+            c if c.is_ascii_digit() => self.number(),
+            c if c.is_lowercase() => {
+                let name = self.name();
+                Ok(Term::Atom(name))
+            }
+            c if c.is_uppercase() || c == '_' => {
+                let name = self.name();
+                Err(ConsultError::Variable(self.line, name))
+            }
+            c => Err(ConsultError::UnexpectedChar(self.line, c)),
+        }
+    }
+
+    fn accept_string_start(&mut self) -> bool {
+        self.skip_whitespace();
+        self.peek() == Some('"')
+    }
+
+    /// Parses the comma-separated terms of a tuple or list, up to `end`
+    fn sequence(&mut self, end: char) -> Result<Vec<Term>, ConsultError> {
+        let mut elements = Vec::new();
+        if self.accept(end) {
+            return Ok(elements);
+        }
+        loop {
+            elements.push(self.term()?);
+            if !self.accept(',') {
+                self.expect(end)?;
+                return Ok(elements);
+            }
+        }
+    }
+
+    fn list(&mut self) -> Result<Term, ConsultError> {
+        let mut elements = Vec::new();
+        if self.accept(']') {
+            return Ok(Term::List(elements));
+        }
+        loop {
+            elements.push(self.term()?);
+            if self.accept('|') {
+                let tail = self.term()?;
+                self.expect(']')?;
+                return match tail.as_list() {
+                    Some(tail) => {
+                        elements.extend_from_slice(tail);
+                        Ok(Term::List(elements))
+                    }
+                    None => Err(ConsultError::Invalid(self.line, "improper list")),
+                };
+            }
+            if !self.accept(',') {
+                self.expect(']')?;
+                return Ok(Term::List(elements));
+            }
+        }
+    }
+
+    fn map(&mut self) -> Result<Term, ConsultError> {
+        let mut entries = Vec::new();
+        if self.accept('}') {
+            return Ok(Term::Map(entries));
+        }
+        loop {
+            let key = self.term()?;
+            self.expect('=')?;
+            if self.next()? != '>' {
+                return Err(ConsultError::Invalid(self.line, "map association"));
+            }
+            entries.push((key, self.term()?));
+            if !self.accept(',') {
+                self.expect('}')?;
+                return Ok(Term::Map(entries));
+            }
+        }
+    }
+
+    fn binary(&mut self) -> Result<Term, ConsultError> {
+        let mut bytes = Vec::new();
+        if self.accept('>') {
+            self.expect('>')?;
+            return Ok(Term::Binary(bytes));
+        }
+        loop {
+            match self.term()? {
+                Term::String(s) => {
+                    if self.accept('/') {
+                        match self.name().as_str() {
+                            "utf8" | "binary" => (),
+                            _ => return Err(ConsultError::Invalid(self.line, "binary segment")),
+                        }
+                    }
+                    bytes.extend_from_slice(s.as_bytes());
+                }
+                Term::Integer(i) if i >= 0 && i <= 255 => bytes.push(i as u8),
+                _ => return Err(ConsultError::Invalid(self.line, "binary segment")),
+            }
+            if !self.accept(',') {
+                self.expect('>')?;
+                self.expect('>')?;
+                return Ok(Term::Binary(bytes));
+            }
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '@' {
+                name.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        name
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String, ConsultError> {
+        self.next()?;
+        let mut s = String::new();
+        loop {
+            match self.next()? {
+                c if c == quote => return Ok(s),
+                '\\' => s.push(self.escape()?),
+                c => s.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ConsultError> {
+        let c = match self.next()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            's' => ' ',
+            'e' => '\x1b',
+            'b' => '\x08',
+            'd' => '\x7f',
+            'f' => '\x0c',
+            'v' => '\x0b',
+            'x' => {
+                let digits = if self.peek() == Some('{') {
+                    self.next()?;
+                    let digits = self.take_while(|c| c.is_ascii_hexdigit());
+                    self.expect('}')?;
+                    digits
+                } else {
+                    let mut digits = String::new();
+                    for _ in 0..2 {
+                        digits.push(self.next()?);
+                    }
+                    digits
+                };
+                return u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or(ConsultError::Invalid(self.line, "escape sequence"));
+            }
+            c if c.is_digit(8) => {
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            self.next()?;
+                            value = value * 8 + digit;
+                        }
+                        None => break,
+                    }
+                }
+                return Ok(std::char::from_u32(value).unwrap());
+            }
+            c => c,
+        };
+        Ok(c)
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> String {
+        let mut taken = String::new();
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            taken.push(c);
+            self.chars.next();
+        }
+        taken
+    }
+
+    fn number(&mut self) -> Result<Term, ConsultError> {
+        let start = self.chars.peek().map_or(self.source.len(), |&(i, _)| i);
+        let digits = self.take_while(|c| c.is_ascii_digit() || c == '_');
+
+        if self.peek() == Some('#') {
+            self.next()?;
+            let radix = digits
+                .parse::<u32>()
+                .ok()
+                .filter(|radix| *radix >= 2 && *radix <= 36)
+                .ok_or(ConsultError::Invalid(self.line, "integer base"))?;
+            let digits = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+            return i64::from_str_radix(&digits.replace('_', ""), radix)
+                .map(Term::Integer)
+                .map_err(|_| ConsultError::Invalid(self.line, "integer"));
+        }
+
+        // A full stop is only part of the number if a digit follows it
+        let is_float =
+            self.peek() == Some('.') && self.peek_second().map_or(false, |c| c.is_ascii_digit());
+        if !is_float {
+            return digits
+                .replace('_', "")
+                .parse()
+                .map(Term::Integer)
+                .map_err(|_| ConsultError::Invalid(self.line, "integer"));
+        }
+
+        self.next()?;
+        self.take_while(|c| c.is_ascii_digit() || c == '_');
+        if self.peek() == Some('e') || self.peek() == Some('E') {
+            self.next()?;
+            if self.peek() == Some('-') || self.peek() == Some('+') {
+                self.next()?;
+            }
+            self.take_while(|c| c.is_ascii_digit());
+        }
+        let end = self.chars.peek().map_or(self.source.len(), |&(i, _)| i);
+        self.source[start..end]
+            .replace('_', "")
+            .parse()
+            .map(Term::Float)
+            .map_err(|_| ConsultError::Invalid(self.line, "float"))
+    }
+}
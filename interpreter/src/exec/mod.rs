@@ -18,8 +18,9 @@ use liblumen_alloc::erts::exception::{Exception, RuntimeException, SystemExcepti
 use liblumen_alloc::erts::process::gc::RootSet;
 use liblumen_alloc::erts::process::{Process, ProcessFlags};
 use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::{Arity, ModuleFunctionArity};
 
-use crate::module::{ErlangFunction, NativeFunctionKind, ResolvedFunction};
+use crate::module::{ErlangFunction, ModuleRegistry, NativeFunctionKind, ResolvedFunction};
 use crate::vm::VMState;
 
 mod r#match;
@@ -207,17 +208,19 @@ impl CallExecutor {
             }
         }
 
+        proc.save_call(ModuleFunctionArity {
+            module,
+            function,
+            arity: arity as Arity,
+        });
+
         match modules.lookup_function(module, function, arity) {
             None => {
-                self.fun_not_found(proc, args[1], module, function, arity);
+                self.undefined_function(vm, proc, &modules, module, function, arity, args);
             }
-            Some(ResolvedFunction::Native(native)) => {
+            Some(resolved) => {
                 assert!(arity + 2 == args.len());
-                self.run_native(vm, proc, native, args);
-            }
-            Some(ResolvedFunction::Erlang(fun)) => {
-                let entry = fun.fun.block_entry();
-                self.run_erlang(vm, proc, fun, entry, args);
+                self.run_resolved(vm, proc, resolved, args);
             }
         }
     }
@@ -250,19 +253,74 @@ impl CallExecutor {
         }
     }
 
+    /// Calls `undefined_function/3` in the process's error handler module, the same as BEAM's
+    /// `error_handler`, so that the error handler can load the module or forward the call.  If the
+    /// error handler does not have `undefined_function/3`, the call fails with `undef`.
+    fn undefined_function(
+        &mut self,
+        vm: &VMState,
+        proc: &Arc<Process>,
+        modules: &ModuleRegistry,
+        module: Atom,
+        function: Atom,
+        arity: usize,
+        mut args: &mut [Term],
+    ) {
+        let error_handler = proc.error_handler();
+        let undefined_function = Atom::from_str("undefined_function");
+
+        match modules.lookup_function(error_handler, undefined_function, 3) {
+            // Calls to the error handler itself can't be handled by the error handler
+            Some(resolved) if module != error_handler => {
+                let mut error_handler_args = try_gc(proc, &mut args, &mut |args| {
+                    let argument_list = proc.list_from_slice(&args[2..])?;
+
+                    Ok(vec![
+                        args[0],
+                        args[1],
+                        module.encode().unwrap(),
+                        function.encode().unwrap(),
+                        argument_list,
+                    ])
+                });
+
+                self.run_resolved(vm, proc, resolved, &mut error_handler_args);
+            }
+            _ => self.fun_not_found(proc, args[1], module, function, arity),
+        }
+    }
+
     fn fun_not_found(
         &self,
-        _proc: &Arc<Process>,
-        _throw_cont: Term,
+        proc: &Arc<Process>,
+        throw_cont: Term,
         module: Atom,
         function: Atom,
         arity: usize,
-    ) -> Term {
-        panic!("Undef: {} {} {}", module, function, arity);
-        //let exit_atom = Atom::str_to_term("EXIT");
-        //let undef_atom = Atom::str_to_term("undef");
-        //let trace_atom = Atom::str_to_term("trace");
-        //self.call_closure(proc, throw_cont, &[exit_atom, undef_atom, trace_atom])
+    ) {
+        trace!("Undef: {} {} {}", module, function, arity);
+
+        call_closure(
+            proc,
+            throw_cont,
+            &mut [atom!("error"), atom!("undef"), atom!("trace")],
+        );
+    }
+
+    fn run_resolved(
+        &mut self,
+        vm: &VMState,
+        proc: &Arc<Process>,
+        resolved: ResolvedFunction,
+        args: &mut [Term],
+    ) {
+        match resolved {
+            ResolvedFunction::Native(native) => self.run_native(vm, proc, native, args),
+            ResolvedFunction::Erlang(fun) => {
+                let entry = fun.fun.block_entry();
+                self.run_erlang(vm, proc, fun, entry, args);
+            }
+        }
     }
 
     fn run_native(
@@ -640,6 +698,7 @@ impl CallExecutor {
                 // Only infinity supported
                 assert!(timeout == Atom::str_to_term("infinity"));
 
+                proc.take_off_heap_messages();
                 proc.mailbox.lock().borrow_mut().recv_start();
 
                 self.next_args.push(Term::NIL);
@@ -650,6 +709,7 @@ impl CallExecutor {
 
                 let curr_cont = self.make_closure(proc, fun, block)?;
 
+                proc.take_off_heap_messages();
                 let mailbox_lock = proc.mailbox.lock();
                 let mut mailbox = mailbox_lock.borrow_mut();
                 if let Some(msg_term) = mailbox.recv_peek() {
@@ -674,18 +734,19 @@ impl CallExecutor {
                 let mailbox_lock = proc.mailbox.lock();
                 let mut mailbox = mailbox_lock.borrow_mut();
 
+                for n in 0..(reads.len() - 1) {
+                    let term = self.make_term(proc, fun, reads[n + 1]).unwrap();
+                    self.next_args.push(term);
+                }
+
                 if mailbox.recv_last_off_heap() {
-                    // Copy to process heap
-                    unimplemented!()
+                    // The terms are still in the message's heap fragment, which the next garbage
+                    // collection moves onto the process heap
+                    mailbox.recv_finish_in_place(proc);
                 } else {
-                    for n in 0..(reads.len() - 1) {
-                        let term = self.make_term(proc, fun, reads[n + 1]).unwrap();
-                        self.next_args.push(term);
-                    }
+                    mailbox.recv_finish(proc);
                 }
 
-                mailbox.recv_finish(proc);
-
                 self.val_call(proc, fun, reads[0])
             }
             OpKind::BinaryPush {
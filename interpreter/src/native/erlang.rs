@@ -199,6 +199,11 @@ pub fn make_erlang() -> NativeModule {
         erlang::whereis_1::result(args[0])
     });
 
+    native.add_simple(
+        Atom::try_from_str("process_info").unwrap(),
+        1,
+        |proc, args| erlang::process_info_1::result(proc, args[0]),
+    );
     native.add_simple(
         Atom::try_from_str("process_info").unwrap(),
         2,
@@ -1,6 +1,9 @@
 mod erlang;
 pub use erlang::make_erlang;
 
+mod ets;
+pub use ets::make_ets;
+
 mod lists;
 pub use lists::make_lists;
 
@@ -26,6 +26,7 @@ impl VMState {
 
         let mut modules = ModuleRegistry::new();
         modules.register_native_module(crate::native::make_erlang());
+        modules.register_native_module(crate::native::make_ets());
         modules.register_native_module(crate::native::make_lists());
         modules.register_native_module(crate::native::make_maps());
         modules.register_native_module(crate::native::make_logger());
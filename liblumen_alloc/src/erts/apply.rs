@@ -61,6 +61,15 @@ pub fn find_symbol(mfa: &ModuleFunctionArity) -> Option<DynamicCallee> {
     }
 }
 
+/// The functions compiled into the executable, which is none when the dispatch table has not
+/// been initialized
+pub fn module_function_arities() -> Vec<ModuleFunctionArity> {
+    match SYMBOLS.get() {
+        Some(symbols) => symbols.functions.keys().map(|mfa| **mfa).collect(),
+        None => Vec::new(),
+    }
+}
+
 pub fn dump_symbols() {
     let symbols = unsafe { SYMBOLS.get_unchecked() };
     symbols.dump();
@@ -1,6 +1,8 @@
 use core::mem;
 use core::ptr::{self, NonNull};
 
+use alloc::vec::Vec;
+
 use intrusive_collections::intrusive_adapter;
 use intrusive_collections::{LinkedListLink, UnsafeRef};
 
@@ -45,6 +47,8 @@ pub struct HeapFragment {
     raw: RawFragment,
     // The amount of used memory in this fragment
     top: *mut u8,
+    // The `ProcBin`s cloned into this fragment, each holding a reference to shared binary data
+    procbins: Vec<NonNull<ProcBin>>,
 }
 impl HeapFragment {
     /// Returns the pointer to the data region of this fragment
@@ -77,6 +81,7 @@ impl HeapFragment {
                         base: NonNull::new_unchecked(data),
                     },
                     top,
+                    procbins: Vec::new(),
                 },
             );
         }
@@ -95,6 +100,15 @@ impl HeapFragment {
 impl Drop for HeapFragment {
     fn drop(&mut self) {
         assert!(!self.link.is_linked());
+        // Release the references held by the `ProcBin`s that garbage collection did not move out,
+        // which left a move marker in place of their header
+        for procbin in self.procbins.drain(..) {
+            let header = unsafe { *(procbin.as_ptr() as *const Term) };
+
+            if header.is_header() {
+                unsafe { ptr::drop_in_place(procbin.as_ptr()) };
+            }
+        }
         // Check if the contained value needs to have its destructor run
         let ptr = self.raw.base.as_ptr() as *mut Term;
         let term = unsafe { *ptr };
@@ -127,6 +141,11 @@ impl Heap for HeapFragment {
     fn heap_end(&self) -> *mut Term {
         unsafe { self.raw.base.as_ptr().add(self.raw.size) as *mut Term }
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.procbins.push(bin.into());
+    }
 }
 impl HeapAlloc for HeapFragment {
     unsafe fn alloc_layout(&mut self, layout: Layout) -> AllocResult<NonNull<Term>> {
@@ -1,6 +1,7 @@
 use core::cell::Cell;
 use core::cmp::{self, Ord, PartialEq, PartialOrd};
 use core::hash::{Hash, Hasher};
+use core::sync::atomic::{AtomicU32, Ordering};
 
 use liblumen_core::locks::Mutex;
 
@@ -10,7 +11,7 @@ use crate::erts::term::prelude::Atom;
 pub struct Node {
     id: usize,
     name: Mutex<Cell<Atom>>,
-    creation: u32,
+    creation: AtomicU32,
 }
 
 impl Node {
@@ -18,12 +19,12 @@ impl Node {
         Self {
             id,
             name: Mutex::new(Cell::new(name)),
-            creation,
+            creation: AtomicU32::new(creation),
         }
     }
 
     pub fn creation(&self) -> u32 {
-        self.creation
+        self.creation.load(Ordering::SeqCst)
     }
 
     pub fn id(&self) -> usize {
@@ -33,6 +34,13 @@ impl Node {
     pub fn name(&self) -> Atom {
         self.name.lock().get()
     }
+
+    /// Renames the node and changes its creation, such as when the local node goes from dead to
+    /// alive and gets its creation from EPMD.
+    pub fn set_name_and_creation(&self, name: Atom, creation: u32) {
+        self.name.lock().set(name);
+        self.creation.store(creation, Ordering::SeqCst);
+    }
 }
 
 impl Eq for Node {}
@@ -7,8 +7,11 @@ mod frames;
 pub mod gc;
 mod heap;
 mod mailbox;
+mod message_queue;
 mod monitor;
 pub mod priority;
+mod saved_calls;
+pub mod trace;
 
 use core::cell::RefCell;
 use core::convert::TryInto;
@@ -53,8 +56,11 @@ use self::gc::{GcError, RootSet};
 pub use self::flags::*;
 pub use self::heap::ProcessHeap;
 pub use self::mailbox::*;
+pub use self::message_queue::OffHeapMessageQueue;
 pub use self::monitor::Monitor;
 pub use self::priority::Priority;
+pub use self::saved_calls::SavedCalls;
+use self::trace::CallTrace;
 
 // 4000 in [BEAM](https://github.com/erlang/otp/blob/61ebe71042fce734a06382054690d240ab027409/erts/emulator/beam/erl_vm.h#L39)
 cfg_if::cfg_if! {
@@ -65,6 +71,20 @@ cfg_if::cfg_if! {
   }
 }
 
+/// The maximum number of minor collections before a full sweep, unless the process is spawned with
+/// `fullsweep_after`.  The same as BEAM.
+pub const DEFAULT_FULLSWEEP_AFTER: usize = 65535;
+
+/// The reductions of processes that have been dropped, so that the total across all processes
+/// never goes down when a process exits
+static DROPPED_REDUCTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// The reductions of all processes that have been dropped.  Add the `reductions` of the processes
+/// that are still alive to get the total since the runtime started.
+pub fn dropped_reductions() -> u64 {
+    DROPPED_REDUCTIONS.load(Ordering::SeqCst)
+}
+
 #[derive(Clone, Debug, Default)]
 #[repr(C)]
 pub struct CalleeSavedRegisters {
@@ -93,25 +113,31 @@ unsafe impl Sync for CalleeSavedRegisters {}
 pub struct Process {
     /// ID of the scheduler that is running the process
     scheduler_id: Mutex<Option<scheduler::ID>>,
-    /// The priority of the process in `scheduler`.
-    pub priority: Priority,
+    /// The priority of the process in `scheduler`.  The scheduler must move the process to the
+    /// run queue for the new priority when it changes.
+    pub priority: RwLock<Priority>,
     /// Process flags, e.g. `Process.flag/1`
     flags: AtomicProcessFlags,
     /// Minimum size of the heap that this process will start with
-    min_heap_size: usize,
+    min_heap_size: AtomicUsize,
     /// The maximum size of the heap allowed for this process
-    max_heap_size: usize,
+    max_heap_size: AtomicUsize,
     /// Minimum virtual heap size for this process
-    min_vheap_size: usize,
+    min_vheap_size: AtomicUsize,
     /// The percentage of used to unused space at which a collection is triggered
     gc_threshold: f64,
     /// The maximum number of minor collections before a full sweep occurs
-    max_gen_gcs: usize,
+    max_gen_gcs: AtomicUsize,
     /// off-heap allocations
     off_heap: SpinLock<LinkedList<HeapFragmentAdapter>>,
     off_heap_size: AtomicUsize,
     /// Process dictionary
     dictionary: DashMap<Term, Term>,
+    /// The module whose `undefined_function/3` is called when this process calls an undefined
+    /// function
+    error_handler: RwLock<Atom>,
+    /// The most recent calls when `save_calls` is set with `process_flag/2`
+    saved_calls: Mutex<SavedCalls>,
     /// The `pid` of the process that `spawn`ed this process.
     parent_pid: Option<Pid>,
     /// The `pid` of the process that does I/O on this process's behalf.
@@ -134,6 +160,8 @@ pub struct Process {
     /// Maps monitor references to the PID of the process being monitored by this process.
     pub monitored_pid_by_reference: DashMap<Reference, Pid>,
     pub mailbox: Mutex<RefCell<Mailbox>>,
+    /// Messages sent while `message_queue_data` is `off_heap`, not yet taken into `mailbox`
+    off_heap_message_queue: OffHeapMessageQueue,
     pub registers: Mutex<CalleeSavedRegisters>,
     pub stack: Mutex<alloc::Stack>,
     // process heap, cache line aligned to avoid false sharing with rest of struct
@@ -163,24 +191,31 @@ impl Process {
         };
 
         Self {
-            flags: AtomicProcessFlags::new(ProcessFlags::Default),
-            min_heap_size: heap_size,
-            max_heap_size: 0,
-            min_vheap_size: 0,
+            flags: AtomicProcessFlags::new(
+                ProcessFlags::Default
+                    | ProcessFlags::MaxHeapSizeKill
+                    | ProcessFlags::MaxHeapSizeErrorLogger,
+            ),
+            min_heap_size: AtomicUsize::new(heap_size),
+            max_heap_size: AtomicUsize::new(0),
+            min_vheap_size: AtomicUsize::new(0),
             gc_threshold: 0.75,
-            max_gen_gcs: 65535,
+            max_gen_gcs: AtomicUsize::new(DEFAULT_FULLSWEEP_AFTER),
             off_heap,
             off_heap_size: AtomicUsize::new(0),
             dictionary: Default::default(),
+            error_handler: RwLock::new(Atom::from_str("error_handler")),
+            saved_calls: Default::default(),
             pid,
             status: Default::default(),
             mailbox: Default::default(),
+            off_heap_message_queue: Default::default(),
             heap: Mutex::new(heap),
             stack: Default::default(),
             registers: Default::default(),
             frames: Default::default(),
             scheduler_id: Mutex::new(None),
-            priority,
+            priority: RwLock::new(priority),
             parent_pid,
             group_leader_pid: Mutex::new(group_leader_pid),
             initial_module_function_arity,
@@ -255,6 +290,79 @@ impl Process {
         self.are_flags_set(ProcessFlags::TrapExit)
     }
 
+    /// Sets whether the process is sensitive.  Returns whether the process was sensitive.
+    pub fn sensitive(&self, value: bool) -> bool {
+        let flag = ProcessFlags::Sensitive;
+
+        let old_flags = if value {
+            self.set_flags(flag)
+        } else {
+            self.clear_flags(flag)
+        };
+
+        old_flags.are_set(flag)
+    }
+
+    /// Sets whether messages are sent to the `OffHeapMessageQueue` instead of the mailbox, as
+    /// `message_queue_data` `off_heap` does.  Returns whether they were.
+    pub fn set_message_queue_off_heap(&self, value: bool) -> bool {
+        let flag = ProcessFlags::MessageQueueOffHeap;
+
+        let old_flags = if value {
+            self.set_flags(flag)
+        } else {
+            self.clear_flags(flag)
+        };
+
+        old_flags.are_set(flag)
+    }
+
+    pub fn is_message_queue_off_heap(&self) -> bool {
+        self.are_flags_set(ProcessFlags::MessageQueueOffHeap)
+    }
+
+    /// Sensitive processes hide their messages, dictionary, stack and calls from introspection
+    /// and tracing.
+    pub fn is_sensitive(&self) -> bool {
+        self.are_flags_set(ProcessFlags::Sensitive)
+    }
+
+    // Error Handler
+
+    pub fn error_handler(&self) -> Atom {
+        *self.error_handler.read()
+    }
+
+    /// Returns the old error handler module
+    pub fn set_error_handler(&self, module: Atom) -> Atom {
+        mem::replace(&mut *self.error_handler.write(), module)
+    }
+
+    // Saved Calls
+
+    /// Saves `module_function_arity` if call saving is active and the process is not sensitive
+    pub fn save_call(&self, module_function_arity: ModuleFunctionArity) {
+        if !self.is_sensitive() {
+            self.saved_calls.lock().push(module_function_arity);
+        }
+    }
+
+    /// The saved calls, oldest first, or `None` if call saving is not active
+    pub fn saved_calls(&self) -> Option<Vec<ModuleFunctionArity>> {
+        let saved_calls = self.saved_calls.lock();
+
+        if saved_calls.is_active() {
+            Some(saved_calls.iter().copied().collect())
+        } else {
+            None
+        }
+    }
+
+    /// Sets the number of calls to save.  `0` disables call saving.  Returns the old number.
+    pub fn set_saved_calls_capacity(&self, capacity: usize) -> usize {
+        self.saved_calls.lock().set_capacity(capacity)
+    }
+
     // Alloc
 
     /// Acquires exclusive access to the process heap, blocking the current thread until it is able
@@ -500,6 +608,36 @@ impl Process {
     // Send
 
     pub fn send_heap_message(&self, heap_fragment: NonNull<HeapFragment>, data: Term) {
+        if self.is_message_queue_off_heap() {
+            self.off_heap_message_queue.push(heap_fragment, data);
+        } else {
+            self.push_heap_message(heap_fragment, data);
+        }
+    }
+
+    /// Moves the messages in the `OffHeapMessageQueue` to the end of the mailbox.  Their data
+    /// stays in heap fragments until they are received.
+    ///
+    /// Must be called before looking for a message in the mailbox, even when `message_queue_data`
+    /// is `on_heap`, as it may have just been changed.
+    pub fn take_off_heap_messages(&self) {
+        if self.off_heap_message_queue.is_empty() {
+            return;
+        }
+
+        // The fragments are only attached to the process when their message is received, so that
+        // garbage collection neither moves nor frees unmatched messages.
+        for (heap_fragment, data) in self.off_heap_message_queue.take() {
+            let unsafe_ref_heap_fragment = unsafe { UnsafeRef::from_raw(heap_fragment.as_ptr()) };
+
+            self.send_message(Message::HeapFragment(message::HeapFragment {
+                unsafe_ref_heap_fragment,
+                data,
+            }));
+        }
+    }
+
+    fn push_heap_message(&self, heap_fragment: NonNull<HeapFragment>, data: Term) {
         let heap_fragment_ptr = heap_fragment.as_ptr();
 
         let off_heap_unsafe_ref_heap_fragment = unsafe { UnsafeRef::from_raw(heap_fragment_ptr) };
@@ -521,6 +659,14 @@ impl Process {
 
     /// Returns `true` if the process should stop waiting and be rescheduled as runnable.
     pub fn send_from_other(&self, data: Term) -> AllocResult<bool> {
+        if self.is_message_queue_off_heap() {
+            let (heap_fragment_data, heap_fragment) = data.clone_to_fragment()?;
+            self.off_heap_message_queue
+                .push(heap_fragment, heap_fragment_data);
+
+            return Ok(self.stop_waiting());
+        }
+
         match self.heap.try_lock() {
             Some(ref mut destination_heap) => match data.clone_to_heap(destination_heap) {
                 Ok(destination_data) => {
@@ -541,17 +687,20 @@ impl Process {
             }
         }
 
-        // status.write() scope
-        {
-            let mut writable_status = self.status.write();
+        Ok(self.stop_waiting())
+    }
 
-            if *writable_status == Status::Waiting {
-                *writable_status = Status::Runnable;
+    /// Returns `true` if the process was waiting and is now runnable, so it needs to be
+    /// rescheduled.
+    pub fn stop_waiting(&self) -> bool {
+        let mut writable_status = self.status.write();
 
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+        if *writable_status == Status::Waiting {
+            *writable_status = Status::Runnable;
+
+            true
+        } else {
+            false
         }
     }
 
@@ -910,8 +1059,115 @@ impl Process {
         }
     }
 
+    // Introspection
+
+    /// The number of messages in the mailbox, including those that have been seen, but not
+    /// matched, by a `receive`
+    pub fn message_queue_len(&self) -> usize {
+        self.mailbox.lock().borrow().len() + self.off_heap_message_queue.len()
+    }
+
+    /// All key/value pairs in the process dictionary.
+    ///
+    /// Unlike `get_entries`, the pairs are not allocated on this process's heap, so that another
+    /// process can clone them to its own heap.
+    pub fn dictionary_entries(&self) -> Vec<(Term, Term)> {
+        self.dictionary
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
+    /// Minimum size (in words) of the heap
+    pub fn min_heap_size(&self) -> usize {
+        self.min_heap_size.load(Ordering::Relaxed)
+    }
+
+    /// Returns the old minimum size (in words) of the heap.  The heap is not resized until the
+    /// next garbage collection.
+    pub fn set_min_heap_size(&self, min_heap_size: usize) -> usize {
+        self.min_heap_size.swap(min_heap_size, Ordering::Relaxed)
+    }
+
+    /// Minimum size (in words) of the virtual binary heap
+    pub fn min_vheap_size(&self) -> usize {
+        self.min_vheap_size.load(Ordering::Relaxed)
+    }
+
+    /// Returns the old minimum size (in words) of the virtual binary heap
+    pub fn set_min_vheap_size(&self, min_vheap_size: usize) -> usize {
+        self.heap
+            .lock()
+            .set_min_virtual_binary_heap_size(min_vheap_size);
+
+        self.min_vheap_size.swap(min_vheap_size, Ordering::Relaxed)
+    }
+
+    /// Maximum size (in words) of the heap.  `0` means there is no limit.
+    pub fn max_heap_size(&self) -> usize {
+        self.max_heap_size.load(Ordering::Relaxed)
+    }
+
+    /// Returns the old maximum size (in words) of the heap
+    pub fn set_max_heap_size(&self, max_heap_size: usize) -> usize {
+        self.max_heap_size.swap(max_heap_size, Ordering::Relaxed)
+    }
+
+    /// The maximum number of minor collections before a full sweep occurs
+    pub fn fullsweep_after(&self) -> usize {
+        self.max_gen_gcs.load(Ordering::Relaxed)
+    }
+
+    /// Returns the old maximum number of minor collections before a full sweep occurs
+    pub fn set_fullsweep_after(&self, fullsweep_after: usize) -> usize {
+        self.max_gen_gcs.swap(fullsweep_after, Ordering::Relaxed)
+    }
+
+    /// The number of minor collections since the last full sweep
+    pub fn minor_gcs(&self) -> usize {
+        self.heap.lock().minor_gcs()
+    }
+
+    /// Size (in words) of the young generation, which includes the stack
+    pub fn heap_size(&self) -> usize {
+        self.heap.lock().heap_size()
+    }
+
+    /// Size (in words) of all generations and heap fragments
+    pub fn total_heap_size(&self) -> usize {
+        let heap = self.heap.lock();
+
+        heap.heap_size() + heap.old_heap_size() + self.off_heap_size()
+    }
+
+    /// Size (in bytes) of the process, including this control structure, the heap, the stack, and
+    /// the heap fragments.
+    pub fn memory(&self) -> usize {
+        mem::size_of::<Self>() + self.total_heap_size() * mem::size_of::<Term>()
+    }
+
+    /// Size (in bytes) of the parts of `memory` that are in use: this control structure, and the
+    /// used parts of the heap, the stack, and the heap fragments.
+    pub fn memory_used(&self) -> usize {
+        let heap = self.heap.lock();
+        let used = heap.heap_used() + heap.old_heap_used() + self.off_heap_size();
+
+        mem::size_of::<Self>() + used * mem::size_of::<Term>()
+    }
+
+    /// Reductions from previous runs and the current run
+    pub fn reductions(&self) -> u64 {
+        self.total_reductions.load(Ordering::SeqCst)
+            + (self.run_reductions.load(Ordering::SeqCst) as u64)
+    }
+
     // Garbage Collection
 
+    /// The number of minor and full collections of the heap
+    pub fn garbage_collections(&self) -> usize {
+        self.heap.lock().gc_count()
+    }
+
     /// Determines if this heap should be collected
     ///
     /// NOTE: We require a mutable reference to self to call this,
@@ -931,13 +1187,29 @@ impl Process {
         heap.should_collect(self.gc_threshold)
     }
 
+    /// Determines if the off-heap binaries referenced by this process have outgrown its virtual
+    /// binary heap.  Unlike the heap, allocating them never fails, so this is checked between runs
+    /// to keep a process from holding on to large binaries it no longer references.
+    #[inline]
+    pub fn should_collect_virtual_binaries(&self) -> bool {
+        if self.is_gc_delayed() || self.is_gc_disabled() {
+            return false;
+        }
+
+        let heap = self.heap.lock();
+        heap.should_collect_virtual(self.gc_threshold)
+    }
+
+    /// Size (in words) of the heap fragments
     #[inline(always)]
-    fn off_heap_size(&self) -> usize {
+    pub fn off_heap_size(&self) -> usize {
         self.off_heap_size.load(Ordering::Acquire)
     }
 
+    /// Whether a collection has been requested, such as by `erlang:garbage_collect/1` while the
+    /// process was running, that its scheduler should run once the process stops running
     #[inline]
-    fn is_gc_forced(&self) -> bool {
+    pub fn is_gc_forced(&self) -> bool {
         self.flags.are_set(ProcessFlags::ForceGC)
     }
 
@@ -979,14 +1251,67 @@ impl Process {
     #[inline]
     pub fn garbage_collect(&self, need: usize, roots: &mut [Term]) -> Result<usize, GcError> {
         let mut heap = self.heap.lock();
+        // Senders wait for the collection to finish, as the messages are roots
+        let mailbox_guard = self.mailbox.lock();
+        let mut mailbox = mailbox_guard.borrow_mut();
         // The roots passed in here are pointers to the native stack/registers, all other roots
         // we are able to pick up from the current process context
         let mut rootset = RootSet::new(roots);
         self.base_root_set(&mut rootset);
+        mailbox.root_set(&mut rootset);
+        // Any forced collection is satisfied by this one
+        self.flags.clear(ProcessFlags::ForceGC);
         // Initialize the collector with the given root set
         heap.garbage_collect(self, need, rootset)
     }
 
+    /// Discards the call stack, except for the current frame and the bottom frame that ends the
+    /// process, then runs a full sweep into the smallest heap that fits the live data.
+    ///
+    /// The current frame's arguments are kept on the stack, so that the current frame can return
+    /// normally.  Without frames, as when called from compiled code, there are no arguments to
+    /// keep.  As with `garbage_collect`, `roots` are updated to where their terms were moved.
+    pub fn hibernate(&self, roots: &mut [Term]) -> Result<usize, GcError> {
+        let mut frames = self.frames.lock();
+        let current_arity = frames
+            .current()
+            .map(|frame| frame.native().arity() as usize)
+            .unwrap_or(0);
+        let bottom_arity = if frames.len() > 1 {
+            frames.bottom().unwrap().native().arity() as usize
+        } else {
+            0
+        };
+        let current_arguments: Vec<Term> = (1..=current_arity)
+            .map(|one_based_index| self.stack_peek(one_based_index).unwrap())
+            .collect();
+
+        frames.discard_between_current_and_bottom();
+        self.stack_popn(self.stack_used() - bottom_arity);
+
+        let mut hibernate_roots: Vec<Term> = Default::default();
+        hibernate_roots.extend(roots.iter());
+        hibernate_roots.extend(current_arguments.iter());
+
+        let reductions = {
+            let mut heap = self.heap.lock();
+            let mailbox_guard = self.mailbox.lock();
+            let mut mailbox = mailbox_guard.borrow_mut();
+            let mut rootset = RootSet::new(&mut hibernate_roots);
+            self.base_root_set(&mut rootset);
+            mailbox.root_set(&mut rootset);
+            self.flags.clear(ProcessFlags::ForceGC);
+
+            heap.hibernate(self, current_arity, rootset)?
+        };
+
+        let (updated_roots, updated_current_arguments) = hibernate_roots.split_at(roots.len());
+        roots.copy_from_slice(updated_roots);
+        self.stack_push_slice(updated_current_arguments)?;
+
+        Ok(reductions)
+    }
+
     /// Cleans up any linked HeapFragments which should have had any live
     /// references moved out by the time this is called.
     ///
@@ -1039,6 +1364,21 @@ impl Process {
         self.run_reductions.fetch_add(1, Ordering::SeqCst);
     }
 
+    /// Counts work done by a single native call that is worth more than one reduction, such as
+    /// walking a long list.
+    pub fn reduce_by(&self, reductions: u16) {
+        self.run_reductions.fetch_add(reductions, Ordering::SeqCst);
+    }
+
+    /// Uses up the rest of this run's reductions, so that the process yields to its scheduler once
+    /// the current native returns.
+    pub fn reduce_to_yield(&self) {
+        if !self.is_reduced() {
+            self.run_reductions
+                .store(MAX_REDUCTIONS_PER_RUN, Ordering::SeqCst);
+        }
+    }
+
     pub fn is_reduced(&self) -> bool {
         MAX_REDUCTIONS_PER_RUN <= self.run_reductions.load(Ordering::SeqCst)
     }
@@ -1096,6 +1436,12 @@ impl Process {
     pub fn wait(&self) {
         *self.status.write() = Status::Waiting;
         self.run_reductions.fetch_add(1, Ordering::AcqRel);
+
+        // Off heap messages are sent without the mailbox lock, so one sent after the process last
+        // took them, but before it was waiting, would not wake it.
+        if !self.off_heap_message_queue.is_empty() {
+            self.stop_waiting();
+        }
     }
 
     pub fn exit(&self, reason: Term, source: ArcError) {
@@ -1157,11 +1503,11 @@ impl Process {
     fn call_current_native(&self) -> CalledCurrentNative {
         // not done inline in `match` argument, so that lock isn't held for `native.apply`, when
         // `native` may want to manipulate `frame_stack`.
-        let native = self
+        let (native, module_function_arity) = self
             .frames
             .lock()
             .current()
-            .map(|frame| frame.native())
+            .map(|frame| (frame.native(), frame.module_function_arity()))
             .unwrap_or_else(|| panic!("Process ({:?}) ran out of frames without exiting", self));
 
         let arity = native.arity() as usize;
@@ -1182,6 +1528,7 @@ impl Process {
             arguments.push(argument);
         }
 
+        let call_trace = self.trace_call(&module_function_arity, &arguments);
         let returned = native.apply(&arguments);
 
         let called_current_native = if returned.is_none() {
@@ -1218,6 +1565,10 @@ impl Process {
             self.frames.lock().pop().unwrap();
             self.stack_popn(arity);
 
+            if let Some(call_trace) = call_trace {
+                self.trace_return(call_trace, &module_function_arity, returned);
+            }
+
             self.stack_queued_frames_with_arguments();
 
             match self.stack_push(returned) {
@@ -1231,6 +1582,42 @@ impl Process {
         called_current_native
     }
 
+    fn trace_call(
+        &self,
+        module_function_arity: &ModuleFunctionArity,
+        arguments: &[Term],
+    ) -> Option<CallTrace> {
+        if self.are_flags_set(ProcessFlags::TraceCall) {
+            trace::call_trace_hooks()
+                .and_then(|hooks| (hooks.call)(self, module_function_arity, arguments))
+        } else {
+            None
+        }
+    }
+
+    fn trace_return(
+        &self,
+        call_trace: CallTrace,
+        module_function_arity: &ModuleFunctionArity,
+        returned: Term,
+    ) {
+        if let Some(hooks) = trace::call_trace_hooks() {
+            let returned_to = self
+                .frames
+                .lock()
+                .current()
+                .map(|frame| frame.module_function_arity());
+
+            (hooks.returned)(
+                self,
+                call_trace,
+                module_function_arity,
+                returned,
+                returned_to,
+            );
+        }
+    }
+
     pub fn stack_queued_frames_with_arguments(&self) {
         let mut frames = self.frames.lock();
         let mut frames_with_arguments = frames.drain_queue();
@@ -1363,6 +1750,12 @@ impl fmt::Display for Process {
     }
 }
 
+impl Drop for Process {
+    fn drop(&mut self) {
+        DROPPED_REDUCTIONS.fetch_add(self.reductions(), Ordering::SeqCst);
+    }
+}
+
 impl Eq for Process {}
 
 impl Hash for Process {
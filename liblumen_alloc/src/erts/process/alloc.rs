@@ -19,6 +19,7 @@ pub use self::virtual_alloc::{VirtualAlloc, VirtualAllocator, VirtualHeap};
 pub use self::virtual_binary_heap::VirtualBinaryHeap;
 
 use core::alloc::{AllocErr, Layout};
+use core::cmp;
 use core::ffi::c_void;
 use core::mem::transmute;
 use core::ptr;
@@ -186,3 +187,28 @@ pub unsafe fn free(heap: *mut Term, size: usize) {
 pub fn next_heap_size(size: usize) -> usize {
     ProcessHeapAlloc::next_heap_size(size)
 }
+
+/// Rounds `size` up to the heap size used for a minimum heap size of `size`, which, as in BEAM, is
+/// never smaller than the default heap size
+pub fn next_min_heap_size(size: usize) -> usize {
+    cmp::max(next_heap_size(size), default_heap_size())
+}
+
+/// Calculates the size (in words) of the virtual binary heap after a collection from the `size`
+/// it had and how much of it is still `used` by live binaries.
+///
+/// The size grows by the golden ratio while more than 75% is used, halves when less than 25% is
+/// used, and is never less than `min_size`.
+pub fn next_vheap_size(used: usize, size: usize, min_size: usize) -> usize {
+    let mut next_size = size;
+
+    if used / 3 > next_size / 4 {
+        while used / 3 > next_size / 4 {
+            next_size = cmp::max((next_size as f64 * 1.618) as usize, next_size + 1);
+        }
+    } else if used < next_size / 4 {
+        next_size /= 2;
+    }
+
+    cmp::max(next_size, min_size)
+}
@@ -8,7 +8,7 @@ use liblumen_core::sys::sysconf::MIN_ALIGN;
 use liblumen_core::util::pointer::{distance_absolute, in_area};
 
 use crate::erts::exception::AllocResult;
-use crate::erts::term::prelude::Term;
+use crate::erts::term::prelude::{Boxed, ProcBin, Term};
 
 /// The core trait for allocating on a heap
 pub trait HeapAlloc {
@@ -105,6 +105,14 @@ pub trait Heap: HeapAlloc {
         self.contains(ptr)
     }
 
+    /// Takes ownership of the reference held by `bin`, a `ProcBin` that was just cloned on to
+    /// this heap, sharing the binary data of the original.
+    ///
+    /// Heaps with a virtual binary heap add it there, so that it counts towards the next
+    /// collection; others must release the reference when they are freed, unless the `ProcBin`
+    /// was moved by garbage collection first.
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>);
+
     #[cfg(debug_assertions)]
     #[inline]
     fn sanity_check(&self) {
@@ -177,4 +185,9 @@ where
     fn is_owner<U: ?Sized>(&self, ptr: *const U) -> bool {
         self.deref().is_owner(ptr)
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.deref_mut().virtual_alloc_clone(bin)
+    }
 }
@@ -29,19 +29,23 @@ impl ProcessHeapAlloc {
     /// Size of word in bytes
     const WORD_SIZE: usize = mem::size_of::<usize>();
 
-    // An array of heap sizes, using the same growth pattern as BEAM
-    // Fibonnaci growth from 233 words, until 1M words, at which point
+    // An array of heap sizes, the same as BEAM's
+    // Fibonnaci growth from 12 words, until 1M words, at which point
     // the growth increases 20% at a time
     generate_heap_sizes! {
         pub(super) const HEAP_SIZES: [usize; PLACEHOLDER] = [];
     }
 
-    /// Corresponds to the first heap size of 233 words
-    pub(super) const MIN_HEAP_SIZE_INDEX: usize = 0;
+    /// Corresponds to the heap size of 233 words, the smallest heap a process starts with
+    pub(super) const MIN_HEAP_SIZE_INDEX: usize = 5;
+
+    /// The number of heap sizes with Fibonacci growth
+    const FIBONACCI_HEAP_SIZES_LEN: usize = 23;
 
     /// Creates a new `ProcessAlloc` instance
     pub fn new() -> Self {
-        let size_classes = &Self::HEAP_SIZES[..19]
+        let size_classes = &Self::HEAP_SIZES
+            [Self::MIN_HEAP_SIZE_INDEX..Self::FIBONACCI_HEAP_SIZES_LEN]
             .iter()
             .map(|size| SizeClass::new(*size))
             .filter(|size_class| SizeClassAlloc::can_fit_multiple_blocks(size_class))
@@ -94,6 +94,12 @@ where
             return true;
         }
         // Next, check virtual heap
+        self.should_collect_virtual(gc_threshold)
+    }
+
+    // Check if the virtual heap of the young generation requires collection
+    #[inline]
+    pub fn should_collect_virtual(&self, gc_threshold: f64) -> bool {
         let used = self.young.virtual_heap_used();
         let unused = self.young.virtual_heap_unused();
         if unused > 0 {
@@ -200,6 +206,11 @@ where
         self.young.sanity_check();
         self.old.sanity_check();
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.virtual_alloc(bin)
+    }
 }
 impl<A, B> HeapAlloc for SemispaceHeap<A, B>
 where
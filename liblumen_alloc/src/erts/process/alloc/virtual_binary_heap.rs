@@ -121,6 +121,19 @@ impl VirtualBinaryHeap {
         }
     }
 
+    /// Sets the size (in words) at which this virtual heap is full, so that a collection is needed
+    /// once the binaries referenced from the process heap grow past it
+    #[inline]
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size * mem::size_of::<usize>();
+    }
+
+    /// Iterates over the binaries on this virtual heap
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &ProcBin> {
+        self.bins.iter()
+    }
+
     #[inline]
     unsafe fn unlink_raw(&mut self, raw: *mut ProcBin) {
         // Remove from the list
@@ -26,6 +26,18 @@ impl ProcessFlags {
     /// This flag indicates the processes linked to this process should send exit messages instead
     /// of causing this process to exit when they exit
     pub const TrapExit: Self = Self(1 << 6);
+    /// This flag indicates that the process is sensitive, so its messages, dictionary, stack and
+    /// calls are hidden from introspection and tracing
+    pub const Sensitive: Self = Self(1 << 7);
+    /// This flag indicates that the process should be killed when it exceeds its max heap size
+    pub const MaxHeapSizeKill: Self = Self(1 << 8);
+    /// This flag indicates that an error should be logged when the process exceeds its max heap
+    /// size
+    pub const MaxHeapSizeErrorLogger: Self = Self(1 << 9);
+    /// This flag indicates that calls are reported to the `CallTraceHooks` of the runtime
+    pub const TraceCall: Self = Self(1 << 10);
+    /// This flag indicates that messages are sent to the process's `OffHeapMessageQueue`
+    pub const MessageQueueOffHeap: Self = Self(1 << 11);
 
     pub fn are_set(&self, flags: ProcessFlags) -> bool {
         (*self & flags) == flags
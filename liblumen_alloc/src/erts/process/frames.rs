@@ -23,6 +23,21 @@ impl Frames {
         self.stack.top()
     }
 
+    /// The oldest frame, which ends the process when the frames above it have returned
+    pub fn bottom(&self) -> Option<&Frame> {
+        self.stack.bottom()
+    }
+
+    /// Discards the frames between the current frame and the bottom frame, so that the current
+    /// frame returns directly to the bottom frame.
+    pub fn discard_between_current_and_bottom(&mut self) {
+        self.stack.discard_between_top_and_bottom()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
     pub fn push(&mut self, frame: Frame) {
         self.stack.push(frame);
     }
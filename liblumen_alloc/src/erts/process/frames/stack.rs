@@ -1,4 +1,5 @@
 use core::fmt::{self, Debug, Display};
+use core::slice;
 
 use alloc::collections::vec_deque::VecDeque;
 use alloc::vec::Vec;
@@ -10,6 +11,19 @@ use crate::process::Frame;
 pub struct Stack(VecDeque<Frame>);
 
 impl Stack {
+    pub fn bottom(&self) -> Option<&Frame> {
+        self.0.back()
+    }
+
+    /// Removes the frames between the top and the bottom
+    pub fn discard_between_top_and_bottom(&mut self) {
+        let len = self.0.len();
+
+        if 2 < len {
+            self.0.drain(1..len - 1);
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -60,6 +74,17 @@ impl Debug for Stack {
 
 pub struct Trace(Vec<ModuleFunctionArity>);
 
+impl Trace {
+    /// Iterates from the most recent (current) frame to the oldest
+    pub fn iter(&self) -> slice::Iter<ModuleFunctionArity> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
 impl Debug for Trace {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for module_function_arity in self.0.iter() {
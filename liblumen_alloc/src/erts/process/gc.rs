@@ -17,10 +17,15 @@ pub use self::rootset::RootSet;
 pub use self::sweep::{Sweep, Sweepable, Sweeper};
 pub use self::young_heap::YoungHeap;
 
+use core::sync::atomic::{AtomicUsize, Ordering};
+
 use super::alloc::SemispaceHeap;
 use crate::erts::exception;
 use thiserror::Error;
 
+static COLLECTIONS: AtomicUsize = AtomicUsize::new(0);
+static WORDS_RECLAIMED: AtomicUsize = AtomicUsize::new(0);
+
 /// Represents the types of errors that can occur during garbage collection.
 ///
 /// See the documentation for each variant to get general advice for how to
@@ -77,3 +82,18 @@ pub fn estimate_cost(moved_live_words: usize, resize_moved_words: usize) -> usiz
         reds
     }
 }
+
+/// The number of completed collections and the words they reclaimed, across all processes
+pub fn statistics() -> (usize, usize) {
+    (
+        COLLECTIONS.load(Ordering::Relaxed),
+        WORDS_RECLAIMED.load(Ordering::Relaxed),
+    )
+}
+
+/// Counts a completed collection that shrank the live data from `size_before` to `size_after`
+/// words
+pub(super) fn record_collection(size_before: usize, size_after: usize) {
+    COLLECTIONS.fetch_add(1, Ordering::Relaxed);
+    WORDS_RECLAIMED.fetch_add(size_before.saturating_sub(size_after), Ordering::Relaxed);
+}
@@ -65,6 +65,12 @@ impl OldHeap {
     pub fn active(&self) -> bool {
         !self.start.is_null()
     }
+
+    /// Iterates over the off-heap binaries referenced from this heap
+    #[inline]
+    pub fn virtual_binaries(&self) -> impl Iterator<Item = &ProcBin> {
+        self.vheap.iter()
+    }
 }
 impl Heap for OldHeap {
     fn is_corrupted(&self) -> bool {
@@ -101,6 +107,11 @@ impl Heap for OldHeap {
     fn heap_end(&self) -> *mut Term {
         self.end
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.virtual_alloc(bin)
+    }
 }
 impl HeapAlloc for OldHeap {
     #[inline]
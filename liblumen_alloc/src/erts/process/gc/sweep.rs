@@ -135,7 +135,9 @@ where
 // If the sub-binary is too large to fit in a heap binary allocation, then
 // the original binary must be a reference-counted binary (procbin), so
 // we don't need to worry about it being collected out from under us, as
-// we count as a reference.
+// we count as a reference.  When the slice is only a small part of that
+// procbin, it is copied into a procbin of its own instead, so that the
+// slice doesn't keep the much larger original alive.
 unsafe impl<G> Sweepable<G> for Boxed<SubBinary>
 where
     G: Sweeper,
@@ -156,6 +158,24 @@ where
             let size = mem::size_of_val(dst.as_ref());
 
             (dst.cast::<Term>().as_ptr(), size)
+        } else if let Ok((bin_ptr, bin_size)) = sub.to_procbin_parts() {
+            // Allocate reference-counted binary via copy
+            let bytes = slice::from_raw_parts(bin_ptr, bin_size);
+            let bin = ProcBin::from_slice(bytes, Encoding::Raw).unwrap();
+            let layout = Layout::new::<ProcBin>();
+            let dst = sweeper
+                .alloc_layout(layout)
+                .unwrap()
+                .cast::<ProcBin>()
+                .as_ptr();
+            dst.write(bin);
+
+            // Link to destination virtual heap
+            sweeper
+                .target_mut()
+                .virtual_alloc(Boxed::new_unchecked(dst));
+
+            (dst as *mut Term, layout.size())
         } else {
             // Move to new location
             let src = self.as_ptr();
@@ -293,6 +313,11 @@ where
         // Move to new location
         src.copy_to_nonoverlapping(dst, 1);
 
+        // Write move marker to previous location, so that the reference is only moved once, and
+        // heap fragments know not to release it
+        let marker: Term = (dst as *mut Term).into();
+        (src as *mut Term).write(marker);
+
         // Link to destination virtual heap
         let boxed = Boxed::new_unchecked(dst);
         sweeper.target_mut().virtual_alloc(boxed);
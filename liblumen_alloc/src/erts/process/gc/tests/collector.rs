@@ -4,6 +4,7 @@ use core::ffi::c_void;
 use core::mem;
 use core::ops::Deref;
 
+use crate::borrow::CloneToProcess;
 use crate::erts::process::alloc::TermAlloc;
 use crate::erts::process::test::process;
 use crate::erts::term::closure::*;
@@ -48,6 +49,82 @@ fn gc_fullsweep_after_tenuring_test() {
     tenuring_gc_test(process, true);
 }
 
+// This test ensures that a reference-counted binary cloned to another process shares its data,
+// and that each process releases its reference once the binary is garbage
+#[test]
+fn gc_procbin_cloned_to_other_process_test() {
+    let sender = process();
+    let receiver = process();
+
+    let bytes = [1; 100];
+    let sender_bin = sender.acquire_heap().binary_from_bytes(&bytes).unwrap();
+    let sender_procbin: Boxed<ProcBin> = sender_bin.try_into().unwrap();
+    assert_eq!(sender_procbin.ref_count(), 1);
+
+    let receiver_bin = sender_bin.clone_to_process(&receiver);
+    let receiver_procbin: Boxed<ProcBin> = receiver_bin.try_into().unwrap();
+    assert_eq!(receiver_procbin.as_bytes(), &bytes[..]);
+    assert_eq!(receiver_procbin.ref_count(), 2);
+
+    let mut roots = [receiver_bin];
+    receiver.set_flags(ProcessFlags::NeedFullSweep);
+    receiver.garbage_collect(0, &mut roots).unwrap();
+
+    let moved_procbin: Boxed<ProcBin> = roots[0].try_into().unwrap();
+    assert_eq!(moved_procbin.ref_count(), 2);
+
+    // Without roots, the sender's `ProcBin` is garbage
+    sender.set_flags(ProcessFlags::NeedFullSweep);
+    sender.garbage_collect(0, &mut []).unwrap();
+
+    assert_eq!(moved_procbin.ref_count(), 1);
+    assert_eq!(moved_procbin.as_bytes(), &bytes[..]);
+}
+
+// This test ensures that a heap fragment releases the reference held by a cloned reference-counted
+// binary that was not moved out of it
+#[test]
+fn gc_procbin_in_dropped_heap_fragment_test() {
+    let process = process();
+
+    let bin = process.acquire_heap().binary_from_bytes(&[2; 100]).unwrap();
+    let procbin: Boxed<ProcBin> = bin.try_into().unwrap();
+
+    let (_fragment_bin, fragment) = bin.clone_to_fragment().unwrap();
+    assert_eq!(procbin.ref_count(), 2);
+
+    unsafe { core::ptr::drop_in_place(fragment.as_ptr()) };
+    assert_eq!(procbin.ref_count(), 1);
+}
+
+// This test ensures that a sub-binary covering a small part of a reference-counted binary is
+// copied into its own reference-counted binary, so that the original can be freed
+#[test]
+fn gc_subbinary_of_procbin_compacted_test() {
+    let process = process();
+
+    let bytes: Vec<u8> = (0..=255).collect();
+    let (original, sub) = {
+        let mut heap = process.acquire_heap();
+        let original = heap.binary_from_bytes(&bytes).unwrap();
+        let sub = heap
+            .subbinary_from_original(original, 16, 0, 65, 0)
+            .unwrap();
+
+        (original, sub)
+    };
+    let original_procbin: Boxed<ProcBin> = original.try_into().unwrap();
+    assert_eq!(original_procbin.ref_count(), 1);
+
+    let mut roots = [sub.into()];
+    process.set_flags(ProcessFlags::NeedFullSweep);
+    process.garbage_collect(0, &mut roots).unwrap();
+
+    let compacted: Boxed<ProcBin> = roots[0].try_into().unwrap();
+    assert_eq!(compacted.as_bytes(), &bytes[16..81]);
+    assert_eq!(compacted.ref_count(), 1);
+}
+
 fn simple_gc_test(process: Process) {
     // Allocate an `{:ok, "hello world"}` tuple
     // First, the `ok` atom, an immediate, is super easy
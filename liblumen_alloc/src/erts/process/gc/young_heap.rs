@@ -123,6 +123,18 @@ impl YoungHeap {
         self.high_water_mark = self.top;
     }
 
+    /// Iterates over the off-heap binaries referenced from this heap
+    #[inline]
+    pub fn virtual_binaries(&self) -> impl Iterator<Item = &ProcBin> {
+        self.vheap.iter()
+    }
+
+    /// Sets the size (in words) of the virtual binary heap, above which a collection is needed
+    #[inline]
+    pub fn set_virtual_size(&mut self, size: usize) {
+        self.vheap.set_size(size)
+    }
+
     #[inline]
     fn stack_slot_address(&self, slot: usize) -> *mut Term {
         assert!(slot < self.stack_size);
@@ -388,6 +400,11 @@ impl Heap for YoungHeap {
     fn sanity_check(&self) {
         self.overrun_check();
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.virtual_alloc(bin)
+    }
 }
 impl HeapAlloc for YoungHeap {
     #[inline]
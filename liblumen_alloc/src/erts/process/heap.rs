@@ -1,4 +1,6 @@
 use core::alloc::Layout;
+use core::cmp;
+use core::mem;
 use core::ptr::NonNull;
 
 use log::trace;
@@ -20,6 +22,8 @@ use super::{Process, ProcessFlags};
 pub struct ProcessHeap {
     // the number of minor collections
     pub(super) gen_gc_count: usize,
+    // the number of minor and full collections
+    gc_count: usize,
     // The semi-space generational heap
     heap: SemispaceProcessHeap,
 }
@@ -30,6 +34,7 @@ impl ProcessHeap {
         let heap = SemispaceHeap::new(young, old);
         Self {
             gen_gc_count: 0,
+            gc_count: 0,
             heap,
         }
     }
@@ -40,6 +45,62 @@ impl ProcessHeap {
         self.heap.should_collect(gc_threshold)
     }
 
+    /// Returns true if the off-heap binaries referenced from the young generation have outgrown
+    /// its virtual binary heap
+    #[inline]
+    pub fn should_collect_virtual(&self, gc_threshold: f64) -> bool {
+        self.heap.should_collect_virtual(gc_threshold)
+    }
+
+    /// Returns the size (in words) of the old generation, which is `0` until the first
+    /// collection that tenures terms
+    #[inline]
+    pub fn old_heap_size(&self) -> usize {
+        self.heap.old_generation().heap_size()
+    }
+
+    /// Returns the amount (in words) of the old generation that is in use
+    #[inline]
+    pub fn old_heap_used(&self) -> usize {
+        self.heap.old_generation().heap_used()
+    }
+
+    /// Returns the number of minor collections since the last full sweep
+    #[inline]
+    pub fn minor_gcs(&self) -> usize {
+        self.gen_gc_count
+    }
+
+    /// Returns the number of minor and full collections
+    #[inline]
+    pub fn gc_count(&self) -> usize {
+        self.gc_count
+    }
+
+    /// Iterates over the off-heap binaries referenced from both generations
+    pub fn virtual_binaries(&self) -> impl Iterator<Item = &ProcBin> {
+        self.heap
+            .young_generation()
+            .virtual_binaries()
+            .chain(self.heap.old_generation().virtual_binaries())
+    }
+
+    /// Size (in words) of the young generation's virtual binary heap, above which a collection
+    /// is needed
+    #[inline]
+    pub fn virtual_binary_heap_size(&self) -> usize {
+        self.heap.young_generation().virtual_size() / mem::size_of::<Term>()
+    }
+
+    /// Grows the young generation's virtual binary heap to at least `min_vheap_size` words
+    pub fn set_min_virtual_binary_heap_size(&mut self, min_vheap_size: usize) {
+        if self.virtual_binary_heap_size() < min_vheap_size {
+            self.heap
+                .young_generation_mut()
+                .set_virtual_size(min_vheap_size);
+        }
+    }
+
     #[cfg(test)]
     pub(super) fn heap(&self) -> &SemispaceProcessHeap {
         &self.heap
@@ -78,11 +139,45 @@ impl ProcessHeap {
 
         // Initialize the collector
         // Determine if the current collection requires a full sweep or not
-        if process.needs_fullsweep() || self.gen_gc_count >= process.max_gen_gcs {
+        let result = if process.needs_fullsweep() || self.gen_gc_count >= process.fullsweep_after()
+        {
             self.collect_full(process, needed, roots)
         } else {
             self.collect_minor(process, needed, roots)
+        };
+
+        if result.is_ok() {
+            self.gc_count += 1;
         }
+
+        result
+    }
+
+    /// Runs a full sweep, then shrinks the young generation to the smallest heap size that fits
+    /// the live data, the stack, and `needed` words.
+    ///
+    /// Unlike the shrinking after a normal full sweep, this ignores the minimum heap size, so that
+    /// a hibernating process holds as little memory as possible.  The next collection after the
+    /// process wakes up grows the heap again.
+    pub fn hibernate(
+        &mut self,
+        process: &Process,
+        needed: usize,
+        roots: RootSet,
+    ) -> Result<usize, GcError> {
+        process.flags.set(ProcessFlags::NeedFullSweep);
+
+        let reductions = self.garbage_collect(process, needed, roots)?;
+
+        let young = self.heap.young_generation();
+        let hibernated_size =
+            alloc::next_heap_size(young.stack_used() + young.heap_used() + needed);
+
+        if hibernated_size < young.heap_size() {
+            self.shrink_young_heap(hibernated_size);
+        }
+
+        Ok(reductions)
     }
 
     /// Handles the specific details required to initialize and execute a full sweep garbage
@@ -95,6 +190,8 @@ impl ProcessHeap {
     ) -> Result<usize, GcError> {
         trace!("Performing a full sweep garbage collection");
 
+        let vheap_size = self.virtual_binary_heap_size();
+
         // Determine the estimated size for the new heap which will receive all live data
         let old_heap_size = self.heap.old_generation().heap_used();
         let young = self.heap.young_generation();
@@ -108,7 +205,10 @@ impl ProcessHeap {
         // If we already have a large enough heap, we don't need to grow it, but if the GROW flag is
         // set, then we should do it anyway, since it will prevent us from doing another full
         // collection for awhile (assuming one is not forced)
-        let baseline_size = alloc::next_heap_size(padded_estimate);
+        let baseline_size = cmp::max(
+            alloc::next_heap_size(padded_estimate),
+            process.min_heap_size(),
+        );
         let new_heap_size =
             if baseline_size == young.heap_size() && process.should_force_heap_growth() {
                 alloc::next_heap_size(baseline_size)
@@ -118,7 +218,7 @@ impl ProcessHeap {
 
         // Verify that our projected heap size is not going to blow the max heap size, if set
         // NOTE: When this happens, we will be left with no choice but to kill the process
-        if process.max_heap_size > 0 && process.max_heap_size < new_heap_size {
+        if process.max_heap_size() > 0 && process.max_heap_size() < new_heap_size {
             return Err(GcError::MaxHeapSizeExceeded);
         }
 
@@ -139,11 +239,10 @@ impl ProcessHeap {
             gc.garbage_collect()?
         };
 
-        // TODO: Move messages to be stored on-heap, on to the heap
-
         // Now that all live data has been swept on to the new heap, we can
         // clean up all of the off heap fragments that we still have laying around
         process.sweep_off_heap();
+        self.resize_virtual_binary_heap(process, vheap_size);
 
         // Reset the generational GC counter
         self.gen_gc_count = 0;
@@ -153,6 +252,7 @@ impl ProcessHeap {
         let stack_used = young.stack_used();
         let heap_used = young.heap_used();
         let size_after = stack_used + heap_used + process.off_heap_size();
+        gc::record_collection(size_before, size_after);
         if size_before >= size_after {
             trace!(
                 "Full sweep reclaimed {} words of garbage",
@@ -198,13 +298,13 @@ impl ProcessHeap {
 
         // Check if the needed space consumes less than 25% of the new heap,
         // and if so, shrink the new heap immediately to free the unused space
-        if total_size > needed_after * 4 && process.min_heap_size < total_size {
+        if total_size > needed_after * 4 && process.min_heap_size() < total_size {
             // Shrink to double our estimated need
             let mut estimate = needed_after * 2;
             // If our estimated need is too low, round up to the min heap size;
             // otherwise, calculate the next heap size bucket our need falls in
-            if estimate < process.min_heap_size {
-                estimate = process.min_heap_size;
+            if estimate < process.min_heap_size() {
+                estimate = process.min_heap_size();
             } else {
                 estimate = alloc::next_heap_size(estimate);
             }
@@ -235,6 +335,8 @@ impl ProcessHeap {
     ) -> Result<usize, GcError> {
         trace!("Performing a minor garbage collection");
 
+        let vheap_size = self.virtual_binary_heap_size();
+
         // Determine the estimated size for the new heap which will receive immature live data
         let off_heap_size = process.off_heap_size();
         let young = self.heap.young_generation();
@@ -248,7 +350,7 @@ impl ProcessHeap {
         // the max heap size, if one was configured.
         //
         // If a max heap size is set, make sure we're not going to exceed it
-        if process.max_heap_size > 0 {
+        if process.max_heap_size() > 0 {
             // First, check if we have exceeded the max heap size
             let mut heap_size = size_before;
             // In this estimate, our stack size includes unused area between stack and heap
@@ -269,7 +371,7 @@ impl ProcessHeap {
             heap_size += alloc::next_heap_size(baseline_size);
 
             // When this error type is returned, a full sweep will be triggered
-            if heap_size > process.max_heap_size {
+            if heap_size > process.max_heap_size() {
                 return Err(GcError::MaxHeapSizeExceeded);
             }
         }
@@ -295,7 +397,10 @@ impl ProcessHeap {
         // the new heap is too small to meet the need that triggered the
         // collection in the first place. Better to shrink it post-collection
         // than to require growing it and re-updating all the roots again
-        let new_size = alloc::next_heap_size(baseline_size);
+        let new_size = cmp::max(
+            alloc::next_heap_size(baseline_size),
+            process.min_heap_size(),
+        );
 
         // Allocate new young generation heap
         let ptr = alloc::heap(new_size).map_err(|alloc| GcError::Alloc(alloc))?;
@@ -315,18 +420,18 @@ impl ProcessHeap {
         // Now that all live data has been swept on to the new heap, we can
         // clean up all of the off heap fragments that we still have laying around
         process.sweep_off_heap();
+        self.resize_virtual_binary_heap(process, vheap_size);
 
         // Increment the generational GC counter
         self.gen_gc_count += 1;
 
-        // TODO: if using on-heap messages, move messages in the queue to the heap
-
         // Calculate memory usage after collection
         let old = self.heap.old_generation();
         let young = self.heap.young_generation();
         let new_mature_size = distance_absolute(old.heap_top(), prev_old_top);
         let heap_used = young.heap_used();
         let size_after = new_mature_size + heap_used; // TODO: add process.mbuf_size
+        gc::record_collection(size_before, size_after);
         let needed_after = heap_used + needed + stack_size;
 
         // Excessively large heaps should be shrunk, but don't even bother on reasonable small heaps
@@ -356,8 +461,8 @@ impl ProcessHeap {
 
             // If the new estimate is less than the min heap size, then round up;
             // otherwise, round the estimate up to the nearest heap size bucket
-            if estimate < process.min_heap_size {
-                estimate = process.min_heap_size;
+            if estimate < process.min_heap_size() {
+                estimate = process.min_heap_size();
             } else {
                 estimate = alloc::next_heap_size(estimate);
             }
@@ -396,6 +501,16 @@ impl ProcessHeap {
     fn shrink_young_heap(&mut self, new_size: usize) {
         unsafe { self.heap.young_generation_mut().shrink(new_size) }
     }
+
+    /// Sizes the virtual binary heap of the new young generation from the `vheap_size` of the
+    /// collected one, and the binaries that survived
+    fn resize_virtual_binary_heap(&mut self, process: &Process, vheap_size: usize) {
+        let young = self.heap.young_generation_mut();
+        let used = young.virtual_heap_used() / mem::size_of::<Term>();
+        let next_vheap_size = alloc::next_vheap_size(used, vheap_size, process.min_vheap_size());
+
+        young.set_virtual_size(next_vheap_size);
+    }
 }
 impl HeapAlloc for ProcessHeap {
     #[inline]
@@ -447,6 +562,11 @@ impl Heap for ProcessHeap {
     fn is_owner<T: ?Sized>(&self, ptr: *const T) -> bool {
         self.heap.contains(ptr)
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.virtual_alloc(bin)
+    }
 }
 impl VirtualHeap<ProcBin> for ProcessHeap {
     #[inline]
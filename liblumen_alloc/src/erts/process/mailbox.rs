@@ -2,12 +2,17 @@ use core::default::Default;
 
 use alloc::collections::vec_deque::Iter;
 use alloc::collections::VecDeque;
+use core::ptr;
+
+use intrusive_collections::UnsafeRef;
 
 use crate::borrow::CloneToProcess;
 use crate::erts::exception::AllocResult;
+use crate::erts::fragment::HeapFragment;
 use crate::erts::message::{self, Message, MessageType};
+use crate::erts::process::gc::RootSet;
 use crate::erts::process::Process;
-use crate::erts::term::prelude::Term;
+use crate::erts::term::prelude::{Reference, Term};
 
 #[derive(Debug)]
 pub struct Mailbox {
@@ -15,6 +20,8 @@ pub struct Mailbox {
     seen: isize,
 
     cursor: usize,
+    marker: Option<Marker>,
+    timer_reference: Option<Reference>,
 }
 
 impl Mailbox {
@@ -58,6 +65,53 @@ impl Mailbox {
         self.remove(self.cursor - 1, proc);
         self.cursor = 0;
     }
+    /// Like `recv_finish`, but a message in a heap fragment leaves the fragment attached to the
+    /// process, so that the terms bound from the message stay valid until the next garbage
+    /// collection moves them onto the heap.
+    pub fn recv_finish_in_place(&mut self, proc: &Process) {
+        if let Message::HeapFragment(message::HeapFragment {
+            unsafe_ref_heap_fragment,
+            ..
+        }) = self.remove_message(self.cursor - 1)
+        {
+            // Messages taken from the `OffHeapMessageQueue` are not attached until received
+            if !unsafe_ref_heap_fragment.link.is_linked() {
+                let heap_fragment_ptr = UnsafeRef::into_raw(unsafe_ref_heap_fragment);
+                proc.attach_fragment(unsafe { &mut *heap_fragment_ptr });
+            }
+        }
+        self.cursor = 0;
+    }
+    /// Marks where the messages that arrive after `reference` was made start.
+    pub fn recv_mark(&mut self, reference: &Reference) {
+        self.marker = Some(Marker {
+            reference: *reference,
+            index: self.messages.len(),
+        });
+    }
+    /// Skips the messages that arrived before `reference` was made, if it is the last reference
+    /// marked.  Only valid when every message the receive matches contains `reference`, as
+    /// the skipped messages cannot.
+    pub fn recv_set(&mut self, reference: &Reference) {
+        if let Some(marker) = &self.marker {
+            if marker.reference == *reference && self.cursor < marker.index {
+                self.cursor = marker.index;
+            }
+        }
+    }
+    /// Whether the timer that ends the current receive was started with `recv_set_timer`.
+    pub fn recv_has_timer(&self) -> bool {
+        self.timer_reference.is_some()
+    }
+    /// Remembers the timer that wakes the process when the current receive times out, so that it
+    /// can be cancelled when the receive finishes.
+    pub fn recv_set_timer(&mut self, reference: Reference) {
+        self.timer_reference = Some(reference);
+    }
+    /// Takes the timer of the current receive, which the caller must cancel.
+    pub fn recv_take_timer(&mut self) -> Option<Reference> {
+        self.timer_reference.take()
+    }
     // End receive implementation for the eir interpreter
 
     pub fn flush<F>(&mut self, predicate: F, process: &Process) -> bool
@@ -78,6 +132,32 @@ impl Mailbox {
         self.messages.iter()
     }
 
+    /// Adds the data of the messages on the process heap, or in heap fragments attached to the
+    /// process, to `rootset`, so that garbage collection moves them with the rest of the live
+    /// data.  The mailbox must not change until the collection is done.
+    ///
+    /// Messages from the `OffHeapMessageQueue` stay in their unattached heap fragments.
+    pub fn root_set(&mut self, rootset: &mut RootSet) {
+        for message in self.messages.iter_mut() {
+            let option_attached_data = match message {
+                Message::HeapFragment(message::HeapFragment {
+                    unsafe_ref_heap_fragment,
+                    data,
+                }) if unsafe_ref_heap_fragment.link.is_linked() => Some(*data),
+                _ => None,
+            };
+
+            // The attached fragment is freed once the collection has moved the data out of it
+            if let Some(data) = option_attached_data {
+                *message = Message::Process(message::Process { data });
+            }
+
+            if let Message::Process(message::Process { data }) = message {
+                rootset.push(data as *mut Term);
+            }
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.messages.len()
     }
@@ -91,6 +171,7 @@ impl Mailbox {
         match self.messages.pop_front() {
             option_message @ Some(_) => {
                 self.decrement_seen();
+                self.decrement_marker(0);
 
                 option_message
             }
@@ -109,6 +190,7 @@ impl Mailbox {
         self.messages.pop_front().map(|message| match message {
             Message::Process(message::Process { data }) => {
                 self.decrement_seen();
+                self.decrement_marker(0);
 
                 Ok(data)
             }
@@ -117,17 +199,10 @@ impl Mailbox {
                 data,
             }) => match data.clone_to_heap(&mut process.acquire_heap()) {
                 Ok(heap_data) => {
-                    let mut off_heap = process.off_heap.lock();
-
-                    unsafe {
-                        let mut cursor =
-                            off_heap.cursor_mut_from_ptr(unsafe_ref_heap_fragment.as_ref());
-                        cursor
-                            .remove()
-                            .expect("HeapFragment was not in process's off_heap");
-                    }
+                    detach(unsafe_ref_heap_fragment, process);
 
                     self.decrement_seen();
+                    self.decrement_marker(0);
 
                     Ok(heap_data)
                 }
@@ -141,25 +216,14 @@ impl Mailbox {
     }
 
     pub fn remove(&mut self, index: usize, process: &Process) {
-        let message = self.messages.remove(index).unwrap();
+        let message = self.remove_message(index);
 
         if let Message::HeapFragment(message::HeapFragment {
-            unsafe_ref_heap_fragment,
+            ref unsafe_ref_heap_fragment,
             ..
         }) = message
         {
-            let mut off_heap = process.off_heap.lock();
-
-            unsafe {
-                let mut cursor = off_heap.cursor_mut_from_ptr(unsafe_ref_heap_fragment.as_ref());
-                cursor
-                    .remove()
-                    .expect("HeapFragment was not in process's off_heap");
-            }
-        }
-
-        if (index as isize) <= self.seen {
-            self.seen -= 1;
+            detach(unsafe_ref_heap_fragment, process);
         }
     }
 
@@ -173,11 +237,49 @@ impl Mailbox {
 
     // Private
 
+    fn remove_message(&mut self, index: usize) -> Message {
+        let message = self.messages.remove(index).unwrap();
+
+        if (index as isize) <= self.seen {
+            self.seen -= 1;
+        }
+
+        self.decrement_marker(index);
+
+        message
+    }
+
     fn decrement_seen(&mut self) {
         if 0 <= self.seen {
             self.seen -= 1;
         }
     }
+
+    /// The message at `index` was removed
+    fn decrement_marker(&mut self, index: usize) {
+        if let Some(marker) = &mut self.marker {
+            if index < marker.index {
+                marker.index -= 1;
+            }
+        }
+    }
+}
+
+impl Drop for Mailbox {
+    fn drop(&mut self) {
+        for message in self.messages.drain(..) {
+            if let Message::HeapFragment(message::HeapFragment {
+                unsafe_ref_heap_fragment,
+                ..
+            }) = message
+            {
+                // Attached fragments are owned by the process
+                if !unsafe_ref_heap_fragment.link.is_linked() {
+                    unsafe { ptr::drop_in_place(UnsafeRef::into_raw(unsafe_ref_heap_fragment)) };
+                }
+            }
+        }
+    }
 }
 
 impl Default for Mailbox {
@@ -186,6 +288,33 @@ impl Default for Mailbox {
             messages: Default::default(),
             seen: -1,
             cursor: 0,
+            marker: None,
+            timer_reference: None,
+        }
+    }
+}
+
+/// Detaches the heap fragment of a message that is removed from the mailbox from `process`.  A
+/// fragment that was never attached, because it was taken from the `OffHeapMessageQueue`, is
+/// freed instead.
+fn detach(unsafe_ref_heap_fragment: &UnsafeRef<HeapFragment>, process: &Process) {
+    if unsafe_ref_heap_fragment.link.is_linked() {
+        let mut off_heap = process.off_heap.lock();
+
+        unsafe {
+            let mut cursor = off_heap.cursor_mut_from_ptr(unsafe_ref_heap_fragment.as_ref());
+            cursor
+                .remove()
+                .expect("HeapFragment was not in process's off_heap");
         }
+    } else {
+        unsafe { ptr::drop_in_place(UnsafeRef::into_raw(unsafe_ref_heap_fragment.clone())) };
     }
 }
+
+#[derive(Debug)]
+struct Marker {
+    reference: Reference,
+    /// The index of the first message that arrived after `reference` was made
+    index: usize,
+}
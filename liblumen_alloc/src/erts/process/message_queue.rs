@@ -0,0 +1,98 @@
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use crate::erts::fragment::HeapFragment;
+use crate::erts::term::prelude::Term;
+
+/// The messages sent to a process whose `message_queue_data` is `off_heap`.
+///
+/// Senders push messages in heap fragments without taking any of the receiving process's locks.
+/// The process takes them into its `Mailbox` when it looks for a message, so that a process with
+/// a huge mailbox neither contends with its senders nor garbage collects its messages before
+/// matching them.
+#[derive(Debug)]
+pub struct OffHeapMessageQueue {
+    /// The most recently pushed message
+    head: AtomicPtr<Node>,
+    len: AtomicUsize,
+}
+
+impl OffHeapMessageQueue {
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::SeqCst).is_null()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::SeqCst)
+    }
+
+    pub fn push(&self, heap_fragment: NonNull<HeapFragment>, data: Term) {
+        let node = Box::into_raw(Box::new(Node {
+            heap_fragment,
+            data,
+            next: ptr::null_mut(),
+        }));
+        // Counted before the message can be taken, so that `take` never makes `len` underflow
+        self.len.fetch_add(1, Ordering::SeqCst);
+
+        let mut head = self.head.load(Ordering::SeqCst);
+
+        loop {
+            unsafe {
+                (*node).next = head;
+            }
+
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => break,
+                Err(current_head) => head = current_head,
+            }
+        }
+    }
+
+    /// Takes all the messages, oldest first
+    pub fn take(&self) -> Vec<(NonNull<HeapFragment>, Term)> {
+        let mut node = self.head.swap(ptr::null_mut(), Ordering::SeqCst);
+        let mut messages = Vec::new();
+
+        while !node.is_null() {
+            let Node {
+                heap_fragment,
+                data,
+                next,
+            } = *unsafe { Box::from_raw(node) };
+            messages.push((heap_fragment, data));
+            node = next;
+        }
+
+        self.len.fetch_sub(messages.len(), Ordering::SeqCst);
+        messages.reverse();
+
+        messages
+    }
+}
+
+impl Default for OffHeapMessageQueue {
+    fn default() -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            len: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Drop for OffHeapMessageQueue {
+    fn drop(&mut self) {
+        for (heap_fragment, _) in self.take() {
+            unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+        }
+    }
+}
+
+struct Node {
+    heap_fragment: NonNull<HeapFragment>,
+    data: Term,
+    next: *mut Node,
+}
@@ -0,0 +1,109 @@
+use alloc::collections::vec_deque::{self, VecDeque};
+
+use crate::erts::ModuleFunctionArity;
+
+/// The most recent calls made by a process, as set with `process_flag(save_calls, N)`.
+///
+/// When full, saving a call drops the oldest saved call, so that only the `capacity` most recent
+/// calls are kept.  A `capacity` of `0` means call saving is not active.
+#[derive(Debug, Default)]
+pub struct SavedCalls {
+    capacity: usize,
+    calls: VecDeque<ModuleFunctionArity>,
+}
+
+impl SavedCalls {
+    /// [BEAM](http://erlang.org/doc/man/erlang.html#process_flag-2) only allows `save_calls`
+    /// between `0` and `10000`.
+    pub const MAX_CAPACITY: usize = 10_000;
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_active(&self) -> bool {
+        0 < self.capacity
+    }
+
+    /// Iterates the saved calls from the oldest to the most recent
+    pub fn iter(&self) -> vec_deque::Iter<ModuleFunctionArity> {
+        self.calls.iter()
+    }
+
+    /// Saves `module_function_arity` if call saving is active
+    pub fn push(&mut self, module_function_arity: ModuleFunctionArity) {
+        if self.is_active() {
+            if self.calls.len() == self.capacity {
+                self.calls.pop_front();
+            }
+
+            self.calls.push_back(module_function_arity);
+        }
+    }
+
+    /// Changes the number of calls saved, dropping the oldest calls if `capacity` is smaller than
+    /// the number of saved calls.  Returns the old capacity.
+    pub fn set_capacity(&mut self, capacity: usize) -> usize {
+        assert!(capacity <= Self::MAX_CAPACITY);
+
+        while capacity < self.calls.len() {
+            self.calls.pop_front();
+        }
+
+        core::mem::replace(&mut self.capacity, capacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::erts::term::prelude::Atom;
+
+    fn module_function_arity(arity: u8) -> ModuleFunctionArity {
+        ModuleFunctionArity {
+            module: Atom::from_str("module"),
+            function: Atom::from_str("function"),
+            arity,
+        }
+    }
+
+    #[test]
+    fn inactive_does_not_save_calls() {
+        let mut saved_calls = SavedCalls::default();
+        saved_calls.push(module_function_arity(0));
+
+        assert!(!saved_calls.is_active());
+        assert_eq!(saved_calls.iter().count(), 0);
+    }
+
+    #[test]
+    fn full_drops_oldest_call() {
+        let mut saved_calls = SavedCalls::default();
+        assert_eq!(saved_calls.set_capacity(2), 0);
+
+        saved_calls.push(module_function_arity(0));
+        saved_calls.push(module_function_arity(1));
+        saved_calls.push(module_function_arity(2));
+
+        let arities: Vec<u8> = saved_calls.iter().map(|mfa| mfa.arity).collect();
+
+        assert_eq!(arities, vec![1, 2]);
+    }
+
+    #[test]
+    fn shrinking_capacity_drops_oldest_calls() {
+        let mut saved_calls = SavedCalls::default();
+        saved_calls.set_capacity(3);
+
+        for arity in 0..3 {
+            saved_calls.push(module_function_arity(arity));
+        }
+
+        assert_eq!(saved_calls.set_capacity(1), 3);
+
+        let arities: Vec<u8> = saved_calls.iter().map(|mfa| mfa.arity).collect();
+
+        assert_eq!(arities, vec![2]);
+    }
+}
@@ -0,0 +1,38 @@
+//! Hooks through which the runtime traces the calls of processes with `ProcessFlags::TraceCall`
+//! set.  The runtime decides which calls match its trace patterns and delivers the trace
+//! messages, so the process only needs to report calls and returns.
+use once_cell::sync::OnceCell;
+
+use crate::erts::process::Process;
+use crate::erts::term::prelude::Term;
+use crate::erts::ModuleFunctionArity;
+
+/// What to trace when a traced call returns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallTrace {
+    /// Trace the value returned from the called function
+    pub return_from: bool,
+    /// Trace the function returned to
+    pub return_to: bool,
+}
+
+pub struct CallTraceHooks {
+    /// Called with the arguments before a frame's native function is called.  Returns `None` if
+    /// the call is not traced.
+    pub call: fn(&Process, &ModuleFunctionArity, &[Term]) -> Option<CallTrace>,
+    /// Called after a traced call returns with the returned value and the function returned to,
+    /// if any.
+    pub returned: fn(&Process, CallTrace, &ModuleFunctionArity, Term, Option<ModuleFunctionArity>),
+}
+
+/// Sets the hooks called for processes with `ProcessFlags::TraceCall`.  Returns `false` if they
+/// were already set.
+pub fn set_call_trace_hooks(hooks: CallTraceHooks) -> bool {
+    CALL_TRACE_HOOKS.set(hooks).is_ok()
+}
+
+pub(super) fn call_trace_hooks() -> Option<&'static CallTraceHooks> {
+    CALL_TRACE_HOOKS.get()
+}
+
+static CALL_TRACE_HOOKS: OnceCell<CallTraceHooks> = OnceCell::new();
@@ -59,6 +59,20 @@ pub fn dump_atoms() {
     table.dump();
 }
 
+/// The number of atoms in the atom table
+pub fn atom_count() -> usize {
+    ATOMS.read().names.len()
+}
+
+/// Bytes used by the atom table for the atoms' names and the entries mapping them to and from IDs
+pub fn atom_memory() -> usize {
+    let table = ATOMS.read();
+    let name_bytes: usize = table.names.values().map(|name| name.len()).sum();
+    let entry_bytes = mem::size_of::<usize>() + mem::size_of::<&'static str>();
+
+    name_bytes + 2 * table.names.len() * entry_bytes
+}
+
 /// An interned string, represented in memory as a integer ID.
 ///
 /// This struct is simply a transparent wrapper around the ID.
@@ -17,6 +17,8 @@ use crate::erts::process::Process;
 use crate::erts::string::Encoding;
 use crate::erts::term::prelude::*;
 
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
 /// This is the header written alongside all procbin binaries in the heap,
 /// it owns the refcount and the raw binary data
 ///
@@ -138,6 +140,7 @@ impl ProcBin {
 
         unsafe {
             let block = sys_alloc::alloc(layout)?;
+            ALLOCATED_BYTES.fetch_add(layout.size(), atomic::Ordering::Relaxed);
             let len = s.len();
 
             let ptr: *mut u8 = block.ptr.as_ptr();
@@ -170,11 +173,24 @@ impl ProcBin {
         if self.inner().refc.fetch_sub(1, atomic::Ordering::Release) == 1 {
             atomic::fence(atomic::Ordering::Acquire);
             let inner = self.inner.as_ref();
-            let layout = Layout::for_value(&inner);
+            let layout = Layout::for_value(inner);
+            ALLOCATED_BYTES.fetch_sub(layout.size(), atomic::Ordering::Relaxed);
             sys_alloc::free(inner as *const _ as *mut u8, layout);
         }
     }
 
+    /// Bytes allocated, across all processes, for the data of reference-counted binaries that
+    /// has not been freed yet
+    pub fn allocated_bytes() -> usize {
+        ALLOCATED_BYTES.load(atomic::Ordering::Relaxed)
+    }
+
+    /// The number of `ProcBin`s, across all processes, sharing this binary's data
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        self.inner().refc.load(atomic::Ordering::Acquire)
+    }
+
     #[inline]
     pub fn full_byte_iter<'a>(&'a self) -> iter::Copied<slice::Iter<'a, u8>> {
         self.inner().as_bytes().iter().copied()
@@ -219,16 +235,12 @@ impl Clone for ProcBin {
 impl CloneToProcess for ProcBin {
     fn clone_to_process(&self, process: &Process) -> Term {
         let mut heap = process.acquire_heap();
-        let boxed = self.clone_to_heap(&mut heap).unwrap();
-        let ptr: *mut Self = boxed.dyn_cast();
-        self.inner().refc.fetch_add(1, atomic::Ordering::AcqRel);
-        // Reify a reference to the newly written clone, and push it
-        // on to the process virtual heap
-        let clone = unsafe { &*ptr };
-        process.virtual_alloc(clone);
-        boxed
+
+        self.clone_to_heap(&mut heap).unwrap()
     }
 
+    /// Only the header is copied: the clone shares the binary data, and holds its own reference
+    /// to it, which `heap` takes ownership of.
     fn clone_to_heap<A>(&self, heap: &mut A) -> AllocResult<Term>
     where
         A: ?Sized + TermAlloc,
@@ -238,14 +250,8 @@ impl CloneToProcess for ProcBin {
             let layout = Layout::new::<Self>();
             let ptr = heap.alloc_layout(layout)?.as_ptr() as *mut Self;
             // Write the binary header with an empty link
-            ptr::write(
-                ptr,
-                Self {
-                    header: self.header.clone(),
-                    inner: self.inner,
-                    link: LinkedListLink::new(),
-                },
-            );
+            ptr::write(ptr, self.clone());
+            heap.virtual_alloc_clone(Boxed::new_unchecked(ptr));
             // Reify result term
             Ok(ptr.into())
         }
@@ -128,6 +128,28 @@ impl SubBinary {
         }
     }
 
+    /// Like `to_heapbin_parts`, but for sub-binaries too large for a heap binary that refer to at
+    /// most a quarter of a `ProcBin`.  Copying those into a `ProcBin` of their own during garbage
+    /// collection lets the original be freed once nothing else refers to it.
+    ///
+    /// NOTE: You should not use this for any other purpose
+    pub(in crate::erts) fn to_procbin_parts(&self) -> Result<(*mut u8, usize), ()> {
+        match self.original.follow_moved().decode().unwrap() {
+            TypedTerm::ProcBin(bin)
+                if self.is_binary()
+                    && self.is_aligned()
+                    && !self.writable
+                    && HeapBin::MAX_SIZE < self.full_byte_len
+                    && self.full_byte_len <= bin.as_ref().full_byte_len() / 4 =>
+            {
+                let (_flags, bytes, len) = unsafe { self.to_raw_parts() };
+
+                Ok((bytes, len))
+            }
+            _ => Err(()),
+        }
+    }
+
     #[inline]
     unsafe fn to_raw_parts(&self) -> (BinaryFlags, *mut u8, usize) {
         let len = self.full_byte_len;
@@ -247,10 +269,36 @@ impl CloneToProcess for SubBinary {
     {
         let layout = Layout::new::<Self>();
         let size = layout.size();
-        match self.original.follow_moved().decode().unwrap() {
-            // For ref-counted binaries and those that are already on the process heap,
-            // we just need to copy the sub binary header, not the binary as well
-            TypedTerm::ProcBin(_bin) => {
+        let original = self.original.follow_moved();
+        match original.decode().unwrap() {
+            // A slice small enough for a heap binary is copied into one, so that it does not keep
+            // a large ref-counted binary alive in the heap it is cloned to
+            TypedTerm::ProcBin(bin) if !heap.is_owner(bin.as_ptr()) => {
+                if let Ok((_flags, bytes_ptr, bytes_len)) = self.to_heapbin_parts() {
+                    let bytes = unsafe { slice::from_raw_parts(bytes_ptr, bytes_len) };
+
+                    heap.heapbin_from_bytes(bytes).map(|bin| bin.into())
+                } else {
+                    // Otherwise, the sub binary shares the ref-counted binary through a clone of
+                    // its `ProcBin` in `heap`
+                    let new_bin = bin.as_ref().clone_to_heap(heap)?;
+                    unsafe {
+                        let ptr = heap.alloc_layout(layout)?.as_ptr() as *mut Self;
+                        ptr::write(
+                            ptr,
+                            Self {
+                                original: new_bin,
+                                ..*self
+                            },
+                        );
+
+                        Ok(ptr.into())
+                    }
+                }
+            }
+            // For ref-counted binaries that are already on the heap and literals, we just need
+            // to copy the sub binary header, not the binary as well
+            TypedTerm::ProcBin(_) | TypedTerm::BinaryLiteral(_) => {
                 // Allocate space for header and copy it
                 unsafe {
                     let ptr = heap.alloc_layout(layout)?.as_ptr() as *mut Self;
@@ -138,6 +138,11 @@ impl Heap for RegionHeap {
     fn high_water_mark(&self) -> *mut Term {
         self.high_water_mark as *mut Term
     }
+
+    #[inline]
+    fn virtual_alloc_clone(&mut self, bin: Boxed<ProcBin>) {
+        self.virtual_alloc(bin)
+    }
 }
 impl HeapAlloc for RegionHeap {
     /// Perform a heap allocation.
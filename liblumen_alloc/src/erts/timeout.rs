@@ -53,6 +53,14 @@ impl ReceiveTimeout {
         }
     }
 
+    /// The time the timeout ends at, when it neither is immediate nor never ends
+    pub fn end(&self) -> Option<u64> {
+        match self {
+            Self::Duration { start, duration } => Some((*start + *duration).as_millis() as u64),
+            _ => None,
+        }
+    }
+
     pub fn is_timed_out(&self, time: u64) -> bool {
         match self {
             Self::Infinity => false,
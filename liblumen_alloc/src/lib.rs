@@ -49,7 +49,7 @@ pub mod std_alloc;
 pub use liblumen_core::alloc::SysAlloc;
 
 /// A tracing allocator for tracking statistics about the allocator it wraps
-pub use self::stats_alloc::StatsAlloc;
+pub use self::stats_alloc::{Statistics, StatsAlloc};
 
 // An allocator that uses segmented sub-allocators to more efficiently manage
 // allocations of variable sizes that fall within predictable size ranges
@@ -89,7 +89,7 @@ unsafe impl<T: AllocRef + Send, H: Histogram + Clone + Default> Send for StatsAl
 /// This struct represents a snapshot of the stats gathered
 /// by an instances of `StatsAlloc`, and is used for display
 #[derive(Debug)]
-pub struct Statistics<H: Histogram + Clone + Default> {
+pub struct Statistics<H: Histogram + Clone + Default = DefaultHistogram> {
     alloc_calls: usize,
     dealloc_calls: usize,
     realloc_calls: usize,
@@ -99,6 +99,13 @@ pub struct Statistics<H: Histogram + Clone + Default> {
     tag: &'static str,
     histogram: H,
 }
+impl<H: Histogram + Clone + Default> Statistics<H> {
+    /// The number of bytes allocated that have not been freed yet
+    pub fn bytes_in_use(&self) -> usize {
+        self.total_bytes_alloced
+            .saturating_sub(self.total_bytes_freed)
+    }
+}
 impl<H: Histogram + Clone + Default> fmt::Display for Statistics<H> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "## Allocator Statistics (tag = {})", self.tag)?;
@@ -56,7 +56,7 @@ use crate::carriers::{MultiBlockCarrier, SingleBlockCarrier};
 use crate::carriers::{MultiBlockCarrierTree, SingleBlockCarrierList};
 use crate::erts::exception::AllocResult;
 use crate::sorted::{SortKey, SortOrder, SortedKeyAdapter};
-use crate::AllocatorInfo;
+use crate::{AllocatorInfo, Statistics};
 
 // The global instance of StandardAlloc
 cfg_if! {
@@ -110,6 +110,18 @@ pub fn alloc_info() -> AllocatorInfo {
     STD_ALLOC.info()
 }
 
+/// Gets the statistics of the global standard allocator, which are only tracked when the
+/// `instrument` feature is enabled
+#[cfg(feature = "instrument")]
+pub fn stats() -> Option<Statistics> {
+    Some(STD_ALLOC.stats())
+}
+
+#[cfg(not(feature = "instrument"))]
+pub fn stats() -> Option<Statistics> {
+    None
+}
+
 struct StandardAlloc {
     sbc_threshold: usize,
     sbc: CachePadded<SpinLock<SingleBlockCarrierList>>,
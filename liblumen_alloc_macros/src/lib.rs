@@ -5,7 +5,6 @@ extern crate proc_macro;
 
 mod size_classes;
 
-use core::mem;
 use proc_macro::TokenStream;
 
 use proc_macro2::Span;
@@ -17,6 +16,9 @@ use syn::{parse_macro_input, parse_quote};
 use syn::{Expr, ExprArray, ItemConst};
 use syn::{ExprLit, Lit, LitInt};
 
+/// The number of heap sizes that follow the Fibonacci-like sequence, before growing by 20%
+const FIBONACCI_HEAP_SIZES_LEN: usize = 23;
+
 #[proc_macro_derive(SizeClassIndex)]
 pub fn derive_size_class_index(input: TokenStream) -> TokenStream {
     self::size_classes::derive(input)
@@ -32,17 +34,15 @@ pub fn generate_heap_sizes(input: TokenStream) -> TokenStream {
         57
     };
     let mut heap_sizes: Vec<usize> = Vec::with_capacity(max_heap_sizes);
-    // The seed for the fibonacci sequence, the first heap size will be 232 words
-    let mut a = 90;
-    let mut b = 142;
-    for i in 0..19 {
-        let c = b + a;
-        heap_sizes.insert(i, c);
-        mem::swap(&mut a, &mut b);
-        b = c;
+    // The same table as BEAM: a Fibonacci-like sequence (each size is the sum of the previous two
+    // plus one) seeded with 12 and 38 words, so that 233 words, the default heap size, is the sixth
+    heap_sizes.push(12);
+    heap_sizes.push(38);
+    for i in 2..FIBONACCI_HEAP_SIZES_LEN {
+        heap_sizes.push(heap_sizes[i - 1] + heap_sizes[i - 2] + 1);
     }
     // Grow heap by 20% from this point on (at ~1M words)
-    for i in 19..max_heap_sizes {
+    for i in FIBONACCI_HEAP_SIZES_LEN..max_heap_sizes {
         let last_heap_size = heap_sizes[i - 1];
         heap_sizes.insert(i, last_heap_size + (last_heap_size / 5));
     }
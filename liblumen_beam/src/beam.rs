@@ -21,6 +21,8 @@
 //! * [org.elixir_lang.beam.Beam in IntelliJ Elixir](https://github.
 //!   com/KronicDeth/intellij-elixir/blob/master/src/org/elixir_lang/beam/Beam.kt) in Kotlin
 
+pub mod builder;
+pub mod disassembler;
 pub mod reader;
 
 pub use self::reader::chunk;
@@ -0,0 +1,583 @@
+//! Assembles BEAM files from structured data, instead of only writing back chunks that were read.
+//!
+//! # Examples
+//!
+//! Build a module with `answer/0` returning `42`:
+//!
+//!     use liblumen_beam::beam::builder::BeamBuilder;
+//!     use liblumen_beam::beam::disassembler::{Instruction, Module, Opcode, Operand};
+//!
+//!     let mut builder = BeamBuilder::new("answer");
+//!     builder.export("answer", 0, 2);
+//!     for instruction in vec![
+//!         Instruction { opcode: Opcode::Label, operands: vec![Operand::Unsigned(1)] },
+//!         Instruction {
+//!             opcode: Opcode::FuncInfo,
+//!             operands: vec![
+//!                 Operand::Atom("answer".to_string()),
+//!                 Operand::Atom("answer".to_string()),
+//!                 Operand::Unsigned(0),
+//!             ],
+//!         },
+//!         Instruction { opcode: Opcode::Label, operands: vec![Operand::Unsigned(2)] },
+//!         Instruction {
+//!             opcode: Opcode::Move,
+//!             operands: vec![Operand::Integer(42.into()), Operand::X(0)],
+//!         },
+//!         Instruction { opcode: Opcode::Return, operands: vec![] },
+//!     ] {
+//!         builder.push(&instruction).unwrap();
+//!     }
+//!
+//!     let beam = builder.build().unwrap();
+//!     let module = Module::from_beam_file(&beam).unwrap();
+//!     assert_eq!(vec![("answer".to_string(), 0)], module.exports);
+//!
+//! ## Alternative Implementations
+//!
+//! - [`beam_asm`](https://github.com/erlang/otp/blob/master/lib/compiler/src/beam_asm.erl) in
+//!   Erlang
+#[cfg(test)]
+mod test;
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt;
+
+use failure::Fail;
+use num::bigint::BigInt;
+
+use crate::beam::disassembler::operand::{
+    self, append_unsigned, append_value, EXTENDED_ALLOCATION_LIST, EXTENDED_FLOAT,
+    EXTENDED_FLOAT_REGISTER, EXTENDED_LIST, EXTENDED_LITERAL, EXTENDED_TYPED_REGISTER, TAG_A,
+    TAG_F, TAG_H, TAG_I, TAG_U, TAG_X, TAG_Y, TAG_Z,
+};
+use crate::beam::disassembler::{Fun, Instruction, Location, Opcode, Operand};
+use crate::beam::reader::chunk::{
+    AtomChunk, AttrChunk, CInfChunk, CodeChunk, ExpTChunk, FunTChunk, ImpTChunk, LitTChunk,
+    LocTChunk, RawChunk, StandardChunk, StrTChunk,
+};
+use crate::beam::reader::{parts, StandardBeamFile};
+use crate::serialization::etf;
+
+pub type Result<T> = std::result::Result<T, BuildError>;
+
+/// Errors which can occur when building a BEAM file
+#[derive(Debug)]
+pub enum BuildError {
+    TooLongAtomName(String),
+    TermEncode(etf::EncodeError),
+    OperandCount {
+        opcode: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    UndefinedFun(u32),
+    UndefinedLabel {
+        function: String,
+        arity: u32,
+        label: u32,
+    },
+}
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::BuildError::*;
+        match *self {
+            TooLongAtomName(ref name) => write!(f, "atom exceeds maximum byte size: {}", name),
+            TermEncode(ref x) => write!(f, "unable to encode term: {}", x),
+            OperandCount {
+                opcode,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} expects {} operands, but {} were given",
+                opcode, expected, actual
+            ),
+            UndefinedFun(index) => {
+                write!(f, "fun index {} was not added with BeamBuilder::fun", index)
+            }
+            UndefinedLabel {
+                ref function,
+                arity,
+                label,
+            } => write!(
+                f,
+                "{}/{} is exported at undefined label {}",
+                function, arity, label
+            ),
+        }
+    }
+}
+impl Fail for BuildError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        match *self {
+            BuildError::TermEncode(ref x) => Some(x),
+            _ => None,
+        }
+    }
+}
+impl From<etf::EncodeError> for BuildError {
+    fn from(x: etf::EncodeError) -> Self {
+        BuildError::TermEncode(x)
+    }
+}
+
+/// Builds a BEAM file that `erl` can load.
+///
+/// Atoms, imports, literals, strings and line locations are added to their chunks as
+/// [Instruction]s referencing them are pushed, the same way `beam_asm` does, so the instructions
+/// can use the resolved [Operand]s that the [disassembler](crate::beam::disassembler) produces.
+/// The `int_code_end` instruction is added by [BeamBuilder::build].
+pub struct BeamBuilder {
+    module: String,
+    atoms: Vec<String>,
+    atom_id_by_name: HashMap<String, parts::AtomId>,
+    imports: Vec<parts::Import>,
+    exports: Vec<parts::Export>,
+    locals: Vec<parts::Local>,
+    funs: Vec<parts::Function>,
+    literals: Vec<etf::Term>,
+    strings: Vec<u8>,
+    attributes: Vec<etf::Term>,
+    /// The file names after the implicit `<module>.erl` at index 0
+    file_names: Vec<String>,
+    /// `(file name index, line)` for each line index after the implicit undefined location at 0
+    lines: Vec<(usize, u32)>,
+    line_instruction_count: u32,
+    bytecode: Vec<u8>,
+    label_count: u32,
+    function_count: u32,
+    opcode_max: u32,
+}
+impl BeamBuilder {
+    pub fn new(module: &str) -> Self {
+        let mut builder = BeamBuilder {
+            module: module.to_string(),
+            atoms: Vec::new(),
+            atom_id_by_name: HashMap::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+            locals: Vec::new(),
+            funs: Vec::new(),
+            literals: Vec::new(),
+            strings: Vec::new(),
+            attributes: Vec::new(),
+            file_names: Vec::new(),
+            lines: Vec::new(),
+            line_instruction_count: 0,
+            bytecode: Vec::new(),
+            // Label 0 is reserved for "no failure label"
+            label_count: 1,
+            function_count: 0,
+            opcode_max: 0,
+        };
+        // The module name is always the first atom
+        builder.atom(module);
+
+        builder
+    }
+
+    /// Returns the one-based index of the atom `name`, adding it if needed.
+    pub fn atom(&mut self, name: &str) -> parts::AtomId {
+        if let Some(id) = self.atom_id_by_name.get(name) {
+            return *id;
+        }
+
+        self.atoms.push(name.to_string());
+        let id = self.atoms.len() as parts::AtomId;
+        self.atom_id_by_name.insert(name.to_string(), id);
+
+        id
+    }
+
+    /// Returns the index of the import of `module:function/arity`, adding it if needed.
+    pub fn import(&mut self, module: &str, function: &str, arity: parts::Arity) -> u32 {
+        let import = parts::Import {
+            module: self.atom(module),
+            function: self.atom(function),
+            arity,
+        };
+
+        match self.imports.iter().position(|existing| *existing == import) {
+            Some(index) => index as u32,
+            None => {
+                self.imports.push(import);
+
+                (self.imports.len() - 1) as u32
+            }
+        }
+    }
+
+    /// Exports `function/arity`, which is entered at `label`.
+    pub fn export(&mut self, function: &str, arity: parts::Arity, label: u32) {
+        let function = self.atom(function);
+        self.exports.push(parts::Export {
+            function,
+            arity,
+            label,
+        });
+    }
+
+    /// Adds the unexported `function/arity`, which is entered at `label`, to the `"LocT"` chunk.
+    pub fn local(&mut self, function: &str, arity: parts::Arity, label: u32) {
+        let function = self.atom(function);
+        self.locals.push(parts::Local {
+            function,
+            arity,
+            label,
+        });
+    }
+
+    /// Adds the anonymous function `function/arity` at `label`, which closes over `num_free`
+    /// variables.  The returned [Fun] is the operand for `make_fun2` and `make_fun3`.
+    pub fn fun(&mut self, function: &str, arity: parts::Arity, label: u32, num_free: u32) -> Fun {
+        let index = self.funs.len() as u32;
+        let old_uniq = 0;
+        let function = self.atom(function);
+        self.funs.push(parts::Function {
+            function,
+            arity,
+            label,
+            index,
+            num_free,
+            old_uniq,
+        });
+
+        Fun {
+            label,
+            index,
+            old_uniq,
+            num_free,
+        }
+    }
+
+    /// Returns the index of `term` in the `"LitT"` chunk, adding it if needed.
+    pub fn literal(&mut self, term: etf::Term) -> u32 {
+        match self.literals.iter().position(|existing| *existing == term) {
+            Some(index) => index as u32,
+            None => {
+                self.literals.push(term);
+
+                (self.literals.len() - 1) as u32
+            }
+        }
+    }
+
+    /// Adds the module attribute `{name, values}` returned by `module_info(attributes)`.
+    /// `-name(Value).` in Erlang is `values` of `[Value]`.
+    pub fn attribute(&mut self, name: &str, values: Vec<etf::Term>) {
+        self.attributes.push(etf::Term::from(etf::Tuple::from(vec![
+            etf::Term::from(etf::Atom::from(name)),
+            etf::Term::from(etf::List::from(values)),
+        ])));
+    }
+
+    /// Encodes `instruction` at the end of the `"Code"` chunk.
+    pub fn push(&mut self, instruction: &Instruction) -> Result<()> {
+        let opcode = instruction.opcode;
+        let actual = instruction.operands.iter().map(encoded_len).sum();
+        if actual != opcode.arity() {
+            return Err(BuildError::OperandCount {
+                opcode: opcode.name(),
+                expected: opcode.arity(),
+                actual,
+            });
+        }
+
+        match opcode {
+            Opcode::Label => {
+                if let Some(label) = instruction.label() {
+                    self.label_count = self.label_count.max(label as u32 + 1);
+                }
+            }
+            Opcode::FuncInfo => self.function_count += 1,
+            Opcode::Line => self.line_instruction_count += 1,
+            _ => (),
+        }
+        self.opcode_max = self.opcode_max.max(opcode as u32);
+
+        let mut bytes = vec![opcode as u8];
+        for operand in &instruction.operands {
+            self.append_operand(&mut bytes, operand)?;
+        }
+        self.bytecode.extend_from_slice(&bytes);
+
+        Ok(())
+    }
+
+    /// Builds the BEAM file, with chunks in the same order as `erlc`.
+    pub fn build(mut self) -> Result<StandardBeamFile> {
+        for export in &self.exports {
+            if self.label_count <= export.label {
+                return Err(BuildError::UndefinedLabel {
+                    function: self.atoms[export.function as usize - 1].clone(),
+                    arity: export.arity,
+                    label: export.label,
+                });
+            }
+        }
+        if let Some(atom) = self
+            .atoms
+            .iter()
+            .find(|atom| u8::MAX as usize <= atom.len())
+        {
+            return Err(BuildError::TooLongAtomName(atom.clone()));
+        }
+
+        self.push(&Instruction {
+            opcode: Opcode::IntCodeEnd,
+            operands: Vec::new(),
+        })?;
+
+        let mut beam = StandardBeamFile::new();
+        beam.push_chunk(StandardChunk::Atom(AtomChunk {
+            is_unicode: true,
+            atoms: self
+                .atoms
+                .iter()
+                .map(|name| parts::Atom { name: name.clone() })
+                .collect(),
+        }));
+        beam.push_chunk(StandardChunk::Code(CodeChunk {
+            info_size: CODE_INFO_SIZE,
+            version: CODE_VERSION,
+            opcode_max: self.opcode_max,
+            label_count: self.label_count,
+            function_count: self.function_count,
+            bytecode: self.bytecode,
+        }));
+        beam.push_chunk(StandardChunk::StrT(StrTChunk {
+            strings: self.strings,
+        }));
+        beam.push_chunk(StandardChunk::ImpT(ImpTChunk {
+            imports: self.imports,
+        }));
+        beam.push_chunk(StandardChunk::ExpT(ExpTChunk {
+            exports: self.exports,
+        }));
+        if !self.funs.is_empty() {
+            beam.push_chunk(StandardChunk::FunT(FunTChunk {
+                functions: self.funs,
+            }));
+        }
+        if !self.literals.is_empty() {
+            let mut literals = Vec::with_capacity(self.literals.len());
+            for literal in &self.literals {
+                literals.push(term_to_binary(literal)?);
+            }
+            beam.push_chunk(StandardChunk::LitT(LitTChunk { literals }));
+        }
+        beam.push_chunk(StandardChunk::LocT(LocTChunk {
+            locals: self.locals,
+        }));
+        beam.push_chunk(StandardChunk::Attr(AttrChunk {
+            term: term_to_binary(&etf::Term::from(etf::List::from(self.attributes)))?,
+        }));
+        beam.push_chunk(StandardChunk::CInf(CInfChunk {
+            term: term_to_binary(&etf::Term::from(etf::List::nil()))?,
+        }));
+        if 0 < self.line_instruction_count {
+            let data = line_chunk_data(self.line_instruction_count, &self.lines, &self.file_names);
+            beam.push_chunk(StandardChunk::Unknown(RawChunk { id: *b"Line", data }));
+        }
+
+        Ok(beam)
+    }
+
+    fn append_operand(&mut self, bytes: &mut Vec<u8>, operand: &Operand) -> Result<()> {
+        match *operand {
+            Operand::Unsigned(value) => append_unsigned(bytes, TAG_U, value),
+            Operand::Integer(ref value) => append_value(bytes, TAG_I, value),
+            Operand::Atom(ref name) => {
+                let id = self.atom(name);
+                append_unsigned(bytes, TAG_A, id as u64)
+            }
+            Operand::Nil => append_unsigned(bytes, TAG_A, 0),
+            Operand::X(n) => append_unsigned(bytes, TAG_X, n),
+            Operand::Y(n) => append_unsigned(bytes, TAG_Y, n),
+            Operand::Label(n) => append_unsigned(bytes, TAG_F, n),
+            Operand::Character(n) => append_unsigned(bytes, TAG_H, n),
+            Operand::FloatRegister(n) => {
+                append_extended(bytes, EXTENDED_FLOAT_REGISTER);
+                append_unsigned(bytes, TAG_U, n)
+            }
+            Operand::Float(value) => {
+                append_extended(bytes, EXTENDED_FLOAT);
+                bytes.extend_from_slice(&value.to_be_bytes());
+            }
+            Operand::List(ref operands) => {
+                append_extended(bytes, EXTENDED_LIST);
+                append_unsigned(bytes, TAG_U, operands.len() as u64);
+                for operand in operands {
+                    self.append_operand(bytes, operand)?;
+                }
+            }
+            Operand::Allocation(ref allocations) => {
+                append_extended(bytes, EXTENDED_ALLOCATION_LIST);
+                append_unsigned(bytes, TAG_U, allocations.len() as u64);
+                for (allocation, count) in allocations {
+                    append_unsigned(bytes, TAG_U, *allocation as u64);
+                    append_unsigned(bytes, TAG_U, *count);
+                }
+            }
+            Operand::Literal(ref term) => {
+                let index = self.literal(term.clone());
+                append_extended(bytes, EXTENDED_LITERAL);
+                append_unsigned(bytes, TAG_U, index as u64)
+            }
+            Operand::TypedRegister {
+                ref register,
+                type_index,
+            } => {
+                append_extended(bytes, EXTENDED_TYPED_REGISTER);
+                self.append_operand(bytes, register)?;
+                append_unsigned(bytes, TAG_U, type_index)
+            }
+            Operand::ExternalFunction(ref external_function) => {
+                let index = self.import(
+                    &external_function.module,
+                    &external_function.function,
+                    external_function.arity,
+                );
+                append_unsigned(bytes, TAG_U, index as u64)
+            }
+            Operand::Fun(ref fun) => {
+                if self.funs.len() <= fun.index as usize {
+                    return Err(BuildError::UndefinedFun(fun.index));
+                }
+                append_unsigned(bytes, TAG_U, fun.index as u64)
+            }
+            Operand::Location(ref location) => {
+                let index = self.line(location.as_ref());
+                append_unsigned(bytes, TAG_U, index)
+            }
+            Operand::String(ref string) => {
+                let offset = self.string(string);
+                append_unsigned(bytes, TAG_U, offset)
+            }
+            Operand::Bitstring {
+                bytes: ref string,
+                bits,
+            } => {
+                let len = operand::bits_to_bytes(bits) as usize;
+                let offset = self.string(&string[..len.min(string.len())]);
+                append_unsigned(bytes, TAG_U, bits);
+                append_unsigned(bytes, TAG_U, offset)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the line index of `location`, adding it if needed.  Index 0 has no location.
+    fn line(&mut self, location: Option<&Location>) -> u64 {
+        let location = match location {
+            Some(location) => location,
+            None => return 0,
+        };
+
+        let file_index = if location.file == format!("{}.erl", self.module) {
+            0
+        } else {
+            match self
+                .file_names
+                .iter()
+                .position(|name| *name == location.file)
+            {
+                Some(index) => index + 1,
+                None => {
+                    self.file_names.push(location.file.clone());
+                    self.file_names.len()
+                }
+            }
+        };
+        let line = (file_index, location.line);
+
+        match self.lines.iter().position(|existing| *existing == line) {
+            Some(index) => index as u64 + 1,
+            None => {
+                self.lines.push(line);
+                self.lines.len() as u64
+            }
+        }
+    }
+
+    /// Returns the offset of `string` in the `"StrT"` chunk, adding it if needed.
+    fn string(&mut self, string: &[u8]) -> u64 {
+        if string.is_empty() {
+            return 0;
+        }
+
+        match self
+            .strings
+            .windows(string.len())
+            .position(|window| window == string)
+        {
+            Some(offset) => offset as u64,
+            None => {
+                let offset = self.strings.len();
+                self.strings.extend_from_slice(string);
+                offset as u64
+            }
+        }
+    }
+}
+
+// Private
+
+/// The size of the `"Code"` chunk header fields after `info_size`
+const CODE_INFO_SIZE: u32 = 16;
+/// The instruction set version that `erl` supports
+const CODE_VERSION: u32 = 0;
+
+fn append_extended(bytes: &mut Vec<u8>, extended_tag: u8) {
+    bytes.push((extended_tag << 4) | TAG_Z);
+}
+
+/// The number of operands `operand` is encoded as.  [Operand::Bitstring] is encoded as the
+/// `bs_match_string` bit count and string offset.
+fn encoded_len(operand: &Operand) -> usize {
+    match *operand {
+        Operand::Bitstring { .. } => 2,
+        _ => 1,
+    }
+}
+
+fn line_chunk_data(
+    line_instruction_count: u32,
+    lines: &[(usize, u32)],
+    file_names: &[String],
+) -> Vec<u8> {
+    let mut data = Vec::new();
+    // version and flags
+    data.extend_from_slice(&0_u32.to_be_bytes());
+    data.extend_from_slice(&0_u32.to_be_bytes());
+    data.extend_from_slice(&line_instruction_count.to_be_bytes());
+    data.extend_from_slice(&(lines.len() as u32).to_be_bytes());
+    data.extend_from_slice(&(file_names.len() as u32).to_be_bytes());
+
+    let mut current_file_index = 0;
+    for &(file_index, line) in lines {
+        if file_index != current_file_index {
+            append_unsigned(&mut data, TAG_A, file_index as u64);
+            current_file_index = file_index;
+        }
+        append_value(&mut data, TAG_I, &BigInt::from(line));
+    }
+
+    for file_name in file_names {
+        let len: u16 = file_name.len().try_into().unwrap_or(u16::MAX);
+        data.extend_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(&file_name.as_bytes()[..len as usize]);
+    }
+
+    data
+}
+
+fn term_to_binary(term: &etf::Term) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    term.encode(&mut buf)?;
+
+    Ok(buf)
+}
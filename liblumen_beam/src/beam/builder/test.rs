@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use super::*;
+use crate::beam::disassembler::Module;
+use crate::beam::reader::chunk::Chunk;
+
+#[test]
+fn round_trip() {
+    let original = StandardBeamFile::from_file(test_file("reader/test.beam")).unwrap();
+    let module = Module::from_beam_file(&original).unwrap();
+
+    let mut builder = BeamBuilder::new(&module.name);
+    for (function, arity) in &module.exports {
+        let entry = module
+            .functions
+            .iter()
+            .find(|f| f.name == *function && f.arity == *arity)
+            .unwrap()
+            .entry;
+        builder.export(function, *arity, entry as u32);
+    }
+    // '-hello/1-fun-0-'/1 closes over the name and is entered at label 8
+    let fun = builder.fun("-hello/1-fun-0-", 1, 8, 1);
+    assert_eq!(0, fun.index);
+    builder.local("-hello/1-fun-0-", 1, 8);
+    for function in &module.functions {
+        for instruction in &function.instructions {
+            builder.push(instruction).unwrap();
+        }
+    }
+
+    let mut bytes = Vec::new();
+    builder.build().unwrap().to_writer(&mut bytes).unwrap();
+    let built = StandardBeamFile::from_reader(&bytes[..]).unwrap();
+    assert_eq!(
+        vec![
+            "AtU8", "Code", "StrT", "ImpT", "ExpT", "FunT", "LitT", "LocT", "Attr", "CInf", "Line"
+        ],
+        built
+            .chunks()
+            .iter()
+            .map(|chunk| std::str::from_utf8(chunk.id()).unwrap())
+            .collect::<Vec<_>>()
+    );
+
+    // `old_uniq` is only used when reloading code, so the builder always uses 0
+    assert_eq!(
+        module
+            .to_string()
+            .replace("{make_fun2,{f,8},0,38182595,1}", "{make_fun2,{f,8},0,0,1}"),
+        Module::from_beam_file(&built).unwrap().to_string()
+    );
+}
+
+#[test]
+fn operand_count() {
+    let mut builder = BeamBuilder::new("test");
+    let result = builder.push(&Instruction {
+        opcode: Opcode::Move,
+        operands: vec![Operand::X(0)],
+    });
+
+    match result {
+        Err(BuildError::OperandCount {
+            opcode: "move",
+            expected: 2,
+            actual: 1,
+        }) => (),
+        other => panic!("expected operand count error, got {:?}", other),
+    }
+}
+
+fn test_file(name: &str) -> PathBuf {
+    let mut path = PathBuf::from("tests/testdata");
+    path.push(name);
+    path
+}
@@ -0,0 +1,532 @@
+//! Disassembles the byte code in the [CodeChunk](crate::beam::chunk::CodeChunk) into
+//! [Instruction]s and prints them in the same format as `erlc -S`.
+//!
+//! # Examples
+//!
+//!     use liblumen_beam::beam::disassembler::Module;
+//!
+//!     let module = Module::from_file("tests/testdata/reader/test.beam").unwrap();
+//!     assert_eq!("test", module.name);
+//!
+//!     let listing = module.to_string();
+//!     assert!(listing.starts_with("{module, test}.  %% version = 0\n"));
+//!     assert!(listing.contains("    {call_ext_only,2,{extfunc,io,format,2}}.\n"));
+//!
+//! ## Alternative Implementations
+//!
+//! - [`beam_disasm`](http://erlang.org/doc/man/beam_disasm.html) in Erlang
+//! - [`org.elixir_lang.beam.chunk.code.Operation#assembly` in IntelliJ Elixir](https://github.com/KronicDeth/intellij-elixir/blob/
+//!   2f5c826040681e258e98c3e2f02b25985cd0766b/src/org/elixir_lang/beam/chunk/code/Operation.kt#
+//!   L23-L164) in Kotlin
+mod format;
+mod instruction;
+mod opcode;
+pub(crate) mod operand;
+
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+use std::fmt::{self, Display};
+use std::io::Cursor;
+use std::path::Path;
+
+use failure::Fail;
+
+use crate::beam::reader::chunk::{AtomChunk, CodeChunk, StandardChunk};
+use crate::beam::reader::{parts, ReadError, StandardBeamFile};
+use crate::serialization::etf;
+
+pub use self::instruction::Instruction;
+pub use self::opcode::Opcode;
+pub use self::operand::{Allocation, ExternalFunction, Fun, Location, Operand};
+
+use self::operand::Decoder;
+
+pub type Result<T> = std::result::Result<T, DisassembleError>;
+
+/// Errors which can occur when disassembling a BEAM file
+#[derive(Debug)]
+pub enum DisassembleError {
+    BeamFile(ReadError),
+    MissingChunk(&'static str),
+    LiteralDecode(Box<etf::DecodeError>),
+    UnknownOpcode {
+        number: u8,
+        offset: usize,
+    },
+    UnexpectedTag {
+        tag: u8,
+        offset: usize,
+    },
+    ValueTooLarge {
+        offset: usize,
+    },
+    UnexpectedOperand {
+        opcode: &'static str,
+        operand: String,
+    },
+    IndexOutOfRange {
+        table: &'static str,
+        index: u64,
+    },
+    UnexpectedEnd,
+}
+impl Display for DisassembleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::DisassembleError::*;
+        match *self {
+            BeamFile(ref x) => write!(f, "invalid beam file: {}", x),
+            MissingChunk(chunk) => write!(f, "missing {} chunk", chunk),
+            LiteralDecode(ref x) => write!(f, "unable to decode literal: {}", x),
+            UnknownOpcode { number, offset } => {
+                write!(f, "unknown opcode {} at byte {}", number, offset)
+            }
+            UnexpectedTag { tag, offset } => write!(f, "unexpected tag {} at byte {}", tag, offset),
+            ValueTooLarge { offset } => {
+                write!(f, "value at byte {} does not fit in 64 bits", offset)
+            }
+            UnexpectedOperand {
+                opcode,
+                ref operand,
+            } => write!(f, "unexpected operand {} for {}", operand, opcode),
+            IndexOutOfRange { table, index } => {
+                write!(f, "{} index {} is out of range", table, index)
+            }
+            UnexpectedEnd => write!(f, "code ended in the middle of an instruction"),
+        }
+    }
+}
+impl Fail for DisassembleError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        match *self {
+            DisassembleError::BeamFile(ref x) => Some(x),
+            DisassembleError::LiteralDecode(ref x) => Some(&**x),
+            _ => None,
+        }
+    }
+}
+impl From<ReadError> for DisassembleError {
+    fn from(x: ReadError) -> Self {
+        DisassembleError::BeamFile(x)
+    }
+}
+impl From<etf::DecodeError> for DisassembleError {
+    fn from(x: etf::DecodeError) -> Self {
+        DisassembleError::LiteralDecode(Box::new(x))
+    }
+}
+
+/// A disassembled module.
+///
+/// `Display` prints the same listing as `erlc -S`.
+#[derive(Debug, PartialEq)]
+pub struct Module {
+    pub name: String,
+    /// The instruction set version from the `"Code"` chunk
+    pub version: u32,
+    /// The exported functions as `(name, arity)`, sorted like `erlc -S`.
+    pub exports: Vec<(String, u32)>,
+    /// The module attributes, without the `vsn` that is added when the module is assembled.
+    pub attributes: Vec<etf::Term>,
+    pub label_count: u32,
+    pub functions: Vec<Function>,
+}
+impl Module {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let beam = StandardBeamFile::from_file(path)?;
+
+        Self::from_beam_file(&beam)
+    }
+
+    pub fn from_beam_file(beam: &StandardBeamFile) -> Result<Self> {
+        let atoms = match beam.atoms() {
+            Some(StandardChunk::Atom(atoms)) => atoms,
+            _ => return Err(DisassembleError::MissingChunk("Atom")),
+        };
+        let code = match beam.get_chunk(b"Code") {
+            Some(StandardChunk::Code(code)) => code,
+            _ => return Err(DisassembleError::MissingChunk("Code")),
+        };
+        let exports = match beam.get_chunk(b"ExpT") {
+            Some(StandardChunk::ExpT(exports)) => &exports.exports[..],
+            _ => return Err(DisassembleError::MissingChunk("ExpT")),
+        };
+        let name = atoms.atoms.first().map(|atom| atom.name.clone()).ok_or(
+            DisassembleError::IndexOutOfRange {
+                table: "atom",
+                index: 1,
+            },
+        )?;
+
+        let tables = Tables::from_beam_file(beam, atoms, &name)?;
+        let instructions = decode(code, &tables)?;
+
+        let mut exports = exports
+            .iter()
+            .map(|export| {
+                Ok((
+                    tables.atom(export.function as u64)?.to_string(),
+                    export.arity,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        exports.sort();
+
+        let attributes = match beam.get_chunk(b"Attr") {
+            Some(StandardChunk::Attr(attr)) => match etf::Term::decode(Cursor::new(&attr.term))? {
+                etf::Term::List(list) => list
+                    .elements
+                    .into_iter()
+                    .filter(|attribute| !is_vsn(attribute))
+                    .collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+
+        Ok(Module {
+            name,
+            version: code.version,
+            exports,
+            attributes,
+            label_count: code.label_count,
+            functions: split_functions(instructions)?,
+        })
+    }
+}
+impl Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{module, ")?;
+        format::write_atom(f, &self.name)?;
+        writeln!(f, "}}.  %% version = {}", self.version)?;
+
+        f.write_str("\n{exports, [")?;
+        for (i, (name, arity)) in self.exports.iter().enumerate() {
+            if i != 0 {
+                f.write_str(",")?;
+            }
+            f.write_str("{")?;
+            format::write_atom(f, name)?;
+            write!(f, ",{}}}", arity)?;
+        }
+        f.write_str("]}.\n")?;
+
+        f.write_str("\n{attributes, [")?;
+        format::write_elements(f, &self.attributes)?;
+        f.write_str("]}.\n")?;
+
+        writeln!(f, "\n{{labels, {}}}.", self.label_count)?;
+
+        for function in &self.functions {
+            write!(f, "\n\n{}", function)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The instructions of one function, starting with the label before its `func_info`.
+#[derive(Debug, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub arity: u32,
+    /// The label after `func_info` where calls enter the function.
+    pub entry: u64,
+    pub instructions: Vec<Instruction>,
+}
+impl Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{function, ")?;
+        format::write_atom(f, &self.name)?;
+        writeln!(f, ", {}, {}}}.", self.arity, self.entry)?;
+
+        for instruction in &self.instructions {
+            let indent = if instruction.opcode == Opcode::Label {
+                "  "
+            } else {
+                "    "
+            };
+            writeln!(f, "{}{}.", indent, instruction)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes all the instructions in `code`, up to and including `int_code_end`.
+pub fn decode(code: &CodeChunk, tables: &Tables) -> Result<Vec<Instruction>> {
+    let mut decoder = Decoder::new(&code.bytecode);
+    let mut instructions = Vec::new();
+
+    while !decoder.is_empty() {
+        let instruction = Instruction::decode(&mut decoder, tables)?;
+        let is_end = instruction.opcode == Opcode::IntCodeEnd;
+        instructions.push(instruction);
+
+        if is_end {
+            break;
+        }
+    }
+
+    Ok(instructions)
+}
+
+/// The chunks that instruction operands index into.
+pub struct Tables<'a> {
+    atoms: &'a [parts::Atom],
+    imports: &'a [parts::Import],
+    funs: &'a [parts::Function],
+    literals: Vec<etf::Term>,
+    strings: &'a [u8],
+    locations: Option<Vec<Option<Location>>>,
+}
+impl<'a> Tables<'a> {
+    /// Collects the tables from `beam`.  Only the `"Atom"` chunk is required, so that modules
+    /// without literals, anonymous functions or line information can still be disassembled.
+    pub fn from_beam_file(
+        beam: &'a StandardBeamFile,
+        atoms: &'a AtomChunk,
+        module: &str,
+    ) -> Result<Self> {
+        let imports = match beam.get_chunk(b"ImpT") {
+            Some(StandardChunk::ImpT(imports)) => &imports.imports[..],
+            _ => &[],
+        };
+        let funs = match beam.get_chunk(b"FunT") {
+            Some(StandardChunk::FunT(funs)) => &funs.functions[..],
+            _ => &[],
+        };
+        let literals = match beam.get_chunk(b"LitT") {
+            Some(StandardChunk::LitT(literals)) => literals
+                .literals
+                .iter()
+                .map(|literal| Ok(etf::Term::decode(Cursor::new(literal))?))
+                .collect::<Result<Vec<_>>>()?,
+            _ => Vec::new(),
+        };
+        let strings = match beam.get_chunk(b"StrT") {
+            Some(StandardChunk::StrT(strings)) => &strings.strings[..],
+            _ => &[],
+        };
+        let locations = match beam.get_chunk(b"Line") {
+            Some(StandardChunk::Unknown(line)) => Some(decode_locations(&line.data, module)?),
+            _ => None,
+        };
+
+        Ok(Tables {
+            atoms: &atoms.atoms,
+            imports,
+            funs,
+            literals,
+            strings,
+            locations,
+        })
+    }
+
+    /// The atom with the one-based `index`
+    fn atom(&self, index: u64) -> Result<&'a str> {
+        index
+            .checked_sub(1)
+            .and_then(|zero_based| self.atoms.get(zero_based as usize))
+            .map(|atom| atom.name.as_str())
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "atom",
+                index,
+            })
+    }
+
+    fn fun(&self, index: u64) -> Result<Fun> {
+        let fun = self
+            .funs
+            .get(index as usize)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "fun",
+                index,
+            })?;
+
+        Ok(Fun {
+            label: fun.label,
+            index: fun.index,
+            old_uniq: fun.old_uniq,
+            num_free: fun.num_free,
+        })
+    }
+
+    fn has_lines(&self) -> bool {
+        self.locations.is_some()
+    }
+
+    fn import(&self, index: u64) -> Result<ExternalFunction> {
+        let import = self
+            .imports
+            .get(index as usize)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "import",
+                index,
+            })?;
+
+        Ok(ExternalFunction {
+            module: self.atom(import.module as u64)?.to_string(),
+            function: self.atom(import.function as u64)?.to_string(),
+            arity: import.arity,
+        })
+    }
+
+    fn literal(&self, index: u64) -> Result<&etf::Term> {
+        self.literals
+            .get(index as usize)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "literal",
+                index,
+            })
+    }
+
+    fn location(&self, index: u64) -> Result<Option<Location>> {
+        self.locations
+            .as_ref()
+            .and_then(|locations| locations.get(index as usize))
+            .cloned()
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "line",
+                index,
+            })
+    }
+
+    fn string(&self, offset: u64, len: u64) -> Result<&'a [u8]> {
+        self.strings
+            .get(offset as usize..(offset + len) as usize)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "string",
+                index: offset,
+            })
+    }
+}
+
+// Private
+
+const LINE_HEADER_LEN: usize = 20;
+
+/// Decodes the `"Line"` chunk into the location for each index used by `line` instructions.
+///
+/// Index 0 has no location.  File name index 0 is the module's own source file, which is not
+/// stored in the chunk.
+fn decode_locations(data: &[u8], module: &str) -> Result<Vec<Option<Location>>> {
+    if data.len() < LINE_HEADER_LEN {
+        return Err(DisassembleError::UnexpectedEnd);
+    }
+    let header_u32 = |index: usize| {
+        u32::from_be_bytes(data[index * 4..index * 4 + 4].try_into().unwrap()) as usize
+    };
+    let line_count = header_u32(3);
+    let file_name_count = header_u32(4);
+
+    let mut decoder = Decoder::new(&data[LINE_HEADER_LEN..]);
+    let mut items = Vec::with_capacity(line_count);
+    let mut file_index = 0;
+    while items.len() < line_count {
+        let offset = decoder.offset();
+        match decoder.read_tagged()? {
+            (operand::TAG_A, index) => file_index = index as usize,
+            (operand::TAG_I, line) => items.push((file_index, line as u32)),
+            (tag, _) => return Err(DisassembleError::UnexpectedTag { tag, offset }),
+        }
+    }
+
+    let mut file_names = vec![format!("{}.erl", module)];
+    let mut remaining = decoder.remaining();
+    for _ in 0..file_name_count {
+        if remaining.len() < 2 {
+            return Err(DisassembleError::UnexpectedEnd);
+        }
+        let (len_bytes, after_len) = remaining.split_at(2);
+        let len = u16::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+        if after_len.len() < len {
+            return Err(DisassembleError::UnexpectedEnd);
+        }
+        let (name_bytes, after_name) = after_len.split_at(len);
+        file_names.push(String::from_utf8_lossy(name_bytes).into_owned());
+        remaining = after_name;
+    }
+
+    let mut locations = vec![None];
+    for (file_index, line) in items {
+        let file = file_names
+            .get(file_index)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "file name",
+                index: file_index as u64,
+            })?;
+        locations.push(Some(Location {
+            file: file.clone(),
+            line,
+        }));
+    }
+
+    Ok(locations)
+}
+
+fn is_vsn(attribute: &etf::Term) -> bool {
+    match attribute {
+        etf::Term::Tuple(tuple) => match tuple.elements.first() {
+            Some(etf::Term::Atom(atom)) => atom.name == "vsn",
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Splits `instructions` at the label before each `func_info`.  `int_code_end` is dropped like in
+/// `erlc -S`.
+fn split_functions(instructions: Vec<Instruction>) -> Result<Vec<Function>> {
+    let func_info_indices: Vec<usize> = instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| instruction.opcode == Opcode::FuncInfo)
+        .map(|(index, _)| index)
+        .collect();
+    let starts: Vec<usize> = func_info_indices
+        .iter()
+        .map(|&func_info_index| {
+            instructions[..func_info_index]
+                .iter()
+                .rposition(|instruction| instruction.opcode == Opcode::Label)
+                .unwrap_or(func_info_index)
+        })
+        .collect();
+
+    let mut functions = Vec::with_capacity(starts.len());
+    for (i, (&start, &func_info_index)) in starts.iter().zip(&func_info_indices).enumerate() {
+        let end = starts.get(i + 1).cloned().unwrap_or(instructions.len());
+        let function_instructions: Vec<Instruction> = instructions[start..end]
+            .iter()
+            .filter(|instruction| instruction.opcode != Opcode::IntCodeEnd)
+            .cloned()
+            .collect();
+
+        let func_info = &instructions[func_info_index];
+        let (name, arity) = match &func_info.operands[..] {
+            [_, Operand::Atom(name), Operand::Unsigned(arity)] => (name.clone(), *arity as u32),
+            [_, operand, _] => {
+                return Err(DisassembleError::UnexpectedOperand {
+                    opcode: Opcode::FuncInfo.name(),
+                    operand: operand.to_string(),
+                })
+            }
+            _ => unreachable!(),
+        };
+        let entry = instructions[func_info_index..end]
+            .iter()
+            .find_map(Instruction::label)
+            .unwrap_or(0);
+
+        functions.push(Function {
+            name,
+            arity,
+            entry,
+            instructions: function_instructions,
+        });
+    }
+
+    Ok(functions)
+}
@@ -0,0 +1,196 @@
+//! Writes terms the way `io:format("~p", [Term])` does, which is how `erlc -S` prints instructions.
+//!
+//! Unlike `~p`, long terms are never wrapped onto multiple lines.
+
+use std::fmt::{self, Write};
+
+use crate::serialization::etf::Term;
+
+const RESERVED_WORDS: &[&str] = &[
+    "after", "and", "andalso", "band", "begin", "bnot", "bor", "bsl", "bsr", "bxor", "case",
+    "catch", "cond", "div", "end", "fun", "if", "let", "not", "of", "or", "orelse", "receive",
+    "rem", "try", "when", "xor",
+];
+
+/// Writes `name` as an atom, only quoting it when it would not parse as a bare atom.
+pub fn write_atom<W: Write>(w: &mut W, name: &str) -> fmt::Result {
+    let mut chars = name.chars();
+    let is_bare = match chars.next() {
+        Some(first) => {
+            is_lowercase(first)
+                && chars.all(|c| {
+                    is_lowercase(c) || is_uppercase(c) || c.is_ascii_digit() || c == '_' || c == '@'
+                })
+                && !RESERVED_WORDS.contains(&name)
+        }
+        None => false,
+    };
+
+    if is_bare {
+        w.write_str(name)
+    } else {
+        w.write_char('\'')?;
+        for c in name.chars() {
+            write_escaped_char(w, c, '\'')?;
+        }
+        w.write_char('\'')
+    }
+}
+
+/// Writes `bytes` as a double-quoted string
+pub fn write_string<W: Write>(w: &mut W, bytes: &[u8]) -> fmt::Result {
+    w.write_char('"')?;
+    for &byte in bytes {
+        write_escaped_char(w, byte as char, '"')?;
+    }
+    w.write_char('"')
+}
+
+/// Writes `bytes` as a binary, using the string syntax when all the bytes are printable.
+pub fn write_binary<W: Write>(w: &mut W, bytes: &[u8], tail_bits: u8) -> fmt::Result {
+    let (whole, tail) = if tail_bits == 8 || bytes.is_empty() {
+        (bytes, None)
+    } else {
+        let (last, whole) = bytes.split_last().unwrap();
+        (whole, Some(*last >> (8 - tail_bits)))
+    };
+
+    w.write_str("<<")?;
+    if !whole.is_empty() && whole.iter().all(|&byte| is_printable(byte as u32)) {
+        write_string(w, whole)?;
+    } else {
+        for (i, byte) in whole.iter().enumerate() {
+            if i != 0 {
+                w.write_char(',')?;
+            }
+            write!(w, "{}", byte)?;
+        }
+    }
+    if let Some(tail) = tail {
+        if !whole.is_empty() {
+            w.write_char(',')?;
+        }
+        write!(w, "{}:{}", tail, tail_bits)?;
+    }
+    w.write_str(">>")
+}
+
+pub fn write_term<W: Write>(w: &mut W, term: &Term) -> fmt::Result {
+    match *term {
+        Term::Atom(ref atom) => write_atom(w, &atom.name),
+        Term::Float(ref float) => write!(w, "{:?}", float.value),
+        Term::Binary(ref binary) => write_binary(w, &binary.bytes, 8),
+        Term::BitBinary(ref bit_binary) => {
+            write_binary(w, &bit_binary.bytes, bit_binary.tail_bits_size)
+        }
+        Term::List(ref list) => match printable_bytes(&list.elements) {
+            Some(bytes) => write_string(w, &bytes),
+            None => {
+                w.write_char('[')?;
+                write_elements(w, &list.elements)?;
+                w.write_char(']')
+            }
+        },
+        Term::ImproperList(ref improper_list) => {
+            w.write_char('[')?;
+            write_elements(w, &improper_list.elements)?;
+            w.write_char('|')?;
+            write_term(w, &improper_list.last)?;
+            w.write_char(']')
+        }
+        Term::Tuple(ref tuple) => {
+            w.write_char('{')?;
+            write_elements(w, &tuple.elements)?;
+            w.write_char('}')
+        }
+        Term::Map(ref map) => {
+            w.write_str("#{")?;
+            for (i, (key, value)) in map.entries.iter().enumerate() {
+                if i != 0 {
+                    w.write_char(',')?;
+                }
+                write_term(w, key)?;
+                w.write_str(" => ")?;
+                write_term(w, value)?;
+            }
+            w.write_char('}')
+        }
+        ref other => write!(w, "{}", other),
+    }
+}
+
+pub fn write_elements<W: Write>(w: &mut W, elements: &[Term]) -> fmt::Result {
+    for (i, element) in elements.iter().enumerate() {
+        if i != 0 {
+            w.write_char(',')?;
+        }
+        write_term(w, element)?;
+    }
+
+    Ok(())
+}
+
+// Private
+
+/// Lowercase Latin-1 letters, which can start a bare atom
+fn is_lowercase(c: char) -> bool {
+    match c {
+        'a'..='z' | '\u{df}'..='\u{ff}' => c != '\u{f7}',
+        _ => false,
+    }
+}
+
+/// Uppercase Latin-1 letters
+fn is_uppercase(c: char) -> bool {
+    match c {
+        'A'..='Z' | '\u{c0}'..='\u{de}' => c != '\u{d7}',
+        _ => false,
+    }
+}
+
+/// The printable Latin-1 characters of `io_lib:printable_list/1`
+fn is_printable(c: u32) -> bool {
+    match c {
+        32..=126 | 160..=255 => true,
+        // \b \t \n \v \f \r \e
+        8..=13 | 27 => true,
+        _ => false,
+    }
+}
+
+fn printable_bytes(elements: &[Term]) -> Option<Vec<u8>> {
+    if elements.is_empty() {
+        return None;
+    }
+
+    elements
+        .iter()
+        .map(|element| match *element {
+            Term::FixInteger(ref integer)
+                if 0 <= integer.value && is_printable(integer.value as u32) =>
+            {
+                Some(integer.value as u8)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn write_escaped_char<W: Write>(w: &mut W, c: char, quote: char) -> fmt::Result {
+    match c {
+        '\\' => w.write_str("\\\\"),
+        '\n' => w.write_str("\\n"),
+        '\r' => w.write_str("\\r"),
+        '\t' => w.write_str("\\t"),
+        '\u{8}' => w.write_str("\\b"),
+        '\u{b}' => w.write_str("\\v"),
+        '\u{c}' => w.write_str("\\f"),
+        '\u{1b}' => w.write_str("\\e"),
+        c if c == quote => {
+            w.write_char('\\')?;
+            w.write_char(c)
+        }
+        c if (c as u32) < 32 || c as u32 == 127 => write!(w, "\\{:o}", c as u32),
+        c => w.write_char(c),
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::{self, Display};
+
+use super::format;
+use super::opcode::{Layout, Opcode};
+use super::operand::{self, Decoder, Operand};
+use super::{DisassembleError, Result, Tables};
+
+/// A decoded instruction from the `"Code"` chunk.
+///
+/// `Display` prints the instruction as the term `erlc -S` would, without the trailing `.`:
+///
+/// ```
+/// use liblumen_beam::beam::disassembler::{Instruction, Opcode, Operand};
+///
+/// let instruction = Instruction {
+///     opcode: Opcode::Move,
+///     operands: vec![Operand::X(0), Operand::Y(0)],
+/// };
+/// assert_eq!("{move,{x,0},{y,0}}", instruction.to_string());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    /// The operands in the order they are encoded, except that `bs_match_string`'s bit count and
+    /// string offset are combined into one [Operand::Bitstring].
+    pub operands: Vec<Operand>,
+}
+impl Instruction {
+    /// Decodes the next instruction from `decoder`.
+    pub(super) fn decode(decoder: &mut Decoder, tables: &Tables) -> Result<Self> {
+        let offset = decoder.offset();
+        let number = decoder.read_u8()?;
+        let opcode =
+            Opcode::from_u8(number).ok_or(DisassembleError::UnknownOpcode { number, offset })?;
+
+        let mut operands = Vec::with_capacity(opcode.arity());
+        for _ in 0..opcode.arity() {
+            operands.push(decoder.read_operand(tables)?);
+        }
+        resolve(opcode, &mut operands, tables)?;
+
+        Ok(Instruction { opcode, operands })
+    }
+
+    /// The label number if this is a `label` instruction
+    pub fn label(&self) -> Option<u64> {
+        match (self.opcode, self.operands.first()) {
+            (Opcode::Label, Some(label)) => label.as_unsigned(),
+            _ => None,
+        }
+    }
+}
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = self.opcode.name();
+        let operands = &self.operands[..];
+
+        match (self.opcode.layout(), operands) {
+            (_, []) => format::write_atom(f, name),
+            (Layout::Test, [fail, rest @ ..]) => {
+                f.write_str("{test,")?;
+                format::write_atom(f, name)?;
+                write!(f, ",{},[", fail)?;
+                operand::write_operands(f, rest)?;
+                f.write_str("]}")
+            }
+            (Layout::TestWithLiveAndDestination, [fail, first, live, rest @ .., destination]) => {
+                f.write_str("{test,")?;
+                format::write_atom(f, name)?;
+                write!(f, ",{},{},[{}", fail, live, first)?;
+                for operand in rest {
+                    write!(f, ",{}", operand)?;
+                }
+                write!(f, "],{}}}", destination)
+            }
+            (Layout::TestWithList, [fail, source, list]) => {
+                f.write_str("{test,")?;
+                format::write_atom(f, name)?;
+                write!(f, ",{},{},{}}}", fail, source, list)
+            }
+            (Layout::Bif0, [Operand::ExternalFunction(bif), destination]) => {
+                f.write_str("{bif,")?;
+                format::write_atom(f, &bif.function)?;
+                write!(f, ",{{f,0}},[],{}}}", destination)
+            }
+            (Layout::Bif, [fail, Operand::ExternalFunction(bif), arguments @ .., destination]) => {
+                f.write_str("{bif,")?;
+                format::write_atom(f, &bif.function)?;
+                write!(f, ",{},[", fail)?;
+                operand::write_operands(f, arguments)?;
+                write!(f, "],{}}}", destination)
+            }
+            (
+                Layout::GcBif,
+                [fail, live, Operand::ExternalFunction(bif), arguments @ .., destination],
+            ) => {
+                f.write_str("{gc_bif,")?;
+                format::write_atom(f, &bif.function)?;
+                write!(f, ",{},{},[", fail, live)?;
+                operand::write_operands(f, arguments)?;
+                write!(f, "],{}}}", destination)
+            }
+            (Layout::MakeFun2, [Operand::Fun(fun)]) => write!(
+                f,
+                "{{make_fun2,{{f,{}}},{},{},{}}}",
+                fun.label, fun.index, fun.old_uniq, fun.num_free
+            ),
+            (Layout::MakeFun3, [Operand::Fun(fun), destination, environment]) => write!(
+                f,
+                "{{make_fun3,{{f,{}}},{},{},{},{}}}",
+                fun.label, fun.index, fun.old_uniq, destination, environment
+            ),
+            _ => {
+                f.write_str("{")?;
+                format::write_atom(f, name)?;
+                f.write_str(",")?;
+                operand::write_operands(f, operands)?;
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+/// Replaces the table indices that are untagged (`u`) operands with what they index.
+fn resolve(opcode: Opcode, operands: &mut Vec<Operand>, tables: &Tables) -> Result<()> {
+    use self::Opcode::*;
+
+    match opcode {
+        CallExt | CallExtLast | CallExtOnly => resolve_import(opcode, &mut operands[1], tables),
+        Bif0 => resolve_import(opcode, &mut operands[0], tables),
+        Bif1 | Bif2 => resolve_import(opcode, &mut operands[1], tables),
+        GcBif1 | GcBif2 | GcBif3 => resolve_import(opcode, &mut operands[2], tables),
+        MakeFun2 | MakeFun3 => {
+            let index = unsigned(opcode, &operands[0])?;
+            operands[0] = Operand::Fun(tables.fun(index)?);
+
+            Ok(())
+        }
+        Line => {
+            if tables.has_lines() {
+                let index = unsigned(opcode, &operands[0])?;
+                operands[0] = Operand::Location(tables.location(index)?);
+            }
+
+            Ok(())
+        }
+        BsPutString => {
+            let len = unsigned(opcode, &operands[0])?;
+            let offset = unsigned(opcode, &operands[1])?;
+            operands[1] = Operand::String(tables.string(offset, len)?.to_vec());
+
+            Ok(())
+        }
+        BsMatchString => {
+            let bits = unsigned(opcode, &operands[2])?;
+            let offset = unsigned(opcode, &operands[3])?;
+            let bytes = tables
+                .string(offset, operand::bits_to_bytes(bits))?
+                .to_vec();
+            operands.truncate(2);
+            operands.push(Operand::Bitstring { bytes, bits });
+
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn resolve_import(opcode: Opcode, operand: &mut Operand, tables: &Tables) -> Result<()> {
+    let index = unsigned(opcode, operand)?;
+    *operand = Operand::ExternalFunction(tables.import(index)?);
+
+    Ok(())
+}
+
+fn unsigned(opcode: Opcode, operand: &Operand) -> Result<u64> {
+    operand
+        .as_unsigned()
+        .ok_or_else(|| DisassembleError::UnexpectedOperand {
+            opcode: opcode.name(),
+            operand: operand.to_string(),
+        })
+}
@@ -0,0 +1,453 @@
+//! Operands in the compact term encoding used by the `"Code"` and `"Line"` chunks.
+//!
+//! ## References
+//!
+//! - [BEAM Wisdoms - Compact Term Encoding](http://beam-wisdoms.clau.se/en/latest/indepth-beam-file.html#beam-compact-term-encoding)
+//! - `beam_asm:encode/2` in OTP's `lib/compiler/src/beam_asm.erl`
+use std::convert::TryInto;
+use std::fmt::{self, Display};
+
+use num::bigint::{BigInt, Sign};
+use num::ToPrimitive;
+
+use crate::serialization::etf;
+
+use super::format;
+use super::{DisassembleError, Result, Tables};
+
+pub(crate) const TAG_U: u8 = 0;
+pub(crate) const TAG_I: u8 = 1;
+pub(crate) const TAG_A: u8 = 2;
+pub(crate) const TAG_X: u8 = 3;
+pub(crate) const TAG_Y: u8 = 4;
+pub(crate) const TAG_F: u8 = 5;
+pub(crate) const TAG_H: u8 = 6;
+pub(crate) const TAG_Z: u8 = 7;
+
+pub(crate) const EXTENDED_FLOAT: u8 = 0;
+pub(crate) const EXTENDED_LIST: u8 = 1;
+pub(crate) const EXTENDED_FLOAT_REGISTER: u8 = 2;
+pub(crate) const EXTENDED_ALLOCATION_LIST: u8 = 3;
+pub(crate) const EXTENDED_LITERAL: u8 = 4;
+pub(crate) const EXTENDED_TYPED_REGISTER: u8 = 5;
+
+/// An operand of an [Instruction](super::Instruction).
+///
+/// Atoms, literals, imports, anonymous functions, lines and strings are resolved against their
+/// chunks, so operands can be printed without the rest of the BEAM file.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Operand {
+    /// An untagged (`u`) integer, such as an arity, a count or a table index.
+    Unsigned(u64),
+    /// `{integer,N}`
+    Integer(BigInt),
+    /// `{atom,Name}`
+    Atom(String),
+    /// `nil`, which is atom index 0.
+    Nil,
+    /// `{x,N}`
+    X(u64),
+    /// `{y,N}`
+    Y(u64),
+    /// `{f,N}`.  Label `0` means the instruction raises instead of jumping.
+    Label(u64),
+    /// `{char,N}`
+    Character(u64),
+    /// `{fr,N}`
+    FloatRegister(u64),
+    /// `{float,F}` from BEAM files before literals were moved to the `"LitT"` chunk.
+    Float(f64),
+    /// `{list,[...]}`, such as the value and label pairs of `select_val`.
+    List(Vec<Operand>),
+    /// `{alloc,[{words,N},{floats,N},{funs,N}]}`
+    Allocation(Vec<(Allocation, u64)>),
+    /// `{literal,Term}` from the `"LitT"` chunk.
+    Literal(etf::Term),
+    /// `{tr,Register,Type}`.  `type_index` is the index into the `"Type"` chunk, which is not
+    /// decoded.
+    TypedRegister {
+        register: Box<Operand>,
+        type_index: u64,
+    },
+    /// `{extfunc,Module,Function,Arity}` from the `"ImpT"` chunk.
+    ExternalFunction(ExternalFunction),
+    /// An entry in the `"FunT"` chunk, which is only used by `make_fun2` and `make_fun3`.
+    Fun(Fun),
+    /// The `[{location,File,Line}]` of a `line` instruction from the `"Line"` chunk or `[]` if
+    /// the index has no location.
+    Location(Option<Location>),
+    /// `{string,"..."}` from the `"StrT"` chunk for `bs_put_string`.
+    String(Vec<u8>),
+    /// A bitstring from the `"StrT"` chunk for `bs_match_string`.
+    Bitstring { bytes: Vec<u8>, bits: u64 },
+}
+impl Operand {
+    /// Returns the value of an untagged (`u`) operand, which is how table indices are encoded.
+    pub fn as_unsigned(&self) -> Option<u64> {
+        match *self {
+            Operand::Unsigned(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Operand::Unsigned(value) => write!(f, "{}", value),
+            Operand::Integer(ref value) => write!(f, "{{integer,{}}}", value),
+            Operand::Atom(ref name) => {
+                f.write_str("{atom,")?;
+                format::write_atom(f, name)?;
+                f.write_str("}")
+            }
+            Operand::Nil => f.write_str("nil"),
+            Operand::X(n) => write!(f, "{{x,{}}}", n),
+            Operand::Y(n) => write!(f, "{{y,{}}}", n),
+            Operand::Label(n) => write!(f, "{{f,{}}}", n),
+            Operand::Character(n) => write!(f, "{{char,{}}}", n),
+            Operand::FloatRegister(n) => write!(f, "{{fr,{}}}", n),
+            Operand::Float(value) => write!(f, "{{float,{:?}}}", value),
+            Operand::List(ref operands) => {
+                f.write_str("{list,[")?;
+                write_operands(f, operands)?;
+                f.write_str("]}")
+            }
+            Operand::Allocation(ref allocations) => {
+                f.write_str("{alloc,[")?;
+                for (i, (allocation, count)) in allocations.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{{{},{}}}", allocation, count)?;
+                }
+                f.write_str("]}")
+            }
+            Operand::Literal(ref term) => {
+                f.write_str("{literal,")?;
+                format::write_term(f, term)?;
+                f.write_str("}")
+            }
+            Operand::TypedRegister {
+                ref register,
+                type_index,
+            } => write!(f, "{{tr,{},{}}}", register, type_index),
+            Operand::ExternalFunction(ref external_function) => external_function.fmt(f),
+            Operand::Fun(ref fun) => write!(f, "{{f,{}}}", fun.label),
+            Operand::Location(None) => f.write_str("[]"),
+            Operand::Location(Some(ref location)) => write!(f, "[{}]", location),
+            Operand::String(ref bytes) => {
+                f.write_str("{string,")?;
+                format::write_string(f, bytes)?;
+                f.write_str("}")
+            }
+            Operand::Bitstring { ref bytes, bits } => {
+                let tail_bits = match bits % 8 {
+                    0 => 8,
+                    tail_bits => tail_bits as u8,
+                };
+                let len = bits_to_bytes(bits) as usize;
+
+                format::write_binary(f, &bytes[..len.min(bytes.len())], tail_bits)
+            }
+        }
+    }
+}
+
+/// The kinds of terms in an [Operand::Allocation]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Allocation {
+    Words = 0,
+    Floats = 1,
+    Funs = 2,
+}
+impl Display for Allocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Allocation::Words => "words",
+            Allocation::Floats => "floats",
+            Allocation::Funs => "funs",
+        })
+    }
+}
+
+/// An imported function, with the atoms resolved.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExternalFunction {
+    pub module: String,
+    pub function: String,
+    pub arity: u32,
+}
+impl Display for ExternalFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{extfunc,")?;
+        format::write_atom(f, &self.module)?;
+        f.write_str(",")?;
+        format::write_atom(f, &self.function)?;
+        write!(f, ",{}}}", self.arity)
+    }
+}
+
+/// An anonymous function from the `"FunT"` chunk.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Fun {
+    pub label: u32,
+    pub index: u32,
+    pub old_uniq: u32,
+    pub num_free: u32,
+}
+
+/// A source location from the `"Line"` chunk.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Location {
+    pub file: String,
+    pub line: u32,
+}
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("{location,")?;
+        format::write_string(f, self.file.as_bytes())?;
+        write!(f, ",{}}}", self.line)
+    }
+}
+
+/// Appends `value` with `tag` in the compact term encoding, which is the inverse of
+/// `Decoder::read_value`.
+pub(crate) fn append_value(bytes: &mut Vec<u8>, tag: u8, value: &BigInt) {
+    match value.to_u16() {
+        Some(small) if small < 0x10 => bytes.push(((small as u8) << 4) | tag),
+        Some(medium) if medium < 0x800 => {
+            bytes.push((((medium >> 3) as u8) & 0b1110_0000) | 0b1000 | tag);
+            bytes.push(medium as u8);
+        }
+        _ => {
+            let mut value_bytes = value.to_signed_bytes_be();
+            // Values that don't fit in 11 bits always use at least 2 bytes
+            if value_bytes.len() < 2 {
+                let extension = if value.sign() == Sign::Minus { 0xFF } else { 0 };
+                value_bytes.insert(0, extension);
+            }
+
+            let len = value_bytes.len();
+            if len <= 8 {
+                bytes.push((((len - 2) as u8) << 5) | 0b1_1000 | tag);
+            } else {
+                bytes.push(0b1111_1000 | tag);
+                append_value(bytes, TAG_U, &BigInt::from(len - 9));
+            }
+            bytes.extend_from_slice(&value_bytes);
+        }
+    }
+}
+
+pub(crate) fn append_unsigned(bytes: &mut Vec<u8>, tag: u8, value: u64) {
+    append_value(bytes, tag, &BigInt::from(value))
+}
+
+/// The number of bytes needed to hold `bits`
+pub(crate) fn bits_to_bytes(bits: u64) -> u64 {
+    bits / 8 + u64::from(bits & 7 != 0)
+}
+
+pub(super) fn write_operands(f: &mut fmt::Formatter, operands: &[Operand]) -> fmt::Result {
+    for (i, operand) in operands.iter().enumerate() {
+        if i != 0 {
+            f.write_str(",")?;
+        }
+        write!(f, "{}", operand)?;
+    }
+
+    Ok(())
+}
+
+/// Reads compact terms from the bytes of a chunk.
+pub(super) struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Decoder { bytes, offset: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset.min(self.bytes.len())..]
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.offset)
+            .ok_or(DisassembleError::UnexpectedEnd)?;
+        self.offset += 1;
+
+        Ok(byte)
+    }
+
+    /// Reads an operand, resolving atoms and literals against `tables`.
+    pub fn read_operand(&mut self, tables: &Tables) -> Result<Operand> {
+        let offset = self.offset;
+        let first = self.read_u8()?;
+
+        match first & 0b111 {
+            TAG_U => self.read_unsigned_value(first).map(Operand::Unsigned),
+            TAG_I => self.read_value(first, true).map(Operand::Integer),
+            TAG_A => match self.read_unsigned_value(first)? {
+                0 => Ok(Operand::Nil),
+                index => tables
+                    .atom(index)
+                    .map(|name| Operand::Atom(name.to_string())),
+            },
+            TAG_X => self.read_unsigned_value(first).map(Operand::X),
+            TAG_Y => self.read_unsigned_value(first).map(Operand::Y),
+            TAG_F => self.read_unsigned_value(first).map(Operand::Label),
+            TAG_H => self.read_unsigned_value(first).map(Operand::Character),
+            TAG_Z => self.read_extended(first >> 4, offset, tables),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Reads an operand that must be an untagged (`u`) integer.
+    pub fn read_unsigned(&mut self) -> Result<u64> {
+        let offset = self.offset;
+        let first = self.read_u8()?;
+
+        if first & 0b111 == TAG_U {
+            self.read_unsigned_value(first)
+        } else {
+            Err(DisassembleError::UnexpectedTag {
+                tag: first & 0b111,
+                offset,
+            })
+        }
+    }
+
+    /// Reads a tagged value without resolving it, as used in the `"Line"` chunk: `(tag, value)`.
+    pub fn read_tagged(&mut self) -> Result<(u8, u64)> {
+        let first = self.read_u8()?;
+        let value = self.read_unsigned_value(first)?;
+
+        Ok((first & 0b111, value))
+    }
+
+    fn read_extended(
+        &mut self,
+        extended_tag: u8,
+        offset: usize,
+        tables: &Tables,
+    ) -> Result<Operand> {
+        match extended_tag {
+            EXTENDED_FLOAT => {
+                let bytes = self.read_bytes(8)?;
+
+                Ok(Operand::Float(f64::from_be_bytes(
+                    bytes.try_into().unwrap(),
+                )))
+            }
+            EXTENDED_LIST => {
+                let len = self.read_unsigned()?;
+                let mut operands = Vec::new();
+                for _ in 0..len {
+                    operands.push(self.read_operand(tables)?);
+                }
+
+                Ok(Operand::List(operands))
+            }
+            EXTENDED_FLOAT_REGISTER => self.read_unsigned().map(Operand::FloatRegister),
+            EXTENDED_ALLOCATION_LIST => {
+                let len = self.read_unsigned()?;
+                let mut allocations = Vec::new();
+                for _ in 0..len {
+                    let kind_offset = self.offset;
+                    let allocation = match self.read_unsigned()? {
+                        0 => Allocation::Words,
+                        1 => Allocation::Floats,
+                        2 => Allocation::Funs,
+                        _ => {
+                            return Err(DisassembleError::UnexpectedTag {
+                                tag: TAG_Z,
+                                offset: kind_offset,
+                            })
+                        }
+                    };
+                    allocations.push((allocation, self.read_unsigned()?));
+                }
+
+                Ok(Operand::Allocation(allocations))
+            }
+            EXTENDED_LITERAL => {
+                let index = self.read_unsigned()?;
+
+                tables
+                    .literal(index)
+                    .map(|term| Operand::Literal(term.clone()))
+            }
+            EXTENDED_TYPED_REGISTER => {
+                let register = self.read_operand(tables)?;
+                let type_index = self.read_unsigned()?;
+
+                Ok(Operand::TypedRegister {
+                    register: Box::new(register),
+                    type_index,
+                })
+            }
+            _ => Err(DisassembleError::UnexpectedTag { tag: TAG_Z, offset }),
+        }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or(DisassembleError::UnexpectedEnd)?;
+        self.offset += len;
+
+        Ok(bytes)
+    }
+
+    fn read_unsigned_value(&mut self, first: u8) -> Result<u64> {
+        let offset = self.offset;
+
+        self.read_value(first, false)?
+            .to_u64()
+            .ok_or(DisassembleError::ValueTooLarge { offset })
+    }
+
+    /// Reads the value after the tag in `first`, which is in `first` itself, `first` and the next
+    /// byte, or in the bytes after `first`.
+    fn read_value(&mut self, first: u8, signed: bool) -> Result<BigInt> {
+        if first & 0b1000 == 0 {
+            Ok(BigInt::from(first >> 4))
+        } else if first & 0b1_0000 == 0 {
+            let low = self.read_u8()?;
+
+            Ok(BigInt::from(
+                (((first as u16) & 0b1110_0000) << 3) | low as u16,
+            ))
+        } else {
+            let len = match first >> 5 {
+                0b111 => {
+                    let len_first = self.read_u8()?;
+                    let len = self.read_unsigned_value(len_first)?;
+
+                    len as usize + 9
+                }
+                len => len as usize + 2,
+            };
+            let bytes = self.read_bytes(len)?;
+
+            if signed {
+                Ok(BigInt::from_signed_bytes_be(bytes))
+            } else {
+                Ok(BigInt::from_bytes_be(Sign::Plus, bytes))
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use super::operand::Decoder;
+use super::*;
+
+#[test]
+fn listing() {
+    let module = Module::from_file(test_file("reader/test.beam")).unwrap();
+
+    assert_eq!(
+        r#"{module, test}.  %% version = 0
+
+{exports, [{hello,1},{module_info,0},{module_info,1}]}.
+
+{attributes, []}.
+
+{labels, 9}.
+
+
+{function, hello, 1, 2}.
+  {label,1}.
+    {line,[{location,"test.erl",7}]}.
+    {func_info,{atom,test},{atom,hello},1}.
+  {label,2}.
+    {allocate,0,1}.
+    {make_fun2,{f,8},0,38182595,1}.
+    {line,[{location,"test.erl",9}]}.
+    {call_fun,0}.
+    {move,{atom,ok},{x,0}}.
+    {deallocate,0}.
+    return.
+
+
+{function, module_info, 0, 4}.
+  {label,3}.
+    {line,[]}.
+    {func_info,{atom,test},{atom,module_info},0}.
+  {label,4}.
+    {move,{atom,test},{x,0}}.
+    {line,[]}.
+    {call_ext_only,1,{extfunc,erlang,get_module_info,1}}.
+
+
+{function, module_info, 1, 6}.
+  {label,5}.
+    {line,[]}.
+    {func_info,{atom,test},{atom,module_info},1}.
+  {label,6}.
+    {move,{x,0},{x,1}}.
+    {move,{atom,test},{x,0}}.
+    {line,[]}.
+    {call_ext_only,2,{extfunc,erlang,get_module_info,2}}.
+
+
+{function, '-hello/1-fun-0-', 1, 8}.
+  {label,7}.
+    {line,[{location,"test.erl",8}]}.
+    {func_info,{atom,test},{atom,'-hello/1-fun-0-'},1}.
+  {label,8}.
+    {test_heap,2,1}.
+    {put_list,{x,0},nil,{x,1}}.
+    {move,{literal,"Hello ~p!"},{x,0}}.
+    {line,[{location,"test.erl",8}]}.
+    {call_ext_only,2,{extfunc,io,format,2}}.
+"#,
+        module.to_string()
+    );
+}
+
+#[test]
+fn instructions() {
+    let module = Module::from_file(test_file("reader/Elixir.Unicode.beam")).unwrap();
+    assert_eq!(
+        vec![
+            "'__info__'/1",
+            "add1/1",
+            "ascii_atom/0",
+            "string/0",
+            "utf8_atom/0",
+            "module_info/0",
+            "module_info/1",
+            "'-add1/1-fun-0-'/1",
+        ],
+        module
+            .functions
+            .iter()
+            .map(|function| {
+                let mut name = String::new();
+                format::write_atom(&mut name, &function.name).unwrap();
+                format!("{}/{}", name, function.arity)
+            })
+            .collect::<Vec<_>>()
+    );
+
+    let listing = module.to_string();
+    assert!(listing.contains("    {test,is_atom,{f,1},[{x,0}]}.\n"));
+    assert!(listing.contains("    {gc_bif,'+',{f,0},1,[{x,0},{integer,1}],{x,0}}.\n"));
+    assert!(listing.contains("    {call_ext_last,2,{extfunc,'Elixir.Enum',map,2},1}.\n"));
+    assert!(listing.contains("    {move,{literal,<<\"string\">>},{x,0}}.\n"));
+    assert!(listing.contains("    {move,{atom,åtom},{x,0}}.\n"));
+
+    let listing = Module::from_file(test_file("simple.beam"))
+        .unwrap()
+        .to_string();
+    assert!(listing.contains("    {bif,self,{f,0},[],{x,0}}.\n"));
+    assert!(listing.contains("    {test,is_tagged_tuple,{f,6},[{x,2},2,{atom,ok}]}.\n"));
+}
+
+#[test]
+fn compact_terms() {
+    let tables = Tables {
+        atoms: &[],
+        imports: &[],
+        funs: &[],
+        literals: Vec::new(),
+        strings: &[],
+        locations: None,
+    };
+    let decode = |bytes: &[u8]| {
+        let mut decoder = Decoder::new(bytes);
+        let operand = decoder.read_operand(&tables).unwrap();
+        assert!(decoder.is_empty());
+        operand.to_string()
+    };
+
+    // 4-bit value
+    assert_eq!("{x,2}", decode(&[0x23]));
+    // 11-bit value
+    assert_eq!("{x,1000}", decode(&[0x6B, 0xE8]));
+    // Negative integers are in 2 or more bytes
+    assert_eq!("{integer,-1}", decode(&[0x19, 0xFF, 0xFF]));
+    // More than 8 bytes have the length as another operand
+    assert_eq!(
+        "{integer,18446744073709551616}",
+        decode(&[0xF9, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 0, 0])
+    );
+    assert_eq!("nil", decode(&[0x02]));
+    assert_eq!("{list,[{x,0},{f,1}]}", decode(&[0x17, 0x20, 0x03, 0x15]));
+    assert_eq!("{fr,1}", decode(&[0x27, 0x10]));
+    assert_eq!(
+        "{alloc,[{words,3},{funs,1}]}",
+        decode(&[0x37, 0x20, 0x00, 0x30, 0x20, 0x10])
+    );
+
+    let mut decoder = Decoder::new(&[0x12]);
+    match decoder.read_operand(&tables) {
+        Err(DisassembleError::IndexOutOfRange {
+            table: "atom",
+            index: 1,
+        }) => (),
+        other => panic!("expected atom out of range, got {:?}", other),
+    }
+}
+
+#[test]
+fn atoms() {
+    let atom = |name| {
+        let mut string = String::new();
+        format::write_atom(&mut string, name).unwrap();
+        string
+    };
+
+    assert_eq!("ok", atom("ok"));
+    assert_eq!("node@host", atom("node@host"));
+    assert_eq!("'Elixir.Enum'", atom("Elixir.Enum"));
+    assert_eq!("'try'", atom("try"));
+    assert_eq!("'+'", atom("+"));
+    assert_eq!("''", atom(""));
+    assert_eq!("'it\\'s'", atom("it's"));
+}
+
+fn test_file(name: &str) -> PathBuf {
+    let mut path = PathBuf::from("tests/testdata");
+    path.push(name);
+    path
+}
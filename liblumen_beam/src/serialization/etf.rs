@@ -27,6 +27,8 @@
 mod codec;
 pub mod convert;
 pub mod pattern;
+#[cfg(feature = "serde")]
+pub mod serde;
 
 #[cfg(test)]
 mod test;
@@ -36,7 +38,7 @@ use std::convert::From;
 use num::bigint::BigInt;
 
 pub use self::codec::{DecodeError, DecodeResult};
-pub use self::codec::{EncodeError, EncodeResult};
+pub use self::codec::{EncodeError, EncodeResult, IdentifierEncoding};
 
 /// Term.
 #[derive(Debug, PartialEq, Clone)]
@@ -68,6 +70,15 @@ impl Term {
         codec::Encoder::new(writer).encode(self)
     }
 
+    /// Encodes the term, with pids, ports and references in `identifier_encoding`.
+    pub fn encode_with<W: std::io::Write>(
+        &self,
+        writer: W,
+        identifier_encoding: IdentifierEncoding,
+    ) -> EncodeResult {
+        codec::Encoder::with_identifier_encoding(writer, identifier_encoding).encode(self)
+    }
+
     pub fn as_match<'a, P>(&'a self, pattern: P) -> pattern::Result<P::Output>
     where
         P: pattern::Pattern<'a>,
@@ -364,10 +375,10 @@ pub struct Pid {
     pub node: Atom,
     pub id: u32,
     pub serial: u32,
-    pub creation: u8,
+    pub creation: u32,
 }
 impl Pid {
-    pub fn new<T>(node: T, id: u32, serial: u32, creation: u8) -> Self
+    pub fn new<T>(node: T, id: u32, serial: u32, creation: u32) -> Self
     where
         Atom: From<T>,
     {
@@ -400,16 +411,17 @@ impl<'a> From<(&'a str, u32, u32)> for Pid {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Port {
     pub node: Atom,
-    pub id: u32,
-    pub creation: u8,
+    /// 64 bits since OTP 24, where `V4_PORT_EXT` is needed for ids that do not fit in 32 bits
+    pub id: u64,
+    pub creation: u32,
 }
 impl std::fmt::Display for Port {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "#Port<{}.{}>", self.node, self.id)
     }
 }
-impl<'a> From<(&'a str, u32)> for Port {
-    fn from((node, id): (&'a str, u32)) -> Self {
+impl<'a> From<(&'a str, u64)> for Port {
+    fn from((node, id): (&'a str, u64)) -> Self {
         Port {
             node: Atom::from(node),
             id,
@@ -423,7 +435,7 @@ impl<'a> From<(&'a str, u32)> for Port {
 pub struct Reference {
     pub node: Atom,
     pub id: Vec<u32>,
-    pub creation: u8,
+    pub creation: u32,
 }
 impl std::fmt::Display for Reference {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
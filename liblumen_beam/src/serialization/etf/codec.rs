@@ -60,6 +60,12 @@ pub enum EncodeError {
     // r.id.len() * 4
     #[fail(display = "reference is too large, exceeds maximum byte size")]
     TooLargeReferenceId(Reference),
+
+    #[fail(display = "creation {} does not fit in the legacy encoding", _0)]
+    TooLargeCreation(u32),
+
+    #[fail(display = "port id does not fit in the legacy encoding")]
+    TooLargePortId(Port),
 }
 impl std::convert::From<std::io::Error> for EncodeError {
     fn from(err: std::io::Error) -> EncodeError {
@@ -67,6 +73,22 @@ impl std::convert::From<std::io::Error> for EncodeError {
     }
 }
 
+/// How pids, ports and references are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierEncoding {
+    /// `PID_EXT`, `PORT_EXT` and `NEW_REFERENCE_EXT` with 8-bit creations, which every release
+    /// can decode
+    Legacy,
+    /// `NEW_PID_EXT`, `NEW_PORT_EXT` (or `V4_PORT_EXT` for 64-bit port ids) and
+    /// `NEWER_REFERENCE_EXT` with 32-bit creations, which OTP 23+ emits by default
+    Modern,
+}
+impl Default for IdentifierEncoding {
+    fn default() -> Self {
+        IdentifierEncoding::Legacy
+    }
+}
+
 pub type DecodeResult = Result<Term, DecodeError>;
 pub type EncodeResult = Result<(), EncodeError>;
 
@@ -77,6 +99,9 @@ const NEW_FLOAT_EXT: u8 = 70;
 const BIT_BINARY_EXT: u8 = 77;
 const COMPRESSED_TERM: u8 = 80;
 const ATOM_CACHE_REF: u8 = 82;
+const NEW_PID_EXT: u8 = 88;
+const NEW_PORT_EXT: u8 = 89;
+const NEWER_REFERENCE_EXT: u8 = 90;
 const SMALL_INTEGER_EXT: u8 = 97;
 const INTEGER_EXT: u8 = 98;
 const FLOAT_EXT: u8 = 99;
@@ -100,6 +125,8 @@ const MAP_EXT: u8 = 116;
 const FUN_EXT: u8 = 117;
 const ATOM_UTF8_EXT: u8 = 118;
 const SMALL_ATOM_UTF8_EXT: u8 = 119;
+const V4_PORT_EXT: u8 = 120;
+const LOCAL_EXT: u8 = 121;
 
 pub struct Decoder<R> {
     reader: R,
@@ -133,6 +160,9 @@ impl<R: std::io::Read> Decoder<R> {
             NEW_FLOAT_EXT => self.decode_new_float_ext(),
             BIT_BINARY_EXT => self.decode_bit_binary_ext(),
             ATOM_CACHE_REF => unimplemented!(),
+            NEW_PID_EXT => self.decode_new_pid_ext(),
+            NEW_PORT_EXT => self.decode_new_port_ext(),
+            NEWER_REFERENCE_EXT => self.decode_newer_reference_ext(),
             SMALL_INTEGER_EXT => self.decode_small_integer_ext(),
             INTEGER_EXT => self.decode_integer_ext(),
             FLOAT_EXT => self.decode_float_ext(),
@@ -156,6 +186,8 @@ impl<R: std::io::Read> Decoder<R> {
             FUN_EXT => self.decode_fun_ext(),
             ATOM_UTF8_EXT => self.decode_atom_utf8_ext(),
             SMALL_ATOM_UTF8_EXT => self.decode_small_atom_utf8_ext(),
+            V4_PORT_EXT => self.decode_v4_port_ext(),
+            LOCAL_EXT => self.decode_local_ext(),
             _ => Err(DecodeError::UnknownTag { tag }),
         }
     }
@@ -242,7 +274,16 @@ impl<R: std::io::Read> Decoder<R> {
             node,
             id: self.reader.read_u32::<BigEndian>()?,
             serial: self.reader.read_u32::<BigEndian>()?,
-            creation: self.reader.read_u8()?,
+            creation: self.reader.read_u8()? as u32,
+        }))
+    }
+    fn decode_new_pid_ext(&mut self) -> DecodeResult {
+        let node = self.decode_term().and_then(auxiliary::term_into_atom)?;
+        Ok(Term::from(Pid {
+            node,
+            id: self.reader.read_u32::<BigEndian>()?,
+            serial: self.reader.read_u32::<BigEndian>()?,
+            creation: self.reader.read_u32::<BigEndian>()?,
         }))
     }
     fn decode_port_ext(&mut self) -> DecodeResult {
@@ -254,8 +295,24 @@ impl<R: std::io::Read> Decoder<R> {
         })?;
         Ok(Term::from(Port {
             node,
-            id: self.reader.read_u32::<BigEndian>()?,
-            creation: self.reader.read_u8()?,
+            id: self.reader.read_u32::<BigEndian>()? as u64,
+            creation: self.reader.read_u8()? as u32,
+        }))
+    }
+    fn decode_new_port_ext(&mut self) -> DecodeResult {
+        let node = self.decode_term().and_then(auxiliary::term_into_atom)?;
+        Ok(Term::from(Port {
+            node,
+            id: self.reader.read_u32::<BigEndian>()? as u64,
+            creation: self.reader.read_u32::<BigEndian>()?,
+        }))
+    }
+    fn decode_v4_port_ext(&mut self) -> DecodeResult {
+        let node = self.decode_term().and_then(auxiliary::term_into_atom)?;
+        Ok(Term::from(Port {
+            node,
+            id: self.reader.read_u64::<BigEndian>()?,
+            creation: self.reader.read_u32::<BigEndian>()?,
         }))
     }
     fn decode_reference_ext(&mut self) -> DecodeResult {
@@ -263,19 +320,35 @@ impl<R: std::io::Read> Decoder<R> {
         Ok(Term::from(Reference {
             node,
             id: vec![self.reader.read_u32::<BigEndian>()?],
-            creation: self.reader.read_u8()?,
+            creation: self.reader.read_u8()? as u32,
         }))
     }
     fn decode_new_reference_ext(&mut self) -> DecodeResult {
         let id_count = self.reader.read_u16::<BigEndian>()? as usize;
         let node = self.decode_term().and_then(auxiliary::term_into_atom)?;
-        let creation = self.reader.read_u8()?;
+        let creation = self.reader.read_u8()? as u32;
         let mut id = Vec::with_capacity(id_count);
         for _ in 0..id_count {
             id.push(self.reader.read_u32::<BigEndian>()?);
         }
         Ok(Term::from(Reference { node, id, creation }))
     }
+    fn decode_newer_reference_ext(&mut self) -> DecodeResult {
+        let id_count = self.reader.read_u16::<BigEndian>()? as usize;
+        let node = self.decode_term().and_then(auxiliary::term_into_atom)?;
+        let creation = self.reader.read_u32::<BigEndian>()?;
+        let mut id = Vec::with_capacity(id_count);
+        for _ in 0..id_count {
+            id.push(self.reader.read_u32::<BigEndian>()?);
+        }
+        Ok(Term::from(Reference { node, id, creation }))
+    }
+    fn decode_local_ext(&mut self) -> DecodeResult {
+        // The hash only identifies the node that encoded the term, so it is skipped
+        let mut hash = [0; 8];
+        self.reader.read_exact(&mut hash)?;
+        self.decode_term()
+    }
     fn decode_export_ext(&mut self) -> DecodeResult {
         let module = self.decode_term().and_then(auxiliary::term_into_atom)?;
         let function = self.decode_term().and_then(auxiliary::term_into_atom)?;
@@ -413,10 +486,17 @@ impl<R: std::io::Read> Decoder<R> {
 
 pub struct Encoder<W> {
     writer: W,
+    identifier_encoding: IdentifierEncoding,
 }
 impl<W: std::io::Write> Encoder<W> {
     pub fn new(writer: W) -> Self {
-        Encoder { writer }
+        Self::with_identifier_encoding(writer, IdentifierEncoding::default())
+    }
+    pub fn with_identifier_encoding(writer: W, identifier_encoding: IdentifierEncoding) -> Self {
+        Encoder {
+            writer,
+            identifier_encoding,
+        }
     }
     pub fn encode(mut self, term: &Term) -> EncodeResult {
         self.writer.write_u8(VERSION)?;
@@ -447,8 +527,13 @@ impl<W: std::io::Write> Encoder<W> {
     }
     fn encode_list(&mut self, x: &List) -> EncodeResult {
         let to_byte = |e: &Term| {
-            e.try_as_ref()
-                .and_then(|&FixInteger { value: i }| if i < 0x100 { Some(i as u8) } else { None })
+            e.try_as_ref().and_then(|&FixInteger { value: i }| {
+                if 0 <= i && i < 0x100 {
+                    Some(i as u8)
+                } else {
+                    None
+                }
+            })
         };
         if !x.elements.is_empty()
             && x.elements.len() <= std::u16::MAX as usize
@@ -569,33 +654,63 @@ impl<W: std::io::Write> Encoder<W> {
         Ok(())
     }
     fn encode_pid(&mut self, x: &Pid) -> EncodeResult {
-        self.writer.write_u8(PID_EXT)?;
+        match self.identifier_encoding {
+            IdentifierEncoding::Legacy => self.writer.write_u8(PID_EXT)?,
+            IdentifierEncoding::Modern => self.writer.write_u8(NEW_PID_EXT)?,
+        }
         self.encode_atom(&x.node)?;
         self.writer.write_u32::<BigEndian>(x.id)?;
         self.writer.write_u32::<BigEndian>(x.serial)?;
-        self.writer.write_u8(x.creation)?;
+        self.encode_creation(x.creation)?;
         Ok(())
     }
     fn encode_port(&mut self, x: &Port) -> EncodeResult {
-        self.writer.write_u8(PORT_EXT)?;
+        let is_v4 = x.id > u64::from(u32::MAX);
+        match self.identifier_encoding {
+            IdentifierEncoding::Legacy if is_v4 => {
+                return Err(EncodeError::TooLargePortId(x.clone()))
+            }
+            IdentifierEncoding::Legacy => self.writer.write_u8(PORT_EXT)?,
+            IdentifierEncoding::Modern if is_v4 => self.writer.write_u8(V4_PORT_EXT)?,
+            IdentifierEncoding::Modern => self.writer.write_u8(NEW_PORT_EXT)?,
+        }
         self.encode_atom(&x.node)?;
-        self.writer.write_u32::<BigEndian>(x.id)?;
-        self.writer.write_u8(x.creation)?;
+        if is_v4 {
+            self.writer.write_u64::<BigEndian>(x.id)?;
+        } else {
+            self.writer.write_u32::<BigEndian>(x.id as u32)?;
+        }
+        self.encode_creation(x.creation)?;
         Ok(())
     }
     fn encode_reference(&mut self, x: &Reference) -> EncodeResult {
-        self.writer.write_u8(NEW_REFERENCE_EXT)?;
+        match self.identifier_encoding {
+            IdentifierEncoding::Legacy => self.writer.write_u8(NEW_REFERENCE_EXT)?,
+            IdentifierEncoding::Modern => self.writer.write_u8(NEWER_REFERENCE_EXT)?,
+        }
         if x.id.len() > std::u16::MAX as usize {
             return Err(EncodeError::TooLargeReferenceId(x.clone()));
         }
         self.writer.write_u16::<BigEndian>(x.id.len() as u16)?;
         self.encode_atom(&x.node)?;
-        self.writer.write_u8(x.creation)?;
+        self.encode_creation(x.creation)?;
         for n in &x.id {
             self.writer.write_u32::<BigEndian>(*n)?;
         }
         Ok(())
     }
+    fn encode_creation(&mut self, creation: u32) -> EncodeResult {
+        match self.identifier_encoding {
+            IdentifierEncoding::Legacy => {
+                if creation > u32::from(u8::MAX) {
+                    return Err(EncodeError::TooLargeCreation(creation));
+                }
+                self.writer.write_u8(creation as u8)?;
+            }
+            IdentifierEncoding::Modern => self.writer.write_u32::<BigEndian>(creation)?,
+        }
+        Ok(())
+    }
     fn encode_external_fun(&mut self, x: &ExternalFun) -> EncodeResult {
         self.writer.write_u8(EXPORT_EXT)?;
         self.encode_atom(&x.module)?;
@@ -636,7 +751,8 @@ impl<W: std::io::Write> Encoder<W> {
 
                 let mut buf = Vec::new();
                 {
-                    let mut tmp = Encoder::new(&mut buf);
+                    let mut tmp =
+                        Encoder::with_identifier_encoding(&mut buf, self.identifier_encoding);
                     tmp.writer.write_u8(arity)?;
                     tmp.writer.write_all(uniq)?;
                     tmp.writer.write_u32::<BigEndian>(index)?;
@@ -39,14 +39,9 @@ pub fn invalid_data_error<T>(message: String) -> std::io::Result<T> {
         message,
     ))
 }
-pub fn other_error<T>(message: String) -> std::io::Result<T> {
-    Err(std::io::Error::new(std::io::ErrorKind::Other, message))
-}
 pub fn latin1_bytes_to_string(buf: &[u8]) -> std::io::Result<String> {
-    // FIXME: Supports Latin1 characters
-    std::str::from_utf8(buf)
-        .or_else(|e| other_error(e.to_string()))
-        .map(|s| s.to_string())
+    // Latin-1 code points are the first 256 Unicode scalar values
+    Ok(buf.iter().map(|&b| b as char).collect())
 }
 pub fn byte_to_sign(b: u8) -> std::io::Result<Sign> {
     match b {
@@ -0,0 +1,138 @@
+//! [Serde](https://serde.rs) support for [Term], enabled by the `serde` feature.
+//!
+//! Rust values map to terms the way they are usually written in Erlang:
+//!
+//! - `bool` is `true` or `false`, and `None` is `undefined`
+//! - strings, `&[u8]` and `Vec<u8>` are binaries
+//! - other sequences are lists and tuples are tuples
+//! - maps are maps
+//! - structs are records: tuples tagged with the snake case struct name, such as `{point, 1, 2}`
+//! - unit variants are atoms, and other variants are tuples tagged with the variant name
+//!
+//! Deserializing also accepts charlists as strings, structs from maps with atom keys and any
+//! list of bytes as `Vec<u8>`.
+//!
+//! # Examples
+//!
+//!     use serde::{Deserialize, Serialize};
+//!     use liblumen_beam::serialization::etf::{self, Term};
+//!
+//!     #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//!     enum Shape {
+//!         Circle { radius: f64 },
+//!         Point,
+//!     }
+//!
+//!     let term = etf::serde::to_term(&vec![Shape::Circle { radius: 1.0 }, Shape::Point]).unwrap();
+//!     assert_eq!("[{'circle',1},'point']", term.to_string());
+//!
+//!     let shapes: Vec<Shape> = etf::serde::from_term(&term).unwrap();
+//!     assert_eq!(vec![Shape::Circle { radius: 1.0 }, Shape::Point], shapes);
+mod de;
+mod ser;
+#[cfg(test)]
+mod test;
+
+use std::fmt::{self, Display};
+use std::io::{Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{DecodeError, EncodeError, Term};
+
+pub use self::de::Deserializer;
+pub use self::ser::Serializer;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors which can occur when converting between Rust values and terms
+#[derive(Debug)]
+pub enum Error {
+    Message(String),
+    Decode(Box<DecodeError>),
+    Encode(EncodeError),
+    UnexpectedTerm { term: Box<Term>, expected: String },
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Message(ref message) => f.write_str(message),
+            Error::Decode(ref error) => error.fmt(f),
+            Error::Encode(ref error) => error.fmt(f),
+            Error::UnexpectedTerm {
+                ref term,
+                ref expected,
+            } => write!(f, "unexpected term {}, expected {}", term, expected),
+        }
+    }
+}
+impl std::error::Error for Error {}
+impl serde::ser::Error for Error {
+    fn custom<T: Display>(message: T) -> Self {
+        Error::Message(message.to_string())
+    }
+}
+impl serde::de::Error for Error {
+    fn custom<T: Display>(message: T) -> Self {
+        Error::Message(message.to_string())
+    }
+}
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Self {
+        Error::Decode(Box::new(error))
+    }
+}
+impl From<EncodeError> for Error {
+    fn from(error: EncodeError) -> Self {
+        Error::Encode(error)
+    }
+}
+
+/// Converts `value` to a term.
+pub fn to_term<T: ?Sized + Serialize>(value: &T) -> Result<Term> {
+    value.serialize(Serializer)
+}
+
+/// Converts `term` to a Rust value, which may borrow strings and binaries from `term`.
+pub fn from_term<'de, T: Deserialize<'de>>(term: &'de Term) -> Result<T> {
+    T::deserialize(Deserializer::new(term))
+}
+
+/// Encodes `value` in the external term format, like [Term::encode].
+pub fn to_writer<W: Write, T: ?Sized + Serialize>(writer: W, value: &T) -> Result<()> {
+    to_term(value)?.encode(writer)?;
+
+    Ok(())
+}
+
+/// Decodes a Rust value from the external term format, like [Term::decode].
+pub fn from_reader<R: Read, T: DeserializeOwned>(reader: R) -> Result<T> {
+    let term = Term::decode(reader)?;
+
+    from_term(&term)
+}
+
+/// The atom tagging records and variants: `name` in snake case, so `HttpRequest` is
+/// `http_request`.
+fn record_tag(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut tag = String::with_capacity(name.len() + 4);
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_lowercase = 0 < i && !chars[i - 1].is_uppercase() && chars[i - 1] != '_';
+            let ends_acronym = 0 < i
+                && chars[i - 1].is_uppercase()
+                && matches!(chars.get(i + 1), Some(next) if next.is_lowercase());
+            if after_lowercase || ends_acronym {
+                tag.push('_');
+            }
+            tag.extend(c.to_lowercase());
+        } else {
+            tag.push(*c);
+        }
+    }
+
+    tag
+}
@@ -0,0 +1,399 @@
+use std::slice;
+
+use num::bigint::BigInt;
+use num::ToPrimitive;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+
+use super::{record_tag, Error, Result};
+use crate::serialization::etf::Term;
+
+/// Converts a [Term] to Rust values.
+#[derive(Clone, Copy)]
+pub struct Deserializer<'de> {
+    term: &'de Term,
+}
+impl<'de> Deserializer<'de> {
+    pub fn new(term: &'de Term) -> Self {
+        Deserializer { term }
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        Err(Error::UnexpectedTerm {
+            term: Box::new(self.term.clone()),
+            expected: expected.to_string(),
+        })
+    }
+
+    /// The fields of a record tagged with `name`
+    fn record_fields(&self, name: &str) -> Result<&'de [Term]> {
+        let tag = record_tag(name);
+        if let Term::Tuple(ref tuple) = *self.term {
+            if let Some((Term::Atom(atom), fields)) = tuple.elements.split_first() {
+                if atom.name == tag {
+                    return Ok(fields);
+                }
+            }
+        }
+
+        self.unexpected(&format!("{{{}, ...}}", tag))
+    }
+
+    /// The characters of a binary or a charlist
+    fn string(&self) -> Result<String> {
+        match *self.term {
+            Term::Binary(ref binary) => match std::str::from_utf8(&binary.bytes) {
+                Ok(string) => Ok(string.to_string()),
+                Err(_) => self.unexpected("UTF-8 binary"),
+            },
+            Term::List(ref list) => {
+                let mut string = String::with_capacity(list.elements.len());
+                for element in &list.elements {
+                    match *element {
+                        Term::FixInteger(ref integer) => {
+                            match std::char::from_u32(integer.value as u32) {
+                                Some(c) => string.push(c),
+                                None => return self.unexpected("charlist"),
+                            }
+                        }
+                        _ => return self.unexpected("charlist"),
+                    }
+                }
+
+                Ok(string)
+            }
+            _ => self.unexpected("binary or charlist"),
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Atom(ref atom) => match atom.name.as_str() {
+                "true" => visitor.visit_bool(true),
+                "false" => visitor.visit_bool(false),
+                name => visitor.visit_borrowed_str(name),
+            },
+            Term::FixInteger(ref integer) => visitor.visit_i32(integer.value),
+            Term::BigInteger(ref integer) => visit_big_integer(&integer.value, visitor),
+            Term::Float(ref float) => visitor.visit_f64(float.value),
+            Term::Binary(ref binary) => visitor.visit_borrowed_bytes(&binary.bytes),
+            Term::List(ref list) => visit_elements(&list.elements, visitor),
+            Term::Tuple(ref tuple) => visit_elements(&tuple.elements, visitor),
+            Term::Map(ref map) => visitor.visit_map(MapDeserializer {
+                entries: map.entries.iter(),
+                value: None,
+            }),
+            _ => self.unexpected("a term with a Rust equivalent"),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Atom(ref atom) if atom.name == "true" => visitor.visit_bool(true),
+            Term::Atom(ref atom) if atom.name == "false" => visitor.visit_bool(false),
+            _ => self.unexpected("true or false"),
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::FixInteger(ref integer) => match std::char::from_u32(integer.value as u32) {
+                Some(c) => visitor.visit_char(c),
+                None => self.unexpected("character"),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Atom(ref atom) => visitor.visit_borrowed_str(&atom.name),
+            Term::Binary(ref binary) => match std::str::from_utf8(&binary.bytes) {
+                Ok(string) => visitor.visit_borrowed_str(string),
+                Err(_) => self.unexpected("UTF-8 binary"),
+            },
+            _ => visitor.visit_string(self.string()?),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Binary(ref binary) => visitor.visit_borrowed_bytes(&binary.bytes),
+            Term::List(ref list) => {
+                let mut bytes = Vec::with_capacity(list.elements.len());
+                for element in &list.elements {
+                    match *element {
+                        Term::FixInteger(ref integer)
+                            if 0 <= integer.value && integer.value < 0x100 =>
+                        {
+                            bytes.push(integer.value as u8)
+                        }
+                        _ => return self.unexpected("binary or list of bytes"),
+                    }
+                }
+                visitor.visit_byte_buf(bytes)
+            }
+            _ => self.unexpected("binary or list of bytes"),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Atom(ref atom) if atom.name == "undefined" => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Tuple(ref tuple) if tuple.elements.is_empty() => visitor.visit_unit(),
+            _ => self.unexpected("{}"),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        if self.record_fields(name)?.is_empty() {
+            visitor.visit_unit()
+        } else {
+            self.unexpected(&format!("{{{}}}", record_tag(name)))
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Binary(ref binary) => visitor.visit_seq(BytesDeserializer {
+                bytes: binary.bytes.iter(),
+            }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Tuple(ref tuple) => visit_elements(&tuple.elements, visitor),
+            _ => self.unexpected("tuple"),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visit_elements(self.record_fields(name)?, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match *self.term {
+            Term::Map(_) => self.deserialize_any(visitor),
+            _ => self.unexpected("map"),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match *self.term {
+            Term::Map(_) => self.deserialize_any(visitor),
+            _ => visit_elements(self.record_fields(name)?, visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let (tag, fields): (&str, &[Term]) = match *self.term {
+            Term::Atom(ref atom) => (&atom.name, &[]),
+            Term::Tuple(ref tuple) => match tuple.elements.split_first() {
+                Some((Term::Atom(atom), fields)) => (&atom.name, fields),
+                _ => return self.unexpected("atom or tagged tuple"),
+            },
+            _ => return self.unexpected("atom or tagged tuple"),
+        };
+        let variant = variants
+            .iter()
+            .find(|variant| record_tag(variant) == tag)
+            .copied()
+            .unwrap_or(tag);
+
+        visitor.visit_enum(EnumDeserializer { variant, fields })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64
+    }
+}
+
+fn visit_big_integer<'de, V: Visitor<'de>>(value: &BigInt, visitor: V) -> Result<V::Value> {
+    if let Some(value) = value.to_i64() {
+        visitor.visit_i64(value)
+    } else if let Some(value) = value.to_u64() {
+        visitor.visit_u64(value)
+    } else if let Some(value) = value.to_i128() {
+        visitor.visit_i128(value)
+    } else if let Some(value) = value.to_u128() {
+        visitor.visit_u128(value)
+    } else {
+        Err(de::Error::custom(format!(
+            "{} does not fit in 128 bits",
+            value
+        )))
+    }
+}
+
+/// Visits `elements` as a sequence, which must be completely consumed.
+fn visit_elements<'de, V: Visitor<'de>>(elements: &'de [Term], visitor: V) -> Result<V::Value> {
+    let mut seq = SeqDeserializer {
+        elements: elements.iter(),
+    };
+    let value = visitor.visit_seq(&mut seq)?;
+
+    if seq.elements.len() == 0 {
+        Ok(value)
+    } else {
+        Err(de::Error::invalid_length(elements.len(), &"fewer elements"))
+    }
+}
+
+struct SeqDeserializer<'de> {
+    elements: slice::Iter<'de, Term>,
+}
+impl<'de> de::SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.elements.next() {
+            Some(term) => seed.deserialize(Deserializer::new(term)).map(Some),
+            None => Ok(None),
+        }
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+struct BytesDeserializer<'de> {
+    bytes: slice::Iter<'de, u8>,
+}
+impl<'de> de::SeqAccess<'de> for BytesDeserializer<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.bytes.next() {
+            Some(byte) => seed.deserialize(byte.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.bytes.len())
+    }
+}
+
+struct MapDeserializer<'de> {
+    entries: slice::Iter<'de, (Term, Term)>,
+    value: Option<&'de Term>,
+}
+impl<'de> de::MapAccess<'de> for MapDeserializer<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Deserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer::new(value))
+    }
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// A variant named `variant`, which was an atom or the tag of a tuple with `fields`
+struct EnumDeserializer<'a, 'de> {
+    variant: &'a str,
+    fields: &'de [Term],
+}
+impl<'a, 'de> de::EnumAccess<'de> for EnumDeserializer<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let variant: de::value::StrDeserializer<Error> = self.variant.into_deserializer();
+
+        Ok((seed.deserialize(variant)?, self))
+    }
+}
+impl<'a, 'de> de::VariantAccess<'de> for EnumDeserializer<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        if self.fields.is_empty() {
+            Ok(())
+        } else {
+            Err(de::Error::invalid_length(self.fields.len(), &"no fields"))
+        }
+    }
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        match *self.fields {
+            [ref field] => seed.deserialize(Deserializer::new(field)),
+            _ => Err(de::Error::invalid_length(self.fields.len(), &"one field")),
+        }
+    }
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        visit_elements(self.fields, visitor)
+    }
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visit_elements(self.fields, visitor)
+    }
+}
@@ -0,0 +1,411 @@
+use num::bigint::BigInt;
+use serde::ser::{self, Impossible, Serialize};
+
+use super::{record_tag, Error, Result};
+use crate::serialization::etf::{
+    Atom, BigInteger, Binary, FixInteger, Float, List, Map, Term, Tuple,
+};
+
+/// Converts Rust values to [Term]s.
+pub struct Serializer;
+impl ser::Serializer for Serializer {
+    type Ok = Term;
+    type Error = Error;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeTuple;
+    type SerializeTupleStruct = SerializeTuple;
+    type SerializeTupleVariant = SerializeTuple;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeTuple;
+    type SerializeStructVariant = SerializeTuple;
+
+    fn serialize_bool(self, v: bool) -> Result<Term> {
+        Ok(atom(if v { "true" } else { "false" }))
+    }
+    fn serialize_i8(self, v: i8) -> Result<Term> {
+        Ok(Term::from(FixInteger::from(i32::from(v))))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Term> {
+        Ok(Term::from(FixInteger::from(i32::from(v))))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Term> {
+        Ok(Term::from(FixInteger::from(v)))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Term> {
+        Ok(integer(BigInt::from(v)))
+    }
+    fn serialize_i128(self, v: i128) -> Result<Term> {
+        Ok(integer(BigInt::from(v)))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Term> {
+        Ok(Term::from(FixInteger::from(i32::from(v))))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Term> {
+        Ok(Term::from(FixInteger::from(i32::from(v))))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Term> {
+        Ok(integer(BigInt::from(v)))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Term> {
+        Ok(integer(BigInt::from(v)))
+    }
+    fn serialize_u128(self, v: u128) -> Result<Term> {
+        Ok(integer(BigInt::from(v)))
+    }
+    fn serialize_f32(self, v: f32) -> Result<Term> {
+        Ok(Term::from(Float::from(f64::from(v))))
+    }
+    fn serialize_f64(self, v: f64) -> Result<Term> {
+        Ok(Term::from(Float::from(v)))
+    }
+    fn serialize_char(self, v: char) -> Result<Term> {
+        Ok(Term::from(FixInteger::from(v as i32)))
+    }
+    fn serialize_str(self, v: &str) -> Result<Term> {
+        Ok(Term::from(Binary::from(v.as_bytes().to_vec())))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Term> {
+        Ok(Term::from(Binary::from(v.to_vec())))
+    }
+    fn serialize_none(self) -> Result<Term> {
+        Ok(atom("undefined"))
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Term> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Term> {
+        Ok(Term::from(Tuple::from(Vec::new())))
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Term> {
+        Ok(Term::from(Tuple::from(vec![atom(&record_tag(name))])))
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Term> {
+        Ok(atom(&record_tag(variant)))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Term> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Term> {
+        Ok(Term::from(Tuple::from(vec![
+            atom(&record_tag(variant)),
+            value.serialize(self)?,
+        ])))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList> {
+        Ok(SerializeList {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+            bytes: Some(Vec::with_capacity(len.unwrap_or(0))),
+        })
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SerializeTuple> {
+        Ok(SerializeTuple {
+            elements: Vec::with_capacity(len),
+        })
+    }
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<SerializeTuple> {
+        Ok(SerializeTuple::tagged(name, len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTuple> {
+        Ok(SerializeTuple::tagged(variant, len))
+    }
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap> {
+        Ok(SerializeMap {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<SerializeTuple> {
+        Ok(SerializeTuple::tagged(name, len))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTuple> {
+        Ok(SerializeTuple::tagged(variant, len))
+    }
+}
+
+/// Serializes sequences as lists, except that non-empty sequences of `u8`, such as `Vec<u8>`,
+/// are binaries.
+pub struct SerializeList {
+    elements: Vec<Term>,
+    /// The elements while all of them are `u8`
+    bytes: Option<Vec<u8>>,
+}
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        if self.bytes.is_some() {
+            match value.serialize(ByteSerializer) {
+                Ok(byte) => self.bytes.as_mut().unwrap().push(byte),
+                Err(_) => self.bytes = None,
+            }
+        }
+        self.elements.push(value.serialize(Serializer)?);
+
+        Ok(())
+    }
+    fn end(self) -> Result<Term> {
+        match self.bytes {
+            Some(bytes) if !bytes.is_empty() => Ok(Term::from(Binary::from(bytes))),
+            _ => Ok(Term::from(List::from(self.elements))),
+        }
+    }
+}
+
+/// Serializes tuples, and structs and variants as tagged tuples.
+pub struct SerializeTuple {
+    elements: Vec<Term>,
+}
+impl SerializeTuple {
+    fn tagged(name: &str, len: usize) -> Self {
+        let mut elements = Vec::with_capacity(1 + len);
+        elements.push(atom(&record_tag(name)));
+
+        SerializeTuple { elements }
+    }
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.elements.push(value.serialize(Serializer)?);
+
+        Ok(())
+    }
+    fn into_term(self) -> Term {
+        Term::from(Tuple::from(self.elements))
+    }
+}
+impl ser::SerializeTuple for SerializeTuple {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Term> {
+        Ok(self.into_term())
+    }
+}
+impl ser::SerializeTupleStruct for SerializeTuple {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Term> {
+        Ok(self.into_term())
+    }
+}
+impl ser::SerializeTupleVariant for SerializeTuple {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Term> {
+        Ok(self.into_term())
+    }
+}
+impl ser::SerializeStruct for SerializeTuple {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Term> {
+        Ok(self.into_term())
+    }
+}
+impl ser::SerializeStructVariant for SerializeTuple {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.push(value)
+    }
+    fn end(self) -> Result<Term> {
+        Ok(self.into_term())
+    }
+}
+
+pub struct SerializeMap {
+    entries: Vec<(Term, Term)>,
+    key: Option<Term>,
+}
+impl ser::SerializeMap for SerializeMap {
+    type Ok = Term;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(Serializer)?);
+
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.entries.push((key, value.serialize(Serializer)?));
+
+        Ok(())
+    }
+    fn end(self) -> Result<Term> {
+        Ok(Term::from(Map::from(self.entries)))
+    }
+}
+
+/// Only serializes `u8`, so [SerializeList] can tell if a sequence is bytes.
+struct ByteSerializer;
+
+macro_rules! not_a_byte {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<u8> {
+                Err(Error::Message("not a byte".to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ByteSerializer {
+    type Ok = u8;
+    type Error = Error;
+    type SerializeSeq = Impossible<u8, Error>;
+    type SerializeTuple = Impossible<u8, Error>;
+    type SerializeTupleStruct = Impossible<u8, Error>;
+    type SerializeTupleVariant = Impossible<u8, Error>;
+    type SerializeMap = Impossible<u8, Error>;
+    type SerializeStruct = Impossible<u8, Error>;
+    type SerializeStructVariant = Impossible<u8, Error>;
+
+    fn serialize_u8(self, v: u8) -> Result<u8> {
+        Ok(v)
+    }
+
+    not_a_byte! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<u8> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _value: &T,
+    ) -> Result<u8> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u8> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(Error::Message("not a byte".to_string()))
+    }
+}
+
+fn atom(name: &str) -> Term {
+    Term::from(Atom::from(name))
+}
+
+/// A `FixInteger` if `value` fits, otherwise a `BigInteger`
+fn integer(value: BigInt) -> Term {
+    use num::ToPrimitive;
+
+    match value.to_i32() {
+        Some(value) => Term::from(FixInteger::from(value)),
+        None => Term::from(BigInteger { value }),
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::beam::reader::chunk::StandardChunk;
+use crate::beam::reader::StandardBeamFile;
+use crate::serialization::etf::{Atom, Binary, FixInteger, List, Map};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum CompileInfo {
+    Options(Vec<CompileOption>),
+    Version(String),
+    Time((u16, u8, u8, u8, u8, u8)),
+    Source(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum CompileOption {
+    Outdir(String),
+    DebugInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Attribute {
+    Vsn(Vec<u128>),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct HttpRequest {
+    method: Method,
+    path: String,
+    body: Vec<u8>,
+    timeout: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Method {
+    Get,
+    Post,
+}
+
+#[test]
+fn fixtures() {
+    for name in &[
+        "reader/test.beam",
+        "reader/Elixir.Unicode.beam",
+        "simple.beam",
+        "ast/test.beam",
+    ] {
+        let beam = StandardBeamFile::from_file(Path::new("tests/testdata").join(name)).unwrap();
+        for chunk in beam.chunks() {
+            if let StandardChunk::CInf(ref chunk) = *chunk {
+                let compile_info: Vec<CompileInfo> = from_reader(&chunk.term[..]).unwrap();
+                assert!(compile_info.iter().any(|info| match *info {
+                    CompileInfo::Source(ref source) => source.starts_with('/'),
+                    _ => false,
+                }));
+                assert_round_trip(&compile_info);
+            }
+        }
+    }
+
+    let beam = StandardBeamFile::from_file("tests/testdata/reader/test.beam").unwrap();
+    for chunk in beam.chunks() {
+        if let StandardChunk::Attr(ref chunk) = *chunk {
+            let attributes: Vec<Attribute> = from_reader(&chunk.term[..]).unwrap();
+            assert_eq!(
+                vec![Attribute::Vsn(vec![96804380222611594373105232420506181676])],
+                attributes
+            );
+            assert_round_trip(&attributes);
+        }
+    }
+}
+
+#[test]
+fn records() {
+    let request = HttpRequest {
+        method: Method::Post,
+        path: "/".to_string(),
+        body: vec![1, 2, 3],
+        timeout: None,
+    };
+    assert_eq!(
+        "{'http_request','post',<<47>>,<<1,2,3>>,'undefined'}",
+        to_term(&request).unwrap().to_string()
+    );
+    assert_round_trip(&request);
+    assert_round_trip(&HttpRequest {
+        method: Method::Get,
+        path: String::new(),
+        body: Vec::new(),
+        timeout: Some(5_000),
+    });
+
+    // Charlists are strings, and maps with atom keys are structs
+    let term = Term::from(Map::from(vec![
+        (atom("method"), atom("get")),
+        (atom("path"), Term::from(List::from(vec![int(47)]))),
+        (atom("body"), Term::from(List::nil())),
+        (atom("timeout"), int(10)),
+    ]));
+    assert_eq!(
+        HttpRequest {
+            method: Method::Get,
+            path: "/".to_string(),
+            body: Vec::new(),
+            timeout: Some(10),
+        },
+        from_term(&term).unwrap()
+    );
+
+    match from_term::<HttpRequest>(&atom("http_request")) {
+        Err(Error::UnexpectedTerm { .. }) => (),
+        other => panic!("expected unexpected term, got {:?}", other),
+    }
+}
+
+#[test]
+fn values() {
+    let mut map = BTreeMap::new();
+    map.insert("one".to_string(), 1_i64);
+    map.insert("big".to_string(), i64::MAX);
+    assert_round_trip(&map);
+
+    assert_eq!("'true'", to_term(&true).unwrap().to_string());
+    assert_eq!("{1,2.5}", to_term(&(1, 2.5)).unwrap().to_string());
+    assert_eq!("[1000,-1]", to_term(&vec![1000, -1]).unwrap().to_string());
+    assert_round_trip(&(Some('a'), vec![-1_i8], 1_u64 << 40, ()));
+
+    let binary = Term::from(Binary::from(b"borrowed".to_vec()));
+    let borrowed: &str = from_term(&binary).unwrap();
+    assert_eq!("borrowed", borrowed);
+}
+
+fn assert_round_trip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let mut buf = Vec::new();
+    to_writer(&mut buf, value).unwrap();
+    assert_eq!(*value, from_reader::<_, T>(&buf[..]).unwrap());
+}
+
+fn atom(name: &str) -> Term {
+    Term::from(Atom::from(name))
+}
+
+fn int(value: i32) -> Term {
+    Term::from(FixInteger::from(value))
+}
@@ -145,6 +145,18 @@ fn pid_test() {
         ])
         .try_into()
     ); // PID_EXT
+    assert_eq!(
+        Ok(Pid::new("foo", 49, 0, 1_000)),
+        decode(&[131, 88, 115, 3, 102, 111, 111, 0, 0, 0, 49, 0, 0, 0, 0, 0, 0, 3, 232]).try_into()
+    ); // NEW_PID_EXT
+    assert_eq!(
+        Ok(Pid::new("foo", 49, 0, 1)),
+        decode(&[
+            131, 121, 1, 2, 3, 4, 5, 6, 7, 8, 103, 115, 3, 102, 111, 111, 0, 0, 0, 49, 0, 0, 0, 0,
+            1
+        ])
+        .try_into()
+    ); // LOCAL_EXT
 
     // Encode
     assert_eq!(
@@ -154,6 +166,14 @@ fn pid_test() {
         ],
         encode(Term::from(Pid::from(("nonode@nohost", 49, 0))))
     );
+    assert_eq!(
+        vec![131, 88, 100, 0, 3, 102, 111, 111, 0, 0, 0, 49, 0, 0, 0, 0, 0, 0, 3, 232],
+        encode_modern(Term::from(Pid::new("foo", 49, 0, 1_000)))
+    );
+    match Term::from(Pid::new("foo", 49, 0, 1_000)).encode(Vec::new()) {
+        Err(EncodeError::TooLargeCreation(1_000)) => (),
+        other => panic!("expected too large creation, got {:?}", other),
+    }
 }
 
 #[test]
@@ -173,6 +193,22 @@ fn port_test() {
         ])
         .try_into()
     ); // PORT_EXT
+    assert_eq!(
+        Ok(Port {
+            node: Atom::from("foo"),
+            id: 366,
+            creation: 1_000,
+        }),
+        decode(&[131, 89, 115, 3, 102, 111, 111, 0, 0, 1, 110, 0, 0, 3, 232]).try_into()
+    ); // NEW_PORT_EXT
+    assert_eq!(
+        Ok(Port {
+            node: Atom::from("foo"),
+            id: 1 << 32,
+            creation: 1_000,
+        }),
+        decode(&[131, 120, 115, 3, 102, 111, 111, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 3, 232]).try_into()
+    ); // V4_PORT_EXT
 
     // Encode
     assert_eq!(
@@ -182,6 +218,14 @@ fn port_test() {
         ],
         encode(Term::from(Port::from(("nonode@nohost", 366))))
     );
+    assert_eq!(
+        vec![131, 89, 100, 0, 3, 102, 111, 111, 0, 0, 1, 110, 0, 0, 0, 0],
+        encode_modern(Term::from(Port::from(("foo", 366))))
+    );
+    assert_eq!(
+        vec![131, 120, 100, 0, 3, 102, 111, 111, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        encode_modern(Term::from(Port::from(("foo", 1 << 32))))
+    );
 }
 
 #[test]
@@ -206,12 +250,25 @@ fn reference_test() {
         // NEW_REFERENCE_EXT
         decode(&[131, 101, 115, 3, 102, 111, 111, 0, 0, 0, 2, 0]).try_into()
     );
+    assert_eq!(
+        Ok(Reference {
+            node: Atom::from("foo"),
+            id: vec![2, 3],
+            creation: 1_000,
+        }),
+        decode(&[131, 90, 0, 2, 115, 3, 102, 111, 111, 0, 0, 3, 232, 0, 0, 0, 2, 0, 0, 0, 3])
+            .try_into()
+    ); // NEWER_REFERENCE_EXT
 
     // Encode
     assert_eq!(
         vec![131, 114, 0, 1, 100, 0, 3, 102, 111, 111, 0, 0, 0, 0, 123],
         encode(Term::from(Reference::from(("foo", 123))))
     );
+    assert_eq!(
+        vec![131, 90, 0, 1, 100, 0, 3, 102, 111, 111, 0, 0, 0, 0, 0, 0, 0, 123],
+        encode_modern(Term::from(Reference::from(("foo", 123))))
+    );
 }
 
 #[test]
@@ -477,6 +534,13 @@ fn encode(term: Term) -> Vec<u8> {
     buf
 }
 
+fn encode_modern(term: Term) -> Vec<u8> {
+    let mut buf = Vec::new();
+    term.encode_with(&mut buf, IdentifierEncoding::Modern)
+        .unwrap();
+    buf
+}
+
 fn decode(bytes: &[u8]) -> Term {
     Term::decode(Cursor::new(bytes)).unwrap()
 }
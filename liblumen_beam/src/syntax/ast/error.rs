@@ -27,26 +27,42 @@ impl Display for UnmatchedTerms {
     }
 }
 
-#[derive(Fail, Debug)]
+#[derive(Debug)]
 pub enum FromBeamError {
-    #[fail(display = "failed to load beam: {}", _0)]
-    IO(#[fail(cause)] std::io::Error),
-
-    #[fail(display = "invalid beam file: {}", _0)]
-    BeamFile(#[fail(cause)] ReadError),
-
-    #[fail(display = "unable to decode term: {}", _0)]
-    TermDecode(#[fail(cause)] etf::DecodeError),
-
-    #[fail(display = "debug info is required but not present")]
+    IO(std::io::Error),
+    BeamFile(ReadError),
+    TermDecode(Box<etf::DecodeError>),
     NoDebugInfo,
-
-    #[fail(display = "missing module attribute")]
+    UnsupportedDebugInfo(String),
+    UnsupportedElixir(String),
     NoModuleAttribute,
-
-    #[fail(display = "unexpected term: {}", _0)]
     UnexpectedTerm(UnmatchedTerms),
 }
+impl Display for FromBeamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::FromBeamError::*;
+        match *self {
+            IO(ref x) => write!(f, "failed to load beam: {}", x),
+            BeamFile(ref x) => write!(f, "invalid beam file: {}", x),
+            TermDecode(ref x) => write!(f, "unable to decode term: {}", x),
+            NoDebugInfo => write!(f, "debug info is required but not present"),
+            UnsupportedDebugInfo(ref x) => write!(f, "unsupported debug info backend: {}", x),
+            UnsupportedElixir(ref x) => write!(f, "unsupported elixir code: {}", x),
+            NoModuleAttribute => write!(f, "missing module attribute"),
+            UnexpectedTerm(ref x) => write!(f, "unexpected term: {}", x),
+        }
+    }
+}
+impl Fail for FromBeamError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        match *self {
+            FromBeamError::IO(ref x) => Some(x),
+            FromBeamError::BeamFile(ref x) => Some(x),
+            FromBeamError::TermDecode(ref x) => Some(&**x),
+            _ => None,
+        }
+    }
+}
 impl From<std::io::Error> for FromBeamError {
     fn from(x: std::io::Error) -> Self {
         FromBeamError::IO(x)
@@ -59,7 +75,7 @@ impl From<ReadError> for FromBeamError {
 }
 impl From<etf::DecodeError> for FromBeamError {
     fn from(x: etf::DecodeError) -> Self {
-        FromBeamError::TermDecode(x)
+        FromBeamError::TermDecode(Box::new(x))
     }
 }
 impl<'a> From<etf::pattern::Unmatch<'a>> for FromBeamError {
@@ -1 +1,2 @@
+pub mod elixir_v1;
 pub mod raw_abstract_v1;
@@ -0,0 +1,1499 @@
+//! Translates the `{elixir_v1, Map, Specs}` debug info written by the `elixir_erl` backend into
+//! Erlang abstract code, like `elixir_erl:debug_info(erlang_v1, ...)` does when Elixir is
+//! available.
+//!
+//! The definitions in `Map` are expanded Elixir AST, so only the special forms which remain after
+//! expansion need to be translated, the same way `elixir_erl_pass` does.  Comprehensions over
+//! bitstring generators and with `uniq: true` are not yet supported and are reported as
+//! `FromBeamError::UnsupportedElixir`.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::serialization::etf::{Atom, FixInteger, List, Term, Tuple};
+use crate::syntax::ast::{FromBeamError, FromBeamResult};
+
+/// Returns the forms for the `debug_info` of `{elixir_v1, Map, Specs}`
+pub fn to_forms(debug_info: &Term) -> FromBeamResult<Vec<Term>> {
+    let elements = tuple_elements(debug_info)
+        .filter(|elements| elements.len() == 3 && is_atom(&elements[0], "elixir_v1"))
+        .ok_or_else(|| unsupported(debug_info))?;
+    let map = match elements[1] {
+        Term::Map(ref map) => map,
+        ref other => return Err(unsupported(other)),
+    };
+    let get = |key: &str| {
+        map.entries
+            .iter()
+            .find(|(k, _)| is_atom(k, key))
+            .map(|(_, v)| v)
+    };
+
+    let line = get("line").and_then(integer).unwrap_or(0);
+    let module = get("module")
+        .and_then(atom_name)
+        .ok_or(FromBeamError::NoModuleAttribute)?;
+    let file = match get("file") {
+        Some(Term::Binary(ref binary)) => String::from_utf8_lossy(&binary.bytes).into_owned(),
+        _ => String::new(),
+    };
+
+    let mut forms = vec![
+        attribute(line, "file", tuple(vec![string(&file), int(line)])),
+        attribute(line, "module", atom(module)),
+        attribute(line, "compile", list(vec![atom("no_auto_import")])),
+    ];
+
+    let definitions = get("definitions")
+        .map(list_elements)
+        .unwrap_or_default()
+        .into_iter()
+        .map(Function::new)
+        .collect::<FromBeamResult<Vec<_>>>()?;
+    let mut exports = vec![tuple(vec![atom("__info__"), int(1)])];
+    let mut functions = vec![info(module, &definitions, &get, line)?];
+    for function in &definitions {
+        if function.is_exported() {
+            exports.push(tuple(vec![atom(&function.name), int(function.arity)]));
+        }
+        functions.push(function.to_form()?);
+    }
+    forms.push(attribute(line, "export", list(exports)));
+
+    for attr in get("attributes").map(list_elements).unwrap_or_default() {
+        match tuple_elements(attr) {
+            Some(elements) if elements.len() == 2 => {
+                let name = atom_name(&elements[0]).ok_or_else(|| unsupported(attr))?;
+                forms.push(attribute(line, name, elements[1].clone()));
+            }
+            _ => return Err(unsupported(attr)),
+        }
+    }
+
+    // Specs are already Erlang abstract code
+    forms.extend(list_elements(&elements[2]).into_iter().cloned());
+    forms.extend(functions);
+
+    Ok(forms)
+}
+
+/// `__info__/1`, which Elixir generates for every module
+fn info<'a>(
+    module: &str,
+    definitions: &[Function],
+    get: &dyn Fn(&str) -> Option<&'a Term>,
+    line: i32,
+) -> FromBeamResult<Term> {
+    let name_arities = |is_macro: bool| {
+        let mut name_arities: Vec<(&str, i32)> = definitions
+            .iter()
+            .filter(|function| function.is_exported() && function.is_macro() == is_macro)
+            .map(|function| function.elixir_name_arity())
+            .collect();
+        name_arities.sort();
+        name_arities.dedup();
+        list(
+            name_arities
+                .into_iter()
+                .map(|(name, arity)| tuple(vec![atom(name), int(arity)]))
+                .collect(),
+        )
+    };
+    let module_info = |key: &str| {
+        remote_call_form(
+            "erlang",
+            "get_module_info",
+            vec![atom_literal(module, line), atom_literal(key, line)],
+            line,
+        )
+    };
+
+    let mut results = vec![
+        ("module", atom_literal(module, line)),
+        ("functions", literal(&name_arities(false), line)?),
+        ("macros", literal(&name_arities(true), line)?),
+        ("attributes", module_info("attributes")),
+        ("compile", module_info("compile")),
+        ("md5", module_info("md5")),
+        (
+            "deprecated",
+            match get("deprecated") {
+                Some(deprecated) => literal(deprecated, line)?,
+                None => nil(line),
+            },
+        ),
+    ];
+    if let Some(r#struct) = get("struct") {
+        results.push(("struct", literal(r#struct, line)?));
+    }
+
+    let clauses = results
+        .into_iter()
+        .map(|(key, result)| clause(vec![atom_literal(key, line)], vec![], vec![result], line))
+        .collect();
+
+    Ok(tuple(vec![
+        atom("function"),
+        int(line),
+        atom("__info__"),
+        int(1),
+        list(clauses),
+    ]))
+}
+
+/// A `{{Name, Arity}, Kind, Meta, Clauses}` definition
+struct Function<'a> {
+    kind: &'a str,
+    name: String,
+    arity: i32,
+    line: i32,
+    clauses: Vec<&'a Term>,
+}
+impl<'a> Function<'a> {
+    fn new(definition: &'a Term) -> FromBeamResult<Self> {
+        let elements = tuple_elements(definition)
+            .filter(|elements| elements.len() == 4)
+            .ok_or_else(|| unsupported(definition))?;
+        let (name, arity) = match tuple_elements(&elements[0]) {
+            Some(name_arity) if name_arity.len() == 2 => (
+                atom_name(&name_arity[0]).ok_or_else(|| unsupported(definition))?,
+                integer(&name_arity[1]).ok_or_else(|| unsupported(definition))?,
+            ),
+            _ => return Err(unsupported(definition)),
+        };
+        let kind = atom_name(&elements[1]).ok_or_else(|| unsupported(definition))?;
+        // Macros are compiled to functions that also take the caller's environment
+        let (name, arity) = match kind {
+            "def" | "defp" => (name.to_string(), arity),
+            "defmacro" | "defmacrop" => (format!("MACRO-{}", name), arity + 1),
+            _ => return Err(unsupported(definition)),
+        };
+
+        Ok(Self {
+            kind,
+            name,
+            arity,
+            line: line(&elements[2], 0),
+            clauses: list_elements(&elements[3]),
+        })
+    }
+
+    fn is_exported(&self) -> bool {
+        self.kind == "def" || self.kind == "defmacro"
+    }
+
+    fn is_macro(&self) -> bool {
+        self.kind == "defmacro" || self.kind == "defmacrop"
+    }
+
+    /// The name and arity in Elixir, before macros are turned into `MACRO-` functions
+    fn elixir_name_arity(&self) -> (&str, i32) {
+        if self.is_macro() {
+            (&self.name["MACRO-".len()..], self.arity - 1)
+        } else {
+            (&self.name, self.arity)
+        }
+    }
+
+    fn to_form(&self) -> FromBeamResult<Term> {
+        let clauses = self
+            .clauses
+            .iter()
+            .map(|clause| self.clause(clause))
+            .collect::<FromBeamResult<Vec<_>>>()?;
+
+        Ok(tuple(vec![
+            atom("function"),
+            int(self.line),
+            atom(&self.name),
+            int(self.arity),
+            list(clauses),
+        ]))
+    }
+
+    /// A `{Meta, Args, Guards, Body}` clause
+    fn clause(&self, clause: &Term) -> FromBeamResult<Term> {
+        let elements = tuple_elements(clause)
+            .filter(|elements| elements.len() == 4)
+            .ok_or_else(|| unsupported(clause))?;
+        let line = line(&elements[0], self.line);
+        let mut patterns = Vec::new();
+        if self.is_macro() {
+            patterns.push(tuple(vec![atom("var"), int(line), atom("_@CALLER")]));
+        }
+        for arg in list_elements(&elements[1]) {
+            patterns.push(pattern(arg, line)?);
+        }
+        let guards = list_elements(&elements[2])
+            .into_iter()
+            .map(|guard| expr(guard, line).map(|guard| list(vec![guard])))
+            .collect::<FromBeamResult<Vec<_>>>()?;
+
+        Ok(tuple(vec![
+            atom("clause"),
+            int(line),
+            list(patterns),
+            list(guards),
+            list(body(&elements[3], line)?),
+        ]))
+    }
+}
+
+const BINARY_OPERATORS: &[&str] = &[
+    "+", "-", "*", "/", "div", "rem", "band", "bor", "bxor", "bsl", "bsr", "and", "or", "xor",
+    "andalso", "orelse", "==", "/=", "=<", "<", ">=", ">", "=:=", "=/=", "++", "--", "!",
+];
+const UNARY_OPERATORS: &[&str] = &["+", "-", "not", "bnot"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    Expr,
+    Pattern,
+}
+
+fn expr(term: &Term, line: i32) -> FromBeamResult<Term> {
+    translate(term, line, Context::Expr)
+}
+
+fn pattern(term: &Term, line: i32) -> FromBeamResult<Term> {
+    translate(term, line, Context::Pattern)
+}
+
+/// The expressions of a body, where a `__block__` is flattened into its expressions
+fn body(term: &Term, line: i32) -> FromBeamResult<Vec<Term>> {
+    match call_parts(term) {
+        Some((Term::Atom(ref name), meta, Some(exprs))) if name.name == "__block__" => {
+            let line = self::line(meta, line);
+            if exprs.is_empty() {
+                return Ok(vec![atom_literal("nil", line)]);
+            }
+            exprs.iter().map(|e| expr(e, line)).collect()
+        }
+        _ => Ok(vec![expr(term, line)?]),
+    }
+}
+
+fn translate(term: &Term, line: i32, context: Context) -> FromBeamResult<Term> {
+    let translate_all = |terms: &[Term], line: i32| {
+        terms
+            .iter()
+            .map(|term| translate(term, line, context))
+            .collect::<FromBeamResult<Vec<_>>>()
+    };
+
+    match *term {
+        Term::Atom(ref a) => Ok(atom_literal(&a.name, line)),
+        Term::FixInteger(_) | Term::BigInteger(_) => {
+            Ok(tuple(vec![atom("integer"), int(line), term.clone()]))
+        }
+        Term::Float(_) => Ok(tuple(vec![atom("float"), int(line), term.clone()])),
+        Term::Binary(ref binary) => {
+            let chars = binary.bytes.iter().map(|&b| int(i32::from(b))).collect();
+            let element = tuple(vec![
+                atom("bin_element"),
+                int(line),
+                tuple(vec![atom("string"), int(line), list(chars)]),
+                atom("default"),
+                atom("default"),
+            ]);
+            Ok(tuple(vec![atom("bin"), int(line), list(vec![element])]))
+        }
+        Term::List(ref l) => {
+            // `[head | tail]` is a list ending in a `{:|, meta, [head, tail]}` call
+            let (elements, tail) = match l.elements.split_last() {
+                Some((last, init)) => match call_parts(last) {
+                    Some((Term::Atom(ref name), _, Some(args)))
+                        if name.name == "|" && args.len() == 2 =>
+                    {
+                        let mut elements = init.to_vec();
+                        elements.push(args[0].clone());
+                        (elements, translate(&args[1], line, context)?)
+                    }
+                    _ => (l.elements.clone(), tuple(vec![atom("nil"), int(line)])),
+                },
+                None => (Vec::new(), tuple(vec![atom("nil"), int(line)])),
+            };
+            translate_all(&elements, line)?
+                .into_iter()
+                .rev()
+                .try_fold(tail, |tail, head| {
+                    Ok(tuple(vec![atom("cons"), int(line), head, tail]))
+                })
+        }
+        Term::Tuple(ref t) if t.elements.len() == 2 => Ok(tuple(vec![
+            atom("tuple"),
+            int(line),
+            list(translate_all(&t.elements, line)?),
+        ])),
+        Term::Tuple(ref t) if t.elements.len() == 3 => {
+            let (callee, meta, args) = (&t.elements[0], &t.elements[1], &t.elements[2]);
+            let line = self::line(meta, line);
+            match (callee, args) {
+                // Variables are `{name, meta, context}`
+                (Term::Atom(name), Term::Atom(var_context)) => {
+                    Ok(var(&name.name, &var_context.name, line))
+                }
+                (Term::Atom(name), Term::List(args)) => {
+                    special_form(term, &name.name, &args.elements, line, context)
+                }
+                (Term::Tuple(_), Term::List(args)) => {
+                    remote_call(term, callee, &args.elements, line, context)
+                }
+                _ => Err(unsupported(term)),
+            }
+        }
+        _ => Err(unsupported(term)),
+    }
+}
+
+fn special_form(
+    term: &Term,
+    name: &str,
+    args: &[Term],
+    line: i32,
+    context: Context,
+) -> FromBeamResult<Term> {
+    match (name, args) {
+        ("{}", elements) => Ok(tuple(vec![
+            atom("tuple"),
+            int(line),
+            list(
+                elements
+                    .iter()
+                    .map(|e| translate(e, line, context))
+                    .collect::<FromBeamResult<_>>()?,
+            ),
+        ])),
+        ("%{}", pairs) => map(term, pairs, line, context),
+        ("=", [left, right]) => Ok(tuple(vec![
+            atom("match"),
+            int(line),
+            pattern(left, line)?,
+            translate(right, line, context)?,
+        ])),
+        ("^", [var]) => translate(var, line, context),
+        ("<<>>", segments) => bitstring(segments, line, context),
+        ("__block__", exprs) => Ok(tuple(vec![
+            atom("block"),
+            int(line),
+            list(
+                exprs
+                    .iter()
+                    .map(|e| expr(e, line))
+                    .collect::<FromBeamResult<_>>()?,
+            ),
+        ])),
+        ("&", [capture]) => fun_capture(term, capture, line),
+        ("fn", clauses) => {
+            let clauses = clauses
+                .iter()
+                .map(|clause| arrow_clause(clause, line))
+                .collect::<FromBeamResult<_>>()?;
+            Ok(tuple(vec![
+                atom("fun"),
+                int(line),
+                tuple(vec![atom("clauses"), list(clauses)]),
+            ]))
+        }
+        ("case", [subject, options]) => {
+            let clauses = do_clauses(options, line)?;
+            Ok(tuple(vec![
+                atom("case"),
+                int(line),
+                expr(subject, line)?,
+                list(clauses),
+            ]))
+        }
+        // `cond` is a `case` on `true` for each condition in turn
+        ("cond", [options]) => {
+            let clauses = do_clauses_terms(options).ok_or_else(|| unsupported(term))?;
+            clauses
+                .iter()
+                .rev()
+                .try_fold(atom_literal("nil", line), |otherwise, clause| {
+                    let (condition, right) =
+                        arrow_parts(clause).ok_or_else(|| unsupported(term))?;
+                    let condition = match condition {
+                        [condition] => expr(condition, line)?,
+                        _ => return Err(unsupported(clause)),
+                    };
+                    let when_true = tuple(vec![
+                        atom("clause"),
+                        int(line),
+                        list(vec![atom_literal("true", line)]),
+                        list(vec![]),
+                        list(body(right, line)?),
+                    ]);
+                    let otherwise = tuple(vec![
+                        atom("clause"),
+                        int(line),
+                        list(vec![tuple(vec![atom("var"), int(line), atom("_")])]),
+                        list(vec![]),
+                        list(vec![otherwise]),
+                    ]);
+                    Ok(tuple(vec![
+                        atom("case"),
+                        int(line),
+                        condition,
+                        list(vec![when_true, otherwise]),
+                    ]))
+                })
+        }
+        ("for", args) if context == Context::Expr => comprehension(term, args, line),
+        ("with", args) if context == Context::Expr => with(term, args, line),
+        ("try", [options]) if context == Context::Expr => r#try(term, options, line),
+        ("receive", [options]) if context == Context::Expr => receive(term, options, line),
+        // A local call
+        (name, args) if context == Context::Expr && !is_special(name) => Ok(tuple(vec![
+            atom("call"),
+            int(line),
+            atom_literal(name, line),
+            list(
+                args.iter()
+                    .map(|arg| expr(arg, line))
+                    .collect::<FromBeamResult<_>>()?,
+            ),
+        ])),
+        _ => Err(unsupported(term)),
+    }
+}
+
+/// Special forms that must not be mistaken for local calls where they are not supported
+fn is_special(name: &str) -> bool {
+    matches!(
+        name,
+        "<<>>" | "for" | "with" | "try" | "receive" | "super" | "__aliases__" | "::"
+    )
+}
+
+const BIT_TYPES: &[&str] = &[
+    "integer",
+    "float",
+    "bits",
+    "bitstring",
+    "binary",
+    "bytes",
+    "utf8",
+    "utf16",
+    "utf32",
+    "signed",
+    "unsigned",
+    "big",
+    "little",
+    "native",
+];
+
+/// `<<segment, ...>>`, where each segment is expanded to `value :: type`
+fn bitstring(segments: &[Term], line: i32, context: Context) -> FromBeamResult<Term> {
+    let elements = segments
+        .iter()
+        .map(|segment| {
+            let (value, spec) = match call_parts(segment) {
+                Some((Term::Atom(ref colons), _, Some([value, spec]))) if colons.name == "::" => {
+                    (value, Some(spec))
+                }
+                _ => (segment, None),
+            };
+            let mut size = atom("default");
+            let mut types = Vec::new();
+            if let Some(spec) = spec {
+                bit_type(spec, line, context, &mut size, &mut types)?;
+            }
+            let is_default_size = is_atom(&size, "default");
+            let value = match *value {
+                // A binary literal is a string segment, as `<<"..."/binary>>` is not allowed
+                Term::Binary(ref binary)
+                    if is_default_size
+                        && types.iter().all(|t| {
+                            matches!(
+                                atom_name(t),
+                                Some("binary") | Some("bitstring") | Some("bits") | Some("bytes")
+                            )
+                        }) =>
+                {
+                    types.clear();
+                    tuple(vec![
+                        atom("string"),
+                        int(line),
+                        list(binary.bytes.iter().map(|&b| int(i32::from(b))).collect()),
+                    ])
+                }
+                _ => translate(value, line, context)?,
+            };
+            let types = if types.is_empty() {
+                atom("default")
+            } else {
+                list(types)
+            };
+
+            Ok(tuple(vec![
+                atom("bin_element"),
+                int(line),
+                value,
+                size,
+                types,
+            ]))
+        })
+        .collect::<FromBeamResult<Vec<_>>>()?;
+
+    Ok(tuple(vec![atom("bin"), int(line), list(elements)]))
+}
+
+/// Adds the size and types of a `type-size(n)-unit(n)` segment type
+fn bit_type(
+    spec: &Term,
+    line: i32,
+    context: Context,
+    size: &mut Term,
+    types: &mut Vec<Term>,
+) -> FromBeamResult<()> {
+    match call_parts(spec) {
+        Some((Term::Atom(ref name), _, Some([left, right]))) if name.name == "-" => {
+            bit_type(left, line, context, size, types)?;
+            bit_type(right, line, context, size, types)
+        }
+        Some((Term::Atom(ref name), _, Some([value]))) if name.name == "size" => {
+            *size = translate(value, line, context)?;
+            Ok(())
+        }
+        Some((Term::Atom(ref name), _, Some([unit]))) if name.name == "unit" => {
+            let unit = integer(unit).ok_or_else(|| unsupported(spec))?;
+            types.push(tuple(vec![atom("unit"), int(unit)]));
+            Ok(())
+        }
+        Some((Term::Atom(ref name), _, args))
+            if args.unwrap_or_default().is_empty() && BIT_TYPES.contains(&name.name.as_str()) =>
+        {
+            types.push(atom(&name.name));
+            Ok(())
+        }
+        _ => Err(unsupported(spec)),
+    }
+}
+
+/// `for` reduces each enumerable generator with `Enum.reduce/3` and filters with `case`, like
+/// `elixir_erl_for` does for comprehensions that cannot be inlined
+fn comprehension(term: &Term, args: &[Term], line: i32) -> FromBeamResult<Term> {
+    let (options, qualifiers) = args.split_last().ok_or_else(|| unsupported(term))?;
+    check_keywords(term, options, &["do", "into", "reduce"])?;
+    let body = keyword(options, "do").ok_or_else(|| unsupported(term))?;
+
+    // `into: []` is the same as no `into`
+    let into = match keyword(options, "into") {
+        Some(Term::List(ref into)) if into.elements.is_empty() => None,
+        into => into,
+    };
+
+    match (keyword(options, "reduce"), into) {
+        (Some(initial), None) => {
+            let clauses = match *body {
+                Term::List(ref clauses) => clauses
+                    .elements
+                    .iter()
+                    .map(|clause| arrow_clause(clause, line))
+                    .collect::<FromBeamResult<Vec<_>>>()?,
+                _ => return Err(unsupported(term)),
+            };
+
+            reduce(qualifiers, expr(initial, line)?, line, &|acc| {
+                Ok(tuple(vec![
+                    atom("case"),
+                    int(line),
+                    acc,
+                    list(clauses.clone()),
+                ]))
+            })
+        }
+        // Without `into`, the elements are collected into a list
+        (None, None) => {
+            let element = block(body, line)?;
+            let reduced = reduce(qualifiers, nil(line), line, &|acc| {
+                Ok(tuple(vec![atom("cons"), int(line), element.clone(), acc]))
+            })?;
+
+            Ok(remote_call_form("lists", "reverse", vec![reduced], line))
+        }
+        (None, Some(into)) => collect(term, qualifiers, into, body, line),
+        _ => Err(unsupported(term)),
+    }
+}
+
+/// `for` with `into:` collects through the `Collectable` protocol
+fn collect(
+    term: &Term,
+    qualifiers: &[Term],
+    into: &Term,
+    body: &Term,
+    line: i32,
+) -> FromBeamResult<Term> {
+    if qualifiers.is_empty() {
+        return Err(unsupported(term));
+    }
+
+    let into_acc = new_var("into", line);
+    let into_fun = new_var("into_fun", line);
+    let element = block(body, line)?;
+    let reduced = reduce(qualifiers, into_acc.clone(), line, &|acc| {
+        Ok(tuple(vec![
+            atom("call"),
+            int(line),
+            into_fun.clone(),
+            list(vec![
+                acc,
+                tuple(vec![
+                    atom("tuple"),
+                    int(line),
+                    list(vec![atom_literal("cont", line), element.clone()]),
+                ]),
+            ]),
+        ]))
+    })?;
+    let collected = tuple(vec![
+        atom("call"),
+        int(line),
+        into_fun.clone(),
+        list(vec![reduced, atom_literal("done", line)]),
+    ]);
+    let into_pattern = tuple(vec![
+        atom("tuple"),
+        int(line),
+        list(vec![into_acc, into_fun]),
+    ]);
+
+    Ok(tuple(vec![
+        atom("case"),
+        int(line),
+        remote_call_form("Elixir.Collectable", "into", vec![expr(into, line)?], line),
+        list(vec![clause(
+            vec![into_pattern],
+            vec![],
+            vec![collected],
+            line,
+        )]),
+    ]))
+}
+
+/// Folds `acc` over the generators and filters in `qualifiers`, with `inner` building the new
+/// accumulator from the innermost one
+fn reduce(
+    qualifiers: &[Term],
+    acc: Term,
+    line: i32,
+    inner: &dyn Fn(Term) -> FromBeamResult<Term>,
+) -> FromBeamResult<Term> {
+    let (qualifier, rest) = match qualifiers.split_first() {
+        Some(split) => split,
+        None => return inner(acc),
+    };
+
+    match call_parts(qualifier) {
+        Some((Term::Atom(ref arrow), _, Some([left, enumerable]))) if arrow.name == "<-" => {
+            let (patterns, guards) = split_when(std::slice::from_ref(left), line)?;
+            let pattern = match patterns {
+                [pattern] => self::pattern(pattern, line)?,
+                _ => return Err(unsupported(qualifier)),
+            };
+            let next_acc = new_var("acc", line);
+            let fun = tuple(vec![
+                atom("fun"),
+                int(line),
+                tuple(vec![
+                    atom("clauses"),
+                    list(vec![
+                        clause(
+                            vec![pattern, next_acc.clone()],
+                            guards,
+                            vec![reduce(rest, next_acc.clone(), line, inner)?],
+                            line,
+                        ),
+                        clause(
+                            vec![underscore(line), next_acc.clone()],
+                            vec![],
+                            vec![next_acc],
+                            line,
+                        ),
+                    ]),
+                ]),
+            ]);
+
+            Ok(remote_call_form(
+                "Elixir.Enum",
+                "reduce",
+                vec![expr(enumerable, line)?, acc, fun],
+                line,
+            ))
+        }
+        Some((Term::Atom(ref name), _, _)) if name.name == "<<>>" => Err(unsupported(qualifier)),
+        // A filter skips the element when it is `false` or `nil`
+        _ => Ok(tuple(vec![
+            atom("case"),
+            int(line),
+            expr(qualifier, line)?,
+            list(vec![
+                clause(
+                    vec![atom_literal("false", line)],
+                    vec![],
+                    vec![acc.clone()],
+                    line,
+                ),
+                clause(
+                    vec![atom_literal("nil", line)],
+                    vec![],
+                    vec![acc.clone()],
+                    line,
+                ),
+                clause(
+                    vec![underscore(line)],
+                    vec![],
+                    vec![reduce(rest, acc, line, inner)?],
+                    line,
+                ),
+            ]),
+        ])),
+    }
+}
+
+/// `with` is a `case` for each `pattern <- expression`, where a value that does not match is
+/// returned or matched by the `else` clauses
+fn with(term: &Term, args: &[Term], line: i32) -> FromBeamResult<Term> {
+    let (options, clauses) = args.split_last().ok_or_else(|| unsupported(term))?;
+    check_keywords(term, options, &["do", "else"])?;
+    let mut exprs = body(
+        keyword(options, "do").ok_or_else(|| unsupported(term))?,
+        line,
+    )?;
+    let else_clauses = match keyword(options, "else") {
+        Some(Term::List(ref clauses)) => Some(
+            clauses
+                .elements
+                .iter()
+                .map(|clause| arrow_clause(clause, line))
+                .collect::<FromBeamResult<Vec<_>>>()?,
+        ),
+        Some(_) => return Err(unsupported(term)),
+        None => None,
+    };
+
+    for clause in clauses.iter().rev() {
+        match call_parts(clause) {
+            Some((Term::Atom(ref arrow), _, Some([left, right]))) if arrow.name == "<-" => {
+                let (patterns, guards) = split_when(std::slice::from_ref(left), line)?;
+                let pattern = match patterns {
+                    [pattern] => self::pattern(pattern, line)?,
+                    _ => return Err(unsupported(clause)),
+                };
+                let other = new_var("other", line);
+                let otherwise = match else_clauses {
+                    Some(ref else_clauses) => else_case(other.clone(), else_clauses, line),
+                    None => other.clone(),
+                };
+                exprs = vec![tuple(vec![
+                    atom("case"),
+                    int(line),
+                    expr(right, line)?,
+                    list(vec![
+                        self::clause(vec![pattern], guards, exprs, line),
+                        self::clause(vec![other], vec![], vec![otherwise], line),
+                    ]),
+                ])];
+            }
+            _ => exprs.insert(0, expr(clause, line)?),
+        }
+    }
+
+    Ok(block_form(exprs, line))
+}
+
+/// Matches `other` with the `else` clauses of `with`, failing with a `WithClauseError` if none
+/// match
+fn else_case(other: Term, else_clauses: &[Term], line: i32) -> Term {
+    let unmatched = new_var("unmatched", line);
+    let error = remote_call_form(
+        "Elixir.WithClauseError",
+        "exception",
+        vec![tuple(vec![
+            atom("cons"),
+            int(line),
+            tuple(vec![
+                atom("tuple"),
+                int(line),
+                list(vec![atom_literal("term", line), unmatched.clone()]),
+            ]),
+            nil(line),
+        ])],
+        line,
+    );
+    let mut clauses = else_clauses.to_vec();
+    clauses.push(clause(
+        vec![unmatched],
+        vec![],
+        vec![remote_call_form("erlang", "error", vec![error], line)],
+        line,
+    ));
+
+    tuple(vec![atom("case"), int(line), other, list(clauses)])
+}
+
+/// `try` with `rescue`, `catch`, `else` and `after`, where `rescue` only catches errors, after
+/// normalizing them to exceptions with `Exception.normalize/3`
+fn r#try(term: &Term, options: &Term, line: i32) -> FromBeamResult<Term> {
+    check_keywords(term, options, &["do", "rescue", "catch", "else", "after"])?;
+    let clauses = |key: &str| -> FromBeamResult<Vec<&Term>> {
+        match keyword(options, key) {
+            Some(Term::List(ref clauses)) => Ok(clauses.elements.iter().collect()),
+            Some(_) => Err(unsupported(term)),
+            None => Ok(Vec::new()),
+        }
+    };
+
+    let body = body(
+        keyword(options, "do").ok_or_else(|| unsupported(term))?,
+        line,
+    )?;
+    let of_clauses = clauses("else")?
+        .into_iter()
+        .map(|clause| arrow_clause(clause, line))
+        .collect::<FromBeamResult<Vec<_>>>()?;
+    let mut catch_clauses = Vec::new();
+    let rescue_clauses = clauses("rescue")?;
+    if !rescue_clauses.is_empty() {
+        catch_clauses.push(rescue(&rescue_clauses, line)?);
+    }
+    for clause in clauses("catch")? {
+        let (args, right) = arrow_parts(clause).ok_or_else(|| unsupported(clause))?;
+        let (args, guards) = split_when(args, line)?;
+        let (kind, value) = match args {
+            [value] => (atom_literal("throw", line), pattern(value, line)?),
+            [kind, value] => (pattern(kind, line)?, pattern(value, line)?),
+            _ => return Err(unsupported(clause)),
+        };
+        let class = tuple(vec![
+            atom("tuple"),
+            int(line),
+            list(vec![kind, value, underscore(line)]),
+        ]);
+        catch_clauses.push(self::clause(
+            vec![class],
+            guards,
+            self::body(right, line)?,
+            line,
+        ));
+    }
+    let after = match keyword(options, "after") {
+        Some(after) => self::body(after, line)?,
+        None => Vec::new(),
+    };
+
+    Ok(tuple(vec![
+        atom("try"),
+        int(line),
+        list(body),
+        list(of_clauses),
+        list(catch_clauses),
+        list(after),
+    ]))
+}
+
+/// The catch clause for the `rescue` clauses, each of which is `variable`, `variable in modules`
+/// or `modules`, where the error is re-raised if no clause matches
+fn rescue(rescue_clauses: &[&Term], line: i32) -> FromBeamResult<Term> {
+    let reason = new_var("reason", line);
+    let stacktrace = new_var("stacktrace", line);
+    let mut clauses = Vec::new();
+
+    for rescue_clause in rescue_clauses {
+        let (args, right) = arrow_parts(rescue_clause).ok_or_else(|| unsupported(rescue_clause))?;
+        let (variable, modules) = match args {
+            [Term::List(ref modules)] => (None, Some(&modules.elements)),
+            [arg] => match call_parts(arg) {
+                Some((Term::Atom(ref r#in), _, Some([variable, Term::List(ref modules)])))
+                    if r#in.name == "in" =>
+                {
+                    (Some(variable), Some(&modules.elements))
+                }
+                _ => (Some(arg), None),
+            },
+            _ => return Err(unsupported(rescue_clause)),
+        };
+        let variable = match variable {
+            Some(variable) => pattern(variable, line)?,
+            None => underscore(line),
+        };
+        let (pattern, guards) = match modules {
+            Some(modules) if !modules.is_empty() => {
+                let module = new_var("module", line);
+                let exception = tuple(vec![
+                    atom("map"),
+                    int(line),
+                    list(vec![tuple(vec![
+                        atom("map_field_exact"),
+                        int(line),
+                        atom_literal("__struct__", line),
+                        module.clone(),
+                    ])]),
+                ]);
+                let mut comparisons = modules
+                    .iter()
+                    .map(|m| {
+                        expr(m, line).map(|m| {
+                            tuple(vec![atom("op"), int(line), atom("=:="), module.clone(), m])
+                        })
+                    })
+                    .collect::<FromBeamResult<Vec<_>>>()?
+                    .into_iter();
+                let first = comparisons.next().unwrap();
+                let guard = comparisons.fold(first, |left, right| {
+                    tuple(vec![atom("op"), int(line), atom("orelse"), left, right])
+                });
+                (
+                    tuple(vec![atom("match"), int(line), variable, exception]),
+                    vec![list(vec![guard])],
+                )
+            }
+            Some(_) => return Err(unsupported(rescue_clause)),
+            None => (variable, Vec::new()),
+        };
+        clauses.push(clause(vec![pattern], guards, body(right, line)?, line));
+    }
+
+    let reraise = remote_call_form(
+        "erlang",
+        "raise",
+        vec![
+            atom_literal("error", line),
+            reason.clone(),
+            stacktrace.clone(),
+        ],
+        line,
+    );
+    clauses.push(clause(vec![underscore(line)], vec![], vec![reraise], line));
+    let exception = remote_call_form(
+        "Elixir.Exception",
+        "normalize",
+        vec![
+            atom_literal("error", line),
+            reason.clone(),
+            stacktrace.clone(),
+        ],
+        line,
+    );
+    let class = tuple(vec![
+        atom("tuple"),
+        int(line),
+        list(vec![atom_literal("error", line), reason, stacktrace]),
+    ]);
+
+    Ok(clause(
+        vec![class],
+        vec![],
+        vec![tuple(vec![
+            atom("case"),
+            int(line),
+            exception,
+            list(clauses),
+        ])],
+        line,
+    ))
+}
+
+/// `receive` with optional `after`
+fn receive(term: &Term, options: &Term, line: i32) -> FromBeamResult<Term> {
+    check_keywords(term, options, &["do", "after"])?;
+    let clauses = match keyword(options, "do") {
+        Some(Term::List(ref clauses)) => clauses
+            .elements
+            .iter()
+            .map(|clause| arrow_clause(clause, line))
+            .collect::<FromBeamResult<Vec<_>>>()?,
+        // `receive do after ... end` has an empty block for `do`
+        Some(block) if is_empty_block(block) => Vec::new(),
+        None => Vec::new(),
+        Some(_) => return Err(unsupported(term)),
+    };
+
+    match keyword(options, "after") {
+        None => Ok(tuple(vec![atom("receive"), int(line), list(clauses)])),
+        Some(Term::List(ref after)) => match after.elements.as_slice() {
+            [after] => {
+                let (timeout, right) = arrow_parts(after).ok_or_else(|| unsupported(after))?;
+                let timeout = match timeout {
+                    [timeout] => expr(timeout, line)?,
+                    _ => return Err(unsupported(after)),
+                };
+                Ok(tuple(vec![
+                    atom("receive"),
+                    int(line),
+                    list(clauses),
+                    timeout,
+                    list(body(right, line)?),
+                ]))
+            }
+            _ => Err(unsupported(term)),
+        },
+        Some(_) => Err(unsupported(term)),
+    }
+}
+
+fn remote_call(
+    term: &Term,
+    callee: &Term,
+    args: &[Term],
+    line: i32,
+    context: Context,
+) -> FromBeamResult<Term> {
+    let (module, function) = match call_parts(callee) {
+        Some((Term::Atom(ref dot), _, Some([module, Term::Atom(ref function)])))
+            if dot.name == "." =>
+        {
+            (module, function.name.as_str())
+        }
+        // `fun.(args)`
+        Some((Term::Atom(ref dot), _, Some([fun]))) if dot.name == "." => {
+            if context == Context::Pattern {
+                return Err(unsupported(term));
+            }
+
+            return Ok(tuple(vec![
+                atom("call"),
+                int(line),
+                expr(fun, line)?,
+                list(
+                    args.iter()
+                        .map(|arg| expr(arg, line))
+                        .collect::<FromBeamResult<_>>()?,
+                ),
+            ]));
+        }
+        _ => return Err(unsupported(term)),
+    };
+    let args = args
+        .iter()
+        .map(|arg| translate(arg, line, context))
+        .collect::<FromBeamResult<Vec<_>>>()?;
+
+    // Operators are allowed in patterns and guards, where calls are not
+    if is_atom(module, "erlang") {
+        match args.as_slice() {
+            [left, right] if BINARY_OPERATORS.contains(&function) => {
+                return Ok(tuple(vec![
+                    atom("op"),
+                    int(line),
+                    atom(function),
+                    left.clone(),
+                    right.clone(),
+                ]));
+            }
+            [operand] if UNARY_OPERATORS.contains(&function) => {
+                return Ok(tuple(vec![
+                    atom("op"),
+                    int(line),
+                    atom(function),
+                    operand.clone(),
+                ]));
+            }
+            _ => (),
+        }
+    }
+    if context == Context::Pattern {
+        return Err(unsupported(term));
+    }
+
+    Ok(tuple(vec![
+        atom("call"),
+        int(line),
+        tuple(vec![
+            atom("remote"),
+            int(line),
+            expr(module, line)?,
+            atom_literal(function, line),
+        ]),
+        list(args),
+    ]))
+}
+
+fn map(term: &Term, pairs: &[Term], line: i32, context: Context) -> FromBeamResult<Term> {
+    let field = if context == Context::Pattern {
+        "map_field_exact"
+    } else {
+        "map_field_assoc"
+    };
+    let fields = |pairs: &[Term], field: &str| {
+        pairs
+            .iter()
+            .map(|pair| match tuple_elements(pair) {
+                Some(kv) if kv.len() == 2 => Ok(tuple(vec![
+                    atom(field),
+                    int(line),
+                    translate(&kv[0], line, context)?,
+                    translate(&kv[1], line, context)?,
+                ])),
+                _ => Err(unsupported(pair)),
+            })
+            .collect::<FromBeamResult<Vec<_>>>()
+    };
+
+    // `%{map | key => value}` only updates existing keys
+    if let [update] = pairs {
+        if let Some((Term::Atom(ref bar), _, Some(update_args))) = call_parts(update) {
+            if bar.name == "|" {
+                return match update_args {
+                    [map, Term::List(ref pairs)] if context == Context::Expr => Ok(tuple(vec![
+                        atom("map"),
+                        int(line),
+                        expr(map, line)?,
+                        list(fields(&pairs.elements, "map_field_exact")?),
+                    ])),
+                    _ => Err(unsupported(term)),
+                };
+            }
+        }
+    }
+
+    Ok(tuple(vec![
+        atom("map"),
+        int(line),
+        list(fields(pairs, field)?),
+    ]))
+}
+
+/// `&name/arity` and `&Module.name/arity`
+fn fun_capture(term: &Term, capture: &Term, line: i32) -> FromBeamResult<Term> {
+    let (function, arity) = match call_parts(capture) {
+        Some((Term::Atom(ref slash), _, Some([function, arity]))) if slash.name == "/" => {
+            (function, integer(arity).ok_or_else(|| unsupported(term))?)
+        }
+        _ => return Err(unsupported(term)),
+    };
+
+    match call_parts(function) {
+        // A local function is `{name, meta, context}`
+        Some((Term::Atom(ref name), _, None)) => Ok(tuple(vec![
+            atom("fun"),
+            int(line),
+            tuple(vec![atom("function"), atom(&name.name), int(arity)]),
+        ])),
+        Some((Term::Tuple(_), _, Some([]))) => {
+            match call_parts(&tuple_elements(function).unwrap()[0]) {
+                Some((Term::Atom(ref dot), _, Some([module, Term::Atom(ref name)])))
+                    if dot.name == "." =>
+                {
+                    Ok(tuple(vec![
+                        atom("fun"),
+                        int(line),
+                        tuple(vec![
+                            atom("function"),
+                            expr(module, line)?,
+                            atom_literal(&name.name, line),
+                            tuple(vec![atom("integer"), int(line), int(arity)]),
+                        ]),
+                    ]))
+                }
+                _ => Err(unsupported(term)),
+            }
+        }
+        _ => Err(unsupported(term)),
+    }
+}
+
+/// The clauses of `do: [left -> right, ...]`
+fn do_clauses(options: &Term, line: i32) -> FromBeamResult<Vec<Term>> {
+    do_clauses_terms(options)
+        .ok_or_else(|| unsupported(options))?
+        .iter()
+        .map(|clause| arrow_clause(clause, line))
+        .collect()
+}
+
+fn do_clauses_terms(options: &Term) -> Option<&[Term]> {
+    match keyword(options, "do")? {
+        Term::List(ref clauses) => Some(clauses.elements.as_slice()),
+        _ => None,
+    }
+}
+
+/// The arguments and body of `{:->, meta, [args, body]}`
+fn arrow_parts(clause: &Term) -> Option<(&[Term], &Term)> {
+    match call_parts(clause) {
+        Some((Term::Atom(ref arrow), _, Some([Term::List(ref args), body])))
+            if arrow.name == "->" =>
+        {
+            Some((args.elements.as_slice(), body))
+        }
+        _ => None,
+    }
+}
+
+/// A clause of `fn` or `case`, whose arguments may be wrapped in `when`
+fn arrow_clause(clause: &Term, line: i32) -> FromBeamResult<Term> {
+    let (args, right) = arrow_parts(clause).ok_or_else(|| unsupported(clause))?;
+    let line = call_parts(clause).map_or(line, |(_, meta, _)| self::line(meta, line));
+    let (args, guards) = split_when(args, line)?;
+    let patterns = args
+        .iter()
+        .map(|arg| pattern(arg, line))
+        .collect::<FromBeamResult<Vec<_>>>()?;
+
+    Ok(tuple(vec![
+        atom("clause"),
+        int(line),
+        list(patterns),
+        list(guards),
+        list(body(right, line)?),
+    ]))
+}
+
+/// The arguments and guards of arguments that may be wrapped in `when`
+fn split_when(args: &[Term], line: i32) -> FromBeamResult<(&[Term], Vec<Term>)> {
+    match args {
+        [when] => match call_parts(when) {
+            Some((Term::Atom(ref name), _, Some(when_args)))
+                if name.name == "when" && !when_args.is_empty() =>
+            {
+                let (guard, args) = when_args.split_last().unwrap();
+                Ok((args, vec![list(vec![expr(guard, line)?])]))
+            }
+            _ => Ok((args, Vec::new())),
+        },
+        _ => Ok((args, Vec::new())),
+    }
+}
+
+/// The value of `key` in the keyword list `options`
+fn keyword<'a>(options: &'a Term, key: &str) -> Option<&'a Term> {
+    list_elements(options).into_iter().find_map(|option| {
+        let kv = tuple_elements(option)?;
+        if kv.len() == 2 && is_atom(&kv[0], key) {
+            Some(&kv[1])
+        } else {
+            None
+        }
+    })
+}
+
+/// Fails unless `options` is a keyword list of only `keys`
+fn check_keywords(term: &Term, options: &Term, keys: &[&str]) -> FromBeamResult<()> {
+    let is_supported = |option: &&Term| match tuple_elements(option) {
+        Some(kv) if kv.len() == 2 => match atom_name(&kv[0]) {
+            Some(key) => keys.contains(&key),
+            None => false,
+        },
+        _ => false,
+    };
+
+    match *options {
+        Term::List(ref options) if options.elements.iter().all(|o| is_supported(&o)) => Ok(()),
+        _ => Err(unsupported(term)),
+    }
+}
+
+/// The abstract code of the literal `term`, like `erl_parse:abstract/2`
+fn literal(term: &Term, line: i32) -> FromBeamResult<Term> {
+    match *term {
+        Term::List(ref l) => l.elements.iter().rev().try_fold(nil(line), |tail, head| {
+            Ok(tuple(vec![
+                atom("cons"),
+                int(line),
+                literal(head, line)?,
+                tail,
+            ]))
+        }),
+        Term::Tuple(ref t) => Ok(tuple(vec![
+            atom("tuple"),
+            int(line),
+            list(
+                t.elements
+                    .iter()
+                    .map(|element| literal(element, line))
+                    .collect::<FromBeamResult<_>>()?,
+            ),
+        ])),
+        Term::Map(ref m) => Ok(tuple(vec![
+            atom("map"),
+            int(line),
+            list(
+                m.entries
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(tuple(vec![
+                            atom("map_field_assoc"),
+                            int(line),
+                            literal(key, line)?,
+                            literal(value, line)?,
+                        ]))
+                    })
+                    .collect::<FromBeamResult<_>>()?,
+            ),
+        ])),
+        Term::Atom(_)
+        | Term::FixInteger(_)
+        | Term::BigInteger(_)
+        | Term::Float(_)
+        | Term::Binary(_) => expr(term, line),
+        _ => Err(unsupported(term)),
+    }
+}
+
+/// Numbers the variables introduced by the translation
+static VAR_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A variable introduced by the translation, which cannot clash with Elixir variables, as those
+/// never start with `_@`
+fn new_var(name: &str, line: i32) -> Term {
+    let count = VAR_COUNT.fetch_add(1, Ordering::Relaxed);
+    tuple(vec![
+        atom("var"),
+        int(line),
+        atom(&format!("_@{}{}", name, count)),
+    ])
+}
+
+fn underscore(line: i32) -> Term {
+    tuple(vec![atom("var"), int(line), atom("_")])
+}
+
+fn nil(line: i32) -> Term {
+    tuple(vec![atom("nil"), int(line)])
+}
+
+fn clause(patterns: Vec<Term>, guards: Vec<Term>, body: Vec<Term>, line: i32) -> Term {
+    tuple(vec![
+        atom("clause"),
+        int(line),
+        list(patterns),
+        list(guards),
+        list(body),
+    ])
+}
+
+fn is_empty_block(term: &Term) -> bool {
+    match call_parts(term) {
+        Some((Term::Atom(ref name), _, Some(exprs))) => {
+            name.name == "__block__" && exprs.is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// The body of `term` as a single expression
+fn block(term: &Term, line: i32) -> FromBeamResult<Term> {
+    body(term, line).map(|exprs| block_form(exprs, line))
+}
+
+fn block_form(mut exprs: Vec<Term>, line: i32) -> Term {
+    if exprs.len() == 1 {
+        exprs.pop().unwrap()
+    } else {
+        tuple(vec![atom("block"), int(line), list(exprs)])
+    }
+}
+
+fn remote_call_form(module: &str, function: &str, args: Vec<Term>, line: i32) -> Term {
+    tuple(vec![
+        atom("call"),
+        int(line),
+        tuple(vec![
+            atom("remote"),
+            int(line),
+            atom_literal(module, line),
+            atom_literal(function, line),
+        ]),
+        list(args),
+    ])
+}
+
+/// Erlang variables must start with an uppercase letter or `_`, and variables from different
+/// macro contexts must not clash
+fn var(name: &str, context: &str, line: i32) -> Term {
+    let name = if name == "_" {
+        name.to_string()
+    } else if context == "nil" {
+        format!("_{}", name)
+    } else {
+        format!("_{}@{}", name, context)
+    };
+    tuple(vec![atom("var"), int(line), atom(&name)])
+}
+
+/// The callee, meta and arguments of a `{callee, meta, args}` node, where `args` is `None` for
+/// variables
+fn call_parts(term: &Term) -> Option<(&Term, &Term, Option<&[Term]>)> {
+    let elements = tuple_elements(term).filter(|elements| elements.len() == 3)?;
+    let args = match elements[2] {
+        Term::List(ref args) => Some(args.elements.as_slice()),
+        Term::Atom(_) => None,
+        _ => return None,
+    };
+    Some((&elements[0], &elements[1], args))
+}
+
+/// The `line` in `meta`, or `default`
+fn line(meta: &Term, default: i32) -> i32 {
+    list_elements(meta)
+        .into_iter()
+        .filter_map(tuple_elements)
+        .find(|kv| kv.len() == 2 && is_atom(&kv[0], "line"))
+        .and_then(|kv| integer(&kv[1]))
+        .unwrap_or(default)
+}
+
+fn attribute(line: i32, name: &str, value: Term) -> Term {
+    tuple(vec![atom("attribute"), int(line), atom(name), value])
+}
+
+fn atom_literal(name: &str, line: i32) -> Term {
+    tuple(vec![atom("atom"), int(line), atom(name)])
+}
+
+fn unsupported(term: &Term) -> FromBeamError {
+    FromBeamError::UnsupportedElixir(term.to_string())
+}
+
+fn is_atom(term: &Term, name: &str) -> bool {
+    atom_name(term) == Some(name)
+}
+
+fn atom_name(term: &Term) -> Option<&str> {
+    match *term {
+        Term::Atom(ref a) => Some(&a.name),
+        _ => None,
+    }
+}
+
+fn integer(term: &Term) -> Option<i32> {
+    match *term {
+        Term::FixInteger(ref i) => Some(i.value),
+        _ => None,
+    }
+}
+
+fn tuple_elements(term: &Term) -> Option<&[Term]> {
+    match *term {
+        Term::Tuple(ref t) => Some(&t.elements),
+        _ => None,
+    }
+}
+
+fn list_elements(term: &Term) -> Vec<&Term> {
+    match *term {
+        Term::List(ref l) => l.elements.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn atom(name: &str) -> Term {
+    Term::from(Atom::from(name))
+}
+
+fn int(value: i32) -> Term {
+    Term::from(FixInteger::from(value))
+}
+
+fn tuple(elements: Vec<Term>) -> Term {
+    Term::from(Tuple::from(elements))
+}
+
+fn list(elements: Vec<Term>) -> Term {
+    Term::from(List::from(elements))
+}
+
+fn string(s: &str) -> Term {
+    list(s.chars().map(|c| int(c as i32)).collect())
+}
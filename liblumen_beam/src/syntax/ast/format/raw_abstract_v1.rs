@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::io::Cursor;
 use std::marker::PhantomData;
 use std::path::Path;
 
@@ -10,6 +11,7 @@ use crate::serialization::etf::pattern::{Pattern, Unmatch};
 use crate::serialization::etf::pattern::{Uint, F64, I32, U32, U64};
 
 use crate::beam::chunk::Chunk;
+use crate::beam::reader::RawBeamFile;
 
 use crate::syntax::ast::ast::clause;
 use crate::syntax::ast::ast::common;
@@ -21,6 +23,8 @@ use crate::syntax::ast::ast::pat;
 use crate::syntax::ast::ast::ty;
 use crate::syntax::ast::{FromBeamError, FromBeamResult};
 
+use super::elixir_v1;
+
 macro_rules! to {
     ($to:ty) => {
         To::<$to>(PhantomData)
@@ -41,13 +45,50 @@ pub struct AbstractCode {
 }
 impl AbstractCode {
     pub fn from_beam_file<P: AsRef<Path>>(path: P) -> FromBeamResult<Self> {
-        let beam = crate::beam::reader::RawBeamFile::from_file(path)?;
-        let chunk = beam
-            .chunks()
-            .into_iter()
-            .find(|c| c.id() == b"Abst")
+        let beam = RawBeamFile::from_file(path)?;
+        Self::from_beam(&beam)
+    }
+    /// Recovers the abstract code from the `Abst` chunk written by OTP 19 and earlier, or from
+    /// the `erl_abstract_code` debug info in the `Dbgi` chunk written by later releases.
+    ///
+    /// Elixir modules have `elixir_erl` debug info, which is translated by [elixir_v1].
+    pub fn from_beam(beam: &RawBeamFile) -> FromBeamResult<Self> {
+        let chunks = beam.chunks();
+        if let Some(chunk) = chunks.iter().find(|c| c.id() == b"Abst") {
+            // Modules compiled without `debug_info` have an empty `Abst` chunk
+            if chunk.data.is_empty() {
+                return Err(FromBeamError::NoDebugInfo);
+            }
+            let code = etf::Term::decode(Cursor::new(&chunk.data))?;
+            return Ok(AbstractCode { code });
+        }
+
+        let chunk = chunks
+            .iter()
+            .find(|c| c.id() == b"Dbgi")
             .ok_or(FromBeamError::NoDebugInfo)?;
-        let code = etf::Term::decode(std::io::Cursor::new(&chunk.data))?;
+        let debug_info = etf::Term::decode(Cursor::new(&chunk.data))?;
+        let (_, backend, _) = debug_info.as_match(("debug_info_v1", AtomName, any()))?;
+        let forms = match backend.as_str() {
+            "erl_abstract_code" => {
+                let (_, _, (forms, _)) =
+                    debug_info.as_match(("debug_info_v1", "erl_abstract_code", (any(), any())))?;
+                if forms.as_match("none").is_ok() {
+                    return Err(FromBeamError::NoDebugInfo);
+                }
+                forms.clone()
+            }
+            "elixir_erl" => {
+                let (_, _, elixir) = debug_info.as_match(("debug_info_v1", "elixir_erl", any()))?;
+                etf::Term::from(etf::List::from(elixir_v1::to_forms(elixir)?))
+            }
+            _ => return Err(FromBeamError::UnsupportedDebugInfo(backend)),
+        };
+
+        let code = etf::Term::from(etf::Tuple::from(vec![
+            etf::Term::from(etf::Atom::from("raw_abstract_v1")),
+            forms,
+        ]));
         Ok(AbstractCode { code })
     }
     pub fn to_forms(&self) -> FromBeamResult<Vec<form::Form>> {
@@ -56,6 +97,73 @@ impl AbstractCode {
             .as_match(("raw_abstract_v1", VarList(to!(form::Form))))?;
         Ok(forms)
     }
+    /// Renders the forms as Erlang terms, each terminated by a `.`, which is the format read by
+    /// `file:consult/1` and by the EIR frontend for abstract Erlang.
+    pub fn to_listing(&self) -> FromBeamResult<String> {
+        let (_, forms) = self.code.as_match(("raw_abstract_v1", VarList(any())))?;
+        let mut listing = String::new();
+        for form in forms {
+            write_term(&mut listing, form);
+            listing.push_str(".\n");
+        }
+        Ok(listing)
+    }
+}
+
+/// Like the `Display` of [etf::Term], except that floats always have a fraction, so `1.0` is
+/// not read back as the integer `1`.
+fn write_term(out: &mut String, term: &etf::Term) {
+    fn write_elements(out: &mut String, elements: &[etf::Term]) {
+        for (i, element) in elements.iter().enumerate() {
+            if i != 0 {
+                out.push(',');
+            }
+            write_term(out, element);
+        }
+    }
+
+    match *term {
+        etf::Term::Float(ref x) => {
+            let value = format!("{:?}", x.value);
+            if !x.value.is_finite() || value.contains('.') {
+                out.push_str(&value);
+            } else if let Some(exponent) = value.find('e') {
+                out.push_str(&format!("{}.0{}", &value[..exponent], &value[exponent..]));
+            } else {
+                out.push_str(&format!("{}.0", value));
+            }
+        }
+        etf::Term::List(ref x) => {
+            out.push('[');
+            write_elements(out, &x.elements);
+            out.push(']');
+        }
+        etf::Term::ImproperList(ref x) => {
+            out.push('[');
+            write_elements(out, &x.elements);
+            out.push('|');
+            write_term(out, &x.last);
+            out.push(']');
+        }
+        etf::Term::Tuple(ref x) => {
+            out.push('{');
+            write_elements(out, &x.elements);
+            out.push('}');
+        }
+        etf::Term::Map(ref x) => {
+            out.push_str("#{");
+            for (i, (k, v)) in x.entries.iter().enumerate() {
+                if i != 0 {
+                    out.push(',');
+                }
+                write_term(out, k);
+                out.push_str("=>");
+                write_term(out, v);
+            }
+            out.push('}');
+        }
+        ref other => out.push_str(&other.to_string()),
+    }
 }
 
 trait FromTerm<'a> {
@@ -9,3 +9,286 @@ fn it_works() {
         })
         .unwrap();
 }
+
+#[test]
+fn from_debug_info() {
+    use crate::beam::reader::chunk::RawChunk;
+    use crate::beam::reader::RawBeamFile;
+    use crate::serialization::etf::{Atom, List, Term, Tuple};
+    use crate::syntax::ast::ast::form;
+    use crate::syntax::ast::error::FromBeamError;
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let abst = AbstractCode::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+    let forms = match abst.code {
+        Term::Tuple(ref tuple) => tuple.elements[1].clone(),
+        ref other => panic!("expected raw_abstract_v1 tuple, got {}", other),
+    };
+    let debug_info = Term::from(Tuple::from(vec![
+        Term::from(Atom::from("debug_info_v1")),
+        Term::from(Atom::from("erl_abstract_code")),
+        Term::from(Tuple::from(vec![forms, Term::from(List::nil())])),
+    ]));
+    let mut data = Vec::new();
+    debug_info.encode(&mut data).unwrap();
+    let mut beam = RawBeamFile::new();
+    beam.push_chunk(RawChunk { id: *b"Dbgi", data });
+
+    let dbgi = AbstractCode::from_beam(&beam).unwrap();
+    assert_eq!(abst.code, dbgi.code);
+    assert_eq!(
+        abst.to_forms().unwrap().len(),
+        dbgi.to_forms().unwrap().len()
+    );
+
+    match AbstractCode::from_beam_file("tests/testdata/simple.beam") {
+        Err(FromBeamError::NoDebugInfo) => (),
+        other => panic!("expected no debug info, got {:?}", other.map(|c| c.code)),
+    }
+
+    let elixir = AbstractCode::from_beam_file("tests/testdata/reader/Elixir.Unicode.beam").unwrap();
+    let forms = elixir.to_forms().unwrap();
+    assert!(forms.iter().any(|form| match *form {
+        form::Form::Module(ref m) => m.name == "Elixir.Unicode",
+        _ => false,
+    }));
+    assert!(forms.iter().any(|form| match *form {
+        form::Form::Fun(ref f) => f.name == "add1" && f.clauses[0].patterns.len() == 1,
+        _ => false,
+    }));
+}
+
+#[test]
+fn to_listing() {
+    use crate::serialization::etf::{Atom, FixInteger, Float, List, Term, Tuple};
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let code = AbstractCode::from_beam_file("tests/testdata/reader/test.beam").unwrap();
+    let listing = code.to_listing().unwrap();
+    assert!(listing.starts_with("{'attribute',1,'file',{"));
+    assert_eq!(
+        code.to_forms().unwrap().len(),
+        listing.matches(".\n").count()
+    );
+
+    let float = |value: f64| {
+        Term::from(Tuple::from(vec![
+            Term::from(Atom::from("float")),
+            Term::from(FixInteger::from(1)),
+            Term::from(Float::from(value)),
+        ]))
+    };
+    let code = AbstractCode {
+        code: Term::from(Tuple::from(vec![
+            Term::from(Atom::from("raw_abstract_v1")),
+            Term::from(List::from(vec![float(1.0), float(2.5), float(1e300)])),
+        ])),
+    };
+    assert_eq!(
+        "{'float',1,1.0}.\n{'float',1,2.5}.\n{'float',1,1.0e300}.\n",
+        code.to_listing().unwrap()
+    );
+}
+
+#[test]
+fn from_elixir_special_forms() {
+    use crate::serialization::etf::{Atom, Binary, FixInteger, List, Map, Term, Tuple};
+    use crate::syntax::ast::ast::form;
+    use crate::syntax::ast::format::elixir_v1;
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let atom = |name: &str| Term::from(Atom::from(name));
+    let int = |value: i32| Term::from(FixInteger::from(value));
+    let binary = |s: &str| Term::from(Binary::from(s.as_bytes()));
+    let list = |elements: Vec<Term>| Term::from(List::from(elements));
+    let tuple = |elements: Vec<Term>| Term::from(Tuple::from(elements));
+    let call = |name: &str, args: Vec<Term>| tuple(vec![atom(name), list(vec![]), list(args)]);
+    let var = |name: &str| tuple(vec![atom(name), list(vec![]), atom("nil")]);
+    let erlang = |function: &str, args: Vec<Term>| {
+        tuple(vec![
+            call(".", vec![atom("erlang"), atom(function)]),
+            list(vec![]),
+            list(args),
+        ])
+    };
+    let arrow = |args: Vec<Term>, body: Term| call("->", vec![list(args), body]);
+    let keyword = |key: &str, value: Term| tuple(vec![atom(key), value]);
+    let def = |name: &str, args: Vec<Term>, body: Term| {
+        tuple(vec![
+            tuple(vec![atom(name), int(args.len() as i32)]),
+            atom("def"),
+            list(vec![]),
+            list(vec![tuple(vec![
+                list(vec![]),
+                list(args),
+                list(vec![]),
+                body,
+            ])]),
+        ])
+    };
+
+    let definitions = vec![
+        // <<"hello ", name::binary, 1::integer-size(8)>>
+        def(
+            "greet",
+            vec![var("name")],
+            call(
+                "<<>>",
+                vec![
+                    call("::", vec![binary("hello "), call("binary", vec![])]),
+                    call("::", vec![var("name"), call("binary", vec![])]),
+                    call(
+                        "::",
+                        vec![
+                            int(1),
+                            call(
+                                "-",
+                                vec![call("integer", vec![]), call("size", vec![int(8)])],
+                            ),
+                        ],
+                    ),
+                ],
+            ),
+        ),
+        // for x <- list, x > 1, do: x * 2
+        def(
+            "double",
+            vec![var("list")],
+            call(
+                "for",
+                vec![
+                    call("<-", vec![var("x"), var("list")]),
+                    erlang(">", vec![var("x"), int(1)]),
+                    list(vec![keyword("do", erlang("*", vec![var("x"), int(2)]))]),
+                ],
+            ),
+        ),
+        // for x <- list, into: %{}, do: {x, x}
+        def(
+            "index",
+            vec![var("list")],
+            call(
+                "for",
+                vec![
+                    call("<-", vec![var("x"), var("list")]),
+                    list(vec![
+                        keyword("into", call("%{}", vec![])),
+                        keyword("do", tuple(vec![var("x"), var("x")])),
+                    ]),
+                ],
+            ),
+        ),
+        // for x <- list, reduce: 0 do acc -> acc + x end
+        def(
+            "sum",
+            vec![var("list")],
+            call(
+                "for",
+                vec![
+                    call("<-", vec![var("x"), var("list")]),
+                    list(vec![
+                        keyword("reduce", int(0)),
+                        keyword(
+                            "do",
+                            list(vec![arrow(
+                                vec![var("acc")],
+                                erlang("+", vec![var("acc"), var("x")]),
+                            )]),
+                        ),
+                    ]),
+                ],
+            ),
+        ),
+        // with {:ok, x} <- value, y = x do y else :error -> nil end
+        def(
+            "unwrap",
+            vec![var("value")],
+            call(
+                "with",
+                vec![
+                    call("<-", vec![tuple(vec![atom("ok"), var("x")]), var("value")]),
+                    call("=", vec![var("y"), var("x")]),
+                    list(vec![
+                        keyword("do", var("y")),
+                        keyword("else", list(vec![arrow(vec![atom("error")], atom("nil"))])),
+                    ]),
+                ],
+            ),
+        ),
+        // try do f.() rescue e in [ArgumentError] -> e catch :exit, r -> r after :ok end
+        def(
+            "safely",
+            vec![var("f")],
+            call(
+                "try",
+                vec![list(vec![
+                    keyword(
+                        "do",
+                        tuple(vec![call(".", vec![var("f")]), list(vec![]), list(vec![])]),
+                    ),
+                    keyword(
+                        "rescue",
+                        list(vec![arrow(
+                            vec![call(
+                                "in",
+                                vec![var("e"), list(vec![atom("Elixir.ArgumentError")])],
+                            )],
+                            var("e"),
+                        )]),
+                    ),
+                    keyword(
+                        "catch",
+                        list(vec![arrow(vec![atom("exit"), var("r")], var("r"))]),
+                    ),
+                    keyword("after", atom("ok")),
+                ])],
+            ),
+        ),
+        // receive do {:message, m} -> m after 100 -> :timeout end
+        def(
+            "wait",
+            vec![],
+            call(
+                "receive",
+                vec![list(vec![
+                    keyword(
+                        "do",
+                        list(vec![arrow(
+                            vec![tuple(vec![atom("message"), var("m")])],
+                            var("m"),
+                        )]),
+                    ),
+                    keyword("after", list(vec![arrow(vec![int(100)], atom("timeout"))])),
+                ])],
+            ),
+        ),
+    ];
+    let debug_info = tuple(vec![
+        atom("elixir_v1"),
+        Term::from(Map::from(vec![
+            (atom("module"), atom("Elixir.Special")),
+            (atom("definitions"), list(definitions)),
+            (atom("deprecated"), list(vec![])),
+        ])),
+        list(vec![]),
+    ]);
+
+    let code = AbstractCode {
+        code: tuple(vec![
+            atom("raw_abstract_v1"),
+            list(elixir_v1::to_forms(&debug_info).unwrap()),
+        ]),
+    };
+    let forms = code.to_forms().unwrap();
+    let function_names: Vec<&str> = forms
+        .iter()
+        .filter_map(|form| match *form {
+            form::Form::Fun(ref f) => Some(f.name.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        function_names,
+        vec!["__info__", "greet", "double", "index", "sum", "unwrap", "safely", "wait"]
+    );
+}
@@ -0,0 +1,96 @@
+mod embedding {
+    use std::process::{Command, Stdio};
+    use std::sync::Once;
+
+    #[test]
+    fn host_program_spawns_sends_and_receives_replies() {
+        ensure_compiled();
+
+        let embedding_output = Command::new("./embedding")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8_lossy(&embedding_output.stdout);
+        let stderr = String::from_utf8_lossy(&embedding_output.stderr);
+
+        assert!(
+            embedding_output.status.success(),
+            "\nstdout = {}\nstderr = {}",
+            stdout,
+            stderr
+        );
+        assert_eq!(stdout, "42\n", "\nstdout = {}\nstderr = {}", stdout, stderr);
+    }
+
+    static COMPILED: Once = Once::new();
+
+    fn ensure_compiled() {
+        COMPILED.call_once(|| {
+            compile_library();
+            compile_host();
+        })
+    }
+
+    fn compile_library() {
+        std::fs::create_dir_all("_build/embedding").unwrap();
+
+        let compile_output = Command::new("../bin/lumen")
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("--output-type")
+            .arg("staticlib")
+            .arg("-o")
+            .arg("_build/embedding/libdoubler.a")
+            // Turn off optimizations as work-around for debug info bug in EIR
+            .arg("-O0")
+            .arg("tests/embedding/doubler.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+    }
+
+    fn compile_host() {
+        let mut command = Command::new("cc");
+
+        command
+            .arg("-o")
+            .arg("embedding")
+            // `lumen.h` is written next to the library
+            .arg("-I_build/embedding")
+            .arg("tests/embedding/host.c")
+            .arg("_build/embedding/libdoubler.a");
+
+        add_link_args(&mut command);
+
+        let cc_output = command.stdin(Stdio::null()).output().unwrap();
+
+        assert!(
+            cc_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&cc_output.stdout),
+            String::from_utf8_lossy(&cc_output.stderr)
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lrt")
+            .arg("-lm");
+    }
+}
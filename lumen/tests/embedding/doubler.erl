@@ -0,0 +1,19 @@
+-module(doubler).
+
+-export([start/1]).
+
+%% Replies to each integer sent to `doubler` by the host program with the integer
+%% multiplied by `Factor`
+-spec start(integer()) -> ok.
+start(Factor) ->
+  true = register(doubler, self()),
+  loop(Factor).
+
+loop(Factor) ->
+  receive
+    stop ->
+      ok;
+    N ->
+      host ! N * Factor,
+      loop(Factor)
+  end.
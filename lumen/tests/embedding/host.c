@@ -0,0 +1,61 @@
+#include <pthread.h>
+#include <sched.h>
+#include <stdio.h>
+
+#include "lumen.h"
+
+static pthread_mutex_t lock = PTHREAD_MUTEX_INITIALIZER;
+static pthread_cond_t replied = PTHREAD_COND_INITIALIZER;
+static int reply = -1;
+
+static void on_message(void *data, const uint8_t *message, size_t len) {
+    (void)data;
+
+    pthread_mutex_lock(&lock);
+    // SMALL_INTEGER_EXT
+    if (len == 3 && message[0] == 131 && message[1] == 97) {
+        reply = message[2];
+    } else {
+        reply = 0;
+    }
+    pthread_cond_signal(&replied);
+    pthread_mutex_unlock(&lock);
+}
+
+int main(int argc, char **argv) {
+    if (lumen_start(argc, (const char *const *)argv) != LUMEN_OK) {
+        return 1;
+    }
+    if (lumen_register_callback("host", on_message, NULL) != LUMEN_OK) {
+        return 2;
+    }
+
+    // [2]
+    const uint8_t args[] = {131, 108, 0, 0, 0, 1, 97, 2, 106};
+    if (lumen_spawn("doubler", "start", args, sizeof(args)) != LUMEN_OK) {
+        return 3;
+    }
+
+    // 21, which can only be sent once the process has registered itself
+    const uint8_t message[] = {131, 97, 21};
+    lumen_status_t status;
+    while ((status = lumen_send("doubler", message, sizeof(message))) == LUMEN_NOPROC) {
+        sched_yield();
+    }
+    if (status != LUMEN_OK) {
+        return 4;
+    }
+
+    pthread_mutex_lock(&lock);
+    while (reply < 0) {
+        pthread_cond_wait(&replied, &lock);
+    }
+    printf("%d\n", reply);
+    pthread_mutex_unlock(&lock);
+
+    // stop
+    const uint8_t stop[] = {131, 119, 4, 's', 't', 'o', 'p'};
+    lumen_send("doubler", stop, sizeof(stop));
+
+    return lumen_stop() == LUMEN_OK ? 0 : 5;
+}
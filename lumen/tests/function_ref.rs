@@ -0,0 +1,95 @@
+mod function_ref {
+    use std::process::{Command, Stdio};
+    use std::sync::Once;
+
+    #[test]
+    fn without_arguments_calls_static_and_dynamic_targets() {
+        ensure_compiled();
+
+        let function_ref_output = Command::new("./function_ref")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8_lossy(&function_ref_output.stdout);
+        let stderr = String::from_utf8_lossy(&function_ref_output.stderr);
+
+        assert_eq!(
+            stdout, "true\n2\n4\n6\n",
+            "\nstdout = {}\nstderr = {}",
+            stdout, stderr
+        );
+    }
+
+    #[test]
+    fn with_module_and_function_arguments_calls_dynamic_targets() {
+        ensure_compiled();
+
+        let function_ref_output = Command::new("./function_ref")
+            .arg("init")
+            .arg("triple")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8_lossy(&function_ref_output.stdout);
+        let stderr = String::from_utf8_lossy(&function_ref_output.stderr);
+
+        assert_eq!(
+            stdout, "true\n2\n6\n9\n",
+            "\nstdout = {}\nstderr = {}",
+            stdout, stderr
+        );
+    }
+
+    static COMPILED: Once = Once::new();
+
+    fn ensure_compiled() {
+        COMPILED.call_once(|| {
+            compile();
+        })
+    }
+
+    fn compile() {
+        std::fs::create_dir_all("_build").unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("-o")
+            .arg("function_ref")
+            // Turn off optimizations as work-around for debug info bug in EIR
+            .arg("-O0")
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/function_ref/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
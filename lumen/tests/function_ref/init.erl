@@ -0,0 +1,25 @@
+-module(init).
+-export([start/0, double/1, triple/1]).
+-import(erlang, [display/1]).
+-spec start() -> ok | error.
+start() ->
+  {Module, Function} = get_target(),
+  Double = fun init:double/1,
+  display(is_function(Double, 1)),
+  display(Double(1)),
+  display(init:Function(2)),
+  display(Module:Function(3)).
+-spec double(integer()) -> integer().
+double(N) ->
+  N * 2.
+-spec triple(integer()) -> integer().
+triple(N) ->
+  N * 3.
+-spec get_target() -> {atom(), atom()}.
+get_target() ->
+  case init:get_plain_arguments() of
+    [_, ModuleName, FunctionName | _] ->
+      {binary_to_atom(ModuleName, utf8), binary_to_atom(FunctionName, utf8)};
+    _ ->
+      {init, double}
+  end.
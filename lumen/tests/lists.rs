@@ -0,0 +1,57 @@
+mod lists {
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn reverses_and_sorts_lists_longer_than_walked_per_call() {
+        std::fs::create_dir_all("_build").unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("-o")
+            .arg("lists")
+            // Turn off optimizations as work-around for debug info bug in EIR
+            .arg("-O0")
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/lists/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+
+        let lists_output = Command::new("./lists").output().unwrap();
+        let lists_stdout = String::from_utf8_lossy(&lists_output.stdout);
+        let lists_stderr = String::from_utf8_lossy(&lists_output.stderr);
+
+        assert_eq!(
+            lists_stdout, "2001\n2001\n1\ntrue\n",
+            "\nstdout = {}\nstderr = {}",
+            lists_stdout, lists_stderr
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
@@ -0,0 +1,15 @@
+-module(init).
+
+-export([start/0]).
+
+-import(erlang, [display/1]).
+
+start() ->
+  %% More elements than the lists functions walk per call when run from frames
+  List = lists:seq(1, 2001),
+  Reversed = lists:reverse(List),
+  display(hd(Reversed)),
+  display(length(Reversed)),
+  Sorted = lists:sort(Reversed),
+  display(hd(Sorted)),
+  display(Sorted =:= List).
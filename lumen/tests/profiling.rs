@@ -0,0 +1,90 @@
+mod profiling {
+    use std::fs;
+    use std::process::{Command, Stdio};
+    use std::sync::Once;
+
+    #[test]
+    fn eprof_writes_folded_stacks_and_report() {
+        ensure_compiled();
+
+        let profiling_output = Command::new("../../profiling")
+            .current_dir("_build/profiling")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8_lossy(&profiling_output.stdout);
+        let stderr = String::from_utf8_lossy(&profiling_output.stderr);
+
+        assert_eq!(
+            stdout, "profiling\nalready_profiling\nprofiling_stopped\nprofiling_already_stopped\n",
+            "\nstdout = {}\nstderr = {}",
+            stdout, stderr
+        );
+
+        let folded_stacks = fs::read_to_string("_build/profiling/lumen-profile.folded").unwrap();
+        assert!(
+            folded_stacks
+                .lines()
+                .any(|line| line.starts_with("#PID<") && line.contains(";init:spin/1")),
+            "folded_stacks = {}",
+            folded_stacks
+        );
+
+        let report = fs::read_to_string("_build/profiling/lumen-profile.report").unwrap();
+        assert!(report.starts_with("PID"), "report = {}", report);
+        assert!(report.contains("init:start/0"), "report = {}", report);
+    }
+
+    static COMPILED: Once = Once::new();
+
+    fn ensure_compiled() {
+        COMPILED.call_once(|| {
+            compile();
+        })
+    }
+
+    fn compile() {
+        // The profile is written to the working directory of `profiling`
+        fs::create_dir_all("_build/profiling").unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("-o")
+            .arg("profiling")
+            // Turn off optimizations as work-around for debug info bug in EIR
+            .arg("-O0")
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/profiling/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
@@ -0,0 +1,16 @@
+-module(init).
+-export([start/0]).
+-import(erlang, [display/1]).
+-spec start() -> ok | error.
+start() ->
+  display(eprof:start_profiling([self()])),
+  {error, Reason} = eprof:start_profiling([self()]),
+  display(Reason),
+  spin(10000000),
+  display(eprof:stop_profiling()),
+  display(eprof:stop_profiling()).
+-spec spin(integer()) -> ok.
+spin(0) ->
+  ok;
+spin(N) ->
+  spin(N - 1).
@@ -0,0 +1,74 @@
+mod rebar_project {
+    use std::process::{Command, Stdio};
+    use std::sync::Once;
+
+    #[test]
+    fn compiles_applications_with_their_options_and_environment() {
+        ensure_compiled();
+
+        let rebar_project_output = Command::new("./rebar_project")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        let stdout = String::from_utf8_lossy(&rebar_project_output.stdout);
+        let stderr = String::from_utf8_lossy(&rebar_project_output.stderr);
+
+        assert_eq!(
+            stdout, "4\n30\n10\n11\n0\n",
+            "\nstdout = {}\nstderr = {}",
+            stdout, stderr
+        );
+    }
+
+    static COMPILED: Once = Once::new();
+
+    fn ensure_compiled() {
+        COMPILED.call_once(|| {
+            compile();
+        })
+    }
+
+    fn compile() {
+        std::fs::create_dir_all("_build").unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("-o")
+            .arg("rebar_project")
+            // Turn off optimizations as work-around for debug info bug in EIR
+            .arg("-O0")
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/rebar_project")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
@@ -0,0 +1,10 @@
+-module(greeter).
+
+-export([greet/1]).
+
+-spec greet(integer()) -> integer().
+-ifdef(EXCITED).
+greet(Count) -> Count * 10.
+-else.
+greet(Count) -> Count.
+-endif.
@@ -0,0 +1,26 @@
+-module(init).
+
+-export([start/0]).
+
+-import(erlang, [display/1]).
+
+-include("rebar_project.hrl").
+
+-spec start() -> ok | error.
+start() ->
+  {ok, Count} = application:get_env(rebar_project, count),
+  display(Count + offset()),
+  display(greeter:greet(Count)),
+  display(?MAX_COUNT),
+  {ok, Volume} = application:get_env(greeter, volume),
+  display(Volume),
+  case application:get_env(rebar_project, missing) of
+    undefined -> display(0);
+    _ -> display(1)
+  end.
+
+-ifdef(OFFSET).
+offset() -> 1.
+-else.
+offset() -> 0.
+-endif.
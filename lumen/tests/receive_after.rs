@@ -0,0 +1,57 @@
+mod receive_after {
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn times_out_and_skips_messages_before_reference() {
+        std::fs::create_dir_all("_build").unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("-o")
+            .arg("receive_after")
+            // Turn off optimizations as work-around for debug info bug in EIR
+            .arg("-O0")
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/receive_after/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+
+        let receive_after_output = Command::new("./receive_after").output().unwrap();
+        let receive_after_stdout = String::from_utf8_lossy(&receive_after_output.stdout);
+        let receive_after_stderr = String::from_utf8_lossy(&receive_after_output.stderr);
+
+        assert_eq!(
+            receive_after_stdout, "reply\nafter_0\nafter_10\nbefore_reference\n",
+            "\nstdout = {}\nstderr = {}",
+            receive_after_stdout, receive_after_stderr
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
@@ -0,0 +1,26 @@
+-module(init).
+
+-export([start/0]).
+
+-import(erlang, [display/1]).
+
+start() ->
+  self() ! before_reference,
+  Reference = make_ref(),
+  self() ! {Reference, reply},
+  receive
+    {Reference, Reply} -> display(Reply)
+  end,
+  receive
+    never -> display(never)
+  after
+    0 -> display(after_0)
+  end,
+  receive
+    never -> display(never)
+  after
+    10 -> display(after_10)
+  end,
+  receive
+    before_reference -> display(before_reference)
+  end.
@@ -0,0 +1,11 @@
+pub mod get_env_2;
+
+use liblumen_alloc::erts::term::prelude::Atom;
+
+fn module() -> Atom {
+    Atom::from_str("application")
+}
+
+fn module_id() -> usize {
+    module().id()
+}
@@ -0,0 +1,20 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::application;
+
+/// Only the `env` of the application specification is available, as applications are
+/// compiled into the executable rather than loaded and configured at runtime
+#[native_implemented::function(application:get_env/2)]
+pub fn result(process: &Process, application: Term, key: Term) -> exception::Result<Term> {
+    let application_atom = term_try_into_atom!(application)?;
+    let key_atom = term_try_into_atom!(key)?;
+
+    match application::get_env(process, application_atom, key_atom)? {
+        Some(value) => process
+            .tuple_from_slice(&[Atom::str_to_term("ok"), value])
+            .map_err(|alloc| alloc.into()),
+        None => Ok(Atom::str_to_term("undefined")),
+    }
+}
@@ -1,3 +1,28 @@
+pub mod bin_to_list_1;
+pub mod bin_to_list_2;
+pub mod bin_to_list_3;
+pub mod compile_pattern_1;
+pub mod copy_1;
+pub mod copy_2;
+pub mod decode_unsigned_1;
+pub mod decode_unsigned_2;
+pub mod encode_unsigned_1;
+pub mod encode_unsigned_2;
+pub mod endianness;
+pub mod longest_common_prefix_1;
+pub mod match_2;
+pub mod match_3;
+pub mod matches_2;
+pub mod matches_3;
+pub mod part_2;
+pub mod part_3;
+pub mod pattern;
+pub mod referenced_byte_size_1;
+pub mod replace_3;
+pub mod replace_4;
+pub mod scope;
+pub mod split_2;
+pub mod split_3;
 pub mod to_term;
 
 use std::backtrace::Backtrace;
@@ -11,6 +36,8 @@ use liblumen_alloc::erts::exception::{self, ArcError, Exception, InternalExcepti
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::Process;
 
+use crate::erlang;
+
 pub struct PartRange {
     pub byte_offset: usize,
     pub byte_len: usize,
@@ -127,6 +154,15 @@ pub fn bin_to_list(
     }
 }
 
+/// The part of `binary` in `range` of its bytes, sharing the bytes of `binary` instead of copying
+/// them.
+pub fn sub_binary(process: &Process, binary: Term, range: Range<usize>) -> exception::Result<Term> {
+    let start = process.integer(range.start)?;
+    let length = process.integer(range.end - range.start)?;
+
+    erlang::binary_part_3::result(process, binary, start, length)
+}
+
 pub fn start_length_to_part_range(
     start: usize,
     length: isize,
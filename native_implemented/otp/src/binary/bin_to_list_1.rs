@@ -0,0 +1,10 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang;
+
+#[native_implemented::function(binary:bin_to_list/1)]
+pub fn result(process: &Process, subject: Term) -> exception::Result<Term> {
+    erlang::binary_to_list_1::result(process, subject)
+}
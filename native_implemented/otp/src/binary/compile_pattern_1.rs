@@ -0,0 +1,14 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::pattern::Pattern;
+
+/// Compiles `pattern` once, so that repeated searches with it do not need to rebuild the
+/// Boyer-Moore or Aho-Corasick tables.
+#[native_implemented::function(binary:compile_pattern/1)]
+pub fn result(process: &Process, pattern: Term) -> exception::Result<Term> {
+    let pattern_pattern = Pattern::try_from_term(process, pattern)?;
+
+    pattern_pattern.into_term(process).map_err(From::from)
+}
@@ -0,0 +1,28 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::*;
+
+/// `count` copies of `subject` in a new binary, which, unlike a sub binary, does not keep the
+/// binary `subject` is a part of alive.
+#[native_implemented::function(binary:copy/2)]
+pub fn result(process: &Process, subject: Term, count: Term) -> exception::Result<Term> {
+    let bytes = process
+        .bytes_from_binary(subject)
+        .with_context(|| term_is_not_binary("subject", subject))?;
+    let count_usize: usize = count
+        .try_into()
+        .with_context(|| term_is_not_non_negative_integer("count", count))?;
+
+    process
+        .binary_from_bytes(&bytes.repeat(count_usize))
+        .map_err(From::from)
+}
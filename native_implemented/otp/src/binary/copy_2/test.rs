@@ -0,0 +1,42 @@
+use crate::binary::copy_2::result;
+use crate::test::with_process;
+
+#[test]
+fn with_zero_count_returns_empty_binary() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"ab").unwrap();
+
+        assert_eq!(
+            result(process, subject, process.integer(0).unwrap()),
+            Ok(process.binary_from_bytes(&[]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_subbinary_returns_count_copies_of_subbinary_bytes() {
+    with_process(|process| {
+        let original = process.binary_from_bytes(&[0, 1, 2, 3]).unwrap();
+        let subject = process
+            .subbinary_from_original(original, 1, 0, 2, 0)
+            .unwrap();
+
+        assert_eq!(
+            result(process, subject, process.integer(3).unwrap()),
+            Ok(process.binary_from_bytes(&[1, 2, 1, 2, 1, 2]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_negative_count_errors_badarg() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"ab").unwrap();
+        let count = process.integer(-1).unwrap();
+
+        assert_badarg!(
+            result(process, subject, count),
+            format!("count ({}) is not a non-negative integer", count)
+        );
+    });
+}
@@ -0,0 +1,10 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::decode_unsigned_2;
+
+#[native_implemented::function(binary:decode_unsigned/1)]
+pub fn result(process: &Process, subject: Term) -> exception::Result<Term> {
+    decode_unsigned_2::result(process, subject, Atom::str_to_term("big"))
+}
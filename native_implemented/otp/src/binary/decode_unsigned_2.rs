@@ -0,0 +1,28 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+use num_bigint::{BigInt, Sign};
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::endianness::Endianness;
+use crate::runtime::context::*;
+
+#[native_implemented::function(binary:decode_unsigned/2)]
+pub fn result(process: &Process, subject: Term, endianness: Term) -> exception::Result<Term> {
+    let bytes = process
+        .bytes_from_binary(subject)
+        .with_context(|| term_is_not_binary("subject", subject))?;
+    let endianness_endianness: Endianness = endianness.try_into()?;
+    let unsigned_big_int = match endianness_endianness {
+        Endianness::Big => BigInt::from_bytes_be(Sign::Plus, bytes),
+        Endianness::Little => BigInt::from_bytes_le(Sign::Plus, bytes),
+    };
+
+    process.integer(unsigned_big_int).map_err(From::from)
+}
@@ -0,0 +1,51 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::{decode_unsigned_2, encode_unsigned_2};
+use crate::test::with_process;
+
+#[test]
+fn with_empty_binary_returns_zero() {
+    with_process(|process| {
+        assert_eq!(
+            decode_unsigned_2::result(
+                process,
+                process.binary_from_bytes(&[]).unwrap(),
+                Atom::str_to_term("big")
+            ),
+            Ok(process.integer(0).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_little_endianness_treats_first_byte_as_least_significant() {
+    with_process(|process| {
+        assert_eq!(
+            decode_unsigned_2::result(
+                process,
+                process.binary_from_bytes(&[3, 2, 1]).unwrap(),
+                Atom::str_to_term("little")
+            ),
+            Ok(process.integer(0x01_02_03).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_encoded_big_integer_returns_big_integer() {
+    with_process(|process| {
+        let unsigned = process.integer(u64::max_value()).unwrap();
+
+        assert!(unsigned.is_boxed_bigint());
+
+        for endianness in &["big", "little"] {
+            let endianness_term = Atom::str_to_term(endianness);
+            let encoded = encode_unsigned_2::result(process, unsigned, endianness_term).unwrap();
+
+            assert_eq!(
+                decode_unsigned_2::result(process, encoded, endianness_term),
+                Ok(unsigned)
+            );
+        }
+    });
+}
@@ -0,0 +1,33 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+use num_bigint::{BigInt, Sign};
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::endianness::Endianness;
+use crate::runtime::context::*;
+
+#[native_implemented::function(binary:encode_unsigned/2)]
+pub fn result(process: &Process, unsigned: Term, endianness: Term) -> exception::Result<Term> {
+    let unsigned_big_int: BigInt = unsigned
+        .try_into()
+        .with_context(|| term_is_not_non_negative_integer("unsigned", unsigned))?;
+
+    if unsigned_big_int.sign() == Sign::Minus {
+        return Err(anyhow!(term_is_not_non_negative_integer("unsigned", unsigned)).into());
+    }
+
+    let endianness_endianness: Endianness = endianness.try_into()?;
+    let (_, bytes) = match endianness_endianness {
+        Endianness::Big => unsigned_big_int.to_bytes_be(),
+        Endianness::Little => unsigned_big_int.to_bytes_le(),
+    };
+
+    process.binary_from_bytes(&bytes).map_err(From::from)
+}
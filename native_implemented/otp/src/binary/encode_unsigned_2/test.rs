@@ -0,0 +1,70 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::encode_unsigned_2::result;
+use crate::test::with_process;
+
+#[test]
+fn with_zero_returns_zero_byte() {
+    with_process(|process| {
+        assert_eq!(
+            result(
+                process,
+                process.integer(0).unwrap(),
+                Atom::str_to_term("big")
+            ),
+            Ok(process.binary_from_bytes(&[0]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_big_endianness_returns_most_significant_byte_first() {
+    with_process(|process| {
+        assert_eq!(
+            result(
+                process,
+                process.integer(0x01_02_03).unwrap(),
+                Atom::str_to_term("big")
+            ),
+            Ok(process.binary_from_bytes(&[1, 2, 3]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_little_endianness_returns_least_significant_byte_first() {
+    with_process(|process| {
+        assert_eq!(
+            result(
+                process,
+                process.integer(0x01_02_03).unwrap(),
+                Atom::str_to_term("little")
+            ),
+            Ok(process.binary_from_bytes(&[3, 2, 1]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_negative_integer_errors_badarg() {
+    with_process(|process| {
+        let unsigned = process.integer(-1).unwrap();
+
+        assert_badarg!(
+            result(process, unsigned, Atom::str_to_term("big")),
+            format!("unsigned ({}) is not a non-negative integer", unsigned)
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_endianness_errors_badarg() {
+    with_process(|process| {
+        let endianness = Atom::str_to_term("middle");
+
+        assert_badarg!(
+            result(process, process.integer(1).unwrap(), endianness),
+            format!("endianness ({}) must be big or little", endianness)
+        );
+    });
+}
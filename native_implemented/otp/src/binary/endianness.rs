@@ -0,0 +1,25 @@
+use std::convert::TryFrom;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+/// The byte order of `binary:encode_unsigned/2` and `binary:decode_unsigned/2`
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+impl TryFrom<Term> for Endianness {
+    type Error = anyhow::Error;
+
+    fn try_from(endianness: Term) -> anyhow::Result<Self> {
+        let atom = term_try_into_atom!(endianness)?;
+
+        match atom.name() {
+            "big" => Ok(Endianness::Big),
+            "little" => Ok(Endianness::Little),
+            _ => Err(anyhow!("endianness ({}) must be big or little", endianness)),
+        }
+    }
+}
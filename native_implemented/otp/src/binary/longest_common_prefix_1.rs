@@ -0,0 +1,45 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::*;
+
+#[native_implemented::function(binary:longest_common_prefix/1)]
+pub fn result(process: &Process, binaries: Term) -> exception::Result<Term> {
+    let binaries_cons = term_try_into_non_empty_list!(binaries)?;
+    let mut option_prefix: Option<&[u8]> = None;
+
+    for result in binaries_cons.into_iter() {
+        let element = result
+            .map_err(|_| ImproperListError)
+            .with_context(|| format!("binaries ({}) is improper", binaries))?;
+        let bytes = process.bytes_from_binary(element).with_context(|| {
+            format!(
+                "binaries ({}) element ({}) is not a binary",
+                binaries, element
+            )
+        })?;
+
+        option_prefix = Some(match option_prefix {
+            Some(prefix) => {
+                let common_len = prefix
+                    .iter()
+                    .zip(bytes.iter())
+                    .take_while(|(prefix_byte, byte)| prefix_byte == byte)
+                    .count();
+
+                &prefix[..common_len]
+            }
+            None => bytes,
+        });
+    }
+
+    process
+        .integer(option_prefix.unwrap().len())
+        .map_err(From::from)
+}
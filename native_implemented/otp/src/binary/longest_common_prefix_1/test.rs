@@ -0,0 +1,43 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::longest_common_prefix_1::result;
+use crate::test::with_process;
+
+#[test]
+fn with_common_prefix_returns_its_length() {
+    with_process(|process| {
+        let binaries = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"erlang").unwrap(),
+                process.binary_from_bytes(b"ergonomy").unwrap(),
+                process.binary_from_bytes(b"era").unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(result(process, binaries), Ok(process.integer(2).unwrap()));
+    });
+}
+
+#[test]
+fn without_common_prefix_returns_zero() {
+    with_process(|process| {
+        let binaries = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"erlang").unwrap(),
+                process.binary_from_bytes(b"lumen").unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(result(process, binaries), Ok(process.integer(0).unwrap()));
+    });
+}
+
+#[test]
+fn with_empty_list_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Term::NIL),
+            "binaries ([]) is not a non-empty list"
+        );
+    });
+}
@@ -0,0 +1,39 @@
+pub mod options;
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::pattern::{self, Pattern};
+use crate::binary::scope::Scope;
+use crate::runtime::context::*;
+
+use options::Options;
+
+/// The first, longest match of `pattern` in `subject` as `{Pos, Len}`, or `nomatch`
+#[native_implemented::function(binary:match/3)]
+pub fn result(
+    process: &Process,
+    subject: Term,
+    pattern: Term,
+    options: Term,
+) -> exception::Result<Term> {
+    let bytes = process
+        .bytes_from_binary(subject)
+        .with_context(|| term_is_not_binary("subject", subject))?;
+    let pattern_pattern = Pattern::try_from_term(process, pattern)?;
+    let options_options: Options = options.try_into()?;
+    let range = Scope::range(options_options.scope, bytes.len())?;
+
+    match pattern_pattern.find(&bytes[..range.end], range.start) {
+        Some(found) => pattern::found_to_term(process, found),
+        None => Ok(Atom::str_to_term("nomatch")),
+    }
+}
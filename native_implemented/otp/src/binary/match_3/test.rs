@@ -0,0 +1,140 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::compile_pattern_1;
+use crate::binary::match_3::result;
+use crate::test::with_process;
+
+#[test]
+fn without_match_returns_nomatch() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcde").unwrap();
+        let pattern = process.binary_from_bytes(b"x").unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, Term::NIL),
+            Ok(Atom::str_to_term("nomatch"))
+        );
+    });
+}
+
+#[test]
+fn with_binary_pattern_returns_first_position_and_length() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcdecd").unwrap();
+        let pattern = process.binary_from_bytes(b"cd").unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, Term::NIL),
+            Ok(position_length(process, 2, 2))
+        );
+    });
+}
+
+#[test]
+fn with_list_pattern_returns_longest_match_at_first_position() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcde").unwrap();
+        let pattern = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"bcd").unwrap(),
+                process.binary_from_bytes(b"b").unwrap(),
+                process.binary_from_bytes(b"de").unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, Term::NIL),
+            Ok(position_length(process, 1, 3))
+        );
+    });
+}
+
+#[test]
+fn with_compiled_pattern_returns_same_as_uncompiled() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcde").unwrap();
+        let pattern = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"d").unwrap(),
+                process.binary_from_bytes(b"e").unwrap(),
+            ])
+            .unwrap();
+        let compiled_pattern = compile_pattern_1::result(process, pattern).unwrap();
+
+        assert_eq!(
+            result(process, subject, compiled_pattern, Term::NIL),
+            result(process, subject, pattern, Term::NIL)
+        );
+    });
+}
+
+#[test]
+fn with_scope_only_matches_in_scope() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcabc").unwrap();
+        let pattern = process.binary_from_bytes(b"abc").unwrap();
+        let options = scope_options(process, 1, 5);
+
+        assert_eq!(
+            result(process, subject, pattern, options),
+            Ok(position_length(process, 3, 3))
+        );
+
+        let partial_options = scope_options(process, 1, 4);
+
+        assert_eq!(
+            result(process, subject, pattern, partial_options),
+            Ok(Atom::str_to_term("nomatch"))
+        );
+    });
+}
+
+#[test]
+fn with_negative_scope_length_matches_before_start() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcabc").unwrap();
+        let pattern = process.binary_from_bytes(b"bc").unwrap();
+        let options = scope_options(process, 4, -4);
+
+        assert_eq!(
+            result(process, subject, pattern, options),
+            Ok(position_length(process, 1, 2))
+        );
+    });
+}
+
+#[test]
+fn with_empty_binary_pattern_errors_badarg() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abc").unwrap();
+        let pattern = process.binary_from_bytes(&[]).unwrap();
+
+        assert_badarg!(
+            result(process, subject, pattern, Term::NIL),
+            "must be a non-empty binary"
+        );
+    });
+}
+
+fn position_length(process: &Process, position: usize, length: usize) -> Term {
+    process
+        .tuple_from_slice(&[
+            process.integer(position).unwrap(),
+            process.integer(length).unwrap(),
+        ])
+        .unwrap()
+}
+
+fn scope_options(process: &Process, start: usize, length: isize) -> Term {
+    let start_length = process
+        .tuple_from_slice(&[
+            process.integer(start).unwrap(),
+            process.integer(length).unwrap(),
+        ])
+        .unwrap();
+    let scope = process
+        .tuple_from_slice(&[Atom::str_to_term("scope"), start_length])
+        .unwrap();
+
+    process.list_from_slice(&[scope]).unwrap()
+}
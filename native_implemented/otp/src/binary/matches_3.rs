@@ -0,0 +1,36 @@
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::match_3::options::Options;
+use crate::binary::pattern::{self, Pattern};
+use crate::binary::scope::Scope;
+use crate::runtime::context::*;
+
+/// All the non-overlapping matches of `pattern` in `subject` as a list of `{Pos, Len}`
+#[native_implemented::function(binary:matches/3)]
+pub fn result(
+    process: &Process,
+    subject: Term,
+    pattern: Term,
+    options: Term,
+) -> exception::Result<Term> {
+    let bytes = process
+        .bytes_from_binary(subject)
+        .with_context(|| term_is_not_binary("subject", subject))?;
+    let pattern_pattern = Pattern::try_from_term(process, pattern)?;
+    let options_options: Options = options.try_into()?;
+    let range = Scope::range(options_options.scope, bytes.len())?;
+
+    let mut found_term_vec = Vec::new();
+
+    for found in pattern_pattern.find_all(bytes, range) {
+        found_term_vec.push(pattern::found_to_term(process, found)?);
+    }
+
+    process.list_from_slice(&found_term_vec).map_err(From::from)
+}
@@ -0,0 +1,10 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang;
+
+#[native_implemented::function(binary:part/2)]
+pub fn result(process: &Process, subject: Term, position_length: Term) -> exception::Result<Term> {
+    erlang::binary_part_2::result(process, subject, position_length)
+}
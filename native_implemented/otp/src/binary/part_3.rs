@@ -0,0 +1,15 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang;
+
+#[native_implemented::function(binary:part/3)]
+pub fn result(
+    process: &Process,
+    subject: Term,
+    position: Term,
+    length: Term,
+) -> exception::Result<Term> {
+    erlang::binary_part_3::result(process, subject, position, length)
+}
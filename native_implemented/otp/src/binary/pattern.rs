@@ -0,0 +1,164 @@
+use std::convert::TryInto;
+use std::ops::Range;
+use std::sync::Arc;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+const PATTERN_CONTEXT: &str =
+    "must be a non-empty binary, a non-empty list of non-empty binaries, or a compiled pattern";
+
+/// A search pattern, either compiled by `binary:compile_pattern/1` or from the binaries passed to
+/// the searching functions directly.
+///
+/// Like BEAM, a single binary is searched for with Boyer-Moore and multiple binaries with
+/// Aho-Corasick.  When several binaries match at the same position, the longest is chosen.
+pub enum Pattern {
+    BoyerMoore {
+        needle: Vec<u8>,
+        /// How far the needle can shift when the byte under its last byte does not match
+        bad_byte_shift: Box<[usize; 256]>,
+    },
+    AhoCorasick(AhoCorasick),
+}
+
+impl Pattern {
+    pub fn new(mut needles: Vec<Vec<u8>>) -> Self {
+        if needles.len() == 1 {
+            let needle = needles.pop().unwrap();
+            let len = needle.len();
+            let mut bad_byte_shift = Box::new([len; 256]);
+
+            for (index, byte) in needle[..len - 1].iter().enumerate() {
+                bad_byte_shift[*byte as usize] = len - 1 - index;
+            }
+
+            Pattern::BoyerMoore {
+                needle,
+                bad_byte_shift,
+            }
+        } else {
+            let aho_corasick = AhoCorasickBuilder::new()
+                .match_kind(MatchKind::LeftmostLongest)
+                .build(needles);
+
+            Pattern::AhoCorasick(aho_corasick)
+        }
+    }
+
+    /// Compiles `term`, unless it is already a compiled pattern
+    pub fn try_from_term(process: &Process, term: Term) -> InternalResult<Arc<Pattern>> {
+        match term.decode().unwrap() {
+            TypedTerm::Tuple(tuple) => {
+                if tuple.len() == 2 {
+                    let resource_result: Result<Boxed<Resource>, _> = tuple[1].try_into();
+
+                    if let Ok(resource) = resource_result {
+                        let resource: Resource = resource.into();
+
+                        if let Some(pattern) = resource.downcast_ref::<Arc<Pattern>>() {
+                            return Ok(pattern.clone());
+                        }
+                    }
+                }
+
+                Err(anyhow!("pattern ({}) {}", term, PATTERN_CONTEXT).into())
+            }
+            TypedTerm::List(cons) => {
+                let mut needles = Vec::new();
+
+                for result in cons.into_iter() {
+                    let element = result
+                        .map_err(|_| ImproperListError)
+                        .with_context(|| format!("pattern ({}) {}", term, PATTERN_CONTEXT))?;
+                    needles.push(needle(process, term, element)?);
+                }
+
+                Ok(Arc::new(Pattern::new(needles)))
+            }
+            _ => Ok(Arc::new(Pattern::new(vec![needle(process, term, term)?]))),
+        }
+    }
+
+    /// `{bm, Resource}` or `{ac, Resource}`, as returned by `binary:compile_pattern/1`
+    pub fn into_term(self: Arc<Self>, process: &Process) -> InternalResult<Term> {
+        let tag = match self.as_ref() {
+            Pattern::BoyerMoore { .. } => Atom::str_to_term("bm"),
+            Pattern::AhoCorasick(_) => Atom::str_to_term("ac"),
+        };
+        let resource = process.resource(self)?;
+
+        process
+            .tuple_from_slice(&[tag, resource])
+            .map_err(From::from)
+    }
+
+    /// The first match in `haystack` at or after `start`, as a range of `haystack`
+    pub fn find(&self, haystack: &[u8], start: usize) -> Option<Range<usize>> {
+        match self {
+            Pattern::BoyerMoore {
+                needle,
+                bad_byte_shift,
+            } => {
+                let len = needle.len();
+                let mut index = start;
+
+                while index + len <= haystack.len() {
+                    if &haystack[index..index + len] == needle.as_slice() {
+                        return Some(index..index + len);
+                    }
+
+                    index += bad_byte_shift[haystack[index + len - 1] as usize];
+                }
+
+                None
+            }
+            Pattern::AhoCorasick(aho_corasick) => aho_corasick
+                .find(&haystack[start..])
+                .map(|found| start + found.start()..start + found.end()),
+        }
+    }
+
+    /// All the non-overlapping matches in `range` of `haystack`, as ranges of `haystack`
+    pub fn find_all(&self, haystack: &[u8], range: Range<usize>) -> Vec<Range<usize>> {
+        let scoped_haystack = &haystack[..range.end];
+        let mut found_vec = Vec::new();
+        let mut start = range.start;
+
+        while let Some(found) = self.find(scoped_haystack, start) {
+            // needles are never empty, so the search always moves forward
+            start = found.end;
+            found_vec.push(found);
+        }
+
+        found_vec
+    }
+}
+
+/// `{Pos, Len}` of a match
+pub fn found_to_term(process: &Process, found: Range<usize>) -> exception::Result<Term> {
+    let position = process.integer(found.start)?;
+    let length = process.integer(found.end - found.start)?;
+
+    process
+        .tuple_from_slice(&[position, length])
+        .map_err(From::from)
+}
+
+// Private
+
+fn needle(process: &Process, pattern: Term, binary: Term) -> InternalResult<Vec<u8>> {
+    let bytes = process
+        .bytes_from_binary(binary)
+        .with_context(|| format!("pattern ({}) {}", pattern, PATTERN_CONTEXT))?;
+
+    if bytes.is_empty() {
+        Err(anyhow!("pattern ({}) {}", pattern, PATTERN_CONTEXT).into())
+    } else {
+        Ok(bytes.to_vec())
+    }
+}
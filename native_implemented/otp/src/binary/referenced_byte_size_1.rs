@@ -0,0 +1,35 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+/// The size of the binary data `binary` keeps alive, which for a sub binary is the size of the
+/// binary it is a slice of.
+#[native_implemented::function(binary:referenced_byte_size/1)]
+pub fn result(process: &Process, binary: Term) -> exception::Result<Term> {
+    let option_referenced_byte_len = match binary.decode().unwrap() {
+        TypedTerm::HeapBinary(heap_binary) => Some(heap_binary.full_byte_len()),
+        TypedTerm::ProcBin(process_binary) => Some(process_binary.full_byte_len()),
+        TypedTerm::BinaryLiteral(binary_literal) => Some(binary_literal.full_byte_len()),
+        TypedTerm::SubBinary(subbinary) if subbinary.is_binary() => {
+            match subbinary.original().decode().unwrap() {
+                TypedTerm::HeapBinary(heap_binary) => Some(heap_binary.full_byte_len()),
+                TypedTerm::ProcBin(process_binary) => Some(process_binary.full_byte_len()),
+                TypedTerm::BinaryLiteral(binary_literal) => Some(binary_literal.full_byte_len()),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    match option_referenced_byte_len {
+        Some(referenced_byte_len) => Ok(process.integer(referenced_byte_len)?),
+        None => Err(TypeError)
+            .context(format!("binary ({}) is not a binary", binary))
+            .map_err(From::from),
+    }
+}
@@ -0,0 +1,45 @@
+use crate::binary::referenced_byte_size_1::result;
+use crate::test::with_process;
+
+#[test]
+fn without_binary_errors_badarg() {
+    with_process(|process| {
+        let bitstring = process
+            .subbinary_from_original(process.binary_from_bytes(&[1, 2]).unwrap(), 0, 0, 1, 3)
+            .unwrap()
+            .into();
+
+        assert_badarg!(
+            result(process, bitstring),
+            format!("binary ({}) is not a binary", bitstring)
+        );
+    });
+}
+
+#[test]
+fn with_heap_binary_is_byte_count() {
+    with_process(|process| {
+        let binary = process.binary_from_bytes(&[1, 2, 3]).unwrap();
+
+        assert_eq!(result(process, binary), Ok(process.integer(3).unwrap()));
+    });
+}
+
+#[test]
+fn with_subbinary_of_procbin_is_byte_count_of_procbin() {
+    with_process(|process| {
+        let original = process.binary_from_bytes(&[0; 100]).unwrap();
+
+        assert!(original.is_boxed_procbin());
+
+        let subbinary = process
+            .subbinary_from_original(original, 10, 0, 5, 0)
+            .unwrap()
+            .into();
+
+        assert_eq!(
+            result(process, subbinary),
+            Ok(process.integer(100).unwrap())
+        );
+    });
+}
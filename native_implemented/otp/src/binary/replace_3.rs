@@ -0,0 +1,15 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::replace_4;
+
+#[native_implemented::function(binary:replace/3)]
+pub fn result(
+    process: &Process,
+    subject: Term,
+    pattern: Term,
+    replacement: Term,
+) -> exception::Result<Term> {
+    replace_4::result(process, subject, pattern, replacement, Term::NIL)
+}
@@ -0,0 +1,83 @@
+mod options;
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::pattern::Pattern;
+use crate::binary::scope::Scope;
+use crate::runtime::context::*;
+
+use options::Options;
+
+/// Replaces the first match of `pattern` in `subject` or, with `global`, every match, with
+/// `replacement`.
+#[native_implemented::function(binary:replace/4)]
+pub fn result(
+    process: &Process,
+    subject: Term,
+    pattern: Term,
+    replacement: Term,
+    options: Term,
+) -> exception::Result<Term> {
+    let bytes = process
+        .bytes_from_binary(subject)
+        .with_context(|| term_is_not_binary("subject", subject))?;
+    let pattern_pattern = Pattern::try_from_term(process, pattern)?;
+    let replacement_bytes = process
+        .bytes_from_binary(replacement)
+        .with_context(|| term_is_not_binary("replacement", replacement))?;
+    let options_options: Options = options.try_into()?;
+
+    if let Some(position) = options_options.insert_replaced.last() {
+        if replacement_bytes.len() < *position {
+            return Err(anyhow!(
+                "insert_replaced position ({}) exceeds replacement ({}) byte size ({})",
+                position,
+                replacement,
+                replacement_bytes.len()
+            )
+            .into());
+        }
+    }
+
+    let range = Scope::range(options_options.scope, bytes.len())?;
+
+    let found_vec = if options_options.global {
+        pattern_pattern.find_all(bytes, range)
+    } else {
+        pattern_pattern
+            .find(&bytes[..range.end], range.start)
+            .into_iter()
+            .collect()
+    };
+
+    let mut replaced_vec = Vec::with_capacity(bytes.len());
+    let mut unmatched_start = 0;
+
+    for found in found_vec {
+        replaced_vec.extend_from_slice(&bytes[unmatched_start..found.start]);
+
+        let mut replacement_start = 0;
+
+        for position in &options_options.insert_replaced {
+            replaced_vec.extend_from_slice(&replacement_bytes[replacement_start..*position]);
+            replaced_vec.extend_from_slice(&bytes[found.clone()]);
+            replacement_start = *position;
+        }
+
+        replaced_vec.extend_from_slice(&replacement_bytes[replacement_start..]);
+        unmatched_start = found.end;
+    }
+
+    replaced_vec.extend_from_slice(&bytes[unmatched_start..]);
+
+    process.binary_from_bytes(&replaced_vec).map_err(From::from)
+}
@@ -0,0 +1,122 @@
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::scope::Scope;
+use crate::runtime::proplist::TryPropListFromTermError;
+
+pub struct Options {
+    pub global: bool,
+    /// Sorted positions in the replacement where the replaced part of the subject is inserted
+    pub insert_replaced: Vec<usize>,
+    pub scope: Option<Scope>,
+}
+
+const SUPPORTED_OPTIONS_CONTEXT: &str =
+    "supported options are global, {scope, {Start, Length}}, or {insert_replaced, Pos | [Pos]}";
+
+impl Options {
+    fn put_option_term(&mut self, option: Term) -> anyhow::Result<&Self> {
+        match option.decode().unwrap() {
+            TypedTerm::Atom(atom) => match atom.name() {
+                "global" => {
+                    self.global = true;
+
+                    Ok(self)
+                }
+                name => Err(TryPropListFromTermError::AtomName(name).into()),
+            },
+            TypedTerm::Tuple(tuple) => {
+                if tuple.len() == 2 {
+                    let atom: Atom = tuple[0]
+                        .try_into()
+                        .map_err(|_| TryPropListFromTermError::KeywordKeyType)?;
+
+                    match atom.name() {
+                        "insert_replaced" => {
+                            self.insert_replaced = positions(tuple[1])?;
+
+                            Ok(self)
+                        }
+                        "scope" => {
+                            self.scope = Some(tuple[1].try_into()?);
+
+                            Ok(self)
+                        }
+                        name => Err(TryPropListFromTermError::KeywordKeyName(name).into()),
+                    }
+                } else {
+                    Err(TryPropListFromTermError::TupleNotPair.into())
+                }
+            }
+            _ => Err(TryPropListFromTermError::PropertyType.into()),
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            global: false,
+            insert_replaced: Vec::new(),
+            scope: None,
+        }
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> anyhow::Result<Self> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.decode().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options
+                        .put_option_term(cons.head)
+                        .context(SUPPORTED_OPTIONS_CONTEXT)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(ImproperListError).context(SUPPORTED_OPTIONS_CONTEXT),
+            }
+        }
+    }
+}
+
+// Private
+
+fn position(term: Term) -> anyhow::Result<usize> {
+    term.try_into()
+        .with_context(|| format!("insert_replaced position ({}) must be non-negative", term))
+}
+
+fn positions(term: Term) -> anyhow::Result<Vec<usize>> {
+    let mut position_vec = match term.decode().unwrap() {
+        TypedTerm::Nil => Vec::new(),
+        TypedTerm::List(cons) => {
+            let mut position_vec = Vec::new();
+
+            for result in cons.into_iter() {
+                let element = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("insert_replaced ({}) is improper", term))?;
+
+                position_vec.push(position(element)?);
+            }
+
+            position_vec
+        }
+        _ => vec![position(term)?],
+    };
+
+    position_vec.sort_unstable();
+
+    Ok(position_vec)
+}
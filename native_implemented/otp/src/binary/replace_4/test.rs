@@ -0,0 +1,82 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::replace_4::result;
+use crate::test::with_process;
+
+#[test]
+fn without_global_replaces_first_match() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcab").unwrap();
+        let pattern = process.binary_from_bytes(b"b").unwrap();
+        let replacement = process.binary_from_bytes(b"[]").unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, replacement, Term::NIL),
+            Ok(process.binary_from_bytes(b"a[]cab").unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_global_replaces_every_match() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcab").unwrap();
+        let pattern = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"a").unwrap(),
+                process.binary_from_bytes(b"b").unwrap(),
+            ])
+            .unwrap();
+        let replacement = process.binary_from_bytes(b"-").unwrap();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("global")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, replacement, options),
+            Ok(process.binary_from_bytes(b"--c--").unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_insert_replaced_inserts_match_at_each_position() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcde").unwrap();
+        let pattern = process.binary_from_bytes(b"b").unwrap();
+        let replacement = process.binary_from_bytes(b"[]").unwrap();
+        let positions = process
+            .list_from_slice(&[process.integer(2).unwrap(), process.integer(1).unwrap()])
+            .unwrap();
+        let insert_replaced = process
+            .tuple_from_slice(&[Atom::str_to_term("insert_replaced"), positions])
+            .unwrap();
+        let options = process.list_from_slice(&[insert_replaced]).unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, replacement, options),
+            Ok(process.binary_from_bytes(b"a[b]bcde").unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_insert_replaced_position_after_replacement_errors_badarg() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abcde").unwrap();
+        let pattern = process.binary_from_bytes(b"b").unwrap();
+        let replacement = process.binary_from_bytes(b"[]").unwrap();
+        let insert_replaced = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("insert_replaced"),
+                process.integer(3).unwrap(),
+            ])
+            .unwrap();
+        let options = process.list_from_slice(&[insert_replaced]).unwrap();
+
+        assert_badarg!(
+            result(process, subject, pattern, replacement, options),
+            "insert_replaced position (3) exceeds replacement"
+        );
+    });
+}
@@ -0,0 +1,53 @@
+use std::convert::{TryFrom, TryInto};
+use std::ops::Range;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::{start_length_to_part_range, PartRangeError};
+
+/// The `{Start, Length}` of a `{scope, {Start, Length}}` option, which limits searching to that
+/// part of the subject.  As with `binary:part/2`, `Length` can be negative to scope the bytes
+/// before `Start`.
+#[derive(Clone, Copy)]
+pub struct Scope {
+    start: usize,
+    length: isize,
+}
+
+impl Scope {
+    /// The range of the subject to search, which is all of it without a scope.
+    pub fn range(
+        option_scope: Option<Scope>,
+        available_byte_count: usize,
+    ) -> Result<Range<usize>, PartRangeError> {
+        match option_scope {
+            Some(Scope { start, length }) => {
+                start_length_to_part_range(start, length, available_byte_count).map(From::from)
+            }
+            None => Ok(0..available_byte_count),
+        }
+    }
+}
+
+impl TryFrom<Term> for Scope {
+    type Error = anyhow::Error;
+
+    fn try_from(scope: Term) -> anyhow::Result<Self> {
+        let tuple = term_try_into_tuple!(scope)?;
+
+        if tuple.len() == 2 {
+            let start: usize = tuple[0]
+                .try_into()
+                .with_context(|| format!("scope start ({}) must be non-negative", tuple[0]))?;
+            let length: isize = tuple[1]
+                .try_into()
+                .with_context(|| format!("scope length ({}) must be an integer", tuple[1]))?;
+
+            Ok(Self { start, length })
+        } else {
+            Err(anyhow!("scope ({}) must be {{Start, Length}}", scope))
+        }
+    }
+}
@@ -0,0 +1,72 @@
+mod options;
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::ops::Range;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::pattern::Pattern;
+use crate::binary::scope::Scope;
+use crate::binary::sub_binary;
+use crate::runtime::context::*;
+
+use options::Options;
+
+/// Splits `subject` around the first match of `pattern` or, with `global`, every match.  The parts
+/// share the bytes of `subject`.
+#[native_implemented::function(binary:split/3)]
+pub fn result(
+    process: &Process,
+    subject: Term,
+    pattern: Term,
+    options: Term,
+) -> exception::Result<Term> {
+    let bytes = process
+        .bytes_from_binary(subject)
+        .with_context(|| term_is_not_binary("subject", subject))?;
+    let pattern_pattern = Pattern::try_from_term(process, pattern)?;
+    let options_options: Options = options.try_into()?;
+    let range = Scope::range(options_options.scope, bytes.len())?;
+
+    let found_vec = if options_options.global {
+        pattern_pattern.find_all(bytes, range)
+    } else {
+        pattern_pattern
+            .find(&bytes[..range.end], range.start)
+            .into_iter()
+            .collect()
+    };
+
+    let mut part_vec: Vec<Range<usize>> = Vec::with_capacity(found_vec.len() + 1);
+    let mut part_start = 0;
+
+    for found in found_vec {
+        part_vec.push(part_start..found.start);
+        part_start = found.end;
+    }
+
+    part_vec.push(part_start..bytes.len());
+
+    if options_options.trim_all {
+        part_vec.retain(|part| part.start < part.end);
+    } else if options_options.trim {
+        while part_vec.last().map_or(false, |part| part.start == part.end) {
+            part_vec.pop();
+        }
+    }
+
+    let mut part_term_vec = Vec::with_capacity(part_vec.len());
+
+    for part in part_vec {
+        part_term_vec.push(sub_binary(process, subject, part)?);
+    }
+
+    process.list_from_slice(&part_term_vec).map_err(From::from)
+}
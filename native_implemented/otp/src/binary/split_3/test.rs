@@ -0,0 +1,103 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::binary::split_3::result;
+use crate::test::with_process;
+
+#[test]
+fn without_match_returns_subject_in_list() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"abc").unwrap();
+        let pattern = process.binary_from_bytes(b"x").unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, Term::NIL),
+            Ok(process.list_from_slice(&[subject]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn without_global_splits_at_first_match() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"a,b,c").unwrap();
+        let pattern = process.binary_from_bytes(b",").unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, Term::NIL),
+            Ok(binaries(process, &[b"a", b"b,c"]))
+        );
+    });
+}
+
+#[test]
+fn with_global_splits_at_every_match() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b",a,,b,").unwrap();
+        let pattern = process.binary_from_bytes(b",").unwrap();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("global")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, options),
+            Ok(binaries(process, &[b"", b"a", b"", b"b", b""]))
+        );
+    });
+}
+
+#[test]
+fn with_trim_removes_trailing_empty_parts() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b",a,,b,,").unwrap();
+        let pattern = process.binary_from_bytes(b",").unwrap();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("global"), Atom::str_to_term("trim")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, options),
+            Ok(binaries(process, &[b"", b"a", b"", b"b"]))
+        );
+    });
+}
+
+#[test]
+fn with_trim_all_removes_all_empty_parts() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b",a,,b,,").unwrap();
+        let pattern = process.binary_from_bytes(b",").unwrap();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("global"), Atom::str_to_term("trim_all")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, subject, pattern, options),
+            Ok(binaries(process, &[b"a", b"b"]))
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_option_errors_badarg() {
+    with_process(|process| {
+        let subject = process.binary_from_bytes(b"a,b").unwrap();
+        let pattern = process.binary_from_bytes(b",").unwrap();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("unsupported")])
+            .unwrap();
+
+        assert_badarg!(
+            result(process, subject, pattern, options),
+            "supported options are global, trim, trim_all, or {scope, {Start, Length}}"
+        );
+    });
+}
+
+fn binaries(process: &Process, bytes_slice: &[&[u8]]) -> Term {
+    let binary_vec: Vec<Term> = bytes_slice
+        .iter()
+        .map(|bytes| process.binary_from_bytes(bytes).unwrap())
+        .collect();
+
+    process.list_from_slice(&binary_vec).unwrap()
+}
@@ -0,0 +1,14 @@
+//! Unlike OTP's `eprof`, which traces calls, processes are sampled by
+//! `lumen_rt_core::profiler`, so the analysis is written to files when profiling stops.
+pub mod start_profiling_1;
+pub mod stop_profiling_0;
+
+use liblumen_alloc::erts::term::prelude::Atom;
+
+fn module() -> Atom {
+    Atom::from_str("eprof")
+}
+
+fn module_id() -> usize {
+    module().id()
+}
@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::profiler::{self, Options};
+use crate::runtime::registry;
+
+/// Samples the processes in `rootset`, which are pids or registered names, until
+/// `eprof:stop_profiling/0`
+#[native_implemented::function(eprof:start_profiling/1)]
+pub fn result(process: &Process, rootset: Term) -> exception::Result<Term> {
+    let rootset = rootset_to_pid_set(rootset)?;
+    let options = Options {
+        rootset: Some(rootset),
+        ..Default::default()
+    };
+
+    match profiler::start(options) {
+        Ok(()) => Ok(Atom::str_to_term("profiling")),
+        Err(err) => {
+            let reason = match err {
+                profiler::ProfilerError::AlreadyProfiling => "already_profiling",
+                _ => "sampler",
+            };
+
+            process
+                .tuple_from_slice(&[Atom::str_to_term("error"), Atom::str_to_term(reason)])
+                .map_err(From::from)
+        }
+    }
+}
+
+fn rootset_to_pid_set(rootset: Term) -> exception::Result<HashSet<Pid>> {
+    let mut pid_set = HashSet::new();
+
+    match rootset.decode().unwrap() {
+        TypedTerm::Nil => (),
+        TypedTerm::List(cons) => {
+            for result in cons.into_iter() {
+                let element = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("rootset ({}) is improper", rootset))?;
+
+                pid_set.insert(element_to_pid(element)?);
+            }
+        }
+        _ => {
+            return Err(TypeError)
+                .context(format!("rootset ({}) is not a list", rootset))
+                .map_err(From::from)
+        }
+    }
+
+    Ok(pid_set)
+}
+
+fn element_to_pid(element: Term) -> exception::Result<Pid> {
+    match element.decode().unwrap() {
+        TypedTerm::Pid(pid) => Ok(pid),
+        TypedTerm::Atom(atom) => match registry::atom_to_process(&atom) {
+            Some(arc_process) => Ok(arc_process.pid()),
+            None => Err(anyhow!("name ({}) is not registered", element).into()),
+        },
+        _ => Err(TypeError)
+            .context(format!(
+                "rootset element ({}) is not a pid or registered name",
+                element
+            ))
+            .map_err(From::from),
+    }
+}
@@ -0,0 +1,19 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::profiler::{self, ProfilerError};
+
+/// Writes the samples as folded stacks to `lumen-profile.folded`, and the reductions and garbage
+/// collections of each process to `lumen-profile.report`, unless `--profile` gave another path
+#[native_implemented::function(eprof:stop_profiling/0)]
+pub fn result(process: &Process) -> exception::Result<Term> {
+    match profiler::stop() {
+        Ok(_) => Ok(Atom::str_to_term("profiling_stopped")),
+        Err(ProfilerError::NotProfiling) => Ok(Atom::str_to_term("profiling_already_stopped")),
+        Err(ProfilerError::Write { .. }) => process
+            .tuple_from_slice(&[Atom::str_to_term("error"), Atom::str_to_term("write")])
+            .map_err(From::from),
+        Err(err) => unreachable!("{}", err),
+    }
+}
@@ -61,6 +61,10 @@ pub mod float_to_list_2;
 mod float_to_string;
 pub mod floor_1;
 pub mod function_exported_3;
+mod garbage_collect;
+pub mod garbage_collect_0;
+pub mod garbage_collect_1;
+pub mod garbage_collect_2;
 pub mod get_0;
 pub mod get_1;
 pub mod get_keys_0;
@@ -69,6 +73,7 @@ pub mod get_stacktrace_0;
 pub mod group_leader_0;
 pub mod group_leader_2;
 pub mod hd_1;
+pub mod hibernate_3;
 pub mod insert_element_3;
 pub mod integer_to_binary_1;
 pub mod integer_to_binary_2;
@@ -121,6 +126,9 @@ pub mod make_tuple_3;
 pub mod map_get_2;
 pub mod map_size_1;
 pub mod max_2;
+mod memory;
+pub mod memory_0;
+pub mod memory_1;
 pub mod min_2;
 pub mod monitor_2;
 pub mod monotonic_time_0;
@@ -135,6 +143,8 @@ mod number_to_integer;
 pub mod or_2;
 pub mod orelse_2;
 pub mod process_flag_2;
+mod process_info;
+pub mod process_info_1;
 pub mod process_info_2;
 pub mod put_2;
 pub mod raise_3;
@@ -164,10 +174,12 @@ pub mod spawn_opt_4;
 pub mod split_binary_2;
 pub mod start_timer_3;
 pub mod start_timer_4;
+pub mod statistics_1;
 mod string_to_float;
 mod string_to_integer;
 pub mod subtract_2;
 pub mod subtract_list_2;
+pub mod system_info_1;
 pub mod system_time_0;
 pub mod system_time_1;
 mod term_to_binary;
@@ -178,6 +190,9 @@ pub mod time_offset_0;
 pub mod time_offset_1;
 pub mod timestamp_0;
 pub mod tl_1;
+pub mod trace_3;
+pub mod trace_pattern_2;
+pub mod trace_pattern_3;
 pub mod trunc_1;
 pub mod tuple_size_1;
 pub mod tuple_to_list_1;
@@ -67,7 +67,8 @@ pub fn frame_with_arguments(module: Term, function: Term, arguments: Term) -> Fr
 // Private
 
 /// `module`, `function`, and arity of `argument_list` must have code registered with
-/// `crate::runtime::code::export::insert` or returns `undef` exception.
+/// `crate::runtime::code::export::insert`, or the call is passed to `undefined_function/3` of the
+/// process's error handler.  The call is saved for `process_info(Pid, last_calls)`.
 #[export_name = "erlang:apply/3"]
 pub extern "C" fn native(module: Term, function: Term, argument_list: Term) -> Term {
     let mut argument_vec: Vec<Term> = Vec::new();
@@ -93,28 +94,61 @@ pub extern "C" fn native(module: Term, function: Term, argument_list: Term) -> T
         arity,
     };
 
+    current_process().save_call(module_function_arity);
+
     match find_symbol(&module_function_arity) {
-        Some(dynamic_call) => {
-            let native = unsafe {
-                let ptr = transmute::<DynamicCallee, *const c_void>(dynamic_call);
+        Some(dynamic_call) => queue(module_function_arity, dynamic_call, &argument_vec),
+        None => undefined_function(module_atom, function_atom, argument_list, arity),
+    }
+}
 
-                Native::from_ptr(ptr, arity)
-            };
-            let frame = Frame::new(module_function_arity, native);
-            let frame_with_arguments = frame.with_arguments(false, &argument_vec);
+fn queue(
+    module_function_arity: ModuleFunctionArity,
+    dynamic_call: DynamicCallee,
+    arguments: &[Term],
+) -> Term {
+    let native = unsafe {
+        let ptr = transmute::<DynamicCallee, *const c_void>(dynamic_call);
 
-            current_process().queue_frame_with_arguments(frame_with_arguments);
+        Native::from_ptr(ptr, module_function_arity.arity)
+    };
+    let frame = Frame::new(module_function_arity, native);
+    let frame_with_arguments = frame.with_arguments(false, arguments);
 
-            Term::NONE
-        }
-        None => undef(
-            module,
-            function,
+    current_process().queue_frame_with_arguments(frame_with_arguments);
+
+    Term::NONE
+}
+
+/// Calls `undefined_function/3` in the process's error handler module, the same as BEAM's
+/// `error_handler`.  If the error handler does not export `undefined_function/3`, or the
+/// undefined function is in the error handler itself, the call fails with `undef`.
+fn undefined_function(module: Atom, function: Atom, argument_list: Term, arity: Arity) -> Term {
+    let error_handler = current_process().error_handler();
+    let error_handler_module_function_arity = ModuleFunctionArity {
+        module: error_handler,
+        function: Atom::try_from_str("undefined_function").unwrap(),
+        arity: 3,
+    };
+
+    match find_symbol(&error_handler_module_function_arity) {
+        Some(dynamic_call) if module != error_handler => queue(
+            error_handler_module_function_arity,
+            dynamic_call,
+            &[
+                module.encode().unwrap(),
+                function.encode().unwrap(),
+                argument_list,
+            ],
+        ),
+        _ => undef(
+            module.encode().unwrap(),
+            function.encode().unwrap(),
             argument_list,
             anyhow!(
                 ":{}.{}/{} is not exported",
-                module_atom.name(),
-                function_atom.name(),
+                module.name(),
+                function.name(),
                 arity
             )
             .into(),
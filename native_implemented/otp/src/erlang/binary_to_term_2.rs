@@ -65,7 +65,7 @@ fn versioned_tagged_bytes_try_into_term(
 ) -> exception::Result<Term> {
     let after_version_bytes = version::check(bytes)?;
     let (term, after_term_bytes) =
-        term::decode_tagged(process, options.existing, after_version_bytes)?;
+        term::decode_tagged(&mut *process.acquire_heap(), options.existing, after_version_bytes)?;
 
     if options.used {
         let used_byte_len = bytes.len() - after_term_bytes.len();
@@ -0,0 +1,99 @@
+use std::convert::TryInto;
+use std::sync::atomic::Ordering;
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::gc::GcError;
+use liblumen_alloc::erts::process::{Process, ProcessFlags, Status};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::exit;
+
+/// Whether a collection is a full sweep of both generations or only of the young generation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Type {
+    Major,
+    Minor,
+}
+
+/// Collects `target`'s heap.
+///
+/// `process` can't collect its own heap while one of its natives is running, and a `target` that
+/// is running on another scheduler can't be collected from this process, so either is forced to
+/// collect when its current run ends instead.  When `target` is `process`, `process` yields, so
+/// that its run ends now.  Returns `false` if `target` is exiting.
+pub fn garbage_collect(
+    process: &Process,
+    target: &Process,
+    r#type: Type,
+) -> exception::Result<bool> {
+    if target.pid() == process.pid() {
+        force(process, r#type);
+        process.reduce_to_yield();
+
+        Ok(true)
+    } else {
+        // Holding the status keeps the scheduler from running `target` during the collection
+        let status = target.status.read();
+
+        match *status {
+            Status::RuntimeException(_) | Status::SystemException(_) => Ok(false),
+            Status::Running => {
+                force(target, r#type);
+
+                Ok(true)
+            }
+            _ => match collect(target, r#type) {
+                Ok(()) => Ok(true),
+                Err(GcError::Alloc(alloc)) => Err(alloc.into()),
+                // `target` exceeding its max heap size is left for when it collects itself
+                Err(_) => {
+                    force(target, r#type);
+
+                    Ok(true)
+                }
+            },
+        }
+    }
+}
+
+/// The exception for `process` failing to collect its own heap
+pub fn gc_error_to_exception(gc_error: GcError) -> Exception {
+    match gc_error {
+        GcError::Alloc(alloc) => alloc.into(),
+        _ => exit!(atom!("kill"), anyhow!(gc_error).into()).into(),
+    }
+}
+
+// Private
+
+fn collect(process: &Process, r#type: Type) -> Result<(), GcError> {
+    if r#type == Type::Major {
+        process.set_flags(ProcessFlags::NeedFullSweep);
+    }
+
+    let reductions = match process.garbage_collect(0, &mut []) {
+        Err(GcError::FullsweepRequired) => {
+            process.set_flags(ProcessFlags::NeedFullSweep);
+
+            process.garbage_collect(0, &mut [])
+        }
+        result => result,
+    }?;
+
+    process
+        .total_reductions
+        .fetch_add(reductions.try_into().unwrap(), Ordering::SeqCst);
+
+    Ok(())
+}
+
+fn force(process: &Process, r#type: Type) {
+    let flags = match r#type {
+        Type::Major => ProcessFlags::ForceGC | ProcessFlags::NeedFullSweep,
+        Type::Minor => ProcessFlags::ForceGC,
+    };
+
+    process.set_flags(flags);
+}
@@ -0,0 +1,13 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::garbage_collect::{garbage_collect, Type};
+
+#[native_implemented::function(erlang:garbage_collect/0)]
+pub fn result(process: &Process) -> exception::Result<Term> {
+    garbage_collect(process, process, Type::Major).map(From::from)
+}
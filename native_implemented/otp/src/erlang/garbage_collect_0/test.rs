@@ -0,0 +1,13 @@
+use liblumen_alloc::erts::process::ProcessFlags;
+
+use crate::erlang::garbage_collect_0::result;
+use crate::test::with_process;
+
+#[test]
+fn returns_true_and_yields_to_full_sweep() {
+    with_process(|process| {
+        assert_eq!(result(process), Ok(true.into()));
+        assert!(process.are_flags_set(ProcessFlags::ForceGC | ProcessFlags::NeedFullSweep));
+        assert!(process.is_reduced());
+    });
+}
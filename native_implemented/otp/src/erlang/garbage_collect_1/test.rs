@@ -0,0 +1,47 @@
+use proptest::strategy::Just;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::garbage_collect_1::result;
+use crate::test;
+use crate::test::{strategy, with_process};
+
+#[test]
+fn without_pid_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_pid(arc_process.clone()),
+            )
+        },
+        |(arc_process, pid)| {
+            prop_assert_is_not_local_pid!(result(&arc_process, pid), pid);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_self_returns_true() {
+    with_process(|process| {
+        assert_eq!(result(process, process.pid_term()), Ok(true.into()));
+    });
+}
+
+#[test]
+fn with_other_process_returns_true() {
+    with_process(|process| {
+        let other_process = test::process::child(process);
+
+        assert_eq!(result(process, other_process.pid_term()), Ok(true.into()));
+    });
+}
+
+#[test]
+fn without_process_returns_false() {
+    with_process(|process| {
+        assert_eq!(result(process, Pid::next_term()), Ok(false.into()));
+    });
+}
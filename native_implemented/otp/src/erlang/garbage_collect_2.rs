@@ -0,0 +1,60 @@
+mod options;
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::garbage_collect::garbage_collect;
+use crate::runtime::registry::pid_to_process;
+
+use crate::erlang::garbage_collect_2::options::Options;
+
+#[native_implemented::function(erlang:garbage_collect/2)]
+pub fn result(process: &Process, pid: Term, option_list: Term) -> exception::Result<Term> {
+    let pid_pid = term_try_into_local_pid!(pid)?;
+    let options: Options = option_list.try_into()?;
+
+    result_with_options(process, pid_pid, options)
+}
+
+// Private
+
+pub(in crate::erlang) fn result_with_options(
+    process: &Process,
+    pid: Pid,
+    options: Options,
+) -> exception::Result<Term> {
+    let Options {
+        r#type,
+        async_request_id,
+    } = options;
+
+    let collected = if pid == process.pid() {
+        garbage_collect(process, process, r#type)?
+    } else {
+        match pid_to_process(&pid) {
+            Some(pid_arc_process) => garbage_collect(process, &pid_arc_process, r#type)?,
+            None => false,
+        }
+    };
+
+    match async_request_id {
+        Some(request_id) => {
+            let message = process.tuple_from_slice(&[
+                atom!("garbage_collect"),
+                request_id,
+                collected.into(),
+            ])?;
+            process.send_from_self(message);
+
+            Ok(atom!("async"))
+        }
+        None => Ok(collected.into()),
+    }
+}
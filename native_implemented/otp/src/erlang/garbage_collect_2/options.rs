@@ -0,0 +1,89 @@
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::garbage_collect::Type;
+use crate::runtime::proplist::TryPropListFromTermError;
+
+pub struct Options {
+    pub r#type: Type,
+    /// The `RequestId` of `{async, RequestId}`, which is sent back in
+    /// `{garbage_collect, RequestId, GCResult}` instead of waiting for the result
+    pub async_request_id: Option<Term>,
+}
+
+const SUPPORTED_OPTIONS_CONTEXT: &str =
+    "supported options are {type, major | minor} or {async, RequestId}";
+
+impl Options {
+    fn put_option_term(&mut self, option: Term) -> anyhow::Result<&Self> {
+        let tuple: Boxed<Tuple> = option
+            .try_into()
+            .map_err(|_| TryPropListFromTermError::PropertyType)?;
+
+        if tuple.len() == 2 {
+            let atom: Atom = tuple[0]
+                .try_into()
+                .map_err(|_| TryPropListFromTermError::KeywordKeyType)?;
+
+            match atom.name() {
+                "async" => {
+                    self.async_request_id = Some(tuple[1]);
+
+                    Ok(self)
+                }
+                "type" => {
+                    let type_atom: Atom = tuple[1]
+                        .try_into()
+                        .with_context(|| format!("type ({}) must be major or minor", tuple[1]))?;
+
+                    self.r#type = match type_atom.name() {
+                        "major" => Type::Major,
+                        "minor" => Type::Minor,
+                        _ => return Err(anyhow!("type ({}) must be major or minor", tuple[1])),
+                    };
+
+                    Ok(self)
+                }
+                name => Err(TryPropListFromTermError::KeywordKeyName(name).into()),
+            }
+        } else {
+            Err(TryPropListFromTermError::TupleNotPair.into())
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            r#type: Type::Major,
+            async_request_id: None,
+        }
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> anyhow::Result<Self> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.decode().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options
+                        .put_option_term(cons.head)
+                        .context(SUPPORTED_OPTIONS_CONTEXT)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(ImproperListError).context(SUPPORTED_OPTIONS_CONTEXT),
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::ProcessFlags;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::garbage_collect_2::result;
+use crate::test::{has_message, with_process};
+
+#[test]
+fn without_supported_option_errors_badarg() {
+    with_process(|process| {
+        let option = process
+            .tuple_from_slice(&[Atom::str_to_term("unsupported"), atom!("true")])
+            .unwrap();
+        let option_list = process.list_from_slice(&[option]).unwrap();
+
+        assert_badarg!(
+            result(process, process.pid_term(), option_list),
+            "supported options are {type, major | minor} or {async, RequestId}"
+        );
+    });
+}
+
+#[test]
+fn without_major_or_minor_type_errors_badarg() {
+    with_process(|process| {
+        let option = process
+            .tuple_from_slice(&[Atom::str_to_term("type"), Atom::str_to_term("full")])
+            .unwrap();
+        let option_list = process.list_from_slice(&[option]).unwrap();
+
+        assert_badarg!(
+            result(process, process.pid_term(), option_list),
+            "type (full) must be major or minor"
+        );
+    });
+}
+
+#[test]
+fn with_minor_type_yields_to_collection_without_full_sweep() {
+    with_process(|process| {
+        let option = process
+            .tuple_from_slice(&[Atom::str_to_term("type"), Atom::str_to_term("minor")])
+            .unwrap();
+        let option_list = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            result(process, process.pid_term(), option_list),
+            Ok(true.into())
+        );
+        assert!(process.are_flags_set(ProcessFlags::ForceGC));
+        assert!(!process.are_flags_set(ProcessFlags::NeedFullSweep));
+        assert!(process.is_reduced());
+    });
+}
+
+#[test]
+fn with_major_type_yields_to_full_sweep() {
+    with_process(|process| {
+        let option = process
+            .tuple_from_slice(&[Atom::str_to_term("type"), Atom::str_to_term("major")])
+            .unwrap();
+        let option_list = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            result(process, process.pid_term(), option_list),
+            Ok(true.into())
+        );
+        assert!(process.are_flags_set(ProcessFlags::ForceGC | ProcessFlags::NeedFullSweep));
+        assert!(process.is_reduced());
+    });
+}
+
+#[test]
+fn with_async_returns_async_and_sends_result() {
+    with_process(|process| {
+        let request_id = process.integer(7).unwrap();
+        let option = process
+            .tuple_from_slice(&[Atom::str_to_term("async"), request_id])
+            .unwrap();
+        let option_list = process.list_from_slice(&[option]).unwrap();
+
+        assert_eq!(
+            result(process, process.pid_term(), option_list),
+            Ok(Atom::str_to_term("async"))
+        );
+
+        let message = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("garbage_collect"),
+                request_id,
+                true.into(),
+            ])
+            .unwrap();
+
+        assert!(has_message(process, message));
+    });
+}
@@ -0,0 +1,57 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::error;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::apply_3;
+use crate::erlang::garbage_collect::gc_error_to_exception;
+
+/// Discards the call stack and shrinks the heap to fit the live data, then waits for a message,
+/// at which point `apply(module, function, arguments)` is called.  When that call returns, the
+/// process exits, as there is nothing left on the stack to return to.
+#[native_implemented::function(erlang:hibernate/3)]
+pub fn result(
+    process: &Process,
+    module: Term,
+    function: Term,
+    arguments: Term,
+) -> exception::Result<Term> {
+    term_try_into_atom!(module)?;
+    term_try_into_atom!(function)?;
+
+    if !arguments.decode()?.is_proper_list() {
+        return Err(TypeError)
+            .with_context(|| format!("arguments ({}) must be a proper list", arguments))
+            .map_err(From::from);
+    }
+
+    // Compiled code calls natives without frames, so it would never run the queued `apply/3`
+    if process.current_module_function_arity().is_none() {
+        return Err(error!(
+            atom!("notsup"),
+            anyhow!("hibernate/3 needs natives to be run from frames").into()
+        )
+        .into());
+    }
+
+    let mut roots = [module, function, arguments];
+    process
+        .hibernate(&mut roots)
+        .map_err(gc_error_to_exception)?;
+
+    let [module, function, arguments] = roots;
+    process.queue_frame_with_arguments(apply_3::frame_with_arguments(module, function, arguments));
+
+    // A message that is already in the mailbox wakes the process immediately
+    if process.message_queue_len() == 0 {
+        process.wait();
+    }
+
+    Ok(Term::NONE)
+}
@@ -0,0 +1,66 @@
+use liblumen_alloc::erts::process::Status;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::hibernate_3::result;
+use crate::test;
+use crate::test::with_process;
+
+#[test]
+fn without_proper_list_arguments_errors_badarg() {
+    with_process(|process| {
+        let module = Atom::str_to_term("module");
+        let function = Atom::str_to_term("function");
+        let arguments = process
+            .cons(process.integer(0).unwrap(), process.integer(1).unwrap())
+            .unwrap();
+
+        assert_badarg!(
+            result(process, module, function, arguments),
+            format!("arguments ({}) must be a proper list", arguments)
+        );
+    });
+}
+
+#[test]
+fn without_message_waits_with_shrunk_heap() {
+    with_process(|parent_process| {
+        let arc_process = test::process::child(parent_process);
+        let heap_size_before = arc_process.heap_size();
+
+        assert_eq!(
+            result(
+                &arc_process,
+                Atom::str_to_term("module"),
+                Atom::str_to_term("function"),
+                Term::NIL
+            ),
+            Ok(Term::NONE)
+        );
+
+        assert_eq!(*arc_process.status.read(), Status::Waiting);
+        assert!(arc_process.heap_size() < heap_size_before);
+        assert_eq!(arc_process.stack_used(), 0);
+    });
+}
+
+#[test]
+fn with_message_does_not_wait() {
+    with_process(|parent_process| {
+        let arc_process = test::process::child(parent_process);
+        arc_process
+            .send_from_other(Atom::str_to_term("message"))
+            .unwrap();
+
+        assert_eq!(
+            result(
+                &arc_process,
+                Atom::str_to_term("module"),
+                Atom::str_to_term("function"),
+                Term::NIL
+            ),
+            Ok(Term::NONE)
+        );
+
+        assert_ne!(*arc_process.status.read(), Status::Waiting);
+    });
+}
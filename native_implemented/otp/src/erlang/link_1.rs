@@ -3,12 +3,14 @@ mod test;
 
 use anyhow::*;
 
-use liblumen_alloc::error;
 use liblumen_alloc::erts::exception;
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{error, exit};
 
+use crate::runtime::distribution;
 use crate::runtime::registry::pid_to_process;
+use crate::runtime::trace;
 
 #[native_implemented::function(erlang:link/1)]
 fn result(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
@@ -20,6 +22,7 @@ fn result(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
                 match pid_to_process(&pid) {
                     Some(pid_arc_process) => {
                         process.link(&pid_arc_process);
+                        trace::link(process, pid_or_port, true);
 
                         Ok(true.into())
                     }
@@ -32,7 +35,26 @@ fn result(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
             }
         }
         TypedTerm::Port(_) => unimplemented!(),
-        TypedTerm::ExternalPid(_) => unimplemented!(),
+        TypedTerm::ExternalPid(external_pid) => {
+            match distribution::connect(external_pid.arc_node().name()) {
+                Ok(connection) => {
+                    connection.link(process.pid(), &external_pid);
+                    trace::link(process, pid_or_port, true);
+
+                    Ok(true.into())
+                }
+                Err(error) => Err(exit!(
+                    Atom::str_to_term("noconnection"),
+                    error
+                        .context(format!(
+                            "pid ({}) is on a node that cannot be connected to",
+                            external_pid
+                        ))
+                        .into()
+                )
+                .into()),
+            }
+        }
         TypedTerm::ExternalPort(_) => unimplemented!(),
         _ => Err(TypeError)
             .context(format!(
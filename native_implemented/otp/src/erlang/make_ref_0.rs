@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::convert::TryInto;
+
 use liblumen_alloc::erts::exception;
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::prelude::*;
@@ -9,5 +11,12 @@ use crate::runtime::scheduler::SchedulerDependentAlloc;
 
 #[native_implemented::function(erlang:make_ref/0)]
 pub fn result(process: &Process) -> exception::Result<Term> {
-    process.next_reference().map_err(|error| error.into())
+    let reference_term = process.next_reference()?;
+    let reference: Boxed<Reference> = reference_term.try_into().unwrap();
+
+    // No message already in the mailbox can contain the new reference, so a receive that only
+    // matches messages containing it can skip them.
+    process.mailbox.lock().borrow_mut().recv_mark(&reference);
+
+    Ok(reference_term)
 }
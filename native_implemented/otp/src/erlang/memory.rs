@@ -0,0 +1,96 @@
+use std::mem;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::atom::atom_memory;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::std_alloc;
+
+use crate::runtime::{ets, registry};
+
+pub const TYPES: &[&str] = &[
+    "total",
+    "processes",
+    "processes_used",
+    "system",
+    "atom",
+    "atom_used",
+    "binary",
+    "code",
+    "ets",
+];
+
+pub const SUPPORTED_TYPES_CONTEXT: &str = "supported types are total, processes, processes_used, system, atom, atom_used, binary, code, and ets";
+
+/// Bytes of memory used by the runtime, by the types reported by `erlang:memory/0,1`
+pub struct Memory {
+    atom: usize,
+    binary: usize,
+    ets: usize,
+    processes: usize,
+    processes_used: usize,
+    system: usize,
+}
+
+impl Memory {
+    pub fn measure() -> Self {
+        let (processes, processes_used) = registry::processes().iter().fold(
+            (0, 0),
+            |(processes, processes_used), arc_process| {
+                (
+                    processes + arc_process.memory(),
+                    processes_used + arc_process.memory_used(),
+                )
+            },
+        );
+        let atom = atom_memory();
+        let binary = ProcBin::allocated_bytes();
+        let ets_words: usize = ets::all().iter().map(|table| table.memory()).sum();
+        let ets = ets_words * mem::size_of::<Term>();
+        // Allocations outside of process heaps, binaries, atoms and tables are only known when
+        // the standard allocator is instrumented
+        let other = std_alloc::stats().map_or(0, |statistics| statistics.bytes_in_use());
+
+        Self {
+            atom,
+            binary,
+            ets,
+            processes,
+            processes_used,
+            system: atom + binary + ets + other,
+        }
+    }
+
+    /// The size of `type` or `None` if `type` is not supported
+    pub fn get(&self, r#type: &str) -> Option<usize> {
+        match r#type {
+            "total" => Some(self.processes + self.system),
+            "processes" => Some(self.processes),
+            "processes_used" => Some(self.processes_used),
+            "system" => Some(self.system),
+            "atom" | "atom_used" => Some(self.atom),
+            "binary" => Some(self.binary),
+            // Code is compiled into the executable, so no memory is allocated when loading it
+            "code" => Some(0),
+            "ets" => Some(self.ets),
+            _ => None,
+        }
+    }
+
+    /// `{Type, Size}`
+    pub fn type_size_to_term(
+        &self,
+        process: &Process,
+        r#type: Atom,
+    ) -> exception::Result<Option<Term>> {
+        match self.get(r#type.name()) {
+            Some(size) => {
+                let size_term = process.integer(size)?;
+                let type_size = process.tuple_from_slice(&[r#type.encode()?, size_term])?;
+
+                Ok(Some(type_size))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::memory::{Memory, TYPES};
+
+/// `[{Type, Size}]` for all the types of memory supported by `erlang:memory/1`
+#[native_implemented::function(erlang:memory/0)]
+pub fn result(process: &Process) -> exception::Result<Term> {
+    let memory = Memory::measure();
+    let mut type_size_vec = Vec::with_capacity(TYPES.len());
+
+    for name in TYPES {
+        let r#type = Atom::from_str(name);
+        type_size_vec.push(memory.type_size_to_term(process, r#type)?.unwrap());
+    }
+
+    process.list_from_slice(&type_size_vec).map_err(From::from)
+}
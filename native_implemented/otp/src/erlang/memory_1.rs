@@ -0,0 +1,61 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::memory::{Memory, SUPPORTED_TYPES_CONTEXT};
+
+/// The size of a type of memory or, for a list of types, `[{Type, Size}]`
+#[native_implemented::function(erlang:memory/1)]
+pub fn result(process: &Process, type_or_type_list: Term) -> exception::Result<Term> {
+    let memory = Memory::measure();
+
+    match type_or_type_list.decode()? {
+        TypedTerm::Atom(r#type) => match memory.get(r#type.name()) {
+            Some(size) => process.integer(size).map_err(From::from),
+            None => Err(TryAtomFromTermError(r#type.name()))
+                .context(SUPPORTED_TYPES_CONTEXT)
+                .map_err(From::from),
+        },
+        TypedTerm::Nil => Ok(Term::NIL),
+        TypedTerm::List(cons) => {
+            let mut type_size_vec = Vec::new();
+
+            for result in cons.into_iter() {
+                let element = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("type_list ({}) is improper", type_or_type_list))?;
+                let r#type: Atom = element
+                    .try_into()
+                    .with_context(|| format!("type ({}) is not an atom", element))
+                    .context(SUPPORTED_TYPES_CONTEXT)?;
+
+                match memory.type_size_to_term(process, r#type)? {
+                    Some(type_size) => type_size_vec.push(type_size),
+                    None => {
+                        return Err(TryAtomFromTermError(r#type.name()))
+                            .context(SUPPORTED_TYPES_CONTEXT)
+                            .map_err(From::from)
+                    }
+                }
+            }
+
+            process.list_from_slice(&type_size_vec).map_err(From::from)
+        }
+        _ => Err(TypeError)
+            .with_context(|| {
+                format!(
+                    "type_or_type_list ({}) is neither an atom nor a list of atoms",
+                    type_or_type_list
+                )
+            })
+            .context(SUPPORTED_TYPES_CONTEXT)
+            .map_err(From::from),
+    }
+}
@@ -0,0 +1,73 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::memory_1::result;
+use crate::test::with_process;
+
+#[test]
+fn without_supported_type_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Atom::str_to_term("unsupported")),
+            "supported types are total, processes, processes_used, system, atom, atom_used, binary, code, and ets"
+        );
+    });
+}
+
+#[test]
+fn with_type_list_returns_type_size_list() {
+    with_process(|process| {
+        let total = Atom::str_to_term("total");
+        let processes = Atom::str_to_term("processes");
+        let system = Atom::str_to_term("system");
+        let type_list = process
+            .list_from_slice(&[total, processes, system])
+            .unwrap();
+
+        let type_size_list = result(process, type_list).unwrap();
+        let type_size_cons: Boxed<Cons> = type_size_list.try_into().unwrap();
+        let sizes: Vec<usize> = type_size_cons
+            .into_iter()
+            .map(|result| {
+                let tuple: Boxed<Tuple> = result.unwrap().try_into().unwrap();
+
+                assert_eq!(tuple.len(), 2);
+
+                tuple[1].try_into().unwrap()
+            })
+            .collect();
+
+        assert_eq!(sizes.len(), 3);
+        // measured once for the whole list, so the sizes are consistent
+        assert_eq!(sizes[0], sizes[1] + sizes[2]);
+    });
+}
+
+#[test]
+fn with_processes_includes_process_memory() {
+    with_process(|process| {
+        let processes: usize = result(process, Atom::str_to_term("processes"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        assert!(process.memory() <= processes);
+    });
+}
+
+#[test]
+fn with_binary_includes_reference_counted_binary() {
+    with_process(|process| {
+        let procbin = process.binary_from_bytes(&[0; 100]).unwrap();
+
+        assert!(procbin.is_boxed_procbin());
+
+        let binary: usize = result(process, Atom::str_to_term("binary"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        assert!(100 <= binary);
+    });
+}
@@ -12,6 +12,7 @@ use liblumen_alloc::erts::term::prelude::*;
 
 use crate::erlang::node_0;
 use crate::runtime::context::*;
+use crate::runtime::distribution::{self, Monitored};
 use crate::runtime::scheduler::SchedulerDependentAlloc;
 use crate::runtime::{process, registry};
 
@@ -40,7 +41,9 @@ fn monitor_process_identifier(
     match process_identifier.decode()? {
         TypedTerm::Atom(atom) => monitor_process_registered_name(process, process_identifier, atom),
         TypedTerm::Pid(pid) => monitor_process_pid(process, process_identifier, pid),
-        TypedTerm::ExternalPid(_) => unimplemented!(),
+        TypedTerm::ExternalPid(external_pid) => {
+            monitor_process_external_pid(process, process_identifier, external_pid.as_ref().clone())
+        }
         TypedTerm::Tuple(tuple) => monitor_process_tuple(process, process_identifier, &tuple),
         _ => Err(TypeError)
             .context(PROCESS_IDENTIFIER_CONTEXT)
@@ -72,6 +75,56 @@ fn monitor_process_pid(
     }
 }
 
+fn monitor_process_external_pid(
+    process: &Process,
+    process_identifier: Term,
+    external_pid: ExternalPid,
+) -> exception::Result<Term> {
+    let node = external_pid.arc_node().name();
+
+    monitor_process_remote(
+        process,
+        process_identifier,
+        node,
+        Monitored::Pid(external_pid),
+    )
+}
+
+/// Monitors a process on another `node`.  If `node` cannot be connected to, the `'DOWN'` message
+/// is sent immediately with `noconnection`.
+fn monitor_process_remote(
+    process: &Process,
+    process_identifier: Term,
+    node: Atom,
+    monitored: Monitored,
+) -> exception::Result<Term> {
+    match distribution::connect(node) {
+        Ok(connection) => {
+            let reference = process.next_reference()?;
+            let reference_reference: Boxed<Reference> = reference.try_into().unwrap();
+            connection.monitor(
+                process.pid(),
+                reference_reference.as_ref().clone(),
+                monitored,
+            );
+
+            Ok(reference)
+        }
+        Err(_) => {
+            let monitor_reference = process.next_reference()?;
+            let noconnection_message = down_message(
+                process,
+                monitor_reference,
+                process_identifier,
+                atom!("noconnection"),
+            )?;
+            process.send_from_self(noconnection_message);
+
+            Ok(monitor_reference)
+        }
+    }
+}
+
 fn monitor_process_registered_name(
     process: &Process,
     process_identifier: Term,
@@ -107,7 +160,7 @@ const PROCESS_IDENTIFIER_CONTEXT: &str =
 
 fn monitor_process_tuple(
     process: &Process,
-    _process_identifier: Term,
+    process_identifier: Term,
     tuple: &Tuple,
 ) -> exception::Result<Term> {
     if tuple.len() == 2 {
@@ -119,13 +172,14 @@ fn monitor_process_tuple(
         if node == node_0::result() {
             monitor_process_registered_name(process, registered_name, registered_name_atom)
         } else {
-            let _: Atom = term_try_into_atom!(node)?;
-
-            unimplemented!(
-                "node ({:?}) is not the local node ({:?})",
-                node,
-                node_0::result()
-            );
+            let node_atom: Atom = term_try_into_atom!(node)?;
+
+            monitor_process_remote(
+                process,
+                process_identifier,
+                node_atom,
+                Monitored::Name(registered_name_atom),
+            )
         }
     } else {
         Err(anyhow!(PROCESS_IDENTIFIER_CONTEXT).into())
@@ -1,27 +1,87 @@
 #[cfg(all(not(target_arch = "wasm32"), test))]
 mod test;
 
+use std::convert::TryInto;
+
 use anyhow::*;
 
-use liblumen_alloc::erts::exception;
-use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::alloc::next_min_heap_size;
+use liblumen_alloc::erts::process::{Priority, Process, ProcessFlags, SavedCalls};
 use liblumen_alloc::erts::term::prelude::*;
 
+use crate::erlang::process_info::max_heap_size_map;
 use crate::runtime::context::*;
+use crate::runtime::process::spawn::options::MessageQueueData;
+use crate::runtime::scheduler::Scheduled;
 
 #[native_implemented::function(erlang:process_flag/2)]
 pub fn result(process: &Process, flag: Term, value: Term) -> exception::Result<Term> {
     let flag_atom = term_try_into_atom!(flag)?;
 
     match flag_atom.name() {
-        "error_handler" => unimplemented!(),
-        "max_heap_size" => unimplemented!(),
-        "message_queue_data" => unimplemented!(),
-        "min_bin_vheap_size" => unimplemented!(),
-        "min_heap_size" => unimplemented!(),
-        "priority" => unimplemented!(),
-        "save_calls" => unimplemented!(),
-        "sensitive" => unimplemented!(),
+        "error_handler" => {
+            let module = term_try_into_atom("error_handler value", value)?;
+
+            process
+                .set_error_handler(module)
+                .encode()
+                .map_err(From::from)
+        }
+        "max_heap_size" => max_heap_size(process, value).map_err(From::from),
+        "message_queue_data" => {
+            let message_queue_data: MessageQueueData = value.try_into()?;
+            let old_off_heap = process.set_message_queue_off_heap(message_queue_data.is_off_heap());
+
+            Atom::from(MessageQueueData::from_off_heap(old_off_heap))
+                .encode()
+                .map_err(From::from)
+        }
+        "min_bin_vheap_size" => {
+            let min_bin_vheap_size = term_try_into_min_heap_size("min_bin_vheap_size value", value)?;
+            let old_min_bin_vheap_size = process.set_min_vheap_size(min_bin_vheap_size);
+
+            process
+                .integer(old_min_bin_vheap_size)
+                .map_err(From::from)
+        }
+        "min_heap_size" => {
+            let min_heap_size = term_try_into_min_heap_size("min_heap_size value", value)?;
+            let old_min_heap_size = process.set_min_heap_size(min_heap_size);
+
+            process.integer(old_min_heap_size).map_err(From::from)
+        }
+        "priority" => {
+            let priority: Priority = value.try_into()?;
+            let old_priority = process
+                .scheduler()
+                .unwrap()
+                .change_priority(process, priority);
+
+            Atom::from(old_priority).encode().map_err(From::from)
+        }
+        "save_calls" => {
+            let capacity: usize = value
+                .try_into()
+                .ok()
+                .filter(|capacity| *capacity <= SavedCalls::MAX_CAPACITY)
+                .with_context(|| {
+                    format!(
+                        "save_calls value ({}) is not an integer between 0 and {}",
+                        value,
+                        SavedCalls::MAX_CAPACITY
+                    )
+                })?;
+            let old_capacity = process.set_saved_calls_capacity(capacity);
+
+            process.integer(old_capacity).map_err(From::from)
+        }
+        "sensitive" => {
+            let value_bool: bool = term_try_into_bool("sensitive value", value)?;
+
+            Ok(process.sensitive(value_bool).into())
+        }
         "trap_exit" => {
             let value_bool: bool = term_try_into_bool("trap_exit value", value)?;
 
@@ -30,3 +90,75 @@ pub fn result(process: &Process, flag: Term, value: Term) -> exception::Result<T
         name => Err(TryAtomFromTermError(name)).context("supported flags are error_handler, max_heap_size, message_queue_data, min_bin_vheap_size, min_heap_size, priority, save_calls, sensitive, and trap_exit").map_err(From::from),
     }
 }
+
+// Private
+
+/// `max_heap_size` can be only the `size` or a map with `size`, `kill` and `error_logger`.  Keys
+/// missing from the map keep their current value.
+fn max_heap_size(process: &Process, value: Term) -> InternalResult<Term> {
+    let (size, kill, error_logger) = match value.decode()? {
+        TypedTerm::Map(map) => {
+            let size = match map.get(atom!("size")) {
+                Some(size) => term_try_into_words("max_heap_size size", size)?,
+                None => process.max_heap_size(),
+            };
+            let kill = match map.get(atom!("kill")) {
+                Some(kill) => term_try_into_bool("max_heap_size kill", kill)?,
+                None => process.are_flags_set(ProcessFlags::MaxHeapSizeKill),
+            };
+            let error_logger = match map.get(atom!("error_logger")) {
+                Some(error_logger) => {
+                    term_try_into_bool("max_heap_size error_logger", error_logger)?
+                }
+                None => process.are_flags_set(ProcessFlags::MaxHeapSizeErrorLogger),
+            };
+
+            (size, kill, error_logger)
+        }
+        _ => {
+            let size = term_try_into_words("max_heap_size value", value)?;
+
+            (
+                size,
+                process.are_flags_set(ProcessFlags::MaxHeapSizeKill),
+                process.are_flags_set(ProcessFlags::MaxHeapSizeErrorLogger),
+            )
+        }
+    };
+
+    if 0 < size && size < process.min_heap_size() {
+        return Err(anyhow!(
+            "max_heap_size size ({}) is less than min_heap_size ({})",
+            size,
+            process.min_heap_size()
+        )
+        .into());
+    }
+
+    let old_max_heap_size = max_heap_size_map(process, process)?;
+
+    process.set_max_heap_size(size);
+    set_flag(process, ProcessFlags::MaxHeapSizeKill, kill);
+    set_flag(process, ProcessFlags::MaxHeapSizeErrorLogger, error_logger);
+
+    Ok(old_max_heap_size)
+}
+
+fn set_flag(process: &Process, flag: ProcessFlags, value: bool) {
+    if value {
+        process.set_flags(flag);
+    } else {
+        process.clear_flags(flag);
+    }
+}
+
+/// Minimum heap sizes are rounded up to the next heap size the allocator uses, as BEAM does
+fn term_try_into_min_heap_size(name: &str, value: Term) -> anyhow::Result<usize> {
+    term_try_into_words(name, value).map(next_min_heap_size)
+}
+
+fn term_try_into_words(name: &str, value: Term) -> anyhow::Result<usize> {
+    value
+        .try_into()
+        .with_context(|| term_is_not_non_negative_integer(name, value))
+}
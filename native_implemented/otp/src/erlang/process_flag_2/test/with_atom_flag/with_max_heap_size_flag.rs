@@ -0,0 +1,60 @@
+use super::*;
+
+use liblumen_alloc::erts::process::{Process, ProcessFlags};
+
+#[test]
+fn with_size_less_than_min_heap_size_errors_badarg() {
+    let arc_process = test::process::default();
+    let size = arc_process.min_heap_size() - 1;
+
+    assert_badarg!(
+        result(&arc_process, flag(), arc_process.integer(size).unwrap()),
+        format!(
+            "max_heap_size size ({}) is less than min_heap_size ({})",
+            size,
+            arc_process.min_heap_size()
+        )
+    );
+}
+
+#[test]
+fn with_size_returns_old_value_map() {
+    let arc_process = test::process::default();
+    let size = arc_process.min_heap_size() * 2;
+
+    assert_eq!(
+        result(&arc_process, flag(), arc_process.integer(size).unwrap()),
+        Ok(max_heap_size_map(&arc_process, 0, true, true))
+    );
+    assert_eq!(arc_process.max_heap_size(), size);
+}
+
+#[test]
+fn with_map_only_changes_given_keys() {
+    let arc_process = test::process::default();
+    let value = arc_process
+        .map_from_slice(&[(Atom::str_to_term("kill"), false.into())])
+        .unwrap();
+
+    assert_eq!(
+        result(&arc_process, flag(), value),
+        Ok(max_heap_size_map(&arc_process, 0, true, true))
+    );
+    assert!(!arc_process.are_flags_set(ProcessFlags::MaxHeapSizeKill));
+    assert!(arc_process.are_flags_set(ProcessFlags::MaxHeapSizeErrorLogger));
+    assert_eq!(arc_process.max_heap_size(), 0);
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("max_heap_size")
+}
+
+fn max_heap_size_map(process: &Process, size: usize, kill: bool, error_logger: bool) -> Term {
+    process
+        .map_from_slice(&[
+            (Atom::str_to_term("error_logger"), error_logger.into()),
+            (Atom::str_to_term("kill"), kill.into()),
+            (Atom::str_to_term("size"), process.integer(size).unwrap()),
+        ])
+        .unwrap()
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+#[test]
+fn without_on_heap_or_off_heap_value_errors_badarg() {
+    let arc_process = test::process::default();
+
+    assert_badarg!(
+        result(&arc_process, flag(), Atom::str_to_term("in_heap")),
+        "supported message_queue_data are off_heap or on_heap"
+    );
+}
+
+#[test]
+fn with_off_heap_value_returns_old_value_on_heap() {
+    let arc_process = test::process::default();
+    let off_heap = Atom::str_to_term("off_heap");
+    let on_heap = Atom::str_to_term("on_heap");
+
+    assert_eq!(result(&arc_process, flag(), off_heap), Ok(on_heap));
+    assert!(arc_process.is_message_queue_off_heap());
+
+    assert_eq!(result(&arc_process, flag(), on_heap), Ok(off_heap));
+    assert!(!arc_process.is_message_queue_off_heap());
+}
+
+#[test]
+fn with_off_heap_value_queues_messages_until_received() {
+    let arc_process = test::process::default();
+
+    assert_eq!(
+        result(&arc_process, flag(), Atom::str_to_term("off_heap")),
+        Ok(Atom::str_to_term("on_heap"))
+    );
+
+    let message = Atom::str_to_term("message");
+    arc_process.send_from_other(message).unwrap();
+
+    assert_eq!(arc_process.message_queue_len(), 1);
+    assert!(has_no_message(&arc_process));
+
+    arc_process.take_off_heap_messages();
+
+    assert_eq!(arc_process.message_queue_len(), 1);
+    assert!(has_heap_message(&arc_process, message));
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("message_queue_data")
+}
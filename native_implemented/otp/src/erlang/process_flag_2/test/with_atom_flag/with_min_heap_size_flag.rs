@@ -0,0 +1,42 @@
+use super::*;
+
+use liblumen_alloc::erts::process::alloc::next_heap_size;
+
+#[test]
+fn without_non_negative_integer_value_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_non_negative_integer(arc_process.clone()),
+            )
+        },
+        |(arc_process, value)| {
+            prop_assert_badarg!(
+                result(&arc_process, flag(), value),
+                format!(
+                    "min_heap_size value ({}) is not a non-negative integer",
+                    value
+                )
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_non_negative_integer_value_returns_old_value_and_rounds_up_to_heap_size() {
+    let arc_process = test::process::default();
+    let old_min_heap_size = arc_process.min_heap_size();
+
+    assert_eq!(
+        result(&arc_process, flag(), arc_process.integer(1_000).unwrap()),
+        Ok(arc_process.integer(old_min_heap_size).unwrap())
+    );
+    assert_eq!(arc_process.min_heap_size(), next_heap_size(1_000));
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("min_heap_size")
+}
@@ -0,0 +1,74 @@
+use super::*;
+
+use liblumen_alloc::erts::process::Priority;
+
+use crate::runtime::scheduler::Scheduled;
+
+#[test]
+fn without_priority_value_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::atom().prop_filter("Cannot be a priority", |atom| {
+                    let atom_atom: Atom = (*atom).try_into().unwrap();
+
+                    match atom_atom.name() {
+                        "low" | "normal" | "high" | "max" => false,
+                        _ => true,
+                    }
+                }),
+            )
+        },
+        |(arc_process, value)| {
+            prop_assert_badarg!(
+                result(&arc_process, flag(), value),
+                "supported priorities are low, normal, high, or max"
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_priority_value_returns_old_priority() {
+    let arc_process = test::process::default();
+
+    assert_eq!(
+        result(&arc_process, flag(), Atom::str_to_term("high")),
+        Ok(Atom::str_to_term("normal"))
+    );
+    assert_eq!(
+        result(&arc_process, flag(), Atom::str_to_term("low")),
+        Ok(Atom::str_to_term("high"))
+    );
+    assert_eq!(*arc_process.priority.read(), Priority::Low);
+}
+
+#[test]
+fn with_max_value_moves_process_to_max_run_queue() {
+    let arc_process = test::process::default();
+    let scheduler = arc_process.scheduler().unwrap();
+
+    let normal_run_queue_len_before = scheduler.run_queue_len(Priority::Normal);
+    let max_run_queue_len_before = scheduler.run_queue_len(Priority::Max);
+
+    assert_eq!(
+        result(&arc_process, flag(), Atom::str_to_term("max")),
+        Ok(Atom::str_to_term("normal"))
+    );
+
+    assert_eq!(
+        scheduler.run_queue_len(Priority::Normal),
+        normal_run_queue_len_before - 1
+    );
+    assert_eq!(
+        scheduler.run_queue_len(Priority::Max),
+        max_run_queue_len_before + 1
+    );
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("priority")
+}
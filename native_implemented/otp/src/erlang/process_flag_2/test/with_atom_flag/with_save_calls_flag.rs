@@ -0,0 +1,72 @@
+use super::*;
+
+use liblumen_alloc::ModuleFunctionArity;
+
+#[test]
+fn without_integer_between_0_and_10000_value_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_integer(arc_process.clone()),
+            )
+        },
+        |(arc_process, value)| {
+            prop_assert_badarg!(
+                result(&arc_process, flag(), value),
+                format!(
+                    "save_calls value ({}) is not an integer between 0 and 10000",
+                    value
+                )
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_integer_value_returns_old_value() {
+    let arc_process = test::process::default();
+
+    assert_eq!(
+        result(&arc_process, flag(), arc_process.integer(2).unwrap()),
+        Ok(arc_process.integer(0).unwrap())
+    );
+    assert_eq!(
+        result(&arc_process, flag(), arc_process.integer(0).unwrap()),
+        Ok(arc_process.integer(2).unwrap())
+    );
+}
+
+#[test]
+fn with_positive_value_saves_most_recent_calls() {
+    let arc_process = test::process::default();
+
+    assert_eq!(
+        result(&arc_process, flag(), arc_process.integer(2).unwrap()),
+        Ok(arc_process.integer(0).unwrap())
+    );
+
+    let module = Atom::from_str("module");
+    let module_function_arity_vec: Vec<ModuleFunctionArity> = (0..3)
+        .map(|arity| ModuleFunctionArity {
+            module,
+            function: Atom::from_str("function"),
+            arity,
+        })
+        .collect();
+
+    for module_function_arity in &module_function_arity_vec {
+        arc_process.save_call(*module_function_arity);
+    }
+
+    assert_eq!(
+        arc_process.saved_calls(),
+        Some(module_function_arity_vec[1..].to_vec())
+    );
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("save_calls")
+}
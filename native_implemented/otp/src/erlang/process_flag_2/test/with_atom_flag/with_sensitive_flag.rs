@@ -0,0 +1,54 @@
+use super::*;
+
+#[test]
+fn without_boolean_value_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_boolean(arc_process.clone()),
+            )
+        },
+        |(arc_process, value)| {
+            prop_assert_is_not_boolean!(
+                result(&arc_process, flag(), value),
+                "sensitive value",
+                value
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_true_value_then_boolean_value_returns_old_value_true() {
+    TestRunner::new(Config::with_source_file(file!()))
+        .run(&strategy::term::is_boolean(), |value| {
+            let arc_process = test::process::default();
+
+            let old_value = true.into();
+            prop_assert_eq!(result(&arc_process, flag(), old_value), Ok(false.into()));
+
+            prop_assert_eq!(result(&arc_process, flag(), value), Ok(old_value));
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn with_true_value_does_not_save_calls() {
+    let arc_process = test::process::default();
+
+    assert_eq!(arc_process.set_saved_calls_capacity(1), 0);
+    assert_eq!(result(&arc_process, flag(), true.into()), Ok(false.into()));
+
+    arc_process.save_call(arc_process.initial_module_function_arity);
+
+    assert_eq!(arc_process.saved_calls(), Some(vec![]));
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("sensitive")
+}
@@ -0,0 +1,514 @@
+//! Items shared by `erlang:process_info/1` and `erlang:process_info/2`
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::borrow::CloneToProcess;
+use liblumen_alloc::erts::exception::{AllocResult, InternalResult};
+use liblumen_alloc::erts::process::alloc::{Heap, StackPrimitives};
+use liblumen_alloc::erts::process::{Monitor, Process, ProcessFlags, Status};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::ModuleFunctionArity;
+
+use crate::erlang::node_0;
+use crate::runtime::process::spawn::options::MessageQueueData;
+use crate::runtime::registry::pid_to_process;
+use crate::runtime::trace;
+
+pub const SUPPORTED_ITEMS_CONTEXT: &str =
+    "supported items are backtrace, binary, current_function, \
+     current_location, current_stacktrace, dictionary, error_handler, \
+     garbage_collection, garbage_collection_info, group_leader, heap_size, \
+     initial_call, links, last_calls, memory, message_queue_len, messages, \
+     min_heap_size, min_bin_vheap_size, monitored_by, monitors, \
+     message_queue_data, priority, reductions, registered_name, \
+     stack_size, status, total_heap_size, trace, trap_exit";
+
+/// The items returned by `erlang:process_info/1`, in the order BEAM returns them.
+/// `registered_name` is only included when the process is registered.  Unlike BEAM, `suspending`
+/// is left out, as suspending processes is not supported.
+const DEFAULT_ITEMS: &[&str] = &[
+    "current_function",
+    "initial_call",
+    "status",
+    "message_queue_len",
+    "links",
+    "dictionary",
+    "trap_exit",
+    "error_handler",
+    "priority",
+    "group_leader",
+    "total_heap_size",
+    "heap_size",
+    "stack_size",
+    "reductions",
+    "garbage_collection",
+];
+
+/// `process_info` only works on processes on this node.  Unlike most BIFs that take a pid, an
+/// external pid is not forwarded to the remote node, so it gets its own error message.
+pub fn term_try_into_local_pid(pid: Term) -> anyhow::Result<Pid> {
+    if pid.is_boxed_remote_pid() {
+        Err(anyhow!(
+            "pid ({}) is not a local pid; process_info only works on processes on this node",
+            pid
+        ))
+    } else {
+        term_try_into_local_pid!(pid)
+    }
+}
+
+/// Calls `f` with `process` if `pid` is `process`'s pid, otherwise with the alive local process
+/// with `pid`.  Returns `None` when there is no such process.
+pub fn with_pid<F>(process: &Process, pid: Pid, f: F) -> InternalResult<Option<Term>>
+where
+    F: FnOnce(&Process) -> InternalResult<Term>,
+{
+    if process.pid() == pid {
+        f(process).map(Some)
+    } else {
+        match pid_to_process(&pid) {
+            Some(pid_arc_process) if !pid_arc_process.is_exiting() => f(&pid_arc_process).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// `erlang:process_info/1` items for `target`, allocated on `process`.
+pub fn default_items(process: &Process, target: &Process) -> InternalResult<Term> {
+    let mut item_value_vec = Vec::with_capacity(DEFAULT_ITEMS.len() + 1);
+
+    if target.registered_name.read().is_some() {
+        item_value_vec.push(item_value(
+            process,
+            target,
+            Atom::from_str("registered_name"),
+        )?);
+    }
+
+    for name in DEFAULT_ITEMS {
+        item_value_vec.push(item_value(process, target, Atom::from_str(name))?);
+    }
+
+    process.list_from_slice(&item_value_vec).map_err(From::from)
+}
+
+/// `{item, value}` for `target`, allocated on `process`.
+///
+/// `registered_name` for an unregistered process is `{registered_name, []}`, as it is when
+/// `item` is in an item list.  Callers that take a single item return `[]` instead.
+pub fn item_value(process: &Process, target: &Process, item: Atom) -> InternalResult<Term> {
+    let value = value(process, target, item)?;
+    let item_term = item.encode()?;
+
+    process
+        .tuple_from_slice(&[item_term, value])
+        .map_err(From::from)
+}
+
+/// `{item, value}` for each item in `item_list`.
+pub fn item_list_values(
+    process: &Process,
+    target: &Process,
+    item_list: Term,
+) -> InternalResult<Term> {
+    let mut item_value_vec = Vec::new();
+    let mut item_list_term = item_list;
+
+    loop {
+        match item_list_term.decode()? {
+            TypedTerm::Nil => break,
+            TypedTerm::List(cons) => {
+                let item = cons.head;
+                let item_atom: Atom = term_try_into_atom!(item)?;
+                item_value_vec.push(item_value(process, target, item_atom)?);
+
+                item_list_term = cons.tail;
+            }
+            _ => {
+                return Err(ImproperListError)
+                    .with_context(|| format!("item_list ({}) is not a proper list", item_list))
+                    .map_err(From::from)
+            }
+        }
+    }
+
+    process.list_from_slice(&item_value_vec).map_err(From::from)
+}
+
+/// `#{error_logger => boolean(), kill => boolean(), size => words}` for `target`, allocated on
+/// `process`.
+pub fn max_heap_size_map(process: &Process, target: &Process) -> InternalResult<Term> {
+    process
+        .map_from_slice(&[
+            (
+                atom!("error_logger"),
+                target
+                    .are_flags_set(ProcessFlags::MaxHeapSizeErrorLogger)
+                    .into(),
+            ),
+            (
+                atom!("kill"),
+                target.are_flags_set(ProcessFlags::MaxHeapSizeKill).into(),
+            ),
+            (atom!("size"), process.integer(target.max_heap_size())?),
+        ])
+        .map_err(From::from)
+}
+
+// Private
+
+fn value(process: &Process, target: &Process, item: Atom) -> InternalResult<Term> {
+    match item.name() {
+        "backtrace" => backtrace(process, target),
+        "binary" => binary(process, target),
+        "current_function" => current_function(process, target),
+        "current_location" => current_location(process, target),
+        "current_stacktrace" => current_stacktrace(process, target),
+        "dictionary" => dictionary(process, target),
+        "error_handler" => target.error_handler().encode().map_err(From::from),
+        "garbage_collection" => garbage_collection(process, target),
+        "garbage_collection_info" => garbage_collection_info(process, target),
+        "group_leader" => Ok(target.get_group_leader_pid_term()),
+        "heap_size" => process.integer(target.heap_size()).map_err(From::from),
+        "initial_call" => {
+            module_function_arity_tuple(process, target.initial_module_function_arity)
+                .map_err(From::from)
+        }
+        "links" => links(process, target),
+        "last_calls" => last_calls(process, target),
+        "memory" => process.integer(target.memory()).map_err(From::from),
+        "message_queue_len" => process
+            .integer(target.message_queue_len())
+            .map_err(From::from),
+        "messages" => messages(process, target),
+        "min_heap_size" => process.integer(target.min_heap_size()).map_err(From::from),
+        "min_bin_vheap_size" => process.integer(target.min_vheap_size()).map_err(From::from),
+        "monitored_by" => monitored_by(process, target),
+        "monitors" => monitors(process, target),
+        "message_queue_data" => Atom::from(MessageQueueData::from_off_heap(
+            target.is_message_queue_off_heap(),
+        ))
+        .encode()
+        .map_err(From::from),
+        "priority" => Atom::from(*target.priority.read())
+            .encode()
+            .map_err(From::from),
+        "reductions" => process.integer(target.reductions()).map_err(From::from),
+        "registered_name" => match *target.registered_name.read() {
+            Some(registered_name) => registered_name.encode().map_err(From::from),
+            None => Ok(Term::NIL),
+        },
+        "stack_size" => process.integer(target.stack_used()).map_err(From::from),
+        "status" => Ok(status(target)),
+        "total_heap_size" => process
+            .integer(target.total_heap_size())
+            .map_err(From::from),
+        "trace" => process
+            .integer(trace::flags(target).otp_bits() as usize)
+            .map_err(From::from),
+        "trap_exit" => Ok(target.traps_exit().into()),
+        name => Err(TryAtomFromTermError(name))
+            .context(SUPPORTED_ITEMS_CONTEXT)
+            .map_err(From::from),
+    }
+}
+
+fn backtrace(process: &Process, target: &Process) -> InternalResult<Term> {
+    let backtrace = if target.is_sensitive() {
+        String::new()
+    } else {
+        format!("{}", target.stacktrace())
+    };
+
+    process.binary_from_str(&backtrace).map_err(From::from)
+}
+
+/// `[{id, size, ref_count}]` for each off-heap binary referenced by `target`
+fn binary(process: &Process, target: &Process) -> InternalResult<Term> {
+    let id_size_ref_count_vec: Vec<(usize, usize, usize)> = {
+        let heap = target.acquire_heap();
+
+        heap.virtual_binaries()
+            .map(|proc_bin| {
+                let id = unsafe { proc_bin.as_byte_ptr() } as usize;
+
+                (id, proc_bin.full_byte_len(), proc_bin.ref_count())
+            })
+            .collect()
+    };
+
+    let mut binary_vec = Vec::with_capacity(id_size_ref_count_vec.len());
+
+    for (id, size, ref_count) in id_size_ref_count_vec {
+        let id_term = process.integer(id)?;
+        let size_term = process.integer(size)?;
+        let ref_count_term = process.integer(ref_count)?;
+
+        binary_vec.push(process.tuple_from_slice(&[id_term, size_term, ref_count_term])?);
+    }
+
+    process.list_from_slice(&binary_vec).map_err(From::from)
+}
+
+fn clone_to_process(process: &Process, target: &Process, term: Term) -> AllocResult<Term> {
+    if process.pid() == target.pid() || term.is_immediate() {
+        Ok(term)
+    } else {
+        term.clone_to_heap(&mut process.acquire_heap())
+    }
+}
+
+fn current_function(process: &Process, target: &Process) -> InternalResult<Term> {
+    match target.current_module_function_arity() {
+        Some(module_function_arity) => {
+            module_function_arity_tuple(process, module_function_arity).map_err(From::from)
+        }
+        None => Ok(atom!("undefined")),
+    }
+}
+
+fn current_location(process: &Process, target: &Process) -> InternalResult<Term> {
+    match target.current_module_function_arity() {
+        Some(module_function_arity) => {
+            location_tuple(process, module_function_arity).map_err(From::from)
+        }
+        None => Ok(atom!("undefined")),
+    }
+}
+
+fn current_stacktrace(process: &Process, target: &Process) -> InternalResult<Term> {
+    if target.is_sensitive() {
+        return Ok(Term::NIL);
+    }
+
+    let stacktrace = target.stacktrace();
+    let mut location_vec = Vec::with_capacity(stacktrace.len());
+
+    for module_function_arity in stacktrace.iter() {
+        location_vec.push(location_tuple(process, *module_function_arity)?);
+    }
+
+    process.list_from_slice(&location_vec).map_err(From::from)
+}
+
+fn dictionary(process: &Process, target: &Process) -> InternalResult<Term> {
+    if target.is_sensitive() {
+        return Ok(Term::NIL);
+    }
+
+    let entries = target.dictionary_entries();
+    let mut entry_vec = Vec::with_capacity(entries.len());
+
+    for (key, value) in entries {
+        let process_key = clone_to_process(process, target, key)?;
+        let process_value = clone_to_process(process, target, value)?;
+
+        entry_vec.push(process.tuple_from_slice(&[process_key, process_value])?);
+    }
+
+    process.list_from_slice(&entry_vec).map_err(From::from)
+}
+
+fn garbage_collection(process: &Process, target: &Process) -> InternalResult<Term> {
+    let max_heap_size = max_heap_size_map(process, target)?;
+
+    let pairs = [
+        (atom!("max_heap_size"), max_heap_size),
+        (
+            atom!("min_bin_vheap_size"),
+            process.integer(target.min_vheap_size())?,
+        ),
+        (
+            atom!("min_heap_size"),
+            process.integer(target.min_heap_size())?,
+        ),
+        (
+            atom!("fullsweep_after"),
+            process.integer(target.fullsweep_after())?,
+        ),
+        (atom!("minor_gcs"), process.integer(target.minor_gcs())?),
+    ];
+
+    keyword_list(process, &pairs)
+}
+
+fn garbage_collection_info(process: &Process, target: &Process) -> InternalResult<Term> {
+    let (heap_block_size, heap_size, old_heap_block_size, old_heap_size, stack_size) = {
+        let heap = target.acquire_heap();
+
+        (
+            heap.heap_size(),
+            heap.heap_used(),
+            heap.old_heap_size(),
+            heap.old_heap_used(),
+            heap.stack_used(),
+        )
+    };
+
+    let pairs = [
+        (
+            atom!("old_heap_block_size"),
+            process.integer(old_heap_block_size)?,
+        ),
+        (atom!("heap_block_size"), process.integer(heap_block_size)?),
+        (atom!("mbuf_size"), process.integer(target.off_heap_size())?),
+        (atom!("stack_size"), process.integer(stack_size)?),
+        (atom!("old_heap_size"), process.integer(old_heap_size)?),
+        (atom!("heap_size"), process.integer(heap_size)?),
+    ];
+
+    keyword_list(process, &pairs)
+}
+
+fn keyword_list(process: &Process, pairs: &[(Term, Term)]) -> InternalResult<Term> {
+    let mut tuple_vec = Vec::with_capacity(pairs.len());
+
+    for (key, value) in pairs {
+        tuple_vec.push(process.tuple_from_slice(&[*key, *value])?);
+    }
+
+    process.list_from_slice(&tuple_vec).map_err(From::from)
+}
+
+/// `false` when call saving is not active; otherwise, `[{module, function, arity}]` from the oldest
+/// to the most recent saved call
+fn last_calls(process: &Process, target: &Process) -> InternalResult<Term> {
+    match target.saved_calls() {
+        Some(saved_calls) => {
+            let mut call_vec = Vec::with_capacity(saved_calls.len());
+
+            for module_function_arity in saved_calls {
+                call_vec.push(module_function_arity_tuple(process, module_function_arity)?);
+            }
+
+            process.list_from_slice(&call_vec).map_err(From::from)
+        }
+        None => Ok(false.into()),
+    }
+}
+
+fn links(process: &Process, target: &Process) -> InternalResult<Term> {
+    let pid_vec: Vec<Term> = target
+        .linked_pid_set
+        .iter()
+        .map(|linked_pid| linked_pid.key().encode().unwrap())
+        .collect();
+
+    process.list_from_slice(&pid_vec).map_err(From::from)
+}
+
+fn location_tuple(
+    process: &Process,
+    module_function_arity: ModuleFunctionArity,
+) -> AllocResult<Term> {
+    // Source file and line are not tracked, so the location is always empty
+    process.tuple_from_slice(&[
+        module_function_arity.module.encode().unwrap(),
+        module_function_arity.function.encode().unwrap(),
+        process.integer(module_function_arity.arity)?,
+        Term::NIL,
+    ])
+}
+
+fn messages(process: &Process, target: &Process) -> InternalResult<Term> {
+    if target.is_sensitive() {
+        return Ok(Term::NIL);
+    }
+
+    target.take_off_heap_messages();
+
+    let data_vec: Vec<Term> = target
+        .mailbox
+        .lock()
+        .borrow()
+        .iter()
+        .map(|message| *message.data())
+        .collect();
+
+    let mut process_data_vec = Vec::with_capacity(data_vec.len());
+
+    for data in data_vec {
+        process_data_vec.push(data.clone_to_heap(&mut process.acquire_heap())?);
+    }
+
+    process
+        .list_from_slice(&process_data_vec)
+        .map_err(From::from)
+}
+
+fn module_function_arity_tuple(
+    process: &Process,
+    module_function_arity: ModuleFunctionArity,
+) -> AllocResult<Term> {
+    process.tuple_from_slice(&[
+        module_function_arity.module.encode().unwrap(),
+        module_function_arity.function.encode().unwrap(),
+        process.integer(module_function_arity.arity)?,
+    ])
+}
+
+fn monitored_by(process: &Process, target: &Process) -> InternalResult<Term> {
+    let pid_vec: Vec<Term> = target
+        .monitor_by_reference
+        .iter()
+        .map(|entry| entry.value().monitoring_pid().encode().unwrap())
+        .collect();
+
+    process.list_from_slice(&pid_vec).map_err(From::from)
+}
+
+fn monitors(process: &Process, target: &Process) -> InternalResult<Term> {
+    let reference_pid_vec: Vec<(Reference, Pid)> = target
+        .monitored_pid_by_reference
+        .iter()
+        .map(|entry| (entry.key().clone(), *entry.value()))
+        .collect();
+
+    let mut monitor_vec = Vec::with_capacity(reference_pid_vec.len());
+
+    for (reference, monitored_pid) in reference_pid_vec {
+        monitor_vec.push(monitor_tuple(process, &reference, monitored_pid)?);
+    }
+
+    process.list_from_slice(&monitor_vec).map_err(From::from)
+}
+
+/// `{process, Pid}` or, when monitoring by registered name, `{process, {Name, Node}}`
+fn monitor_tuple(
+    process: &Process,
+    reference: &Reference,
+    monitored_pid: Pid,
+) -> InternalResult<Term> {
+    let monitored_name = pid_to_process(&monitored_pid).and_then(|monitored_arc_process| {
+        monitored_arc_process
+            .monitor_by_reference
+            .get(reference)
+            .and_then(|monitor| match *monitor {
+                Monitor::Name { monitored_name, .. } => Some(monitored_name),
+                Monitor::Pid { .. } => None,
+            })
+    });
+
+    let identifier = match monitored_name {
+        Some(monitored_name) => {
+            process.tuple_from_slice(&[monitored_name.encode()?, node_0::result()])?
+        }
+        None => monitored_pid.encode()?,
+    };
+
+    process
+        .tuple_from_slice(&[atom!("process"), identifier])
+        .map_err(From::from)
+}
+
+fn status(target: &Process) -> Term {
+    let name = match *target.status.read() {
+        Status::Unrunnable | Status::Runnable => "runnable",
+        Status::Running => "running",
+        Status::Waiting => "waiting",
+        Status::RuntimeException(_) | Status::SystemException(_) => "exiting",
+    };
+
+    Atom::str_to_term(name)
+}
@@ -0,0 +1,18 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::process_info::{self, default_items, with_pid};
+
+#[native_implemented::function(erlang:process_info/1)]
+pub fn result(process: &Process, pid: Term) -> exception::Result<Term> {
+    let pid_pid = process_info::term_try_into_local_pid(pid)?;
+
+    with_pid(process, pid_pid, |target| default_items(process, target))
+        .map(|option| option.unwrap_or_else(|| atom!("undefined")))
+        .map_err(From::from)
+}
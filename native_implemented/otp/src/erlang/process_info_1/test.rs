@@ -0,0 +1,120 @@
+use std::convert::TryInto;
+
+use proptest::strategy::Just;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::registry;
+
+use crate::erlang::process_info_1::result;
+use crate::test;
+use crate::test::{registered_name, strategy, with_process_arc};
+
+#[test]
+fn without_pid_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_pid(arc_process.clone()),
+            )
+        },
+        |(arc_process, pid)| {
+            prop_assert_is_not_local_pid!(result(&arc_process, pid), pid);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_external_pid_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::pid::external(arc_process.clone()),
+            )
+        },
+        |(arc_process, pid)| {
+            prop_assert_badarg!(
+                result(&arc_process, pid),
+                format!("pid ({}) is not a local pid", pid)
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn without_process_returns_undefined() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            result(&arc_process, Pid::next_term()),
+            Ok(Atom::str_to_term("undefined"))
+        );
+    });
+}
+
+#[test]
+fn without_registered_name_returns_default_items() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+
+        assert_eq!(
+            items(result(&parent_arc_process, child_arc_process.pid_term()).unwrap()),
+            vec![
+                "current_function",
+                "initial_call",
+                "status",
+                "message_queue_len",
+                "links",
+                "dictionary",
+                "trap_exit",
+                "error_handler",
+                "priority",
+                "group_leader",
+                "total_heap_size",
+                "heap_size",
+                "stack_size",
+                "reductions",
+                "garbage_collection"
+            ]
+        );
+    });
+}
+
+#[test]
+fn with_registered_name_returns_registered_name_first() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+        let registered_name = registered_name();
+        let registered_name_atom: Atom = registered_name.try_into().unwrap();
+
+        assert!(registry::put_atom_to_process(
+            registered_name_atom,
+            child_arc_process.clone()
+        ));
+
+        let list = result(&parent_arc_process, child_arc_process.pid_term()).unwrap();
+        let cons: Boxed<Cons> = list.try_into().unwrap();
+        let first: Boxed<Tuple> = cons.head.try_into().unwrap();
+
+        assert_eq!(first[0], Atom::str_to_term("registered_name"));
+        assert_eq!(first[1], registered_name);
+    });
+}
+
+fn items(list: Term) -> Vec<&'static str> {
+    let cons: Boxed<Cons> = list.try_into().unwrap();
+
+    cons.into_iter()
+        .map(|result| {
+            let tuple: Boxed<Tuple> = result.unwrap().try_into().unwrap();
+            let item: Atom = tuple[0].try_into().unwrap();
+
+            item.name()
+        })
+        .collect()
+}
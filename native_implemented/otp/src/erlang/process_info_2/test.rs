@@ -14,12 +14,12 @@ use crate::test;
 use crate::test::{registered_name, strategy, with_process_arc};
 
 #[test]
-fn without_local_pid_errors_badarg() {
+fn without_pid_errors_badarg() {
     run!(
         |arc_process| {
             (
                 Just(arc_process.clone()),
-                strategy::term::is_not_local_pid(arc_process.clone()),
+                strategy::term::is_not_pid(arc_process.clone()),
             )
         },
         |(arc_process, pid)| {
@@ -31,3 +31,25 @@ fn without_local_pid_errors_badarg() {
         },
     );
 }
+
+#[test]
+fn with_external_pid_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::pid::external(arc_process.clone()),
+            )
+        },
+        |(arc_process, pid)| {
+            let item = Atom::str_to_term("registered_name");
+
+            prop_assert_badarg!(
+                result(&arc_process, pid, item),
+                format!("pid ({}) is not a local pid", pid)
+            );
+
+            Ok(())
+        },
+    );
+}
@@ -1,4 +1,13 @@
+mod with_dictionary;
+mod with_initial_call;
+mod with_item_list;
+mod with_last_calls;
+mod with_links;
+mod with_message_queue_len;
+mod with_messages;
+mod with_priority;
 mod with_registered_name;
+mod with_trap_exit;
 
 use super::*;
 
@@ -10,14 +19,13 @@ fn without_supported_item_errors_badarg() {
                 let pid = arc_process.pid_term();
                 prop_assert_badarg!(
                     result(&arc_process, pid, item),
-                    "supported items are backtrace, binary, catchlevel, current_function, \
+                    "supported items are backtrace, binary, current_function, \
                      current_location, current_stacktrace, dictionary, error_handler, \
                      garbage_collection, garbage_collection_info, group_leader, heap_size, \
                      initial_call, links, last_calls, memory, message_queue_len, messages, \
                      min_heap_size, min_bin_vheap_size, monitored_by, monitors, \
                      message_queue_data, priority, reductions, registered_name, \
-                     sequential_trace_token, stack_size, status, suspending, \
-                     total_heap_size, trace, trap_exit"
+                     stack_size, status, total_heap_size, trace, trap_exit"
                 );
 
                 Ok(())
@@ -29,7 +37,36 @@ fn without_supported_item_errors_badarg() {
 fn unsupported_item_atom() -> BoxedStrategy<Term> {
     strategy::atom()
         .prop_filter("Item cannot be supported", |atom| match atom.name() {
-            "registered_name" => false,
+            "backtrace"
+            | "binary"
+            | "current_function"
+            | "current_location"
+            | "current_stacktrace"
+            | "dictionary"
+            | "error_handler"
+            | "garbage_collection"
+            | "garbage_collection_info"
+            | "group_leader"
+            | "heap_size"
+            | "initial_call"
+            | "links"
+            | "last_calls"
+            | "memory"
+            | "message_queue_len"
+            | "messages"
+            | "min_heap_size"
+            | "min_bin_vheap_size"
+            | "monitored_by"
+            | "monitors"
+            | "message_queue_data"
+            | "priority"
+            | "reductions"
+            | "registered_name"
+            | "stack_size"
+            | "status"
+            | "total_heap_size"
+            | "trace"
+            | "trap_exit" => false,
             _ => true,
         })
         .prop_map(|atom| atom.encode().unwrap())
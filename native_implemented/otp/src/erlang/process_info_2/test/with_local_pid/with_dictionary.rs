@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn with_self_returns_entries() {
+    with_process_arc(|arc_process| {
+        let key = Atom::str_to_term("key");
+        let value = Atom::str_to_term("value");
+        arc_process.put(key, value).unwrap();
+
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    arc_process
+                        .list_from_slice(&[arc_process.tuple_from_slice(&[key, value]).unwrap()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_other_copies_entries_to_process() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+
+        let key = Atom::str_to_term("key");
+        let value = child_arc_process.list_from_slice(&[key]).unwrap();
+        child_arc_process.put(key, value).unwrap();
+
+        let actual = result(&parent_arc_process, child_arc_process.pid_term(), item()).unwrap();
+        let actual_tuple: Boxed<Tuple> = actual.try_into().unwrap();
+        let entries: Boxed<Cons> = actual_tuple[1].try_into().unwrap();
+        let entry: Boxed<Tuple> = entries.head.try_into().unwrap();
+
+        assert_eq!(entry[0], key);
+        assert_eq!(entry[1], value);
+
+        let entry_value: Boxed<Cons> = entry[1].try_into().unwrap();
+        assert!(parent_arc_process.is_owner(entry_value.as_ptr()));
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("dictionary")
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn returns_module_function_arity_process_was_spawned_with() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+
+        assert_eq!(
+            result(&parent_arc_process, child_arc_process.pid_term(), item()),
+            Ok(parent_arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    parent_arc_process
+                        .tuple_from_slice(&[
+                            test::loop_0::module().encode().unwrap(),
+                            test::loop_0::function().encode().unwrap(),
+                            parent_arc_process.integer(0).unwrap()
+                        ])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("initial_call")
+}
@@ -0,0 +1,64 @@
+use super::*;
+
+#[test]
+fn without_proper_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        let item_list = arc_process
+            .improper_list_from_slice(&[Atom::str_to_term("trap_exit")], Atom::str_to_term("tail"))
+            .unwrap();
+
+        assert_badarg!(
+            result(&arc_process, arc_process.pid_term(), item_list),
+            format!("item_list ({}) is not a proper list", item_list)
+        );
+    });
+}
+
+#[test]
+fn with_empty_list_returns_empty_list() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), Term::NIL),
+            Ok(Term::NIL)
+        );
+    });
+}
+
+#[test]
+fn without_registered_returns_registered_name_with_empty_list_in_order() {
+    with_process_arc(|arc_process| {
+        let registered_name = Atom::str_to_term("registered_name");
+        let trap_exit = Atom::str_to_term("trap_exit");
+        let item_list = arc_process
+            .list_from_slice(&[registered_name, trap_exit])
+            .unwrap();
+
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item_list),
+            Ok(arc_process
+                .list_from_slice(&[
+                    arc_process
+                        .tuple_from_slice(&[registered_name, Term::NIL])
+                        .unwrap(),
+                    arc_process
+                        .tuple_from_slice(&[trap_exit, false.into()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn without_process_returns_undefined() {
+    with_process_arc(|arc_process| {
+        let item_list = arc_process
+            .list_from_slice(&[Atom::str_to_term("trap_exit")])
+            .unwrap();
+
+        assert_eq!(
+            result(&arc_process, Pid::next_term(), item_list),
+            Ok(Atom::str_to_term("undefined"))
+        );
+    });
+}
@@ -0,0 +1,42 @@
+use super::*;
+
+#[test]
+fn without_save_calls_returns_false() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), false.into()])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_save_calls_returns_saved_calls() {
+    with_process_arc(|arc_process| {
+        arc_process.set_saved_calls_capacity(1);
+
+        let module_function_arity = arc_process.initial_module_function_arity;
+        arc_process.save_call(module_function_arity);
+
+        let call = arc_process
+            .tuple_from_slice(&[
+                module_function_arity.module.encode().unwrap(),
+                module_function_arity.function.encode().unwrap(),
+                arc_process.integer(module_function_arity.arity).unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), arc_process.list_from_slice(&[call]).unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("last_calls")
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn returns_number_of_messages() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+
+        assert_eq!(
+            result(&parent_arc_process, child_arc_process.pid_term(), item()),
+            Ok(parent_arc_process
+                .tuple_from_slice(&[item(), parent_arc_process.integer(0).unwrap()])
+                .unwrap())
+        );
+
+        child_arc_process
+            .send_from_other(Atom::str_to_term("first"))
+            .unwrap();
+        child_arc_process
+            .send_from_other(Atom::str_to_term("second"))
+            .unwrap();
+
+        assert_eq!(
+            result(&parent_arc_process, child_arc_process.pid_term(), item()),
+            Ok(parent_arc_process
+                .tuple_from_slice(&[item(), parent_arc_process.integer(2).unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("message_queue_len")
+}
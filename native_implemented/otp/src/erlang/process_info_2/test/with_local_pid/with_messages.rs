@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn returns_messages_in_receive_order() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+
+        let first = Atom::str_to_term("first");
+        let second = child_arc_process.list_from_slice(&[first]).unwrap();
+        child_arc_process.send_from_other(first).unwrap();
+        child_arc_process.send_from_other(second).unwrap();
+
+        assert_eq!(
+            result(&parent_arc_process, child_arc_process.pid_term(), item()),
+            Ok(parent_arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    parent_arc_process
+                        .list_from_slice(&[
+                            first,
+                            parent_arc_process.list_from_slice(&[first]).unwrap()
+                        ])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+        // messages are not removed from the mailbox
+        assert_eq!(child_arc_process.message_queue_len(), 2);
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("messages")
+}
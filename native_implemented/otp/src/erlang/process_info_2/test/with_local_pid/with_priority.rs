@@ -0,0 +1,17 @@
+use super::*;
+
+#[test]
+fn returns_priority() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), Atom::str_to_term("normal")])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("priority")
+}
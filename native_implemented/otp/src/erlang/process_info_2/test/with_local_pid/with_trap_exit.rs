@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn without_trap_exit_returns_false() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), false.into()])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_trap_exit_returns_true() {
+    with_process_arc(|arc_process| {
+        arc_process.trap_exit(true);
+
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), true.into()])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("trap_exit")
+}
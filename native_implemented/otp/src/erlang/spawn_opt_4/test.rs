@@ -1,4 +1,5 @@
 mod with_empty_list_options;
+mod with_gc_options_in_options_list;
 mod with_link_in_options_list;
 
 use std::convert::TryInto;
@@ -45,7 +46,7 @@ fn without_proper_list_options_errors_badarg() {
 
 const SUPPORTED_OPTIONS: &str = "supported options are :link, :monitor, \
                                  {:fullsweep_after, generational_collections :: pos_integer()}, \
-                                 {:max_heap_size, words :: pos_integer()}, \
+                                 {:max_heap_size, words :: non_neg_integer() | %{size: words, kill: boolean(), error_logger: boolean()}}, \
                                  {:message_queue_data, :off_heap | :on_heap}, \
                                  {:min_bin_vheap_size, words :: pos_integer()}, \
                                  {:min_heap_size, words :: pos_integer()}, and \
@@ -0,0 +1,96 @@
+use super::*;
+
+use std::sync::Arc;
+
+use liblumen_alloc::erts::process::alloc::next_heap_size;
+use liblumen_alloc::erts::process::ProcessFlags;
+
+#[test]
+fn with_gc_options_sets_them_on_child() {
+    let parent_arc_process = test::process::init();
+    let max_heap_size = parent_arc_process
+        .map_from_slice(&[
+            (atom!("size"), parent_arc_process.integer(100_000).unwrap()),
+            (atom!("kill"), false.into()),
+        ])
+        .unwrap();
+    let options = parent_arc_process
+        .list_from_slice(&[
+            option(&parent_arc_process, "fullsweep_after", 10),
+            option(&parent_arc_process, "min_heap_size", 1_000),
+            option(&parent_arc_process, "min_bin_vheap_size", 50_000),
+            parent_arc_process
+                .tuple_from_slice(&[atom!("max_heap_size"), max_heap_size])
+                .unwrap(),
+        ])
+        .unwrap();
+
+    let child_arc_process = spawn(&parent_arc_process, options);
+
+    assert_eq!(child_arc_process.fullsweep_after(), 10);
+    assert_eq!(child_arc_process.min_heap_size(), next_heap_size(1_000));
+    assert_eq!(child_arc_process.min_vheap_size(), 50_000);
+    assert_eq!(child_arc_process.max_heap_size(), 100_000);
+    assert!(!child_arc_process.are_flags_set(ProcessFlags::MaxHeapSizeKill));
+    // Not in the map, so it keeps the default
+    assert!(child_arc_process.are_flags_set(ProcessFlags::MaxHeapSizeErrorLogger));
+}
+
+#[test]
+fn with_integer_max_heap_size_only_sets_size() {
+    let parent_arc_process = test::process::init();
+    let options = parent_arc_process
+        .list_from_slice(&[option(&parent_arc_process, "max_heap_size", 100_000)])
+        .unwrap();
+
+    let child_arc_process = spawn(&parent_arc_process, options);
+
+    assert_eq!(child_arc_process.max_heap_size(), 100_000);
+    assert!(child_arc_process.are_flags_set(ProcessFlags::MaxHeapSizeKill));
+    assert!(child_arc_process.are_flags_set(ProcessFlags::MaxHeapSizeErrorLogger));
+}
+
+#[test]
+fn with_max_heap_size_less_than_min_heap_size_errors_badarg() {
+    let parent_arc_process = test::process::init();
+    let options = parent_arc_process
+        .list_from_slice(&[
+            option(&parent_arc_process, "min_heap_size", 1_000),
+            option(&parent_arc_process, "max_heap_size", 500),
+        ])
+        .unwrap();
+
+    assert_badarg!(
+        result(
+            &parent_arc_process,
+            atom!("erlang"),
+            atom!("self"),
+            Term::NIL,
+            options
+        ),
+        format!(
+            "max_heap_size size (500) is less than min_heap_size ({})",
+            next_heap_size(1_000)
+        )
+    );
+}
+
+fn option(process: &Process, name: &str, value: usize) -> Term {
+    process
+        .tuple_from_slice(&[Atom::str_to_term(name), process.integer(value).unwrap()])
+        .unwrap()
+}
+
+fn spawn(parent_process: &Process, options: Term) -> Arc<Process> {
+    let child_pid = result(
+        parent_process,
+        atom!("erlang"),
+        atom!("self"),
+        Term::NIL,
+        options,
+    )
+    .unwrap();
+    let child_pid_pid: Pid = child_pid.try_into().unwrap();
+
+    pid_to_process(&child_pid_pid).unwrap()
+}
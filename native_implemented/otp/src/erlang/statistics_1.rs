@@ -0,0 +1,100 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::gc;
+use liblumen_alloc::erts::process::{self, Process};
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::registry;
+use crate::runtime::scheduler;
+use crate::runtime::time::{cpu, monotonic};
+
+const SUPPORTED_ITEMS_CONTEXT: &str = "supported items are context_switches, garbage_collection, reductions, run_queue, run_queue_lengths, runtime, and wall_clock";
+
+// The totals when the `SinceLastCall` of each item was last returned
+static LAST_REDUCTIONS: AtomicU64 = AtomicU64::new(0);
+static LAST_RUNTIME: AtomicU64 = AtomicU64::new(0);
+static LAST_WALL_CLOCK: AtomicU64 = AtomicU64::new(0);
+
+#[native_implemented::function(erlang:statistics/1)]
+pub fn result(process: &Process, item: Term) -> exception::Result<Term> {
+    let item_atom = term_try_into_atom!(item).context(SUPPORTED_ITEMS_CONTEXT)?;
+
+    match item_atom.name() {
+        "context_switches" => process
+            .tuple_from_slice(&[
+                process.integer(scheduler::context_switches())?,
+                process.integer(0)?,
+            ])
+            .map_err(From::from),
+        "garbage_collection" => {
+            let (collections, words_reclaimed) = gc::statistics();
+
+            process
+                .tuple_from_slice(&[
+                    process.integer(collections)?,
+                    process.integer(words_reclaimed)?,
+                    process.integer(0)?,
+                ])
+                .map_err(From::from)
+        }
+        "reductions" => {
+            let alive: u64 = registry::processes()
+                .iter()
+                .map(|arc_process| arc_process.reductions())
+                .sum();
+            // A process that exits between listing the processes and reading the dropped total
+            // is briefly counted by neither, so never report less than last time.
+            let total =
+                (process::dropped_reductions() + alive).max(LAST_REDUCTIONS.load(Ordering::SeqCst));
+
+            total_and_since_last(process, total, &LAST_REDUCTIONS)
+        }
+        "run_queue" => {
+            let run_queue_len: usize = scheduler::all()
+                .iter()
+                .map(|arc_scheduler| arc_scheduler.run_queues_len())
+                .sum();
+
+            process.integer(run_queue_len).map_err(From::from)
+        }
+        "run_queue_lengths" => {
+            let mut run_queue_len_vec = Vec::new();
+
+            for arc_scheduler in scheduler::all() {
+                run_queue_len_vec.push(process.integer(arc_scheduler.run_queues_len())?);
+            }
+
+            process
+                .list_from_slice(&run_queue_len_vec)
+                .map_err(From::from)
+        }
+        "runtime" => total_and_since_last(process, cpu::time_in_milliseconds(), &LAST_RUNTIME),
+        "wall_clock" => {
+            total_and_since_last(process, monotonic::time_in_milliseconds(), &LAST_WALL_CLOCK)
+        }
+        name => Err(TryAtomFromTermError(name))
+            .context(SUPPORTED_ITEMS_CONTEXT)
+            .map_err(From::from),
+    }
+}
+
+// Private
+
+/// `{Total, SinceLastCall}`
+fn total_and_since_last(
+    process: &Process,
+    total: u64,
+    last: &AtomicU64,
+) -> exception::Result<Term> {
+    let since_last = total.saturating_sub(last.swap(total, Ordering::SeqCst));
+
+    process
+        .tuple_from_slice(&[process.integer(total)?, process.integer(since_last)?])
+        .map_err(From::from)
+}
@@ -0,0 +1,71 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::statistics_1::result;
+use crate::test::with_process;
+
+#[test]
+fn without_supported_item_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Atom::str_to_term("unsupported")),
+            "supported items are context_switches, garbage_collection, reductions, run_queue, run_queue_lengths, runtime, and wall_clock"
+        );
+    });
+}
+
+#[test]
+fn with_wall_clock_returns_total_and_since_last_call() {
+    with_process(|process| {
+        let elements = tuple_elements(result(process, Atom::str_to_term("wall_clock")).unwrap());
+
+        assert_eq!(elements.len(), 2);
+
+        let total: u64 = elements[0].try_into().unwrap();
+        let since_last: u64 = elements[1].try_into().unwrap();
+
+        assert!(since_last <= total);
+    });
+}
+
+#[test]
+fn with_reductions_includes_process_reductions() {
+    with_process(|process| {
+        let elements = tuple_elements(result(process, Atom::str_to_term("reductions")).unwrap());
+
+        assert_eq!(elements.len(), 2);
+
+        let total: u64 = elements[0].try_into().unwrap();
+
+        assert!(process.reductions() <= total);
+    });
+}
+
+#[test]
+fn with_garbage_collection_counts_collection() {
+    with_process(|process| {
+        let garbage_collection = Atom::str_to_term("garbage_collection");
+        let collections_before = collections(result(process, garbage_collection).unwrap());
+
+        process.garbage_collect(0, &mut []).unwrap();
+
+        let collections_after = collections(result(process, garbage_collection).unwrap());
+
+        assert!(collections_before < collections_after);
+    });
+}
+
+fn collections(garbage_collection: Term) -> usize {
+    let elements = tuple_elements(garbage_collection);
+
+    assert_eq!(elements.len(), 3);
+
+    elements[0].try_into().unwrap()
+}
+
+fn tuple_elements(term: Term) -> Vec<Term> {
+    let tuple: Boxed<Tuple> = term.try_into().unwrap();
+
+    tuple.iter().copied().collect()
+}
@@ -0,0 +1,43 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::mem;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::atom::{atom_count, MAX_ATOMS};
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::{registry, scheduler};
+
+const SUPPORTED_ITEMS_CONTEXT: &str = "supported items are atom_count, atom_limit, otp_release, process_count, schedulers, schedulers_online, version, and wordsize";
+
+/// The OTP release whose distribution protocol and external term format are supported
+const OTP_RELEASE: &str = "23";
+
+#[native_implemented::function(erlang:system_info/1)]
+pub fn result(process: &Process, item: Term) -> exception::Result<Term> {
+    let item_atom = term_try_into_atom!(item).context(SUPPORTED_ITEMS_CONTEXT)?;
+
+    match item_atom.name() {
+        "atom_count" => process.integer(atom_count()).map_err(From::from),
+        "atom_limit" => process.integer(MAX_ATOMS).map_err(From::from),
+        "otp_release" => process.charlist_from_str(OTP_RELEASE).map_err(From::from),
+        "process_count" => process
+            .integer(registry::processes().len())
+            .map_err(From::from),
+        // All schedulers are online, as they are only started and stopped with the runtime
+        "schedulers" | "schedulers_online" => {
+            process.integer(scheduler::all().len()).map_err(From::from)
+        }
+        "version" => process
+            .charlist_from_str(env!("CARGO_PKG_VERSION"))
+            .map_err(From::from),
+        "wordsize" => process.integer(mem::size_of::<Term>()).map_err(From::from),
+        name => Err(TryAtomFromTermError(name))
+            .context(SUPPORTED_ITEMS_CONTEXT)
+            .map_err(From::from),
+    }
+}
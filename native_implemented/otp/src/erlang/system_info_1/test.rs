@@ -0,0 +1,63 @@
+use std::convert::TryInto;
+use std::mem;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::system_info_1::result;
+use crate::test::with_process;
+
+#[test]
+fn without_supported_item_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Atom::str_to_term("unsupported")),
+            "supported items are atom_count, atom_limit, otp_release, process_count, schedulers, schedulers_online, version, and wordsize"
+        );
+    });
+}
+
+#[test]
+fn with_atom_count_counts_new_atom() {
+    with_process(|process| {
+        let atom_count = Atom::str_to_term("atom_count");
+        let before: usize = result(process, atom_count).unwrap().try_into().unwrap();
+
+        Atom::str_to_term("system_info_1_with_atom_count_counts_new_atom");
+
+        let after: usize = result(process, atom_count).unwrap().try_into().unwrap();
+
+        assert!(before < after);
+    });
+}
+
+#[test]
+fn with_otp_release_returns_charlist() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, Atom::str_to_term("otp_release")),
+            Ok(process.charlist_from_str("23").unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_process_count_counts_process() {
+    with_process(|process| {
+        let process_count: usize = result(process, Atom::str_to_term("process_count"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        assert!(1 <= process_count);
+    });
+}
+
+#[test]
+fn with_wordsize_returns_bytes_per_term() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, Atom::str_to_term("wordsize")),
+            Ok(process.integer(mem::size_of::<Term>()).unwrap())
+        );
+    });
+}
@@ -0,0 +1,103 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::trace::{self, Target, TraceFlags};
+
+/// Only processes can be traced, and the only tracer option is `{tracer, Pid}`.  Returns the number
+/// of processes whose flags were changed.
+#[native_implemented::function(erlang:trace/3)]
+pub fn result(
+    process: &Process,
+    pid_port_spec: Term,
+    how: Term,
+    flag_list: Term,
+) -> exception::Result<Term> {
+    let target = term_try_into_target(pid_port_spec)?;
+    let how_bool = term_try_into_bool!(how)?;
+    let (flags, tracer) = term_try_into_flags_and_tracer(process, flag_list)?;
+
+    let count = trace::trace(target, how_bool, flags, tracer);
+
+    process.integer(count).map_err(From::from)
+}
+
+fn term_try_into_target(pid_port_spec: Term) -> exception::Result<Target> {
+    match pid_port_spec.decode()? {
+        TypedTerm::Atom(atom) => match atom.name() {
+            "all" | "processes" => Ok(Target::All),
+            "existing" | "existing_processes" => Ok(Target::Existing),
+            "new" | "new_processes" => Ok(Target::New),
+            _ => Err(anyhow!(
+                "pid_port_spec ({}) is not a pid, all, processes, existing, existing_processes, \
+                 new or new_processes",
+                pid_port_spec
+            )
+            .into()),
+        },
+        TypedTerm::Pid(pid) => Ok(Target::Pid(pid)),
+        _ => Err(TypeError)
+            .context(format!(
+                "pid_port_spec ({}) is not a local pid or atom",
+                pid_port_spec
+            ))
+            .map_err(From::from),
+    }
+}
+
+/// The tracer defaults to the calling process
+fn term_try_into_flags_and_tracer(
+    process: &Process,
+    flag_list: Term,
+) -> exception::Result<(TraceFlags, Pid)> {
+    let mut flags = TraceFlags::default();
+    let mut tracer = process.pid();
+
+    match flag_list.decode()? {
+        TypedTerm::Nil => (),
+        TypedTerm::List(cons) => {
+            for result in cons.into_iter() {
+                let flag = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("flag_list ({}) is improper", flag_list))?;
+
+                match flag.decode()? {
+                    TypedTerm::Atom(atom) => match TraceFlags::from_name(atom.name()) {
+                        Some(named_flags) => flags |= named_flags,
+                        None => {
+                            return Err(anyhow!(
+                                "flag ({}) is not all, call, procs, receive, return_to, send, \
+                                 set_on_spawn or {{tracer, Pid}}",
+                                flag
+                            )
+                            .into())
+                        }
+                    },
+                    TypedTerm::Tuple(tuple)
+                        if tuple.len() == 2 && tuple[0] == Atom::str_to_term("tracer") =>
+                    {
+                        let tracer_term = tuple[1];
+                        tracer = term_try_into_local_pid!(tracer_term)?;
+                    }
+                    _ => {
+                        return Err(TypeError)
+                            .context(format!("flag ({}) is not an atom or {{tracer, Pid}}", flag))
+                            .map_err(From::from)
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(TypeError)
+                .context(format!("flag_list ({}) is not a list", flag_list))
+                .map_err(From::from)
+        }
+    }
+
+    Ok((flags, tracer))
+}
@@ -0,0 +1,94 @@
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::{process_info_2, send_2};
+use crate::test;
+use crate::test::{has_message, with_process_arc};
+
+use super::result;
+
+#[test]
+fn without_list_flag_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        let flag_list = Atom::str_to_term("send");
+
+        assert_badarg!(
+            result(&arc_process, arc_process.pid_term(), true.into(), flag_list),
+            format!("flag_list ({}) is not a list", flag_list)
+        );
+    });
+}
+
+#[test]
+fn with_send_flag_sends_trace_message_to_tracer() {
+    with_process_arc(|arc_process| {
+        let tracer_arc_process = test::process::child(&arc_process);
+        let flag_list = arc_process
+            .list_from_slice(&[
+                Atom::str_to_term("send"),
+                arc_process
+                    .tuple_from_slice(&[Atom::str_to_term("tracer"), tracer_arc_process.pid_term()])
+                    .unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), true.into(), flag_list),
+            Ok(arc_process.integer(1).unwrap())
+        );
+
+        let message = Atom::str_to_term("message");
+
+        assert_eq!(
+            send_2::result(&arc_process, arc_process.pid_term(), message),
+            Ok(message)
+        );
+
+        assert!(has_message(
+            &tracer_arc_process,
+            arc_process
+                .tuple_from_slice(&[
+                    Atom::str_to_term("trace"),
+                    arc_process.pid_term(),
+                    Atom::str_to_term("send"),
+                    message,
+                    arc_process.pid_term()
+                ])
+                .unwrap()
+        ));
+    });
+}
+
+#[test]
+fn with_false_how_clears_flags() {
+    with_process_arc(|arc_process| {
+        let flag_list = arc_process
+            .list_from_slice(&[Atom::str_to_term("procs")])
+            .unwrap();
+
+        assert_eq!(
+            result(&arc_process, arc_process.pid_term(), true.into(), flag_list),
+            Ok(arc_process.integer(1).unwrap())
+        );
+        assert_ne!(trace(&arc_process), arc_process.integer(0).unwrap());
+
+        assert_eq!(
+            result(
+                &arc_process,
+                arc_process.pid_term(),
+                false.into(),
+                flag_list
+            ),
+            Ok(arc_process.integer(1).unwrap())
+        );
+        assert_eq!(trace(&arc_process), arc_process.integer(0).unwrap());
+    });
+}
+
+fn trace(arc_process: &Process) -> Term {
+    let item = Atom::str_to_term("trace");
+    let info = process_info_2::result(arc_process, arc_process.pid_term(), item).unwrap();
+    let tuple: Boxed<Tuple> = info.try_into().unwrap();
+
+    tuple[1]
+}
@@ -0,0 +1,10 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::trace_pattern_3;
+
+#[native_implemented::function(erlang:trace_pattern/2)]
+pub fn result(process: &Process, mfa: Term, match_spec: Term) -> exception::Result<Term> {
+    trace_pattern_3::result(process, mfa, match_spec, Term::NIL)
+}
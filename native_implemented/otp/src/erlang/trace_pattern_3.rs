@@ -0,0 +1,139 @@
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::error;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::term_try_into_arity;
+use crate::runtime::trace::match_spec::MatchSpec;
+use crate::runtime::trace::{self, FunctionPattern};
+
+/// Compiled functions are not distinguished as local or global, so `local` and `global` in
+/// `flag_list` are ignored, and neither `call_count` nor `call_time` are supported.  Returns the
+/// number of functions matching `mfa`.
+///
+/// Only calls run as frames are traced, so when natives are not run from frames, as in the minimal
+/// runtime, calls made by compiled code cannot be traced and this fails with `notsup`.
+#[native_implemented::function(erlang:trace_pattern/3)]
+pub fn result(
+    process: &Process,
+    mfa: Term,
+    match_spec: Term,
+    flag_list: Term,
+) -> exception::Result<Term> {
+    let pattern = term_try_into_function_pattern(mfa)?;
+    let match_spec = term_try_into_match_spec(match_spec)?;
+    check_flag_list(flag_list)?;
+
+    if process.current_module_function_arity().is_none() {
+        return Err(error!(
+            atom!("notsup"),
+            anyhow!("call tracing needs natives to be run from frames; calls made by compiled code are not traced").into()
+        )
+        .into());
+    }
+
+    let count = trace::trace_pattern(pattern, match_spec);
+
+    process.integer(count).map_err(From::from)
+}
+
+fn term_try_into_function_pattern(mfa: Term) -> exception::Result<FunctionPattern> {
+    let tuple: Boxed<Tuple> = mfa
+        .try_into()
+        .with_context(|| format!("mfa ({}) is not {{Module, Function, Arity}}", mfa))?;
+
+    if tuple.len() != 3 {
+        return Err(anyhow!("mfa ({}) is not {{Module, Function, Arity}}", mfa).into());
+    }
+
+    let module = term_try_into_atom_or_any("module", tuple[0])?;
+    let function = term_try_into_atom_or_any("function", tuple[1])?;
+    let arity = if is_any(tuple[2]) {
+        None
+    } else {
+        Some(term_try_into_arity(tuple[2])?)
+    };
+
+    // Like BEAM, a wildcard can only be followed by wildcards
+    if (module.is_none() && (function.is_some() || arity.is_some()))
+        || (function.is_none() && arity.is_some())
+    {
+        return Err(anyhow!(
+            "mfa ({}) has a '_' followed by a module, function or arity",
+            mfa
+        )
+        .into());
+    }
+
+    Ok(FunctionPattern {
+        module,
+        function,
+        arity,
+    })
+}
+
+fn term_try_into_atom_or_any(name: &str, value: Term) -> anyhow::Result<Option<Atom>> {
+    if is_any(value) {
+        Ok(None)
+    } else {
+        let atom: Atom = value
+            .try_into()
+            .with_context(|| format!("{} ({}) is not an atom or '_'", name, value))?;
+
+        Ok(Some(atom))
+    }
+}
+
+fn is_any(value: Term) -> bool {
+    value == Atom::str_to_term("_")
+}
+
+/// `None` stops tracing the calls, while `Some(None)` traces all calls
+fn term_try_into_match_spec(match_spec: Term) -> exception::Result<Option<Option<MatchSpec>>> {
+    match match_spec.decode()? {
+        TypedTerm::Atom(atom) if atom == "true" => Ok(Some(None)),
+        TypedTerm::Atom(atom) if atom == "false" => Ok(None),
+        TypedTerm::Nil => Ok(Some(None)),
+        TypedTerm::List(_) => {
+            let match_spec = MatchSpec::try_from_term(match_spec)?;
+
+            Ok(Some(Some(match_spec)))
+        }
+        _ => Err(anyhow!(
+            "match_spec ({}) is not true, false or a match specification",
+            match_spec
+        )
+        .into()),
+    }
+}
+
+fn check_flag_list(flag_list: Term) -> exception::Result<()> {
+    match flag_list.decode()? {
+        TypedTerm::Nil => Ok(()),
+        TypedTerm::List(cons) => {
+            for result in cons.into_iter() {
+                let flag = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("flag_list ({}) is improper", flag_list))?;
+
+                if flag != Atom::str_to_term("global") && flag != Atom::str_to_term("local") {
+                    return Err(anyhow!(
+                        "flag ({}) is not supported; supported flags are global and local",
+                        flag
+                    )
+                    .into());
+                }
+            }
+
+            Ok(())
+        }
+        _ => Err(TypeError)
+            .context(format!("flag_list ({}) is not a list", flag_list))
+            .map_err(From::from),
+    }
+}
@@ -7,7 +7,9 @@ use liblumen_alloc::erts::exception;
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::prelude::*;
 
+use crate::runtime::distribution;
 use crate::runtime::registry::pid_to_process;
+use crate::runtime::trace;
 
 #[native_implemented::function(erlang:unlink/1)]
 fn result(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
@@ -19,6 +21,7 @@ fn result(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
                 match pid_to_process(&pid) {
                     Some(pid_arc_process) => {
                         process.unlink(&pid_arc_process);
+                        trace::link(process, pid_or_port, false);
                     }
                     None => (),
                 }
@@ -27,7 +30,14 @@ fn result(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
             }
         }
         TypedTerm::Port(_) => unimplemented!(),
-        TypedTerm::ExternalPid(_) => unimplemented!(),
+        TypedTerm::ExternalPid(external_pid) => {
+            if let Some(connection) = distribution::connection(external_pid.arc_node().name()) {
+                connection.unlink(process.pid(), &external_pid);
+                trace::link(process, pid_or_port, false);
+            }
+
+            Ok(true.into())
+        }
         TypedTerm::ExternalPort(_) => unimplemented!(),
         _ => Err(TypeError)
             .context(format!(
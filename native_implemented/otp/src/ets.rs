@@ -0,0 +1,66 @@
+pub mod delete_1;
+pub mod delete_2;
+pub mod info_2;
+pub mod insert_2;
+pub mod lookup_2;
+pub mod match_object_2;
+mod match_spec;
+pub mod new_2;
+pub mod select_2;
+pub mod tab2list_1;
+pub mod update_counter_3;
+
+use std::sync::Arc;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::ets::{self, Table};
+
+fn module() -> Atom {
+    Atom::from_str("ets")
+}
+
+fn module_id() -> usize {
+    module().id()
+}
+
+/// The table `tab` if `process` can read it
+fn table_readable_by(process: &Process, tab: Term) -> exception::Result<Arc<Table>> {
+    let table = term_try_into_table(tab)?;
+
+    if table.can_read(process.pid()) {
+        Ok(table)
+    } else {
+        Err(anyhow!(
+            "table ({}) is private and not owned by {}",
+            tab,
+            process.pid()
+        )
+        .into())
+    }
+}
+
+/// The table `tab` if `process` can insert, update or delete objects in it
+fn table_writable_by(process: &Process, tab: Term) -> exception::Result<Arc<Table>> {
+    let table = term_try_into_table(tab)?;
+
+    if table.can_write(process.pid()) {
+        Ok(table)
+    } else {
+        Err(anyhow!(
+            "table ({}) is {} and not owned by {}",
+            tab,
+            Atom::from(table.access()),
+            process.pid()
+        )
+        .into())
+    }
+}
+
+fn term_try_into_table(tab: Term) -> anyhow::Result<Arc<Table>> {
+    ets::term_to_table(tab).with_context(|| format!("table ({}) does not exist", tab))
+}
@@ -0,0 +1,17 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::ets;
+
+/// Deletes the whole table `tab`.
+#[native_implemented::function(ets:delete/1)]
+pub fn result(process: &Process, tab: Term) -> exception::Result<Term> {
+    let table = super::table_writable_by(process, tab)?;
+    ets::delete(&table);
+
+    Ok(true.into())
+}
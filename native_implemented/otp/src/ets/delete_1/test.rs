@@ -0,0 +1,49 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::delete_1::result;
+use crate::ets::{info_2, new_2};
+use crate::test;
+
+#[test]
+fn without_table_errors_badarg() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert_eq!(result(&arc_process, tid), Ok(true.into()));
+    assert_badarg!(result(&arc_process, tid), "does not exist");
+}
+
+#[test]
+fn with_table_deletes_table() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert_eq!(result(&arc_process, tid), Ok(true.into()));
+    assert_eq!(
+        info_2::result(&arc_process, tid, atom!("size")),
+        Ok(atom!("undefined"))
+    );
+}
+
+#[test]
+fn with_named_table_frees_name() {
+    let arc_process = test::process::default();
+    let name = Atom::str_to_term("ets_delete_1_with_named_table_frees_name");
+    let options = arc_process
+        .list_from_slice(&[atom!("named_table")])
+        .unwrap();
+
+    assert_eq!(new_2::result(&arc_process, name, options), Ok(name));
+    assert_eq!(result(&arc_process, name), Ok(true.into()));
+    assert_eq!(new_2::result(&arc_process, name, options), Ok(name));
+}
+
+#[test]
+fn with_protected_table_not_owned_errors_badarg() {
+    let owner_arc_process = test::process::default();
+    let other_arc_process = test::process::default();
+    let tid = new_2::result(&owner_arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert_badarg!(result(&other_arc_process, tid), "is protected");
+}
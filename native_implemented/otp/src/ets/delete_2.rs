@@ -0,0 +1,15 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+/// Deletes all objects with `key` from `tab`.
+#[native_implemented::function(ets:delete/2)]
+pub fn result(process: &Process, tab: Term, key: Term) -> exception::Result<Term> {
+    let table = super::table_writable_by(process, tab)?;
+    table.data_mut().delete(key);
+
+    Ok(true.into())
+}
@@ -0,0 +1,41 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::delete_2::result;
+use crate::ets::{insert_2, lookup_2, new_2};
+use crate::test;
+
+#[test]
+fn with_key_deletes_all_objects_with_key() {
+    let arc_process = test::process::default();
+    let options = arc_process.list_from_slice(&[atom!("bag")]).unwrap();
+    let tid = new_2::result(&arc_process, atom!("table"), options).unwrap();
+    let objects = arc_process
+        .list_from_slice(&[
+            arc_process
+                .tuple_from_slice(&[atom!("key"), atom!("first")])
+                .unwrap(),
+            arc_process
+                .tuple_from_slice(&[atom!("key"), atom!("second")])
+                .unwrap(),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        insert_2::result(&arc_process, tid, objects),
+        Ok(true.into())
+    );
+    assert_eq!(result(&arc_process, tid, atom!("key")), Ok(true.into()));
+    assert_eq!(
+        lookup_2::result(&arc_process, tid, atom!("key")),
+        Ok(Term::NIL)
+    );
+}
+
+#[test]
+fn without_key_returns_true() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert_eq!(result(&arc_process, tid, atom!("key")), Ok(true.into()));
+}
@@ -0,0 +1,46 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use crate::runtime::context::*;
+use crate::runtime::ets;
+
+/// Returns `undefined` if `tab` does not exist.
+#[native_implemented::function(ets:info/2)]
+pub fn result(process: &Process, tab: Term, item: Term) -> exception::Result<Term> {
+    let item_atom = term_try_into_atom!(item)?;
+
+    let table = match ets::term_to_table(tab) {
+        Some(table) => table,
+        None => return Ok(atom!("undefined")),
+    };
+
+    match item_atom.name() {
+        "compressed" => Ok(table.is_compressed().into()),
+        "heir" => match table.heir() {
+            Some(heir) => Ok(heir.encode()?),
+            None => Ok(atom!("none")),
+        },
+        "id" => Ok(table.tid().clone_to_process(process)),
+        "keypos" => process.integer(table.keypos()).map_err(From::from),
+        "memory" => process.integer(table.memory()).map_err(From::from),
+        "name" => Ok(table.name().encode()?),
+        "named_table" => Ok(table.is_named().into()),
+        "owner" => Ok(table.owner().encode()?),
+        "protection" => Ok(Atom::from(table.access()).encode()?),
+        "read_concurrency" => Ok(table.read_concurrency().into()),
+        "size" => process.integer(table.data().len()).map_err(From::from),
+        "type" => Ok(Atom::from(table.r#type()).encode()?),
+        "write_concurrency" => Ok(table.write_concurrency().into()),
+        name => Err(TryAtomFromTermError(name))
+            .context("supported items are compressed, heir, id, keypos, memory, name, named_table, owner, protection, read_concurrency, size, type, and write_concurrency")
+            .map_err(From::from),
+    }
+}
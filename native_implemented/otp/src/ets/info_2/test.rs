@@ -0,0 +1,85 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::info_2::result;
+use crate::ets::{delete_1, insert_2, new_2};
+use crate::test;
+
+#[test]
+fn without_table_returns_undefined() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+    delete_1::result(&arc_process, tid).unwrap();
+
+    assert_eq!(
+        result(&arc_process, tid, atom!("size")),
+        Ok(atom!("undefined"))
+    );
+}
+
+#[test]
+fn with_unsupported_item_errors_badarg() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert_badarg!(
+        result(&arc_process, tid, atom!("unsupported")),
+        "supported items are"
+    );
+}
+
+#[test]
+fn with_defaults_returns_default_items() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert_eq!(result(&arc_process, tid, atom!("id")), Ok(tid));
+    assert_eq!(result(&arc_process, tid, atom!("name")), Ok(atom!("table")));
+    assert_eq!(result(&arc_process, tid, atom!("type")), Ok(atom!("set")));
+    assert_eq!(
+        result(&arc_process, tid, atom!("protection")),
+        Ok(atom!("protected"))
+    );
+    assert_eq!(
+        result(&arc_process, tid, atom!("keypos")),
+        Ok(arc_process.integer(1).unwrap())
+    );
+    assert_eq!(
+        result(&arc_process, tid, atom!("owner")),
+        Ok(arc_process.pid_term())
+    );
+    assert_eq!(result(&arc_process, tid, atom!("heir")), Ok(atom!("none")));
+    assert_eq!(
+        result(&arc_process, tid, atom!("size")),
+        Ok(arc_process.integer(0).unwrap())
+    );
+}
+
+#[test]
+fn with_objects_size_and_memory_grow() {
+    let arc_process = test::process::default();
+    let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+    let empty_memory: usize = result(&arc_process, tid, atom!("memory"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let object = arc_process
+        .tuple_from_slice(&[atom!("key"), atom!("value")])
+        .unwrap();
+
+    insert_2::result(&arc_process, tid, object).unwrap();
+
+    assert_eq!(
+        result(&arc_process, tid, atom!("size")),
+        Ok(arc_process.integer(1).unwrap())
+    );
+
+    let memory: usize = result(&arc_process, tid, atom!("memory"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    assert!(empty_memory < memory);
+}
@@ -0,0 +1,58 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::ets::Object;
+
+/// `object_or_objects` is a tuple or a list of tuples.  Either all objects are inserted or none
+/// are.
+#[native_implemented::function(ets:insert/2)]
+pub fn result(process: &Process, tab: Term, object_or_objects: Term) -> exception::Result<Term> {
+    let table = super::table_writable_by(process, tab)?;
+    let keypos = table.keypos();
+    let objects = match object_or_objects.decode()? {
+        TypedTerm::Nil => Vec::new(),
+        TypedTerm::List(cons) => {
+            let mut objects = Vec::new();
+
+            for result in cons.into_iter() {
+                let object = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("objects ({}) is improper", object_or_objects))?;
+
+                objects.push(try_into_object(object, keypos)?);
+            }
+
+            objects
+        }
+        _ => vec![try_into_object(object_or_objects, keypos)?],
+    };
+
+    let mut data = table.data_mut();
+
+    for object in objects {
+        data.insert(keypos, object);
+    }
+
+    Ok(true.into())
+}
+
+// Private
+
+/// Copies `object` off the process heap if it is a tuple with at least `keypos` elements
+fn try_into_object(object: Term, keypos: usize) -> exception::Result<Object> {
+    match object.decode()? {
+        TypedTerm::Tuple(tuple) if keypos <= tuple.len() => Object::new(object).map_err(From::from),
+        _ => Err(anyhow!(
+            "object ({}) is not a tuple with at least {} element(s)",
+            object,
+            keypos
+        )
+        .into()),
+    }
+}
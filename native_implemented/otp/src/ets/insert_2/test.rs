@@ -0,0 +1,178 @@
+use proptest::strategy::{Just, Strategy};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::insert_2::result;
+use crate::ets::{lookup_2, new_2};
+use crate::test::*;
+
+#[test]
+fn without_tuple_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_tuple(arc_process.clone())
+                    .prop_filter("Objects cannot be a list", |object| !object.is_list()),
+            )
+        },
+        |(arc_process, object)| {
+            let tid = new_table(&arc_process, atom!("set"));
+
+            prop_assert_badarg!(
+                result(&arc_process, tid, object),
+                format!(
+                    "object ({}) is not a tuple with at least 1 element(s)",
+                    object
+                )
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_tuple_smaller_than_keypos_errors_badarg() {
+    with_process(|process| {
+        let keypos = process
+            .tuple_from_slice(&[atom!("keypos"), process.integer(2).unwrap()])
+            .unwrap();
+        let options = process.list_from_slice(&[keypos]).unwrap();
+        let tid = new_2::result(process, atom!("table"), options).unwrap();
+        let object = process.tuple_from_slice(&[atom!("key")]).unwrap();
+
+        assert_badarg!(
+            result(process, tid, object),
+            "is not a tuple with at least 2 element(s)"
+        );
+    });
+}
+
+#[test]
+fn with_invalid_object_in_list_inserts_no_objects() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+        let objects = process
+            .list_from_slice(&[key_value(process, atom!("key"), 1), atom!("invalid")])
+            .unwrap();
+
+        assert_badarg!(result(process, tid, objects), "is not a tuple");
+        assert_eq!(lookup_2::result(process, tid, atom!("key")), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_set_replaces_object_with_same_key() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+        let second = key_value(process, atom!("key"), 2);
+
+        assert_eq!(
+            result(process, tid, key_value(process, atom!("key"), 1)),
+            Ok(true.into())
+        );
+        assert_eq!(result(process, tid, second), Ok(true.into()));
+        assert_eq!(
+            lookup_2::result(process, tid, atom!("key")),
+            Ok(process.list_from_slice(&[second]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_set_keys_are_exactly_equal() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+        let integer_key = process.integer(1).unwrap();
+        let float_key = process.float(1.0).unwrap();
+        let integer_object = process
+            .tuple_from_slice(&[integer_key, atom!("integer")])
+            .unwrap();
+        let float_object = process
+            .tuple_from_slice(&[float_key, atom!("float")])
+            .unwrap();
+        let objects = process
+            .list_from_slice(&[integer_object, float_object])
+            .unwrap();
+
+        assert_eq!(result(process, tid, objects), Ok(true.into()));
+        assert_eq!(
+            lookup_2::result(process, tid, integer_key),
+            Ok(process.list_from_slice(&[integer_object]).unwrap())
+        );
+        assert_eq!(
+            lookup_2::result(process, tid, float_key),
+            Ok(process.list_from_slice(&[float_object]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_ordered_set_keys_are_equal_after_conversion() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("ordered_set"));
+        let float_object = process
+            .tuple_from_slice(&[process.float(1.0).unwrap(), atom!("float")])
+            .unwrap();
+        let objects = process
+            .list_from_slice(&[
+                process
+                    .tuple_from_slice(&[process.integer(1).unwrap(), atom!("integer")])
+                    .unwrap(),
+                float_object,
+            ])
+            .unwrap();
+
+        assert_eq!(result(process, tid, objects), Ok(true.into()));
+        assert_eq!(
+            lookup_2::result(process, tid, process.integer(1).unwrap()),
+            Ok(process.list_from_slice(&[float_object]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_bag_skips_duplicate_objects() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("bag"));
+        let first = key_value(process, atom!("key"), 1);
+        let second = key_value(process, atom!("key"), 2);
+        let objects = process.list_from_slice(&[first, second, first]).unwrap();
+
+        assert_eq!(result(process, tid, objects), Ok(true.into()));
+        assert_eq!(
+            lookup_2::result(process, tid, atom!("key")),
+            Ok(process.list_from_slice(&[first, second]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_duplicate_bag_keeps_duplicate_objects() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("duplicate_bag"));
+        let first = key_value(process, atom!("key"), 1);
+        let objects = process.list_from_slice(&[first, first]).unwrap();
+
+        assert_eq!(result(process, tid, objects), Ok(true.into()));
+        assert_eq!(
+            lookup_2::result(process, tid, atom!("key")),
+            Ok(process.list_from_slice(&[first, first]).unwrap())
+        );
+    });
+}
+
+fn key_value(process: &Process, key: Term, value: isize) -> Term {
+    process
+        .tuple_from_slice(&[key, process.integer(value).unwrap()])
+        .unwrap()
+}
+
+fn new_table(process: &Process, r#type: Term) -> Term {
+    let options = process.list_from_slice(&[r#type]).unwrap();
+
+    new_2::result(process, atom!("table"), options).unwrap()
+}
@@ -0,0 +1,21 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+/// Returns a list of the objects with `key`.
+#[native_implemented::function(ets:lookup/2)]
+pub fn result(process: &Process, tab: Term, key: Term) -> exception::Result<Term> {
+    let table = super::table_readable_by(process, tab)?;
+    let data = table.data();
+    let objects: Vec<Term> = data
+        .lookup(key)
+        .into_iter()
+        .map(|object| object.term().clone_to_process(process))
+        .collect();
+
+    process.list_from_slice(&objects).map_err(From::from)
+}
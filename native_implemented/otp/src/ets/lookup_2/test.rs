@@ -0,0 +1,64 @@
+use proptest::prop_assert_eq;
+use proptest::strategy::{Just, Strategy};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::lookup_2::result;
+use crate::ets::{insert_2, new_2};
+use crate::test::*;
+
+#[test]
+fn without_table_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_atom(arc_process.clone())
+                    .prop_filter("Tid cannot be a reference", |tid| !tid.is_reference()),
+            )
+        },
+        |(arc_process, tid)| {
+            prop_assert_badarg!(
+                result(&arc_process, tid, atom!("key")),
+                format!("table ({}) does not exist", tid)
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_key_returns_copy_of_object() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term(arc_process.clone()),
+                strategy::term(arc_process.clone()),
+            )
+        },
+        |(arc_process, key, value)| {
+            let tid = new_2::result(&arc_process, atom!("table"), Term::NIL).unwrap();
+            let object = arc_process.tuple_from_slice(&[key, value]).unwrap();
+
+            prop_assert_eq!(insert_2::result(&arc_process, tid, object), Ok(true.into()));
+            prop_assert_eq!(
+                result(&arc_process, tid, key),
+                Ok(arc_process.list_from_slice(&[object]).unwrap())
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn without_key_returns_empty_list() {
+    with_process(|process| {
+        let tid = new_2::result(process, atom!("table"), Term::NIL).unwrap();
+
+        assert_eq!(result(process, tid, atom!("key")), Ok(Term::NIL));
+    });
+}
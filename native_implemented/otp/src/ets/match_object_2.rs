@@ -0,0 +1,23 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::match_spec::MatchSpec;
+
+/// Returns a list of the objects that match `pattern`, where `'_'` matches anything and `'$N'`
+/// variables must match the same term everywhere they appear.
+#[native_implemented::function(ets:match_object/2)]
+pub fn result(process: &Process, tab: Term, pattern: Term) -> exception::Result<Term> {
+    let table = super::table_readable_by(process, tab)?;
+    let match_spec = MatchSpec::from_pattern(pattern);
+    let data = table.data();
+    let objects: Vec<Term> = data
+        .iter()
+        .filter_map(|object| match_spec.run(process, object.term()))
+        .collect();
+
+    process.list_from_slice(&objects).map_err(From::from)
+}
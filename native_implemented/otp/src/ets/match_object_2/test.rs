@@ -0,0 +1,71 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::match_object_2::result;
+use crate::ets::{insert_2, new_2};
+use crate::test::*;
+
+#[test]
+fn with_wildcard_returns_matching_objects() {
+    with_process(|process| {
+        let tid = new_table(process);
+        let pattern = process.tuple_from_slice(&[atom!("_"), atom!("a")]).unwrap();
+
+        assert_eq!(
+            result(process, tid, pattern),
+            Ok(process
+                .list_from_slice(&[object(process, 1, atom!("a"))])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_repeated_variable_returns_objects_with_equal_elements() {
+    with_process(|process| {
+        let tid = new_table(process);
+        let variable = Atom::str_to_term("$1");
+        let pattern = process.tuple_from_slice(&[variable, variable]).unwrap();
+        let expected = object(process, 2, process.integer(2).unwrap());
+
+        assert_eq!(
+            result(process, tid, pattern),
+            Ok(process.list_from_slice(&[expected]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn without_matches_returns_empty_list() {
+    with_process(|process| {
+        let tid = new_table(process);
+        let pattern = process
+            .tuple_from_slice(&[atom!("_"), atom!("_"), atom!("_")])
+            .unwrap();
+
+        assert_eq!(result(process, tid, pattern), Ok(Term::NIL));
+    });
+}
+
+fn new_table(process: &Process) -> Term {
+    let options = process.list_from_slice(&[atom!("ordered_set")]).unwrap();
+    let tid = new_2::result(process, atom!("table"), options).unwrap();
+    let objects = process
+        .list_from_slice(&[
+            object(process, 1, atom!("a")),
+            object(process, 2, process.integer(2).unwrap()),
+            object(process, 3, atom!("b")),
+        ])
+        .unwrap();
+
+    insert_2::result(process, tid, objects).unwrap();
+
+    tid
+}
+
+fn object(process: &Process, key: isize, value: Term) -> Term {
+    process
+        .tuple_from_slice(&[process.integer(key).unwrap(), value])
+        .unwrap()
+}
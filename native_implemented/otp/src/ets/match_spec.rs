@@ -0,0 +1,389 @@
+//! [Match specifications](http://erlang.org/doc/apps/erts/match_spec.html) for `ets:select/2`
+//! and the patterns of `ets:match_object/2`.
+//!
+//! Objects are matched while they are still owned by the table, so only the values that are
+//! returned are copied to the process heap.
+
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use crate::erlang;
+use crate::runtime::context::*;
+
+pub struct MatchSpec {
+    clauses: Vec<Clause>,
+}
+
+impl MatchSpec {
+    /// `ets:match_object/2`'s `pattern` is the same as the match specification
+    /// `[{Pattern, [], ['$_']}]`.
+    pub fn from_pattern(pattern: Term) -> Self {
+        Self {
+            clauses: vec![Clause {
+                head: pattern,
+                guards: Vec::new(),
+                body: vec![atom!("$_")],
+            }],
+        }
+    }
+
+    /// Returns the value of the body of the first clause whose head and guards match `object`.
+    /// The value is on the heap of `process`.
+    pub fn run(&self, process: &Process, object: Term) -> Option<Term> {
+        self.clauses
+            .iter()
+            .find_map(|clause| clause.run(process, object))
+    }
+}
+
+const SUPPORTED_MATCH_SPEC_CONTEXT: &str =
+    "match specification must be a list of {Head, [Guard], [Body]} tuples";
+
+impl TryFrom<Term> for MatchSpec {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let clauses = proper_list_to_vec("match specification", term)
+            .context(SUPPORTED_MATCH_SPEC_CONTEXT)?
+            .into_iter()
+            .map(|clause| clause.try_into().context(SUPPORTED_MATCH_SPEC_CONTEXT))
+            .collect::<Result<Vec<Clause>, _>>()?;
+
+        Ok(Self { clauses })
+    }
+}
+
+// Private
+
+type Bindings = BTreeMap<usize, Term>;
+
+struct Clause {
+    head: Term,
+    guards: Vec<Term>,
+    body: Vec<Term>,
+}
+
+impl Clause {
+    fn run(&self, process: &Process, object: Term) -> Option<Term> {
+        let mut bindings = Bindings::new();
+
+        if !match_head(self.head, object, &mut bindings) {
+            return None;
+        }
+
+        let evaluator = Evaluator {
+            process,
+            object,
+            bindings,
+        };
+
+        // An exception in a guard fails the guard, as in a function clause
+        let guards_pass = self
+            .guards
+            .iter()
+            .all(|guard| match evaluator.eval(*guard) {
+                Ok(value) => value == Term::from(true),
+                Err(_) => false,
+            });
+
+        if guards_pass {
+            let mut value = atom!("EXIT");
+
+            for expression in &self.body {
+                match evaluator.eval(*expression) {
+                    Ok(expression_value) => value = expression_value,
+                    // > If the body raises an exception, the result of the body is `'EXIT'`
+                    Err(_) => return Some(atom!("EXIT")),
+                }
+            }
+
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<Term> for Clause {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let tuple: Boxed<Tuple> = term_try_into_tuple("match specification clause", term)?;
+
+        if tuple.len() == 3 {
+            let head = tuple[0];
+            let guards = proper_list_to_vec("guards", tuple[1])?;
+            let body = proper_list_to_vec("body", tuple[2])?;
+
+            if body.is_empty() {
+                Err(anyhow!("body of clause ({}) is empty", term))
+            } else {
+                Ok(Self { head, guards, body })
+            }
+        } else {
+            Err(anyhow!("clause ({}) is not a 3-tuple", term))
+        }
+    }
+}
+
+struct Evaluator<'a> {
+    process: &'a Process,
+    /// The object owned by the table
+    object: Term,
+    /// Values owned by the table
+    bindings: Bindings,
+}
+
+impl<'a> Evaluator<'a> {
+    fn eval(&self, expression: Term) -> exception::Result<Term> {
+        match expression.decode()? {
+            TypedTerm::Atom(atom) => match atom.name() {
+                "$_" => Ok(self.object.clone_to_process(self.process)),
+                "$$" => {
+                    let values: Vec<Term> = self
+                        .bindings
+                        .values()
+                        .map(|value| value.clone_to_process(self.process))
+                        .collect();
+
+                    self.process.list_from_slice(&values).map_err(From::from)
+                }
+                _ => match variable(atom) {
+                    Some(number) => self
+                        .bindings
+                        .get(&number)
+                        .map(|value| value.clone_to_process(self.process))
+                        .with_context(|| format!("variable ({}) is unbound", expression))
+                        .map_err(From::from),
+                    None => Ok(expression),
+                },
+            },
+            TypedTerm::List(cons) => {
+                let head = self.eval(cons.head)?;
+                let tail = self.eval(cons.tail)?;
+
+                self.process.cons(head, tail).map_err(From::from)
+            }
+            TypedTerm::Tuple(tuple) => self.eval_tuple(expression, &tuple),
+            _ => Ok(expression),
+        }
+    }
+
+    /// `{{...}}` constructs a tuple, `{const, Term}` is `Term` unevaluated, and any other tuple
+    /// calls the function named by the first element.
+    fn eval_tuple(&self, expression: Term, tuple: &Tuple) -> exception::Result<Term> {
+        let elements = tuple.elements();
+
+        if elements.len() == 1 {
+            if let Ok(inner_tuple) = Boxed::<Tuple>::try_from(elements[0]) {
+                let values = self.eval_all(inner_tuple.elements())?;
+
+                return self.process.tuple_from_slice(&values).map_err(From::from);
+            }
+        }
+
+        let (function, arguments) = elements
+            .split_first()
+            .with_context(|| format!("expression ({}) is an empty tuple", expression))?;
+        let function_atom = term_try_into_atom("function", *function)?;
+
+        match (function_atom.name(), arguments) {
+            ("const", [term]) => Ok(*term),
+            ("andalso", _) => {
+                for argument in arguments {
+                    if !term_try_into_bool("andalso argument", self.eval(*argument)?)? {
+                        return Ok(false.into());
+                    }
+                }
+
+                Ok(true.into())
+            }
+            ("orelse", _) => {
+                for argument in arguments {
+                    if term_try_into_bool("orelse argument", self.eval(*argument)?)? {
+                        return Ok(true.into());
+                    }
+                }
+
+                Ok(false.into())
+            }
+            _ => {
+                let values = self.eval_all(arguments)?;
+
+                self.call(function_atom, &values)
+            }
+        }
+    }
+
+    fn eval_all(&self, expressions: &[Term]) -> exception::Result<Vec<Term>> {
+        expressions
+            .iter()
+            .map(|expression| self.eval(*expression))
+            .collect()
+    }
+
+    fn call(&self, function: Atom, arguments: &[Term]) -> exception::Result<Term> {
+        let process = self.process;
+
+        match (function.name(), arguments) {
+            ("is_atom", [term]) => Ok(erlang::is_atom_1::result(*term)),
+            ("is_binary", [term]) => Ok(erlang::is_binary_1::result(*term)),
+            ("is_boolean", [term]) => Ok(erlang::is_boolean_1::result(*term)),
+            ("is_float", [term]) => Ok(erlang::is_float_1::result(*term)),
+            ("is_function", [term]) => Ok(erlang::is_function_1::result(*term)),
+            ("is_integer", [term]) => Ok(erlang::is_integer_1::result(*term)),
+            ("is_list", [term]) => Ok(erlang::is_list_1::result(*term)),
+            ("is_map", [term]) => Ok(erlang::is_map_1::result(*term)),
+            ("is_number", [term]) => Ok(erlang::is_number_1::result(*term)),
+            ("is_pid", [term]) => Ok(erlang::is_pid_1::result(*term)),
+            ("is_reference", [term]) => Ok(erlang::is_reference_1::result(*term)),
+            ("is_tuple", [term]) => Ok(erlang::is_tuple_1::result(*term)),
+            ("==", [left, right]) => {
+                Ok(erlang::are_equal_after_conversion_2::result(*left, *right))
+            }
+            ("/=", [left, right]) => Ok(erlang::are_not_equal_after_conversion_2::result(
+                *left, *right,
+            )),
+            ("=:=", [left, right]) => Ok(erlang::are_exactly_equal_2::result(*left, *right)),
+            ("=/=", [left, right]) => Ok(erlang::are_exactly_not_equal_2::result(*left, *right)),
+            ("<", [left, right]) => Ok(erlang::is_less_than_2::result(*left, *right)),
+            ("=<", [left, right]) => Ok(erlang::is_equal_or_less_than_2::result(*left, *right)),
+            (">", [left, right]) => Ok(erlang::is_greater_than_2::result(*left, *right)),
+            (">=", [left, right]) => Ok(erlang::is_greater_than_or_equal_2::result(*left, *right)),
+            ("not", [boolean]) => erlang::not_1::result(*boolean),
+            ("and", [left, right]) => {
+                let left_bool = term_try_into_bool("left", *left)?;
+                let right_bool = term_try_into_bool("right", *right)?;
+
+                Ok((left_bool && right_bool).into())
+            }
+            ("or", [left, right]) => erlang::or_2::result(*left, *right),
+            ("xor", [left, right]) => erlang::xor_2::result(*left, *right),
+            ("+", [augend, addend]) => erlang::add_2::result(process, *augend, *addend),
+            ("-", [number]) => erlang::negate_1::result(process, *number),
+            ("-", [minuend, subtrahend]) => {
+                erlang::subtract_2::result(process, *minuend, *subtrahend)
+            }
+            ("*", [multiplier, multiplicand]) => {
+                erlang::multiply_2::result(process, *multiplier, *multiplicand)
+            }
+            ("/", [dividend, divisor]) => erlang::divide_2::result(process, *dividend, *divisor),
+            ("div", [dividend, divisor]) => erlang::div_2::result(process, *dividend, *divisor),
+            ("rem", [dividend, divisor]) => erlang::rem_2::result(process, *dividend, *divisor),
+            ("band", [left, right]) => erlang::band_2::result(process, *left, *right),
+            ("bor", [left, right]) => erlang::bor_2::result(process, *left, *right),
+            ("bxor", [left, right]) => erlang::bxor_2::result(process, *left, *right),
+            ("bnot", [integer]) => erlang::bnot_1::result(process, *integer),
+            ("bsl", [integer, shift]) => erlang::bsl_2::result(process, *integer, *shift),
+            ("bsr", [integer, shift]) => erlang::bsr_2::result(process, *integer, *shift),
+            ("element", [index, tuple]) => erlang::element_2::result(*index, *tuple),
+            ("hd", [list]) => erlang::hd_1::result(*list),
+            ("tl", [list]) => erlang::tl_1::result(*list),
+            ("length", [list]) => erlang::length_1::result(process, *list),
+            ("map_get", [key, map]) => erlang::map_get_2::result(process, *key, *map),
+            ("map_size", [map]) => erlang::map_size_1::result(process, *map),
+            ("max", [term1, term2]) => Ok(erlang::max_2::result(*term1, *term2)),
+            ("min", [term1, term2]) => Ok(erlang::min_2::result(*term1, *term2)),
+            ("self", []) => Ok(erlang::self_0::result(process)),
+            ("size", [binary_or_tuple]) => erlang::size_1::result(process, *binary_or_tuple),
+            ("tuple_size", [tuple]) => erlang::tuple_size_1::result(process, *tuple),
+            (name, _) => Err(anyhow!(
+                "{}/{} is not a supported match specification function",
+                name,
+                arguments.len()
+            )
+            .into()),
+        }
+    }
+}
+
+fn exactly_equal(left: Term, right: Term) -> bool {
+    left.decode().unwrap().exact_eq(&right.decode().unwrap())
+}
+
+/// Matches the `pattern` from the process against the `object` owned by the table, binding `'$N'`
+/// variables to the parts of `object`.
+fn match_head(pattern: Term, object: Term, bindings: &mut Bindings) -> bool {
+    match pattern.decode().unwrap() {
+        TypedTerm::Atom(atom) => {
+            if atom.name() == "_" {
+                true
+            } else if let Some(number) = variable(atom) {
+                match bindings.get(&number) {
+                    Some(bound) => exactly_equal(*bound, object),
+                    None => {
+                        bindings.insert(number, object);
+
+                        true
+                    }
+                }
+            } else {
+                exactly_equal(pattern, object)
+            }
+        }
+        TypedTerm::List(pattern_cons) => match object.decode().unwrap() {
+            TypedTerm::List(object_cons) => {
+                match_head(pattern_cons.head, object_cons.head, bindings)
+                    && match_head(pattern_cons.tail, object_cons.tail, bindings)
+            }
+            _ => false,
+        },
+        TypedTerm::Map(pattern_map) => match object.decode().unwrap() {
+            TypedTerm::Map(object_map) => {
+                pattern_map
+                    .iter()
+                    .all(|(key, value_pattern)| match object_map.get(*key) {
+                        Some(value) => match_head(*value_pattern, value, bindings),
+                        None => false,
+                    })
+            }
+            _ => false,
+        },
+        TypedTerm::Tuple(pattern_tuple) => match object.decode().unwrap() {
+            TypedTerm::Tuple(object_tuple) => {
+                pattern_tuple.len() == object_tuple.len()
+                    && pattern_tuple.iter().zip(object_tuple.iter()).all(
+                        |(element_pattern, element)| {
+                            match_head(*element_pattern, *element, bindings)
+                        },
+                    )
+            }
+            _ => false,
+        },
+        _ => exactly_equal(pattern, object),
+    }
+}
+
+fn proper_list_to_vec(name: &str, list: Term) -> anyhow::Result<Vec<Term>> {
+    match list.decode().unwrap() {
+        TypedTerm::Nil => Ok(Vec::new()),
+        TypedTerm::List(cons) => cons
+            .into_iter()
+            .map(|result| {
+                result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("{} ({}) is improper", name, list))
+            })
+            .collect(),
+        _ => Err(TypeError).with_context(|| format!("{} ({}) is not a list", name, list)),
+    }
+}
+
+/// The `N` of a `'$N'` variable
+fn variable(atom: Atom) -> Option<usize> {
+    let name = atom.name();
+
+    if name.starts_with('$') {
+        name[1..].parse().ok()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::*;
+use crate::runtime::ets::{self, Options, Table};
+use crate::runtime::scheduler::SchedulerDependentAlloc;
+
+/// Returns `name` for a `named_table`, otherwise the table's reference.
+#[native_implemented::function(ets:new/2)]
+pub fn result(process: &Process, name: Term, options: Term) -> exception::Result<Term> {
+    let name_atom = term_try_into_atom!(name)?;
+    let options: Options = options.try_into()?;
+    let reference_term = process.next_reference()?;
+    let reference: Boxed<Reference> = reference_term.try_into().unwrap();
+    let table = Table::new(
+        name_atom,
+        *reference,
+        reference_term,
+        process.pid(),
+        &options,
+    )?;
+    ets::insert(table)?;
+
+    if options.named_table {
+        Ok(name)
+    } else {
+        Ok(reference_term)
+    }
+}
@@ -0,0 +1,196 @@
+use proptest::strategy::Just;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::new_2::result;
+use crate::ets::{info_2, insert_2, lookup_2};
+use crate::runtime::ets;
+use crate::test;
+use crate::test::*;
+
+#[test]
+fn without_atom_name_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_atom(arc_process.clone()),
+            )
+        },
+        |(arc_process, name)| {
+            prop_assert_is_not_atom!(result(&arc_process, name, Term::NIL), name);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_unsupported_option_errors_badarg() {
+    with_process(|process| {
+        let options = process.list_from_slice(&[atom!("unsupported")]).unwrap();
+
+        assert_badarg!(
+            result(process, atom!("table"), options),
+            "supported options are"
+        );
+    });
+}
+
+#[test]
+fn with_keypos_zero_errors_badarg() {
+    with_process(|process| {
+        let keypos = process
+            .tuple_from_slice(&[atom!("keypos"), process.integer(0).unwrap()])
+            .unwrap();
+        let options = process.list_from_slice(&[keypos]).unwrap();
+
+        assert_badarg!(
+            result(process, atom!("table"), options),
+            "keypos (0) is not a positive integer"
+        );
+    });
+}
+
+#[test]
+fn without_named_table_returns_reference() {
+    let arc_process = test::process::default();
+
+    let tid = result(&arc_process, atom!("table"), Term::NIL).unwrap();
+
+    assert!(tid.is_reference());
+    assert_eq!(
+        info_2::result(&arc_process, tid, atom!("named_table")),
+        Ok(false.into())
+    );
+}
+
+#[test]
+fn with_named_table_returns_name() {
+    let arc_process = test::process::default();
+    let name = Atom::str_to_term("ets_new_2_with_named_table_returns_name");
+    let options = arc_process
+        .list_from_slice(&[atom!("named_table")])
+        .unwrap();
+
+    assert_eq!(result(&arc_process, name, options), Ok(name));
+    assert_eq!(
+        info_2::result(&arc_process, name, atom!("named_table")),
+        Ok(true.into())
+    );
+}
+
+#[test]
+fn with_named_table_with_name_in_use_errors_badarg() {
+    let arc_process = test::process::default();
+    let name = Atom::str_to_term("ets_new_2_with_named_table_with_name_in_use_errors_badarg");
+    let options = arc_process
+        .list_from_slice(&[atom!("named_table")])
+        .unwrap();
+
+    assert_eq!(result(&arc_process, name, options), Ok(name));
+    assert_badarg!(result(&arc_process, name, options), "is already in use");
+}
+
+#[test]
+fn with_heir_when_owner_exits_gives_table_to_heir() {
+    let owner_arc_process = test::process::default();
+    let heir_arc_process = test::process::default();
+    let heir_data = atom!("heir_data");
+    let heir = owner_arc_process
+        .tuple_from_slice(&[atom!("heir"), heir_arc_process.pid_term(), heir_data])
+        .unwrap();
+    let options = owner_arc_process.list_from_slice(&[heir]).unwrap();
+    let tid = result(&owner_arc_process, atom!("table"), options).unwrap();
+
+    ets::propagate_exit(&owner_arc_process);
+
+    assert_eq!(
+        info_2::result(&heir_arc_process, tid, atom!("owner")),
+        Ok(heir_arc_process.pid_term())
+    );
+    assert_has_message!(
+        &heir_arc_process,
+        heir_arc_process
+            .tuple_from_slice(&[
+                Atom::str_to_term("ETS-TRANSFER"),
+                tid,
+                owner_arc_process.pid_term(),
+                heir_data
+            ])
+            .unwrap()
+    );
+}
+
+#[test]
+fn without_heir_when_owner_exits_deletes_table() {
+    let owner_arc_process = test::process::default();
+    let tid = result(&owner_arc_process, atom!("table"), Term::NIL).unwrap();
+
+    ets::propagate_exit(&owner_arc_process);
+
+    assert_eq!(
+        info_2::result(&owner_arc_process, tid, atom!("owner")),
+        Ok(atom!("undefined"))
+    );
+}
+
+#[test]
+fn with_private_table_other_process_cannot_read() {
+    let owner_arc_process = test::process::default();
+    let other_arc_process = test::process::default();
+    let tid = new_table(&owner_arc_process, atom!("private"));
+
+    insert_and_lookup(&owner_arc_process, tid);
+    assert_badarg!(
+        lookup_2::result(&other_arc_process, tid, atom!("key")),
+        "is private"
+    );
+}
+
+#[test]
+fn with_protected_table_other_process_can_read_but_not_write() {
+    let owner_arc_process = test::process::default();
+    let other_arc_process = test::process::default();
+    let tid = new_table(&owner_arc_process, atom!("protected"));
+
+    insert_and_lookup(&owner_arc_process, tid);
+    assert!(lookup_2::result(&other_arc_process, tid, atom!("key")).is_ok());
+    assert_badarg!(
+        insert_2::result(&other_arc_process, tid, object(&other_arc_process)),
+        "is protected"
+    );
+}
+
+#[test]
+fn with_public_table_other_process_can_read_and_write() {
+    let owner_arc_process = test::process::default();
+    let other_arc_process = test::process::default();
+    let tid = new_table(&owner_arc_process, atom!("public"));
+
+    insert_and_lookup(&other_arc_process, tid);
+}
+
+fn insert_and_lookup(process: &Process, tid: Term) {
+    let object = object(process);
+
+    assert_eq!(insert_2::result(process, tid, object), Ok(true.into()));
+    assert_eq!(
+        lookup_2::result(process, tid, atom!("key")),
+        Ok(process.list_from_slice(&[object]).unwrap())
+    );
+}
+
+fn new_table(process: &Process, access: Term) -> Term {
+    let options = process.list_from_slice(&[access]).unwrap();
+
+    result(process, atom!("table"), options).unwrap()
+}
+
+fn object(process: &Process) -> Term {
+    process
+        .tuple_from_slice(&[atom!("key"), atom!("value")])
+        .unwrap()
+}
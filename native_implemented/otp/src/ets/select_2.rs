@@ -0,0 +1,24 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::match_spec::MatchSpec;
+
+/// Returns a list of the values of the match specification's body for each matching object.
+#[native_implemented::function(ets:select/2)]
+pub fn result(process: &Process, tab: Term, match_spec: Term) -> exception::Result<Term> {
+    let table = super::table_readable_by(process, tab)?;
+    let match_spec: MatchSpec = match_spec.try_into()?;
+    let data = table.data();
+    let values: Vec<Term> = data
+        .iter()
+        .filter_map(|object| match_spec.run(process, object.term()))
+        .collect();
+
+    process.list_from_slice(&values).map_err(From::from)
+}
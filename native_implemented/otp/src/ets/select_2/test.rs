@@ -0,0 +1,119 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::select_2::result;
+use crate::ets::{insert_2, new_2};
+use crate::test::*;
+
+#[test]
+fn without_list_match_spec_errors_badarg() {
+    with_process(|process| {
+        let tid = new_table(process);
+
+        assert_badarg!(
+            result(process, tid, atom!("match_spec")),
+            "match specification must be a list"
+        );
+    });
+}
+
+#[test]
+fn with_guard_returns_body_for_matching_objects() {
+    with_process(|process| {
+        let tid = new_table(process);
+        let key = Atom::str_to_term("$1");
+        let value = Atom::str_to_term("$2");
+        let head = process.tuple_from_slice(&[key, value]).unwrap();
+        // [{'>', '$1', 1}]
+        let guards = process
+            .list_from_slice(&[process
+                .tuple_from_slice(&[Atom::str_to_term(">"), key, process.integer(1).unwrap()])
+                .unwrap()])
+            .unwrap();
+        // ['$2']
+        let body = process.list_from_slice(&[value]).unwrap();
+        let match_spec = match_spec(process, head, guards, body);
+
+        assert_eq!(
+            result(process, tid, match_spec),
+            Ok(process.list_from_slice(&[atom!("b"), atom!("c")]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_tuple_construction_in_body_returns_constructed_tuples() {
+    with_process(|process| {
+        let tid = new_table(process);
+        let key = Atom::str_to_term("$1");
+        let head = process.tuple_from_slice(&[key, atom!("a")]).unwrap();
+        // [{{'$1', {'+', '$1', 1}}}]
+        let increment = process
+            .tuple_from_slice(&[Atom::str_to_term("+"), key, process.integer(1).unwrap()])
+            .unwrap();
+        let construction = process
+            .tuple_from_slice(&[process.tuple_from_slice(&[key, increment]).unwrap()])
+            .unwrap();
+        let body = process.list_from_slice(&[construction]).unwrap();
+        let match_spec = match_spec(process, head, Term::NIL, body);
+
+        assert_eq!(
+            result(process, tid, match_spec),
+            Ok(process
+                .list_from_slice(&[process
+                    .tuple_from_slice(&[process.integer(1).unwrap(), process.integer(2).unwrap()])
+                    .unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_failing_guard_skips_object() {
+    with_process(|process| {
+        let tid = new_table(process);
+        // [{element, 3, '$_'}] fails because objects only have 2 elements
+        let guards = process
+            .list_from_slice(&[process
+                .tuple_from_slice(&[
+                    atom!("element"),
+                    process.integer(3).unwrap(),
+                    Atom::str_to_term("$_"),
+                ])
+                .unwrap()])
+            .unwrap();
+        let body = process.list_from_slice(&[Atom::str_to_term("$_")]).unwrap();
+        let match_spec = match_spec(process, atom!("_"), guards, body);
+
+        assert_eq!(result(process, tid, match_spec), Ok(Term::NIL));
+    });
+}
+
+fn match_spec(process: &Process, head: Term, guards: Term, body: Term) -> Term {
+    process
+        .list_from_slice(&[process.tuple_from_slice(&[head, guards, body]).unwrap()])
+        .unwrap()
+}
+
+fn new_table(process: &Process) -> Term {
+    let options = process.list_from_slice(&[atom!("ordered_set")]).unwrap();
+    let tid = new_2::result(process, atom!("table"), options).unwrap();
+    let objects = process
+        .list_from_slice(&[
+            object(process, 1, atom!("a")),
+            object(process, 2, atom!("b")),
+            object(process, 3, atom!("c")),
+        ])
+        .unwrap();
+
+    insert_2::result(process, tid, objects).unwrap();
+
+    tid
+}
+
+fn object(process: &Process, key: isize, value: Term) -> Term {
+    process
+        .tuple_from_slice(&[process.integer(key).unwrap(), value])
+        .unwrap()
+}
@@ -0,0 +1,20 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+/// Returns a list of all objects in `tab`.
+#[native_implemented::function(ets:tab2list/1)]
+pub fn result(process: &Process, tab: Term) -> exception::Result<Term> {
+    let table = super::table_readable_by(process, tab)?;
+    let data = table.data();
+    let objects: Vec<Term> = data
+        .iter()
+        .map(|object| object.term().clone_to_process(process))
+        .collect();
+
+    process.list_from_slice(&objects).map_err(From::from)
+}
@@ -0,0 +1,39 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::tab2list_1::result;
+use crate::ets::{insert_2, new_2};
+use crate::test::*;
+
+#[test]
+fn with_empty_table_returns_empty_list() {
+    with_process(|process| {
+        let tid = new_2::result(process, atom!("table"), Term::NIL).unwrap();
+
+        assert_eq!(result(process, tid), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_ordered_set_returns_objects_in_key_order() {
+    with_process(|process| {
+        let options = process.list_from_slice(&[atom!("ordered_set")]).unwrap();
+        let tid = new_2::result(process, atom!("table"), options).unwrap();
+        let first = process
+            .tuple_from_slice(&[process.integer(1).unwrap()])
+            .unwrap();
+        let second = process
+            .tuple_from_slice(&[process.integer(2).unwrap()])
+            .unwrap();
+        let third = process
+            .tuple_from_slice(&[process.integer(3).unwrap()])
+            .unwrap();
+        let objects = process.list_from_slice(&[third, first, second]).unwrap();
+
+        assert_eq!(insert_2::result(process, tid, objects), Ok(true.into()));
+        assert_eq!(
+            result(process, tid),
+            Ok(process.list_from_slice(&[first, second, third]).unwrap())
+        );
+    });
+}
@@ -0,0 +1,182 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use crate::erlang::add_2;
+use crate::runtime::ets::{Object, Type};
+
+/// `update_op` is an `Increment` for the element after the key, a `{Position, Increment}` or
+/// `{Position, Increment, Threshold, SetValue}` tuple, or a list of those tuples.  Returns the new
+/// counter or a list of the new counters for a list of operations.
+#[native_implemented::function(ets:update_counter/3)]
+pub fn result(process: &Process, tab: Term, key: Term, update_op: Term) -> exception::Result<Term> {
+    let table = super::table_writable_by(process, tab)?;
+
+    match table.r#type() {
+        Type::Set | Type::OrderedSet => (),
+        r#type => {
+            return Err(anyhow!(
+                "table ({}) is a {} instead of a set or ordered_set",
+                tab,
+                Atom::from(r#type)
+            )
+            .into())
+        }
+    }
+
+    let keypos = table.keypos();
+    let (updates, is_list) = term_try_into_updates(update_op, keypos)?;
+    let mut data = table.data_mut();
+
+    // The object is copied to the process, so the counters can be added with process integers
+    let object = match data.lookup(key).first() {
+        Some(object) => object.term().clone_to_process(process),
+        None => return Err(anyhow!("table ({}) does not have key ({})", tab, key).into()),
+    };
+    let object_tuple: Boxed<Tuple> = object.try_into().unwrap();
+    let mut elements = object_tuple.elements().to_vec();
+    let mut counters = Vec::with_capacity(updates.len());
+
+    for update in updates {
+        counters.push(update.apply(process, &mut elements)?);
+    }
+
+    let updated_object = process.tuple_from_slice(&elements)?;
+    data.insert(keypos, Object::new(updated_object)?);
+
+    if is_list {
+        process.list_from_slice(&counters).map_err(From::from)
+    } else {
+        Ok(counters[0])
+    }
+}
+
+// Private
+
+struct Update {
+    /// 1-based
+    position: usize,
+    increment: Term,
+    threshold_set_value: Option<(Term, Term)>,
+}
+
+impl Update {
+    fn apply(&self, process: &Process, elements: &mut [Term]) -> exception::Result<Term> {
+        let counter = elements.get(self.position - 1).copied().with_context(|| {
+            format!(
+                "position ({}) is greater than the object size ({})",
+                self.position,
+                elements.len()
+            )
+        })?;
+
+        if !counter.is_integer() {
+            return Err(anyhow!(
+                "element ({}) at position ({}) is not an integer",
+                counter,
+                self.position
+            )
+            .into());
+        }
+
+        let mut updated_counter = add_2::result(process, counter, self.increment)?;
+
+        if let Some((threshold, set_value)) = self.threshold_set_value {
+            let zero = process.integer(0)?;
+            let crossed = if self.increment < zero {
+                updated_counter < threshold
+            } else {
+                threshold < updated_counter
+            };
+
+            if crossed {
+                updated_counter = set_value;
+            }
+        }
+
+        elements[self.position - 1] = updated_counter;
+
+        Ok(updated_counter)
+    }
+}
+
+fn term_try_into_integer(name: &str, term: Term) -> anyhow::Result<Term> {
+    if term.is_integer() {
+        Ok(term)
+    } else {
+        Err(anyhow!("{} ({}) is not an integer", name, term))
+    }
+}
+
+fn term_try_into_update(update_op: Term, keypos: usize) -> anyhow::Result<Update> {
+    match update_op.decode().unwrap() {
+        TypedTerm::Tuple(tuple) if tuple.len() == 2 || tuple.len() == 4 => {
+            let position: usize = tuple[0]
+                .try_into()
+                .ok()
+                .filter(|position| 0 < *position && *position != keypos)
+                .with_context(|| {
+                    format!(
+                        "position ({}) is not a positive integer other than keypos ({})",
+                        tuple[0], keypos
+                    )
+                })?;
+            let increment = term_try_into_integer("increment", tuple[1])?;
+            let threshold_set_value = if tuple.len() == 4 {
+                Some((
+                    term_try_into_integer("threshold", tuple[2])?,
+                    term_try_into_integer("set value", tuple[3])?,
+                ))
+            } else {
+                None
+            };
+
+            Ok(Update {
+                position,
+                increment,
+                threshold_set_value,
+            })
+        }
+        _ => Err(anyhow!(
+            "update operation ({}) is not an integer, {{Position, Increment}}, or {{Position, Increment, Threshold, SetValue}}",
+            update_op
+        )),
+    }
+}
+
+/// Returns the updates and whether `update_op` was a list
+fn term_try_into_updates(update_op: Term, keypos: usize) -> anyhow::Result<(Vec<Update>, bool)> {
+    match update_op.decode().unwrap() {
+        TypedTerm::Nil => Ok((Vec::new(), true)),
+        TypedTerm::List(cons) => {
+            let mut updates = Vec::new();
+
+            for result in cons.into_iter() {
+                let element = result
+                    .map_err(|_| ImproperListError)
+                    .with_context(|| format!("update operations ({}) is improper", update_op))?;
+
+                updates.push(term_try_into_update(element, keypos)?);
+            }
+
+            Ok((updates, true))
+        }
+        TypedTerm::SmallInteger(_) | TypedTerm::BigInteger(_) => Ok((
+            vec![Update {
+                position: keypos + 1,
+                increment: update_op,
+                threshold_set_value: None,
+            }],
+            false,
+        )),
+        _ => term_try_into_update(update_op, keypos).map(|update| (vec![update], false)),
+    }
+}
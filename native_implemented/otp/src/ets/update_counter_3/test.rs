@@ -0,0 +1,133 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::update_counter_3::result;
+use crate::ets::{insert_2, lookup_2, new_2};
+use crate::test::*;
+
+#[test]
+fn without_key_errors_badarg() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+
+        assert_badarg!(
+            result(process, tid, atom!("missing"), process.integer(1).unwrap()),
+            "does not have key (missing)"
+        );
+    });
+}
+
+#[test]
+fn with_bag_errors_badarg() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("bag"));
+
+        assert_badarg!(
+            result(process, tid, atom!("key"), process.integer(1).unwrap()),
+            "instead of a set or ordered_set"
+        );
+    });
+}
+
+#[test]
+fn with_increment_updates_element_after_key() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+
+        assert_eq!(
+            result(process, tid, atom!("key"), process.integer(5).unwrap()),
+            Ok(process.integer(15).unwrap())
+        );
+        assert_eq!(
+            lookup_2::result(process, tid, atom!("key")),
+            Ok(process
+                .list_from_slice(&[counters(process, 15, 20)])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_keypos_position_errors_badarg() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+        let update_op = process
+            .tuple_from_slice(&[process.integer(1).unwrap(), process.integer(1).unwrap()])
+            .unwrap();
+
+        assert_badarg!(
+            result(process, tid, atom!("key"), update_op),
+            "is not a positive integer other than keypos (1)"
+        );
+    });
+}
+
+#[test]
+fn with_threshold_sets_value_when_crossed() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("ordered_set"));
+        let update_op = process
+            .tuple_from_slice(&[
+                process.integer(3).unwrap(),
+                process.integer(5).unwrap(),
+                process.integer(22).unwrap(),
+                process.integer(0).unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(process, tid, atom!("key"), update_op),
+            Ok(process.integer(0).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_list_returns_list_of_counters() {
+    with_process(|process| {
+        let tid = new_table(process, atom!("set"));
+        let update_ops = process
+            .list_from_slice(&[
+                process
+                    .tuple_from_slice(&[process.integer(2).unwrap(), process.integer(1).unwrap()])
+                    .unwrap(),
+                process
+                    .tuple_from_slice(&[process.integer(3).unwrap(), process.integer(-1).unwrap()])
+                    .unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(process, tid, atom!("key"), update_ops),
+            Ok(process
+                .list_from_slice(&[process.integer(11).unwrap(), process.integer(19).unwrap()])
+                .unwrap())
+        );
+        assert_eq!(
+            lookup_2::result(process, tid, atom!("key")),
+            Ok(process
+                .list_from_slice(&[counters(process, 11, 19)])
+                .unwrap())
+        );
+    });
+}
+
+fn counters(process: &Process, first: isize, second: isize) -> Term {
+    process
+        .tuple_from_slice(&[
+            atom!("key"),
+            process.integer(first).unwrap(),
+            process.integer(second).unwrap(),
+        ])
+        .unwrap()
+}
+
+fn new_table(process: &Process, r#type: Term) -> Term {
+    let options = process.list_from_slice(&[r#type]).unwrap();
+    let tid = new_2::result(process, atom!("table"), options).unwrap();
+
+    insert_2::result(process, tid, counters(process, 10, 20)).unwrap();
+
+    tid
+}
@@ -7,8 +7,11 @@
 #[macro_use]
 mod macros;
 
+pub mod application;
 pub mod binary;
+pub mod eprof;
 pub mod erlang;
+pub mod ets;
 pub mod lists;
 pub mod maps;
 pub mod number;
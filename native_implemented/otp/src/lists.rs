@@ -1,12 +1,46 @@
 //! Mirrors [lists](http://erlang.org/doc/man/lists.html) module
+//!
+//! When run from a frame, functions that walk lists only walk `ELEMENTS_PER_CALL` elements per
+//! call.  When there are more elements, they queue a frame to continue from where they stopped and
+//! return `Term::NONE`, so that long lists yield to the scheduler between calls instead of holding
+//! it for the whole list.  Functions that take a function queue a frame to call it for each
+//! element, which also yields.
+//!
+//! Compiled code calls these functions directly, without a frame, and never runs queued frames,
+//! so there they walk the whole list and call functions directly in one call.
 
+pub mod append_1;
+pub mod duplicate_2;
+pub mod flatten_1;
+pub mod foldl_3;
+pub mod keydelete_3;
 pub mod keyfind_3;
 pub mod keymember_3;
+pub mod keysort_2;
+pub mod keystore_4;
+pub mod last_1;
+pub mod map_2;
 pub mod member_2;
+pub mod merge_2;
+pub mod nth_2;
+pub mod nthtail_2;
 pub mod reverse_1;
 pub mod reverse_2;
+pub mod seq_2;
+pub mod seq_3;
+pub mod sort_1;
+pub mod sort_2;
+pub mod sublist_2;
+pub mod sublist_3;
+pub mod usort_1;
 
-use liblumen_alloc::erts::term::prelude::Atom;
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::{FrameWithArguments, Process};
+use liblumen_alloc::erts::term::prelude::*;
 
 fn module() -> Atom {
     Atom::from_str("lists")
@@ -15,3 +49,83 @@ fn module() -> Atom {
 fn module_id() -> usize {
     module().id()
 }
+
+// Private
+
+/// The number of elements walked before a call queues its continuation
+const ELEMENTS_PER_CALL: usize = 1_000;
+
+/// Walking an element is much cheaper than calling a function, so like BEAM's list BIFs, only
+/// every `ELEMENTS_PER_REDUCTION` elements count as a reduction.
+const ELEMENTS_PER_REDUCTION: usize = 10;
+
+fn reduce(process: &Process, elements: usize) {
+    let reductions = (elements / ELEMENTS_PER_REDUCTION)
+        .try_into()
+        .unwrap_or(std::u16::MAX);
+
+    process.reduce_by(reductions);
+}
+
+/// Whether `process` is running natives from frames, so that a call can queue frames to continue
+fn runs_frames(process: &Process) -> bool {
+    process.frames.lock().current().is_some()
+}
+
+/// The number of elements a call walks before it queues its continuation, which is all of them
+/// when it can't queue frames
+fn elements_per_call(process: &Process) -> usize {
+    if runs_frames(process) {
+        ELEMENTS_PER_CALL
+    } else {
+        std::usize::MAX
+    }
+}
+
+/// Calls `function` with `arguments` directly, for when frames can't be queued.  `None` when
+/// `function` raised an exception, which is already recorded in the process's status.
+fn call(function: Boxed<Closure>, arguments: Vec<Term>) -> Option<Term> {
+    let FrameWithArguments {
+        frame, arguments, ..
+    } = function.frame_with_arguments(false, arguments);
+    let returned = frame.native().apply(&arguments);
+
+    if returned.is_none() {
+        None
+    } else {
+        Some(returned)
+    }
+}
+
+fn function_of_arity(function: Term, arity: u8) -> exception::Result<Boxed<Closure>> {
+    let result_boxed_closure: Result<Boxed<Closure>, _> = function.try_into();
+
+    match result_boxed_closure {
+        Ok(boxed_closure) if boxed_closure.arity() == arity => Ok(boxed_closure),
+        _ => Err(anyhow!(TypeError)
+            .context(format!(
+                "function ({}) is not a function of arity {}",
+                function, arity
+            ))
+            .into()),
+    }
+}
+
+fn improper_list(name: &str, list: Term) -> exception::Exception {
+    anyhow!(ImproperListError)
+        .context(format!("{} ({}) is not a proper list", name, list))
+        .into()
+}
+
+/// Whether `element` is a tuple whose `index`th element is equal to `key`
+fn is_keyed(element: Term, index: OneBasedIndex, key: Term) -> bool {
+    let result_tuple: Result<Boxed<Tuple>, _> = element.try_into();
+
+    match result_tuple {
+        Ok(tuple) => match tuple.get_element(index) {
+            Ok(candidate) => candidate == key,
+            Err(_) => false,
+        },
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, improper_list, reduce, reverse_2};
+
+#[native_implemented::function(lists:append/1)]
+pub fn result(process: &Process, lists: Term) -> exception::Result<Term> {
+    append(process, lists, Term::NIL, Term::NIL)
+}
+
+/// Copies the rest of `list` and then the elements of each of `lists`, except the last, onto
+/// `reversed`.  The last of `lists` is not copied, but becomes the tail of the appended list.
+fn append(process: &Process, lists: Term, list: Term, reversed: Term) -> exception::Result<Term> {
+    let mut lists = lists;
+    let mut list = list;
+    let mut reversed = reversed;
+
+    let elements_per_call = elements_per_call(process);
+
+    for elements in 0..elements_per_call {
+        match list.decode()? {
+            TypedTerm::Nil => match lists.decode()? {
+                TypedTerm::Nil => {
+                    reduce(process, elements);
+
+                    return reverse_2::result(process, reversed, Term::NIL);
+                }
+                TypedTerm::List(lists_cons) => {
+                    if lists_cons.tail.is_nil() {
+                        reduce(process, elements);
+
+                        return reverse_2::result(process, reversed, lists_cons.head);
+                    }
+
+                    list = lists_cons.head;
+                    lists = lists_cons.tail;
+                }
+                _ => return Err(improper_list("lists", lists)),
+            },
+            TypedTerm::List(cons) => {
+                reversed = process.cons(cons.head, reversed)?;
+                list = cons.tail;
+            }
+            _ => return Err(improper_list("list", list)),
+        }
+    }
+
+    reduce(process, elements_per_call);
+    process.queue_frame_with_arguments(
+        label_1::frame().with_arguments(false, &[lists, list, reversed]),
+    );
+
+    Ok(Term::NONE)
+}
@@ -0,0 +1,12 @@
+//! Continues `append` with the rest of `list` and then `lists` left to copy
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(process: &Process, lists: Term, list: Term, reversed: Term) -> exception::Result<Term> {
+    super::append(process, lists, list, reversed)
+}
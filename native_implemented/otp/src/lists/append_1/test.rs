@@ -0,0 +1,85 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::append_1::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn with_empty_list_returns_empty_list() {
+    with_process(|process| {
+        assert_eq!(result(process, Term::NIL), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_one_list_returns_it() {
+    with_process(|process| {
+        let lists = process.list_from_slice(&[atom!("improper")]).unwrap();
+
+        assert_eq!(result(process, lists), Ok(atom!("improper")));
+    });
+}
+
+#[test]
+fn with_lists_returns_their_elements_in_order_with_last_list_as_tail() {
+    with_process(|process| {
+        let lists = process
+            .list_from_slice(&[
+                process.list_from_slice(&[atom!("a"), atom!("b")]).unwrap(),
+                Term::NIL,
+                process.list_from_slice(&[atom!("c")]).unwrap(),
+                process.cons(atom!("d"), atom!("tail")).unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(process, lists),
+            Ok(process
+                .improper_list_from_slice(
+                    &[atom!("a"), atom!("b"), atom!("c"), atom!("d")],
+                    atom!("tail")
+                )
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_improper_list_before_last_list_errors_badarg() {
+    with_process(|process| {
+        let list = process.cons(atom!("a"), atom!("tail")).unwrap();
+        let lists = process.list_from_slice(&[list, Term::NIL]).unwrap();
+
+        assert_badarg!(result(process, lists), "is not a proper list");
+    });
+}
+
+#[test]
+fn with_more_elements_than_copied_per_call_yields_and_then_returns_appended_list() {
+    let len = ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let first = child_process.list_from_iter((0..len).map(|i| fixnum!(i)))?;
+            let second = child_process.list_from_iter((len..(2 * len)).map(|i| fixnum!(i)))?;
+            let third = child_process.list_from_slice(&[fixnum!(2 * len)])?;
+            let lists = child_process.list_from_slice(&[first, second, third])?;
+
+            Ok(vec![lists])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..=(2 * len)).map(|i| fixnum!(i)))
+            .unwrap())
+    );
+}
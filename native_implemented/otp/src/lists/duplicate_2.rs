@@ -0,0 +1,39 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, reduce};
+
+#[native_implemented::function(lists:duplicate/2)]
+pub fn result(process: &Process, n: Term, element: Term) -> exception::Result<Term> {
+    let n_usize = term_try_into_non_negative_integer!(n)?;
+
+    duplicate(process, n_usize, element, Term::NIL)
+}
+
+/// Conses `n` copies of `element` onto `tail`
+fn duplicate(process: &Process, n: usize, element: Term, tail: Term) -> exception::Result<Term> {
+    let consed = n.min(elements_per_call(process));
+    let mut list = tail;
+
+    for _ in 0..consed {
+        list = process.cons(element, list)?;
+    }
+
+    reduce(process, consed);
+
+    if consed < n {
+        process.queue_frame_with_arguments(
+            label_1::frame().with_arguments(false, &[process.integer(n - consed)?, element, list]),
+        );
+
+        Ok(Term::NONE)
+    } else {
+        Ok(list)
+    }
+}
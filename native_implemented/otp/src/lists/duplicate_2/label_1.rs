@@ -0,0 +1,14 @@
+//! Continues `duplicate` with the `n` copies of `element` left to cons
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(process: &Process, n: Term, element: Term, list: Term) -> exception::Result<Term> {
+    super::duplicate(process, n.try_into().unwrap(), element, list)
+}
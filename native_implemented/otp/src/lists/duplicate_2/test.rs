@@ -0,0 +1,59 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::duplicate_2::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_non_negative_n_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, fixnum!(-1), atom!("element")),
+            "n (-1) is not a non-negative integer"
+        );
+    });
+}
+
+#[test]
+fn with_zero_n_returns_empty_list() {
+    with_process(|process| {
+        assert_eq!(result(process, fixnum!(0), atom!("element")), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_positive_n_returns_n_copies_of_element() {
+    with_process(|process| {
+        let element = process.tuple_from_slice(&[atom!("element")]).unwrap();
+
+        assert_eq!(
+            result(process, fixnum!(3), element),
+            Ok(process
+                .list_from_slice(&[element, element, element])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_more_copies_than_consed_per_call_yields_and_then_returns_n_copies() {
+    let n = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |_| Ok(vec![fixnum!(n), atom!("element")])),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..n).map(|_| atom!("element")))
+            .unwrap())
+    );
+}
@@ -0,0 +1,62 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, improper_list, reduce, reverse_2};
+
+#[native_implemented::function(lists:flatten/1)]
+pub fn result(process: &Process, deep_list: Term) -> exception::Result<Term> {
+    flatten(process, deep_list, Term::NIL, Term::NIL)
+}
+
+/// Walks `list` depth-first, consing elements that are not lists onto `reversed`.  `stack` holds
+/// the tails of the outer lists to return to once `list` ends.
+fn flatten(process: &Process, list: Term, stack: Term, reversed: Term) -> exception::Result<Term> {
+    let mut list = list;
+    let mut stack = stack;
+    let mut reversed = reversed;
+
+    let elements_per_call = elements_per_call(process);
+
+    for elements in 0..elements_per_call {
+        match list.decode()? {
+            TypedTerm::Nil => match stack.decode()? {
+                TypedTerm::Nil => {
+                    reduce(process, elements);
+
+                    return reverse_2::result(process, reversed, Term::NIL);
+                }
+                TypedTerm::List(stack_cons) => {
+                    list = stack_cons.head;
+                    stack = stack_cons.tail;
+                }
+                _ => unreachable!(),
+            },
+            TypedTerm::List(cons) => {
+                if cons.head.is_list() {
+                    if !cons.tail.is_nil() {
+                        stack = process.cons(cons.tail, stack)?;
+                    }
+
+                    list = cons.head;
+                } else {
+                    reversed = process.cons(cons.head, reversed)?;
+                    list = cons.tail;
+                }
+            }
+            _ => return Err(improper_list("list", list)),
+        }
+    }
+
+    reduce(process, elements_per_call);
+    process.queue_frame_with_arguments(
+        label_1::frame().with_arguments(false, &[list, stack, reversed]),
+    );
+
+    Ok(Term::NONE)
+}
@@ -0,0 +1,12 @@
+//! Continues `flatten` with the rest of `list` and the outer lists' tails on `stack`
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(process: &Process, list: Term, stack: Term, reversed: Term) -> exception::Result<Term> {
+    super::flatten(process, list, stack, reversed)
+}
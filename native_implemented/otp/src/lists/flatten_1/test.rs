@@ -0,0 +1,75 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::flatten_1::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn with_improper_list_errors_badarg() {
+    with_process(|process| {
+        let deep_list = process
+            .list_from_slice(&[process.cons(atom!("a"), atom!("tail")).unwrap()])
+            .unwrap();
+
+        assert_badarg!(result(process, deep_list), "is not a proper list");
+    });
+}
+
+#[test]
+fn with_nested_lists_returns_their_elements_in_order() {
+    with_process(|process| {
+        let tuple = process.tuple_from_slice(&[Term::NIL]).unwrap();
+        let deep_list = process
+            .list_from_slice(&[
+                atom!("a"),
+                process
+                    .list_from_slice(&[
+                        Term::NIL,
+                        process.list_from_slice(&[atom!("b"), tuple]).unwrap(),
+                    ])
+                    .unwrap(),
+                atom!("c"),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            result(process, deep_list),
+            Ok(process
+                .list_from_slice(&[atom!("a"), atom!("b"), tuple, atom!("c")])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_more_elements_than_walked_per_call_yields_and_then_returns_flat_list() {
+    let len = 2 * ELEMENTS_PER_CALL;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            // [[0, 1], [2, 3], ...]
+            let mut deep_list = Term::NIL;
+
+            for i in (0..len).step_by(2).rev() {
+                let pair = child_process.list_from_slice(&[fixnum!(i), fixnum!(i + 1)])?;
+                deep_list = child_process.cons(pair, deep_list)?;
+            }
+
+            Ok(vec![deep_list])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..len).map(|i| fixnum!(i)))
+            .unwrap())
+    );
+}
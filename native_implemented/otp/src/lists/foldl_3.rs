@@ -0,0 +1,56 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{call, function_of_arity, improper_list, runs_frames};
+
+#[native_implemented::function(lists:foldl/3)]
+pub fn result(
+    process: &Process,
+    function: Term,
+    acc0: Term,
+    list: Term,
+) -> exception::Result<Term> {
+    function_of_arity(function, 2)?;
+
+    foldl(process, acc0, function, list)
+}
+
+/// Calls `function(head, acc)` and then folds the tail of `list` into what it returns
+fn foldl(process: &Process, acc: Term, function: Term, list: Term) -> exception::Result<Term> {
+    let mut acc = acc;
+    let mut list = list;
+
+    loop {
+        match list.decode()? {
+            TypedTerm::Nil => return Ok(acc),
+            TypedTerm::List(cons) => {
+                let boxed_closure = function_of_arity(function, 2)?;
+
+                if runs_frames(process) {
+                    process.queue_frame_with_arguments(
+                        boxed_closure.frame_with_arguments(false, vec![cons.head, acc]),
+                    );
+                    process.queue_frame_with_arguments(
+                        label_1::frame().with_arguments(true, &[function, cons.tail]),
+                    );
+
+                    return Ok(Term::NONE);
+                }
+
+                match call(boxed_closure, vec![cons.head, acc]) {
+                    Some(returned) => acc = returned,
+                    None => return Ok(Term::NONE),
+                }
+
+                list = cons.tail;
+            }
+            _ => return Err(improper_list("list", list)),
+        }
+    }
+}
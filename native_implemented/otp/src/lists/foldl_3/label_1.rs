@@ -0,0 +1,12 @@
+//! Folds the rest of `list` into the `acc` returned by `function`
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(process: &Process, acc: Term, function: Term, list: Term) -> exception::Result<Term> {
+    super::foldl(process, acc, function, list)
+}
@@ -0,0 +1,79 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::erlang;
+use crate::lists::foldl_3::{frame, result};
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_function_of_arity_2_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, atom!("function"), fixnum!(0), Term::NIL),
+            "function (function) is not a function of arity 2"
+        );
+    });
+}
+
+#[test]
+fn with_empty_list_returns_acc0() {
+    with_process(|process| {
+        let function = process
+            .export_closure(
+                erlang::module(),
+                erlang::add_2::function(),
+                erlang::add_2::ARITY,
+                Some(erlang::add_2::native as _),
+            )
+            .unwrap();
+
+        assert_eq!(
+            result(process, function, atom!("acc0"), Term::NIL),
+            Ok(atom!("acc0"))
+        );
+    });
+}
+
+#[test]
+fn with_proper_list_folds_elements_from_the_left() {
+    let n = 100;
+
+    let Ready { result, .. } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let function = child_process.export_closure(
+                erlang::module(),
+                erlang::add_2::function(),
+                erlang::add_2::ARITY,
+                Some(erlang::add_2::native as _),
+            )?;
+            let list = child_process.list_from_iter((1..=n).map(|i| fixnum!(i)))?;
+
+            Ok(vec![function, fixnum!(0), list])
+        }),
+    );
+
+    assert_eq!(result, Ok(fixnum!(n * (n + 1) / 2)));
+}
+
+#[test]
+fn with_improper_list_errors_badarg() {
+    let Ready { result, .. } = test::run_until_ready(
+        frame(),
+        Box::new(|child_process| {
+            let function = child_process.export_closure(
+                erlang::module(),
+                erlang::add_2::function(),
+                erlang::add_2::ARITY,
+                Some(erlang::add_2::native as _),
+            )?;
+            let list = child_process.cons(fixnum!(1), atom!("tail"))?;
+
+            Ok(vec![function, fixnum!(0), list])
+        }),
+    );
+
+    assert_badarg!(result, "list (tail) is not a proper list");
+}
@@ -0,0 +1,18 @@
+//! Continues `keydelete` with the rest of `tuple_list`
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    key: Term,
+    index: Term,
+    tuple_list: Term,
+    reversed: Term,
+) -> exception::Result<Term> {
+    super::keydelete(process, key, index, tuple_list, reversed)
+}
@@ -0,0 +1,63 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::keydelete_3::result;
+use crate::test::with_process;
+
+#[test]
+fn without_one_based_index_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, atom!("key"), fixnum!(0), Term::NIL),
+            "index (0) is not a 1-based integer"
+        );
+    });
+}
+
+#[test]
+fn with_key_deletes_first_tuple_with_key() {
+    with_process(|process| {
+        let one = process.integer(1).unwrap();
+        let float_one = process.float(1.0).unwrap();
+        let first = process.tuple_from_slice(&[atom!("a"), one]).unwrap();
+        let second = process.tuple_from_slice(&[atom!("b"), float_one]).unwrap();
+        let third = process.tuple_from_slice(&[atom!("c"), one]).unwrap();
+        let tuple_list = process
+            .list_from_slice(&[atom!("not_tuple"), first, second, third])
+            .unwrap();
+
+        // keys are compared with `==`, so `1.0` matches `1`
+        assert_eq!(
+            result(process, float_one, fixnum!(2), tuple_list),
+            Ok(process
+                .list_from_slice(&[atom!("not_tuple"), second, third])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn without_key_returns_equal_list() {
+    with_process(|process| {
+        let tuple = process.tuple_from_slice(&[atom!("a")]).unwrap();
+        let tuple_list = process.list_from_slice(&[tuple]).unwrap();
+
+        assert_eq!(
+            result(process, atom!("a"), fixnum!(2), tuple_list),
+            Ok(tuple_list)
+        );
+    });
+}
+
+#[test]
+fn with_improper_list_without_key_errors_badarg() {
+    with_process(|process| {
+        let tuple_list = process.cons(atom!("a"), atom!("tail")).unwrap();
+
+        assert_badarg!(
+            result(process, atom!("key"), fixnum!(1), tuple_list),
+            format!("tuple_list ({}) is not a proper list", tuple_list)
+        );
+    });
+}
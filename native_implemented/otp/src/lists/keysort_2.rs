@@ -0,0 +1,18 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::term_try_into_one_based_index;
+
+use super::merge_2::Order;
+use super::sort_1::sort;
+
+#[native_implemented::function(lists:keysort/2)]
+pub fn result(process: &Process, n: Term, tuple_list: Term) -> exception::Result<Term> {
+    let index = term_try_into_one_based_index(n)?;
+
+    sort(process, tuple_list, Order::Key(index))
+}
@@ -0,0 +1,94 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::keysort_2::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_one_based_index_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, fixnum!(0), Term::NIL),
+            "index (0) is not a 1-based integer"
+        );
+    });
+}
+
+#[test]
+fn without_tuple_with_key_errors_badarg() {
+    with_process(|process| {
+        let tuple = process.tuple_from_slice(&[atom!("a")]).unwrap();
+        let tuple_list = process.list_from_slice(&[tuple]).unwrap();
+
+        assert_badarg!(
+            result(process, fixnum!(2), tuple_list),
+            format!(
+                "tuple ({}) is not a tuple with an element at index (2)",
+                tuple
+            )
+        );
+    });
+}
+
+#[test]
+fn with_tuples_sorts_by_key_and_keeps_equal_keys_in_list_order() {
+    with_process(|process| {
+        let first = process
+            .tuple_from_slice(&[fixnum!(2), atom!("first")])
+            .unwrap();
+        let second = process
+            .tuple_from_slice(&[fixnum!(1), atom!("second")])
+            .unwrap();
+        let third = process
+            .tuple_from_slice(&[fixnum!(2), atom!("third")])
+            .unwrap();
+        let tuple_list = process.list_from_slice(&[first, second, third]).unwrap();
+
+        assert_eq!(
+            result(process, fixnum!(1), tuple_list),
+            Ok(process.list_from_slice(&[second, first, third]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_more_tuples_than_split_per_call_yields_and_then_sorts_by_key() {
+    let n = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let mut tuples = Vec::with_capacity(n);
+
+            for i in 0..n {
+                tuples.push(child_process.tuple_from_slice(&[fixnum!(n - i), fixnum!(i)])?);
+            }
+
+            let tuple_list = child_process.list_from_slice(&tuples)?;
+
+            Ok(vec![fixnum!(1), tuple_list])
+        }),
+    );
+
+    let sorted: Boxed<Cons> = result.unwrap().try_into().unwrap();
+    let keys: Vec<Term> = sorted
+        .into_iter()
+        .map(|result_element| {
+            let tuple: Boxed<Tuple> = result_element.unwrap().try_into().unwrap();
+
+            tuple[0]
+        })
+        .collect();
+
+    assert_eq!(keys, (1..=n).map(|key| fixnum!(key)).collect::<Vec<Term>>());
+
+    std::mem::drop(child_arc_process);
+}
@@ -0,0 +1,73 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::term_try_into_one_based_index;
+
+use super::{elements_per_call, improper_list, is_keyed, reduce, reverse_2};
+
+#[native_implemented::function(lists:keystore/4)]
+pub fn result(
+    process: &Process,
+    key: Term,
+    index: Term,
+    tuple_list: Term,
+    new_tuple: Term,
+) -> exception::Result<Term> {
+    term_try_into_one_based_index(index)?;
+    term_try_into_tuple!(new_tuple)?;
+
+    keystore(process, key, index, tuple_list, Term::NIL, new_tuple)
+}
+
+/// Copies the elements of `tuple_list` onto `reversed` until the first tuple with `key` at `index`,
+/// which is replaced by `new_tuple`.  Without such a tuple, `new_tuple` is appended.
+fn keystore(
+    process: &Process,
+    key: Term,
+    index: Term,
+    tuple_list: Term,
+    reversed: Term,
+    new_tuple: Term,
+) -> exception::Result<Term> {
+    let one_based_index = term_try_into_one_based_index(index)?;
+    let mut remaining = tuple_list;
+    let mut reversed = reversed;
+
+    let elements_per_call = elements_per_call(process);
+
+    for elements in 0..elements_per_call {
+        match remaining.decode()? {
+            TypedTerm::Nil => {
+                reduce(process, elements);
+                let tail = process.cons(new_tuple, Term::NIL)?;
+
+                return reverse_2::result(process, reversed, tail);
+            }
+            TypedTerm::List(cons) => {
+                if is_keyed(cons.head, one_based_index, key) {
+                    reduce(process, elements);
+                    let tail = process.cons(new_tuple, cons.tail)?;
+
+                    return reverse_2::result(process, reversed, tail);
+                }
+
+                reversed = process.cons(cons.head, reversed)?;
+                remaining = cons.tail;
+            }
+            _ => return Err(improper_list("tuple_list", tuple_list)),
+        }
+    }
+
+    reduce(process, elements_per_call);
+    process.queue_frame_with_arguments(
+        label_1::frame().with_arguments(false, &[key, index, remaining, reversed, new_tuple]),
+    );
+
+    Ok(Term::NONE)
+}
@@ -0,0 +1,19 @@
+//! Continues `keystore` with the rest of `tuple_list`
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    key: Term,
+    index: Term,
+    tuple_list: Term,
+    reversed: Term,
+    new_tuple: Term,
+) -> exception::Result<Term> {
+    super::keystore(process, key, index, tuple_list, reversed, new_tuple)
+}
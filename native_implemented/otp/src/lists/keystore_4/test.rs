@@ -0,0 +1,52 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::keystore_4::result;
+use crate::test::with_process;
+
+#[test]
+fn without_tuple_new_tuple_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(
+                process,
+                atom!("key"),
+                fixnum!(1),
+                Term::NIL,
+                atom!("new_tuple")
+            ),
+            "new_tuple (new_tuple) is not a tuple"
+        );
+    });
+}
+
+#[test]
+fn with_key_replaces_first_tuple_with_key() {
+    with_process(|process| {
+        let first = process.tuple_from_slice(&[atom!("a"), fixnum!(1)]).unwrap();
+        let second = process.tuple_from_slice(&[atom!("b"), fixnum!(2)]).unwrap();
+        let third = process.tuple_from_slice(&[atom!("b"), fixnum!(3)]).unwrap();
+        let tuple_list = process.list_from_slice(&[first, second, third]).unwrap();
+        let new_tuple = process.tuple_from_slice(&[atom!("b"), fixnum!(4)]).unwrap();
+
+        assert_eq!(
+            result(process, atom!("b"), fixnum!(1), tuple_list, new_tuple),
+            Ok(process.list_from_slice(&[first, new_tuple, third]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn without_key_appends_new_tuple() {
+    with_process(|process| {
+        let first = process.tuple_from_slice(&[atom!("a"), fixnum!(1)]).unwrap();
+        let tuple_list = process.list_from_slice(&[first]).unwrap();
+        let new_tuple = process.tuple_from_slice(&[atom!("b"), fixnum!(2)]).unwrap();
+
+        assert_eq!(
+            result(process, atom!("b"), fixnum!(1), tuple_list, new_tuple),
+            Ok(process.list_from_slice(&[first, new_tuple]).unwrap())
+        );
+    });
+}
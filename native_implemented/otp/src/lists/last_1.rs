@@ -0,0 +1,32 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, reduce};
+
+#[native_implemented::function(lists:last/1)]
+pub fn result(process: &Process, list: Term) -> exception::Result<Term> {
+    let mut cons = term_try_into_non_empty_list!(list)?;
+
+    let elements_per_call = elements_per_call(process);
+
+    for elements in 0..elements_per_call {
+        match cons.tail.decode()? {
+            TypedTerm::List(tail_cons) => cons = tail_cons,
+            _ => {
+                reduce(process, elements);
+
+                return Ok(cons.head);
+            }
+        }
+    }
+
+    reduce(process, elements_per_call);
+    // `last(list)` is `last(tail)` for any non-empty `tail`
+    process.queue_frame_with_arguments(frame().with_arguments(false, &[cons.into()]));
+
+    Ok(Term::NONE)
+}
@@ -0,0 +1,45 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::last_1::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn with_empty_list_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Term::NIL),
+            "list ([]) is not a non-empty list"
+        );
+    });
+}
+
+#[test]
+fn with_non_empty_list_returns_last_element() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[atom!("a"), atom!("b"), atom!("c")])
+            .unwrap();
+
+        assert_eq!(result(process, list), Ok(atom!("c")));
+    });
+}
+
+#[test]
+fn with_more_elements_than_walked_per_call_yields_and_then_returns_last_element() {
+    let len = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready { result, .. } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let list = child_process.list_from_iter((0..len).map(|i| fixnum!(i)))?;
+
+            Ok(vec![list])
+        }),
+    );
+
+    assert_eq!(result, Ok(fixnum!(len - 1)));
+}
@@ -0,0 +1,51 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{call, function_of_arity, improper_list, reverse_2, runs_frames};
+
+#[native_implemented::function(lists:map/2)]
+pub fn result(process: &Process, function: Term, list: Term) -> exception::Result<Term> {
+    function_of_arity(function, 1)?;
+
+    map(process, function, list, Term::NIL)
+}
+
+/// Calls `function(head)` and then maps the tail of `list`, consing each return onto `reversed`
+fn map(process: &Process, function: Term, list: Term, reversed: Term) -> exception::Result<Term> {
+    let mut list = list;
+    let mut reversed = reversed;
+
+    loop {
+        match list.decode()? {
+            TypedTerm::Nil => return reverse_2::result(process, reversed, Term::NIL),
+            TypedTerm::List(cons) => {
+                let boxed_closure = function_of_arity(function, 1)?;
+
+                if runs_frames(process) {
+                    process.queue_frame_with_arguments(
+                        boxed_closure.frame_with_arguments(false, vec![cons.head]),
+                    );
+                    process.queue_frame_with_arguments(
+                        label_1::frame().with_arguments(true, &[function, cons.tail, reversed]),
+                    );
+
+                    return Ok(Term::NONE);
+                }
+
+                match call(boxed_closure, vec![cons.head]) {
+                    Some(returned) => reversed = process.cons(returned, reversed)?,
+                    None => return Ok(Term::NONE),
+                }
+
+                list = cons.tail;
+            }
+            _ => return Err(improper_list("list", list)),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Conses what `function` returned onto `reversed` and then maps the rest of `list`
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    returned: Term,
+    function: Term,
+    list: Term,
+    reversed: Term,
+) -> exception::Result<Term> {
+    let reversed = process.cons(returned, reversed)?;
+
+    super::map(process, function, list, reversed)
+}
@@ -0,0 +1,48 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::erlang;
+use crate::lists::map_2::{frame, result};
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_function_of_arity_1_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, atom!("function"), Term::NIL),
+            "function (function) is not a function of arity 1"
+        );
+    });
+}
+
+#[test]
+fn with_proper_list_returns_function_of_each_element_in_order() {
+    let n: isize = 100;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let function = child_process.export_closure(
+                erlang::module(),
+                erlang::abs_1::function(),
+                erlang::abs_1::ARITY,
+                Some(erlang::abs_1::native as _),
+            )?;
+            let list = child_process.list_from_iter((0..n).map(|i| fixnum!(-i)))?;
+
+            Ok(vec![function, list])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..n).map(|i| fixnum!(i)))
+            .unwrap())
+    );
+}
@@ -0,0 +1,375 @@
+//! The sorts are bottom-up merge sorts.  Each pass merges the runs left from the previous pass in
+//! pairs.  Merging conses the smaller (or larger) head onto the merged run, so merged runs are in
+//! the opposite direction to the runs they are merged from.  Rather than reversing every merged
+//! run, each pass merges in the opposite direction to the last: ascending runs are merged into
+//! descending runs, which are merged into ascending runs.
+//!
+//! The merged runs are also listed in the opposite order to the runs they are merged from, so
+//! the first of a pair is the later run in ascending passes and the earlier run in descending
+//! passes.  Either way, equal elements are merged from the second of the pair first, which keeps
+//! the sorts stable.
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+mod label_2;
+
+use std::cmp::Ordering;
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, improper_list, reduce, runs_frames};
+
+/// Of equal elements, those from `list1` come first.
+#[native_implemented::function(lists:merge/2)]
+pub fn result(process: &Process, list1: Term, list2: Term) -> exception::Result<Term> {
+    // Runs are listed latest first, like `sort_1::split` lists them
+    let runs = process.list_from_slice(&[list2, list1])?;
+
+    merge_runs(process, runs, Order::Terms)
+}
+
+/// How elements are ordered
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Order {
+    /// Term order
+    Terms,
+    /// Term order, keeping only the first of equal elements
+    UniqueTerms,
+    /// Term order of the `index`th element of tuples
+    Key(OneBasedIndex),
+    /// `function(left, right)` returns whether `left` is before or equal to `right`
+    Function(Term),
+}
+
+impl Order {
+    /// Compares `left` to `right` without calling a function
+    pub(super) fn compare(self, left: Term, right: Term) -> exception::Result<Ordering> {
+        match self {
+            Order::Terms | Order::UniqueTerms => Ok(left.cmp(&right)),
+            Order::Key(index) => Ok(key(left, index)?.cmp(&key(right, index)?)),
+            Order::Function(_) => unreachable!("{:?} is compared by calling it", self),
+        }
+    }
+
+    pub(super) fn decode(term: Term) -> Self {
+        if term.is_boxed_function() {
+            Order::Function(term)
+        } else if term.is_integer() {
+            Order::Key(term.try_into().unwrap())
+        } else if term == atom!("usort") {
+            Order::UniqueTerms
+        } else {
+            Order::Terms
+        }
+    }
+
+    pub(super) fn encode(self, process: &Process) -> exception::Result<Term> {
+        match self {
+            Order::Terms => Ok(atom!("sort")),
+            Order::UniqueTerms => Ok(atom!("usort")),
+            Order::Key(index) => {
+                let zero_based_index: usize = index.into();
+
+                process.integer(zero_based_index + 1).map_err(From::from)
+            }
+            Order::Function(function) => Ok(function),
+        }
+    }
+}
+
+/// The `index`th element of `tuple`, which `keysort` orders by
+pub(super) fn key(tuple: Term, index: OneBasedIndex) -> exception::Result<Term> {
+    let result_boxed_tuple: Result<Boxed<Tuple>, _> = tuple.try_into();
+
+    match result_boxed_tuple
+        .ok()
+        .and_then(|boxed_tuple| boxed_tuple.get_element(index).ok())
+    {
+        Some(key) => Ok(key),
+        None => {
+            let zero_based_index: usize = index.into();
+
+            Err(anyhow!(
+                "tuple ({}) is not a tuple with an element at index ({})",
+                tuple,
+                zero_based_index + 1
+            )
+            .into())
+        }
+    }
+}
+
+/// Merges ascending `runs`, which are listed latest first, into one ascending list
+pub(super) fn merge_runs(process: &Process, runs: Term, order: Order) -> exception::Result<Term> {
+    match runs.decode()? {
+        TypedTerm::Nil => Ok(Term::NIL),
+        TypedTerm::List(cons) if cons.tail.is_nil() => Ok(cons.head),
+        _ => {
+            let mut passes = Passes {
+                runs,
+                merged: Term::NIL,
+                ascending: true,
+                order,
+            };
+
+            match passes.next()? {
+                Next::Merge { left, right } => merge(process, left, right, Term::NIL, passes),
+                Next::Sorted(sorted) => Ok(sorted),
+            }
+        }
+    }
+}
+
+// Private
+
+/// The runs left to merge in this pass and the runs already merged by it
+struct Passes {
+    runs: Term,
+    merged: Term,
+    /// Whether `runs` are ascending
+    ascending: bool,
+    order: Order,
+}
+
+impl Passes {
+    fn decode(term: Term) -> Self {
+        let tuple: Boxed<Tuple> = term.try_into().unwrap();
+
+        Self {
+            runs: tuple[0],
+            merged: tuple[1],
+            ascending: tuple[2].try_into().unwrap(),
+            order: Order::decode(tuple[3]),
+        }
+    }
+
+    fn encode(&self, process: &Process) -> exception::Result<Term> {
+        let order = self.order.encode(process)?;
+
+        process
+            .tuple_from_slice(&[self.runs, self.merged, self.ascending.into(), order])
+            .map_err(From::from)
+    }
+
+    /// Takes the next pair of runs to merge, starting the next pass when this one is done
+    fn next(&mut self) -> exception::Result<Next> {
+        if self.runs.is_nil() {
+            self.ascending = !self.ascending;
+
+            match self.merged.decode()? {
+                TypedTerm::List(cons) if cons.tail.is_nil() && self.ascending => {
+                    return Ok(Next::Sorted(cons.head))
+                }
+                _ => {
+                    self.runs = self.merged;
+                    self.merged = Term::NIL;
+                }
+            }
+        }
+
+        let runs_cons: Boxed<Cons> = self.runs.try_into().unwrap();
+        let left = runs_cons.head;
+
+        // A run without a pair is merged with the empty list, which reverses its direction like
+        // the other merged runs
+        let right = match runs_cons.tail.decode()? {
+            TypedTerm::List(tail_cons) => {
+                self.runs = tail_cons.tail;
+
+                tail_cons.head
+            }
+            _ => {
+                self.runs = Term::NIL;
+
+                Term::NIL
+            }
+        };
+
+        Ok(Next::Merge { left, right })
+    }
+
+    /// The arguments to call an order function with to compare `left_head` to `right_head`.  The
+    /// function returns whether its first argument is before or equal to its second, so it is
+    /// called with the arguments that make `true` mean `right` is first.
+    fn arguments(&self, left_head: Term, right_head: Term) -> Vec<Term> {
+        if self.ascending {
+            vec![right_head, left_head]
+        } else {
+            vec![left_head, right_head]
+        }
+    }
+
+    /// Whether the head of `right` is merged before the head of `left` when `left` compares
+    /// `ordering` to `right`
+    fn is_right_first(&self, ordering: Ordering) -> bool {
+        if self.ascending {
+            ordering != Ordering::Less
+        } else {
+            ordering != Ordering::Greater
+        }
+    }
+}
+
+enum Next {
+    Merge { left: Term, right: Term },
+    Sorted(Term),
+}
+
+/// Merges `left` and `right` onto `reversed`, and then the rest of the `passes`
+fn merge(
+    process: &Process,
+    left: Term,
+    right: Term,
+    reversed: Term,
+    passes: Passes,
+) -> exception::Result<Term> {
+    let mut left = left;
+    let mut right = right;
+    let mut reversed = reversed;
+    let mut passes = passes;
+
+    let elements_per_call = elements_per_call(process);
+
+    for elements in 0..elements_per_call {
+        match (left.decode()?, right.decode()?) {
+            (TypedTerm::Nil, TypedTerm::Nil) => {
+                passes.merged = process.cons(reversed, passes.merged)?;
+
+                match passes.next()? {
+                    Next::Merge {
+                        left: next_left,
+                        right: next_right,
+                    } => {
+                        left = next_left;
+                        right = next_right;
+                        reversed = Term::NIL;
+                    }
+                    Next::Sorted(sorted) => {
+                        reduce(process, elements);
+
+                        return Ok(sorted);
+                    }
+                }
+            }
+            (TypedTerm::List(left_cons), TypedTerm::Nil) => {
+                reversed = process.cons(left_cons.head, reversed)?;
+                left = left_cons.tail;
+            }
+            (TypedTerm::Nil, TypedTerm::List(right_cons)) => {
+                reversed = process.cons(right_cons.head, reversed)?;
+                right = right_cons.tail;
+            }
+            (TypedTerm::List(left_cons), TypedTerm::List(right_cons)) => match passes.order {
+                Order::Function(function) => {
+                    if runs_frames(process) {
+                        reduce(process, elements);
+
+                        return call(process, function, left, right, reversed, passes);
+                    }
+
+                    let boxed_closure: Boxed<Closure> = function.try_into().unwrap();
+                    let arguments = passes.arguments(left_cons.head, right_cons.head);
+
+                    let is_right_first = match super::call(boxed_closure, arguments) {
+                        Some(returned) => term_try_into_bool!(returned)?,
+                        None => return Ok(Term::NONE),
+                    };
+
+                    if is_right_first {
+                        reversed = process.cons(right_cons.head, reversed)?;
+                        right = right_cons.tail;
+                    } else {
+                        reversed = process.cons(left_cons.head, reversed)?;
+                        left = left_cons.tail;
+                    }
+                }
+                order => {
+                    let ordering = order.compare(left_cons.head, right_cons.head)?;
+
+                    if let (Order::UniqueTerms, Ordering::Equal) = (order, ordering) {
+                        // Only the earlier of the pair's equal elements is kept, which is the
+                        // second's in ascending passes and the first's in descending passes
+                        if passes.ascending {
+                            left = left_cons.tail;
+                        } else {
+                            right = right_cons.tail;
+                        }
+                    } else if passes.is_right_first(ordering) {
+                        reversed = process.cons(right_cons.head, reversed)?;
+                        right = right_cons.tail;
+                    } else {
+                        reversed = process.cons(left_cons.head, reversed)?;
+                        left = left_cons.tail;
+                    }
+                }
+            },
+            (TypedTerm::Nil, _) | (TypedTerm::List(_), _) => {
+                return Err(improper_list("list", right))
+            }
+            _ => return Err(improper_list("list", left)),
+        }
+    }
+
+    reduce(process, elements_per_call);
+    process.queue_frame_with_arguments(
+        label_1::frame().with_arguments(false, &[left, right, reversed, passes.encode(process)?]),
+    );
+
+    Ok(Term::NONE)
+}
+
+/// Calls `function` to order the heads of `left` and `right`, which `label_2` merges
+fn call(
+    process: &Process,
+    function: Term,
+    left: Term,
+    right: Term,
+    reversed: Term,
+    passes: Passes,
+) -> exception::Result<Term> {
+    let boxed_closure: Boxed<Closure> = function.try_into().unwrap();
+    let left_head = left.try_into().map(|cons: Boxed<Cons>| cons.head).unwrap();
+    let right_head = right.try_into().map(|cons: Boxed<Cons>| cons.head).unwrap();
+
+    let arguments = passes.arguments(left_head, right_head);
+
+    process.queue_frame_with_arguments(boxed_closure.frame_with_arguments(false, arguments));
+    process.queue_frame_with_arguments(
+        label_2::frame().with_arguments(true, &[left, right, reversed, passes.encode(process)?]),
+    );
+
+    Ok(Term::NONE)
+}
+
+/// Merges the head of `right` if `is_right_first`, otherwise the head of `left`, and then
+/// continues merging
+fn merge_called(
+    process: &Process,
+    is_right_first: bool,
+    left: Term,
+    right: Term,
+    reversed: Term,
+    passes: Passes,
+) -> exception::Result<Term> {
+    let (head, left, right) = if is_right_first {
+        let right_cons: Boxed<Cons> = right.try_into().unwrap();
+
+        (right_cons.head, left, right_cons.tail)
+    } else {
+        let left_cons: Boxed<Cons> = left.try_into().unwrap();
+
+        (left_cons.head, left_cons.tail, right)
+    };
+
+    let reversed = process.cons(head, reversed)?;
+
+    merge(process, left, right, reversed, passes)
+}
@@ -0,0 +1,20 @@
+//! Continues `merge` with the rest of `left` and `right`, and the rest of `passes`
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::Passes;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    left: Term,
+    right: Term,
+    reversed: Term,
+    passes: Term,
+) -> exception::Result<Term> {
+    super::merge(process, left, right, reversed, Passes::decode(passes))
+}
@@ -0,0 +1,30 @@
+//! Continues `merge` once the order function returns whether the head of `right` is first
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::Passes;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    is_right_first: Term,
+    left: Term,
+    right: Term,
+    reversed: Term,
+    passes: Term,
+) -> exception::Result<Term> {
+    let is_right_first = term_try_into_bool!(is_right_first)?;
+
+    super::merge_called(
+        process,
+        is_right_first,
+        left,
+        right,
+        reversed,
+        Passes::decode(passes),
+    )
+}
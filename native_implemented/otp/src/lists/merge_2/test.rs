@@ -0,0 +1,106 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::merge_2::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn with_empty_lists_returns_empty_list() {
+    with_process(|process| {
+        assert_eq!(result(process, Term::NIL, Term::NIL), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_empty_list2_returns_list1() {
+    with_process(|process| {
+        let list1 = process.list_from_slice(&[fixnum!(1), fixnum!(2)]).unwrap();
+
+        assert_eq!(result(process, list1, Term::NIL), Ok(list1));
+    });
+}
+
+#[test]
+fn with_sorted_lists_returns_sorted_list() {
+    with_process(|process| {
+        let list1 = process
+            .list_from_slice(&[fixnum!(1), fixnum!(4), fixnum!(5)])
+            .unwrap();
+        let list2 = process
+            .list_from_slice(&[fixnum!(2), fixnum!(3), fixnum!(6)])
+            .unwrap();
+
+        assert_eq!(
+            result(process, list1, list2),
+            Ok(process
+                .list_from_slice(&[
+                    fixnum!(1),
+                    fixnum!(2),
+                    fixnum!(3),
+                    fixnum!(4),
+                    fixnum!(5),
+                    fixnum!(6)
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_equal_elements_returns_list1_element_first() {
+    with_process(|process| {
+        let float_one = process.float(1.0).unwrap();
+        let list1 = process.list_from_slice(&[float_one]).unwrap();
+        let list2 = process.list_from_slice(&[fixnum!(1)]).unwrap();
+
+        assert_eq!(
+            result(process, list1, list2),
+            Ok(process.list_from_slice(&[float_one, fixnum!(1)]).unwrap())
+        );
+        assert_eq!(
+            result(process, list2, list1),
+            Ok(process.list_from_slice(&[fixnum!(1), float_one]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_improper_list_errors_badarg() {
+    with_process(|process| {
+        let list1 = process.list_from_slice(&[fixnum!(1)]).unwrap();
+        let list2 = process.cons(fixnum!(2), atom!("tail")).unwrap();
+
+        assert_badarg!(
+            result(process, list1, list2),
+            format!("list ({}) is not a proper list", atom!("tail"))
+        );
+    });
+}
+
+#[test]
+fn with_more_elements_than_merged_per_call_yields_and_then_returns_sorted_list() {
+    let n = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let evens = child_process.list_from_iter((0..n).map(|i| fixnum!(2 * i)))?;
+            let odds = child_process.list_from_iter((0..n).map(|i| fixnum!(2 * i + 1)))?;
+
+            Ok(vec![evens, odds])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..(2 * n)).map(|i| fixnum!(i)))
+            .unwrap())
+    );
+}
@@ -0,0 +1,46 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::term_try_into_one_based_index;
+
+use super::{elements_per_call, reduce};
+
+#[native_implemented::function(lists:nth/2)]
+pub fn result(process: &Process, n: Term, list: Term) -> exception::Result<Term> {
+    let n_usize: usize = term_try_into_one_based_index(n)?.into();
+    let walked = (n_usize - 1).min(elements_per_call(process));
+    let mut tail = list;
+
+    for _ in 0..walked {
+        match tail.decode()? {
+            TypedTerm::List(cons) => tail = cons.tail,
+            _ => return Err(fewer_than_n_elements(list, n)),
+        }
+    }
+
+    reduce(process, walked);
+
+    if walked < n_usize - 1 {
+        // `nth(n, list)` is `nth(n - walked, tail)`
+        process.queue_frame_with_arguments(
+            frame().with_arguments(false, &[process.integer(n_usize - walked)?, tail]),
+        );
+
+        Ok(Term::NONE)
+    } else {
+        match tail.decode()? {
+            TypedTerm::List(cons) => Ok(cons.head),
+            _ => Err(fewer_than_n_elements(list, n)),
+        }
+    }
+}
+
+fn fewer_than_n_elements(list: Term, n: Term) -> exception::Exception {
+    anyhow!("list ({}) has fewer than n ({}) elements", list, n).into()
+}
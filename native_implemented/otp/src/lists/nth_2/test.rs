@@ -0,0 +1,60 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::nth_2::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_one_based_index_errors_badarg() {
+    with_process(|process| {
+        let list = process.list_from_slice(&[fixnum!(1)]).unwrap();
+
+        assert_badarg!(
+            result(process, fixnum!(0), list),
+            "index (0) is not a 1-based integer"
+        );
+    });
+}
+
+#[test]
+fn with_n_in_list_returns_nth_element() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[atom!("a"), atom!("b"), atom!("c")])
+            .unwrap();
+
+        assert_eq!(result(process, fixnum!(1), list), Ok(atom!("a")));
+        assert_eq!(result(process, fixnum!(3), list), Ok(atom!("c")));
+    });
+}
+
+#[test]
+fn with_n_past_end_of_list_errors_badarg() {
+    with_process(|process| {
+        let list = process.list_from_slice(&[atom!("a")]).unwrap();
+
+        assert_badarg!(
+            result(process, fixnum!(2), list),
+            format!("list ({}) has fewer than n (2) elements", list)
+        );
+    });
+}
+
+#[test]
+fn with_n_past_elements_walked_per_call_yields_and_then_returns_nth_element() {
+    let len = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready { result, .. } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let list = child_process.list_from_iter((0..len).map(|i| fixnum!(i)))?;
+
+            Ok(vec![fixnum!(len), list])
+        }),
+    );
+
+    assert_eq!(result, Ok(fixnum!(len - 1)));
+}
@@ -0,0 +1,37 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, reduce};
+
+#[native_implemented::function(lists:nthtail/2)]
+pub fn result(process: &Process, n: Term, list: Term) -> exception::Result<Term> {
+    let n_usize = term_try_into_non_negative_integer!(n)?;
+    let walked = n_usize.min(elements_per_call(process));
+    let mut tail = list;
+
+    for _ in 0..walked {
+        match tail.decode()? {
+            TypedTerm::List(cons) => tail = cons.tail,
+            _ => return Err(anyhow!("list ({}) has fewer than n ({}) elements", list, n).into()),
+        }
+    }
+
+    reduce(process, walked);
+
+    if walked < n_usize {
+        // `nthtail(n, list)` is `nthtail(n - walked, tail)`
+        process.queue_frame_with_arguments(
+            frame().with_arguments(false, &[process.integer(n_usize - walked)?, tail]),
+        );
+
+        Ok(Term::NONE)
+    } else {
+        Ok(tail)
+    }
+}
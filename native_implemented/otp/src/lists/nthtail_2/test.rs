@@ -0,0 +1,78 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::nthtail_2::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_non_negative_n_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, fixnum!(-1), Term::NIL),
+            "n (-1) is not a non-negative integer"
+        );
+    });
+}
+
+#[test]
+fn with_zero_n_returns_list() {
+    with_process(|process| {
+        let list = atom!("improper");
+
+        assert_eq!(result(process, fixnum!(0), list), Ok(list));
+    });
+}
+
+#[test]
+fn with_n_in_list_returns_tail_after_n_elements() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[atom!("a"), atom!("b"), atom!("c")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, fixnum!(1), list),
+            Ok(process.list_from_slice(&[atom!("b"), atom!("c")]).unwrap())
+        );
+        assert_eq!(result(process, fixnum!(3), list), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_n_past_end_of_list_errors_badarg() {
+    with_process(|process| {
+        let list = process.list_from_slice(&[atom!("a")]).unwrap();
+
+        assert_badarg!(
+            result(process, fixnum!(2), list),
+            format!("list ({}) has fewer than n (2) elements", list)
+        );
+    });
+}
+
+#[test]
+fn with_n_past_elements_walked_per_call_yields_and_then_returns_tail() {
+    let len = 2 * ELEMENTS_PER_CALL + 2;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let list = child_process.list_from_iter((0..len).map(|i| fixnum!(i)))?;
+
+            Ok(vec![fixnum!(len - 1), list])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_slice(&[fixnum!(len - 1)])
+            .unwrap())
+    );
+}
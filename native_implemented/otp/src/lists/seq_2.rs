@@ -0,0 +1,25 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::seq_3;
+
+#[native_implemented::function(lists:seq/2)]
+pub fn result(process: &Process, first: Term, last: Term) -> exception::Result<Term> {
+    let first_isize = term_try_into_isize!(first)?;
+    let last_isize = term_try_into_isize!(last)?;
+
+    // `first - 1 == last` is the empty list
+    if first_isize <= last_isize.saturating_add(1) {
+        let len = ((last_isize as i128) - (first_isize as i128) + 1) as usize;
+
+        seq_3::seq(process, len, last_isize, 1, Term::NIL)
+    } else {
+        Err(anyhow!("last ({}) is less than first ({}) - 1", last, first).into())
+    }
+}
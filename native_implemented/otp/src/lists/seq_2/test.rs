@@ -0,0 +1,46 @@
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::seq_2::result;
+use crate::test::with_process;
+
+#[test]
+fn without_integer_first_errors_badarg() {
+    with_process(|process| {
+        let first = Atom::str_to_term("first");
+
+        assert_badarg!(
+            result(process, first, fixnum!(2)),
+            format!("first ({}) is not an integer", first)
+        );
+    });
+}
+
+#[test]
+fn with_last_greater_than_first_counts_up_to_last() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, fixnum!(-1), fixnum!(2)),
+            Ok(process
+                .list_from_slice(&[fixnum!(-1), fixnum!(0), fixnum!(1), fixnum!(2)])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_last_one_less_than_first_returns_empty_list() {
+    with_process(|process| {
+        assert_eq!(result(process, fixnum!(3), fixnum!(2)), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_last_more_than_one_less_than_first_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, fixnum!(3), fixnum!(1)),
+            "last (1) is less than first (3) - 1"
+        );
+    });
+}
@@ -0,0 +1,85 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, reduce};
+
+#[native_implemented::function(lists:seq/3)]
+pub fn result(
+    process: &Process,
+    first: Term,
+    last: Term,
+    increment: Term,
+) -> exception::Result<Term> {
+    let first_isize = term_try_into_isize!(first)?;
+    let last_isize = term_try_into_isize!(last)?;
+    let increment_isize = term_try_into_isize!(increment)?;
+
+    // `i128`, so that stepping past `first` or `last` can't overflow
+    let first_i128 = first_isize as i128;
+    let last_i128 = last_isize as i128;
+    let increment_i128 = increment_isize as i128;
+
+    let len = if (0 < increment_i128 && first_i128 - increment_i128 <= last_i128)
+        || (increment_i128 < 0 && last_i128 <= first_i128 - increment_i128)
+    {
+        ((last_i128 - first_i128 + increment_i128) / increment_i128) as usize
+    } else if increment_i128 == 0 && first_i128 == last_i128 {
+        1
+    } else {
+        return Err(anyhow!(
+            "last ({}) cannot be reached from first ({}) by increment ({})",
+            last,
+            first,
+            increment
+        )
+        .into());
+    };
+
+    let last_element = first_isize + (len as isize - 1) * increment_isize;
+
+    seq(process, len, last_element, increment_isize, Term::NIL)
+}
+
+/// Conses the `len` integers counting down by `increment` from `element` onto `tail`
+pub(super) fn seq(
+    process: &Process,
+    len: usize,
+    element: isize,
+    increment: isize,
+    tail: Term,
+) -> exception::Result<Term> {
+    let walked = len.min(elements_per_call(process));
+    let mut element = element;
+    let mut list = tail;
+
+    for _ in 0..walked {
+        list = process.cons(process.integer(element)?, list)?;
+        element -= increment;
+    }
+
+    reduce(process, walked);
+
+    if walked < len {
+        process.queue_frame_with_arguments(label_1::frame().with_arguments(
+            false,
+            &[
+                process.integer(len - walked)?,
+                process.integer(element)?,
+                process.integer(increment)?,
+                list,
+            ],
+        ));
+
+        Ok(Term::NONE)
+    } else {
+        Ok(list)
+    }
+}
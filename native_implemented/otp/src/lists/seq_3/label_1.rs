@@ -0,0 +1,26 @@
+//! Continues `seq` with the `len` elements left to cons after `element`
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    len: Term,
+    element: Term,
+    increment: Term,
+    list: Term,
+) -> exception::Result<Term> {
+    super::seq(
+        process,
+        len.try_into().unwrap(),
+        element.try_into().unwrap(),
+        increment.try_into().unwrap(),
+        list,
+    )
+}
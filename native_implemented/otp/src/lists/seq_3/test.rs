@@ -0,0 +1,93 @@
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::seq_3::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_integer_increment_errors_badarg() {
+    with_process(|process| {
+        let increment = Atom::str_to_term("increment");
+
+        assert_badarg!(
+            result(process, fixnum!(1), fixnum!(2), increment),
+            format!("increment ({}) is not an integer", increment)
+        );
+    });
+}
+
+#[test]
+fn with_positive_increment_counts_up_to_last() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, fixnum!(1), fixnum!(8), fixnum!(3)),
+            Ok(process
+                .list_from_slice(&[fixnum!(1), fixnum!(4), fixnum!(7)])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_negative_increment_counts_down_to_last() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, fixnum!(3), fixnum!(-1), fixnum!(-2)),
+            Ok(process
+                .list_from_slice(&[fixnum!(3), fixnum!(1), fixnum!(-1)])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_last_one_increment_before_first_returns_empty_list() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, fixnum!(5), fixnum!(3), fixnum!(2)),
+            Ok(Term::NIL)
+        );
+    });
+}
+
+#[test]
+fn with_zero_increment_and_last_equal_to_first_returns_first() {
+    with_process(|process| {
+        assert_eq!(
+            result(process, fixnum!(5), fixnum!(5), fixnum!(0)),
+            Ok(process.list_from_slice(&[fixnum!(5)]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_increment_away_from_last_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, fixnum!(1), fixnum!(10), fixnum!(-1)),
+            "last (10) cannot be reached from first (1) by increment (-1)"
+        );
+    });
+}
+
+#[test]
+fn with_more_elements_than_consed_per_call_yields_and_then_counts_up_to_last() {
+    let len = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |_| Ok(vec![fixnum!(0), fixnum!(2 * (len - 1)), fixnum!(2)])),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..len).map(|i| fixnum!(2 * i)))
+            .unwrap())
+    );
+}
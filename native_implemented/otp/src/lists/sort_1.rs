@@ -0,0 +1,106 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use std::cmp::Ordering;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::merge_2::{self, Order};
+use super::{elements_per_call, improper_list, reduce};
+
+#[native_implemented::function(lists:sort/1)]
+pub fn result(process: &Process, list: Term) -> exception::Result<Term> {
+    sort(process, list, Order::Terms)
+}
+
+/// Sorts `list` stably by `order`
+pub(super) fn sort(process: &Process, list: Term, order: Order) -> exception::Result<Term> {
+    split(process, list, list, Term::NIL, order)
+}
+
+/// Splits `remaining` into sorted runs of up to `elements_per_call` elements, which are consed
+/// onto `runs` and then merged.  When `order` is a function, every run is a single element, as
+/// only merging calls the function.
+fn split(
+    process: &Process,
+    list: Term,
+    remaining: Term,
+    runs: Term,
+    order: Order,
+) -> exception::Result<Term> {
+    let mut remaining = remaining;
+    let mut run = Vec::new();
+
+    let elements_per_call = elements_per_call(process);
+
+    while run.len() < elements_per_call {
+        match remaining.decode()? {
+            TypedTerm::Nil => break,
+            TypedTerm::List(cons) => {
+                if let Order::Function(_) = order {
+                    let singleton = process.list_from_slice(&[cons.head])?;
+                    run.push(singleton);
+                } else {
+                    run.push(cons.head);
+                }
+
+                remaining = cons.tail;
+            }
+            _ => return Err(improper_list("list", list)),
+        }
+    }
+
+    reduce(process, run.len());
+
+    let runs = match order {
+        Order::Function(_) => run
+            .into_iter()
+            .try_fold(runs, |runs, singleton| process.cons(singleton, runs))?,
+        _ => {
+            sort_run(&mut run, order)?;
+
+            if remaining.is_nil() && runs.is_nil() {
+                return process.list_from_slice(&run).map_err(From::from);
+            }
+
+            process.cons(process.list_from_slice(&run)?, runs)?
+        }
+    };
+
+    if remaining.is_nil() {
+        merge_2::merge_runs(process, runs, order)
+    } else {
+        process.queue_frame_with_arguments(
+            label_1::frame()
+                .with_arguments(false, &[list, remaining, runs, order.encode(process)?]),
+        );
+
+        Ok(Term::NONE)
+    }
+}
+
+/// Sorts `run` stably without calling a function
+fn sort_run(run: &mut Vec<Term>, order: Order) -> exception::Result<()> {
+    match order {
+        Order::Terms => run.sort(),
+        Order::UniqueTerms => {
+            run.sort();
+            run.dedup_by(|later, earlier| later == earlier);
+        }
+        Order::Key(index) => {
+            // Check every element has a key before sorting, so that comparing can't fail
+            for element in run.iter() {
+                merge_2::key(*element, index)?;
+            }
+
+            run.sort_by(|left, right| order.compare(*left, *right).unwrap_or(Ordering::Equal));
+        }
+        Order::Function(_) => unreachable!("{:?} only sorts singleton runs", order),
+    }
+
+    Ok(())
+}
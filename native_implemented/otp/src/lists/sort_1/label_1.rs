@@ -0,0 +1,20 @@
+//! Continues splitting the `remaining` elements of `list` into sorted runs
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::lists::merge_2::Order;
+
+// Private
+
+#[native_implemented::label]
+fn result(
+    process: &Process,
+    list: Term,
+    remaining: Term,
+    runs: Term,
+    order: Term,
+) -> exception::Result<Term> {
+    super::split(process, list, remaining, runs, Order::decode(order))
+}
@@ -0,0 +1,100 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::sort_1::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn with_empty_list_returns_empty_list() {
+    with_process(|process| {
+        assert_eq!(result(process, Term::NIL), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_improper_list_errors_badarg() {
+    with_process(|process| {
+        let list = process
+            .improper_list_from_slice(&[fixnum!(2), fixnum!(1)], atom!("tail"))
+            .unwrap();
+
+        assert_badarg!(
+            result(process, list),
+            format!("list ({}) is not a proper list", list)
+        );
+    });
+}
+
+#[test]
+fn with_proper_list_returns_list_in_term_order() {
+    with_process(|process| {
+        let tuple = process.tuple_from_slice(&[]).unwrap();
+        let list = process
+            .list_from_slice(&[tuple, fixnum!(2), atom!("atom"), fixnum!(1)])
+            .unwrap();
+
+        assert_eq!(
+            result(process, list),
+            Ok(process
+                .list_from_slice(&[fixnum!(1), fixnum!(2), atom!("atom"), tuple])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_equal_elements_keeps_them_in_list_order() {
+    with_process(|process| {
+        let float_one = process.float(1.0).unwrap();
+        let list = process
+            .list_from_slice(&[fixnum!(1), fixnum!(0), float_one])
+            .unwrap();
+
+        assert_eq!(
+            result(process, list),
+            Ok(process
+                .list_from_slice(&[fixnum!(0), fixnum!(1), float_one])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_more_elements_than_split_per_call_yields_and_then_returns_sorted_list() {
+    let n = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            // Descending odd numbers interleaved with zeros, so every run is merged
+            let list = child_process.list_from_iter((0..n).map(|i| {
+                if i % 2 == 0 {
+                    fixnum!(n - i)
+                } else {
+                    fixnum!(0)
+                }
+            }))?;
+
+            Ok(vec![list])
+        }),
+    );
+
+    let zeros = n / 2;
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter(
+                (0..zeros)
+                    .map(|_| fixnum!(0))
+                    .chain((0..(n - zeros)).map(|i| fixnum!(2 * i + 1)))
+            )
+            .unwrap())
+    );
+}
@@ -0,0 +1,18 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::function_of_arity;
+use super::merge_2::Order;
+use super::sort_1::sort;
+
+/// `function(a, b)` returns whether `a` is before or equal to `b`
+#[native_implemented::function(lists:sort/2)]
+pub fn result(process: &Process, function: Term, list: Term) -> exception::Result<Term> {
+    function_of_arity(function, 2)?;
+
+    sort(process, list, Order::Function(function))
+}
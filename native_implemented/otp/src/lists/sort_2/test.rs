@@ -0,0 +1,85 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::erlang;
+use crate::lists::sort_2::{frame, result};
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_function_of_arity_2_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, atom!("function"), Term::NIL),
+            "function (function) is not a function of arity 2"
+        );
+    });
+}
+
+#[test]
+fn with_equal_or_less_than_sorts_ascending_and_keeps_equal_elements_in_list_order() {
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(|child_process| {
+            let function = child_process.export_closure(
+                erlang::module(),
+                erlang::is_equal_or_less_than_2::function(),
+                erlang::is_equal_or_less_than_2::ARITY,
+                Some(erlang::is_equal_or_less_than_2::native as _),
+            )?;
+            let float_one = child_process.float(1.0)?;
+            let list = child_process.list_from_slice(&[
+                fixnum!(3),
+                fixnum!(1),
+                fixnum!(2),
+                float_one,
+                fixnum!(0),
+            ])?;
+
+            Ok(vec![function, list])
+        }),
+    );
+
+    let float_one = child_arc_process.float(1.0).unwrap();
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_slice(&[fixnum!(0), fixnum!(1), float_one, fixnum!(2), fixnum!(3)])
+            .unwrap())
+    );
+}
+
+#[test]
+fn with_greater_than_or_equal_sorts_descending() {
+    let n = 100;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let function = child_process.export_closure(
+                erlang::module(),
+                erlang::is_greater_than_or_equal_2::function(),
+                erlang::is_greater_than_or_equal_2::ARITY,
+                Some(erlang::is_greater_than_or_equal_2::native as _),
+            )?;
+            let list = child_process.list_from_iter((0..n).map(|i| fixnum!(i)))?;
+
+            Ok(vec![function, list])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..n).rev().map(|i| fixnum!(i)))
+            .unwrap())
+    );
+}
@@ -0,0 +1,58 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+mod label_1;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{elements_per_call, improper_list, reduce, reverse_2};
+
+#[native_implemented::function(lists:sublist/2)]
+pub fn result(process: &Process, list: Term, len: Term) -> exception::Result<Term> {
+    let len_usize = term_try_into_non_negative_integer!(len)?;
+
+    sublist(process, list, len_usize, Term::NIL)
+}
+
+/// Takes up to `len` elements from `list` onto `reversed`, which is reversed once `len` elements
+/// are taken or `list` ends.
+pub(super) fn sublist(
+    process: &Process,
+    list: Term,
+    len: usize,
+    reversed: Term,
+) -> exception::Result<Term> {
+    let walked = len.min(elements_per_call(process));
+    let mut remaining = list;
+    let mut reversed = reversed;
+
+    for elements in 0..walked {
+        match remaining.decode()? {
+            TypedTerm::Nil => {
+                reduce(process, elements);
+
+                return reverse_2::result(process, reversed, Term::NIL);
+            }
+            TypedTerm::List(cons) => {
+                reversed = process.cons(cons.head, reversed)?;
+                remaining = cons.tail;
+            }
+            _ => return Err(improper_list("list", list)),
+        }
+    }
+
+    reduce(process, walked);
+
+    if walked < len {
+        process.queue_frame_with_arguments(label_1::frame().with_arguments(
+            false,
+            &[remaining, process.integer(len - walked)?, reversed],
+        ));
+
+        Ok(Term::NONE)
+    } else {
+        reverse_2::result(process, reversed, Term::NIL)
+    }
+}
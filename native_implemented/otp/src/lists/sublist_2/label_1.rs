@@ -0,0 +1,14 @@
+//! Continues `sublist` with `len` elements left to take from `list`
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+// Private
+
+#[native_implemented::label]
+fn result(process: &Process, list: Term, len: Term, reversed: Term) -> exception::Result<Term> {
+    super::sublist(process, list, len.try_into().unwrap(), reversed)
+}
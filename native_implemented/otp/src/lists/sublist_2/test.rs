@@ -0,0 +1,78 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::sublist_2::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn without_non_negative_len_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Term::NIL, fixnum!(-1)),
+            "len (-1) is not a non-negative integer"
+        );
+    });
+}
+
+#[test]
+fn with_len_less_than_list_length_returns_first_len_elements() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[atom!("a"), atom!("b"), atom!("c")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, list, fixnum!(2)),
+            Ok(process.list_from_slice(&[atom!("a"), atom!("b")]).unwrap())
+        );
+        assert_eq!(result(process, list, fixnum!(0)), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_len_greater_than_list_length_returns_list() {
+    with_process(|process| {
+        let list = process.list_from_slice(&[atom!("a"), atom!("b")]).unwrap();
+
+        assert_eq!(result(process, list, fixnum!(3)), Ok(list));
+    });
+}
+
+#[test]
+fn with_improper_list_shorter_than_len_errors_badarg() {
+    with_process(|process| {
+        let list = process.cons(atom!("a"), atom!("tail")).unwrap();
+
+        assert_badarg!(
+            result(process, list, fixnum!(2)),
+            format!("list ({}) is not a proper list", list)
+        );
+    });
+}
+
+#[test]
+fn with_len_past_elements_taken_per_call_yields_and_then_returns_first_len_elements() {
+    let len = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let list = child_process.list_from_iter((0..(len + 1)).map(|i| fixnum!(i)))?;
+
+            Ok(vec![list, fixnum!(len)])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_iter((0..len).map(|i| fixnum!(i)))
+            .unwrap())
+    );
+}
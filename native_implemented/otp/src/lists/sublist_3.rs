@@ -0,0 +1,47 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::runtime::context::term_try_into_one_based_index;
+
+use super::{elements_per_call, reduce, sublist_2};
+
+#[native_implemented::function(lists:sublist/3)]
+pub fn result(process: &Process, list: Term, start: Term, len: Term) -> exception::Result<Term> {
+    let start_usize: usize = term_try_into_one_based_index(start)?.into();
+    let len_usize = term_try_into_non_negative_integer!(len)?;
+    let walked = (start_usize - 1).min(elements_per_call(process));
+    let mut tail = list;
+
+    for _ in 0..walked {
+        match tail.decode()? {
+            TypedTerm::List(cons) => tail = cons.tail,
+            _ => {
+                return Err(anyhow!(
+                    "start ({}) is more than one past the end of list ({})",
+                    start,
+                    list
+                )
+                .into())
+            }
+        }
+    }
+
+    reduce(process, walked);
+
+    if walked < start_usize - 1 {
+        // `sublist(list, start, len)` is `sublist(tail, start - walked, len)`
+        process.queue_frame_with_arguments(
+            frame().with_arguments(false, &[tail, process.integer(start_usize - walked)?, len]),
+        );
+
+        Ok(Term::NONE)
+    } else {
+        sublist_2::sublist(process, tail, len_usize, Term::NIL)
+    }
+}
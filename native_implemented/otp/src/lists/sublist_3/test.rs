@@ -0,0 +1,55 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::sublist_3::result;
+use crate::test::with_process;
+
+#[test]
+fn without_one_based_start_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            result(process, Term::NIL, fixnum!(0), fixnum!(1)),
+            "index (0) is not a 1-based integer"
+        );
+    });
+}
+
+#[test]
+fn with_start_in_list_returns_len_elements_from_start() {
+    with_process(|process| {
+        let list = process
+            .list_from_slice(&[atom!("a"), atom!("b"), atom!("c"), atom!("d")])
+            .unwrap();
+
+        assert_eq!(
+            result(process, list, fixnum!(2), fixnum!(2)),
+            Ok(process.list_from_slice(&[atom!("b"), atom!("c")]).unwrap())
+        );
+        assert_eq!(
+            result(process, list, fixnum!(4), fixnum!(2)),
+            Ok(process.list_from_slice(&[atom!("d")]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_start_one_past_end_of_list_returns_empty_list() {
+    with_process(|process| {
+        let list = process.list_from_slice(&[atom!("a")]).unwrap();
+
+        assert_eq!(result(process, list, fixnum!(2), fixnum!(1)), Ok(Term::NIL));
+    });
+}
+
+#[test]
+fn with_start_more_than_one_past_end_of_list_errors_badarg() {
+    with_process(|process| {
+        let list = process.list_from_slice(&[atom!("a")]).unwrap();
+
+        assert_badarg!(
+            result(process, list, fixnum!(3), fixnum!(1)),
+            format!("start (3) is more than one past the end of list ({})", list)
+        );
+    });
+}
@@ -0,0 +1,15 @@
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::merge_2::Order;
+use super::sort_1::sort;
+
+/// Of equal elements, only the first is kept.
+#[native_implemented::function(lists:usort/1)]
+pub fn result(process: &Process, list: Term) -> exception::Result<Term> {
+    sort(process, list, Order::UniqueTerms)
+}
@@ -0,0 +1,59 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::fixnum;
+
+use crate::lists::usort_1::{frame, result};
+use crate::lists::ELEMENTS_PER_CALL;
+use crate::runtime::future::Ready;
+use crate::test::{self, with_process};
+
+#[test]
+fn with_improper_list_errors_badarg() {
+    with_process(|process| {
+        let list = process.cons(fixnum!(1), atom!("tail")).unwrap();
+
+        assert_badarg!(
+            result(process, list),
+            format!("list ({}) is not a proper list", list)
+        );
+    });
+}
+
+#[test]
+fn with_equal_elements_keeps_first() {
+    with_process(|process| {
+        let float_one = process.float(1.0).unwrap();
+        let list = process
+            .list_from_slice(&[fixnum!(2), float_one, fixnum!(2), fixnum!(1)])
+            .unwrap();
+
+        assert_eq!(
+            result(process, list),
+            Ok(process.list_from_slice(&[float_one, fixnum!(2)]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_equal_elements_in_different_runs_yields_and_then_keeps_first() {
+    let n = 2 * ELEMENTS_PER_CALL + 1;
+
+    let Ready {
+        arc_process: child_arc_process,
+        result,
+    } = test::run_until_ready(
+        frame(),
+        Box::new(move |child_process| {
+            let list = child_process.list_from_iter((0..n).map(|i| fixnum!(i % 3)))?;
+
+            Ok(vec![list])
+        }),
+    );
+
+    assert_eq!(
+        result,
+        Ok(child_arc_process
+            .list_from_slice(&[fixnum!(0), fixnum!(1), fixnum!(2)])
+            .unwrap())
+    );
+}
@@ -46,6 +46,12 @@ macro_rules! term_try_into_non_empty_list {
     };
 }
 
+macro_rules! term_try_into_non_negative_integer {
+    ($name:ident) => {
+        crate::runtime::context::term_try_into_non_negative_integer(stringify!($name), $name)
+    };
+}
+
 macro_rules! term_try_into_time_unit {
     ($name:ident) => {
         crate::runtime::context::term_try_into_time_unit(stringify!($name), $name)
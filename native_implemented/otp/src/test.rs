@@ -23,11 +23,14 @@ use std::convert::TryInto;
 use std::sync::Arc;
 
 use liblumen_alloc::atom;
-use liblumen_alloc::erts::exception;
-use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::erts::exception::{self, AllocResult};
+use liblumen_alloc::erts::process::{Frame, Process, Status};
 use liblumen_alloc::erts::term::prelude::*;
 
 use crate::erlang::{self, exit_1};
+use crate::runtime;
+use crate::runtime::future::Ready;
+use crate::runtime::process::spawn::options::Options;
 use crate::runtime::scheduler::{Scheduled, SchedulerDependentAlloc};
 use crate::runtime::time::{monotonic, Milliseconds};
 use crate::runtime::timer;
@@ -118,6 +121,28 @@ pub fn module_id() -> usize {
     module().id()
 }
 
+/// Runs `frame` with the `arguments` allocated on a new process's heap until the process returns,
+/// for functions that yield or call other functions instead of returning directly.  The returned
+/// `Ready` holds the process, so that the result stays on its heap.
+pub fn run_until_ready(
+    frame: Frame,
+    arguments: Box<dyn FnOnce(&Process) -> AllocResult<Vec<Term>>>,
+) -> Ready {
+    let mut options: Options = Default::default();
+    options.min_heap_size = Some(16_000);
+
+    runtime::future::run_until_ready(
+        options,
+        Box::new(move |child_process| {
+            let arguments = arguments(child_process)?;
+
+            Ok(vec![frame.with_arguments(false, &arguments)])
+        }),
+        100,
+    )
+    .unwrap()
+}
+
 pub fn with_big_int(f: fn(&Process, Term) -> ()) {
     with_process(|process| {
         let big_int: Term = process.integer(SmallInteger::MAX_VALUE + 1).unwrap();
@@ -0,0 +1,104 @@
+//! The environment of the applications compiled into the executable
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, HeapFragment, Process};
+
+use crate::distribution::external_term_format::{term, version};
+
+static ENV: AtomicPtr<u8> = AtomicPtr::new(ptr::null_mut());
+static ENV_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+/// Performs one-time initialization of the application environment at program start, using
+/// the environment encoded by the compiler in the external term format.
+///
+/// It is expected that this will be called by code generated by the compiler, during the
+/// earliest phase of startup, before any process can call `application:get_env/2`.
+#[no_mangle]
+pub unsafe extern "C" fn InitializeLumenAppEnv(env: *const u8, len: usize) -> bool {
+    if env.is_null() {
+        return false;
+    }
+    ENV_SIZE.store(len, Ordering::SeqCst);
+    ENV.store(env as *mut u8, Ordering::SeqCst);
+    true
+}
+
+/// Returns the value of `key` in the environment of `application`, if set.  Only the value is
+/// copied to the heap of `process`.
+pub fn get_env(process: &Process, application: Atom, key: Atom) -> exception::Result<Option<Term>> {
+    let mut decoded = DECODED.lock();
+
+    let apps = match *decoded {
+        Some(ref decoded) => decoded.apps,
+        None => match decode()? {
+            Some(new_decoded) => decoded.get_or_insert(new_decoded).apps,
+            None => return Ok(None),
+        },
+    };
+
+    let app_env = match find_value(apps, application.encode()?)? {
+        Some(app_env) => app_env,
+        None => return Ok(None),
+    };
+
+    match find_value(app_env, key.encode()?)? {
+        Some(value) => Ok(Some(value.clone_to_heap(&mut *process.acquire_heap())?)),
+        None => Ok(None),
+    }
+}
+
+// Private
+
+/// The environment decoded on the first call to `get_env`.  It is never freed, as it lives as
+/// long as the program.
+struct Decoded {
+    /// A list of `{Application, [{Key, Value}]}` tuples
+    apps: Term,
+    #[allow(dead_code)]
+    heap_fragment: NonNull<HeapFragment>,
+}
+
+// The `HeapFragment` is never written after decoding and is only read under `DECODED`'s lock
+unsafe impl Send for Decoded {}
+
+lazy_static! {
+    static ref DECODED: Mutex<Option<Decoded>> = Default::default();
+}
+
+fn decode() -> exception::Result<Option<Decoded>> {
+    let env = ENV.load(Ordering::SeqCst);
+    if env.is_null() {
+        return Ok(None);
+    }
+    let bytes = unsafe { slice::from_raw_parts(env, ENV_SIZE.load(Ordering::SeqCst)) };
+
+    let after_version_bytes = version::check(bytes)?;
+    let (apps, heap_fragment, _) = term::decode_tagged_to_fragment(false, after_version_bytes)?;
+
+    Ok(Some(Decoded {
+        apps,
+        heap_fragment,
+    }))
+}
+
+/// Returns `Value` of the first `{Key, Value}` tuple in `list` with a key of `key`
+fn find_value(list: Term, key: Term) -> exception::Result<Option<Term>> {
+    match list.decode()? {
+        TypedTerm::List(cons) => match cons.keyfind(Default::default(), key)? {
+            Some(found) => match found.decode()? {
+                TypedTerm::Tuple(tuple) => Ok(tuple.elements().get(1).copied()),
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
@@ -148,6 +148,12 @@ pub fn term_try_into_non_empty_list(name: &str, value: Term) -> anyhow::Result<B
         .with_context(|| term_is_not_non_empty_list(name, value))
 }
 
+pub fn term_try_into_non_negative_integer(name: &str, value: Term) -> anyhow::Result<usize> {
+    value
+        .try_into()
+        .with_context(|| term_is_not_non_negative_integer(name, value))
+}
+
 pub fn term_try_into_one_based_index(index: Term) -> anyhow::Result<OneBasedIndex> {
     index
         .try_into()
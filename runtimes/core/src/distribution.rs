@@ -1,2 +1,291 @@
+mod connection;
+pub mod control;
+pub mod epmd;
 pub mod external_term_format;
+pub mod handshake;
 pub mod nodes;
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::*;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::RuntimeException;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+
+pub use connection::{Connection, Monitored};
+
+use self::nodes::node;
+
+/// Makes the local node alive as `name`, which is either `alive@host` or just `alive` for the
+/// local host.  Other nodes can connect once `name` is registered with the EPMD on `epmd_port`.
+pub fn start(name: &str, cookie: &str, epmd_port: u16) -> anyhow::Result<()> {
+    if is_alive() {
+        return Err(anyhow!("node is already alive as {}", node::atom()));
+    }
+
+    let (alive_name, host) = split_node_name(name)?;
+    let listener = TcpListener::bind(("0.0.0.0", 0)).context("could not listen for nodes")?;
+    let port = listener.local_addr()?.port();
+    let registration = epmd::register(epmd_port, &alive_name, port)?;
+
+    let full_name = Atom::try_from_str(&format!("{}@{}", alive_name, host))?;
+    nodes::set_name_and_creation(&node::arc_node(), full_name, registration.creation);
+
+    *ALIVE.write() = Some(Alive {
+        cookie: cookie.to_string(),
+        epmd_port,
+        _registration: registration,
+    });
+
+    thread::Builder::new()
+        .name("distribution acceptor".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().filter_map(Result::ok) {
+                thread::spawn(move || {
+                    // A failed handshake only affects the node that tried to connect
+                    let _ = accept(stream);
+                });
+            }
+        })?;
+
+    Ok(())
+}
+
+/// The connection to `node`, connecting if there is no connection yet.
+///
+/// Looking up `node` with EPMD, connecting and the handshake happen on a background thread, so
+/// that processes don't block their scheduler.  Until the handshake completes, anything written
+/// to the connection is queued.  If connecting fails, links and monitors through the connection
+/// are broken with `noconnection` and the queued messages are dropped, like messages to a dead
+/// local process.
+pub fn connect(node: Atom) -> anyhow::Result<Arc<Connection>> {
+    if let Some(connection) = connection(node) {
+        return Ok(connection);
+    }
+
+    let (cookie, epmd_port) = match ALIVE.read().as_ref() {
+        Some(alive) => (alive.cookie.clone(), alive.epmd_port),
+        None => return Err(anyhow!("local node is not alive")),
+    };
+
+    let connection = match CONNECTION_BY_NODE.entry(node) {
+        // Another process started connecting to `node` first
+        Entry::Occupied(occupied) => return Ok(occupied.get().clone()),
+        Entry::Vacant(vacant) => {
+            let connection = Arc::new(Connection::connecting(arc_node(node)));
+            vacant.insert(connection.clone());
+
+            connection
+        }
+    };
+
+    let connecting = connection.clone();
+    let spawned = thread::Builder::new()
+        .name(format!("{} connector", node))
+        .spawn(move || {
+            if initiate(&connecting, &cookie, epmd_port).is_err() {
+                connecting.disconnected();
+                remove_connection(&connecting);
+            }
+        });
+
+    match spawned {
+        Ok(_) => Ok(connection),
+        Err(error) => {
+            remove_connection(&connection);
+
+            Err(error.into())
+        }
+    }
+}
+
+/// The connection to `node` if it is connected
+pub fn connection(node: Atom) -> Option<Arc<Connection>> {
+    CONNECTION_BY_NODE
+        .get(&node)
+        .map(|connection| connection.value().clone())
+}
+
+/// Removes the monitor with `reference` by `monitoring_pid` of a process on another node.  Returns
+/// whether there was such a monitor.
+pub fn demonitor(monitoring_pid: Pid, reference: &Reference) -> bool {
+    CONNECTION_BY_NODE
+        .iter()
+        .any(|connection| connection.value().demonitor(monitoring_pid, reference))
+}
+
+pub fn is_alive() -> bool {
+    ALIVE.read().is_some()
+}
+
+/// The names of the connected nodes.  Nodes that are still being connected to are left out.
+pub fn nodes() -> Vec<Atom> {
+    CONNECTION_BY_NODE
+        .iter()
+        .filter(|connection| connection.value().is_connected())
+        .map(|connection| *connection.key())
+        .collect()
+}
+
+/// Sends the exit of `process` to the links and monitors on other nodes
+pub fn propagate_exit(process: &Process, exception: &RuntimeException) {
+    let reason = exception.reason().unwrap_or_else(|| atom!("system_error"));
+
+    for connection in CONNECTION_BY_NODE.iter() {
+        connection.value().propagate_exit(process.pid(), reason);
+    }
+}
+
+// Private
+
+struct Alive {
+    cookie: String,
+    epmd_port: u16,
+    /// Keeps the name registered with EPMD
+    _registration: epmd::Registration,
+}
+
+lazy_static! {
+    static ref ALIVE: RwLock<Option<Alive>> = Default::default();
+    static ref CONNECTION_BY_NODE: DashMap<Atom, Arc<Connection>> = Default::default();
+}
+
+fn accept(mut stream: TcpStream) -> anyhow::Result<()> {
+    let cookie = match ALIVE.read().as_ref() {
+        Some(alive) => alive.cookie.clone(),
+        None => return Err(anyhow!("local node is not alive")),
+    };
+
+    let local_name = node::atom();
+    let local = handshake::Local {
+        name: local_name.name(),
+        creation: node::arc_node().creation(),
+        cookie: &cookie,
+    };
+    let peer = handshake::accept(&mut stream, &local, |name| {
+        Atom::try_from_str(name)
+            .map(|atom| CONNECTION_BY_NODE.contains_key(&atom))
+            .unwrap_or(false)
+    })?;
+
+    let name = Atom::try_from_str(&peer.name)?;
+    let arc_node = arc_node(name);
+    set_creation(&arc_node, peer.creation);
+
+    let connection = Arc::new(Connection::new(arc_node, peer.flags, stream.try_clone()?));
+    CONNECTION_BY_NODE.insert(name, connection.clone());
+
+    run(&connection, stream)
+}
+
+/// The node named `name`, which is added to the known nodes if this is the first time it is seen
+fn arc_node(name: Atom) -> Arc<Node> {
+    match nodes::atom_to_arc_node(&name) {
+        Some(arc_node) => arc_node,
+        None => {
+            // The creation is not known until the handshake
+            let arc_node = Arc::new(Node::new(nodes::next_id(), name, 0));
+            nodes::insert(arc_node.clone());
+
+            arc_node
+        }
+    }
+}
+
+/// Looks up the other node with EPMD, connects to it and performs the handshake for `connection`
+/// that was returned by `connect` before it was connected
+fn initiate(connection: &Arc<Connection>, cookie: &str, epmd_port: u16) -> anyhow::Result<()> {
+    let arc_node = connection.arc_node();
+    let node = arc_node.name();
+    let (alive_name, host) = split_node_name(node.name())?;
+    let port = epmd::port_please(&host, epmd_port, &alive_name)?
+        .with_context(|| format!("node ({}) is not registered with EPMD", node))?;
+    let mut stream = TcpStream::connect((host.as_str(), port))
+        .with_context(|| format!("could not connect to node ({})", node))?;
+
+    let local_name = node::atom();
+    let local = handshake::Local {
+        name: local_name.name(),
+        creation: node::arc_node().creation(),
+        cookie,
+    };
+    let peer = handshake::initiate(&mut stream, &local)?;
+    set_creation(&arc_node, peer.creation);
+    connection.connected(peer.flags, stream.try_clone()?);
+
+    run(connection, stream)
+}
+
+fn remove_connection(connection: &Arc<Connection>) {
+    CONNECTION_BY_NODE.remove_if(&connection.arc_node().name(), |_, registered| {
+        Arc::ptr_eq(registered, connection)
+    });
+}
+
+/// Starts ticking and receiving on `connection` after a successful handshake
+fn run(connection: &Arc<Connection>, receive_stream: TcpStream) -> anyhow::Result<()> {
+    connection.spawn_ticker()?;
+
+    let receive_connection = connection.clone();
+    thread::Builder::new()
+        .name(format!("{} receiver", connection.arc_node().name()))
+        .spawn(move || {
+            receive_connection.receive(receive_stream);
+            remove_connection(&receive_connection);
+        })?;
+
+    Ok(())
+}
+
+fn set_creation(arc_node: &Arc<Node>, creation: u32) {
+    // The node restarted since its pids and references were last seen
+    if arc_node.creation() != creation {
+        nodes::set_name_and_creation(arc_node, arc_node.name(), creation);
+    }
+}
+
+/// Splits `alive@host` into `alive` and `host`.  Without a host, the short name of the local host
+/// is used, like `erl -sname`.
+fn split_node_name(name: &str) -> anyhow::Result<(String, String)> {
+    match name.find('@') {
+        Some(index) => Ok((name[..index].to_string(), name[index + 1..].to_string())),
+        None => {
+            let host_name = host_name()?;
+            let short_host_name = host_name.split('.').next().unwrap();
+
+            Ok((name.to_string(), short_host_name.to_string()))
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn host_name() -> anyhow::Result<String> {
+    let mut buffer = [0 as libc::c_char; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr(), buffer.len()) };
+
+    if result == 0 {
+        let c_str = unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) };
+
+        Ok(c_str
+            .to_str()
+            .context("host name is not UTF-8")?
+            .to_string())
+    } else {
+        Err(anyhow!("could not get host name"))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn host_name() -> anyhow::Result<String> {
+    Err(anyhow!("host name is not available on wasm32"))
+}
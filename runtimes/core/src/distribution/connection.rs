@@ -0,0 +1,633 @@
+use std::io::{self, Read, Write};
+use std::mem;
+use std::net::{Shutdown, TcpStream};
+use std::ptr::{self, NonNull};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::*;
+use dashmap::{DashMap, DashSet};
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::{Pid as LocalPid, Reference as LocalReference, *};
+use liblumen_alloc::erts::{HeapFragment, Node};
+use liblumen_alloc::CloneToProcess;
+
+use crate::distribution::control::{self, Control};
+use crate::distribution::external_term_format::{term, version, Pid, Reference};
+use crate::registry::{atom_to_process, pid_to_process};
+use crate::scheduler;
+use crate::send;
+
+/// What a local process monitors on the other node
+#[derive(Clone, Debug)]
+pub enum Monitored {
+    Pid(ExternalPid),
+    Name(Atom),
+}
+
+/// A connection to another node after a successful handshake.  Links and monitors between local
+/// processes and processes on the other node are kept here instead of in the processes, so that
+/// they can be broken with `noconnection` when the connection is lost.
+pub struct Connection {
+    arc_node: Arc<Node>,
+    flags: AtomicU64,
+    output: Mutex<Output>,
+    links: DashSet<(LocalPid, ExternalPid)>,
+    /// Monitors by local processes of processes on the other node
+    outgoing_monitor_by_reference: DashMap<LocalReference, OutgoingMonitor>,
+    /// Monitors by processes on the other node of local processes
+    incoming_monitors_by_pid: DashMap<LocalPid, Vec<IncomingMonitor>>,
+    next_unlink_id: AtomicU64,
+}
+
+impl Connection {
+    pub fn new(arc_node: Arc<Node>, flags: u64, stream: TcpStream) -> Self {
+        let connection = Self::connecting(arc_node);
+        connection.connected(flags, stream);
+
+        connection
+    }
+
+    /// A connection whose handshake has not completed yet.  Control messages written to it are
+    /// queued until it is `connected`.
+    pub fn connecting(arc_node: Arc<Node>) -> Self {
+        Self {
+            arc_node,
+            flags: AtomicU64::new(0),
+            output: Mutex::new(Output::Connecting(Vec::new())),
+            links: Default::default(),
+            outgoing_monitor_by_reference: Default::default(),
+            incoming_monitors_by_pid: Default::default(),
+            next_unlink_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Writes the control messages queued while connecting to `stream` after the handshake
+    pub fn connected(&self, flags: u64, mut stream: TcpStream) {
+        self.flags.store(flags, Ordering::SeqCst);
+
+        let mut output = self.output.lock();
+
+        if let Output::Connecting(frames) = &*output {
+            let written = frames.iter().all(|frame| stream.write_all(frame).is_ok());
+
+            if !written {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+
+            *output = Output::Connected(stream);
+        } else {
+            // Connecting already failed, so the other node is told the same
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    pub fn arc_node(&self) -> Arc<Node> {
+        self.arc_node.clone()
+    }
+
+    /// The distribution flags of the other node
+    pub fn flags(&self) -> u64 {
+        self.flags.load(Ordering::SeqCst)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        match *self.output.lock() {
+            Output::Connected(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn demonitor(&self, monitoring_pid: LocalPid, reference: &LocalReference) -> bool {
+        match self
+            .outgoing_monitor_by_reference
+            .remove_if(reference, |_, monitor| {
+                monitor.monitoring_pid == monitoring_pid
+            }) {
+            Some((_, monitor)) => {
+                self.write(
+                    Control::DemonitorP {
+                        from: Pid::Local(monitoring_pid),
+                        to: monitor.monitored.into(),
+                        reference: Reference::Local(*reference),
+                    },
+                    None,
+                );
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Breaks the links and monitors through the connection with `noconnection`, either because the
+    /// other node disconnected or because connecting to it failed
+    pub fn disconnected(&self) {
+        if let Output::Connected(stream) =
+            mem::replace(&mut *self.output.lock(), Output::Disconnected)
+        {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+
+        let noconnection = atom!("noconnection");
+
+        for link in self.links.iter() {
+            let (local_pid, external_pid) = link.key();
+
+            if let Some(arc_process) = pid_to_process(local_pid) {
+                let _ = exit_signal(&arc_process, external_pid, |_| Ok(noconnection));
+            }
+        }
+        self.links.clear();
+
+        for entry in self.outgoing_monitor_by_reference.iter() {
+            let monitor = entry.value();
+
+            if let Some(arc_process) = pid_to_process(&monitor.monitoring_pid) {
+                let _ = self.down(&arc_process, entry.key(), &monitor.monitored, |_| {
+                    Ok(noconnection)
+                });
+            }
+        }
+        self.outgoing_monitor_by_reference.clear();
+        self.incoming_monitors_by_pid.clear();
+    }
+
+    pub fn link(&self, from: LocalPid, to: &ExternalPid) {
+        if self.links.insert((from, to.clone())) {
+            self.write(
+                Control::Link {
+                    from: Pid::Local(from),
+                    to: Pid::External(to.clone()),
+                },
+                None,
+            );
+        }
+    }
+
+    pub fn monitor(
+        &self,
+        monitoring_pid: LocalPid,
+        reference: LocalReference,
+        monitored: Monitored,
+    ) {
+        self.outgoing_monitor_by_reference.insert(
+            reference,
+            OutgoingMonitor {
+                monitoring_pid,
+                monitored: monitored.clone(),
+            },
+        );
+
+        self.write(
+            Control::MonitorP {
+                from: Pid::Local(monitoring_pid),
+                to: monitored.into(),
+                reference: Reference::Local(reference),
+            },
+            None,
+        );
+    }
+
+    /// Sends the exit of the local process with `pid` to its links and monitors on the other node
+    pub fn propagate_exit(&self, pid: LocalPid, reason: Term) {
+        let linked_external_pids: Vec<ExternalPid> = self
+            .links
+            .iter()
+            .filter(|link| link.0 == pid)
+            .map(|link| link.1.clone())
+            .collect();
+
+        for external_pid in linked_external_pids {
+            self.links.remove(&(pid, external_pid.clone()));
+            self.write(
+                Control::Exit {
+                    from: Pid::Local(pid),
+                    to: Pid::External(external_pid),
+                },
+                Some(reason),
+            );
+        }
+
+        if let Some((_, incoming_monitors)) = self.incoming_monitors_by_pid.remove(&pid) {
+            for incoming_monitor in incoming_monitors {
+                let from = match incoming_monitor.monitored_name {
+                    Some(name) => control::Process::Name(name),
+                    None => control::Process::Pid(Pid::Local(pid)),
+                };
+
+                self.write(
+                    Control::MonitorPExit {
+                        from,
+                        to: Pid::External(incoming_monitor.monitoring_pid),
+                        reference: Reference::External(incoming_monitor.reference),
+                    },
+                    Some(reason),
+                );
+            }
+        }
+
+        let references: Vec<LocalReference> = self
+            .outgoing_monitor_by_reference
+            .iter()
+            .filter(|entry| entry.value().monitoring_pid == pid)
+            .map(|entry| *entry.key())
+            .collect();
+
+        for reference in references {
+            self.demonitor(pid, &reference);
+        }
+    }
+
+    /// Reads control messages until the other node disconnects
+    pub fn receive(&self, mut stream: TcpStream) {
+        loop {
+            match read_frame(&mut stream) {
+                // tick
+                Ok(frame) if frame.is_empty() => continue,
+                Ok(frame) => {
+                    // A bad message only affects its destination, so the connection stays up
+                    let _ = self.handle(&frame);
+                }
+                Err(_) => break,
+            }
+        }
+
+        self.disconnected();
+    }
+
+    pub fn reg_send(&self, from: LocalPid, to_name: Atom, message: Term) {
+        self.write(
+            Control::RegSend {
+                from: Pid::Local(from),
+                to_name,
+            },
+            Some(message),
+        );
+    }
+
+    pub fn send(&self, to: &ExternalPid, message: Term) {
+        self.write(
+            Control::Send {
+                to: Pid::External(to.clone()),
+            },
+            Some(message),
+        );
+    }
+
+    /// Sends ticks on a background thread, so the other node does not think this node is down when
+    /// no messages are sent.
+    pub fn spawn_ticker(self: &Arc<Self>) -> io::Result<()> {
+        let weak = Arc::downgrade(self);
+
+        thread::Builder::new()
+            .name(format!("{} ticker", self.arc_node.name()))
+            .spawn(move || loop {
+                thread::sleep(TICK_INTERVAL);
+
+                match weak.upgrade() {
+                    Some(connection) => match &mut *connection.output.lock() {
+                        Output::Connected(stream) => {
+                            let _ = stream.write_all(&0_u32.to_be_bytes());
+                        }
+                        _ => break,
+                    },
+                    None => break,
+                }
+            })
+            .map(|_| ())
+    }
+
+    pub fn unlink(&self, from: LocalPid, to: &ExternalPid) {
+        if self.links.remove(&(from, to.clone())).is_some() {
+            let id = self.next_unlink_id.fetch_add(1, Ordering::SeqCst);
+
+            self.write(
+                Control::UnlinkId {
+                    id,
+                    from: Pid::Local(from),
+                    to: Pid::External(to.clone()),
+                },
+                None,
+            );
+        }
+    }
+
+    // Private
+
+    /// Sends `{'DOWN', reference, process, identifier, info}` to `arc_process`, where `info` is
+    /// built in the same heap fragment as the message
+    fn down<I>(
+        &self,
+        arc_process: &Process,
+        reference: &LocalReference,
+        monitored: &Monitored,
+        info: I,
+    ) -> InternalResult<()>
+    where
+        I: Fn(&mut HeapFragment) -> InternalResult<Term>,
+    {
+        let (down, heap_fragment) = term::to_fragment(0, |heap| {
+            let info = info(heap)?;
+            let reference_term: Term = heap
+                .reference(reference.scheduler_id(), reference.number())?
+                .into();
+            let identifier = match monitored {
+                Monitored::Pid(external_pid) => external_pid.clone_to_heap(heap)?,
+                Monitored::Name(name) => heap
+                    .tuple_from_slice(&[
+                        name.encode().unwrap(),
+                        self.arc_node.name().encode().unwrap(),
+                    ])?
+                    .into(),
+            };
+            let down = heap.tuple_from_slice(&[
+                atom!("DOWN"),
+                reference_term,
+                atom!("process"),
+                identifier,
+                info,
+            ])?;
+
+            Ok(down.into())
+        })?;
+        send::send_heap_message(arc_process, heap_fragment, down);
+
+        Ok(())
+    }
+
+    fn handle(&self, frame: &[u8]) -> InternalResult<()> {
+        let after_pass_through_bytes = match frame.split_first() {
+            Some((&control::PASS_THROUGH, after_pass_through_bytes)) => after_pass_through_bytes,
+            _ => return Err(anyhow!("only pass through control messages are supported").into()),
+        };
+
+        let (control, after_control_bytes) = Control::decode(after_pass_through_bytes)?;
+
+        match control {
+            Control::Link {
+                from: Pid::External(from),
+                to: Pid::Local(to),
+            } => match pid_to_process(&to) {
+                Some(_) => {
+                    self.links.insert((to, from));
+                }
+                None => {
+                    let noproc = atom!("noproc");
+
+                    self.write(
+                        Control::Exit {
+                            from: Pid::Local(to),
+                            to: Pid::External(from),
+                        },
+                        Some(noproc),
+                    );
+                }
+            },
+            Control::Send { to: Pid::Local(to) } => {
+                if let Some(arc_process) = pid_to_process(&to) {
+                    let (message, heap_fragment) = decode_versioned(after_control_bytes)?;
+                    send::send_heap_message(&arc_process, heap_fragment, message);
+                }
+            }
+            Control::RegSend { to_name, .. } => {
+                if let Some(arc_process) = atom_to_process(&to_name) {
+                    let (message, heap_fragment) = decode_versioned(after_control_bytes)?;
+                    send::send_heap_message(&arc_process, heap_fragment, message);
+                }
+            }
+            Control::Exit {
+                from: Pid::External(from),
+                to: Pid::Local(to),
+            } => {
+                if self.links.remove(&(to, from.clone())).is_some() {
+                    if let Some(arc_process) = pid_to_process(&to) {
+                        exit_signal(&arc_process, &from, |heap| {
+                            decode_reason(heap, after_control_bytes)
+                        })?;
+                    }
+                }
+            }
+            Control::Exit2 {
+                from: Pid::External(from),
+                to: Pid::Local(to),
+            } => {
+                if let Some(arc_process) = pid_to_process(&to) {
+                    exit_signal(&arc_process, &from, |heap| {
+                        decode_reason(heap, after_control_bytes)
+                    })?;
+                }
+            }
+            Control::UnlinkId {
+                id,
+                from: Pid::External(from),
+                to: Pid::Local(to),
+            } => {
+                self.links.remove(&(to, from.clone()));
+
+                self.write(
+                    Control::UnlinkIdAck {
+                        id,
+                        from: Pid::Local(to),
+                        to: Pid::External(from),
+                    },
+                    None,
+                );
+            }
+            Control::UnlinkIdAck { .. } => (),
+            Control::MonitorP {
+                from: Pid::External(from),
+                to,
+                reference: Reference::External(reference),
+            } => {
+                let (monitored_pid, monitored_name) = match &to {
+                    control::Process::Pid(Pid::Local(pid)) => (
+                        pid_to_process(pid).map(|arc_process| arc_process.pid()),
+                        None,
+                    ),
+                    control::Process::Name(name) => (
+                        atom_to_process(name).map(|arc_process| arc_process.pid()),
+                        Some(*name),
+                    ),
+                    control::Process::Pid(Pid::External(_)) => {
+                        return Err(anyhow!("monitored pid is not on this node").into())
+                    }
+                };
+
+                match monitored_pid {
+                    Some(monitored_pid) => self
+                        .incoming_monitors_by_pid
+                        .entry(monitored_pid)
+                        .or_insert_with(Default::default)
+                        .push(IncomingMonitor {
+                            monitoring_pid: from,
+                            monitored_name,
+                            reference,
+                        }),
+                    None => {
+                        let noproc = atom!("noproc");
+
+                        self.write(
+                            Control::MonitorPExit {
+                                from: to,
+                                to: Pid::External(from),
+                                reference: Reference::External(reference),
+                            },
+                            Some(noproc),
+                        );
+                    }
+                }
+            }
+            Control::DemonitorP {
+                reference: Reference::External(reference),
+                ..
+            } => {
+                for mut entry in self.incoming_monitors_by_pid.iter_mut() {
+                    entry
+                        .value_mut()
+                        .retain(|incoming_monitor| incoming_monitor.reference != reference);
+                }
+            }
+            Control::MonitorPExit {
+                reference: Reference::Local(reference),
+                ..
+            } => {
+                if let Some((_, monitor)) = self.outgoing_monitor_by_reference.remove(&reference) {
+                    if let Some(arc_process) = pid_to_process(&monitor.monitoring_pid) {
+                        self.down(&arc_process, &reference, &monitor.monitored, |heap| {
+                            decode_reason(heap, after_control_bytes)
+                        })?;
+                    }
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "control message from node ({}) has pids or references from the wrong nodes",
+                    self.arc_node.name()
+                )
+                .into())
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, control: Control, term: Option<Term>) {
+        let byte_vec = control.to_byte_vec(term);
+        let mut frame = Vec::with_capacity(4 + byte_vec.len());
+        frame.extend_from_slice(&(byte_vec.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&byte_vec);
+
+        match &mut *self.output.lock() {
+            Output::Connecting(frames) => frames.push(frame),
+            Output::Connected(stream) => {
+                // A failed write closes the connection, which is handled by `receive`
+                if stream.write_all(&frame).is_err() {
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+            }
+            // Like a message to a dead local process, a message to a disconnected node is dropped
+            Output::Disconnected => (),
+        }
+    }
+}
+
+// Private
+
+/// Less than a quarter of the default `net_ticktime` of 60 seconds
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+enum Output {
+    /// Frames written before the handshake completed
+    Connecting(Vec<Vec<u8>>),
+    Connected(TcpStream),
+    Disconnected,
+}
+
+struct OutgoingMonitor {
+    monitoring_pid: LocalPid,
+    monitored: Monitored,
+}
+
+struct IncomingMonitor {
+    monitoring_pid: ExternalPid,
+    /// The name the process was monitored by, which is used in the `MONITOR_P_EXIT`
+    monitored_name: Option<Atom>,
+    reference: ExternalReference,
+}
+
+impl From<Monitored> for control::Process {
+    fn from(monitored: Monitored) -> Self {
+        match monitored {
+            Monitored::Pid(external_pid) => control::Process::Pid(Pid::External(external_pid)),
+            Monitored::Name(name) => control::Process::Name(name),
+        }
+    }
+}
+
+/// Decodes the reason of an exit signal or the info of a `'DOWN'` message, which, unlike messages,
+/// are not prefixed with the version
+fn decode_reason(heap: &mut HeapFragment, bytes: &[u8]) -> InternalResult<Term> {
+    let (reason, _) = term::decode_tagged(heap, false, bytes)?;
+
+    Ok(reason)
+}
+
+/// Decodes a message into a heap fragment, as the receiving process may be running on a scheduler
+fn decode_versioned(bytes: &[u8]) -> InternalResult<(Term, NonNull<HeapFragment>)> {
+    let after_version_bytes = version::check(bytes)?;
+    let (message, heap_fragment, _) = term::decode_tagged_to_fragment(false, after_version_bytes)?;
+
+    Ok((message, heap_fragment))
+}
+
+/// An exit signal from `from` on the other node, either because of a link or `exit/2`, whose
+/// `reason` is built in a heap fragment, so the exiting process's heap is never touched from here
+fn exit_signal<R>(process: &Process, from: &ExternalPid, reason: R) -> InternalResult<()>
+where
+    R: Fn(&mut HeapFragment) -> InternalResult<Term>,
+{
+    if process.traps_exit() {
+        let (exit_message, heap_fragment) = term::to_fragment(0, |heap| {
+            let reason = reason(heap)?;
+            let from_term = from.clone_to_heap(heap)?;
+            let exit_message = heap.tuple_from_slice(&[atom!("EXIT"), from_term, reason])?;
+
+            Ok(exit_message.into())
+        })?;
+        send::send_heap_message(process, heap_fragment, exit_message);
+    } else {
+        let (reason, mut heap_fragment) = term::to_fragment(0, reason)?;
+
+        if reason != atom!("normal") {
+            process.attach_fragment(unsafe { heap_fragment.as_mut() });
+            process.exit(
+                reason,
+                anyhow!("exit signal from {} on another node", from).into(),
+            );
+            scheduler::wake(process);
+        } else {
+            unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+        }
+    }
+
+    Ok(())
+}
+
+fn read_frame(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut len = [0; 4];
+    stream.read_exact(&mut len)?;
+
+    let mut frame = vec![0; u32::from_be_bytes(len) as usize];
+    stream.read_exact(&mut frame)?;
+
+    Ok(frame)
+}
@@ -0,0 +1,392 @@
+//! [Control messages](http://erlang.org/doc/apps/erts/erl_dist_protocol.html#control-message)
+//! sent between connected nodes.
+//!
+//! Control messages are decoded without a process, so that the destination process is known
+//! before any terms are put on a heap.  The reason or message that follows is decoded directly
+//! into the destination process.
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::distribution::external_term_format::encode::{
+    append_external_pid, append_external_reference, append_local_pid, append_local_reference,
+    append_term, append_tuple_header,
+};
+use crate::distribution::external_term_format::{
+    decode_atom, try_split_at, version, Pid, Reference, Tag,
+};
+
+/// A process on either node, as the target of a monitor
+pub enum Process {
+    Pid(Pid),
+    Name(Atom),
+}
+
+pub enum Control {
+    Link {
+        from: Pid,
+        to: Pid,
+    },
+    /// Followed by the message
+    Send {
+        to: Pid,
+    },
+    /// The reason is the last element of the control message
+    Exit {
+        from: Pid,
+        to: Pid,
+    },
+    /// Followed by the message
+    RegSend {
+        from: Pid,
+        to_name: Atom,
+    },
+    /// The reason is the last element of the control message
+    Exit2 {
+        from: Pid,
+        to: Pid,
+    },
+    MonitorP {
+        from: Pid,
+        to: Process,
+        reference: Reference,
+    },
+    DemonitorP {
+        from: Pid,
+        to: Process,
+        reference: Reference,
+    },
+    /// The reason is the last element of the control message
+    MonitorPExit {
+        from: Process,
+        to: Pid,
+        reference: Reference,
+    },
+    UnlinkId {
+        id: u64,
+        from: Pid,
+        to: Pid,
+    },
+    UnlinkIdAck {
+        id: u64,
+        from: Pid,
+        to: Pid,
+    },
+}
+
+impl Control {
+    /// Decodes the control message after the pass through byte.  The returned bytes are the reason
+    /// for messages that have one or the versioned message for `Send` and `RegSend`.
+    pub fn decode(bytes: &[u8]) -> InternalResult<(Self, &[u8])> {
+        let after_version_bytes = version::check(bytes)?;
+        let (tag, after_tag_bytes) = Tag::decode(after_version_bytes)?;
+
+        let after_arity_bytes = match tag {
+            Tag::SmallTuple => try_split_at(after_tag_bytes, 1)?.1,
+            Tag::LargeTuple => try_split_at(after_tag_bytes, 4)?.1,
+            _ => return Err(anyhow!("control message is not a tuple").into()),
+        };
+
+        let (operation, after_operation_bytes) = decode_u64(after_arity_bytes)?;
+
+        // Control messages are trusted, so atoms can be created
+        let safe = false;
+
+        match operation {
+            LINK => {
+                let (from, after_from_bytes) = Pid::decode(safe, after_operation_bytes)?;
+                let (to, after_to_bytes) = Pid::decode(safe, after_from_bytes)?;
+
+                Ok((Control::Link { from, to }, after_to_bytes))
+            }
+            SEND => {
+                let (_unused, after_unused_bytes) = decode_atom(safe, after_operation_bytes)?;
+                let (to, after_to_bytes) = Pid::decode(safe, after_unused_bytes)?;
+
+                Ok((Control::Send { to }, after_to_bytes))
+            }
+            EXIT | EXIT2 => {
+                let (from, after_from_bytes) = Pid::decode(safe, after_operation_bytes)?;
+                let (to, after_to_bytes) = Pid::decode(safe, after_from_bytes)?;
+
+                let control = if operation == EXIT {
+                    Control::Exit { from, to }
+                } else {
+                    Control::Exit2 { from, to }
+                };
+
+                Ok((control, after_to_bytes))
+            }
+            REG_SEND => {
+                let (from, after_from_bytes) = Pid::decode(safe, after_operation_bytes)?;
+                let (_unused, after_unused_bytes) = decode_atom(safe, after_from_bytes)?;
+                let (to_name, after_to_name_bytes) = decode_atom(safe, after_unused_bytes)?;
+
+                Ok((Control::RegSend { from, to_name }, after_to_name_bytes))
+            }
+            MONITOR_P | DEMONITOR_P => {
+                let (from, after_from_bytes) = Pid::decode(safe, after_operation_bytes)?;
+                let (to, after_to_bytes) = decode_process(safe, after_from_bytes)?;
+                let (reference, after_reference_bytes) = Reference::decode(safe, after_to_bytes)?;
+
+                let control = if operation == MONITOR_P {
+                    Control::MonitorP {
+                        from,
+                        to,
+                        reference,
+                    }
+                } else {
+                    Control::DemonitorP {
+                        from,
+                        to,
+                        reference,
+                    }
+                };
+
+                Ok((control, after_reference_bytes))
+            }
+            MONITOR_P_EXIT => {
+                let (from, after_from_bytes) = decode_process(safe, after_operation_bytes)?;
+                let (to, after_to_bytes) = Pid::decode(safe, after_from_bytes)?;
+                let (reference, after_reference_bytes) = Reference::decode(safe, after_to_bytes)?;
+
+                Ok((
+                    Control::MonitorPExit {
+                        from,
+                        to,
+                        reference,
+                    },
+                    after_reference_bytes,
+                ))
+            }
+            UNLINK_ID | UNLINK_ID_ACK => {
+                let (id, after_id_bytes) = decode_u64(after_operation_bytes)?;
+                let (from, after_from_bytes) = Pid::decode(safe, after_id_bytes)?;
+                let (to, after_to_bytes) = Pid::decode(safe, after_from_bytes)?;
+
+                let control = if operation == UNLINK_ID {
+                    Control::UnlinkId { id, from, to }
+                } else {
+                    Control::UnlinkIdAck { id, from, to }
+                };
+
+                Ok((control, after_to_bytes))
+            }
+            _ => Err(anyhow!("control message operation ({}) is not supported", operation).into()),
+        }
+    }
+
+    /// Encodes the control message with the pass through byte.  `term` is the reason for messages
+    /// that have one and the message for `Send` and `RegSend`.
+    pub fn to_byte_vec(&self, term: Option<Term>) -> Vec<u8> {
+        let mut byte_vec = vec![PASS_THROUGH, version::NUMBER];
+
+        match self {
+            Control::Link { from, to } => {
+                append_tuple_header(&mut byte_vec, 3);
+                append_u8(&mut byte_vec, LINK);
+                append_pid(&mut byte_vec, from);
+                append_pid(&mut byte_vec, to);
+            }
+            Control::Send { to } => {
+                append_tuple_header(&mut byte_vec, 3);
+                append_u8(&mut byte_vec, SEND);
+                append_term(&mut byte_vec, Atom::str_to_term(""));
+                append_pid(&mut byte_vec, to);
+            }
+            Control::Exit { from, to } | Control::Exit2 { from, to } => {
+                let operation = if let Control::Exit { .. } = self {
+                    EXIT
+                } else {
+                    EXIT2
+                };
+
+                append_tuple_header(&mut byte_vec, 4);
+                append_u8(&mut byte_vec, operation);
+                append_pid(&mut byte_vec, from);
+                append_pid(&mut byte_vec, to);
+            }
+            Control::RegSend { from, to_name } => {
+                append_tuple_header(&mut byte_vec, 4);
+                append_u8(&mut byte_vec, REG_SEND);
+                append_pid(&mut byte_vec, from);
+                append_term(&mut byte_vec, Atom::str_to_term(""));
+                append_term(&mut byte_vec, to_name.encode().unwrap());
+            }
+            Control::MonitorP {
+                from,
+                to,
+                reference,
+            }
+            | Control::DemonitorP {
+                from,
+                to,
+                reference,
+            } => {
+                let operation = if let Control::MonitorP { .. } = self {
+                    MONITOR_P
+                } else {
+                    DEMONITOR_P
+                };
+
+                append_tuple_header(&mut byte_vec, 4);
+                append_u8(&mut byte_vec, operation);
+                append_pid(&mut byte_vec, from);
+                append_process(&mut byte_vec, to);
+                append_reference(&mut byte_vec, reference);
+            }
+            Control::MonitorPExit {
+                from,
+                to,
+                reference,
+            } => {
+                append_tuple_header(&mut byte_vec, 5);
+                append_u8(&mut byte_vec, MONITOR_P_EXIT);
+                append_process(&mut byte_vec, from);
+                append_pid(&mut byte_vec, to);
+                append_reference(&mut byte_vec, reference);
+            }
+            Control::UnlinkId { id, from, to } | Control::UnlinkIdAck { id, from, to } => {
+                let operation = if let Control::UnlinkId { .. } = self {
+                    UNLINK_ID
+                } else {
+                    UNLINK_ID_ACK
+                };
+
+                append_tuple_header(&mut byte_vec, 4);
+                append_u8(&mut byte_vec, operation);
+                append_u64(&mut byte_vec, *id);
+                append_pid(&mut byte_vec, from);
+                append_pid(&mut byte_vec, to);
+            }
+        }
+
+        if let Some(term) = term {
+            match self {
+                Control::Send { .. } | Control::RegSend { .. } => {
+                    byte_vec.push(version::NUMBER);
+                    append_term(&mut byte_vec, term);
+                }
+                // The reason is the last element of the control message tuple
+                _ => append_term(&mut byte_vec, term),
+            }
+        }
+
+        byte_vec
+    }
+}
+
+/// Every control message starts with this byte when the atom cache is not used
+pub const PASS_THROUGH: u8 = 112;
+
+// Private
+
+const LINK: u64 = 1;
+const SEND: u64 = 2;
+const EXIT: u64 = 3;
+const REG_SEND: u64 = 6;
+const EXIT2: u64 = 8;
+const MONITOR_P: u64 = 19;
+const DEMONITOR_P: u64 = 20;
+const MONITOR_P_EXIT: u64 = 21;
+const UNLINK_ID: u64 = 35;
+const UNLINK_ID_ACK: u64 = 36;
+
+fn append_pid(byte_vec: &mut Vec<u8>, pid: &Pid) {
+    match pid {
+        Pid::Local(local_pid) => append_local_pid(byte_vec, *local_pid),
+        Pid::External(external_pid) => append_external_pid(byte_vec, external_pid),
+    }
+}
+
+fn append_process(byte_vec: &mut Vec<u8>, process: &Process) {
+    match process {
+        Process::Pid(pid) => append_pid(byte_vec, pid),
+        Process::Name(name) => append_term(byte_vec, name.encode().unwrap()),
+    }
+}
+
+fn append_reference(byte_vec: &mut Vec<u8>, reference: &Reference) {
+    match reference {
+        Reference::Local(local_reference) => append_local_reference(byte_vec, local_reference),
+        Reference::External(external_reference) => {
+            append_external_reference(byte_vec, external_reference)
+        }
+    }
+}
+
+fn append_u8(byte_vec: &mut Vec<u8>, integer: u64) {
+    byte_vec.push(Tag::SmallInteger.into());
+    byte_vec.push(integer.try_into().unwrap());
+}
+
+/// Unlink ids can be larger than a `SmallInteger`, so they are encoded as `SMALL_BIG_EXT`
+fn append_u64(byte_vec: &mut Vec<u8>, integer: u64) {
+    let little_endian_bytes = integer.to_le_bytes();
+    let len = little_endian_bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |index| index + 1);
+
+    byte_vec.push(Tag::SmallBig.into());
+    byte_vec.push(len as u8);
+    // positive
+    byte_vec.push(0);
+    byte_vec.extend_from_slice(&little_endian_bytes[..len]);
+}
+
+fn decode_process(safe: bool, bytes: &[u8]) -> InternalResult<(Process, &[u8])> {
+    let (tag, _) = Tag::decode(bytes)?;
+
+    match tag {
+        Tag::PID | Tag::NewPID => Pid::decode(safe, bytes)
+            .map(|(pid, after_pid_bytes)| (Process::Pid(pid), after_pid_bytes)),
+        _ => decode_atom(safe, bytes)
+            .map(|(name, after_name_bytes)| (Process::Name(name), after_name_bytes)),
+    }
+}
+
+/// Decodes the non-negative integers used for operations and unlink ids
+fn decode_u64(bytes: &[u8]) -> InternalResult<(u64, &[u8])> {
+    let (tag, after_tag_bytes) = Tag::decode(bytes)?;
+
+    match tag {
+        Tag::SmallInteger => {
+            let (integer_bytes, after_integer_bytes) = try_split_at(after_tag_bytes, 1)?;
+
+            Ok((integer_bytes[0] as u64, after_integer_bytes))
+        }
+        Tag::Integer => {
+            let (integer_bytes, after_integer_bytes) = try_split_at(after_tag_bytes, 4)?;
+            let integer = i32::from_be_bytes(integer_bytes.try_into().unwrap());
+
+            if integer < 0 {
+                Err(anyhow!("integer ({}) is negative", integer).into())
+            } else {
+                Ok((integer as u64, after_integer_bytes))
+            }
+        }
+        Tag::SmallBig => {
+            let (header_bytes, after_header_bytes) = try_split_at(after_tag_bytes, 2)?;
+            let len = header_bytes[0] as usize;
+            let sign = header_bytes[1];
+
+            if sign != 0 || 8 < len {
+                return Err(anyhow!("integer is not a u64").into());
+            }
+
+            let (digit_bytes, after_digit_bytes) = try_split_at(after_header_bytes, len)?;
+            let integer = digit_bytes
+                .iter()
+                .rev()
+                .fold(0_u64, |acc, digit| (acc << 8) | (*digit as u64));
+
+            Ok((integer, after_digit_bytes))
+        }
+        _ => Err(anyhow!("{:?} is not an integer tag", tag).into()),
+    }
+}
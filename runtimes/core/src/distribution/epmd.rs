@@ -0,0 +1,185 @@
+//! Client for the [Erlang Port Mapper Daemon](http://erlang.org/doc/apps/erts/erl_dist_protocol.html#epmd-protocol),
+//! which maps the alive names of nodes on a host to their distribution ports.
+
+mod server;
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::env;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::str;
+
+use anyhow::*;
+
+pub use server::Server;
+
+/// The port EPMD listens on when `ERL_EPMD_PORT` is not set
+pub const DEFAULT_PORT: u16 = 4369;
+
+/// The distribution protocol version.  Only version 6 (OTP 23+) handshakes are supported.
+pub const VERSION: u16 = 6;
+
+/// The port EPMD listens on, from `ERL_EPMD_PORT` like `erl` and `epmd`
+pub fn port() -> u16 {
+    env::var("ERL_EPMD_PORT")
+        .ok()
+        .and_then(|port_string| port_string.parse().ok())
+        .unwrap_or(DEFAULT_PORT)
+}
+
+/// The open connection to EPMD that keeps `alive_name` registered.  EPMD unregisters the node when
+/// the connection is closed, so this must live as long as the node is alive.
+pub struct Registration {
+    #[allow(dead_code)]
+    stream: TcpStream,
+    pub creation: u32,
+}
+
+/// Registers `alive_name` with distribution `port` with the EPMD on `epmd_port` of the local host.
+pub fn register(epmd_port: u16, alive_name: &str, port: u16) -> anyhow::Result<Registration> {
+    let mut stream = TcpStream::connect(("127.0.0.1", epmd_port))
+        .with_context(|| format!("could not connect to EPMD on port {}", epmd_port))?;
+
+    let mut request = vec![ALIVE2_REQ];
+    request.extend_from_slice(&port.to_be_bytes());
+    request.push(NODE_TYPE_NORMAL);
+    request.push(PROTOCOL_TCP_IPV4);
+    request.extend_from_slice(&VERSION.to_be_bytes());
+    request.extend_from_slice(&VERSION.to_be_bytes());
+    append_u16_len_bytes(&mut request, alive_name.as_bytes())?;
+    append_u16_len_bytes(&mut request, &[])?;
+    write_request(&mut stream, &request)?;
+
+    let mut tag = [0; 1];
+    stream.read_exact(&mut tag)?;
+
+    let (result, creation) = match tag[0] {
+        ALIVE2_X_RESP => {
+            let mut response = [0; 5];
+            stream.read_exact(&mut response)?;
+
+            (
+                response[0],
+                u32::from_be_bytes(response[1..5].try_into().unwrap()),
+            )
+        }
+        ALIVE2_RESP => {
+            let mut response = [0; 3];
+            stream.read_exact(&mut response)?;
+
+            (
+                response[0],
+                u16::from_be_bytes(response[1..3].try_into().unwrap()) as u32,
+            )
+        }
+        tag => return Err(anyhow!("unexpected EPMD response ({}) to ALIVE2_REQ", tag)),
+    };
+
+    if result == 0 {
+        Ok(Registration { stream, creation })
+    } else {
+        Err(anyhow!(
+            "EPMD refused to register name ({}) with result ({})",
+            alive_name,
+            result
+        ))
+    }
+}
+
+/// The distribution port of `alive_name` on `host` or `None` if it is not registered
+pub fn port_please(host: &str, epmd_port: u16, alive_name: &str) -> anyhow::Result<Option<u16>> {
+    let address = (host, epmd_port)
+        .to_socket_addrs()
+        .with_context(|| format!("could not resolve host ({})", host))?
+        .next()
+        .with_context(|| format!("host ({}) has no addresses", host))?;
+    let mut stream = TcpStream::connect(address)
+        .with_context(|| format!("could not connect to EPMD at {}", address))?;
+
+    let mut request = vec![PORT_PLEASE2_REQ];
+    request.extend_from_slice(alive_name.as_bytes());
+    write_request(&mut stream, &request)?;
+
+    let mut response = [0; 2];
+    stream.read_exact(&mut response)?;
+
+    match response {
+        [PORT2_RESP, 0] => {
+            let mut port = [0; 2];
+            stream.read_exact(&mut port)?;
+
+            Ok(Some(u16::from_be_bytes(port)))
+        }
+        [PORT2_RESP, _] => Ok(None),
+        [tag, _] => Err(anyhow!(
+            "unexpected EPMD response ({}) to PORT_PLEASE2_REQ",
+            tag
+        )),
+    }
+}
+
+// Private
+
+const ALIVE2_X_RESP: u8 = 118;
+const PORT_PLEASE2_REQ: u8 = 122;
+const PORT2_RESP: u8 = 119;
+const ALIVE2_REQ: u8 = 120;
+const ALIVE2_RESP: u8 = 121;
+
+const NODE_TYPE_NORMAL: u8 = 77;
+const PROTOCOL_TCP_IPV4: u8 = 0;
+
+fn append_u16_len_bytes(byte_vec: &mut Vec<u8>, bytes: &[u8]) -> anyhow::Result<()> {
+    let len_u16: u16 = bytes
+        .len()
+        .try_into()
+        .context("length does not fit in 16 bits")?;
+    byte_vec.extend_from_slice(&len_u16.to_be_bytes());
+    byte_vec.extend_from_slice(bytes);
+
+    Ok(())
+}
+
+/// Reads a length-prefixed request, as EPMD does
+fn read_request(stream: &mut TcpStream) -> anyhow::Result<Vec<u8>> {
+    let mut len = [0; 2];
+    stream.read_exact(&mut len)?;
+
+    let mut request = vec![0; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut request)?;
+
+    Ok(request)
+}
+
+fn read_u16_len_str(bytes: &[u8]) -> anyhow::Result<(&str, &[u8])> {
+    if bytes.len() < 2 {
+        return Err(anyhow!("length is missing"));
+    }
+
+    let (len_bytes, after_len_bytes) = bytes.split_at(2);
+    let len = u16::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
+
+    if after_len_bytes.len() < len {
+        return Err(anyhow!(
+            "needed {} bytes, but only {} available",
+            len,
+            after_len_bytes.len()
+        ));
+    }
+
+    let (string_bytes, after_string_bytes) = after_len_bytes.split_at(len);
+    let string = str::from_utf8(string_bytes).context("name is not UTF-8")?;
+
+    Ok((string, after_string_bytes))
+}
+
+fn write_request(stream: &mut TcpStream, request: &[u8]) -> anyhow::Result<()> {
+    let mut framed = Vec::with_capacity(2 + request.len());
+    append_u16_len_bytes(&mut framed, request)?;
+    stream.write_all(&framed)?;
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::*;
+
+use liblumen_core::locks::Mutex;
+
+use super::*;
+
+/// A minimal EPMD that only supports registering names and looking up their ports.  It lets tests
+/// and hosts without `epmd` run nodes that find each other.
+pub struct Server {
+    port: u16,
+    listener: TcpListener,
+}
+
+impl Server {
+    /// Listens on `port` of the local host.  Use `0` to get a free port.
+    pub fn bind(port: u16) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .with_context(|| format!("could not listen on port {}", port))?;
+        let port = listener.local_addr()?.port();
+
+        Ok(Self { port, listener })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Serves requests on a background thread until the process exits
+    pub fn spawn(self) -> anyhow::Result<u16> {
+        let port = self.port;
+        let port_by_name: Arc<Mutex<PortByName>> = Default::default();
+        let listener = self.listener;
+
+        thread::Builder::new()
+            .name("epmd".to_string())
+            .spawn(move || {
+                for stream in listener.incoming().filter_map(Result::ok) {
+                    let port_by_name = port_by_name.clone();
+
+                    thread::spawn(move || {
+                        // Errors only affect the node that sent the bad request
+                        let _ = handle(stream, port_by_name);
+                    });
+                }
+            })?;
+
+        Ok(port)
+    }
+}
+
+// Private
+
+struct Registered {
+    port: u16,
+    highest_version: u16,
+    lowest_version: u16,
+}
+
+type PortByName = HashMap<String, Registered>;
+
+fn handle(mut stream: TcpStream, port_by_name: Arc<Mutex<PortByName>>) -> anyhow::Result<()> {
+    let request = read_request(&mut stream)?;
+
+    match request.split_first() {
+        Some((&ALIVE2_REQ, after_tag_bytes)) => alive2(stream, port_by_name, after_tag_bytes),
+        Some((&PORT_PLEASE2_REQ, name_bytes)) => {
+            let name = str::from_utf8(name_bytes).context("name is not UTF-8")?;
+            let mut response = vec![PORT2_RESP];
+
+            match port_by_name.lock().get(name) {
+                Some(registered) => {
+                    response.push(0);
+                    response.extend_from_slice(&registered.port.to_be_bytes());
+                    response.push(NODE_TYPE_NORMAL);
+                    response.push(PROTOCOL_TCP_IPV4);
+                    response.extend_from_slice(&registered.highest_version.to_be_bytes());
+                    response.extend_from_slice(&registered.lowest_version.to_be_bytes());
+                    append_u16_len_bytes(&mut response, name.as_bytes())?;
+                    append_u16_len_bytes(&mut response, &[])?;
+                }
+                None => response.push(1),
+            }
+
+            stream.write_all(&response)?;
+            stream.shutdown(Shutdown::Both)?;
+
+            Ok(())
+        }
+        Some((tag, _)) => Err(anyhow!("unsupported EPMD request ({})", tag)),
+        None => Err(anyhow!("empty EPMD request")),
+    }
+}
+
+fn alive2(
+    mut stream: TcpStream,
+    port_by_name: Arc<Mutex<PortByName>>,
+    bytes: &[u8],
+) -> anyhow::Result<()> {
+    if bytes.len() < 8 {
+        return Err(anyhow!("ALIVE2_REQ is too short"));
+    }
+
+    let port = u16::from_be_bytes(bytes[0..2].try_into().unwrap());
+    let highest_version = u16::from_be_bytes(bytes[4..6].try_into().unwrap());
+    let lowest_version = u16::from_be_bytes(bytes[6..8].try_into().unwrap());
+    let (name, _) = read_u16_len_str(&bytes[8..])?;
+    let name = name.to_string();
+
+    let registered = {
+        let mut locked_port_by_name = port_by_name.lock();
+
+        if locked_port_by_name.contains_key(&name) {
+            false
+        } else {
+            locked_port_by_name.insert(
+                name.clone(),
+                Registered {
+                    port,
+                    highest_version,
+                    lowest_version,
+                },
+            );
+
+            true
+        }
+    };
+
+    let mut response = vec![ALIVE2_X_RESP];
+
+    if registered {
+        response.push(0);
+        response.extend_from_slice(&creation().to_be_bytes());
+        stream.write_all(&response)?;
+
+        // The name stays registered until the node closes the connection
+        let mut buffer = [0; 1];
+        while let Ok(read) = stream.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+        }
+
+        port_by_name.lock().remove(&name);
+    } else {
+        response.push(1);
+        response.extend_from_slice(&0_u32.to_be_bytes());
+        stream.write_all(&response)?;
+    }
+
+    Ok(())
+}
+
+/// A non-zero creation that differs between registrations, so that identifiers from a restarted
+/// node are not confused with the old node's.
+fn creation() -> u32 {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static NEXT_CREATION: AtomicU32 = AtomicU32::new(1);
+
+    NEXT_CREATION.fetch_add(1, Ordering::SeqCst)
+}
@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn registered_name_has_port_until_registration_is_dropped() {
+    let epmd_port = Server::bind(0).unwrap().spawn().unwrap();
+
+    assert_eq!(
+        port_please("127.0.0.1", epmd_port, "registered").unwrap(),
+        None
+    );
+
+    let registration = register(epmd_port, "registered", 4370).unwrap();
+
+    assert_ne!(registration.creation, 0);
+    assert_eq!(
+        port_please("127.0.0.1", epmd_port, "registered").unwrap(),
+        Some(4370)
+    );
+    assert!(register(epmd_port, "registered", 4371).is_err());
+
+    drop(registration);
+
+    // The server unregisters after it reads the connection close
+    let mut port = Some(4370);
+
+    for _ in 0..100 {
+        port = port_please("127.0.0.1", epmd_port, "registered").unwrap();
+
+        if port.is_none() {
+            break;
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    assert_eq!(port, None);
+}
@@ -4,6 +4,7 @@ mod atom_utf8;
 mod big;
 mod binary;
 mod bit_binary;
+pub mod encode;
 mod export;
 mod f64;
 mod i32;
@@ -38,10 +39,11 @@ use anyhow::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use thiserror::Error;
 
-use liblumen_alloc::erts::exception::{ArcError, InternalException, InternalResult};
+use liblumen_alloc::erts::exception::{AllocResult, ArcError, InternalException, InternalResult};
+use liblumen_alloc::erts::process::alloc::TermAlloc;
 use liblumen_alloc::erts::term::closure::Creator;
-use liblumen_alloc::erts::term::prelude::{Pid as LocalPid, *};
-use liblumen_alloc::erts::{Node, Process};
+use liblumen_alloc::erts::term::prelude::{Pid as LocalPid, Reference as LocalReference, *};
+use liblumen_alloc::erts::Node;
 use liblumen_alloc::CloneToProcess;
 
 use crate::distribution::nodes::node;
@@ -140,7 +142,7 @@ pub enum Pid {
 }
 
 impl Pid {
-    fn decode(safe: bool, bytes: &[u8]) -> InternalResult<(Self, &[u8])> {
+    pub fn decode(safe: bool, bytes: &[u8]) -> InternalResult<(Self, &[u8])> {
         let (tag, after_tag_bytes) = Tag::decode(bytes)?;
 
         match tag {
@@ -169,14 +171,49 @@ impl Pid {
         Ok(pid)
     }
 
-    fn clone_to_process(&self, process: &Process) -> Term {
+    fn clone_to_heap<A: TermAlloc>(&self, heap: &mut A) -> AllocResult<Term> {
         match self {
-            Pid::Local(local_pid) => local_pid.clone().into(),
-            Pid::External(external_pid) => external_pid.clone_to_process(process),
+            Pid::Local(local_pid) => Ok(local_pid.clone().into()),
+            Pid::External(external_pid) => external_pid.clone_to_heap(heap),
         }
     }
 }
 
+pub enum Reference {
+    Local(LocalReference),
+    External(ExternalReference),
+}
+
+impl Reference {
+    pub fn decode(safe: bool, bytes: &[u8]) -> InternalResult<(Self, &[u8])> {
+        let (tag, after_tag_bytes) = Tag::decode(bytes)?;
+
+        match tag {
+            Tag::NewerReference => newer_reference::decode_reference(safe, after_tag_bytes),
+            _ => Err(DecodeError::UnexpectedTag {
+                tag,
+                backtrace: Backtrace::capture(),
+            })
+            .with_context(|| format!("Expected tag is {:?}", Tag::NewerReference))
+            .map_err(|error| error.into()),
+        }
+    }
+
+    fn clone_to_heap<A: TermAlloc>(&self, heap: &mut A) -> AllocResult<Term> {
+        match self {
+            Reference::Local(local_reference) => heap
+                .reference(local_reference.scheduler_id(), local_reference.number())
+                .map(From::from),
+            Reference::External(external_reference) => external_reference.clone_to_heap(heap),
+        }
+    }
+}
+
+/// Decodes an atom without a process, as atoms are not stored on process heaps
+pub fn decode_atom(safe: bool, bytes: &[u8]) -> InternalResult<(Atom, &[u8])> {
+    atom::decode_tagged(safe, bytes)
+}
+
 impl Into<Creator> for Pid {
     fn into(self) -> Creator {
         match self {
@@ -188,8 +225,21 @@ impl Into<Creator> for Pid {
 
 // Private
 
-fn decode_vec_term<'a>(
-    process: &Process,
+/// Like `TermAlloc::binary_from_bytes`, but for any heap, including `HeapFragment`s, which don't
+/// have a virtual binary heap of their own
+fn binary_from_bytes<A: TermAlloc>(heap: &mut A, bytes: &[u8]) -> AllocResult<Term> {
+    if bytes.len() > 64 {
+        let procbin = heap.procbin_from_bytes(bytes)?;
+        heap.virtual_alloc_clone(procbin);
+
+        Ok(procbin.into())
+    } else {
+        heap.heapbin_from_bytes(bytes).map(From::from)
+    }
+}
+
+fn decode_vec_term<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
     len: usize,
@@ -198,7 +248,7 @@ fn decode_vec_term<'a>(
     let mut remaining_bytes = bytes;
 
     for _ in 0..len {
-        let (element, after_element_bytes) = term::decode_tagged(process, safe, remaining_bytes)?;
+        let (element, after_element_bytes) = term::decode_tagged(heap, safe, remaining_bytes)?;
         element_vec.push(element);
         remaining_bytes = after_element_bytes;
     }
@@ -1,10 +1,10 @@
 use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
 use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
 
-use super::{try_split_at, u32, u8};
+use super::{binary_from_bytes, try_split_at, u32, u8};
 
-pub fn decode<'a>(process: &Process, bytes: &'a [u8]) -> InternalResult<(Term, &'a [u8])> {
+pub fn decode<'a, A: TermAlloc>(heap: &mut A, bytes: &'a [u8]) -> InternalResult<(Term, &'a [u8])> {
     let (len_u32, after_len_bytes) = u32::decode(bytes)?;
     let len_usize = len_u32 as usize;
 
@@ -13,14 +13,10 @@ pub fn decode<'a>(process: &Process, bytes: &'a [u8]) -> InternalResult<(Term, &
 
     try_split_at(after_partial_byte_bit_len_bytes, len_usize).and_then(
         |(data_bytes, after_data_bytes)| {
-            let original = process.binary_from_bytes(data_bytes)?;
-            let subbinary = process.subbinary_from_original(
-                original,
-                0,
-                0,
-                len_usize - 1,
-                partial_byte_bit_len,
-            )?;
+            let original = binary_from_bytes(heap, data_bytes)?;
+            let subbinary = heap
+                .subbinary_from_original(original, 0, 0, len_usize - 1, partial_byte_bit_len)?
+                .into();
 
             Ok((subbinary, after_data_bytes))
         },
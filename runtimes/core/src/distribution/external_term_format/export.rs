@@ -2,15 +2,15 @@ use std::ffi::c_void;
 use std::mem;
 
 use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
 use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
 
 use super::{atom, small_integer};
 use liblumen_alloc::erts::apply::find_symbol;
 use liblumen_alloc::ModuleFunctionArity;
 
-pub fn decode<'a>(
-    process: &Process,
+pub fn decode<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
 ) -> InternalResult<(Term, &'a [u8])> {
@@ -27,7 +27,9 @@ pub fn decode<'a>(
     let option_native = find_symbol(&module_function_arity)
         .map(|dynamic_callee| unsafe { mem::transmute::<_, *const c_void>(dynamic_callee) });
 
-    let closure = process.export_closure(module, function, arity, option_native)?;
+    let closure = heap
+        .export_closure(module, function, arity, option_native)?
+        .into();
 
     Ok((closure, after_arity_bytes))
 }
@@ -1,13 +1,13 @@
 use hashbrown::HashMap;
 
 use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
 use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
 
 use super::{term, u32};
 
-pub fn decode<'a>(
-    process: &Process,
+pub fn decode<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
 ) -> InternalResult<(Term, &'a [u8])> {
@@ -17,13 +17,13 @@ pub fn decode<'a>(
     let mut remaining_bytes = after_len_bytes;
 
     for _ in 0..pair_len_usize {
-        let (key, after_key_bytes) = term::decode_tagged(process, safe, remaining_bytes)?;
-        let (value, after_value_bytes) = term::decode_tagged(process, safe, after_key_bytes)?;
+        let (key, after_key_bytes) = term::decode_tagged(heap, safe, remaining_bytes)?;
+        let (value, after_value_bytes) = term::decode_tagged(heap, safe, after_key_bytes)?;
         hash_map.insert(key, value);
         remaining_bytes = after_value_bytes;
     }
 
-    let map = process.map_from_hash_map(hash_map)?;
+    let map = heap.map_from_hash_map(hash_map)?.into();
 
     Ok((map, remaining_bytes))
 }
@@ -4,16 +4,16 @@ use std::mem;
 
 use liblumen_alloc::erts::apply::find_symbol;
 use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
 use liblumen_alloc::erts::term::closure::{Definition, OldUnique};
 use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
 use liblumen_alloc::ModuleFunctionArity;
 
 use super::{atom, decode_vec_term, isize, u32, u8, Pid};
 use crate::distribution::external_term_format::try_split_at;
 
-pub fn decode<'a>(
-    process: &Process,
+pub fn decode<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
 ) -> InternalResult<(Term, &'a [u8])> {
@@ -34,7 +34,7 @@ pub fn decode<'a>(
 
     let env_len: usize = num_free as usize;
     let (env_vec, after_vec_term_bytes) =
-        decode_vec_term(process, safe, after_creator_bytes, env_len)?;
+        decode_vec_term(heap, safe, after_creator_bytes, env_len)?;
 
     assert_eq!(
         bytes.len() - after_vec_term_bytes.len(),
@@ -56,16 +56,18 @@ pub fn decode<'a>(
     let option_native = find_symbol(&module_function_arity)
         .map(|dynamic_callee| unsafe { mem::transmute::<_, *const c_void>(dynamic_callee) });
 
-    let closure = process.anonymous_closure_with_env_from_slice(
-        module,
-        index,
-        old_unique,
-        uniq,
-        arity,
-        option_native,
-        creator.into(),
-        &env_vec,
-    )?;
+    let closure = heap
+        .anonymous_closure_with_env_from_slice(
+            module,
+            index,
+            old_unique,
+            uniq,
+            arity,
+            option_native,
+            creator.into(),
+            &env_vec,
+        )?
+        .into();
 
     Ok((closure, after_vec_term_bytes))
 }
@@ -1,19 +1,29 @@
 use std::mem;
 
+use anyhow::*;
+
 use liblumen_alloc::erts::exception::InternalResult;
-use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::term::prelude::{Reference as LocalReference, *};
 
 use crate::distribution::external_term_format::try_split_at;
 use crate::distribution::nodes::node;
 
-use super::{arc_node, u16, u32, u64};
+use super::{arc_node, u16, u32, u64, Reference};
+
+const NEWER_REFERENCE_EXT_MAX_U32_LEN: u16 = 3;
 
-pub fn decode<'a>(
-    process: &Process,
+pub fn decode<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
 ) -> InternalResult<(Term, &'a [u8])> {
+    let (reference, after_reference_bytes) = decode_reference(safe, bytes)?;
+
+    Ok((reference.clone_to_heap(heap)?, after_reference_bytes))
+}
+
+pub fn decode_reference<'a>(safe: bool, bytes: &'a [u8]) -> InternalResult<(Reference, &'a [u8])> {
     let (u32_len_u16, after_len_bytes) = u16::decode(bytes)?;
     let len_usize = (u32_len_u16 as usize) * mem::size_of::<u32>();
 
@@ -26,12 +36,36 @@ pub fn decode<'a>(
             let (scheduler_id_u32, after_scheduler_id_bytes) = u32::decode(id_bytes)?;
             let (number_u64, _) = u64::decode(after_scheduler_id_bytes)?;
 
-            let reference =
-                process.reference_from_scheduler(scheduler_id_u32.into(), number_u64)?;
+            let local_reference = LocalReference::new(scheduler_id_u32.into(), number_u64);
 
-            Ok((reference, after_id_bytes))
+            Ok((Reference::Local(local_reference), after_id_bytes))
         } else {
-            unimplemented!()
+            // Other nodes' references are stored in the same 3 words as local references, so that
+            // they encode back to the same ID words.
+            if NEWER_REFERENCE_EXT_MAX_U32_LEN < u32_len_u16 {
+                return Err(anyhow!(
+                    "reference from node ({}) has {} ID words, but at most {} are supported",
+                    arc_node.name(),
+                    u32_len_u16,
+                    NEWER_REFERENCE_EXT_MAX_U32_LEN
+                )
+                .into());
+            }
+
+            let mut words = [0_u32; NEWER_REFERENCE_EXT_MAX_U32_LEN as usize];
+            let mut remaining_bytes = id_bytes;
+
+            for word in words.iter_mut().take(u32_len_u16 as usize) {
+                let (word_u32, after_word_bytes) = u32::decode(remaining_bytes)?;
+                *word = word_u32;
+                remaining_bytes = after_word_bytes;
+            }
+
+            let number_u64 = ((words[1] as u64) << 32) | (words[2] as u64);
+            let local_reference = LocalReference::new(words[0].into(), number_u64);
+            let external_reference = ExternalReference::new(arc_node, local_reference);
+
+            Ok((Reference::External(external_reference), after_id_bytes))
         }
     })
 }
@@ -1,7 +1,14 @@
+use std::mem;
+use std::ptr::{self, NonNull};
+
+use liblumen_alloc::erts::exception::SystemException;
+use liblumen_alloc::erts::process::alloc::Heap;
+use liblumen_alloc::erts::HeapFragment;
+
 use super::*;
 
-pub fn decode_tagged<'a>(
-    process: &Process,
+pub fn decode_tagged<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
 ) -> InternalResult<(Term, &'a [u8])> {
@@ -11,31 +18,84 @@ pub fn decode_tagged<'a>(
         Tag::Atom => atom::decode_term(safe, after_tag_bytes),
         Tag::AtomCacheReference => unimplemented!("{:?}", tag),
         Tag::AtomUTF8 => atom_utf8::decode_term(safe, after_tag_bytes),
-        Tag::Binary => binary::decode(process, after_tag_bytes),
-        Tag::BitBinary => bit_binary::decode(process, after_tag_bytes),
-        Tag::Export => export::decode(process, safe, after_tag_bytes),
+        Tag::Binary => binary::decode(heap, after_tag_bytes),
+        Tag::BitBinary => bit_binary::decode(heap, after_tag_bytes),
+        Tag::Export => export::decode(heap, safe, after_tag_bytes),
         Tag::Float => unimplemented!("{:?}", tag),
         Tag::Function => unimplemented!("{:?}", tag),
-        Tag::Integer => integer::decode(process, after_tag_bytes),
-        Tag::LargeBig => big::large::decode(process, after_tag_bytes),
-        Tag::LargeTuple => tuple::large::decode(process, safe, after_tag_bytes),
-        Tag::List => list::decode(process, safe, after_tag_bytes),
-        Tag::Map => map::decode(process, safe, after_tag_bytes),
-        Tag::NewFloat => new_float::decode(process, after_tag_bytes),
-        Tag::NewFunction => new_function::decode(process, safe, after_tag_bytes),
-        Tag::NewPID => new_pid::decode_term(process, safe, after_tag_bytes),
+        Tag::Integer => integer::decode(heap, after_tag_bytes),
+        Tag::LargeBig => big::large::decode(heap, after_tag_bytes),
+        Tag::LargeTuple => tuple::large::decode(heap, safe, after_tag_bytes),
+        Tag::List => list::decode(heap, safe, after_tag_bytes),
+        Tag::Map => map::decode(heap, safe, after_tag_bytes),
+        Tag::NewFloat => new_float::decode(heap, after_tag_bytes),
+        Tag::NewFunction => new_function::decode(heap, safe, after_tag_bytes),
+        Tag::NewPID => new_pid::decode_term(heap, safe, after_tag_bytes),
         Tag::NewPort => unimplemented!("{:?}", tag),
         Tag::NewReference => unimplemented!("{:?}", tag),
-        Tag::NewerReference => newer_reference::decode(process, safe, after_tag_bytes),
+        Tag::NewerReference => newer_reference::decode(heap, safe, after_tag_bytes),
         Tag::Nil => Ok((Term::NIL, after_tag_bytes)),
-        Tag::PID => pid::decode_term(process, safe, after_tag_bytes),
+        Tag::PID => pid::decode_term(heap, safe, after_tag_bytes),
         Tag::Port => unimplemented!("{:?}", tag),
         Tag::Reference => unimplemented!("{:?}", tag),
         Tag::SmallAtom => small_atom::decode(safe, after_tag_bytes),
         Tag::SmallAtomUTF8 => small_atom_utf8::decode_term(safe, after_tag_bytes),
-        Tag::SmallBig => big::small::decode(process, after_tag_bytes),
-        Tag::SmallInteger => small_integer::decode(process, after_tag_bytes),
-        Tag::SmallTuple => tuple::small::decode(process, safe, after_tag_bytes),
-        Tag::String => string::decode(process, after_tag_bytes),
+        Tag::SmallBig => big::small::decode(heap, after_tag_bytes),
+        Tag::SmallInteger => small_integer::decode(heap, after_tag_bytes),
+        Tag::SmallTuple => tuple::small::decode(heap, safe, after_tag_bytes),
+        Tag::String => string::decode(heap, after_tag_bytes),
     }
 }
+
+/// Decodes a term into a new `HeapFragment` instead of a process's heap, so that terms received
+/// outside of the schedulers can be sent with `Process::send_heap_message` without touching the
+/// heap of a process that may be running or collecting garbage on another scheduler.
+pub fn decode_tagged_to_fragment<'a>(
+    safe: bool,
+    bytes: &'a [u8],
+) -> InternalResult<(Term, NonNull<HeapFragment>, &'a [u8])> {
+    // Binaries take about a word for every word of their encoding, so that is the first guess.
+    let word_size = bytes.len() / mem::size_of::<Term>();
+
+    to_fragment(word_size, |heap_fragment| {
+        decode_tagged(heap_fragment, safe, bytes)
+    })
+    .map(|((term, after_term_bytes), heap_fragment)| (term, heap_fragment, after_term_bytes))
+}
+
+/// Runs `build` on a new `HeapFragment` of at least `word_size` words.  If `build` runs out of
+/// space, it is run again on a fragment twice the size until it fits, so that terms whose size is
+/// only known after building them, such as decoded terms, can still go in a single fragment.
+pub fn to_fragment<T, F>(
+    word_size: usize,
+    mut build: F,
+) -> InternalResult<(T, NonNull<HeapFragment>)>
+where
+    F: FnMut(&mut HeapFragment) -> InternalResult<T>,
+{
+    let mut word_size = word_size + MIN_FRAGMENT_WORD_SIZE;
+
+    loop {
+        let mut heap_fragment = HeapFragment::new_from_word_size(word_size)?;
+        let heap_fragment_ref = unsafe { heap_fragment.as_mut() };
+        // `HeapFragment`'s `Drop` releases the term in the first word, which would otherwise be
+        // uninitialized if `build` does not allocate anything, such as when it returns an atom.
+        unsafe { heap_fragment_ref.heap_start().write(Term::NIL) };
+
+        match build(heap_fragment_ref) {
+            Ok(built) => return Ok((built, heap_fragment)),
+            Err(error) => {
+                unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+
+                match error {
+                    InternalException::System(SystemException::Alloc(_)) => word_size *= 2,
+                    _ => return Err(error),
+                }
+            }
+        }
+    }
+}
+
+// Private
+
+const MIN_FRAGMENT_WORD_SIZE: usize = 16;
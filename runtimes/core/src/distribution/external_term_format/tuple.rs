@@ -2,19 +2,19 @@ pub mod large;
 pub mod small;
 
 use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
 use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
 
 use super::decode_vec_term;
 
-fn decode<'a>(
-    process: &Process,
+fn decode<'a, A: TermAlloc>(
+    heap: &mut A,
     safe: bool,
     bytes: &'a [u8],
     len: usize,
 ) -> InternalResult<(Term, &'a [u8])> {
-    let (element_vec, after_elements_vec) = decode_vec_term(process, safe, bytes, len)?;
-    let tuple = process.tuple_from_slice(&element_vec)?;
+    let (element_vec, after_elements_vec) = decode_vec_term(heap, safe, bytes, len)?;
+    let tuple = heap.tuple_from_slice(&element_vec)?.into();
 
     Ok((tuple, after_elements_vec))
 }
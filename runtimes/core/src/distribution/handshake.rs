@@ -0,0 +1,267 @@
+//! The [distribution handshake](http://erlang.org/doc/apps/erts/erl_dist_protocol.html#distribution-handshake)
+//! that authenticates both nodes with the shared cookie before any control messages are sent.
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::io::{Read, Write};
+use std::str;
+
+use anyhow::*;
+
+/// The node is published in EPMD, so it shows up in `nodes()`
+pub const PUBLISHED: u64 = 0x1;
+pub const EXTENDED_REFERENCES: u64 = 0x4;
+pub const DIST_MONITOR: u64 = 0x8;
+pub const FUN_TAGS: u64 = 0x10;
+pub const NEW_FUN_TAGS: u64 = 0x80;
+pub const EXTENDED_PIDS_PORTS: u64 = 0x100;
+pub const EXPORT_PTR_TAG: u64 = 0x200;
+pub const BIT_BINARIES: u64 = 0x400;
+pub const NEW_FLOATS: u64 = 0x800;
+pub const UTF8_ATOMS: u64 = 0x10000;
+pub const MAP_TAG: u64 = 0x20000;
+pub const BIG_CREATION: u64 = 0x40000;
+pub const HANDSHAKE_23: u64 = 0x0100_0000;
+pub const UNLINK_ID: u64 = 0x0200_0000;
+pub const V4_NC: u64 = 0x0004_0000_0000;
+
+/// The flags every node since OTP 25 requires
+pub const MANDATORY: u64 = EXTENDED_REFERENCES
+    | FUN_TAGS
+    | EXTENDED_PIDS_PORTS
+    | UTF8_ATOMS
+    | NEW_FUN_TAGS
+    | BIG_CREATION
+    | NEW_FLOATS
+    | MAP_TAG
+    | EXPORT_PTR_TAG
+    | BIT_BINARIES
+    | HANDSHAKE_23;
+
+/// The flags this node sends in its name and challenge
+pub const FLAGS: u64 = MANDATORY | PUBLISHED | DIST_MONITOR | UNLINK_ID | V4_NC;
+
+/// The local side of the handshake
+pub struct Local<'a> {
+    pub name: &'a str,
+    pub creation: u32,
+    pub cookie: &'a str,
+}
+
+/// The other node, once the handshake succeeds
+#[derive(Debug)]
+pub struct Peer {
+    pub name: String,
+    pub flags: u64,
+    pub creation: u32,
+}
+
+/// Performs the handshake for the node that connected
+pub fn initiate<S: Read + Write>(stream: &mut S, local: &Local) -> anyhow::Result<Peer> {
+    send_name(stream, local)?;
+
+    let status_message = read_message(stream)?;
+    match status_message.split_first() {
+        Some((b's', status)) => match status {
+            b"ok" | b"ok_simultaneous" => (),
+            _ => {
+                return Err(anyhow!(
+                    "connection refused with status ({})",
+                    String::from_utf8_lossy(status)
+                ))
+            }
+        },
+        _ => return Err(anyhow!("expected status message")),
+    }
+
+    let challenge_message = read_message(stream)?;
+    let (peer, peer_challenge) = match challenge_message.split_first() {
+        Some((b'N', after_tag_bytes)) => decode_challenge(after_tag_bytes)?,
+        _ => return Err(anyhow!("expected challenge (N) message")),
+    };
+
+    let challenge = new_challenge()?;
+    let mut reply = vec![b'r'];
+    reply.extend_from_slice(&challenge.to_be_bytes());
+    reply.extend_from_slice(&digest(local.cookie, peer_challenge));
+    write_message(stream, &reply)?;
+
+    let ack = read_message(stream)?;
+    match ack.split_first() {
+        Some((b'a', peer_digest)) if peer_digest == digest(local.cookie, challenge) => Ok(peer),
+        Some((b'a', _)) => Err(anyhow!("node ({}) has a different cookie", peer.name)),
+        _ => Err(anyhow!("expected challenge ack (a) message")),
+    }
+}
+
+/// Performs the handshake for the node that accepted the connection.  `is_connected` rejects nodes
+/// that already have a connection.
+pub fn accept<S: Read + Write>(
+    stream: &mut S,
+    local: &Local,
+    is_connected: impl Fn(&str) -> bool,
+) -> anyhow::Result<Peer> {
+    let name_message = read_message(stream)?;
+    let peer = match name_message.split_first() {
+        Some((b'N', after_tag_bytes)) => decode_name(after_tag_bytes)?,
+        Some((b'n', _)) => {
+            write_message(stream, b"snot_allowed")?;
+
+            return Err(anyhow!("only version 6 (OTP 23+) handshakes are supported"));
+        }
+        _ => return Err(anyhow!("expected name (N) message")),
+    };
+
+    if peer.flags & MANDATORY != MANDATORY {
+        write_message(stream, b"snot_allowed")?;
+
+        return Err(anyhow!(
+            "node ({}) does not support the mandatory distribution flags",
+            peer.name
+        ));
+    }
+
+    if is_connected(&peer.name) {
+        write_message(stream, b"snok")?;
+
+        return Err(anyhow!("node ({}) is already connected", peer.name));
+    }
+
+    write_message(stream, b"sok")?;
+
+    let challenge = new_challenge()?;
+    let mut challenge_message = vec![b'N'];
+    challenge_message.extend_from_slice(&FLAGS.to_be_bytes());
+    challenge_message.extend_from_slice(&challenge.to_be_bytes());
+    challenge_message.extend_from_slice(&local.creation.to_be_bytes());
+    append_name(&mut challenge_message, local.name)?;
+    write_message(stream, &challenge_message)?;
+
+    let reply = read_message(stream)?;
+    let peer_challenge = match reply.split_first() {
+        Some((b'r', after_tag_bytes)) if after_tag_bytes.len() == 4 + DIGEST_LEN => {
+            let (challenge_bytes, peer_digest) = after_tag_bytes.split_at(4);
+
+            if peer_digest != digest(local.cookie, challenge) {
+                return Err(anyhow!("node ({}) has a different cookie", peer.name));
+            }
+
+            u32::from_be_bytes(challenge_bytes.try_into().unwrap())
+        }
+        _ => return Err(anyhow!("expected challenge reply (r) message")),
+    };
+
+    let mut ack = vec![b'a'];
+    ack.extend_from_slice(&digest(local.cookie, peer_challenge));
+    write_message(stream, &ack)?;
+
+    Ok(peer)
+}
+
+/// MD5 of the cookie followed by the challenge as a decimal string
+pub fn digest(cookie: &str, challenge: u32) -> [u8; DIGEST_LEN] {
+    let mut input = cookie.as_bytes().to_vec();
+    input.extend_from_slice(challenge.to_string().as_bytes());
+
+    md5::compute(input).0
+}
+
+// Private
+
+const DIGEST_LEN: usize = 16;
+
+fn append_name(byte_vec: &mut Vec<u8>, name: &str) -> anyhow::Result<()> {
+    let len_u16: u16 = name.len().try_into().context("name is too long")?;
+    byte_vec.extend_from_slice(&len_u16.to_be_bytes());
+    byte_vec.extend_from_slice(name.as_bytes());
+
+    Ok(())
+}
+
+/// Decodes the `N` challenge after its tag, which is the name message with a challenge
+fn decode_challenge(bytes: &[u8]) -> anyhow::Result<(Peer, u32)> {
+    if bytes.len() < 18 {
+        return Err(anyhow!("challenge (N) message is too short"));
+    }
+
+    let flags = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+    let challenge = u32::from_be_bytes(bytes[8..12].try_into().unwrap());
+    let creation = u32::from_be_bytes(bytes[12..16].try_into().unwrap());
+    let name = decode_u16_len_name(&bytes[16..])?;
+
+    Ok((
+        Peer {
+            name,
+            flags,
+            creation,
+        },
+        challenge,
+    ))
+}
+
+/// Decodes the `N` name message after its tag
+fn decode_name(bytes: &[u8]) -> anyhow::Result<Peer> {
+    if bytes.len() < 14 {
+        return Err(anyhow!("name (N) message is too short"));
+    }
+
+    let flags = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+    let creation = u32::from_be_bytes(bytes[8..12].try_into().unwrap());
+    let name = decode_u16_len_name(&bytes[12..])?;
+
+    Ok(Peer {
+        name,
+        flags,
+        creation,
+    })
+}
+
+fn decode_u16_len_name(bytes: &[u8]) -> anyhow::Result<String> {
+    let len = u16::from_be_bytes(bytes[0..2].try_into().unwrap()) as usize;
+    let name_bytes = bytes
+        .get(2..2 + len)
+        .context("name is shorter than its length")?;
+
+    str::from_utf8(name_bytes)
+        .map(ToString::to_string)
+        .context("name is not UTF-8")
+}
+
+/// Challenges must be unpredictable, or a node that doesn't know the cookie could replay a digest
+fn new_challenge() -> anyhow::Result<u32> {
+    let mut bytes = [0; 4];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|error| anyhow!("could not generate challenge: {}", error))?;
+
+    Ok(u32::from_be_bytes(bytes))
+}
+
+fn read_message<S: Read>(stream: &mut S) -> anyhow::Result<Vec<u8>> {
+    let mut len = [0; 2];
+    stream.read_exact(&mut len)?;
+
+    let mut message = vec![0; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut message)?;
+
+    Ok(message)
+}
+
+fn send_name<S: Write>(stream: &mut S, local: &Local) -> anyhow::Result<()> {
+    let mut name_message = vec![b'N'];
+    name_message.extend_from_slice(&FLAGS.to_be_bytes());
+    name_message.extend_from_slice(&local.creation.to_be_bytes());
+    append_name(&mut name_message, local.name)?;
+
+    write_message(stream, &name_message)
+}
+
+fn write_message<S: Write>(stream: &mut S, message: &[u8]) -> anyhow::Result<()> {
+    let len_u16: u16 = message.len().try_into().context("message is too long")?;
+    let mut framed = len_u16.to_be_bytes().to_vec();
+    framed.extend_from_slice(message);
+    stream.write_all(&framed)?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use super::*;
+
+#[test]
+fn with_same_cookie_both_nodes_learn_the_other() {
+    let (accepted, initiated) = handshake("cookie", "cookie");
+
+    let accepted_peer = accepted.unwrap();
+    assert_eq!(accepted_peer.name, "initiator@localhost");
+    assert_eq!(accepted_peer.creation, 1);
+    assert_eq!(accepted_peer.flags, FLAGS);
+
+    let initiated_peer = initiated.unwrap();
+    assert_eq!(initiated_peer.name, "acceptor@localhost");
+    assert_eq!(initiated_peer.creation, 2);
+}
+
+#[test]
+fn with_different_cookie_acceptor_rejects_initiator() {
+    let (accepted, initiated) = handshake("acceptor_cookie", "initiator_cookie");
+
+    assert!(accepted.is_err());
+    assert!(initiated.is_err());
+}
+
+#[test]
+fn digest_is_md5_of_cookie_and_decimal_challenge() {
+    assert_eq!(digest("cookie", 123), md5::compute(b"cookie123").0);
+}
+
+fn handshake(
+    acceptor_cookie: &'static str,
+    initiator_cookie: &'static str,
+) -> (anyhow::Result<Peer>, anyhow::Result<Peer>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let acceptor = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let local = Local {
+            name: "acceptor@localhost",
+            creation: 2,
+            cookie: acceptor_cookie,
+        };
+
+        accept(&mut stream, &local, |_| false)
+    });
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let local = Local {
+        name: "initiator@localhost",
+        creation: 1,
+        cookie: initiator_cookie,
+    };
+    let initiated = initiate(&mut stream, &local);
+    // Unblock the acceptor if the initiator gave up early
+    drop(stream);
+
+    (acceptor.join().unwrap(), initiated)
+}
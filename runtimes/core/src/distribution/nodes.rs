@@ -1,6 +1,7 @@
 pub mod node;
 
 use std::backtrace::Backtrace;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use hashbrown::HashMap;
@@ -75,6 +76,20 @@ pub fn insert(arc_node: Arc<Node>) {
         .unwrap_none();
 }
 
+/// An id for a node that is not in the registry yet
+pub fn next_id() -> usize {
+    NEXT_ID.fetch_add(1, Ordering::SeqCst)
+}
+
+/// Renames `arc_node` and changes its creation, keeping the registry keyed by the new name
+pub fn set_name_and_creation(arc_node: &Arc<Node>, name: Atom, creation: u32) {
+    let mut arc_node_by_name = RW_LOCK_ARC_NODE_BY_NAME.write();
+    arc_node_by_name.remove(&arc_node.name());
+
+    arc_node.set_name_and_creation(name, creation);
+    arc_node_by_name.insert(name, arc_node.clone());
+}
+
 #[derive(Debug, Error)]
 pub enum NodeNotFound {
     #[error("No node with name ({name})")]
@@ -99,6 +114,9 @@ impl From<NodeNotFound> for RuntimeException {
     }
 }
 
+// The local node is always id 0
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
 lazy_static! {
     static ref RW_LOCK_ARC_NODE_BY_ID: RwLock<HashMap<usize, Arc<Node>>> = {
         let mut hash_map = HashMap::new();
@@ -0,0 +1,160 @@
+//! Erlang Term Storage (ETS): tables of tuples that are shared between processes.
+//!
+//! Objects are copied off of the inserting process's heap into `HeapFragment`s owned by the
+//! table, so that they outlive the process that inserted them.  Tables are owned by a process and
+//! when the owner exits they are either given to the heir or deleted.
+mod object;
+mod options;
+pub mod table;
+
+use std::ptr;
+use std::sync::Arc;
+
+use anyhow::*;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::HeapFragment;
+
+use crate::registry::pid_to_process;
+use crate::scheduler;
+
+pub use object::Object;
+pub use options::Options;
+pub use table::{Access, Data, Heir, Table, Type};
+
+lazy_static! {
+    static ref TABLE_BY_REFERENCE: DashMap<Reference, Arc<Table>> = Default::default();
+    static ref REFERENCE_BY_NAME: DashMap<Atom, Reference> = Default::default();
+}
+
+/// All tables, in no particular order
+pub fn all() -> Vec<Arc<Table>> {
+    TABLE_BY_REFERENCE
+        .iter()
+        .map(|entry| entry.value().clone())
+        .collect()
+}
+
+/// Removes `table`, so that it can no longer be found by its name or reference.  Objects are
+/// freed once the last in-flight operation on the table finishes.
+pub fn delete(table: &Table) -> bool {
+    if table.is_named() {
+        REFERENCE_BY_NAME.remove(&table.name());
+    }
+
+    TABLE_BY_REFERENCE.remove(&table.reference()).is_some()
+}
+
+/// Registers `table`.  Fails if `table` is named and another table already has the same name.
+pub fn insert(table: Table) -> anyhow::Result<Arc<Table>> {
+    let arc_table = Arc::new(table);
+
+    if arc_table.is_named() {
+        let name = arc_table.name();
+
+        // Check and insert under the same shard lock, so that two tables can't take the same name
+        match REFERENCE_BY_NAME.entry(name) {
+            dashmap::mapref::entry::Entry::Occupied(_) => {
+                return Err(anyhow!("table name ({}) is already in use", name));
+            }
+            dashmap::mapref::entry::Entry::Vacant(vacant) => {
+                vacant.insert(arc_table.reference());
+            }
+        }
+    }
+
+    TABLE_BY_REFERENCE.insert(arc_table.reference(), arc_table.clone());
+
+    Ok(arc_table)
+}
+
+/// Gives each table owned by the exiting `process` to its heir or deletes it.
+///
+/// > If the heir is not alive, or is the owner itself, the table is deleted.
+/// > -- http://erlang.org/doc/man/ets.html#new-2
+pub fn propagate_exit(process: &Process) {
+    let pid = process.pid();
+
+    for table in all().into_iter().filter(|table| table.owner() == pid) {
+        let heir_arc_process = table
+            .heir()
+            .filter(|heir_pid| *heir_pid != pid)
+            .and_then(|heir_pid| pid_to_process(&heir_pid))
+            .filter(|heir_arc_process| !heir_arc_process.is_exiting());
+
+        match heir_arc_process {
+            Some(heir_arc_process) => {
+                table.set_owner(heir_arc_process.pid());
+
+                // Messages can't be allocated on the exiting process or the table, so if the
+                // message can't be allocated, the heir still owns the table, but isn't told.
+                let _ = send_transfer(
+                    &heir_arc_process,
+                    table.tid(),
+                    pid,
+                    table.heir_data().unwrap(),
+                );
+            }
+            None => {
+                delete(&table);
+            }
+        }
+    }
+}
+
+/// Finds a table by its name (`Atom`) or its `Reference`
+pub fn term_to_table(tid: Term) -> Option<Arc<Table>> {
+    match tid.decode() {
+        Ok(TypedTerm::Atom(name)) => REFERENCE_BY_NAME
+            .get(&name)
+            .and_then(|reference| reference_to_table(reference.value())),
+        Ok(TypedTerm::Reference(reference)) => reference_to_table(&reference),
+        _ => None,
+    }
+}
+
+/// Sends `{'ETS-TRANSFER', tid, from_pid, heir_data}` to `to_process`, such as when the owner
+/// gives away the table or exits and the table goes to its heir.  `tid` and `heir_data` may be
+/// owned by the table instead of a process, as they are copied to `to_process`.
+pub fn send_transfer(
+    to_process: &Process,
+    tid: Term,
+    from_pid: Pid,
+    heir_data: Term,
+) -> AllocResult<()> {
+    let elements = [
+        atom!("ETS-TRANSFER"),
+        tid,
+        from_pid.encode().unwrap(),
+        heir_data,
+    ];
+    let (layout, _) = Tuple::layout_for(&elements);
+    let mut heap_fragment = HeapFragment::new(layout)?;
+
+    let result = unsafe { heap_fragment.as_mut() }
+        .tuple_from_slice(&elements)
+        // `send_from_other` copies `message`, so the fragment is only needed until then.
+        .and_then(|message| to_process.send_from_other(message.into()));
+
+    unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+
+    if result? {
+        scheduler::wake(to_process);
+    }
+
+    Ok(())
+}
+
+// Private
+
+fn reference_to_table(reference: &Reference) -> Option<Arc<Table>> {
+    TABLE_BY_REFERENCE
+        .get(reference)
+        .map(|entry| entry.value().clone())
+}
@@ -0,0 +1,74 @@
+use std::convert::TryInto;
+use std::fmt::{self, Debug};
+use std::ptr::{self, NonNull};
+
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc::Heap;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+/// A term copied off of a process heap into a `HeapFragment` owned by a table.  The fragment is
+/// freed when the `Object` is dropped, so the `term` must be copied to a process heap before the
+/// table lock is released.
+pub struct Object {
+    term: Term,
+    /// Immediates don't need a fragment
+    heap_fragment: Option<NonNull<HeapFragment>>,
+}
+
+impl Object {
+    pub fn new(term: Term) -> AllocResult<Self> {
+        if term.is_immediate() {
+            Ok(Self {
+                term,
+                heap_fragment: None,
+            })
+        } else {
+            let (term, heap_fragment) = term.clone_to_fragment()?;
+
+            Ok(Self {
+                term,
+                heap_fragment: Some(heap_fragment),
+            })
+        }
+    }
+
+    /// The element at the 1-based `keypos`.  `Object`s in a table are always tuples with at least
+    /// `keypos` elements.
+    pub fn key(&self, keypos: usize) -> Term {
+        let tuple: Boxed<Tuple> = self.term.try_into().unwrap();
+
+        tuple[keypos - 1]
+    }
+
+    /// The size of the `HeapFragment` in words
+    pub fn size_in_words(&self) -> usize {
+        match self.heap_fragment {
+            Some(heap_fragment) => unsafe { heap_fragment.as_ref() }.heap_size(),
+            None => 0,
+        }
+    }
+
+    pub fn term(&self) -> Term {
+        self.term
+    }
+}
+
+impl Debug for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Object({})", self.term)
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        if let Some(heap_fragment) = self.heap_fragment.take() {
+            // `HeapFragment`'s `Drop` deallocates its own memory
+            unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+        }
+    }
+}
+
+// The `HeapFragment` is only accessed through the table's lock
+unsafe impl Send for Object {}
+unsafe impl Sync for Object {}
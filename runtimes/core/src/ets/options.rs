@@ -0,0 +1,183 @@
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::proplist::TryPropListFromTermError;
+
+use super::table::{Access, Type};
+
+/// Options for `ets:new/2`
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub r#type: Type,
+    pub access: Access,
+    pub named_table: bool,
+    /// 1-based index of the key in each object
+    pub keypos: usize,
+    /// The heir's pid and the data sent to the heir when the table is given to it.  The data is
+    /// only valid until the table is created with these options, as it is on the process heap.
+    pub heir: Option<(Pid, Term)>,
+    pub read_concurrency: bool,
+    pub write_concurrency: bool,
+    pub compressed: bool,
+}
+
+impl Options {
+    fn put_option_atom(&mut self, atom: Atom) -> Result<&Self, anyhow::Error> {
+        match atom.name() {
+            "bag" => {
+                self.r#type = Type::Bag;
+
+                Ok(self)
+            }
+            "compressed" => {
+                self.compressed = true;
+
+                Ok(self)
+            }
+            "duplicate_bag" => {
+                self.r#type = Type::DuplicateBag;
+
+                Ok(self)
+            }
+            "named_table" => {
+                self.named_table = true;
+
+                Ok(self)
+            }
+            "ordered_set" => {
+                self.r#type = Type::OrderedSet;
+
+                Ok(self)
+            }
+            "private" => {
+                self.access = Access::Private;
+
+                Ok(self)
+            }
+            "protected" => {
+                self.access = Access::Protected;
+
+                Ok(self)
+            }
+            "public" => {
+                self.access = Access::Public;
+
+                Ok(self)
+            }
+            "set" => {
+                self.r#type = Type::Set;
+
+                Ok(self)
+            }
+            name => Err(TryPropListFromTermError::AtomName(name).into()),
+        }
+    }
+
+    fn put_option_term(&mut self, term: Term) -> Result<&Self, anyhow::Error> {
+        match term.decode().unwrap() {
+            TypedTerm::Atom(atom) => self.put_option_atom(atom),
+            TypedTerm::Tuple(tuple) => self.put_option_tuple(&tuple),
+            _ => Err(TryPropListFromTermError::PropertyType.into()),
+        }
+    }
+
+    fn put_option_tuple(&mut self, tuple: &Tuple) -> Result<&Self, anyhow::Error> {
+        let key = *tuple
+            .elements()
+            .first()
+            .ok_or(TryPropListFromTermError::TupleNotPair)?;
+        let atom: Atom = key
+            .try_into()
+            .map_err(|_| TryPropListFromTermError::KeywordKeyType)?;
+
+        match (atom.name(), tuple.len()) {
+            ("heir", 2) => {
+                let none: Atom = tuple[1].try_into().context("heir is not none")?;
+
+                if none.name() == "none" {
+                    self.heir = None;
+
+                    Ok(self)
+                } else {
+                    Err(anyhow!("heir ({}) is not none", none))
+                }
+            }
+            ("heir", 3) => {
+                let pid: Pid = tuple[1].try_into().context("heir is not a local pid")?;
+                self.heir = Some((pid, tuple[2]));
+
+                Ok(self)
+            }
+            ("keypos", 2) => {
+                let keypos: usize = tuple[1].try_into().context("keypos")?;
+
+                if 0 < keypos {
+                    self.keypos = keypos;
+
+                    Ok(self)
+                } else {
+                    Err(anyhow!("keypos ({}) is not a positive integer", tuple[1]))
+                }
+            }
+            ("read_concurrency", 2) => {
+                self.read_concurrency = tuple[1].try_into().context("read_concurrency")?;
+
+                Ok(self)
+            }
+            ("write_concurrency", 2) => {
+                self.write_concurrency = tuple[1].try_into().context("write_concurrency")?;
+
+                Ok(self)
+            }
+            (_, 2) => Err(TryPropListFromTermError::KeywordKeyName(atom.name()).into()),
+            _ => Err(TryPropListFromTermError::TupleNotPair.into()),
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            r#type: Type::Set,
+            access: Access::Protected,
+            named_table: false,
+            keypos: 1,
+            heir: None,
+            read_concurrency: false,
+            write_concurrency: false,
+            compressed: false,
+        }
+    }
+}
+
+const SUPPORTED_OPTIONS_CONTEXT: &str = "supported options are :set, :ordered_set, :bag, \
+     :duplicate_bag, :public, :protected, :private, :named_table, :compressed, \
+     {:keypos, pos_integer()}, {:heir, pid(), heir_data :: term()}, {:heir, :none}, \
+     {:read_concurrency, boolean()}, and {:write_concurrency, boolean()}";
+
+impl TryFrom<Term> for Options {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.decode().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options
+                        .put_option_term(cons.head)
+                        .context(SUPPORTED_OPTIONS_CONTEXT)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(ImproperListError).context(SUPPORTED_OPTIONS_CONTEXT),
+            };
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use liblumen_core::locks::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::{Object, Options};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Type {
+    Set,
+    OrderedSet,
+    Bag,
+    DuplicateBag,
+}
+
+impl From<Type> for Atom {
+    fn from(r#type: Type) -> Self {
+        let name = match r#type {
+            Type::Set => "set",
+            Type::OrderedSet => "ordered_set",
+            Type::Bag => "bag",
+            Type::DuplicateBag => "duplicate_bag",
+        };
+
+        Atom::from_str(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// Any process can read or write
+    Public,
+    /// Any process can read, but only the owner can write
+    Protected,
+    /// Only the owner can read or write
+    Private,
+}
+
+impl From<Access> for Atom {
+    fn from(access: Access) -> Self {
+        let name = match access {
+            Access::Public => "public",
+            Access::Protected => "protected",
+            Access::Private => "private",
+        };
+
+        Atom::from_str(name)
+    }
+}
+
+/// The process that inherits the table when the owner exits
+#[derive(Debug)]
+pub struct Heir {
+    pub pid: Pid,
+    /// Sent to `pid` in the `'ETS-TRANSFER'` message
+    pub data: Object,
+}
+
+#[derive(Debug)]
+pub struct Table {
+    reference: Reference,
+    /// `reference` as a `Term`, so it can be put in messages without a process heap
+    reference_object: Object,
+    name: Atom,
+    named: bool,
+    r#type: Type,
+    access: Access,
+    keypos: usize,
+    owner: RwLock<Pid>,
+    heir: RwLock<Option<Heir>>,
+    read_concurrency: bool,
+    write_concurrency: bool,
+    compressed: bool,
+    data: RwLock<Data>,
+}
+
+impl Table {
+    pub fn new(
+        name: Atom,
+        reference: Reference,
+        reference_term: Term,
+        owner: Pid,
+        options: &Options,
+    ) -> AllocResult<Self> {
+        let heir = match options.heir {
+            Some((pid, data)) => Some(Heir {
+                pid,
+                data: Object::new(data)?,
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            reference,
+            reference_object: Object::new(reference_term)?,
+            name,
+            named: options.named_table,
+            r#type: options.r#type,
+            access: options.access,
+            keypos: options.keypos,
+            owner: RwLock::new(owner),
+            heir: RwLock::new(heir),
+            read_concurrency: options.read_concurrency,
+            write_concurrency: options.write_concurrency,
+            compressed: options.compressed,
+            data: RwLock::new(Data::new(options.r#type)),
+        })
+    }
+
+    pub fn access(&self) -> Access {
+        self.access
+    }
+
+    /// Whether `pid` can read objects from the table
+    pub fn can_read(&self, pid: Pid) -> bool {
+        match self.access {
+            Access::Public | Access::Protected => true,
+            Access::Private => self.owner() == pid,
+        }
+    }
+
+    /// Whether `pid` can insert, update, or delete objects in the table
+    pub fn can_write(&self, pid: Pid) -> bool {
+        match self.access {
+            Access::Public => true,
+            Access::Protected | Access::Private => self.owner() == pid,
+        }
+    }
+
+    pub fn data(&self) -> RwLockReadGuard<Data> {
+        self.data.read()
+    }
+
+    pub fn data_mut(&self) -> RwLockWriteGuard<Data> {
+        self.data.write()
+    }
+
+    pub fn heir(&self) -> Option<Pid> {
+        self.heir.read().as_ref().map(|heir| heir.pid)
+    }
+
+    /// The `heir_data` owned by the table.  It must be copied to a process heap before the table
+    /// is deleted.
+    pub fn heir_data(&self) -> Option<Term> {
+        self.heir.read().as_ref().map(|heir| heir.data.term())
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    pub fn is_named(&self) -> bool {
+        self.named
+    }
+
+    pub fn keypos(&self) -> usize {
+        self.keypos
+    }
+
+    /// The memory used by the table and its objects in words
+    pub fn memory(&self) -> usize {
+        let data = self.data();
+
+        mem::size_of::<Self>() / mem::size_of::<Term>()
+            + self.reference_object.size_in_words()
+            + data.size_in_words()
+    }
+
+    pub fn name(&self) -> Atom {
+        self.name
+    }
+
+    pub fn owner(&self) -> Pid {
+        *self.owner.read()
+    }
+
+    pub fn read_concurrency(&self) -> bool {
+        self.read_concurrency
+    }
+
+    pub fn reference(&self) -> Reference {
+        self.reference
+    }
+
+    pub fn set_owner(&self, owner: Pid) {
+        *self.owner.write() = owner;
+    }
+
+    /// The table identifier returned from `ets:new/2` when the table is not named
+    pub fn tid(&self) -> Term {
+        self.reference_object.term()
+    }
+
+    pub fn r#type(&self) -> Type {
+        self.r#type
+    }
+
+    pub fn write_concurrency(&self) -> bool {
+        self.write_concurrency
+    }
+}
+
+/// The objects in a table, stored according to the table's `Type`.
+///
+/// Keys in the maps point into the `HeapFragment` of the `Object` they are the key of, so an entry
+/// must be removed before its `Object` is replaced.
+#[derive(Debug)]
+pub enum Data {
+    Set(HashMap<Key, Object>),
+    /// Keys are compared with `==`, so `1` and `1.0` are the same key
+    OrderedSet(BTreeMap<Term, Object>),
+    /// Objects with the same key are kept unless they are exactly equal
+    Bag(HashMap<Key, Vec<Object>>),
+    DuplicateBag(HashMap<Key, Vec<Object>>),
+}
+
+impl Data {
+    pub fn new(r#type: Type) -> Self {
+        match r#type {
+            Type::Set => Data::Set(Default::default()),
+            Type::OrderedSet => Data::OrderedSet(Default::default()),
+            Type::Bag => Data::Bag(Default::default()),
+            Type::DuplicateBag => Data::DuplicateBag(Default::default()),
+        }
+    }
+
+    /// Deletes all objects with `key`
+    pub fn delete(&mut self, key: Term) {
+        match self {
+            Data::Set(map) => {
+                map.remove(&Key(key));
+            }
+            Data::OrderedSet(map) => {
+                map.remove(&key);
+            }
+            Data::Bag(map) | Data::DuplicateBag(map) => {
+                map.remove(&Key(key));
+            }
+        }
+    }
+
+    pub fn delete_all(&mut self) {
+        match self {
+            Data::Set(map) => map.clear(),
+            Data::OrderedSet(map) => map.clear(),
+            Data::Bag(map) | Data::DuplicateBag(map) => map.clear(),
+        }
+    }
+
+    /// Inserts `object`, replacing the object with the same key in `Set`s and `OrderedSet`s.
+    pub fn insert(&mut self, keypos: usize, object: Object) {
+        let key = object.key(keypos);
+
+        match self {
+            Data::Set(map) => {
+                // `insert` keeps the old key, which is freed with the old object
+                map.remove(&Key(key));
+                map.insert(Key(key), object);
+            }
+            Data::OrderedSet(map) => {
+                map.remove(&key);
+                map.insert(key, object);
+            }
+            Data::Bag(map) => {
+                let objects = map.entry(Key(key)).or_insert_with(Default::default);
+                let object_typed_term = object.term().decode().unwrap();
+
+                if !objects.iter().any(|existing| {
+                    existing
+                        .term()
+                        .decode()
+                        .unwrap()
+                        .exact_eq(&object_typed_term)
+                }) {
+                    objects.push(object);
+                }
+            }
+            Data::DuplicateBag(map) => {
+                map.entry(Key(key))
+                    .or_insert_with(Default::default)
+                    .push(object);
+            }
+        }
+    }
+
+    /// All objects, in key order for `OrderedSet`s and in no particular key order otherwise.  Like
+    /// BEAM, objects with the same key in a bag are in insertion order.
+    pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Object> + 'a> {
+        match self {
+            Data::Set(map) => Box::new(map.values()),
+            Data::OrderedSet(map) => Box::new(map.values()),
+            Data::Bag(map) | Data::DuplicateBag(map) => Box::new(map.values().flatten()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Data::Set(map) => map.len(),
+            Data::OrderedSet(map) => map.len(),
+            Data::Bag(map) | Data::DuplicateBag(map) => map.values().map(Vec::len).sum(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The objects with `key`
+    pub fn lookup(&self, key: Term) -> Vec<&Object> {
+        match self {
+            Data::Set(map) => map.get(&Key(key)).into_iter().collect(),
+            Data::OrderedSet(map) => map.get(&key).into_iter().collect(),
+            Data::Bag(map) | Data::DuplicateBag(map) => map
+                .get(&Key(key))
+                .map(|objects| objects.iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The size of all objects in words
+    pub fn size_in_words(&self) -> usize {
+        self.iter().map(Object::size_in_words).sum()
+    }
+}
+
+/// A key compared with `=:=`, so that `1` and `1.0` are different keys, as in `set`s and bags.
+#[derive(Clone, Copy, Debug)]
+pub struct Key(Term);
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .decode()
+            .unwrap()
+            .exact_eq(&other.0.decode().unwrap())
+    }
+}
+
+impl Eq for Key {}
@@ -0,0 +1,51 @@
+//! A program embedding the runtime receives messages through callbacks, which are registered
+//! under a name like a process, so that Erlang code can reply with `Name ! Message`.
+use std::ffi::c_void;
+use std::fmt::{self, Debug};
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::distribution::external_term_format::encode::term_to_byte_vec;
+
+/// Called with the `data` given at registration and a message in the external term format.
+///
+/// The message bytes are only valid for the duration of the call.
+pub type Callback = unsafe extern "C" fn(data: *mut c_void, message: *const u8, len: usize);
+
+#[derive(Clone, Copy)]
+pub struct Host {
+    callback: Callback,
+    data: *mut c_void,
+}
+
+// The program embedding the runtime is responsible for `data` being usable from any scheduler
+unsafe impl Send for Host {}
+unsafe impl Sync for Host {}
+
+impl Host {
+    pub fn new(callback: Callback, data: *mut c_void) -> Self {
+        Self { callback, data }
+    }
+
+    /// Encodes `message` and passes it to the callback on the calling thread
+    pub fn send(&self, message: Term) {
+        let byte_vec = term_to_byte_vec(message);
+
+        unsafe { (self.callback)(self.data, byte_vec.as_ptr(), byte_vec.len()) }
+    }
+}
+
+impl Debug for Host {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Host")
+            .field("callback", &(self.callback as *const c_void))
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl PartialEq for Host {
+    fn eq(&self, other: &Host) -> bool {
+        (self.callback as usize) == (other.callback as usize) && self.data == other.data
+    }
+}
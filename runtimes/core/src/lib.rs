@@ -4,12 +4,16 @@
 #![feature(option_unwrap_none)]
 #![feature(trait_alias)]
 
+pub mod application;
 pub mod binary_to_string;
 pub mod builtins;
 pub mod context;
 pub mod distribution;
+pub mod ets;
 pub mod future;
+pub mod host;
 pub mod process;
+pub mod profiler;
 pub mod proplist;
 pub mod registry;
 pub mod scheduler;
@@ -19,3 +23,4 @@ pub mod sys;
 pub mod test;
 pub mod time;
 pub mod timer;
+pub mod trace;
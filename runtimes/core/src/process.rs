@@ -11,9 +11,12 @@ use liblumen_alloc::erts::process::{Process, ProcessHeap};
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::{atom, CloneToProcess, HeapFragment, Monitor};
 
+use crate::distribution;
+use crate::ets;
 use crate::registry::*;
 use crate::scheduler::SchedulerDependentAlloc;
 use crate::sys;
+use crate::trace;
 
 thread_local! {
   pub static CURRENT_PROCESS: RefCell<Option<Arc<Process>>> = RefCell::new(None);
@@ -96,6 +99,12 @@ pub fn monitor(process: &Process, monitored_process: &Process) -> AllocResult<Te
 }
 
 pub fn propagate_exit(process: &Process, exception: &RuntimeException) {
+    trace::exit(
+        process,
+        exception.reason().unwrap_or_else(|| atom!("system_error")),
+    );
+    ets::propagate_exit(process);
+    distribution::propagate_exit(process, exception);
     monitor::propagate_exit(process, exception);
     propagate_exit_to_links(process, exception);
 }
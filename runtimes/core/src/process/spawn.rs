@@ -12,6 +12,7 @@ use liblumen_alloc::{Arity, ModuleFunctionArity};
 
 use crate::registry::put_pid_to_process;
 use crate::scheduler::{self, Scheduled, Scheduler};
+use crate::trace;
 
 pub use self::options::{Connection, Options};
 
@@ -40,7 +41,7 @@ pub fn apply_3(
 ) -> exception::Result<Spawned> {
     let arity = arity(arguments);
 
-    spawn(
+    let spawned = spawn(
         Some(parent_process),
         options,
         module,
@@ -55,7 +56,17 @@ pub fn apply_3(
 
             Ok(vec![frame_with_arguments])
         }),
-    )
+    )?;
+
+    trace::spawn(
+        parent_process,
+        &spawned.process,
+        module,
+        function,
+        trace::Arguments::List(arguments),
+    );
+
+    Ok(spawned)
 }
 
 /// Spawns a process with `arguments` on its stack and `native` run with those arguments instead
@@ -70,7 +81,7 @@ pub fn native<'a>(
 ) -> exception::Result<Spawned> {
     let arity = arguments.len() as u8;
 
-    spawn(
+    let spawned = spawn(
         parent_process,
         options,
         module,
@@ -87,7 +98,19 @@ pub fn native<'a>(
 
             Ok(vec![frame_with_arguments])
         }),
-    )
+    )?;
+
+    if let Some(parent_process) = parent_process {
+        trace::spawn(
+            parent_process,
+            &spawned.process,
+            module,
+            function,
+            trace::Arguments::Slice(arguments),
+        );
+    }
+
+    Ok(spawned)
 }
 
 pub fn spawn<'a>(
@@ -1,20 +1,35 @@
+mod max_heap_size;
 mod message_queue_data;
 
 use std::convert::{TryFrom, TryInto};
 
 use anyhow::*;
+use lazy_static::lazy_static;
 
 use liblumen_alloc::erts::exception::Alloc;
-use liblumen_alloc::erts::process::alloc::{default_heap_size, heap, next_heap_size};
+use liblumen_alloc::erts::process::alloc::{default_heap_size, heap, next_min_heap_size};
 use liblumen_alloc::erts::process::priority::Priority;
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::ModuleFunctionArity;
+use liblumen_core::locks::RwLock;
 
 use crate::process;
 use crate::proplist::TryPropListFromTermError;
 
-use message_queue_data::*;
+pub use max_heap_size::MaxHeapSize;
+pub use message_queue_data::MessageQueueData;
+
+/// The memory options of processes spawned without them
+pub fn defaults() -> Defaults {
+    *DEFAULTS.read()
+}
+
+/// Sets the memory options of processes spawned without them, like `erl`'s `+hms`, `+hmbs` and
+/// `+hmax`.  Already spawned processes are not changed.
+pub fn set_defaults(defaults: Defaults) {
+    *DEFAULTS.write() = defaults;
+}
 
 #[must_use]
 pub struct Connection {
@@ -23,11 +38,14 @@ pub struct Connection {
     pub monitor_reference: Option<Term>,
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct MaxHeapSize {
-    size: Option<usize>,
-    kill: Option<bool>,
-    error_logger: Option<bool>,
+/// The memory options of `Options` that can be set for the whole runtime.  `None` uses the
+/// default for `Process`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Defaults {
+    pub fullsweep_after: Option<usize>,
+    pub min_heap_size: Option<usize>,
+    pub min_bin_vheap_size: Option<usize>,
+    pub max_heap_size: Option<MaxHeapSize>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -83,13 +101,14 @@ impl Options {
         function: Atom,
         arity: u8,
     ) -> Result<Process, Alloc> {
+        let defaults = defaults();
         let priority = self.cascaded_priority(parent_process);
         let module_function_arity = ModuleFunctionArity {
             module,
             function,
             arity,
         };
-        let (heap, heap_size) = self.sized_heap()?;
+        let (heap, heap_size) = self.sized_heap(&defaults)?;
 
         let process = Process::new(
             priority,
@@ -99,6 +118,28 @@ impl Options {
             heap_size,
         );
 
+        if let Some(fullsweep_after) = self.fullsweep_after.or(defaults.fullsweep_after) {
+            process.set_fullsweep_after(fullsweep_after);
+        }
+
+        if let Some(min_bin_vheap_size) = self.min_bin_vheap_size.or(defaults.min_bin_vheap_size) {
+            process.set_min_vheap_size(min_bin_vheap_size);
+        }
+
+        if let Some(max_heap_size) = defaults.max_heap_size {
+            max_heap_size.apply(&process);
+        }
+
+        // Fields not set by the option keep their runtime-wide defaults, as when set with
+        // `process_flag/2`
+        if let Some(max_heap_size) = self.max_heap_size {
+            max_heap_size.apply(&process);
+        }
+
+        if self.message_queue_data.is_off_heap() {
+            process.set_message_queue_off_heap(true);
+        }
+
         Ok(process)
     }
 
@@ -108,16 +149,16 @@ impl Options {
         match self.priority {
             Some(priority) => priority,
             None => match parent_process {
-                Some(process) => process.priority,
+                Some(process) => *process.priority.read(),
                 None => Default::default(),
             },
         }
     }
 
     /// `heap` size in words.
-    fn heap_size(&self) -> usize {
-        match self.min_heap_size {
-            Some(min_heap_size) => next_heap_size(min_heap_size),
+    fn heap_size(&self, defaults: &Defaults) -> usize {
+        match self.min_heap_size.or(defaults.min_heap_size) {
+            Some(min_heap_size) => next_min_heap_size(min_heap_size),
             None => default_heap_size(),
         }
     }
@@ -159,7 +200,12 @@ impl Options {
 
                     Ok(self)
                 }
-                "max_heap_size" => unimplemented!(),
+                "max_heap_size" => {
+                    let max_heap_size = tuple[1].try_into().context("max_heap_size")?;
+                    self.max_heap_size = Some(max_heap_size);
+
+                    Ok(self)
+                }
                 "message_queue_data" => {
                     let message_queue_data = tuple[1].try_into().context("message_queue_data")?;
                     self.message_queue_data = message_queue_data;
@@ -191,12 +237,34 @@ impl Options {
         }
     }
 
-    fn sized_heap(&self) -> Result<(*mut Term, usize), Alloc> {
-        let heap_size = self.heap_size();
-        let heap = heap(self.heap_size())?;
+    fn sized_heap(&self, defaults: &Defaults) -> Result<(*mut Term, usize), Alloc> {
+        let heap_size = self.heap_size(defaults);
+        let heap = heap(heap_size)?;
 
         Ok((heap, heap_size))
     }
+
+    /// A process that can't grow its heap to its minimum size would be killed on its first
+    /// collection, so, like BEAM, `spawn_opt` doesn't allow it
+    fn validate_heap_sizes(&self) -> Result<(), anyhow::Error> {
+        if let Some(MaxHeapSize {
+            size: Some(max_heap_size),
+            ..
+        }) = self.max_heap_size
+        {
+            let min_heap_size = self.heap_size(&defaults());
+
+            if 0 < max_heap_size && max_heap_size < min_heap_size {
+                return Err(anyhow!(
+                    "max_heap_size size ({}) is less than min_heap_size ({})",
+                    max_heap_size,
+                    min_heap_size
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Options {
@@ -216,7 +284,7 @@ impl Default for Options {
 
 const SUPPORTED_OPTIONS_CONTEXT: &str = "supported options are :link, :monitor, \
      {:fullsweep_after, generational_collections :: pos_integer()}, \
-     {:max_heap_size, words :: pos_integer()}, \
+     {:max_heap_size, words :: non_neg_integer() | %{size: words, kill: boolean(), error_logger: boolean()}}, \
      {:message_queue_data, :off_heap | :on_heap}, \
      {:min_bin_vheap_size, words :: pos_integer()}, \
      {:min_heap_size, words :: pos_integer()}, and \
@@ -231,7 +299,11 @@ impl TryFrom<Term> for Options {
 
         loop {
             match options_term.decode().unwrap() {
-                TypedTerm::Nil => return Ok(options),
+                TypedTerm::Nil => {
+                    options.validate_heap_sizes()?;
+
+                    return Ok(options);
+                }
                 TypedTerm::List(cons) => {
                     options
                         .put_option_term(cons.head)
@@ -245,3 +317,9 @@ impl TryFrom<Term> for Options {
         }
     }
 }
+
+// Private
+
+lazy_static! {
+    static ref DEFAULTS: RwLock<Defaults> = Default::default();
+}
@@ -0,0 +1,83 @@
+use std::convert::TryFrom;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::{Process, ProcessFlags};
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::context::{term_try_into_bool, term_try_into_non_negative_integer};
+
+/// `max_heap_size` is either only the `size` or a map with `size`, `kill` and `error_logger`.
+/// Fields that are not set keep the process's defaults: no maximum, killing the process, and
+/// logging the error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MaxHeapSize {
+    /// The maximum size (in words) of the heap.  `0` means there is no limit.
+    pub size: Option<usize>,
+    pub kill: Option<bool>,
+    pub error_logger: Option<bool>,
+}
+
+impl MaxHeapSize {
+    pub fn apply(&self, process: &Process) {
+        if let Some(size) = self.size {
+            process.set_max_heap_size(size);
+        }
+
+        if let Some(kill) = self.kill {
+            set_flag(process, ProcessFlags::MaxHeapSizeKill, kill);
+        }
+
+        if let Some(error_logger) = self.error_logger {
+            set_flag(process, ProcessFlags::MaxHeapSizeErrorLogger, error_logger);
+        }
+    }
+}
+
+impl TryFrom<Term> for MaxHeapSize {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match term.decode().unwrap() {
+            TypedTerm::Map(map) => {
+                let size = match map.get(atom!("size")) {
+                    Some(size) => Some(term_try_into_non_negative_integer(
+                        "max_heap_size size",
+                        size,
+                    )?),
+                    None => None,
+                };
+                let kill = match map.get(atom!("kill")) {
+                    Some(kill) => Some(term_try_into_bool("max_heap_size kill", kill)?),
+                    None => None,
+                };
+                let error_logger = match map.get(atom!("error_logger")) {
+                    Some(error_logger) => Some(term_try_into_bool(
+                        "max_heap_size error_logger",
+                        error_logger,
+                    )?),
+                    None => None,
+                };
+
+                Ok(Self {
+                    size,
+                    kill,
+                    error_logger,
+                })
+            }
+            _ => Ok(Self {
+                size: Some(term_try_into_non_negative_integer("max_heap_size", term)?),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+// Private
+
+fn set_flag(process: &Process, flag: ProcessFlags, value: bool) {
+    if value {
+        process.set_flags(flag);
+    } else {
+        process.clear_flags(flag);
+    }
+}
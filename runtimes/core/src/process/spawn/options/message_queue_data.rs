@@ -4,18 +4,41 @@ use anyhow::Context;
 
 use liblumen_alloc::erts::term::prelude::*;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MessageQueueData {
     OnHeap,
     OffHeap,
 }
 
+impl MessageQueueData {
+    pub fn from_off_heap(off_heap: bool) -> Self {
+        if off_heap {
+            MessageQueueData::OffHeap
+        } else {
+            MessageQueueData::OnHeap
+        }
+    }
+
+    pub fn is_off_heap(&self) -> bool {
+        *self == MessageQueueData::OffHeap
+    }
+}
+
 impl Default for MessageQueueData {
     fn default() -> Self {
         MessageQueueData::OnHeap
     }
 }
 
+impl From<MessageQueueData> for Atom {
+    fn from(message_queue_data: MessageQueueData) -> Self {
+        match message_queue_data {
+            MessageQueueData::OnHeap => Atom::from_str("on_heap"),
+            MessageQueueData::OffHeap => Atom::from_str("off_heap"),
+        }
+    }
+}
+
 impl TryFrom<Term> for MessageQueueData {
     type Error = anyhow::Error;
 
@@ -0,0 +1,421 @@
+//! A sampling profiler for Erlang processes.
+//!
+//! While profiling, schedulers report which process they are running, and a sampler thread
+//! records the frames of each running process every interval.  Compiled code pushes no frames, so
+//! a process running compiled code is instead marked as due for a sample, which is taken from the
+//! native stack of the process when it next yields, while its functions are still on that stack.
+//! When profiling stops, two files are written:
+//!
+//! * the samples as folded stacks, one `<pid>;m:f/a;m:f/a count` line per distinct stack, which
+//!   `flamegraph.pl` or `inferno-flamegraph` turn into a flame graph.
+//! * a report, with `.report` as the extension, of the samples, reductions and garbage
+//!   collections of each profiled process.
+use std::backtrace::Backtrace;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use thiserror::Error;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::scheduler::ID;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::ModuleFunctionArity;
+
+/// Samples every millisecond, like `perf record -F 1000`
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1);
+
+pub struct Options {
+    /// Where the folded stacks are written.  The report is written next to it.
+    pub path: PathBuf,
+    pub interval: Duration,
+    /// Only these processes are profiled.  When `None`, all processes are.
+    pub rootset: Option<HashSet<Pid>>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("lumen-profile.folded"),
+            interval: DEFAULT_INTERVAL,
+            rootset: None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProfilerError {
+    #[error("already profiling")]
+    AlreadyProfiling,
+    #[error("not profiling")]
+    NotProfiling,
+    #[error("could not start sampler thread")]
+    Sampler(#[source] io::Error),
+    #[error("could not write {}", .path.display())]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+pub fn is_profiling() -> bool {
+    PROFILING.load(Ordering::Relaxed)
+}
+
+/// Starts sampling the processes run by all schedulers
+pub fn start(options: Options) -> Result<(), ProfilerError> {
+    let mut profiler = PROFILER.lock();
+
+    if profiler.is_some() {
+        return Err(ProfilerError::AlreadyProfiling);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let sampler_stop = stop.clone();
+    let interval = options.interval;
+    let sampler = thread::Builder::new()
+        .name("profiler".to_owned())
+        .spawn(move || {
+            while !sampler_stop.load(Ordering::SeqCst) {
+                thread::sleep(interval);
+                sample();
+            }
+        })
+        .map_err(ProfilerError::Sampler)?;
+
+    *PROFILE.lock() = Profile {
+        rootset: options.rootset,
+        ..Default::default()
+    };
+    *profiler = Some(Profiler {
+        path: options.path,
+        stop,
+        sampler,
+    });
+    PROFILING.store(true, Ordering::SeqCst);
+
+    Ok(())
+}
+
+/// Stops sampling and writes the folded stacks and report.  Returns the path of the folded
+/// stacks.
+pub fn stop() -> Result<PathBuf, ProfilerError> {
+    let profiler = PROFILER.lock().take().ok_or(ProfilerError::NotProfiling)?;
+
+    PROFILING.store(false, Ordering::SeqCst);
+    profiler.stop.store(true, Ordering::SeqCst);
+    let _ = profiler.sampler.join();
+    RUNNING.lock().clear();
+
+    let profile = std::mem::take(&mut *PROFILE.lock());
+    let report_path = profiler.path.with_extension("report");
+
+    write(&profiler.path, profile.folded_stacks())?;
+    write(&report_path, profile.report())?;
+
+    Ok(profiler.path)
+}
+
+/// Called by the scheduler `id` before it runs `arc_process`
+pub fn running(id: ID, arc_process: &Arc<Process>) {
+    if !is_profiling() {
+        return;
+    }
+
+    let mut profile = PROFILE.lock();
+
+    if profile.is_profiled(arc_process.pid()) {
+        profile
+            .stats_by_pid
+            .entry(arc_process.pid())
+            .or_insert_with(|| ProcessStats::new(arc_process));
+
+        RUNNING.lock().insert(id, arc_process.clone());
+    }
+}
+
+/// Called by the scheduler `id` after `arc_process` has run.  When `arc_process` runs compiled
+/// code, this must be called on its stack, before switching to another process.
+pub fn stopped_running(id: ID, arc_process: &Process) {
+    if !is_profiling() {
+        return;
+    }
+
+    if RUNNING.lock().remove(&id).is_some() {
+        let pid = arc_process.pid();
+        let due_folded_stack = if DUE.lock().remove(&pid) {
+            Some(native_folded_stack(arc_process))
+        } else {
+            None
+        };
+        let mut profile = PROFILE.lock();
+
+        if let Some(folded_stack) = due_folded_stack {
+            profile.record(pid, folded_stack);
+        }
+
+        if let Some(stats) = profile.stats_by_pid.get_mut(&pid) {
+            stats.update(arc_process);
+        }
+    }
+}
+
+// Private
+
+struct Profiler {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    sampler: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct Profile {
+    rootset: Option<HashSet<Pid>>,
+    count_by_folded_stack: HashMap<String, u64>,
+    stats_by_pid: HashMap<Pid, ProcessStats>,
+}
+
+impl Profile {
+    fn is_profiled(&self, pid: Pid) -> bool {
+        match &self.rootset {
+            Some(rootset) => rootset.contains(&pid),
+            None => true,
+        }
+    }
+
+    fn record(&mut self, pid: Pid, folded_stack: String) {
+        *self.count_by_folded_stack.entry(folded_stack).or_insert(0) += 1;
+
+        if let Some(stats) = self.stats_by_pid.get_mut(&pid) {
+            stats.samples += 1;
+        }
+    }
+
+    fn folded_stacks(&self) -> String {
+        let mut folded_stacks: Vec<_> = self.count_by_folded_stack.iter().collect();
+        folded_stacks.sort();
+
+        let mut output = String::new();
+
+        for (folded_stack, count) in folded_stacks {
+            writeln!(output, "{} {}", folded_stack, count).unwrap();
+        }
+
+        output
+    }
+
+    fn report(&self) -> String {
+        let mut stats_vec: Vec<_> = self.stats_by_pid.iter().collect();
+        // Busiest first
+        stats_vec.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.reductions()));
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            "{:<20} {:<40} {:>10} {:>12} {:>6} {:>12} {:>12}",
+            "PID", "INITIAL CALL", "SAMPLES", "REDUCTIONS", "GCS", "HEAP", "TOTAL HEAP"
+        )
+        .unwrap();
+
+        for (pid, stats) in stats_vec {
+            writeln!(
+                output,
+                "{:<20} {:<40} {:>10} {:>12} {:>6} {:>12} {:>12}",
+                pid.to_string(),
+                folded_frame(&stats.initial_module_function_arity),
+                stats.samples,
+                stats.reductions(),
+                stats.garbage_collections(),
+                stats.heap_size,
+                stats.total_heap_size
+            )
+            .unwrap();
+        }
+
+        output
+    }
+}
+
+struct ProcessStats {
+    initial_module_function_arity: ModuleFunctionArity,
+    samples: u64,
+    /// Reductions of the process when it was first run while profiling
+    start_reductions: u64,
+    end_reductions: u64,
+    /// Garbage collections of the process when it was first run while profiling
+    start_garbage_collections: usize,
+    end_garbage_collections: usize,
+    /// Size in words
+    heap_size: usize,
+    /// Size in words
+    total_heap_size: usize,
+}
+
+impl ProcessStats {
+    fn new(process: &Process) -> Self {
+        let reductions = process.reductions();
+        let garbage_collections = process.garbage_collections();
+
+        Self {
+            initial_module_function_arity: process.initial_module_function_arity,
+            samples: 0,
+            start_reductions: reductions,
+            end_reductions: reductions,
+            start_garbage_collections: garbage_collections,
+            end_garbage_collections: garbage_collections,
+            heap_size: process.heap_size(),
+            total_heap_size: process.total_heap_size(),
+        }
+    }
+
+    fn reductions(&self) -> u64 {
+        self.end_reductions - self.start_reductions
+    }
+
+    fn garbage_collections(&self) -> usize {
+        self.end_garbage_collections - self.start_garbage_collections
+    }
+
+    fn update(&mut self, process: &Process) {
+        self.end_reductions = process.reductions();
+        self.end_garbage_collections = process.garbage_collections();
+        self.heap_size = process.heap_size();
+        self.total_heap_size = process.total_heap_size();
+    }
+}
+
+lazy_static! {
+    static ref PROFILER: Mutex<Option<Profiler>> = Mutex::new(None);
+    static ref PROFILE: Mutex<Profile> = Default::default();
+    /// The process each scheduler is running
+    static ref RUNNING: Mutex<HashMap<ID, Arc<Process>>> = Default::default();
+    /// Processes running compiled code that are sampled when they stop running
+    static ref DUE: Mutex<HashSet<Pid>> = Default::default();
+}
+
+/// Checked by the schedulers before taking any lock, so that they are not slowed down when not
+/// profiling
+static PROFILING: AtomicBool = AtomicBool::new(false);
+
+fn sample() {
+    // Cloned, so that the schedulers are not blocked while the frames are read
+    let arc_process_vec: Vec<Arc<Process>> = RUNNING.lock().values().cloned().collect();
+    let mut sample_vec: Vec<(Pid, String)> = Vec::with_capacity(arc_process_vec.len());
+
+    for arc_process in &arc_process_vec {
+        match folded_stack(arc_process) {
+            Some(folded_stack) => sample_vec.push((arc_process.pid(), folded_stack)),
+            None => {
+                DUE.lock().insert(arc_process.pid());
+            }
+        }
+    }
+
+    let mut profile = PROFILE.lock();
+
+    for (pid, folded_stack) in sample_vec {
+        profile.record(pid, folded_stack);
+    }
+}
+
+/// The frames of `process` from the oldest to the current, as `flamegraph.pl` expects, under the
+/// pid, so that each process is its own tower in the flame graph.  `None` if `process` is running
+/// compiled code, which does not push frames.
+fn folded_stack(process: &Process) -> Option<String> {
+    let stacktrace = process.stacktrace();
+
+    if stacktrace.len() == 0 {
+        None
+    } else {
+        let mut folded_stack = process.pid().to_string();
+
+        for module_function_arity in stacktrace.iter().rev() {
+            folded_stack.push(';');
+            folded_stack.push_str(&folded_frame(module_function_arity));
+        }
+
+        Some(folded_stack)
+    }
+}
+
+/// The compiled functions on the native stack of the current thread, which is running `process`.
+/// Compiled functions are named `module:function/arity`, so they are told apart from the runtime's
+/// own functions by their symbol names.  If none are found, only the initial call is known.
+fn native_folded_stack(process: &Process) -> String {
+    let backtrace = Backtrace::force_capture().to_string();
+    // Frames are printed from the current to the oldest
+    let mut folded_frames: Vec<&str> = backtrace
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim_start().splitn(2, ": ");
+            let index = parts.next()?;
+            let symbol_name = parts.next()?;
+
+            if !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()) {
+                Some(symbol_name)
+            } else {
+                None
+            }
+        })
+        .filter(|symbol_name| is_folded_frame(symbol_name))
+        .collect();
+    folded_frames.reverse();
+
+    let mut folded_stack = process.pid().to_string();
+
+    if folded_frames.is_empty() {
+        folded_stack.push(';');
+        folded_stack.push_str(&folded_frame(&process.initial_module_function_arity));
+    } else {
+        for folded_frame in folded_frames {
+            folded_stack.push(';');
+            folded_stack.push_str(folded_frame);
+        }
+    }
+
+    folded_stack
+}
+
+/// Whether `symbol_name` is `module:function/arity`, unlike the `path::to::function` of Rust
+/// symbols
+fn is_folded_frame(symbol_name: &str) -> bool {
+    match (symbol_name.find(':'), symbol_name.rfind('/')) {
+        (Some(colon_index), Some(slash_index)) if colon_index < slash_index => {
+            let module = &symbol_name[..colon_index];
+            let function = &symbol_name[colon_index + 1..slash_index];
+            let arity = &symbol_name[slash_index + 1..];
+
+            !module.is_empty()
+                && !function.is_empty()
+                && !function.starts_with(':')
+                && arity.parse::<u8>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn folded_frame(module_function_arity: &ModuleFunctionArity) -> String {
+    format!(
+        "{}:{}/{}",
+        module_function_arity.module.name(),
+        module_function_arity.function.name(),
+        module_function_arity.arity
+    )
+}
+
+fn write(path: &PathBuf, contents: String) -> Result<(), ProfilerError> {
+    fs::write(path, contents).map_err(|source| ProfilerError::Write {
+        path: path.clone(),
+        source,
+    })
+}
@@ -1,4 +1,4 @@
-/// Maps registered names (`Atom`) to `LocalPid` or `Port`
+/// Maps registered names (`Atom`) to `LocalPid`, `Port` or a callback of the embedding program
 use std::sync::{Arc, Weak};
 
 use dashmap::DashMap;
@@ -9,6 +9,8 @@ use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::exception;
 use liblumen_alloc::Process;
 
+use crate::host::Host;
+
 lazy_static! {
     static ref REGISTERED_BY_NAME: DashMap<Atom, Registered> = Default::default();
     // Strong references are owned by the scheduler run queues
@@ -20,6 +22,16 @@ pub fn atom_to_process(name: &Atom) -> Option<Arc<Process>> {
         .get(name)
         .and_then(|registered| match registered.value() {
             Registered::Process(weak_process) => weak_process.upgrade(),
+            Registered::Host(_) => None,
+        })
+}
+
+pub fn atom_to_host(name: &Atom) -> Option<Host> {
+    REGISTERED_BY_NAME
+        .get(name)
+        .and_then(|registered| match registered.value() {
+            Registered::Host(host) => Some(*host),
+            Registered::Process(_) => None,
         })
 }
 
@@ -43,6 +55,14 @@ pub fn pid_to_process(pid: &Pid) -> Option<Arc<Process>> {
         .and_then(|weak_process| weak_process.clone().upgrade())
 }
 
+/// The processes that are still alive
+pub fn processes() -> Vec<Arc<Process>> {
+    WEAK_PROCESS_CONTROL_BLOCK_BY_PID
+        .iter()
+        .filter_map(|entry| entry.value().upgrade())
+        .collect()
+}
+
 pub fn pid_to_self_or_process(pid: Pid, process_arc: &Arc<Process>) -> Option<Arc<Process>> {
     if process_arc.pid() == pid {
         Some(process_arc.clone())
@@ -71,6 +91,17 @@ pub fn register_in(arc_process: Arc<Process>, name: Atom) -> bool {
     }
 }
 
+/// Registers `host` under `name`, unless `name` is already registered
+pub fn register_host(name: Atom, host: Host) -> bool {
+    if !REGISTERED_BY_NAME.contains_key(&name) {
+        REGISTERED_BY_NAME.insert(name, Registered::Host(host));
+
+        true
+    } else {
+        false
+    }
+}
+
 pub fn put_pid_to_process(arc_process: &Arc<Process>) {
     if let Some(_) =
         WEAK_PROCESS_CONTROL_BLOCK_BY_PID.insert(arc_process.pid(), Arc::downgrade(&arc_process))
@@ -90,6 +121,7 @@ pub fn unregister(name: &Atom) -> bool {
             }
             None => false,
         },
+        Some((_, Registered::Host(_))) => true,
         None => false,
     }
 }
@@ -97,6 +129,7 @@ pub fn unregister(name: &Atom) -> bool {
 #[cfg_attr(test, derive(Debug))]
 pub enum Registered {
     Process(Weak<Process>),
+    Host(Host),
 }
 
 impl PartialEq for Registered {
@@ -105,6 +138,8 @@ impl PartialEq for Registered {
             (Registered::Process(self_weak_process), Registered::Process(other_weak_process)) => {
                 Weak::ptr_eq(&self_weak_process, &other_weak_process)
             }
+            (Registered::Host(self_host), Registered::Host(other_host)) => self_host == other_host,
+            _ => false,
         }
     }
 }
@@ -2,7 +2,7 @@ pub mod run_queue;
 
 use std::any::Any;
 use std::fmt::Debug;
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
 
 use hashbrown::HashMap;
@@ -40,6 +40,28 @@ fn current_from_id(id: &ID) -> Option<Arc<dyn Scheduler>> {
     })
 }
 
+/// All registered schedulers, ordered by ID
+pub fn all() -> Vec<Arc<dyn Scheduler>> {
+    let mut schedulers: Vec<Arc<dyn Scheduler>> = SCHEDULER_BY_ID
+        .lock()
+        .values()
+        .filter_map(|weak_scheduler| weak_scheduler.upgrade())
+        .collect();
+    schedulers.sort_by_key(|scheduler| scheduler.id());
+
+    schedulers
+}
+
+/// Counts a scheduler switching to a process to run it
+pub fn count_context_switch() {
+    CONTEXT_SWITCHES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The number of times, across all schedulers, a process has been switched to
+pub fn context_switches() -> u64 {
+    CONTEXT_SWITCHES.load(Ordering::Relaxed)
+}
+
 pub fn from_id(id: &ID) -> Option<Arc<dyn Scheduler>> {
     current_from_id(id).or_else(|| {
         SCHEDULER_BY_ID
@@ -68,6 +90,29 @@ fn registered() -> Arc<dyn Scheduler> {
     arc_scheduler
 }
 
+/// Removes a runnable process from another scheduler's run queues, so that `thief` can run it.
+/// Returns `None` if no other scheduler has a process it can give up.
+pub fn steal_runnable(thief: &ID) -> Option<Arc<Process>> {
+    // Upgrade outside the `SCHEDULER_BY_ID` lock, so it isn't held while taking the victims'
+    // run queue locks.
+    let victims: Vec<Arc<dyn Scheduler>> = SCHEDULER_BY_ID
+        .lock()
+        .iter()
+        .filter(|(id, _)| *id != thief)
+        .filter_map(|(_, weak_scheduler)| weak_scheduler.upgrade())
+        .collect();
+
+    victims.iter().find_map(|victim| victim.steal_runnable())
+}
+
+/// Marks `process` as runnable if it is waiting and moves it to its scheduler's run queue, such as
+/// after a message is sent to it from outside its scheduler
+pub fn stop_waiting(process: &Process) {
+    if process.stop_waiting() {
+        wake(process);
+    }
+}
+
 pub fn unregister(id: &ID) {
     let mut locked_scheduler_by_id = SCHEDULER_BY_ID.lock();
 
@@ -76,6 +121,14 @@ pub fn unregister(id: &ID) {
         .expect("Scheduler not registered");
 }
 
+/// Has the scheduler of `process` move it out of its waiting queue, whether it is now runnable or
+/// exiting
+pub fn wake(process: &Process) {
+    if let Some(arc_scheduler) = process.scheduler_id().and_then(|id| from_id(&id)) {
+        arc_scheduler.stop_waiting(process);
+    }
+}
+
 /// Returns `true` if `arc_process` was run; otherwise, `false`.
 #[must_use]
 pub fn run_through(process: &Process) -> bool {
@@ -156,6 +209,9 @@ pub trait Scheduler: Debug + Send + Sync {
     /// scheduler should sleep or work steal.
     #[must_use]
     fn run_once(&self) -> bool;
+    /// Changes the priority of `process`, moving it to the run queue for `priority` if it is
+    /// queued.  Returns the old priority.
+    fn change_priority(&self, process: &Process, priority: Priority) -> Priority;
     fn run_queue_len(&self, priority: Priority) -> usize;
     /// Returns the length of the current scheduler's run queue
     fn run_queues_len(&self) -> usize;
@@ -165,7 +221,14 @@ pub trait Scheduler: Debug + Send + Sync {
     /// (primary) scheduler creation.
     fn spawn_init(&self, minimum_heap_size: usize) -> Result<Arc<Process>, SystemException>;
     fn shutdown(&self) -> anyhow::Result<()>;
+    /// Removes a runnable process from the run queues, so that an idle scheduler can steal it.
+    /// Running and waiting processes are never given up.
+    fn steal_runnable(&self) -> Option<Arc<Process>> {
+        None
+    }
     fn stop_waiting(&self, process: &Process);
+    /// Wakes the scheduler's thread if it is parked because it had nothing to run
+    fn unpark(&self) {}
 }
 
 pub trait SchedulerDependentAlloc {
@@ -205,6 +268,8 @@ thread_local! {
   static SCHEDULER: Arc<dyn Scheduler> = registered();
 }
 
+static CONTEXT_SWITCHES: AtomicU64 = AtomicU64::new(0);
+
 lazy_static! {
     static ref RW_LOCK_OPTION_UNREGISTERED: RwLock<Option<Box<dyn Fn() -> Arc<dyn Scheduler> + 'static + Sync + Send>>> =
         RwLock::new(None);
@@ -17,6 +17,21 @@ pub struct Queues {
     max: Immediate,
 }
 impl Queues {
+    /// Moves `process` from the run queue for `old_priority` to the run queue for its current
+    /// priority.  Does nothing if `process` is not in a run queue, such as when it is running or
+    /// waiting.
+    pub fn change_priority(&mut self, process: &Process, old_priority: Priority) {
+        let removed = match old_priority {
+            Priority::Low | Priority::Normal => self.normal_low.remove(process),
+            Priority::High => self.high.remove(process),
+            Priority::Max => self.max.remove(process),
+        };
+
+        if let Some(arc_process) = removed {
+            self.enqueue(arc_process);
+        }
+    }
+
     pub fn contains(&self, value: &Arc<Process>) -> bool {
         self.waiting.contains(value)
             || self.normal_low.contains(value)
@@ -45,7 +60,9 @@ impl Queues {
     }
 
     pub fn enqueue(&mut self, arc_process: Arc<Process>) {
-        match arc_process.priority {
+        let priority = *arc_process.priority.read();
+
+        match priority {
             Priority::Low | Priority::Normal => self.normal_low.enqueue(arc_process),
             Priority::High => self.high.enqueue(arc_process),
             Priority::Max => self.max.enqueue(arc_process),
@@ -75,6 +92,15 @@ impl Queues {
         }
     }
 
+    /// Removes the runnable process that would be run last, in priority order, for another
+    /// scheduler to run.  Waiting processes are not stolen.
+    pub fn steal(&mut self) -> Option<Arc<Process>> {
+        self.max
+            .steal()
+            .or_else(|| self.high.steal())
+            .or_else(|| self.normal_low.steal())
+    }
+
     pub fn stop_waiting(&mut self, process: &Process) {
         match self.waiting.get(process) {
             Some(arc_process) => {
@@ -180,6 +206,20 @@ impl Immediate {
     pub fn enqueue(&mut self, process: Arc<Process>) {
         self.0.push_back(process);
     }
+
+    pub fn remove(&mut self, process: &Process) -> Option<Arc<Process>> {
+        let index = self
+            .0
+            .iter()
+            .position(|arc_process| arc_process.pid() == process.pid())?;
+
+        self.0.remove(index)
+    }
+
+    /// Steals from the back, so the thief doesn't contend for the front with the owner
+    pub fn steal(&mut self) -> Option<Arc<Process>> {
+        self.0.pop_back()
+    }
 }
 
 /// A run queue where the `Arc<Process` is run only when its delay is `0`.  This allows
@@ -219,6 +259,31 @@ impl Delayed {
         let delayed_process = DelayedProcess::new(arc_process);
         self.0.push_back(delayed_process);
     }
+
+    /// Removes `process` without regard for its remaining delay
+    pub fn remove(&mut self, process: &Process) -> Option<Arc<Process>> {
+        let index = self
+            .0
+            .iter()
+            .position(|delayed_process| delayed_process.arc_process.pid() == process.pid())?;
+
+        self.0
+            .remove(index)
+            .map(|delayed_process| delayed_process.arc_process)
+    }
+
+    /// Steals the process nearest the back whose delay has run out, so that a `Priority::Low`
+    /// process is not run more often by being stolen than by being dequeued.
+    pub fn steal(&mut self) -> Option<Arc<Process>> {
+        let index = self
+            .0
+            .iter()
+            .rposition(|delayed_process| delayed_process.delay == 0)?;
+
+        self.0
+            .remove(index)
+            .map(|delayed_process| delayed_process.arc_process)
+    }
 }
 
 type Delay = u8;
@@ -232,7 +297,7 @@ struct DelayedProcess {
 impl DelayedProcess {
     fn new(arc_process: Arc<Process>) -> DelayedProcess {
         DelayedProcess {
-            delay: Self::priority_to_delay(arc_process.priority),
+            delay: Self::priority_to_delay(*arc_process.priority.read()),
             arc_process,
         }
     }
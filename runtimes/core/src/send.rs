@@ -1,16 +1,19 @@
 mod options;
 
 use std::convert::TryInto;
+use std::ptr::NonNull;
 
 use anyhow::*;
 
 use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::HeapFragment;
 use liblumen_alloc::term::prelude::*;
 use liblumen_alloc::Process;
 
-use crate::distribution::nodes::node;
+use crate::distribution::{self, nodes::node};
 use crate::registry::{self, pid_to_process};
 use crate::scheduler;
+use crate::trace;
 
 pub use options::*;
 
@@ -20,6 +23,8 @@ pub fn send(
     options: Options,
     process: &Process,
 ) -> InternalResult<Sent> {
+    trace::send(process, message, destination);
+
     match destination.decode()? {
         TypedTerm::Atom(destination_atom) => {
             send_to_name(destination_atom, message, options, process)
@@ -45,7 +50,13 @@ pub fn send(
                         } else if !options.suspend {
                             Ok(Sent::SuspendRequired)
                         } else {
-                            unimplemented!("distribution")
+                            // Like a message to a dead local process, a message to an unreachable
+                            // node is dropped
+                            if let Ok(connection) = distribution::connect(node_atom) {
+                                connection.reg_send(process.pid(), name_atom, message);
+                            }
+
+                            Ok(Sent::Sent)
                         }
                     }
                 }
@@ -55,12 +66,15 @@ pub fn send(
         }
         TypedTerm::Pid(destination_pid) => {
             if destination_pid == process.pid() {
+                trace::receive(process, process, message);
                 process.send_from_self(message);
 
                 Ok(Sent::Sent)
             } else {
                 match pid_to_process(&destination_pid) {
                     Some(destination_arc_process) => {
+                        trace::receive(process, &destination_arc_process, message);
+
                         if destination_arc_process.send_from_other(message)? {
                             let scheduler_id = destination_arc_process.scheduler_id().unwrap();
                             let arc_scheduler = scheduler::from_id(&scheduler_id).unwrap();
@@ -73,6 +87,15 @@ pub fn send(
                 }
             }
         }
+        TypedTerm::ExternalPid(destination_external_pid) => {
+            if let Ok(connection) =
+                distribution::connect(destination_external_pid.arc_node().name())
+            {
+                connection.send(&destination_external_pid, message);
+            }
+
+            Ok(Sent::Sent)
+        }
         _ => Err(TypeError)
             .context(format!(
                 "destination ({}) is not registered_name (atom), {{registered_name, node}}, or pid",
@@ -82,6 +105,13 @@ pub fn send(
     }
 }
 
+/// Sends `message`, which is in `heap_fragment` instead of on any process's heap, to `process`
+/// from outside its scheduler, such as from a distribution connection or an embedder.
+pub fn send_heap_message(process: &Process, heap_fragment: NonNull<HeapFragment>, message: Term) {
+    process.send_heap_message(heap_fragment, message);
+    scheduler::stop_waiting(process);
+}
+
 pub enum Sent {
     Sent,
     SuspendRequired,
@@ -98,12 +128,15 @@ fn send_to_name(
     process: &Process,
 ) -> InternalResult<Sent> {
     if *process.registered_name.read() == Some(destination) {
+        trace::receive(process, process, message);
         process.send_from_self(message);
 
         Ok(Sent::Sent)
     } else {
         match registry::atom_to_process(&destination) {
             Some(destination_arc_process) => {
+                trace::receive(process, &destination_arc_process, message);
+
                 if destination_arc_process.send_from_other(message)? {
                     let scheduler_id = destination_arc_process.scheduler_id().unwrap();
                     let arc_scheduler = scheduler::from_id(&scheduler_id).unwrap();
@@ -112,7 +145,14 @@ fn send_to_name(
 
                 Ok(Sent::Sent)
             }
-            None => Err(anyhow!("name ({}) not registered", destination).into()),
+            None => match registry::atom_to_host(&destination) {
+                Some(host) => {
+                    host.send(message);
+
+                    Ok(Sent::Sent)
+                }
+                None => Err(anyhow!("name ({}) not registered", destination).into()),
+            },
         }
     }
 }
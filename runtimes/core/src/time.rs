@@ -1,3 +1,4 @@
+pub mod cpu;
 pub mod datetime;
 pub mod monotonic;
 pub mod system;
@@ -0,0 +1,25 @@
+use crate::time::Milliseconds;
+
+/// CPU time, in both user and system mode, used by all the threads of the runtime
+#[cfg(unix)]
+pub fn time_in_milliseconds() -> Milliseconds {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+
+    if result == 0 {
+        timeval_to_milliseconds(usage.ru_utime) + timeval_to_milliseconds(usage.ru_stime)
+    } else {
+        0
+    }
+}
+
+/// Without a way to measure CPU time, the time the runtime has been running is the upper bound
+#[cfg(not(unix))]
+pub fn time_in_milliseconds() -> Milliseconds {
+    crate::time::monotonic::time_in_milliseconds()
+}
+
+#[cfg(unix)]
+fn timeval_to_milliseconds(timeval: libc::timeval) -> Milliseconds {
+    (timeval.tv_sec as Milliseconds) * 1_000 + (timeval.tv_usec as Milliseconds) / 1_000
+}
@@ -17,6 +17,7 @@ use liblumen_alloc::erts::Process;
 
 use crate::registry;
 use crate::scheduler::{self, Scheduled, Scheduler};
+use crate::send;
 use crate::time::{monotonic, Milliseconds};
 
 pub fn cancel(timer_reference: &Reference) -> Option<Milliseconds> {
@@ -55,6 +56,23 @@ pub fn start(
     result
 }
 
+/// Starts a timer that wakes `arc_process` at `monotonic_time_milliseconds`, so that a receive
+/// waiting for a message sees that it timed out.  Unlike `start`, no message is sent.
+pub fn start_receive_timeout(
+    monotonic_time_milliseconds: Milliseconds,
+    arc_process: &Arc<Process>,
+) -> Reference {
+    let arc_scheduler = scheduler::current();
+
+    let result = arc_scheduler.hierarchy().write().start_receive_timeout(
+        monotonic_time_milliseconds,
+        Destination::Process(Arc::downgrade(arc_process)),
+        arc_scheduler.clone(),
+    );
+
+    result
+}
+
 /// Times out the timers for the thread that have timed out since the last time `timeout` was
 /// called.
 pub fn timeout() {
@@ -114,6 +132,19 @@ impl Hierarchy {
             })
     }
 
+    /// The milliseconds until the next timer times out, or `None` if there are no timers, so that
+    /// an idle scheduler knows how long it can park.
+    pub fn milliseconds_until_next_timeout(&self) -> Option<Milliseconds> {
+        self.timer_by_reference_number
+            .values()
+            .filter_map(Weak::upgrade)
+            .map(|arc_timer| arc_timer.monotonic_time_milliseconds)
+            .min()
+            .map(|next_monotonic_time_milliseconds| {
+                next_monotonic_time_milliseconds.saturating_sub(monotonic::time_in_milliseconds())
+            })
+    }
+
     fn position(&self, monotonic_time_milliseconds: Milliseconds) -> Position {
         if monotonic_time_milliseconds < self.soon.slot_monotonic_time_milliseconds {
             Position::AtOnce
@@ -161,16 +192,51 @@ impl Hierarchy {
                 process_tuple.clone_to_fragment()?
             }
         };
-        let position = self.position(monotonic_time_milliseconds);
-
-        let timer = Timer {
+        self.insert(
             reference_number,
             monotonic_time_milliseconds,
             destination,
-            message_heap: Mutex::new(HeapFragment {
+            Some(HeapFragment {
                 heap_fragment,
                 term: heap_fragment_message,
             }),
+        );
+
+        Ok(process_reference)
+    }
+
+    pub fn start_receive_timeout(
+        &mut self,
+        monotonic_time_milliseconds: Milliseconds,
+        destination: Destination,
+        arc_scheduler: Arc<dyn Scheduler>,
+    ) -> Reference {
+        let reference_number = arc_scheduler.next_reference_number();
+
+        self.insert(
+            reference_number,
+            monotonic_time_milliseconds,
+            destination,
+            None,
+        );
+
+        Reference::new(arc_scheduler.id(), reference_number)
+    }
+
+    fn insert(
+        &mut self,
+        reference_number: ReferenceNumber,
+        monotonic_time_milliseconds: Milliseconds,
+        destination: Destination,
+        message_heap: Option<HeapFragment>,
+    ) {
+        let position = self.position(monotonic_time_milliseconds);
+
+        let timer = Timer {
+            reference_number,
+            monotonic_time_milliseconds,
+            destination,
+            message_heap: Mutex::new(message_heap),
             position: Mutex::new(position),
         };
 
@@ -187,8 +253,6 @@ impl Hierarchy {
 
         self.timer_by_reference_number
             .insert(reference_number, cancellable);
-
-        Ok(process_reference)
     }
 
     pub fn timeout(&mut self) {
@@ -376,7 +440,8 @@ struct Timer {
     reference_number: ReferenceNumber,
     monotonic_time_milliseconds: Milliseconds,
     destination: Destination,
-    message_heap: Mutex<HeapFragment>,
+    // `None` for receive timeouts, which only wake the destination
+    message_heap: Mutex<Option<HeapFragment>>,
     position: Mutex<Position>,
 }
 
@@ -401,12 +466,13 @@ impl Timer {
         };
 
         if let Some(destination_arc_process) = option_destination_arc_process {
-            let HeapFragment {
-                heap_fragment,
-                term,
-            } = self.message_heap.into_inner();
-
-            destination_arc_process.send_heap_message(heap_fragment, term);
+            match self.message_heap.into_inner() {
+                Some(HeapFragment {
+                    heap_fragment,
+                    term,
+                }) => send::send_heap_message(&destination_arc_process, heap_fragment, term),
+                None => scheduler::stop_waiting(&destination_arc_process),
+            }
         }
     }
 }
@@ -415,8 +481,10 @@ impl Debug for Timer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "  {} ms ", self.monotonic_time_milliseconds)?;
 
-        let HeapFragment { term, .. } = *self.message_heap.lock();
-        write!(f, "{} -> ", term)?;
+        match *self.message_heap.lock() {
+            Some(HeapFragment { term, .. }) => write!(f, "{} -> ", term)?,
+            None => write!(f, "receive timeout -> ")?,
+        }
 
         match &self.destination {
             Destination::Process(weak_process) => match weak_process.upgrade() {
@@ -0,0 +1,599 @@
+//! Tracing of processes with `erlang:trace/3` and `erlang:trace_pattern/3`.
+//!
+//! Each traced process has `TraceFlags` and a tracer, to which trace messages are sent.  The trace
+//! messages are built in a `HeapFragment` sized for the whole message, so they do not depend on
+//! free space on the heap of the traced process, and the fragment is then sent to the tracer.
+//!
+//! Calls are traced through the `CallTraceHooks` of `liblumen_alloc`, so only calls of functions
+//! run as `Frame`s are traced.  Compiled code calls other compiled functions directly, without
+//! pushing a `Frame`, and codegen emits no trace checks in function prologues, so calls made by
+//! compiled code are never traced and `erlang:trace_pattern/3` fails there.  `send`, `'receive'`,
+//! `procs` and the other process events are traced by both runtimes.
+pub mod match_spec;
+
+use std::ops::{BitOr, BitOrAssign};
+use std::ptr;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc::{Heap, TermAlloc};
+use liblumen_alloc::erts::process::trace::{set_call_trace_hooks, CallTrace, CallTraceHooks};
+use liblumen_alloc::erts::process::{Process, ProcessFlags};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::{self, HeapFragment, ModuleFunctionArity};
+use liblumen_alloc::{fixnum, CloneToProcess};
+
+use crate::registry::{self, pid_to_process};
+use crate::send::send_heap_message;
+
+use self::match_spec::MatchSpec;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TraceFlags(u32);
+
+impl TraceFlags {
+    #![allow(non_upper_case_globals)]
+
+    /// `{trace, Pid, send, Message, To}`
+    pub const Send: Self = Self(1 << 0);
+    /// `{trace, Pid, 'receive', Message}`
+    pub const Receive: Self = Self(1 << 1);
+    /// `{trace, Pid, spawn | exit | link | unlink, ...}`
+    pub const Procs: Self = Self(1 << 2);
+    /// `{trace, Pid, call, {Module, Function, Arguments}}` for calls matching `trace_pattern/3`
+    pub const Call: Self = Self(1 << 3);
+    /// `{trace, Pid, return_to, {Module, Function, Arity}}` after traced calls
+    pub const ReturnTo: Self = Self(1 << 4);
+    /// Processes spawned by the traced process inherit its flags and tracer
+    pub const SetOnSpawn: Self = Self(1 << 5);
+
+    /// The flags for `all` in the flag list of `erlang:trace/3`
+    pub const All: Self = Self(0b11_1111);
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "send" => Some(Self::Send),
+            "receive" => Some(Self::Receive),
+            "procs" => Some(Self::Procs),
+            "call" => Some(Self::Call),
+            "return_to" => Some(Self::ReturnTo),
+            "set_on_spawn" => Some(Self::SetOnSpawn),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn are_set(&self, flags: TraceFlags) -> bool {
+        (self.0 & flags.0) == flags.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The flags as the bits `process_info(Pid, trace)` returns in BEAM
+    pub fn otp_bits(&self) -> u32 {
+        const OTP_BITS: [(TraceFlags, u32); 6] = [
+            (TraceFlags::Send, 1 << 1),
+            (TraceFlags::Receive, 1 << 2),
+            (TraceFlags::SetOnSpawn, 1 << 3),
+            (TraceFlags::Call, 1 << 7),
+            (TraceFlags::Procs, 1 << 8),
+            (TraceFlags::ReturnTo, 1 << 13),
+        ];
+
+        OTP_BITS
+            .iter()
+            .filter(|(flags, _)| self.are_set(*flags))
+            .fold(0, |otp_bits, (_, otp_bit)| otp_bits | otp_bit)
+    }
+
+    fn without(self, flags: TraceFlags) -> Self {
+        Self(self.0 & !flags.0)
+    }
+}
+
+impl BitOr for TraceFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for TraceFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0
+    }
+}
+
+/// The first argument of `erlang:trace/3`
+pub enum Target {
+    Pid(Pid),
+    /// All processes that currently exist
+    Existing,
+    /// All processes that will be created
+    New,
+    /// Both `Existing` and `New`
+    All,
+}
+
+/// Where a function pattern of `erlang:trace_pattern/3` applies.  `None` matches any.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionPattern {
+    pub module: Option<Atom>,
+    pub function: Option<Atom>,
+    pub arity: Option<u8>,
+}
+
+impl FunctionPattern {
+    fn matches(&self, module_function_arity: &ModuleFunctionArity) -> bool {
+        self.module
+            .map_or(true, |module| module == module_function_arity.module)
+            && self
+                .function
+                .map_or(true, |function| function == module_function_arity.function)
+            && self
+                .arity
+                .map_or(true, |arity| arity == module_function_arity.arity)
+    }
+}
+
+/// Sets (`how` is `true`) or clears the `flags` of `target`.  Set flags are traced to `tracer`.
+/// Returns the number of processes whose flags were changed.
+pub fn trace(target: Target, how: bool, flags: TraceFlags, tracer: Pid) -> usize {
+    init();
+
+    match target {
+        Target::Pid(pid) => match pid_to_process(&pid) {
+            Some(arc_process) if !arc_process.is_exiting() => {
+                set(&arc_process, how, flags, tracer);
+
+                1
+            }
+            _ => 0,
+        },
+        Target::Existing => existing(how, flags, tracer),
+        Target::New => {
+            new(how, flags, tracer);
+
+            0
+        }
+        Target::All => {
+            new(how, flags, tracer);
+
+            existing(how, flags, tracer)
+        }
+    }
+}
+
+/// Sets the `match_spec` of calls to functions matching `pattern`, or stops tracing them when
+/// `match_spec` is `None`.  A `Some(None)` `match_spec` traces all calls.
+///
+/// Returns the number of functions matching `pattern`.
+pub fn trace_pattern(pattern: FunctionPattern, match_spec: Option<Option<MatchSpec>>) -> usize {
+    init();
+
+    let mut writable_call_patterns = CALL_PATTERNS.write();
+    writable_call_patterns.retain(|call_pattern| call_pattern.pattern != pattern);
+
+    if let Some(match_spec) = match_spec {
+        writable_call_patterns.push(CallPattern {
+            pattern,
+            match_spec,
+        });
+    }
+
+    match pattern {
+        FunctionPattern {
+            module: Some(_),
+            function: Some(_),
+            arity: Some(_),
+        } => 1,
+        _ => liblumen_alloc::erts::apply::module_function_arities()
+            .iter()
+            .filter(|module_function_arity| pattern.matches(module_function_arity))
+            .count(),
+    }
+}
+
+/// The flags of `process`
+pub fn flags(process: &Process) -> TraceFlags {
+    TRACEE_BY_PID
+        .get(&process.pid())
+        .map(|tracee| tracee.flags)
+        .unwrap_or_default()
+}
+
+/// `process` sent `message` to `destination`
+pub fn send(process: &Process, message: Term, destination: Term) {
+    if let Some(tracer) = tracer_for(process, TraceFlags::Send) {
+        let _ = trace_message(
+            process,
+            tracer,
+            "send",
+            &[Element::Term(message), Element::Term(destination)],
+        );
+    }
+}
+
+/// `process` delivered `message` to `destination`
+pub fn receive(process: &Process, destination: &Process, message: Term) {
+    if let Some(tracer) = tracer_for(destination, TraceFlags::Receive) {
+        let _ = send_trace_message(
+            process,
+            tracer,
+            destination.pid_term(),
+            "receive",
+            &[Element::Term(message)],
+        );
+    }
+}
+
+/// The arguments of a spawned `module:function(arguments)`
+pub enum Arguments<'a> {
+    List(Term),
+    /// Only copied into a list when the spawn is traced
+    Slice(&'a [Term]),
+}
+
+/// `parent` spawned `child` to call `module:function(arguments)`
+pub fn spawn(
+    parent: &Process,
+    child: &Process,
+    module: Atom,
+    function: Atom,
+    arguments: Arguments,
+) {
+    if let Some(parent_tracee) = TRACEE_BY_PID.get(&parent.pid()).map(|tracee| *tracee) {
+        if parent_tracee.flags.are_set(TraceFlags::SetOnSpawn) {
+            set(child, true, parent_tracee.flags, parent_tracee.tracer);
+        }
+
+        if parent_tracee.flags.are_set(TraceFlags::Procs) {
+            let mfa = match arguments {
+                Arguments::List(list) => Element::Mfa(module, function, list),
+                Arguments::Slice(slice) => Element::Call(module, function, slice),
+            };
+            let _ = trace_message(
+                parent,
+                parent_tracee.tracer,
+                "spawn",
+                &[Element::Term(child.pid_term()), mfa],
+            );
+        }
+    } else if let Some(new_tracee) = *NEW.read() {
+        set(child, true, new_tracee.flags, new_tracee.tracer);
+    }
+}
+
+/// `process` linked to (`linked` is `true`) or unlinked from `other`
+pub fn link(process: &Process, other: Term, linked: bool) {
+    if let Some(tracer) = tracer_for(process, TraceFlags::Procs) {
+        let tag = if linked { "link" } else { "unlink" };
+        let _ = trace_message(process, tracer, tag, &[Element::Term(other)]);
+    }
+}
+
+/// `process` exited with `reason`.  Its flags are cleared, as it can no longer be traced.
+pub fn exit(process: &Process, reason: Term) {
+    if let Some(tracer) = tracer_for(process, TraceFlags::Procs) {
+        let _ = trace_message(process, tracer, "exit", &[Element::Term(reason)]);
+    }
+
+    TRACEE_BY_PID.remove(&process.pid());
+}
+
+// Private
+
+#[derive(Clone, Copy)]
+struct Tracee {
+    flags: TraceFlags,
+    tracer: Pid,
+}
+
+struct CallPattern {
+    pattern: FunctionPattern,
+    /// `None` traces all calls
+    match_spec: Option<MatchSpec>,
+}
+
+lazy_static! {
+    static ref TRACEE_BY_PID: DashMap<Pid, Tracee> = Default::default();
+    /// The flags of processes spawned by untraced parents
+    static ref NEW: RwLock<Option<Tracee>> = Default::default();
+    /// The last pattern set for a function wins
+    static ref CALL_PATTERNS: RwLock<Vec<CallPattern>> = Default::default();
+}
+
+fn init() {
+    // Fails when already set, which is fine
+    set_call_trace_hooks(CallTraceHooks { call, returned });
+}
+
+fn set(process: &Process, how: bool, flags: TraceFlags, tracer: Pid) {
+    let pid = process.pid();
+    let tracee_flags = match TRACEE_BY_PID.get(&pid).map(|tracee| tracee.flags) {
+        Some(old_flags) if how => old_flags | flags,
+        Some(old_flags) => old_flags.without(flags),
+        None if how => flags,
+        None => TraceFlags::default(),
+    };
+
+    if tracee_flags.is_empty() {
+        TRACEE_BY_PID.remove(&pid);
+    } else {
+        TRACEE_BY_PID.insert(
+            pid,
+            Tracee {
+                flags: tracee_flags,
+                tracer,
+            },
+        );
+    }
+
+    if tracee_flags.are_set(TraceFlags::Call) {
+        process.set_flags(ProcessFlags::TraceCall);
+    } else {
+        process.clear_flags(ProcessFlags::TraceCall);
+    }
+}
+
+fn existing(how: bool, flags: TraceFlags, tracer: Pid) -> usize {
+    let arc_process_vec: Vec<Arc<Process>> = registry::processes()
+        .into_iter()
+        // Like BEAM, the tracer is not traced, so that it does not trace its own receives
+        .filter(|arc_process| !arc_process.is_exiting() && arc_process.pid() != tracer)
+        .collect();
+
+    for arc_process in &arc_process_vec {
+        set(arc_process, how, flags, tracer);
+    }
+
+    arc_process_vec.len()
+}
+
+fn new(how: bool, flags: TraceFlags, tracer: Pid) {
+    let mut writable_new = NEW.write();
+    let new_flags = match *writable_new {
+        Some(Tracee {
+            flags: old_flags, ..
+        }) if how => old_flags | flags,
+        Some(Tracee {
+            flags: old_flags, ..
+        }) => old_flags.without(flags),
+        None if how => flags,
+        None => TraceFlags::default(),
+    };
+
+    *writable_new = if new_flags.is_empty() {
+        None
+    } else {
+        Some(Tracee {
+            flags: new_flags,
+            tracer,
+        })
+    };
+}
+
+fn tracer_for(process: &Process, flags: TraceFlags) -> Option<Pid> {
+    TRACEE_BY_PID
+        .get(&process.pid())
+        .filter(|tracee| tracee.flags.are_set(flags))
+        .map(|tracee| tracee.tracer)
+}
+
+fn call(
+    process: &Process,
+    module_function_arity: &ModuleFunctionArity,
+    arguments: &[Term],
+) -> Option<CallTrace> {
+    let tracee = TRACEE_BY_PID.get(&process.pid()).map(|tracee| *tracee)?;
+
+    if !tracee.flags.are_set(TraceFlags::Call) {
+        return None;
+    }
+
+    let readable_call_patterns = CALL_PATTERNS.read();
+    let call_pattern = readable_call_patterns
+        .iter()
+        .rev()
+        .find(|call_pattern| call_pattern.pattern.matches(module_function_arity))?;
+    let actions = match &call_pattern.match_spec {
+        Some(match_spec) => match_spec.run(arguments)?,
+        None => match_spec::Actions {
+            return_trace: false,
+            message: true,
+        },
+    };
+
+    if actions.message {
+        let mfa = Element::Call(
+            module_function_arity.module,
+            module_function_arity.function,
+            arguments,
+        );
+        let _ = trace_message(process, tracee.tracer, "call", &[mfa]);
+    }
+
+    Some(CallTrace {
+        return_from: actions.return_trace,
+        return_to: tracee.flags.are_set(TraceFlags::ReturnTo),
+    })
+}
+
+fn returned(
+    process: &Process,
+    call_trace: CallTrace,
+    module_function_arity: &ModuleFunctionArity,
+    value: Term,
+    returned_to: Option<ModuleFunctionArity>,
+) {
+    let tracer = match tracer_for(process, TraceFlags::Call) {
+        Some(tracer) => tracer,
+        None => return,
+    };
+
+    if call_trace.return_from {
+        let _ = trace_message(
+            process,
+            tracer,
+            "return_from",
+            &[arity_mfa(module_function_arity), Element::Term(value)],
+        );
+    }
+
+    if call_trace.return_to {
+        if let Some(returned_to) = returned_to {
+            let _ = trace_message(process, tracer, "return_to", &[arity_mfa(&returned_to)]);
+        }
+    }
+}
+
+/// An element after the tag of a trace message, copied or built into the message's fragment
+enum Element<'a> {
+    Term(Term),
+    /// `{Module, Function, ArgumentsOrArity}`
+    Mfa(Atom, Atom, Term),
+    /// `{Module, Function, Arguments}` with the `Arguments` list built from the slice
+    Call(Atom, Atom, &'a [Term]),
+}
+
+impl<'a> Element<'a> {
+    fn need_in_words(&self) -> usize {
+        match self {
+            Self::Term(term) => term.size_in_words(),
+            Self::Mfa(_, _, arguments_or_arity) => {
+                erts::to_word_size(Tuple::layout_for_len(3).size())
+                    + arguments_or_arity.size_in_words()
+            }
+            Self::Call(_, _, arguments) => {
+                erts::to_word_size(Tuple::layout_for_len(3).size())
+                    + Cons::need_in_words_from_len(arguments.len())
+                    + arguments
+                        .iter()
+                        .map(|argument| argument.size_in_words())
+                        .sum::<usize>()
+            }
+        }
+    }
+
+    fn build(&self, heap_fragment: &mut HeapFragment) -> AllocResult<Term> {
+        match self {
+            Self::Term(term) => term.clone_to_heap(heap_fragment),
+            Self::Mfa(module, function, arguments_or_arity) => {
+                let arguments_or_arity = arguments_or_arity.clone_to_heap(heap_fragment)?;
+
+                mfa(heap_fragment, *module, *function, arguments_or_arity)
+            }
+            Self::Call(module, function, arguments) => {
+                let mut argument_vec = Vec::with_capacity(arguments.len());
+
+                for argument in arguments.iter() {
+                    argument_vec.push(argument.clone_to_heap(heap_fragment)?);
+                }
+
+                let argument_list = heap_fragment
+                    .list_from_slice(&argument_vec)?
+                    .map(|cons| cons.into())
+                    .unwrap_or(Term::NIL);
+
+                mfa(heap_fragment, *module, *function, argument_list)
+            }
+        }
+    }
+}
+
+/// `{Module, Function, Arity}`
+fn arity_mfa(module_function_arity: &ModuleFunctionArity) -> Element {
+    Element::Mfa(
+        module_function_arity.module,
+        module_function_arity.function,
+        fixnum!(module_function_arity.arity),
+    )
+}
+
+fn mfa(
+    heap_fragment: &mut HeapFragment,
+    module: Atom,
+    function: Atom,
+    arguments_or_arity: Term,
+) -> AllocResult<Term> {
+    heap_fragment
+        .tuple_from_slice(&[
+            module.encode().unwrap(),
+            function.encode().unwrap(),
+            arguments_or_arity,
+        ])
+        .map(|tuple| tuple.into())
+}
+
+/// `{trace, Pid, Tag, ...}` about `process`
+fn trace_message(process: &Process, tracer: Pid, tag: &str, rest: &[Element]) -> AllocResult<()> {
+    send_trace_message(process, tracer, process.pid_term(), tag, rest)
+}
+
+/// `{trace, traced, Tag, ...}` built in a `HeapFragment` and sent to `tracer`
+fn send_trace_message(
+    process: &Process,
+    tracer: Pid,
+    traced: Term,
+    tag: &str,
+    rest: &[Element],
+) -> AllocResult<()> {
+    let need_in_words = erts::to_word_size(Tuple::layout_for_len(3 + rest.len()).size())
+        + traced.size_in_words()
+        + rest
+            .iter()
+            .map(|element| element.need_in_words())
+            .sum::<usize>();
+    let mut heap_fragment = HeapFragment::new_from_word_size(need_in_words)?;
+    // `HeapFragment`'s `Drop` releases the term in the first word, which must be initialized even
+    // if building the message fails before anything is allocated
+    unsafe { heap_fragment.as_mut().heap_start().write(Term::NIL) };
+
+    let message = match trace_message_in(unsafe { heap_fragment.as_mut() }, traced, tag, rest) {
+        Ok(message) => message,
+        Err(error) => {
+            unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+
+            return Err(error);
+        }
+    };
+
+    if tracer == process.pid() {
+        process.send_heap_message(heap_fragment, message);
+    } else if let Some(tracer_arc_process) = pid_to_process(&tracer) {
+        send_heap_message(&tracer_arc_process, heap_fragment, message);
+    } else {
+        // Like a message to a dead process, a trace message to a dead tracer is dropped
+        unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+    }
+
+    Ok(())
+}
+
+fn trace_message_in(
+    heap_fragment: &mut HeapFragment,
+    traced: Term,
+    tag: &str,
+    rest: &[Element],
+) -> AllocResult<Term> {
+    let mut element_vec = Vec::with_capacity(3 + rest.len());
+    element_vec.push(Atom::str_to_term("trace"));
+    element_vec.push(traced.clone_to_heap(heap_fragment)?);
+    element_vec.push(Atom::str_to_term(tag));
+
+    for element in rest {
+        element_vec.push(element.build(heap_fragment)?);
+    }
+
+    heap_fragment
+        .tuple_from_slice(&element_vec)
+        .map(|tuple| tuple.into())
+}
@@ -0,0 +1,450 @@
+//! Match specifications, as given to `erlang:trace_pattern/3`.
+//!
+//! A match specification outlives the heap of the process that set it, so it is converted from
+//! its term form when it is set.  Only immediates (atoms, small integers, `[]` and local pids)
+//! can be literals in it.
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+pub struct MatchSpec(Vec<Clause>);
+
+/// What the body of the matching clause asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Actions {
+    pub return_trace: bool,
+    /// `false` if the body has `{message, false}`, which suppresses the call trace message
+    pub message: bool,
+}
+
+impl MatchSpec {
+    pub fn try_from_term(match_spec: Term) -> anyhow::Result<Self> {
+        let clause_terms = list_to_vec(match_spec)
+            .with_context(|| format!("match_spec ({}) is not a list", match_spec))?;
+        let mut clause_vec = Vec::with_capacity(clause_terms.len());
+
+        for clause_term in clause_terms {
+            let clause = Clause::try_from_term(clause_term).with_context(|| {
+                format!(
+                    "clause ({}) is not {{MatchHead, [MatchCondition], [MatchBody]}}",
+                    clause_term
+                )
+            })?;
+            clause_vec.push(clause);
+        }
+
+        Ok(Self(clause_vec))
+    }
+
+    /// The actions of the first clause that matches `arguments`, or `None` if none do
+    pub fn run(&self, arguments: &[Term]) -> Option<Actions> {
+        self.0.iter().find_map(|clause| clause.run(arguments))
+    }
+}
+
+// Private
+
+struct Clause {
+    head: Pattern,
+    guards: Vec<Expression>,
+    body: Vec<Action>,
+}
+
+impl Clause {
+    fn try_from_term(clause: Term) -> anyhow::Result<Self> {
+        let tuple: Boxed<Tuple> = clause.try_into()?;
+
+        if tuple.len() != 3 {
+            return Err(anyhow!("clause ({}) is not a 3-tuple", clause));
+        }
+
+        let head = Pattern::try_from_term(tuple[0])?;
+        let guards = list_to_vec(tuple[1])?
+            .into_iter()
+            .map(Expression::try_from_term)
+            .collect::<anyhow::Result<_>>()?;
+        let body = list_to_vec(tuple[2])?
+            .into_iter()
+            .map(Action::try_from_term)
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self { head, guards, body })
+    }
+
+    fn run(&self, arguments: &[Term]) -> Option<Actions> {
+        let mut bindings = Vec::new();
+
+        if !self.head.match_arguments(arguments, &mut bindings) {
+            return None;
+        }
+
+        for guard in &self.guards {
+            if guard.evaluate(&bindings) != Some(true.into()) {
+                return None;
+            }
+        }
+
+        let mut actions = Actions {
+            return_trace: false,
+            message: true,
+        };
+
+        for action in &self.body {
+            match action {
+                Action::ReturnTrace => actions.return_trace = true,
+                Action::Message(message) => actions.message = *message,
+            }
+        }
+
+        Some(actions)
+    }
+}
+
+enum Pattern {
+    /// `'_'`
+    Ignore,
+    /// `'$1'`, `'$2'`, ...
+    Variable(usize),
+    Immediate(Term),
+    List(Vec<Pattern>, Box<Pattern>),
+    Tuple(Vec<Pattern>),
+}
+
+impl Pattern {
+    fn try_from_term(pattern: Term) -> anyhow::Result<Self> {
+        match pattern.decode()? {
+            TypedTerm::Atom(atom) => Ok(match variable(atom) {
+                Some(Variable::Ignore) => Pattern::Ignore,
+                Some(Variable::Numbered(number)) => Pattern::Variable(number),
+                None => Pattern::Immediate(pattern),
+            }),
+            TypedTerm::List(cons) => {
+                let mut element_vec = Vec::new();
+                let mut tail = Term::NIL;
+
+                for result in cons.into_iter() {
+                    match result {
+                        Ok(element) => element_vec.push(Pattern::try_from_term(element)?),
+                        Err(ImproperList { tail: improper }) => tail = improper,
+                    }
+                }
+
+                Ok(Pattern::List(
+                    element_vec,
+                    Box::new(Pattern::try_from_term(tail)?),
+                ))
+            }
+            TypedTerm::Tuple(tuple) => Ok(Pattern::Tuple(
+                tuple
+                    .iter()
+                    .map(|element| Pattern::try_from_term(*element))
+                    .collect::<anyhow::Result<_>>()?,
+            )),
+            _ => immediate(pattern).map(Pattern::Immediate),
+        }
+    }
+
+    /// The head of a trace match specification matches the list of arguments
+    fn match_arguments(&self, arguments: &[Term], bindings: &mut Vec<Option<Term>>) -> bool {
+        match self {
+            Pattern::Ignore => true,
+            Pattern::List(element_patterns, tail_pattern) => {
+                element_patterns.len() == arguments.len()
+                    && matches_nil(tail_pattern)
+                    && element_patterns
+                        .iter()
+                        .zip(arguments)
+                        .all(|(pattern, argument)| pattern.match_term(*argument, bindings))
+            }
+            Pattern::Immediate(term) => term.is_nil() && arguments.is_empty(),
+            _ => false,
+        }
+    }
+
+    fn match_term(&self, term: Term, bindings: &mut Vec<Option<Term>>) -> bool {
+        match self {
+            Pattern::Ignore => true,
+            Pattern::Variable(number) => {
+                if bindings.len() <= *number {
+                    bindings.resize(number + 1, None);
+                }
+
+                match bindings[*number] {
+                    Some(bound) => exact_eq(bound, term),
+                    None => {
+                        bindings[*number] = Some(term);
+
+                        true
+                    }
+                }
+            }
+            Pattern::Immediate(immediate) => exact_eq(*immediate, term),
+            Pattern::List(element_patterns, tail_pattern) => {
+                let mut remaining = term;
+
+                for element_pattern in element_patterns {
+                    match remaining.decode() {
+                        Ok(TypedTerm::List(cons)) => {
+                            if !element_pattern.match_term(cons.head, bindings) {
+                                return false;
+                            }
+
+                            remaining = cons.tail;
+                        }
+                        _ => return false,
+                    }
+                }
+
+                tail_pattern.match_term(remaining, bindings)
+            }
+            Pattern::Tuple(element_patterns) => match term.decode() {
+                Ok(TypedTerm::Tuple(tuple)) => {
+                    tuple.len() == element_patterns.len()
+                        && element_patterns
+                            .iter()
+                            .zip(tuple.iter())
+                            .all(|(pattern, element)| pattern.match_term(*element, bindings))
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+enum Expression {
+    Variable(usize),
+    Immediate(Term),
+    Call(Atom, Vec<Expression>),
+}
+
+impl Expression {
+    fn try_from_term(expression: Term) -> anyhow::Result<Self> {
+        match expression.decode()? {
+            TypedTerm::Atom(atom) => match variable(atom) {
+                Some(Variable::Numbered(number)) => Ok(Expression::Variable(number)),
+                Some(Variable::Ignore) => Err(anyhow!(
+                    "'_' can only be used in the match head, not in conditions"
+                )),
+                None => Ok(Expression::Immediate(expression)),
+            },
+            TypedTerm::Tuple(tuple) if 0 < tuple.len() => {
+                let function: Atom = tuple[0].try_into().with_context(|| {
+                    format!(
+                        "condition ({}) does not start with a function name",
+                        expression
+                    )
+                })?;
+
+                if function == "const" && tuple.len() == 2 {
+                    return immediate(tuple[1]).map(Expression::Immediate);
+                }
+
+                let argument_vec = tuple[1..]
+                    .iter()
+                    .map(|argument| Expression::try_from_term(*argument))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                if is_supported_function(function, argument_vec.len()) {
+                    Ok(Expression::Call(function, argument_vec))
+                } else {
+                    Err(anyhow!(
+                        "{}/{} is not a supported condition function",
+                        function.name(),
+                        argument_vec.len()
+                    ))
+                }
+            }
+            _ => immediate(expression).map(Expression::Immediate),
+        }
+    }
+
+    /// `None` when the guard fails, such as by using an unbound variable
+    fn evaluate(&self, bindings: &[Option<Term>]) -> Option<Term> {
+        match self {
+            Expression::Variable(number) => bindings.get(*number).cloned().flatten(),
+            Expression::Immediate(term) => Some(*term),
+            Expression::Call(function, arguments) => {
+                match (function.name(), arguments.as_slice()) {
+                    ("andalso", [left, right]) => {
+                        if boolean(left.evaluate(bindings)?)? {
+                            Some(boolean(right.evaluate(bindings)?)?.into())
+                        } else {
+                            Some(false.into())
+                        }
+                    }
+                    ("orelse", [left, right]) => {
+                        if boolean(left.evaluate(bindings)?)? {
+                            Some(true.into())
+                        } else {
+                            Some(boolean(right.evaluate(bindings)?)?.into())
+                        }
+                    }
+                    (name, [argument]) => {
+                        let value = argument.evaluate(bindings)?;
+
+                        let result = match name {
+                            "not" => !boolean(value)?,
+                            "is_atom" => value.is_atom(),
+                            "is_binary" => value.is_binary(),
+                            "is_float" => value.is_float(),
+                            "is_integer" => value.is_integer(),
+                            "is_list" => value.is_list(),
+                            "is_map" => value.is_map(),
+                            "is_number" => value.is_number(),
+                            "is_pid" => value.is_pid(),
+                            "is_tuple" => value.is_tuple(),
+                            _ => unreachable!(),
+                        };
+
+                        Some(result.into())
+                    }
+                    (name, [left, right]) => {
+                        let left = left.evaluate(bindings)?;
+                        let right = right.evaluate(bindings)?;
+
+                        let result = match name {
+                            "and" => boolean(left)? & boolean(right)?,
+                            "or" => boolean(left)? | boolean(right)?,
+                            "xor" => boolean(left)? ^ boolean(right)?,
+                            "==" => left.eq(&right),
+                            "/=" => left.ne(&right),
+                            "=:=" => exact_eq(left, right),
+                            "=/=" => !exact_eq(left, right),
+                            "<" => left.lt(&right),
+                            "=<" => left.le(&right),
+                            ">" => left.gt(&right),
+                            ">=" => left.ge(&right),
+                            _ => unreachable!(),
+                        };
+
+                        Some(result.into())
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+enum Action {
+    ReturnTrace,
+    Message(bool),
+}
+
+impl Action {
+    fn try_from_term(action: Term) -> anyhow::Result<Self> {
+        let tuple: Boxed<Tuple> = action
+            .try_into()
+            .with_context(|| format!("action ({}) is not a tuple", action))?;
+
+        let name = if tuple.len() > 0 {
+            tuple[0]
+        } else {
+            Term::NONE
+        };
+
+        match tuple.len() {
+            1 if name == Atom::str_to_term("return_trace") => Ok(Action::ReturnTrace),
+            2 if name == Atom::str_to_term("message") => match tuple[1].decode()? {
+                TypedTerm::Atom(atom) if atom == "true" => Ok(Action::Message(true)),
+                TypedTerm::Atom(atom) if atom == "false" => Ok(Action::Message(false)),
+                _ => Err(anyhow!(
+                    "only {{message, true}} and {{message, false}} are supported"
+                )),
+            },
+            _ => Err(anyhow!(
+                "action ({}) is not supported; supported actions are {{return_trace}} and \
+                 {{message, boolean()}}",
+                action
+            )),
+        }
+    }
+}
+
+enum Variable {
+    Ignore,
+    Numbered(usize),
+}
+
+fn variable(atom: Atom) -> Option<Variable> {
+    let name = atom.name();
+
+    if name == "_" {
+        Some(Variable::Ignore)
+    } else if name.starts_with('$') {
+        name[1..].parse().ok().map(Variable::Numbered)
+    } else {
+        None
+    }
+}
+
+fn immediate(term: Term) -> anyhow::Result<Term> {
+    if term.is_immediate() && !term.is_none() {
+        Ok(term)
+    } else {
+        Err(anyhow!(
+            "literal ({}) is not an atom, small integer, [] or local pid",
+            term
+        ))
+    }
+}
+
+fn is_supported_function(function: Atom, arity: usize) -> bool {
+    match (function.name(), arity) {
+        ("not", 1)
+        | ("is_atom", 1)
+        | ("is_binary", 1)
+        | ("is_float", 1)
+        | ("is_integer", 1)
+        | ("is_list", 1)
+        | ("is_map", 1)
+        | ("is_number", 1)
+        | ("is_pid", 1)
+        | ("is_tuple", 1) => true,
+        ("and", 2)
+        | ("or", 2)
+        | ("xor", 2)
+        | ("andalso", 2)
+        | ("orelse", 2)
+        | ("==", 2)
+        | ("/=", 2)
+        | ("=:=", 2)
+        | ("=/=", 2)
+        | ("<", 2)
+        | ("=<", 2)
+        | (">", 2)
+        | (">=", 2) => true,
+        _ => false,
+    }
+}
+
+fn boolean(term: Term) -> Option<bool> {
+    term.try_into().ok()
+}
+
+fn exact_eq(left: Term, right: Term) -> bool {
+    match (left.decode(), right.decode()) {
+        (Ok(left), Ok(right)) => left.exact_eq(&right),
+        _ => false,
+    }
+}
+
+fn matches_nil(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Immediate(term) => term.is_nil(),
+        _ => false,
+    }
+}
+
+fn list_to_vec(list: Term) -> anyhow::Result<Vec<Term>> {
+    match list.decode()? {
+        TypedTerm::Nil => Ok(Vec::new()),
+        TypedTerm::List(cons) => cons
+            .into_iter()
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| anyhow!("list ({}) is improper", list)),
+        _ => Err(anyhow!("{} is not a list", list)),
+    }
+}
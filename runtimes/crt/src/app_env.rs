@@ -0,0 +1,16 @@
+extern "C" {
+    /// This symbol is defined in the compiled executable,
+    /// and specifies the size in bytes of the application environment.
+    #[link_name = "__LUMEN_APP_ENV_SIZE"]
+    pub static APP_ENV_SIZE: usize;
+
+    /// This symbol is defined in the compiled executable,
+    /// and provides a pointer to the environment of the applications
+    /// in the build, encoded in the external term format as a list of
+    /// `{Application, [{Key, Value}]}` tuples.
+    #[link_name = "__LUMEN_APP_ENV"]
+    pub static APP_ENV: *const u8;
+
+    /// This function is defined in `lumen_rt_core::application`
+    pub fn InitializeLumenAppEnv(env: *const u8, len: usize) -> bool;
+}
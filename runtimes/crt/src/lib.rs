@@ -1,6 +1,8 @@
+#![feature(linkage)]
 #![feature(main)]
 #![feature(termination_trait_lib)]
 
+mod app_env;
 mod atoms;
 mod symbols;
 
@@ -19,7 +21,9 @@ extern "C" {
     fn lang_start(main: &dyn Fn() -> i32, argc: isize, argv: *const *const i8) -> isize;
 }
 
+// Weak, so that a program embedding a library built by the compiler can define its own `main`
 #[no_mangle]
+#[linkage = "weak"]
 pub extern "C" fn main(argc: i32, argv: *const *const std::os::raw::c_char) -> i32 {
     unsafe { lang_start(&move || main_internal(), argc as isize, argv) as i32 }
 }
@@ -32,23 +36,41 @@ pub extern "C" fn main(argc: i32, argv: *const *const std::os::raw::c_char) -> i
 /// up the schedulers and other high-level runtime functionality.
 #[main]
 pub fn main_internal() -> i32 {
+    if let Err(code) = initialize() {
+        return code;
+    }
+
+    // Invoke platform-specific entry point
+    unsafe { lumen_entry() }
+}
+
+/// Initializes the core runtime functionality from the data generated by the compiler
+///
+/// This is called by `main_internal`, or by the runtime when it is embedded in another
+/// program, and returns the exit code to use if initialization fails.
+pub fn initialize() -> Result<(), i32> {
+    use crate::app_env::*;
     use crate::atoms::*;
     use crate::symbols::*;
 
     // Initialize atom table
     if unsafe { InitializeLumenAtomTable(ATOM_TABLE, NUM_ATOMS) } == false {
-        return 102;
+        return Err(102);
     }
 
     eprintln!("Initalized atom table");
 
     // Initialize the dispatch table
     if unsafe { InitializeLumenDispatchTable(SYMBOL_TABLE, NUM_SYMBOLS) } == false {
-        return 103;
+        return Err(103);
     }
 
     eprintln!("Initalized dispatch table");
 
-    // Invoke platform-specific entry point
-    unsafe { lumen_entry() }
+    // Initialize the application environment
+    if unsafe { InitializeLumenAppEnv(APP_ENV, APP_ENV_SIZE) } == false {
+        return Err(104);
+    }
+
+    Ok(())
 }
@@ -1,302 +1,908 @@
-///! This module is just a sketch in Rust syntax of the algorithm, the code is not valie
-pub trait GarbageCollector {
-    fn gc(&mut self);
+//! An incremental collector for the areas shared between processes: literals, off-heap binaries,
+//! and message fragments.
+//!
+//! Off-heap binaries and message fragments are still allocated by `liblumen_alloc` and freed by
+//! reference counting and by the receiving process, so nothing allocates in a `SharedArea` yet,
+//! and the runtime does not start the collector.
+//!
+//! The collector divides each collection cycle into stages, and each stage stops once it has used
+//! up its `Quantum` of time or work, so mutators are never paused for longer than a quantum.  How
+//! much live data a cycle has to trace isn't known when the cycle starts, so the amount mutators
+//! may allocate between stages is limited instead: reaching the allocation limit wakes the
+//! collector for its next stage.
+//!
+//! # Design
+//!
+//! * The young generation has two spaces.  Mutators allocate in the nursery.  A cycle starts by
+//!   swapping the nursery and the from space, which is atomic as mutators are locked out of the
+//!   area during a swap, and the live objects of the from space are then copied to the old
+//!   generation.
+//! * Forwarding pointers are kept in a forwarding area beside the from space, instead of in the
+//!   objects, so mutators can still read objects in the from space between stages.  The from space
+//!   is only freed once the cycle has copied all of its live objects.
+//! * The old generation is made of pages of slots, which are allocated from a free list, except
+//!   in the page fetched at the start of each major cycle, which is allocated from by bumping.
+//!   Running out of slots in the old generation makes the next cycle major.
+//! * Major cycles also mark the live objects of the old generation in a black map, as objects'
+//!   headers have no bits to spare for the mark, then sweep the unmarked slots onto the free list
+//!   a page per step, releasing pages that end up empty.
+//! * Only the roots of dirty mutators are scanned.  A mutator is dirty if it obtained a reference
+//!   into the nursery before the nursery became the from space.  A mutator that obtains another
+//!   reference into the nursery is moved to the back of the dirty set, so the busiest mutators are
+//!   scanned last, giving them a chance to produce more garbage, or to die, first.  All mutators
+//!   are dirty in a major cycle.  Mutators that are running when they are due to be scanned are
+//!   scanned in a later stage.
+//!
+//! Unlike in the sketch this collector started as, the nursery never has to be scanned at the end
+//! of a cycle: mutators obtain and store references only through the barriers in `SharedArea`,
+//! which copy objects out of the from space and, in major cycles, mark old objects, before the
+//! references can be stored in the nursery or in a scanned mutator.
+#[cfg(test)]
+mod test;
+
+use std::collections::VecDeque;
+use std::io;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use thiserror::Error;
+
+use liblumen_core::locks::{Condvar, Mutex};
+
+/// Stages stop after copying or scanning 4096 words, unless configured otherwise
+pub const DEFAULT_QUANTUM: Quantum = Quantum::Work(4096);
+
+/// Cycles start after 1MiB is allocated in the nursery, unless configured otherwise
+pub const DEFAULT_NURSERY_SIZE: usize = 1 << 20;
+
+/// How long a stage may use before yielding to mutators
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quantum {
+    Time(Duration),
+    /// The number of words copied or scanned
+    Work(usize),
 }
 
-enum Cycle {
-    None,
-    Major,
-    Minor,
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub quantum: Quantum,
+    /// The bytes allocated in the nursery that start a cycle
+    pub nursery_size: usize,
 }
 
-/// During a collection cycle, the collector might exceed its allowable (time or work based) quantum, and
-/// be forced to abort collection and yield to the mutator.
-///
-/// In incremental tracing collectors like this one, the amount of work to be done in one collection cycle
-/// depends on the amount of live data when the root set is taken. Since we don't know the quantity, we
-/// devise a mechanism that allows us to control how much allocation the mutator is allowed to do between stages.
-///
-/// When the mutator reaches the allocation limit, the collector is invoked.
-///
-/// # Design Notes
-///
-/// * Forwarding area implemented as a array of constant size, same size as the from-space,
-/// * Preallocate an array of pages, 32k words per page, those not used by the old generation are kept in a linked list on the side
-/// * Require locking the PCB when snapshotting roots; and when removing a process from the dirty set to prevent removing the dirty
-///   bit from a process at the same time it becomes dirty by a message send
-/// * Forwarding pointers are stored in a separate areas to allow the mutator to access objects in the from space between collection stages
-///
-/// ## Atomic Operations
-///
-/// Atomic opertions in the collector are stages during which the collector cannot be interuptted:
-///
-/// * Swapping the nursery and the from space (this has to be truly atomic, as mutators cannot be trying to allocate during the swap)
-/// * Setting up and cleaning up auxiliary areas for collection, since checks for quantum expiration occur at the beginning of
-///   various loops, anything internal occuring between checks are effectively atomic operations for the collector (they are only visible by the collector)
-///
-/// ## Optimizations
-///
-/// * Processes added to the dirty set at the beginning of a major collection are done so in order of age,
-///   but each time a process receives a message, it is moved to the last in the set, as if it was a newly spawned process, this keeps the busiest
-///   processes last, scanning them late as possible, making the dirty set effectively a queue. The reasons for this are:
-///     * avoid repeated re-activation of message-exchanging processes
-///     * allow processes to execute long enough to generate garbage
-///     * give processes a chance to die before taking a snapshot of their root set, potentially allowing us to avoid considering them at all
-/// * Process the stack of gray objects after each process, postponing the processing of other processes (as above)
-/// * In minor collections, we remember the top of the heap for each process, and only consider roots data which was created since the process
-///   was taken off the dirty process set
-/// * When the collector rescues objects from the young gen to the old, it uses the free list; but since a new page is allocated at the beginning
-///   of each major collection, we can cheaply allocate in this page by pointer bumping during the collection
-/// * A key optimization is to have process-local collections record pointers into the message area in a remembered set; this way we avoid scanning
-///   the old generation of their local heaps.
-pub struct Incremental {
-    cycle: Cycle,
-    next_cycle: Option<Cycle>,
-    nursery: *const u8,
-    from_space: *const u8,
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            quantum: DEFAULT_QUANTUM,
+            nursery_size: DEFAULT_NURSERY_SIZE,
+        }
+    }
 }
-impl GarbageCollector for Incremental {
-    fn gc(&mut self) {
-        match self.cycle {
-            // Set if we're in a cycle and it is a major
-            Gc::Major => self.major_gc(),
-            // Set if we're in a cycle, but not major
-            Gc::Minor => self.minor_gc(),
-            // Set if we're not in a cycle
-            // Indicates we need to start a new cycle
-            Gc::Idle => {
-                // Start by swapping the nursery and from space roles, this is atomic
-                self.swap();
-                // Resets all the forwarding pointers
-                self.clear_forwarding_area();
-                // If during the last cycle we determined a major collection was needed, this is set
-                match self.next_cycle {
-                    Some(Gc::Major) => {
-                        self.cycle = Gc::Major;
-                        self.next_cycle = None;
-                        self.gc();
-                    },
-                    _ => {
-                        self.cycle = Gc::Minor;
-                        self.gc();
-                    }
+
+#[derive(Debug, Error)]
+pub enum CollectorError {
+    #[error("already collecting")]
+    AlreadyCollecting,
+    #[error("not collecting")]
+    NotCollecting,
+    #[error("could not start collector thread")]
+    Thread(#[source] io::Error),
+}
+
+/// Starts collecting a new shared area in a dedicated thread
+pub fn start(options: Options) -> Result<Arc<SharedArea>, CollectorError> {
+    let mut collector = COLLECTOR.lock();
+
+    if collector.is_some() {
+        return Err(CollectorError::AlreadyCollecting);
+    }
+
+    let area = Arc::new(SharedArea::new(options));
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_area = area.clone();
+    let thread_stop = stop.clone();
+    let thread = thread::Builder::new()
+        .name("shared_gc".to_owned())
+        .spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                if thread_area.wait_for_stage(IDLE_INTERVAL) {
+                    thread_area.stage();
                 }
             }
+        })
+        .map_err(CollectorError::Thread)?;
+
+    *collector = Some(Collector {
+        area: area.clone(),
+        stop,
+        thread,
+    });
+
+    Ok(area)
+}
+
+/// Stops the collector thread.  The shared area is left as it was after the last stage.
+pub fn stop() -> Result<(), CollectorError> {
+    let collector = COLLECTOR
+        .lock()
+        .take()
+        .ok_or(CollectorError::NotCollecting)?;
+
+    collector.stop.store(true, Ordering::SeqCst);
+    collector.area.stage_requested.notify_all();
+    let _ = collector.thread.join();
+
+    Ok(())
+}
+
+/// The shared area collected by the collector thread, if it was started
+pub fn shared_area() -> Option<Arc<SharedArea>> {
+    COLLECTOR
+        .lock()
+        .as_ref()
+        .map(|collector| collector.area.clone())
+}
+
+/// What an object in the shared area holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Literal,
+    Binary,
+    MessageFragment,
+}
+
+/// A reference to an object in the shared area, held by a mutator or by another object.
+///
+/// References held by mutators are updated by the collector through `Mutator::roots` when it
+/// copies their objects, so two references to the same object may differ.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Reference {
+    space: Space,
+    index: usize,
+}
+
+impl Reference {
+    fn old(index: usize) -> Self {
+        Self {
+            space: Space::Old,
+            index,
+        }
+    }
+}
+
+/// Identifies a mutator registered with `SharedArea::register`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MutatorId(u64);
+
+/// A holder of references into the shared area, such as a process
+pub trait Mutator: Send + Sync {
+    /// Calls `update` with each reference the mutator holds, so that the collector can replace
+    /// references to the objects it copies.  A mutator that is running can't have its roots
+    /// scanned, so it returns `false` instead, and it is scanned in a later stage.
+    ///
+    /// The `SharedArea` is locked while this is called, so it must not be called into or wait for
+    /// a mutator that may be calling into it.
+    fn roots(&self, update: &mut dyn FnMut(&mut Reference)) -> bool;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub minor_cycles: usize,
+    pub major_cycles: usize,
+    pub stages: usize,
+    /// Words copied from the young generation to the old generation
+    pub words_copied: usize,
+    /// Objects freed from either generation
+    pub objects_freed: usize,
+    /// Pages currently in the old generation
+    pub pages: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// The quantum ran out before the cycle finished
+    Yielded,
+    /// The cycle finished
+    Completed,
+}
+
+pub struct SharedArea {
+    quantum: Quantum,
+    state: Mutex<State>,
+    /// Notified when mutators reach the allocation limit
+    stage_requested: Condvar,
+}
+
+impl SharedArea {
+    pub fn new(options: Options) -> Self {
+        Self {
+            quantum: options.quantum,
+            state: Mutex::new(State::new(options.nursery_size)),
+            stage_requested: Condvar::new(),
+        }
+    }
+
+    /// Registers `mutator`, so that its roots are scanned while it is alive
+    pub fn register<M: Mutator + 'static>(&self, mutator: &Arc<M>) -> MutatorId {
+        let weak: Weak<M> = Arc::downgrade(mutator);
+        let mut state = self.state.lock();
+        let id = MutatorId(state.next_mutator_id);
+        state.next_mutator_id += 1;
+        state.mutators.insert(id, weak);
+
+        id
+    }
+
+    pub fn unregister(&self, id: MutatorId) {
+        let mut state = self.state.lock();
+        state.mutators.remove(&id);
+        state.dirty.retain(|dirty_id| *dirty_id != id);
+        state.touched.retain(|touched_id| *touched_id != id);
+    }
+
+    /// Allocates an object in the nursery for `mutator`, which must hold on to the returned
+    /// reference.  `references` must be held by `mutator`.
+    pub fn allocate(
+        &self,
+        mutator: MutatorId,
+        kind: Kind,
+        bytes: &[u8],
+        references: &[Reference],
+    ) -> Reference {
+        let mut state = self.state.lock();
+        let references: Box<[Reference]> = references
+            .iter()
+            .map(|reference| state.copymark(*reference))
+            .collect();
+        let object = Object {
+            kind,
+            bytes: bytes.into(),
+            references,
         };
-        // Calculates how much the mutator is allowed to work before next collection
-        self.update_allocation_limit();
+        let reference = state.allocate(object);
+        state.touch(mutator);
+
+        if state.is_stage_due() {
+            self.stage_requested.notify_one();
+        }
+
+        reference
     }
 
-    // Incremental minor collection.
-    // All roots are traversed and live objects in the from space are copied to
-    // the old generation and marked as gray. Afterwards, all gray objects are traversed
-    // in a similar way to copy their children
-    //
-    // Starts by picking up the first process from the dirty set and conceptually takes
-    // a snapshot of its root set. We do not want to take an actual copy, so we record
-    // the values of the stack and process-local heap pointer and the pointers in the message queue;
-    // in other words, taking a snapshot consists of recording a set of pointers.
-    //
-    // Afterwards, the mutator can continue allocating in the nursery and reading from the from space
-    // as there are no destructive updates. The snapshot is scanned and when a live object is found
-    // in the from space and this object has not yet been forwarded, the object is copied to the old
-    // generation and added to a stack of gray objects. Each time we copy an object, we update the original
-    // root references to point to the new location (update source ref), and store a forwarding pointer
-    // in the forward area to ensure objects are copied at most once (set foward pointer). If the object
-    // was previously forwarded, we just update its reference in the root set
-    //
-    // When all roots are scanned, we pop the gray objects one by one and each object is scanned for references
-    // If the popped object refers to a an object in the from space that has not already been forwarded, the
-    // newly found object is copied and pushed onto the gray stack. In the generational setting, an object is
-    // gray if it has been copied to the old generation but not yet scanned for references to other objects. An
-    // object is fully processed and becomes black when all its children are either black or gray
-    //
-    // Eventually the gray stack is empty and the process is removed from the dirty set, and we move on to the next process.
-    //
-    // During a collection cycle, processes may become dirty again only by receiving a message allocated in the from space,
-    // in effect this acts as a write barrier, as it requires one extra test for each send operation.
-    //
-    // At the end of the cycle, we also have to look through the objects in the nursery to update references still
-    // pointing to the from space (or possibly copy the referred objects), since the mutator can create references from
-    // objects in the nursery to objects in the from space between collection stages. Because the nursery might contain
-    // references to objects not copied to the old generation yet, a final check that the gray stack is empty is needed at the end
-    fn minor_gc(&mut self) {
-        for p in self.dirty_process_set {
-            let roots = self.collect_roots(p); // atomic in time-based collector
-            for r in roots {
-                if self.quantum_expired {
-                    return;
-                }
-                if self.points_to(r, self.from_space) {
-                    self.forward(r);
-                }
-            }
+    /// The write barrier for sending `reference` in a message: returns the reference the
+    /// receiving mutator, `to`, must hold instead.
+    pub fn send(&self, to: MutatorId, reference: Reference) -> Reference {
+        let mut state = self.state.lock();
+        let sent = state.copymark(reference);
+        state.touch_if_nursery(to, sent);
 
-            for g in self.gray() {
-                for rf in g.reference_fields() {
-                    if self.quantum_expired {
-                        return;
-                    }
-                    if self.points_to(rf, self.from_space) {
-                        self.forward(rf);
+        sent
+    }
+
+    /// The read barrier for the reference at `index` in the object at `reference`: returns the
+    /// reference `mutator` must hold.
+    pub fn load(&self, mutator: MutatorId, reference: Reference, index: usize) -> Reference {
+        let mut state = self.state.lock();
+        let field = state.object(reference).references[index];
+        let loaded = state.copymark(field);
+        state.touch_if_nursery(mutator, loaded);
+
+        loaded
+    }
+
+    pub fn kind(&self, reference: Reference) -> Kind {
+        self.state.lock().object(reference).kind
+    }
+
+    pub fn references_len(&self, reference: Reference) -> usize {
+        self.state.lock().object(reference).references.len()
+    }
+
+    /// Calls `f` with the bytes of the object at `reference`.  The area is locked during `f`.
+    pub fn with_bytes<F, R>(&self, reference: Reference, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        f(&self.state.lock().object(reference).bytes)
+    }
+
+    /// Runs the next stage of the current cycle, starting a cycle if there isn't one
+    pub fn stage(&self) -> Stage {
+        self.state.lock().stage(self.quantum)
+    }
+
+    /// Runs stages until the current cycle, or a new one if there isn't one, finishes
+    pub fn collect(&self) {
+        while self.stage() == Stage::Yielded {}
+    }
+
+    /// Makes the next cycle major
+    pub fn request_major(&self) {
+        self.state.lock().next_cycle = Some(Cycle::Major);
+    }
+
+    pub fn is_collecting(&self) -> bool {
+        self.state.lock().cycle.is_some()
+    }
+
+    /// The number of objects in both generations, including garbage that hasn't been collected
+    pub fn object_count(&self) -> usize {
+        let state = self.state.lock();
+        let young_count: usize = state
+            .young
+            .iter()
+            .map(|space| space.iter().filter(|object| object.is_some()).count())
+            .sum();
+
+        young_count + state.old.count()
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        let state = self.state.lock();
+
+        Statistics {
+            pages: state.old.page_count(),
+            ..state.statistics
+        }
+    }
+
+    /// Waits up to `timeout` for mutators to reach the allocation limit.  Returns whether a stage
+    /// should run.
+    fn wait_for_stage(&self, timeout: Duration) -> bool {
+        let mut state = self.state.lock();
+
+        if !state.is_stage_due() {
+            self.stage_requested.wait_for(&mut state, timeout);
+        }
+
+        state.is_stage_due() || state.cycle.is_some()
+    }
+}
+
+// Private
+
+/// How long the collector thread sleeps between stages when mutators aren't allocating
+const IDLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The slots in each page of the old generation
+const PAGE_SLOTS: usize = 1024;
+
+/// The fraction of the nursery size mutators may allocate between the stages of a cycle
+const STAGE_ALLOCATION_DIVISOR: usize = 16;
+
+lazy_static! {
+    static ref COLLECTOR: Mutex<Option<Collector>> = Default::default();
+}
+
+struct Collector {
+    area: Arc<SharedArea>,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+struct Object {
+    kind: Kind,
+    bytes: Box<[u8]>,
+    references: Box<[Reference]>,
+}
+
+impl Object {
+    fn size(&self) -> usize {
+        mem::size_of::<Self>()
+            + self.bytes.len()
+            + self.references.len() * mem::size_of::<Reference>()
+    }
+
+    fn words(&self) -> usize {
+        let word = mem::size_of::<usize>();
+
+        (self.size() + word - 1) / word
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Space {
+    Young(usize),
+    Old,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cycle {
+    Minor,
+    Major,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Idle,
+    /// Copying live objects out of the from space and, in major cycles, marking live old objects
+    Marking,
+    /// Freeing the unmarked objects of the old generation, a page per step
+    Sweeping {
+        page: usize,
+    },
+}
+
+struct Budget {
+    quantum: Quantum,
+    started: Instant,
+}
+
+impl Budget {
+    fn new(quantum: Quantum) -> Self {
+        Self {
+            quantum,
+            started: Instant::now(),
+        }
+    }
+
+    fn is_expired(&self, work: usize) -> bool {
+        match self.quantum {
+            Quantum::Time(duration) => duration <= self.started.elapsed(),
+            Quantum::Work(words) => words <= work,
+        }
+    }
+}
+
+struct State {
+    cycle: Option<Cycle>,
+    next_cycle: Option<Cycle>,
+    phase: Phase,
+    young: [Vec<Option<Object>>; 2],
+    /// Which of `young` is the nursery.  The other is the from space.
+    nursery: usize,
+    nursery_bytes: usize,
+    nursery_size: usize,
+    /// The old generation index of each copied object of the from space
+    forwarding: Vec<Option<usize>>,
+    old: OldGeneration,
+    /// Old objects that have been copied or marked, but whose references haven't been scanned
+    gray: Vec<usize>,
+    mutators: HashMap<MutatorId, Weak<dyn Mutator>>,
+    next_mutator_id: u64,
+    /// The mutators whose roots are still to be scanned in the current cycle
+    dirty: VecDeque<MutatorId>,
+    /// The mutators that obtained references into the nursery, which become dirty when it becomes
+    /// the from space
+    touched: VecDeque<MutatorId>,
+    /// The words copied or scanned in the current stage
+    work: usize,
+    allocated_since_stage: usize,
+    allocation_limit: usize,
+    statistics: Statistics,
+}
+
+impl State {
+    fn new(nursery_size: usize) -> Self {
+        Self {
+            cycle: None,
+            next_cycle: None,
+            phase: Phase::Idle,
+            young: [Vec::new(), Vec::new()],
+            nursery: 0,
+            nursery_bytes: 0,
+            nursery_size,
+            forwarding: Vec::new(),
+            old: Default::default(),
+            gray: Vec::new(),
+            mutators: Default::default(),
+            next_mutator_id: 0,
+            dirty: Default::default(),
+            touched: Default::default(),
+            work: 0,
+            allocated_since_stage: 0,
+            allocation_limit: nursery_size,
+            statistics: Default::default(),
+        }
+    }
+
+    fn young_from_space(&self) -> usize {
+        1 - self.nursery
+    }
+
+    fn is_stage_due(&self) -> bool {
+        self.allocation_limit <= self.allocated_since_stage
+    }
+
+    fn allocate(&mut self, object: Object) -> Reference {
+        let size = object.size();
+        self.nursery_bytes += size;
+        self.allocated_since_stage += size;
+
+        let nursery = &mut self.young[self.nursery];
+        let reference = Reference {
+            space: Space::Young(self.nursery),
+            index: nursery.len(),
+        };
+        nursery.push(Some(object));
+
+        reference
+    }
+
+    fn object(&self, reference: Reference) -> &Object {
+        match reference.space {
+            Space::Young(space) => {
+                if space == self.young_from_space() {
+                    if let Some(old_index) = self.forwarding[reference.index] {
+                        return self.old.get(old_index);
                     }
                 }
-                self.mark_black(g); // remove g from the gray stack
+
+                self.young[space][reference.index]
+                    .as_ref()
+                    .expect("young object was freed while still referenced")
             }
-            self.remove_from_dirty_process_set(p);
+            Space::Old => self.old.get(reference.index),
         }
+    }
 
-        for r in self.nursery.references() {
-            if self.quantum_expired {
-                return;
-            }
-            if self.points_to(r, self.from_space) {
-                self.forward(r);
-            }
+    /// Moves `mutator` to the back of the mutators to scan in the next cycle
+    fn touch(&mut self, mutator: MutatorId) {
+        if let Some(position) = self.touched.iter().position(|id| *id == mutator) {
+            self.touched.remove(position);
         }
 
-        for g in self.gray() {
-            for rf in g.reference_fields() {
-                if self.quantum_expired {
-                    return;
-                }
-                if self.points_to(rf, self.from_space) {
-                    self.forward(rf);
-                }
-            }
-            self.mark_black(g); // remove g from the gray stack
+        self.touched.push_back(mutator);
+    }
+
+    fn touch_if_nursery(&mut self, mutator: MutatorId, reference: Reference) {
+        if reference.space == Space::Young(self.nursery) {
+            self.touch(mutator);
         }
+    }
 
-        self.cycle = Cycle::None;
-    }
-
-    // Incremental major collection
-    // Marks all processes as dirty, collects old generation
-    //
-    // A new page is linked to the old generation and added to the free list to allow
-    // the copying collector of the young generation to finish its current minor collection
-    //
-    // The major collector is a combination of a copying collector and a mark/sweep collector,
-    // the copying part is the same as in the minor collection, copying objects from the young to the old
-    // generation, linking in new pages to the old generation as needed; the old generation itself is collected
-    // by the mark/sweep collector.
-    //
-    // While scanning the root set, reachable objects in the young generation that are not already marked are copied
-    // to the old generation, pushed onto the gray stak and immediately marked as live; reachable objects in the old
-    // generation are the same, except no copying is needed.
-    //
-    // When all roots have been scanned, like in the minor collection, we pop all the gray objects; if the popped object
-    // refers to an object that has not already been marked as live, the referred object is copied if it resides in the
-    // young generation, pushed onto the gray stack and marked
-    //
-    // Because the mutator (which allocates in the nursery) might, in the meantime, have created pointers to the message
-    // area, we also scan the nursery. When all dirty processes have been processed and we are out of gray objects, we proceed
-    // to the sweep phase. In the sweep phase, we simply scan the black map and build a new free-list from the unmarke areas.
-    // If an entire page turns out to be free, we release the page.
-    fn major_gc(&mut self) {
-        match self.next_cycle {
-            Some(Gc::Major) => {
-                self.mark_all_as_dirty();
-                self.clear_blackmap();
-                self.cycle = Gc::Major;
-                self.next_cycle = None;
-                self.fetch_new_page_for_old();
-            },
-            _ => ()
+    fn stage(&mut self, quantum: Quantum) -> Stage {
+        let budget = Budget::new(quantum);
+        self.statistics.stages += 1;
+        self.work = 0;
+
+        if self.cycle.is_none() {
+            self.start_cycle();
         }
 
-        for p in self.dirty_process_set {
-            let roots = self.collect_roots(p);
-            for r in roots {
-                if self.quantum_expired {
-                    return
+        // The dirty mutators in a row that were running when their roots were scanned
+        let mut running = 0;
+
+        let stage = loop {
+            match self.phase {
+                Phase::Marking => {
+                    if let Some(index) = self.gray.pop() {
+                        self.scan(index);
+                        running = 0;
+                    } else if let Some(id) = self.dirty.pop_front() {
+                        if self.scan_roots(id) {
+                            running = 0;
+                        } else {
+                            self.dirty.push_back(id);
+                            running += 1;
+
+                            if self.dirty.len() <= running {
+                                break Stage::Yielded;
+                            }
+                        }
+                    } else {
+                        self.finish_marking();
+
+                        if self.cycle.is_none() {
+                            break Stage::Completed;
+                        }
+                    }
                 }
-                self.copymark(r);
-            }
+                Phase::Sweeping { page } => {
+                    if page < self.old.pages.len() {
+                        self.statistics.objects_freed += self.old.sweep(page);
+                        self.work += PAGE_SLOTS;
+                        self.phase = Phase::Sweeping { page: page + 1 };
+                    } else {
+                        self.finish_cycle();
 
-            for g in self.gray() {
-                for rf in g.reference_fields() {
-                    if self.quantum_expired {
-                        return
+                        break Stage::Completed;
                     }
-                    self.copymark(rf);
                 }
-                self.mark_black(g);
+                Phase::Idle => unreachable!("stage without a cycle"),
             }
-            self.remove_from_dirty_process_set(p)
-        }
 
-        for r in self.nursery.references() {
-            if self.quantum_expired {
-                return;
+            if budget.is_expired(self.work) {
+                break Stage::Yielded;
             }
-            self.copymark(r);
+        };
+
+        self.update_allocation_limit();
+
+        stage
+    }
+
+    fn start_cycle(&mut self) {
+        let cycle = self.next_cycle.take().unwrap_or(Cycle::Minor);
+
+        // Swap the nursery and the from space
+        self.nursery = self.young_from_space();
+        self.nursery_bytes = 0;
+        self.forwarding = vec![None; self.young[self.young_from_space()].len()];
+
+        let touched = mem::take(&mut self.touched);
+
+        if cycle == Cycle::Major {
+            // Oldest first, but the recently busy ones last
+            let mut ids: Vec<MutatorId> = self
+                .mutators
+                .keys()
+                .filter(|id| !touched.contains(*id))
+                .copied()
+                .collect();
+            ids.sort();
+
+            self.dirty.extend(ids);
+            self.old.clear_marks();
+            self.old.fetch_new_page();
+        }
+
+        self.dirty.extend(touched);
+        self.cycle = Some(cycle);
+        self.phase = Phase::Marking;
+    }
+
+    /// Copies `reference`'s object out of the from space, or, in the marking of a major cycle,
+    /// marks it in the old generation.  Returns where the object is now.
+    fn copymark(&mut self, reference: Reference) -> Reference {
+        if self.phase != Phase::Marking {
+            return reference;
         }
 
-        for g in self.gray() {
-            for rf in g.reference_fields() {
-                if self.quantum_expired {
-                    return
+        match reference.space {
+            Space::Young(space) if space == self.young_from_space() => {
+                self.forward(reference.index)
+            }
+            Space::Old if self.cycle == Some(Cycle::Major) => {
+                if self.old.mark(reference.index) {
+                    self.gray.push(reference.index);
                 }
-                self.copymark(rf);
+
+                reference
             }
-            self.mark_black(g);
+            _ => reference,
+        }
+    }
+
+    /// Copies the from space object at `index` to the old generation, unless it already was
+    fn forward(&mut self, index: usize) -> Reference {
+        if let Some(old_index) = self.forwarding[index] {
+            return Reference::old(old_index);
         }
 
-        self.sweep(); // traverse the black map and build a free-list of the unmarked areas
-        self.cycle = Cycle::None;
+        let from_space = self.young_from_space();
+        let object = self.young[from_space][index]
+            .take()
+            .expect("from space object was freed while still referenced");
+        let words = object.words();
+        let (old_index, fetched_page) = self.old.allocate(object);
+
+        if fetched_page && self.cycle != Some(Cycle::Major) {
+            self.next_cycle = Some(Cycle::Major);
+        }
+
+        if self.cycle == Some(Cycle::Major) {
+            self.old.mark(old_index);
+        }
+
+        self.forwarding[index] = Some(old_index);
+        self.gray.push(old_index);
+        self.work += words;
+        self.statistics.words_copied += words;
+
+        Reference::old(old_index)
+    }
+
+    /// Copies or marks the objects referenced by the old object at `index`
+    fn scan(&mut self, index: usize) {
+        let mut references = mem::take(&mut self.old.get_mut(index).references);
+
+        for reference in references.iter_mut() {
+            *reference = self.copymark(*reference);
+        }
+
+        self.work += 1 + references.len();
+        self.old.get_mut(index).references = references;
+    }
+
+    /// Returns `false` if the mutator was running, so its roots couldn't be scanned
+    fn scan_roots(&mut self, id: MutatorId) -> bool {
+        let mutator = match self.mutators.get(&id).and_then(Weak::upgrade) {
+            Some(mutator) => mutator,
+            // The mutator died before its roots needed to be scanned
+            None => {
+                self.mutators.remove(&id);
+
+                return true;
+            }
+        };
+        let mut roots_len = 0;
+
+        let scanned = mutator.roots(&mut |reference| {
+            *reference = self.copymark(*reference);
+            roots_len += 1;
+        });
+
+        self.work += 1 + roots_len;
+
+        scanned
     }
 
-    fn swap(&mut self) {
-        let swap = self.nursery;
-        self.nursery = self.from_space;
-        self.from_space = swap;
+    fn finish_marking(&mut self) {
+        // Everything in the from space that is still referenced has been copied
+        let from_space = self.young_from_space();
+        let freed = self.young[from_space]
+            .drain(..)
+            .filter(|object| object.is_some())
+            .count();
+        self.forwarding.clear();
+        self.statistics.objects_freed += freed;
+
+        match self.cycle {
+            Some(Cycle::Major) => self.phase = Phase::Sweeping { page: 0 },
+            _ => self.finish_cycle(),
+        }
     }
 
-    fn clear_forwarding_area(&mut self) {
+    fn finish_cycle(&mut self) {
+        match self.cycle.take() {
+            Some(Cycle::Major) => self.statistics.major_cycles += 1,
+            _ => self.statistics.minor_cycles += 1,
+        }
+
+        self.phase = Phase::Idle;
     }
 
+    /// How much mutators may allocate before the next stage
     fn update_allocation_limit(&mut self) {
+        self.allocated_since_stage = 0;
+        self.allocation_limit = if self.cycle.is_some() {
+            (self.nursery_size / STAGE_ALLOCATION_DIVISOR).max(1)
+        } else {
+            self.nursery_size.saturating_sub(self.nursery_bytes).max(1)
+        };
+    }
+}
+
+#[derive(Default)]
+struct OldGeneration {
+    /// Released pages are `None` until they are fetched again
+    pages: Vec<Option<Vec<Option<Object>>>>,
+    /// Slots that were freed by sweeping
+    free: Vec<usize>,
+    /// The slots that are allocated by bumping, before using the free list
+    bump: usize,
+    bump_end: usize,
+    /// The black map, one bit per slot
+    marks: Vec<u64>,
+}
+
+impl OldGeneration {
+    fn get(&self, index: usize) -> &Object {
+        self.pages[index / PAGE_SLOTS].as_ref().unwrap()[index % PAGE_SLOTS]
+            .as_ref()
+            .expect("old object was freed while still referenced")
+    }
+
+    fn get_mut(&mut self, index: usize) -> &mut Object {
+        self.pages[index / PAGE_SLOTS].as_mut().unwrap()[index % PAGE_SLOTS]
+            .as_mut()
+            .expect("old object was freed while still referenced")
     }
 
-    fn copymark(&mut self, r) {
-        if self.points_to(r, self.from_space) {
-            self.forward(r);
-            self.mark_blackmap(r);
-        } else if self.points_to(r, self.old_generation) {
-            match self.color(r) {
-                Tricolor::White => {
-                    self.push_gray(r);
-                    self.mark_blackmap(r);
-                },
-                _ => ()
+    fn count(&self) -> usize {
+        self.pages
+            .iter()
+            .flatten()
+            .map(|page| page.iter().filter(|slot| slot.is_some()).count())
+            .sum()
+    }
+
+    fn page_count(&self) -> usize {
+        self.pages.iter().filter(|page| page.is_some()).count()
+    }
+
+    /// Returns the slot index of `object` and whether a page had to be fetched for it
+    fn allocate(&mut self, object: Object) -> (usize, bool) {
+        let mut fetched_page = false;
+
+        let index = if self.bump < self.bump_end {
+            self.bump += 1;
+
+            self.bump - 1
+        } else if let Some(index) = self.free.pop() {
+            index
+        } else {
+            self.fetch_new_page();
+            fetched_page = true;
+            self.bump += 1;
+
+            self.bump - 1
+        };
+
+        self.pages[index / PAGE_SLOTS].as_mut().unwrap()[index % PAGE_SLOTS] = Some(object);
+
+        (index, fetched_page)
+    }
+
+    /// Starts allocating by bumping in a new page
+    fn fetch_new_page(&mut self) {
+        let page = match self.pages.iter().position(|page| page.is_none()) {
+            Some(page) => page,
+            None => {
+                self.pages.push(None);
+                self.marks.resize(self.pages.len() * PAGE_SLOTS / 64, 0);
+
+                self.pages.len() - 1
             }
+        };
+
+        self.pages[page] = Some((0..PAGE_SLOTS).map(|_| None).collect());
+        self.bump = page * PAGE_SLOTS;
+        self.bump_end = self.bump + PAGE_SLOTS;
+    }
+
+    /// Marks the slot at `index`.  Returns whether it was unmarked.
+    fn mark(&mut self, index: usize) -> bool {
+        let bit = 1 << (index % 64);
+        let word = &mut self.marks[index / 64];
+        let unmarked = *word & bit == 0;
+        *word |= bit;
+
+        unmarked
+    }
+
+    fn is_marked(&self, index: usize) -> bool {
+        self.marks[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn clear_marks(&mut self) {
+        for word in self.marks.iter_mut() {
+            *word = 0;
         }
     }
 
-    // Copies objects to the old generation
-    // If allocation in the old generation fails, we set the next cycle to Major
-    fn copy_object(&self, obj) -> copy {
-        if self.available_in_old < sizeof(obj) {
-            self.allocate_new_page();
-            match self.cycle {
-                Cycle::Major => (),
-                _ => self.next_cycle = Some(Cycle::Major)
+    /// Frees the unmarked objects of `page`, releasing it if it ends up empty.  Returns the number
+    /// of objects freed.
+    fn sweep(&mut self, page: usize) -> usize {
+        let start = page * PAGE_SLOTS;
+        let end = start + PAGE_SLOTS;
+
+        if self.pages[page].is_none() {
+            return 0;
+        }
+
+        let mut freed = 0;
+
+        for index in start..end {
+            let is_marked = self.is_marked(index);
+            let slot = &mut self.pages[page].as_mut().unwrap()[index - start];
+
+            if slot.is_some() && !is_marked {
+                *slot = None;
+                self.free.push(index);
+                freed += 1;
             }
         }
-        self.copy_to_old(obj)
-    }
 
-    // Forwards objects to the old generation, if not already forwarded, it is
-    // copied to the old generation and pushed onto the gray stack
-    fn forward(&mut self, obj) {
-        if self.is_forwarded(obj) {
-            self.update_source_ref(obj);
-        } else {
-            let destination = self.copy_object(obj);
-            self.push_gray(destination.clone());
-            self.update_source_ref(obj);
-            self.set_fowarding_pointer(obj, destination);
+        let is_empty = self.pages[page]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|slot| slot.is_none());
+        // The bump page is kept so that copying doesn't have to fetch it again
+        let is_bump_page = self.bump_end == end;
+
+        if is_empty && !is_bump_page {
+            self.pages[page] = None;
+            self.free.retain(|index| !(start <= *index && *index < end));
         }
+
+        freed
     }
 }
@@ -0,0 +1,369 @@
+use super::*;
+
+use std::collections::HashSet;
+use std::sync::Barrier;
+
+#[test]
+fn minor_cycle_frees_unreachable_objects_and_keeps_reachable_bytes() {
+    let area = SharedArea::new(Default::default());
+    let mutator = Arc::new(TestMutator::default());
+    let id = area.register(&mutator);
+
+    let kept = area.allocate(id, Kind::Binary, b"kept", &[]);
+    mutator.push(kept, 0);
+    area.allocate(id, Kind::Binary, b"garbage", &[]);
+
+    assert_eq!(area.object_count(), 2);
+
+    area.collect();
+
+    assert_eq!(area.object_count(), 1);
+    assert_eq!(area.statistics().minor_cycles, 1);
+
+    let kept = mutator.get(0);
+
+    assert_eq!(area.kind(kept), Kind::Binary);
+    assert!(area.with_bytes(kept, |bytes| bytes == b"kept"));
+}
+
+#[test]
+fn objects_referenced_only_by_other_objects_survive() {
+    let area = SharedArea::new(Default::default());
+    let mutator = Arc::new(TestMutator::default());
+    let id = area.register(&mutator);
+
+    let element = area.allocate(id, Kind::Literal, b"element", &[]);
+    let fragment = area.allocate(id, Kind::MessageFragment, b"fragment", &[element]);
+    mutator.push(fragment, 0);
+
+    area.collect();
+    area.request_major();
+    area.collect();
+
+    let fragment = mutator.get(0);
+    let element = area.load(id, fragment, 0);
+
+    assert_eq!(area.references_len(fragment), 1);
+    assert_eq!(area.kind(element), Kind::Literal);
+    assert!(area.with_bytes(element, |bytes| bytes == b"element"));
+}
+
+#[test]
+fn major_cycle_frees_old_objects_and_releases_pages() {
+    let area = SharedArea::new(Default::default());
+    let mutator = Arc::new(TestMutator::default());
+    let id = area.register(&mutator);
+
+    for value in 0..(3 * PAGE_SLOTS as u64) {
+        let reference = area.allocate(id, Kind::Binary, &value.to_le_bytes(), &[]);
+        mutator.push(reference, value);
+    }
+
+    area.collect();
+
+    assert_eq!(area.object_count(), 3 * PAGE_SLOTS);
+    assert_eq!(area.statistics().pages, 3);
+
+    mutator.truncate(1);
+    area.request_major();
+    area.collect();
+
+    let statistics = area.statistics();
+
+    assert_eq!(statistics.major_cycles, 1);
+    assert_eq!(area.object_count(), 1);
+    assert!(statistics.pages < 3);
+    mutator.assert_bytes(&area);
+}
+
+#[test]
+fn with_work_quantum_stages_yield_between_mutator_operations() {
+    let area = SharedArea::new(Options {
+        quantum: Quantum::Work(1),
+        nursery_size: DEFAULT_NURSERY_SIZE,
+    });
+    let sender = Arc::new(TestMutator::default());
+    let sender_id = area.register(&sender);
+    let receiver = Arc::new(TestMutator::default());
+    let receiver_id = area.register(&receiver);
+
+    for value in 0..64_u64 {
+        let reference = area.allocate(sender_id, Kind::Binary, &value.to_le_bytes(), &[]);
+        sender.push(reference, value);
+    }
+
+    area.request_major();
+
+    assert_eq!(area.stage(), Stage::Yielded);
+    assert!(area.is_collecting());
+
+    let mut stages = 1;
+
+    while area.stage() == Stage::Yielded {
+        let (reference, value) = match sender.pop() {
+            Some(popped) => popped,
+            None => continue,
+        };
+        let sent = area.send(receiver_id, reference);
+        receiver.push(sent, value);
+
+        let fragment = area.allocate(
+            receiver_id,
+            Kind::MessageFragment,
+            &value.to_le_bytes(),
+            &[sent],
+        );
+        let loaded = area.load(receiver_id, fragment, 0);
+
+        assert!(area.with_bytes(loaded, |bytes| bytes == value.to_le_bytes()));
+
+        receiver.push(fragment, value);
+        stages += 1;
+    }
+
+    assert!(1 < stages);
+    assert!(!area.is_collecting());
+    sender.assert_bytes(&area);
+    receiver.assert_bytes(&area);
+
+    area.collect();
+    area.request_major();
+    area.collect();
+
+    sender.assert_bytes(&area);
+    receiver.assert_bytes(&area);
+}
+
+#[test]
+fn dead_mutators_roots_are_not_scanned() {
+    let area = SharedArea::new(Default::default());
+    let mutator = Arc::new(TestMutator::default());
+    let id = area.register(&mutator);
+
+    let reference = area.allocate(id, Kind::Binary, b"dead", &[]);
+    mutator.push(reference, 0);
+
+    std::mem::drop(mutator);
+    area.collect();
+
+    assert_eq!(area.object_count(), 0);
+}
+
+#[test]
+fn stress_with_time_quantum() {
+    stress(Quantum::Time(Duration::from_micros(50)));
+}
+
+#[test]
+fn stress_with_work_quantum() {
+    stress(Quantum::Work(16));
+}
+
+const STRESS_MUTATORS: usize = 4;
+const STRESS_ITERATIONS: u64 = 2_000;
+
+/// Interleaves mutators that allocate, send to each other, load and drop references, with a
+/// collector that runs stages as often as it can, checking that every reference still reaches its
+/// bytes.
+fn stress(quantum: Quantum) {
+    let area = Arc::new(SharedArea::new(Options {
+        quantum,
+        nursery_size: 4096,
+    }));
+    let mutators: Vec<Arc<TestMutator>> = (0..STRESS_MUTATORS)
+        .map(|_| Arc::new(TestMutator::default()))
+        .collect();
+    let ids: Vec<MutatorId> = mutators
+        .iter()
+        .map(|mutator| area.register(mutator))
+        .collect();
+    let stop = Arc::new(AtomicBool::new(false));
+    let barrier = Arc::new(Barrier::new(STRESS_MUTATORS + 1));
+
+    let collector = {
+        let area = area.clone();
+        let stop = stop.clone();
+        let barrier = barrier.clone();
+
+        thread::spawn(move || {
+            barrier.wait();
+
+            let mut cycles = 0;
+
+            while !stop.load(Ordering::SeqCst) {
+                if area.stage() == Stage::Completed {
+                    cycles += 1;
+
+                    if cycles % 4 == 0 {
+                        area.request_major();
+                    }
+                }
+            }
+        })
+    };
+
+    let threads: Vec<JoinHandle<()>> = (0..STRESS_MUTATORS)
+        .map(|index| {
+            let area = area.clone();
+            let mutators = mutators.clone();
+            let ids = ids.clone();
+            let barrier = barrier.clone();
+
+            thread::spawn(move || {
+                let mutator = &mutators[index];
+                let id = ids[index];
+                let next = (index + 1) % STRESS_MUTATORS;
+
+                barrier.wait();
+
+                for iteration in 0..STRESS_ITERATIONS {
+                    // Holding the roots marks the mutator as running, so they can't be scanned
+                    // while references are held outside of them
+                    let mut roots = mutator.roots.lock();
+                    roots.extend(mutator.mailbox.lock().drain(..));
+
+                    let value = (index as u64) << 32 | iteration;
+                    let references: Vec<Reference> = roots
+                        .last()
+                        .map(|(reference, _)| *reference)
+                        .into_iter()
+                        .collect();
+                    let reference =
+                        area.allocate(id, Kind::Binary, &value.to_le_bytes(), &references);
+                    roots.push((reference, value));
+
+                    match iteration % 4 {
+                        0 => {
+                            let (reference, value) = roots.pop().unwrap();
+                            let mut mailbox = mutators[next].mailbox.lock();
+                            let sent = area.send(ids[next], reference);
+                            mailbox.push((sent, value));
+                        }
+                        1 => {
+                            if 0 < area.references_len(reference) {
+                                let loaded = area.load(id, reference, 0);
+                                let loaded_value = area.with_bytes(loaded, value_from_bytes);
+                                roots.push((loaded, loaded_value));
+                            }
+                        }
+                        2 => {
+                            if 32 < roots.len() {
+                                roots.drain(..16);
+                            }
+                        }
+                        _ => (),
+                    }
+
+                    if iteration % 64 == 0 {
+                        for (reference, value) in roots.iter() {
+                            assert_eq!(area.with_bytes(*reference, value_from_bytes), *value);
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    stop.store(true, Ordering::SeqCst);
+    collector.join().unwrap();
+
+    area.collect();
+    area.request_major();
+    area.collect();
+
+    // After a major cycle, only the objects reachable from the mutators are left
+    let mut reachable = HashSet::new();
+
+    for (mutator, id) in mutators.iter().zip(ids.iter()) {
+        mutator.assert_bytes(&area);
+
+        let mut unvisited = mutator.references();
+
+        while let Some(reference) = unvisited.pop() {
+            if reachable.insert(reference) {
+                for index in 0..area.references_len(reference) {
+                    unvisited.push(area.load(*id, reference, index));
+                }
+            }
+        }
+    }
+
+    let statistics = area.statistics();
+
+    assert!(0 < statistics.minor_cycles);
+    assert!(0 < statistics.major_cycles);
+    assert!(0 < statistics.objects_freed);
+    assert_eq!(area.object_count(), reachable.len());
+}
+
+fn value_from_bytes(bytes: &[u8]) -> u64 {
+    let mut array = [0; 8];
+    array.copy_from_slice(bytes);
+
+    u64::from_le_bytes(array)
+}
+
+/// Holds references along with the value their bytes must encode
+#[derive(Default)]
+struct TestMutator {
+    roots: Mutex<Vec<(Reference, u64)>>,
+    /// References sent by other mutators, which are moved to `roots` when the mutator next runs
+    mailbox: Mutex<Vec<(Reference, u64)>>,
+}
+
+impl TestMutator {
+    fn push(&self, reference: Reference, value: u64) {
+        self.roots.lock().push((reference, value));
+    }
+
+    fn pop(&self) -> Option<(Reference, u64)> {
+        self.roots.lock().pop()
+    }
+
+    fn get(&self, index: usize) -> Reference {
+        self.roots.lock()[index].0
+    }
+
+    fn references(&self) -> Vec<Reference> {
+        let roots = self.roots.lock();
+        let mailbox = self.mailbox.lock();
+
+        roots
+            .iter()
+            .chain(mailbox.iter())
+            .map(|(reference, _)| *reference)
+            .collect()
+    }
+
+    fn truncate(&self, len: usize) {
+        self.roots.lock().truncate(len);
+    }
+
+    fn assert_bytes(&self, area: &SharedArea) {
+        let roots = self.roots.lock();
+        let mailbox = self.mailbox.lock();
+
+        for (reference, value) in roots.iter().chain(mailbox.iter()) {
+            assert_eq!(area.with_bytes(*reference, value_from_bytes), *value);
+        }
+    }
+}
+
+impl Mutator for TestMutator {
+    fn roots(&self, update: &mut dyn FnMut(&mut Reference)) -> bool {
+        match (self.roots.try_lock(), self.mailbox.try_lock()) {
+            (Some(mut roots), Some(mut mailbox)) => {
+                for (reference, _) in roots.iter_mut().chain(mailbox.iter_mut()) {
+                    update(reference);
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -2,9 +2,15 @@ use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use clap::{App, AppSettings, Arg, SubCommand};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+use lumen_rt_core::process::spawn::options::{Defaults, MaxHeapSize};
+
+use crate::profiler;
+use crate::sys::host::cpus;
 
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
 //TODO: Needs to be HashMap<Atom, HashMap<Atom, Term>>
@@ -51,12 +57,29 @@ pub struct Config {
     pub debug: bool,
     pub name: Option<String>,
     pub cookie: Option<String>,
+    /// The number of schedulers, which defaults to one per logical core
+    pub schedulers: usize,
+    /// Where to write the folded stacks of the profiler, which runs from startup when set
+    pub profile: Option<PathBuf>,
+    pub profile_interval: Duration,
+    /// The memory options of processes spawned without them
+    pub spawn_defaults: Defaults,
     pub command: Command,
     pub extra: Vec<String>,
 }
 
 impl Config {
     pub fn from_argv(app: String, version: String, argv: Vec<String>) -> ConfigResult<Config> {
+        // `erl` style `+S Schedulers` and `+h*` heap flags
+        let argv = argv.into_iter().map(|arg| match arg.as_str() {
+            "+S" => "--schedulers".to_string(),
+            "+hms" => "--min-heap-size".to_string(),
+            "+hmbs" => "--min-bin-vheap-size".to_string(),
+            "+hmax" => "--max-heap-size".to_string(),
+            "+hmaxk" => "--max-heap-size-kill".to_string(),
+            "+hmaxel" => "--max-heap-size-error-logger".to_string(),
+            _ => arg,
+        });
         let matches = App::new(app)
             .version(version.as_str())
             .setting(AppSettings::TrailingVarArg)
@@ -92,6 +115,63 @@ impl Config {
                      .help("The secret cookie to use in distributed mode")
                      .takes_value(true)
                      .env("COOKIE"))
+            .arg(Arg::with_name("schedulers")
+                     .long("schedulers")
+                     .help("The number of schedulers to run, like `erl +S`\n\
+                            Defaults to the number of logical cores")
+                     .takes_value(true)
+                     .validator(is_valid_scheduler_count))
+            .arg(Arg::with_name("profile")
+                     .long("profile")
+                     .help("Sample the running processes from startup until shutdown, writing the\n\
+                            samples as folded stacks to the given path and a report of each\n\
+                            process's reductions and garbage collections next to it")
+                     .takes_value(true))
+            .arg(Arg::with_name("profile_interval")
+                     .long("profile-interval")
+                     .help("The microseconds between samples when profiling\n\
+                            Defaults to 1000")
+                     .takes_value(true)
+                     .validator(is_valid_profile_interval))
+            .arg(Arg::with_name("fullsweep_after")
+                     .long("fullsweep-after")
+                     .help("The number of minor collections before a full sweep, like\n\
+                            `ERL_FULLSWEEP_AFTER`\n\
+                            Defaults to 65535")
+                     .takes_value(true)
+                     .validator(is_valid_words))
+            .arg(Arg::with_name("min_heap_size")
+                     .long("min-heap-size")
+                     .help("The minimum heap size in words of processes, like `erl +hms`\n\
+                            Defaults to 233")
+                     .takes_value(true)
+                     .validator(is_valid_words))
+            .arg(Arg::with_name("min_bin_vheap_size")
+                     .long("min-bin-vheap-size")
+                     .help("The minimum virtual binary heap size in words of processes, like\n\
+                            `erl +hmbs`")
+                     .takes_value(true)
+                     .validator(is_valid_words))
+            .arg(Arg::with_name("max_heap_size")
+                     .long("max-heap-size")
+                     .help("The maximum heap size in words of processes, like `erl +hmax`\n\
+                            Defaults to 0, which is no maximum")
+                     .takes_value(true)
+                     .validator(is_valid_words))
+            .arg(Arg::with_name("max_heap_size_kill")
+                     .long("max-heap-size-kill")
+                     .help("Whether processes are killed when they exceed the maximum heap size,\n\
+                            like `erl +hmaxk`\n\
+                            Defaults to true")
+                     .takes_value(true)
+                     .possible_values(&["true", "false"]))
+            .arg(Arg::with_name("max_heap_size_error_logger")
+                     .long("max-heap-size-error-logger")
+                     .help("Whether to log when processes exceed the maximum heap size, like\n\
+                            `erl +hmaxel`\n\
+                            Defaults to true")
+                     .takes_value(true)
+                     .possible_values(&["true", "false"]))
             .arg(Arg::with_name("extra")
                      .last(true)
                      .multiple(true)
@@ -132,6 +212,15 @@ impl Config {
             debug: matches.is_present("debug"),
             name: matches.value_of("name").map(|v| v.to_string()),
             cookie: matches.value_of("cookie").map(|v| v.to_string()),
+            schedulers: matches
+                .value_of("schedulers")
+                .map(|v| parse_scheduler_count(v).unwrap())
+                .unwrap_or_else(cpus::num_logical),
+            profile: matches.value_of_os("profile").map(PathBuf::from),
+            profile_interval: matches
+                .value_of("profile_interval")
+                .map(|v| parse_profile_interval(v).unwrap())
+                .unwrap_or(profiler::DEFAULT_INTERVAL),
             command,
             extra: extra.iter().map(|v| v.to_string()).collect(),
         })
@@ -143,6 +232,59 @@ fn is_valid_node_name(_f: String) -> Result<(), String> {
     Ok(())
 }
 
+fn is_valid_scheduler_count(v: String) -> Result<(), String> {
+    parse_scheduler_count(&v).map(|_| ())
+}
+
+/// Parses `Schedulers` or `erl`'s `Schedulers:SchedulersOnline`, where all schedulers are online
+fn parse_scheduler_count(v: &str) -> Result<usize, String> {
+    let schedulers = v.split(':').next().unwrap_or(v);
+
+    match schedulers.parse::<usize>() {
+        Ok(count) if 0 < count => Ok(count),
+        _ => Err(format!("{} is not a positive number of schedulers", v)),
+    }
+}
+
+fn is_valid_profile_interval(v: String) -> Result<(), String> {
+    parse_profile_interval(&v).map(|_| ())
+}
+
+fn parse_profile_interval(v: &str) -> Result<Duration, String> {
+    match v.parse::<u64>() {
+        Ok(micros) if 0 < micros => Ok(Duration::from_micros(micros)),
+        _ => Err(format!("{} is not a positive number of microseconds", v)),
+    }
+}
+
+fn is_valid_words(v: String) -> Result<(), String> {
+    parse_words(&v).map(|_| ())
+}
+
+fn parse_words(v: &str) -> Result<usize, String> {
+    v.parse::<usize>()
+        .map_err(|_| format!("{} is not a non-negative number of words", v))
+}
+
+/// `None` unless at least one of the `max_heap_size` flags is given
+fn parse_max_heap_size(matches: &ArgMatches) -> Option<MaxHeapSize> {
+    let max_heap_size = MaxHeapSize {
+        size: matches
+            .value_of("max_heap_size")
+            .map(|v| parse_words(v).unwrap()),
+        kill: matches.value_of("max_heap_size_kill").map(|v| v == "true"),
+        error_logger: matches
+            .value_of("max_heap_size_error_logger")
+            .map(|v| v == "true"),
+    };
+
+    if max_heap_size == Default::default() {
+        None
+    } else {
+        Some(max_heap_size)
+    }
+}
+
 fn with_file<T>(v: Option<&OsStr>, default: T, fun: fn(String) -> T) -> ConfigResult<T> {
     match v {
         None => Ok(default),
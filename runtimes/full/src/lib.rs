@@ -29,16 +29,16 @@
 #![feature(alloc_layout_extra)]
 #![feature(termination_trait_lib)]
 
-extern crate alloc;
 extern crate cfg_if;
 
 extern crate chrono;
 
 pub use lumen_rt_core::{
-    binary_to_string, context, distribution, future, proplist, registry, send, stacktrace, time,
-    timer,
+    application, binary_to_string, context, distribution, ets, future, host, profiler, proplist,
+    registry, send, stacktrace, time, timer, trace,
 };
 
+pub mod alloc;
 #[cfg(not(any(test, target_arch = "wasm32")))]
 mod config;
 mod logging;
@@ -74,10 +74,11 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
     use self::sys::break_handler::{self, Signal};
     use bus::Bus;
     use log::Level;
+    use lumen_rt_core::scheduler::Scheduler as _;
     use std::thread;
 
     // Load system configuration
-    let _config = match Config::from_argv(name.to_string(), version.to_string(), argv) {
+    let config = match Config::from_argv(name.to_string(), version.to_string(), argv) {
         Ok(config) => config,
         Err(err) => {
             eprintln!("Config error: {}", err);
@@ -95,7 +96,47 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
     // Start logger
     Logger::init(Level::Info).expect("Unexpected failure initializing logger");
 
-    let scheduler = scheduler::current();
+    // Start distribution, so that other nodes can connect
+    if let Some(node_name) = &config.name {
+        let started = distribution_cookie(config.cookie.as_ref())
+            .and_then(|cookie| distribution::start(node_name, &cookie, distribution::epmd::port()));
+
+        if let Err(err) = started {
+            eprintln!("Distribution error: {:#}", err);
+            return Err(());
+        }
+    }
+
+    // Set before the scheduler spawns any processes
+    lumen_rt_core::process::spawn::options::set_defaults(config.spawn_defaults);
+
+    // This thread runs the first scheduler, so that it can also check for signals
+    let pool = match scheduler::start(config.schedulers) {
+        Ok(pool) => pool,
+        Err(err) => {
+            eprintln!("Scheduler error: {}", err);
+            return Err(());
+        }
+    };
+
+    if let Some(path) = &config.profile {
+        let options = profiler::Options {
+            path: path.clone(),
+            interval: config.profile_interval,
+            ..Default::default()
+        };
+
+        if let Err(err) = profiler::start(options) {
+            eprintln!("Profiler error: {:#}", anyhow::Error::new(err));
+            return Err(());
+        }
+    }
+
+    let arc_dyn_scheduler = scheduler::current();
+    let scheduler = arc_dyn_scheduler
+        .as_any()
+        .downcast_ref::<scheduler::Scheduler>()
+        .unwrap();
     loop {
         // Run the scheduler for a cycle
         let scheduled = scheduler.run_once();
@@ -104,6 +145,12 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
             match sig {
                 // For now, SIGINT initiates a controlled shutdown
                 Signal::INT => {
+                    pool.stop();
+
+                    if config.profile.is_some() {
+                        stop_profiler();
+                    }
+
                     // If an error occurs, report it before shutdown
                     if let Err(err) = scheduler.shutdown() {
                         eprintln!("System error: {}", err);
@@ -124,9 +171,10 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
                 _ => (),
             }
         }
-        // If the scheduler scheduled a process this cycle, then we're busy
-        // and should keep working until we have an idle period
-        if scheduled {
+        // If the scheduler scheduled a process this cycle, or could steal one
+        // from another scheduler, then we're busy and should keep working
+        // until we have an idle period
+        if scheduled || scheduler.steal() {
             continue;
         }
         // Otherwise,
@@ -145,3 +193,49 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
 
     Ok(())
 }
+
+/// Writes the profile started by `--profile`, unless `eprof:stop_profiling/0` already did
+#[cfg(not(any(test, target_arch = "wasm32")))]
+fn stop_profiler() {
+    match profiler::stop() {
+        Ok(path) => eprintln!("Profile written to {}", path.display()),
+        Err(profiler::ProfilerError::NotProfiling) => (),
+        Err(err) => eprintln!("Profiler error: {:#}", anyhow::Error::new(err)),
+    }
+}
+
+/// The cookie from `--cookie` or `~/.erlang.cookie`, which is generated if it does not exist
+#[cfg(not(any(test, target_arch = "wasm32")))]
+fn distribution_cookie(cookie: Option<&String>) -> anyhow::Result<String> {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    use anyhow::Context;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    if let Some(cookie) = cookie {
+        return Ok(cookie.clone());
+    }
+
+    let home = std::env::var_os("HOME").context("HOME is not set to find ~/.erlang.cookie")?;
+    let path: PathBuf = [home, ".erlang.cookie".into()].iter().collect();
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(contents.trim().to_string()),
+        Err(_) => {
+            // Like `erl`, 20 uppercase letters
+            let mut rng = OsRng::new().context("could not generate cookie")?;
+            let cookie: String = (0..20)
+                .map(|_| (b'A' + rng.gen_range(0, 26)) as char)
+                .collect();
+
+            fs::write(&path, &cookie)
+                .with_context(|| format!("could not write {}", path.display()))?;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o400))?;
+
+            Ok(cookie)
+        }
+    }
+}
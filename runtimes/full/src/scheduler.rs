@@ -4,23 +4,31 @@ pub mod test;
 use std::any::Any;
 use std::convert::TryInto;
 use std::fmt::{self, Debug};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::thread::{self, JoinHandle, Thread};
+use std::time::Duration;
 
 use liblumen_core::locks::RwLock;
 
 use liblumen_alloc::erts::exception::SystemException;
-use liblumen_alloc::erts::process::{Priority, Process, Status};
+use liblumen_alloc::erts::process::gc::GcError;
+use liblumen_alloc::erts::process::{Priority, Process, ProcessFlags, Status};
 pub use liblumen_alloc::erts::scheduler::{id, ID};
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::Ran;
 
 use lumen_rt_core::process::{log_exit, propagate_exit, CURRENT_PROCESS};
+use lumen_rt_core::profiler;
 use lumen_rt_core::registry::put_pid_to_process;
 pub use lumen_rt_core::scheduler::{
-    current, from_id, run_through, Scheduled, SchedulerDependentAlloc, Spawned,
+    all, context_switches, current, from_id, run_through, Scheduled, SchedulerDependentAlloc,
+    Spawned,
+};
+use lumen_rt_core::scheduler::{
+    count_context_switch, run_queue, steal_runnable, unregister, Run, Scheduler as SchedulerTrait,
 };
-use lumen_rt_core::scheduler::{run_queue, unregister, Run, Scheduler as SchedulerTrait};
 use lumen_rt_core::timer::Hierarchy;
 
 use crate::process;
@@ -33,9 +41,58 @@ fn unregistered() -> Arc<dyn lumen_rt_core::scheduler::Scheduler> {
         reference_count: AtomicU64::new(0),
         run_queues: Default::default(),
         unique_integer: AtomicU64::new(0),
+        parked: AtomicBool::new(false),
+        // `unregistered` is called when the thread-local scheduler is first used, so this is the
+        // thread that runs the scheduler.
+        thread: thread::current(),
     })
 }
 
+/// Starts `count` schedulers: the calling thread's scheduler, which the caller runs, and
+/// `count - 1` threads that each run their own scheduler until [Pool::stop].
+pub fn start(count: usize) -> std::io::Result<Pool> {
+    let mut threads = Vec::with_capacity(count.saturating_sub(1));
+
+    for number in 2..=count {
+        let join_handle = thread::Builder::new()
+            .name(format!("scheduler {}", number))
+            .spawn(|| {
+                let arc_dyn_scheduler = current();
+                let scheduler = arc_dyn_scheduler
+                    .as_any()
+                    .downcast_ref::<Scheduler>()
+                    .unwrap();
+
+                scheduler.run();
+            })?;
+
+        threads.push(join_handle);
+    }
+
+    Ok(Pool { threads })
+}
+
+/// The threads started by [start]
+pub struct Pool {
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Pool {
+    /// Stops the scheduler threads after they finish running their current process.
+    pub fn stop(self) {
+        SHUTTING_DOWN.store(true, Ordering::SeqCst);
+
+        for join_handle in &self.threads {
+            join_handle.thread().unpark();
+        }
+
+        for join_handle in self.threads {
+            // A scheduler thread that panicked has already reported its panic
+            let _ = join_handle.join();
+        }
+    }
+}
+
 pub struct Scheduler {
     pub id: ID,
     pub hierarchy: RwLock<Hierarchy>,
@@ -45,6 +102,9 @@ pub struct Scheduler {
     // Non-monotonic unique integers are scoped to the scheduler ID and then use this per-scheduler
     // `u64`.
     unique_integer: AtomicU64,
+    // Whether `thread` is parked, or about to park, because it has nothing to run or steal
+    parked: AtomicBool,
+    thread: Thread,
 }
 
 impl Scheduler {
@@ -58,15 +118,83 @@ impl Scheduler {
     /// > 8. Pick a process to execute
     /// > -- [The Scheduler Loop](https://blog.stenmans.org/theBeamBook/#_the_scheduler_loop)
     pub fn run(&self) {
-        loop {
-            // TODO sleep or steal if nothing run
-            let _ = self.run_once();
+        while !SHUTTING_DOWN.load(Ordering::SeqCst) {
+            if self.run_once() || self.steal() {
+                continue;
+            }
+
+            // Marked as parked before running or stealing one last time, so that a process made
+            // runnable after that unparks this scheduler instead of waiting for it.
+            self.parked.store(true, Ordering::SeqCst);
+            PARKED_COUNT.fetch_add(1, Ordering::SeqCst);
+
+            if !(self.run_once() || self.steal()) {
+                // Parked until a process becomes runnable on this scheduler or can be stolen from
+                // another, but only for as long as the next timer can wait to be timed out.
+                let milliseconds_until_next_timeout =
+                    self.hierarchy.read().milliseconds_until_next_timeout();
+
+                match milliseconds_until_next_timeout {
+                    Some(milliseconds) => thread::park_timeout(Duration::from_millis(milliseconds)),
+                    None => thread::park(),
+                }
+            }
+
+            PARKED_COUNT.fetch_sub(1, Ordering::SeqCst);
+            self.parked.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// Moves a runnable process from another scheduler's run queues to this scheduler's.  Returns
+    /// `true` if a process was stolen.
+    pub fn steal(&self) -> bool {
+        match steal_runnable(&self.id) {
+            Some(arc_process) => {
+                arc_process.schedule_with(self.id);
+                self.run_queues.write().enqueue(arc_process);
+                // The victim may have more for another idle scheduler to steal
+                self.unpark_thief();
+
+                true
+            }
+            None => false,
         }
     }
 
     pub fn is_run_queued(&self, value: &Arc<Process>) -> bool {
         self.run_queues.read().contains(value)
     }
+
+    /// Unparks this scheduler to run a process that became runnable, or, if it is busy, another
+    /// parked scheduler to steal it.
+    fn unpark_runnable(&self) {
+        if self.parked.load(Ordering::SeqCst) {
+            self.unpark();
+        } else {
+            self.unpark_thief();
+        }
+    }
+
+    /// Unparks one other parked scheduler, if any, so it can steal from this one.
+    fn unpark_thief(&self) {
+        // Avoids locking the registered schedulers on every message sent while none are parked
+        if PARKED_COUNT.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        let thief = all().into_iter().find(|arc_dyn_scheduler| {
+            arc_dyn_scheduler
+                .as_any()
+                .downcast_ref::<Scheduler>()
+                .map_or(false, |scheduler| {
+                    scheduler.id != self.id && scheduler.parked.load(Ordering::SeqCst)
+                })
+        });
+
+        if let Some(thief) = thief {
+            thief.unpark();
+        }
+    }
 }
 
 impl Debug for Scheduler {
@@ -131,29 +259,28 @@ impl SchedulerTrait for Scheduler {
                     // Without this check, a process.exit() from outside the process during WAITING
                     // will return to the Frame that called `process.wait()`
                     if !arc_process.is_exiting() {
+                        profiler::running(self.id, &arc_process);
+                        count_context_switch();
+
                         match arc_process.run() {
-                            Ran::Waiting | Ran::Reduced | Ran::RuntimeException => (),
+                            Ran::Waiting | Ran::Reduced => {
+                                if arc_process.is_gc_forced()
+                                    || arc_process.should_collect_virtual_binaries()
+                                {
+                                    garbage_collect(&arc_process);
+                                }
+                            }
+                            Ran::RuntimeException => (),
                             Ran::SystemException => {
                                 let runnable = match &*arc_process.status.read() {
                                     Status::SystemException(system_exception) => {
                                         match system_exception {
                                             SystemException::Alloc(_) => {
-                                                match arc_process.garbage_collect(0, &mut []) {
-                                                    Ok(reductions) => {
-                                                        arc_process.total_reductions.fetch_add(
-                                                            reductions.try_into().unwrap(),
-                                                            Ordering::SeqCst,
-                                                        );
-
-                                                        // Clear the status for `requeue` on
-                                                        // successful `garbage_collect`
-                                                        true
-                                                    }
-                                                    Err(gc_err) => panic!(
-                                                        "fatal garbage collection error: {:?}",
-                                                        gc_err
-                                                    ),
-                                                }
+                                                garbage_collect(&arc_process);
+
+                                                // Clear the status for `requeue` on successful
+                                                // `garbage_collect`
+                                                true
                                             }
                                             err => panic!("system error: {}", err),
                                         }
@@ -167,6 +294,8 @@ impl SchedulerTrait for Scheduler {
                                 }
                             }
                         }
+
+                        profiler::stopped_running(self.id, &arc_process);
                     } else {
                         arc_process.reduce()
                     }
@@ -187,12 +316,20 @@ impl SchedulerTrait for Scheduler {
                     break true;
                 }
                 Run::Delayed => continue,
-                // TODO steal processes or sleep if nothing to steal
+                // `run` steals or parks
                 Run::None => break false,
             }
         }
     }
 
+    fn change_priority(&self, process: &Process, priority: Priority) -> Priority {
+        let mut writable_run_queues = self.run_queues.write();
+        let old_priority = mem::replace(&mut *process.priority.write(), priority);
+        writable_run_queues.change_priority(process, old_priority);
+
+        old_priority
+    }
+
     fn run_queue_len(&self, priority: Priority) -> usize {
         self.run_queues.read().run_queue_len(priority)
     }
@@ -210,6 +347,9 @@ impl SchedulerTrait for Scheduler {
         let arc_process = Arc::new(process);
 
         writable_run_queues.enqueue(Arc::clone(&arc_process));
+        drop(writable_run_queues);
+        // The spawning process may be running on another scheduler
+        self.unpark_runnable();
 
         arc_process
     }
@@ -243,7 +383,43 @@ impl SchedulerTrait for Scheduler {
         Ok(arc_process)
     }
 
+    fn steal_runnable(&self) -> Option<Arc<Process>> {
+        self.run_queues.write().steal()
+    }
+
     fn stop_waiting(&self, process: &Process) {
         self.run_queues.write().stop_waiting(process);
+        self.unpark_runnable();
+    }
+
+    fn unpark(&self) {
+        self.thread.unpark();
+    }
+}
+
+// Private
+
+/// The number of schedulers that are `parked`
+static PARKED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+fn garbage_collect(process: &Process) {
+    let result = match process.garbage_collect(0, &mut []) {
+        Err(GcError::FullsweepRequired) => {
+            process.set_flags(ProcessFlags::NeedFullSweep);
+
+            process.garbage_collect(0, &mut [])
+        }
+        result => result,
+    };
+
+    match result {
+        Ok(reductions) => {
+            process
+                .total_reductions
+                .fetch_add(reductions.try_into().unwrap(), Ordering::SeqCst);
+        }
+        Err(gc_err) => panic!("fatal garbage collection error: {:?}", gc_err),
     }
 }
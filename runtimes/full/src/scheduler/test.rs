@@ -1,7 +1,13 @@
 mod erlang;
 
+use std::sync::Arc;
+use std::thread;
+
+use liblumen_alloc::erts::process::{Priority, Process};
 use liblumen_alloc::erts::term::prelude::*;
 
+use lumen_rt_core::scheduler::{run_queue, Run, Scheduler as SchedulerTrait};
+
 use crate::scheduler::{Scheduled, Scheduler};
 use crate::{scheduler, test};
 
@@ -25,3 +31,94 @@ fn scheduler_does_not_requeue_exiting_process() {
     assert!(scheduler::run_through(&arc_process));
     assert!(!scheduler.is_run_queued(&arc_process));
 }
+
+#[test]
+fn run_queues_steal_in_priority_order_from_the_back() {
+    let init = test::process::init();
+    let normal = test::process::child(&init);
+    let first_high = with_priority(test::process::child(&init), Priority::High);
+    let second_high = with_priority(test::process::child(&init), Priority::High);
+    let max = with_priority(test::process::child(&init), Priority::Max);
+
+    let mut queues = run_queue::Queues::default();
+    queues.enqueue(Arc::clone(&normal));
+    queues.enqueue(Arc::clone(&first_high));
+    queues.enqueue(Arc::clone(&second_high));
+    queues.enqueue(Arc::clone(&max));
+
+    assert_eq!(queues.steal(), Some(max));
+    assert_eq!(queues.steal(), Some(second_high));
+    assert_eq!(queues.steal(), Some(first_high));
+    assert_eq!(queues.steal(), Some(normal));
+    assert_eq!(queues.steal(), None);
+}
+
+#[test]
+fn run_queues_steal_low_priority_only_when_its_delay_has_run_out() {
+    let init = test::process::init();
+    let low = with_priority(test::process::child(&init), Priority::Low);
+
+    let mut queues = run_queue::Queues::default();
+    queues.enqueue(Arc::clone(&low));
+
+    for _ in 0..7 {
+        assert_eq!(queues.steal(), None);
+
+        match queues.dequeue() {
+            Run::Delayed => (),
+            _ => panic!("Priority::Low process should be delayed"),
+        }
+    }
+
+    assert_eq!(queues.steal(), Some(low));
+}
+
+#[test]
+fn run_queues_do_not_steal_waiting_processes() {
+    let arc_process = test::process::default();
+    arc_process.wait();
+
+    let mut queues = run_queue::Queues::default();
+    assert!(queues.requeue(Arc::clone(&arc_process)).is_none());
+
+    assert!(queues.contains(&arc_process));
+    assert_eq!(queues.steal(), None);
+}
+
+#[test]
+fn another_scheduler_steals_runnable_processes_in_priority_order() {
+    let init = test::process::init();
+    let normal = test::process::child(&init);
+    let high = test::process::child(&init);
+    let max = test::process::child(&init);
+
+    let victim = scheduler::current();
+    victim.change_priority(&high, Priority::High);
+    victim.change_priority(&max, Priority::Max);
+
+    let thief_victim = Arc::clone(&victim);
+    let stolen = thread::spawn(move || {
+        let thief_id = scheduler::current().id();
+        assert_ne!(thief_id, thief_victim.id());
+
+        (0..3)
+            .map(|_| thief_victim.steal_runnable().unwrap())
+            .collect::<Vec<_>>()
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(stolen, vec![max, high, normal]);
+
+    let scheduler = victim.as_any().downcast_ref::<Scheduler>().unwrap();
+
+    for arc_process in &stolen {
+        assert!(!scheduler.is_run_queued(arc_process));
+    }
+}
+
+fn with_priority(arc_process: Arc<Process>, priority: Priority) -> Arc<Process> {
+    *arc_process.priority.write() = priority;
+
+    arc_process
+}
@@ -0,0 +1,112 @@
+/*
+ * The C API for embedding Erlang compiled by Lumen in another program.
+ *
+ * Libraries built with `lumen compile --output-type=staticlib` or
+ * `--output-type=dylib` export these functions, and this header is written
+ * next to the library.
+ *
+ * Terms cross the API in the external term format, as produced by
+ * `term_to_binary/1`, including the leading version byte (131).
+ *
+ * Example:
+ *
+ *     static void on_reply(void *data, const uint8_t *message, size_t len) {
+ *         // `message` is only valid until this returns
+ *     }
+ *
+ *     lumen_start(argc, argv);
+ *     lumen_register_callback("host", on_reply, NULL);
+ *
+ *     // [2]
+ *     const uint8_t args[] = {131, 108, 0, 0, 0, 1, 97, 2, 106};
+ *     lumen_spawn("doubler", "start", args, sizeof(args));
+ *
+ *     // 21, which `doubler` replies to with `host ! 42`
+ *     const uint8_t message[] = {131, 97, 21};
+ *     lumen_send("doubler", message, sizeof(message));
+ *
+ *     ...
+ *
+ *     lumen_stop();
+ */
+#ifndef LUMEN_H
+#define LUMEN_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum lumen_status {
+    LUMEN_OK = 0,
+    /* The atom table, dispatch table or application environment could not be
+     * initialized */
+    LUMEN_INIT_FAILED = 1,
+    LUMEN_ALREADY_STARTED = 2,
+    LUMEN_NOT_STARTED = 3,
+    /* A name is not valid UTF-8, or a term is not in the external term format */
+    LUMEN_BADARG = 4,
+    /* The function to spawn is not compiled into the library */
+    LUMEN_UNDEF = 5,
+    /* No process, or callback, is registered under the name */
+    LUMEN_NOPROC = 6,
+    /* The name to register a callback under is already registered */
+    LUMEN_ALREADY_REGISTERED = 7,
+    /* The heap of a process to spawn could not be allocated */
+    LUMEN_NO_MEMORY = 8,
+} lumen_status_t;
+
+/*
+ * Called with the `data` given to `lumen_register_callback` and a message
+ * in the external term format.
+ *
+ * It is called on a scheduler thread, from the process sending the message,
+ * so it should return quickly. `message` is only valid until it returns.
+ */
+typedef void (*lumen_callback_t)(void *data, const uint8_t *message, size_t len);
+
+/*
+ * Starts a scheduler on a thread of its own, which runs until `lumen_stop`.
+ *
+ * `argv` becomes the result of `init:get_plain_arguments/0`, and is only read
+ * the first time the runtime is started.
+ */
+lumen_status_t lumen_start(int argc, const char *const *argv);
+
+/* Stops the scheduler and waits for its thread to exit. */
+lumen_status_t lumen_stop(void);
+
+/*
+ * Spawns a process calling `module:function(Arguments...)`, where `arguments`
+ * is a list. The arity of the function is the length of the list, so lists
+ * longer than 255 elements return `LUMEN_BADARG`.
+ *
+ * To be sent messages by name, the process must register itself with
+ * `register/2`.
+ */
+lumen_status_t lumen_spawn(const char *module,
+                           const char *function,
+                           const uint8_t *arguments,
+                           size_t arguments_len);
+
+/* Sends `message` to the process registered under `name`. */
+lumen_status_t lumen_send(const char *name, const uint8_t *message, size_t len);
+
+/*
+ * Registers `callback` under `name`, like a process, so that Erlang code can
+ * reply with `name ! Message`.
+ */
+lumen_status_t lumen_register_callback(const char *name,
+                                       lumen_callback_t callback,
+                                       void *data);
+
+/* Removes the callback registered under `name`. */
+lumen_status_t lumen_unregister_callback(const char *name);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LUMEN_H */
@@ -8,6 +8,8 @@ use liblumen_alloc::erts::Process;
 
 use lumen_rt_core::process::current_process;
 use lumen_rt_core::registry;
+use lumen_rt_core::send::{self, Sent};
+use lumen_rt_core::trace;
 
 extern "Rust" {
     #[link_name = "__scheduler_stop_waiting"]
@@ -18,14 +20,19 @@ extern "Rust" {
 pub extern "C" fn builtin_send(to_term: Term, msg: Term) -> Term {
     let result = panic::catch_unwind(|| {
         let decoded_result: Result<Pid, _> = to_term.decode().unwrap().try_into();
+        let p = current_process();
         if let Ok(to) = decoded_result {
-            let p = current_process();
+            trace::send(&p, msg, to_term);
+
             let self_pid = p.pid();
             if self_pid == to {
+                trace::receive(&p, &p, msg);
                 p.send_from_self(msg);
                 return msg;
             } else {
                 if let Some(ref to_proc) = registry::pid_to_process(&to) {
+                    trace::receive(&p, to_proc, msg);
+
                     if let Ok(resume) = to_proc.send_from_other(msg) {
                         if resume {
                             unsafe {
@@ -36,6 +43,9 @@ pub extern "C" fn builtin_send(to_term: Term, msg: Term) -> Term {
                     }
                 }
             }
+        } else if let Ok(Sent::Sent) = send::send(to_term, msg, Default::default(), &p) {
+            // Registered names, which may belong to the program embedding the runtime
+            return msg;
         }
 
         Term::NONE
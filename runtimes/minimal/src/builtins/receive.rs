@@ -1,11 +1,16 @@
+use std::convert::TryInto;
 use std::panic;
+use std::sync::Arc;
 
 use liblumen_alloc::erts::message::MessageType;
+use liblumen_alloc::erts::process::Mailbox;
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::erts::timeout::{ReceiveTimeout, Timeout};
+use liblumen_alloc::erts::Process;
 
 use lumen_rt_core::process::current_process;
 use lumen_rt_core::time::monotonic;
+use lumen_rt_core::timer;
 
 extern "C" {
     #[link_name = "__lumen_builtin_yield"]
@@ -29,7 +34,8 @@ pub enum ReceiveState {
 /// and used during cleanup in `receive_done` to determine what, if any,
 /// cleanup needs to be performed.
 ///
-/// It is read from non-Rust code, so we use `repr(C)`.
+/// It is read from non-Rust code, so we use `repr(C)`.  The timer that ends the receive is kept
+/// in the `Mailbox`, as a `Reference` has no C representation.
 #[repr(C)]
 pub struct ReceiveContext {
     timeout: ReceiveTimeout,
@@ -81,6 +87,16 @@ impl ReceiveContext {
         let now = monotonic::time_in_milliseconds();
         self.timeout.is_timed_out(now)
     }
+
+    /// Wakes the process when the timeout ends, so that it does not wait forever for a message
+    /// that never arrives.  Only started once the process has to wait.
+    fn start_timer(&self, mbox: &mut Mailbox, arc_process: &Arc<Process>) {
+        if !mbox.recv_has_timer() {
+            if let Some(end) = self.timeout.end() {
+                mbox.recv_set_timer(timer::start_receive_timeout(end, arc_process));
+            }
+        }
+    }
 }
 
 #[export_name = "__lumen_builtin_receive_start"]
@@ -93,6 +109,7 @@ pub extern "C" fn builtin_receive_start(timeout: Term) -> ReceiveContext {
         };
         let context = ReceiveContext::new(to);
         let p = current_process();
+        p.take_off_heap_messages();
         let mbox = p.mailbox.lock();
         mbox.borrow().recv_start();
         context
@@ -111,6 +128,7 @@ pub extern "C" fn builtin_receive_wait(context: *mut ReceiveContext) -> bool {
         loop {
             {
                 let p = current_process();
+                p.take_off_heap_messages();
                 let mbox_lock = p.mailbox.lock();
                 let mut mbox = mbox_lock.borrow_mut();
                 if let Some((msg, msg_type)) = mbox.recv_peek_with_type() {
@@ -122,6 +140,7 @@ pub extern "C" fn builtin_receive_wait(context: *mut ReceiveContext) -> bool {
                     break true;
                 } else {
                     // If there are no messages, wait and yield
+                    context.start_timer(&mut mbox, &p);
                     p.wait();
                 }
             }
@@ -140,17 +159,42 @@ pub extern "C" fn builtin_receive_wait(context: *mut ReceiveContext) -> bool {
     }
 }
 
+/// Called after `__lumen_builtin_receive_start` when every message the receive matches contains
+/// `marker`.  If `marker` is the last reference made by `make_ref/0`, the messages that arrived
+/// before it was made are skipped.
+#[export_name = "__lumen_builtin_receive_set"]
+pub extern "C" fn builtin_receive_set(marker: Term) -> bool {
+    let result = panic::catch_unwind(|| {
+        let reference: Result<Boxed<Reference>, _> = marker.try_into();
+
+        if let Ok(reference) = reference {
+            let p = current_process();
+            let mbox_lock = p.mailbox.lock();
+            mbox_lock.borrow_mut().recv_set(&reference);
+        }
+    });
+    result.is_ok()
+}
+
 #[export_name = "__lumen_builtin_receive_done"]
 pub extern "C" fn builtin_receive_done(context: ReceiveContext) -> bool {
     let result = panic::catch_unwind(|| {
         let p = current_process();
+        // Cancelled without holding the mailbox lock, as timers that time out send to mailboxes
+        let timer_reference = p.mailbox.lock().borrow_mut().recv_take_timer();
+        if let Some(timer_reference) = timer_reference {
+            timer::cancel(&timer_reference);
+        }
+
         let mbox_lock = p.mailbox.lock();
         let mut mbox = mbox_lock.borrow_mut();
 
         match context.state {
             ReceiveState::Received if context.message_needs_move => {
-                // Copy to process heap
-                unimplemented!();
+                // The terms bound from the message are still in its heap fragment, which the next
+                // garbage collection moves onto the process heap
+                mbox.recv_finish_in_place(&p);
+                true
             }
             ReceiveState::Received | ReceiveState::Timeout => {
                 mbox.recv_finish(&p);
@@ -0,0 +1,360 @@
+//! The C API for embedding the runtime in another program, which is declared in
+//! `include/lumen.h` and exported from libraries built with `--output-type=staticlib` or
+//! `--output-type=dylib`.
+//!
+//! Unlike an executable, the schedulers do not run on the thread calling `main`. Instead,
+//! `lumen_start` starts a scheduler on a thread of its own, which keeps running, parked when
+//! idle, until `lumen_stop` is called.
+use std::ffi::{c_void, CStr};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use once_cell::sync::OnceCell;
+
+use liblumen_core::locks::Mutex;
+use liblumen_core::sys::dynamic_call::{self, DynamicCallee};
+
+use liblumen_alloc::erts::apply;
+use liblumen_alloc::erts::process::alloc::{self, default_heap_size};
+use liblumen_alloc::erts::process::{Priority, Process};
+use liblumen_alloc::erts::scheduler::ID;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::ModuleFunctionArity;
+use liblumen_alloc::CloneToProcess;
+
+use lumen_rt_core::distribution::external_term_format::{term, version};
+use lumen_rt_core::host::{Callback, Host};
+use lumen_rt_core::registry;
+use lumen_rt_core::scheduler::Scheduler as SchedulerTrait;
+use lumen_rt_core::send;
+
+use crate::env;
+use crate::scheduler::{self, Scheduler};
+
+/// The result of each function in the C API, mirrored by `lumen_status_t`
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// The atom table, dispatch table or application environment could not be initialized
+    InitFailed = 1,
+    AlreadyStarted = 2,
+    NotStarted = 3,
+    /// A name is not valid UTF-8, or a term is not valid external term format
+    BadArg = 4,
+    /// The function to spawn is not compiled into the library
+    Undef = 5,
+    /// No process is registered under the name a message is sent to
+    NoProc = 6,
+    /// The name to register a callback under is already registered
+    AlreadyRegistered = 7,
+    /// The heap of a process to spawn could not be allocated
+    NoMemory = 8,
+}
+
+struct Runtime {
+    scheduler_id: ID,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+lazy_static! {
+    static ref RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+    /// The arguments of processes spawned by `lumen_spawn`, which are on the heap of the
+    /// process, until `apply_spawn_arguments` passes them to the spawned function
+    static ref SPAWN_ARGUMENTS: Mutex<HashMap<Pid, (DynamicCallee, Vec<Term>)>> =
+        Mutex::new(HashMap::new());
+}
+
+static INITIALIZED: OnceCell<Result<(), i32>> = OnceCell::new();
+
+/// Starts the runtime, with `argv` as the arguments returned by `init:get_plain_arguments/0`
+#[no_mangle]
+pub unsafe extern "C" fn lumen_start(argc: c_int, argv: *const *const c_char) -> Status {
+    let mut runtime = RUNTIME.lock();
+    if runtime.is_some() {
+        return Status::AlreadyStarted;
+    }
+
+    // The tables generated by the compiler can only be initialized once per program, so they
+    // outlive `lumen_stop`
+    let initialized = INITIALIZED.get_or_init(|| {
+        liblumen_crt::initialize()?;
+        env::init_argv(argv, argc as u32).map_err(|_| 1)
+    });
+    if initialized.is_err() {
+        return Status::InitFailed;
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let (id_sender, id_receiver) = mpsc::channel();
+    let scheduler_stop = stop.clone();
+    let spawned = thread::Builder::new()
+        .name("lumen scheduler".to_owned())
+        .spawn(move || {
+            let arc_dyn_scheduler = scheduler::current();
+            id_sender.send(arc_dyn_scheduler.id()).unwrap();
+
+            while !scheduler_stop.load(Ordering::SeqCst) {
+                if arc_dyn_scheduler.run_once() {
+                    continue;
+                }
+
+                // Parked until the embedding program sends a message or spawns a process, which
+                // unparks the scheduler, but only for as long as the next timer can wait
+                let milliseconds_until_next_timeout = arc_dyn_scheduler
+                    .hierarchy()
+                    .read()
+                    .milliseconds_until_next_timeout();
+
+                match milliseconds_until_next_timeout {
+                    Some(milliseconds) => thread::park_timeout(Duration::from_millis(milliseconds)),
+                    None => thread::park(),
+                }
+            }
+
+            if let Err(err) = arc_dyn_scheduler.shutdown() {
+                eprintln!("System error: {}", err);
+            }
+        });
+    let thread = match spawned {
+        Ok(thread) => thread,
+        Err(_) => return Status::InitFailed,
+    };
+
+    *runtime = Some(Runtime {
+        scheduler_id: id_receiver.recv().unwrap(),
+        stop,
+        thread,
+    });
+
+    Status::Ok
+}
+
+/// Stops the scheduler and waits for its thread to exit
+#[no_mangle]
+pub extern "C" fn lumen_stop() -> Status {
+    let runtime = match RUNTIME.lock().take() {
+        Some(runtime) => runtime,
+        None => return Status::NotStarted,
+    };
+
+    runtime.stop.store(true, Ordering::SeqCst);
+    runtime.thread.thread().unpark();
+    let _ = runtime.thread.join();
+
+    Status::Ok
+}
+
+/// Spawns a process that calls `module:function(Arguments...)`, where `arguments` is a list
+/// encoded in the external term format
+#[no_mangle]
+pub unsafe extern "C" fn lumen_spawn(
+    module: *const c_char,
+    function: *const c_char,
+    arguments: *const u8,
+    arguments_len: usize,
+) -> Status {
+    let arc_dyn_scheduler = match RUNTIME
+        .lock()
+        .as_ref()
+        .and_then(|runtime| scheduler::from_id(&runtime.scheduler_id))
+    {
+        Some(arc_dyn_scheduler) => arc_dyn_scheduler,
+        None => return Status::NotStarted,
+    };
+    let scheduler = arc_dyn_scheduler
+        .as_any()
+        .downcast_ref::<Scheduler>()
+        .unwrap();
+    let (module, function) = match (atom_from_c_str(module), atom_from_c_str(function)) {
+        (Some(module), Some(function)) => (module, function),
+        _ => return Status::BadArg,
+    };
+    let arguments_bytes = slice::from_raw_parts(arguments, arguments_len);
+
+    // Decoded into a heap fragment first, so that the heap of the process can be sized to hold
+    // the arguments
+    let (arguments, heap_fragment) = match version::check(arguments_bytes)
+        .and_then(|after_version_bytes| term::decode_tagged_to_fragment(false, after_version_bytes))
+    {
+        Ok((arguments, heap_fragment, _)) => (arguments, heap_fragment),
+        Err(_) => return Status::BadArg,
+    };
+    let spawned = spawn_process(module, function, arguments);
+    ptr::drop_in_place(heap_fragment.as_ptr());
+    let (process, argument_vec) = match spawned {
+        Ok(spawned) => spawned,
+        Err(status) => return status,
+    };
+
+    let callee = match apply::find_symbol(&process.initial_module_function_arity) {
+        Some(callee) => callee,
+        None => return Status::Undef,
+    };
+    let arc_process = Arc::new(process);
+    registry::put_pid_to_process(&arc_process);
+
+    if argument_vec.is_empty() {
+        scheduler.spawn_with_init_fn(arc_process, callee);
+    } else {
+        SPAWN_ARGUMENTS
+            .lock()
+            .insert(arc_process.pid(), (callee, argument_vec));
+        let init_fn: extern "C" fn() -> usize = apply_spawn_arguments;
+        scheduler.spawn_with_init_fn(arc_process, init_fn);
+    }
+
+    Status::Ok
+}
+
+/// Sends `message`, encoded in the external term format, to the process registered as `name`
+#[no_mangle]
+pub unsafe extern "C" fn lumen_send(name: *const c_char, message: *const u8, len: usize) -> Status {
+    if RUNTIME.lock().is_none() {
+        return Status::NotStarted;
+    }
+    let name = match atom_from_c_str(name) {
+        Some(name) => name,
+        None => return Status::BadArg,
+    };
+    let arc_process = match registry::atom_to_process(&name) {
+        Some(arc_process) => arc_process,
+        None => return Status::NoProc,
+    };
+
+    // Decoded into a heap fragment, as the process may be running on the scheduler's thread
+    let decoded =
+        version::check(slice::from_raw_parts(message, len)).and_then(|after_version_bytes| {
+            term::decode_tagged_to_fragment(false, after_version_bytes)
+        });
+
+    match decoded {
+        Ok((message, heap_fragment, _)) => {
+            send::send_heap_message(&arc_process, heap_fragment, message);
+
+            Status::Ok
+        }
+        Err(_) => Status::BadArg,
+    }
+}
+
+/// Registers `callback` under `name`, so that messages sent to `name` by Erlang code are
+/// passed to `callback`, along with `data`
+#[no_mangle]
+pub unsafe extern "C" fn lumen_register_callback(
+    name: *const c_char,
+    callback: Callback,
+    data: *mut c_void,
+) -> Status {
+    let name = match atom_from_c_str(name) {
+        Some(name) => name,
+        None => return Status::BadArg,
+    };
+
+    if registry::register_host(name, Host::new(callback, data)) {
+        Status::Ok
+    } else {
+        Status::AlreadyRegistered
+    }
+}
+
+/// Removes the callback registered under `name`
+#[no_mangle]
+pub unsafe extern "C" fn lumen_unregister_callback(name: *const c_char) -> Status {
+    let name = match atom_from_c_str(name) {
+        Some(name) => name,
+        None => return Status::BadArg,
+    };
+
+    match registry::atom_to_host(&name) {
+        Some(_) => {
+            registry::unregister(&name);
+
+            Status::Ok
+        }
+        None => Status::NoProc,
+    }
+}
+
+// Private
+
+/// The initial function of processes spawned with arguments, since the scheduler can only start
+/// processes in a function without arguments
+#[unwind(allowed)]
+extern "C" fn apply_spawn_arguments() -> usize {
+    let pid = lumen_rt_core::process::current_process().pid();
+    let (callee, argument_vec) = SPAWN_ARGUMENTS.lock().remove(&pid).unwrap();
+
+    unsafe {
+        dynamic_call::apply(
+            callee,
+            argument_vec.as_ptr() as *const usize,
+            argument_vec.len(),
+        )
+    }
+}
+
+unsafe fn atom_from_c_str(name: *const c_char) -> Option<Atom> {
+    if name.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(name)
+        .to_str()
+        .ok()
+        .and_then(|name| Atom::try_from_str(name).ok())
+}
+
+/// Creates the process for `lumen_spawn`, with `arguments` copied onto its heap
+fn spawn_process(
+    module: Atom,
+    function: Atom,
+    arguments: Term,
+) -> Result<(Process, Vec<Term>), Status> {
+    let arity = match list_to_vec(arguments) {
+        Some(argument_vec) if argument_vec.len() <= (std::u8::MAX as usize) => {
+            argument_vec.len() as u8
+        }
+        _ => return Err(Status::BadArg),
+    };
+    let module_function_arity = ModuleFunctionArity {
+        module,
+        function,
+        arity,
+    };
+
+    let heap_size = alloc::next_heap_size(default_heap_size() + arguments.size_in_words());
+    let heap = alloc::heap(heap_size).map_err(|_| Status::NoMemory)?;
+    let process = Process::new_with_stack(
+        Priority::Normal,
+        None,
+        module_function_arity,
+        heap,
+        heap_size,
+    )
+    .map_err(|_| Status::NoMemory)?;
+    // The process isn't scheduled yet, so nothing else can be using its heap
+    let argument_vec = arguments
+        .clone_to_heap(&mut *process.acquire_heap())
+        .ok()
+        .and_then(list_to_vec)
+        .ok_or(Status::NoMemory)?;
+
+    Ok((process, argument_vec))
+}
+
+fn list_to_vec(list: Term) -> Option<Vec<Term>> {
+    match list.decode().ok()? {
+        TypedTerm::Nil => Some(Vec::new()),
+        TypedTerm::List(cons) => cons.into_iter().map(|result| result.ok()).collect(),
+        _ => None,
+    }
+}
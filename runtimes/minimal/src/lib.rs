@@ -3,6 +3,7 @@
 #![feature(naked_functions)]
 #![feature(termination_trait_lib)]
 #![feature(thread_local)]
+#![feature(unwind_attributes)]
 
 #[cfg(not(unix))]
 compile_error!("lumen_rt_minimal is only supported on unix targets!");
@@ -14,6 +15,7 @@ extern crate panic;
 mod macros;
 mod builtins;
 mod config;
+pub mod embed;
 pub mod env;
 mod logging;
 pub mod process;
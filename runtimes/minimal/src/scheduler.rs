@@ -8,6 +8,7 @@ use std::ops::Deref;
 use std::ptr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
+use std::thread::{self, Thread};
 
 use hashbrown::HashMap;
 
@@ -17,6 +18,7 @@ use lazy_static::lazy_static;
 use log::info;
 
 use liblumen_core::locks::{Mutex, RwLock};
+use liblumen_core::sys::dynamic_call::DynamicCallee;
 use liblumen_core::util::thread_local::ThreadLocalCell;
 
 use liblumen_alloc::atom;
@@ -30,10 +32,12 @@ use liblumen_alloc::erts::ModuleFunctionArity;
 
 use lumen_rt_core as rt_core;
 use lumen_rt_core::process::{log_exit, propagate_exit, CURRENT_PROCESS};
+use lumen_rt_core::profiler;
 use lumen_rt_core::scheduler::Scheduler as SchedulerTrait;
 use lumen_rt_core::scheduler::{self, run_queue, unregister, Run};
 pub use lumen_rt_core::scheduler::{
-    current, from_id, run_through, Scheduled, SchedulerDependentAlloc, Spawned,
+    all, context_switches, current, from_id, run_through, Scheduled, SchedulerDependentAlloc,
+    Spawned,
 };
 use lumen_rt_core::timer::Hierarchy;
 
@@ -231,6 +235,7 @@ pub struct Scheduler {
     root: Arc<Process>,
     init: ThreadLocalCell<Arc<Process>>,
     current: ThreadLocalCell<Arc<Process>>,
+    thread: Thread,
 }
 // This guarantee holds as long as `init` and `current` are only
 // ever accessed by the scheduler when scheduling
@@ -282,6 +287,9 @@ impl Scheduler {
             hierarchy: Default::default(),
             reference_count: AtomicU64::new(0),
             unique_integer: AtomicU64::new(0),
+            // `new` is called when the thread-local scheduler is first used, so this is the thread
+            // that runs the scheduler.
+            thread: thread::current(),
         })
     }
 
@@ -338,6 +346,14 @@ impl SchedulerTrait for Scheduler {
         self.process_yield(/* is_root= */ true)
     }
 
+    fn change_priority(&self, process: &Process, priority: Priority) -> Priority {
+        let mut writable_run_queues = self.run_queues.write();
+        let old_priority = mem::replace(&mut *process.priority.write(), priority);
+        writable_run_queues.change_priority(process, old_priority);
+
+        old_priority
+    }
+
     fn run_queue_len(&self, priority: Priority) -> usize {
         self.run_queues.read().run_queue_len(priority)
     }
@@ -406,6 +422,11 @@ impl SchedulerTrait for Scheduler {
 
     fn stop_waiting(&self, process: &Process) {
         self.run_queues.write().stop_waiting(process);
+        self.unpark();
+    }
+
+    fn unpark(&self) {
+        self.thread.unpark();
     }
 }
 
@@ -523,6 +544,12 @@ impl Scheduler {
                 .fetch_add(prev_reductions as u64, Ordering::Relaxed);
         }
 
+        profiler::stopped_running(self.id, &prev);
+        if new.pid() != self.root.pid() {
+            profiler::running(self.id, &new);
+            scheduler::count_context_switch();
+        }
+
         // Change the previous process status to Runnable
         {
             let mut prev_status = prev.status.write();
@@ -589,8 +616,6 @@ impl Scheduler {
     }
 
     fn spawn_internal(process: Arc<Process>, id: id::ID, run_queues: &RwLock<run_queue::Queues>) {
-        process.schedule_with(id);
-
         let mfa = &process.initial_module_function_arity;
         let init_fn_result = apply::find_symbol(&mfa);
         if init_fn_result.is_none() {
@@ -601,6 +626,25 @@ impl Scheduler {
         }
         let init_fn = init_fn_result.unwrap();
 
+        Self::spawn_internal_with_init_fn(process, init_fn, id, run_queues)
+    }
+
+    /// Schedules `process` to start in `init_fn`, which is called with no arguments, instead of
+    /// the function of its initial module, function and arity
+    pub(crate) fn spawn_with_init_fn(&self, process: Arc<Process>, init_fn: DynamicCallee) {
+        Self::spawn_internal_with_init_fn(process, init_fn, self.id, &self.run_queues);
+        // The process may be spawned from outside the scheduler's thread, such as by an embedder
+        self.unpark();
+    }
+
+    fn spawn_internal_with_init_fn(
+        process: Arc<Process>,
+        init_fn: DynamicCallee,
+        id: id::ID,
+        run_queues: &RwLock<run_queue::Queues>,
+    ) {
+        process.schedule_with(id);
+
         #[inline(always)]
         unsafe fn push(sp: &mut StackPointer, value: u64) {
             sp.0 = sp.0.offset(-1);